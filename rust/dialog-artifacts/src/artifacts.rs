@@ -33,9 +33,15 @@ pub use symbol::*;
 mod entity;
 pub use entity::*;
 
+mod instant;
+pub use instant::*;
+
 mod value;
 pub use value::*;
 
+mod record;
+pub use record::*;
+
 mod ordkey;
 pub use ordkey::*;
 
@@ -3186,10 +3192,8 @@ mod tests {
     }
 
     /// Every reconstructable spillable value type round-trips through a spill:
-    /// String and Bytes (including a `0x00`-escape case) reconstruct exactly.
-    /// `Record` reconstruction from raw bytes is unimplemented workspace-wide
-    /// (see `Value::try_from`), which is orthogonal to spilling; it is not
-    /// exercised here.
+    /// String, Bytes (including a `0x00`-escape case) and Record reconstruct
+    /// exactly.
     #[dialog_common::test]
     async fn it_spills_and_round_trips_every_value_type() -> Result<()> {
         let n = dialog_search_tree::Manifest::default().inline_n as usize + 64;
@@ -3203,7 +3207,7 @@ mod tests {
             }),
             // A spilled record must reconstruct from its raw block bytes;
             // this path once hit an `unimplemented!()` and panicked on read.
-            Value::Record(vec![7u8; n]),
+            Value::Record(crate::Record::from(vec![7u8; n])),
         ];
         for value in values {
             assert!(
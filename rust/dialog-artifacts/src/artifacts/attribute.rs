@@ -49,6 +49,18 @@ impl Attribute {
         self.0.split_once('/').map(|(_, name)| name).unwrap_or("")
     }
 
+    /// Whether this attribute is reserved for machinery-written facts
+    /// (revision records, delegation records): the `dialog.` namespace,
+    /// less `dialog.rule/*`, `dialog.concept/*` and `dialog.attribute/*`,
+    /// whose facts an application writes like any other (see
+    /// [`WriteScope`](crate::tree::WriteScope)).
+    pub fn is_reserved(&self) -> bool {
+        self.0.starts_with("dialog.")
+            && !self.0.starts_with("dialog.rule/")
+            && !self.0.starts_with("dialog.concept/")
+            && !self.0.starts_with("dialog.attribute/")
+    }
+
     /// Split this attribute into its typed halves: the domain as a
     /// [`Symbol`] and the name as a [`Name`] (a [`Symbol`] when it
     /// starts lowercase, a fractional position when it starts with an
@@ -0,0 +1,141 @@
+//! A point in time, stored as a first-class [`Value`](crate::Value).
+//!
+//! An [`Instant`] is a signed count of nanoseconds since the Unix epoch
+//! (1970-01-01T00:00:00Z), in UTC. It carries no time zone or calendar: those
+//! are presentation concerns handled by the query layer's temporal formulas.
+//! Because the count is an `i128`, every instant a calendar library can
+//! represent (and a great many more) fits without loss, and the key encoding
+//! reuses the order-preserving signed integer codec — so instants sort
+//! chronologically in the AEV and VAE indexes, including across the epoch.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::{Deserialize, Serialize};
+
+/// Nanoseconds in one second.
+pub const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+/// Nanoseconds in one (UTC, leap-second free) day.
+pub const NANOS_PER_DAY: i128 = 86_400 * NANOS_PER_SECOND;
+
+/// A point in time as nanoseconds since the Unix epoch, in UTC.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Instant(i128);
+
+impl Instant {
+    /// The Unix epoch, 1970-01-01T00:00:00Z.
+    pub const UNIX_EPOCH: Instant = Instant(0);
+
+    /// The instant `nanos` nanoseconds after (or, when negative, before) the
+    /// Unix epoch.
+    pub const fn from_unix_nanos(nanos: i128) -> Self {
+        Self(nanos)
+    }
+
+    /// The instant `seconds` seconds after (or before) the Unix epoch.
+    pub const fn from_unix_seconds(seconds: i64) -> Self {
+        Self(seconds as i128 * NANOS_PER_SECOND)
+    }
+
+    /// Nanoseconds since the Unix epoch; negative before it.
+    pub const fn unix_nanos(&self) -> i128 {
+        self.0
+    }
+
+    /// This instant shifted by `nanos` nanoseconds, or `None` when the result
+    /// would overflow.
+    pub fn checked_add(&self, nanos: i128) -> Option<Self> {
+        self.0.checked_add(nanos).map(Self)
+    }
+
+    /// The signed number of nanoseconds from `earlier` to `self`, or `None`
+    /// when it would overflow.
+    pub fn checked_since(&self, earlier: &Instant) -> Option<i128> {
+        self.0.checked_sub(earlier.0)
+    }
+
+    /// The start of the UTC day containing this instant, or `None` when that
+    /// midnight lies outside the representable range. Rounds toward the
+    /// past, so instants before the epoch truncate to the midnight that
+    /// precedes them rather than the one that follows.
+    pub fn truncate_to_day(&self) -> Option<Self> {
+        self.0
+            .div_euclid(NANOS_PER_DAY)
+            .checked_mul(NANOS_PER_DAY)
+            .map(Self)
+    }
+
+    /// The fixed-width little-endian byte form used by [`crate::Value::to_bytes`].
+    pub fn to_le_bytes(&self) -> [u8; 16] {
+        self.0.to_le_bytes()
+    }
+
+    /// Inverse of [`Instant::to_le_bytes`].
+    pub fn from_le_bytes(bytes: [u8; 16]) -> Self {
+        Self(i128::from_le_bytes(bytes))
+    }
+}
+
+impl Display for Instant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<i128> for Instant {
+    fn from(nanos: i128) -> Self {
+        Self(nanos)
+    }
+}
+
+impl From<Instant> for i128 {
+    fn from(instant: Instant) -> Self {
+        instant.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(unexpected_cfgs)]
+    #![allow(clippy::unused_async)]
+
+    use super::*;
+
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    /// Truncation rounds toward the past on both sides of the epoch.
+    #[dialog_common::test]
+    async fn it_truncates_to_the_preceding_midnight() -> anyhow::Result<()> {
+        let noon = Instant::from_unix_seconds(86_400 + 43_200);
+        assert_eq!(
+            noon.truncate_to_day(),
+            Some(Instant::from_unix_seconds(86_400))
+        );
+
+        let before_epoch = Instant::from_unix_nanos(-1);
+        assert_eq!(
+            before_epoch.truncate_to_day(),
+            Some(Instant::from_unix_seconds(-86_400))
+        );
+
+        assert_eq!(
+            Instant::UNIX_EPOCH.truncate_to_day(),
+            Some(Instant::UNIX_EPOCH)
+        );
+        Ok(())
+    }
+
+    /// The earliest days have no representable midnight; the latest do.
+    #[dialog_common::test]
+    async fn it_refuses_to_truncate_past_the_range() -> anyhow::Result<()> {
+        assert_eq!(Instant::from_unix_nanos(i128::MIN).truncate_to_day(), None);
+
+        let latest = Instant::from_unix_nanos(i128::MAX);
+        let midnight = latest.truncate_to_day().expect("a representable midnight");
+        assert!(midnight <= latest);
+        assert!(latest.checked_since(&midnight).unwrap() < NANOS_PER_DAY);
+        Ok(())
+    }
+}
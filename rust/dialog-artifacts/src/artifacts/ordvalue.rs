@@ -13,7 +13,7 @@
 //! caller that knows the tree's manifest.
 
 use crate::{
-    Value, ValueDataType,
+    Instant, Record, Value, ValueDataType,
     artifacts::ordkey::{
         decode_bool, decode_bytes, decode_f64, decode_i128, decode_u128, encode_bool, encode_bytes,
         encode_f64, encode_i128, encode_u128,
@@ -31,9 +31,10 @@ pub fn encode_value(value: &Value, out: &mut Vec<u8>) {
         Value::Boolean(boolean) => encode_bool(*boolean, out),
         Value::String(string) => encode_bytes(string.as_bytes(), out),
         Value::Bytes(bytes) => encode_bytes(bytes, out),
-        Value::Record(bytes) => encode_bytes(bytes, out),
+        Value::Record(bytes) => encode_bytes(bytes.as_bytes(), out),
         Value::Entity(entity) => encode_bytes(entity.as_str().as_bytes(), out),
         Value::Symbol(attribute) => encode_bytes(attribute.to_string().as_bytes(), out),
+        Value::Instant(instant) => encode_i128(instant.unix_nanos(), out),
     }
 }
 
@@ -75,7 +76,7 @@ pub fn decode_value(value_type: ValueDataType, bytes: &[u8]) -> Option<(Value, &
         }
         ValueDataType::Record => {
             let (raw, rest) = decode_bytes(bytes)?;
-            (Value::Record(raw), rest)
+            (Value::Record(Record::from(raw)), rest)
         }
         ValueDataType::Entity => {
             let (raw, rest) = decode_bytes(bytes)?;
@@ -87,6 +88,10 @@ pub fn decode_value(value_type: ValueDataType, bytes: &[u8]) -> Option<(Value, &
             let attribute = String::from_utf8(raw).ok()?.try_into().ok()?;
             (Value::Symbol(attribute), rest)
         }
+        ValueDataType::Instant => {
+            let (nanos, rest) = decode_i128(bytes)?;
+            (Value::Instant(Instant::from_unix_nanos(nanos)), rest)
+        }
     })
 }
 
@@ -112,7 +117,8 @@ mod tests {
             Value::Boolean(true),
             Value::String("hello".into()),
             Value::Bytes(vec![1, 2, 3]),
-            Value::Record(vec![9, 8, 7]),
+            Value::Record(Record::from(vec![9, 8, 7])),
+            Value::Instant(Instant::from_unix_nanos(-1_500)),
         ];
         for value in values {
             let encoded = encode_value_owned(&value);
@@ -155,6 +161,18 @@ mod tests {
         Ok(())
     }
 
+    /// Instants sort chronologically, including across the Unix epoch, so a
+    /// VAE range scan over a time window returns exactly that window.
+    #[dialog_common::test]
+    async fn it_orders_instants_chronologically() -> anyhow::Result<()> {
+        let before = encode_value_owned(&Value::Instant(Instant::from_unix_seconds(-86_400)));
+        let epoch = encode_value_owned(&Value::Instant(Instant::UNIX_EPOCH));
+        let after = encode_value_owned(&Value::Instant(Instant::from_unix_nanos(1)));
+        let later = encode_value_owned(&Value::Instant(Instant::from_unix_seconds(1_700_000_000)));
+        assert!(before < epoch && epoch < after && after < later);
+        Ok(())
+    }
+
     /// Strings sort lexically and are prefix-safe (the terminator).
     #[dialog_common::test]
     async fn it_orders_strings_prefix_safely() -> anyhow::Result<()> {
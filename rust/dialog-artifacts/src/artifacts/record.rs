@@ -0,0 +1,464 @@
+//! Record values: structured data stored as one atomic [`Value`].
+//!
+//! Some values are compound but still atomic — a geolocation's latitude and
+//! longitude, a color's channels — and splitting them into separate claims
+//! lets the parts drift apart. A [`Record`] keeps such a value in one claim.
+//! The storage and query layers carry, compare, index and replicate its bytes
+//! without looking inside; only a type implementing [`RecordFormat`] knows
+//! how to read them, and it does so through [`Record::realize`], which
+//! decodes once per record and type and reuses the decoded form thereafter.
+//!
+//! This crate knows two formats, both self-describing so that a reader can
+//! take a record apart without knowing which Rust type wrote it:
+//! [`RecordFields`], named fields each holding a [`Value`], and
+//! [`RecordList`], an ordered sequence of [`Value`]s.
+
+use std::any::{Any, TypeId};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+
+use dialog_common::{ConditionalSend, ConditionalSync};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_bytes::ByteBuf;
+
+use crate::{RecordError, Value, ValueDataType, decode_value, encode_value_owned};
+
+/// A decoded form memoized on a [`Record`] (an `Arc<F>`), erased to its
+/// type.
+#[cfg(not(target_arch = "wasm32"))]
+type Form = Box<dyn Any + Send + Sync>;
+
+/// A decoded form memoized on a [`Record`] (an `Arc<F>`), erased to its
+/// type.
+#[cfg(target_arch = "wasm32")]
+type Form = Box<dyn Any>;
+
+/// A type whose values are stored as [`Record`]s: it encodes itself into a
+/// record's bytes and decodes itself back out of them.
+///
+/// Encoding must be deterministic — records compare, hash and index by
+/// their bytes, so two equal forms must encode identically.
+pub trait RecordFormat: Any + ConditionalSend + ConditionalSync + Sized {
+    /// Decode a form from record bytes.
+    fn decode(bytes: &[u8]) -> Result<Self, RecordError>;
+
+    /// Encode this form into record bytes.
+    fn encode(&self) -> Result<Vec<u8>, RecordError>;
+}
+
+struct RecordState {
+    source: Vec<u8>,
+    forms: RwLock<HashMap<TypeId, Form>>,
+}
+
+/// A structured value, held as the bytes its [`RecordFormat`] encodes.
+///
+/// Clones share one interior, so a form decoded through any clone is
+/// reused by all of them. Equality, ordering and hashing are over the
+/// bytes alone, never the memoized forms, so records are safe to use as
+/// map keys.
+#[derive(Clone)]
+pub struct Record(Arc<RecordState>);
+
+impl Record {
+    /// Encode `form` into a record, keeping `form` as the record's decoded
+    /// form so realizing it as `F` costs nothing.
+    pub fn encode<F: RecordFormat>(form: F) -> Result<Self, RecordError> {
+        let record = Self::from(form.encode()?);
+        record.memoize(Arc::new(form));
+        Ok(record)
+    }
+
+    /// The record's bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0.source
+    }
+
+    /// The record's bytes, owned.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.0.source.clone()
+    }
+
+    /// The number of bytes in the record.
+    pub fn len(&self) -> usize {
+        self.0.source.len()
+    }
+
+    /// Whether the record holds no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.0.source.is_empty()
+    }
+
+    /// The record read as `F`.
+    ///
+    /// The first call for a given `F` decodes the bytes and memoizes the
+    /// form; later calls (through any clone) return the memoized form. When
+    /// the memo is contended, the bytes are decoded directly rather than
+    /// waiting for it.
+    pub fn realize<F: RecordFormat>(&self) -> Result<Arc<F>, RecordError> {
+        if let Ok(forms) = self.0.forms.try_read()
+            && let Some(form) = forms
+                .get(&TypeId::of::<F>())
+                .and_then(|form| form.downcast_ref::<Arc<F>>())
+        {
+            return Ok(form.clone());
+        }
+        let form = Arc::new(F::decode(&self.0.source)?);
+        self.memoize(form.clone());
+        Ok(form)
+    }
+
+    /// Memoize `form` as the record read as `F`, unless the memo is
+    /// contended.
+    fn memoize<F: RecordFormat>(&self, form: Arc<F>) {
+        if let Ok(mut forms) = self.0.forms.try_write() {
+            forms.insert(TypeId::of::<F>(), Box::new(form));
+        }
+    }
+}
+
+impl From<Vec<u8>> for Record {
+    fn from(source: Vec<u8>) -> Self {
+        Self(Arc::new(RecordState {
+            source,
+            forms: RwLock::new(HashMap::new()),
+        }))
+    }
+}
+
+impl From<&[u8]> for Record {
+    fn from(source: &[u8]) -> Self {
+        Self::from(source.to_vec())
+    }
+}
+
+impl AsRef<[u8]> for Record {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Debug for Record {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        const PREFIX: usize = 16;
+        write!(f, "Record(")?;
+        for byte in self.as_bytes().iter().take(PREFIX) {
+            write!(f, "{byte:02x}")?;
+        }
+        if self.len() > PREFIX {
+            write!(f, "…")?;
+        }
+        write!(f, ", {} bytes)", self.len())
+    }
+}
+
+impl PartialEq for Record {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for Record {}
+
+impl Hash for Record {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state)
+    }
+}
+
+impl PartialOrd for Record {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Record {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_bytes().cmp(other.as_bytes())
+    }
+}
+
+/// The serialized shape of a [`Record`]: `{"record": <bytes>}`.
+///
+/// The wrapping key is what tells a record apart from plain bytes when a
+/// [`Value`] is read back without its type — in the formal notation a
+/// bare byte array is [`Value::Bytes`].
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecordRepr {
+    record: ByteBuf,
+}
+
+impl Serialize for Record {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RecordRepr {
+            record: ByteBuf::from(self.to_vec()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Record {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = RecordRepr::deserialize(deserializer)?;
+        Ok(Self::from(repr.record.into_vec()))
+    }
+}
+
+/// A self-describing record of named fields, each holding a [`Value`].
+///
+/// Each field is stored as its value's type byte followed by the value's
+/// order-preserving encoding (the form keys carry), in a dag-cbor map keyed
+/// by field name, so every [`Value`] — a nested record included —
+/// round-trips exactly. Types that want their fields projectable in queries
+/// encode through this format.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RecordFields(BTreeMap<String, Value>);
+
+impl RecordFields {
+    /// An empty set of fields.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add (or overwrite) the field `name`.
+    pub fn with(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.0.insert(name.into(), value.into());
+        self
+    }
+
+    /// The value of the field `name`, if present.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+
+    /// The fields, in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.0.iter().map(|(name, value)| (name.as_str(), value))
+    }
+}
+
+impl<N: Into<String>> FromIterator<(N, Value)> for RecordFields {
+    fn from_iter<I: IntoIterator<Item = (N, Value)>>(fields: I) -> Self {
+        Self(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name.into(), value))
+                .collect(),
+        )
+    }
+}
+
+impl RecordFormat for RecordFields {
+    fn decode(bytes: &[u8]) -> Result<Self, RecordError> {
+        let encoded: BTreeMap<String, ByteBuf> = serde_ipld_dagcbor::from_slice(bytes)
+            .map_err(|error| RecordError::Decode(error.to_string()))?;
+        let mut fields = BTreeMap::new();
+        for (name, field) in encoded {
+            let value = decode_tagged(&field)
+                .ok_or_else(|| RecordError::Decode(format!("malformed field {name:?}")))?;
+            fields.insert(name, value);
+        }
+        Ok(Self(fields))
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, RecordError> {
+        let encoded: BTreeMap<&str, ByteBuf> = self
+            .0
+            .iter()
+            .map(|(name, value)| (name.as_str(), encode_tagged(value)))
+            .collect();
+        serde_ipld_dagcbor::to_vec(&encoded).map_err(|error| RecordError::Encode(error.to_string()))
+    }
+}
+
+/// A self-describing record of [`Value`]s in order, such as the members
+/// `collect-set` gathers.
+///
+/// Each member is stored as [`RecordFields`] stores a field — its type byte
+/// followed by its order-preserving encoding — in a dag-cbor array, so the
+/// members round-trip exactly and in the order they were given.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RecordList(Vec<Value>);
+
+impl RecordList {
+    /// An empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The members, in order.
+    pub fn iter(&self) -> impl Iterator<Item = &Value> {
+        self.0.iter()
+    }
+
+    /// The number of members.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the list has no members.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<Value>> for RecordList {
+    fn from(members: Vec<Value>) -> Self {
+        Self(members)
+    }
+}
+
+impl FromIterator<Value> for RecordList {
+    fn from_iter<I: IntoIterator<Item = Value>>(members: I) -> Self {
+        Self(members.into_iter().collect())
+    }
+}
+
+impl RecordFormat for RecordList {
+    fn decode(bytes: &[u8]) -> Result<Self, RecordError> {
+        let encoded: Vec<ByteBuf> = serde_ipld_dagcbor::from_slice(bytes)
+            .map_err(|error| RecordError::Decode(error.to_string()))?;
+        encoded
+            .iter()
+            .enumerate()
+            .map(|(index, member)| {
+                decode_tagged(member)
+                    .ok_or_else(|| RecordError::Decode(format!("malformed member {index}")))
+            })
+            .collect()
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, RecordError> {
+        let encoded: Vec<ByteBuf> = self.0.iter().map(encode_tagged).collect();
+        serde_ipld_dagcbor::to_vec(&encoded).map_err(|error| RecordError::Encode(error.to_string()))
+    }
+}
+
+/// A value as a record part: its type byte, then its order-preserving
+/// encoding.
+fn encode_tagged(value: &Value) -> ByteBuf {
+    let mut tagged = vec![u8::from(value.data_type())];
+    tagged.extend(encode_value_owned(value));
+    ByteBuf::from(tagged)
+}
+
+/// The value an [`encode_tagged`] part holds, if it is well formed.
+fn decode_tagged(tagged: &[u8]) -> Option<Value> {
+    let (&tag, rest) = tagged.split_first()?;
+    if tag > u8::from(ValueDataType::max()) {
+        return None;
+    }
+    match decode_value(ValueDataType::from(tag), rest) {
+        Some((value, [])) => Some(value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::Entity;
+
+    #[derive(Debug, PartialEq)]
+    struct Point {
+        x: f64,
+        y: f64,
+    }
+
+    impl RecordFormat for Point {
+        fn decode(bytes: &[u8]) -> Result<Self, RecordError> {
+            let fields = RecordFields::decode(bytes)?;
+            match (fields.get("x"), fields.get("y")) {
+                (Some(Value::Float(x)), Some(Value::Float(y))) => Ok(Point { x: *x, y: *y }),
+                _ => Err(RecordError::Decode("not a point".into())),
+            }
+        }
+
+        fn encode(&self) -> Result<Vec<u8>, RecordError> {
+            RecordFields::new()
+                .with("x", self.x)
+                .with("y", self.y)
+                .encode()
+        }
+    }
+
+    #[dialog_common::test]
+    fn it_memoizes_the_decoded_form() -> Result<(), RecordError> {
+        let record = Record::from(Point { x: 1.0, y: -2.5 }.encode()?);
+        let first = record.realize::<Point>()?;
+        let again = record.clone().realize::<Point>()?;
+        assert!(Arc::ptr_eq(&first, &again));
+        assert_eq!(*first, Point { x: 1.0, y: -2.5 });
+
+        // The same bytes read as a different format decode independently.
+        let fields = record.realize::<RecordFields>()?;
+        assert_eq!(fields.get("y"), Some(&Value::Float(-2.5)));
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    fn it_compares_records_by_their_bytes() -> Result<(), RecordError> {
+        let encoded = Record::encode(Point { x: 3.0, y: 4.0 })?;
+        let read = Record::from(encoded.to_vec());
+        assert_eq!(encoded, read);
+        assert_ne!(encoded, Record::encode(Point { x: 4.0, y: 3.0 })?);
+        assert!(read.realize::<Point>().is_ok());
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    fn it_round_trips_every_field_type() -> Result<(), RecordError> {
+        let nested = Record::encode(Point { x: 0.5, y: 0.25 })?;
+        let fields = RecordFields::new()
+            .with("name", "origin".to_string())
+            .with("count", 3u128)
+            .with("delta", -7i128)
+            .with("flag", true)
+            .with("raw", vec![0u8, 0xFF, 0])
+            .with("owner", Entity::new().unwrap())
+            .with("point", Value::Record(nested));
+        let decoded = RecordFields::decode(&fields.encode()?)?;
+        assert_eq!(decoded, fields);
+        assert!(RecordFields::decode(b"not cbor").is_err());
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    fn it_round_trips_a_list_in_order() -> Result<(), RecordError> {
+        let list: RecordList = [
+            Value::String("pear".to_string()),
+            Value::UnsignedInt(3),
+            Value::String("apple".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(RecordList::decode(&list.encode()?)?, list);
+        assert!(RecordList::decode(&RecordFields::new().encode()?).is_err());
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    fn it_serializes_apart_from_bytes() {
+        let value = Value::Record(Record::from(vec![1, 2, 3]));
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(json, serde_json::json!({ "record": [1, 2, 3] }));
+        assert_eq!(serde_json::from_value::<Value>(json).unwrap(), value);
+        assert_eq!(
+            serde_json::from_value::<Value>(serde_json::json!([1, 2, 3])).unwrap(),
+            Value::Bytes(vec![1, 2, 3])
+        );
+
+        let cbor = serde_ipld_dagcbor::to_vec(&value).unwrap();
+        assert_eq!(
+            serde_ipld_dagcbor::from_slice::<Value>(&cbor).unwrap(),
+            value
+        );
+    }
+}
@@ -12,7 +12,9 @@ use std::{
     str::FromStr,
 };
 
-use crate::{Attribute, Cause, DialogArtifactsError, Entity, TypeError, make_reference};
+use crate::{
+    Attribute, Cause, DialogArtifactsError, Entity, Instant, Record, TypeError, make_reference,
+};
 use base58::{FromBase58, ToBase58};
 use dialog_storage::Blake3Hash;
 use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
@@ -36,10 +38,12 @@ pub enum Value {
     SignedInt(i128),
     /// A floating point number
     Float(f64),
-    /// TBD structured data (flatbuffers?)
-    Record(Vec<u8>),
+    /// Structured data, read through a [`RecordFormat`](crate::RecordFormat)
+    Record(Record),
     /// A symbol type, used to distinguish attributes from other strings
     Symbol(Attribute),
+    /// A point in time (see [`Instant`])
+    Instant(Instant),
 }
 
 impl Value {
@@ -55,6 +59,7 @@ impl Value {
             Value::Float(_) => ValueDataType::Float,
             Value::Record(_) => ValueDataType::Record,
             Value::Symbol(_) => ValueDataType::Symbol,
+            Value::Instant(_) => ValueDataType::Instant,
         }
     }
 
@@ -68,9 +73,10 @@ impl Value {
             Value::UnsignedInt(value) => value.to_le_bytes().to_vec(),
             Value::SignedInt(value) => value.to_le_bytes().to_vec(),
             Value::Float(value) => value.to_le_bytes().to_vec(),
-            Value::Record(value) => value.to_owned(),
+            Value::Record(value) => value.to_vec(),
             // TODO: Change this to bytes of string representation
             Value::Symbol(value) => value.key_bytes().to_vec(),
+            Value::Instant(instant) => instant.to_le_bytes().to_vec(),
         }
     }
 
@@ -84,8 +90,9 @@ impl Value {
             Value::UnsignedInt(number) => format!("uint:{}", number),
             Value::SignedInt(number) => format!("sint:{}", number),
             Value::Float(number) => format!("float:{}", number),
-            Value::Record(record) => format!("record:{}", record.to_base58()),
+            Value::Record(record) => format!("record:{}", record.as_bytes().to_base58()),
             Value::Symbol(attribute) => format!("attribute:{}", attribute),
+            Value::Instant(instant) => format!("instant:{}", instant),
         }
     }
 
@@ -107,6 +114,7 @@ impl Hash for Value {
             Value::SignedInt(i) => i.hash(state),
             Value::Record(r) => r.hash(state),
             Value::Symbol(s) => s.hash(state),
+            Value::Instant(i) => i.hash(state),
         }
     }
 }
@@ -123,6 +131,7 @@ impl PartialEq for Value {
             (Value::Float(a), Value::Float(b)) => a.to_le_bytes() == b.to_le_bytes(),
             (Value::Record(a), Value::Record(b)) => a == b,
             (Value::Symbol(a), Value::Symbol(b)) => a == b,
+            (Value::Instant(a), Value::Instant(b)) => a == b,
             _ => false,
         }
     }
@@ -180,8 +189,13 @@ impl FromStr for Value {
             "uint" => Value::UnsignedInt(value.parse().map_err(to_dialog_error)?),
             "sint" => Value::SignedInt(value.parse().map_err(to_dialog_error)?),
             "float" => Value::Float(value.parse().map_err(to_dialog_error)?),
-            "record" => Value::Record(value.from_base58().map_err(to_dialog_error_debug)?),
+            "record" => Value::Record(Record::from(
+                value.from_base58().map_err(to_dialog_error_debug)?,
+            )),
             "attribute" => Value::Symbol(Attribute::from_str(value)?),
+            "instant" => Value::Instant(Instant::from_unix_nanos(
+                value.parse().map_err(to_dialog_error)?,
+            )),
             _ => {
                 return Err(DialogArtifactsError::InvalidValue(
                     "Value part of serialized string is empty".into(),
@@ -243,12 +257,12 @@ impl TryFrom<(ValueDataType, Vec<u8>)> for Value {
                 },
             )?)),
             // A record is opaque bytes at this layer; interpretation is the
-            // reader's concern (see e.g. `history::RevisionRecord`). Its byte
+            // reader's concern, through `Record::realize`. Its byte
             // representation is its raw bytes (`to_bytes` returns them
             // verbatim), so reconstruction is the identity. This is also the
             // spilled-value read-back path: a record above the inline
             // threshold must round-trip, not panic.
-            ValueDataType::Record => Value::Record(value),
+            ValueDataType::Record => Value::Record(Record::from(value)),
             ValueDataType::Symbol => match String::from_utf8(value) {
                 Ok(value) => Value::Symbol(Attribute::try_from(
                     value.split('\u{0000}').take(1).collect::<String>(),
@@ -259,6 +273,14 @@ impl TryFrom<(ValueDataType, Vec<u8>)> for Value {
                     )));
                 }
             },
+            ValueDataType::Instant => Value::Instant(Instant::from_le_bytes(
+                value.try_into().map_err(|value: Vec<u8>| {
+                    DialogArtifactsError::InvalidValue(format!(
+                        "Wrong number of bytes for instant (expected 16, got {})",
+                        value.len()
+                    ))
+                })?,
+            )),
         })
     }
 }
@@ -277,6 +299,20 @@ impl TryFrom<Value> for Attribute {
     }
 }
 
+impl TryFrom<Value> for Instant {
+    type Error = TypeError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Instant(instant) => Ok(instant),
+            _ => Err(TypeError::TypeMismatch(
+                ValueDataType::Instant,
+                value.data_type(),
+            )),
+        }
+    }
+}
+
 impl TryFrom<Value> for Entity {
     type Error = TypeError;
 
@@ -544,6 +580,26 @@ impl TryFrom<Value> for Vec<u8> {
     }
 }
 
+impl TryFrom<Value> for Record {
+    type Error = TypeError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Record(record) => Ok(record),
+            _ => Err(TypeError::TypeMismatch(
+                ValueDataType::Record,
+                value.data_type(),
+            )),
+        }
+    }
+}
+
+impl From<Record> for Value {
+    fn from(value: Record) -> Self {
+        Value::Record(value)
+    }
+}
+
 impl From<Vec<u8>> for Value {
     fn from(value: Vec<u8>) -> Self {
         Value::Bytes(value)
@@ -646,6 +702,12 @@ impl From<Attribute> for Value {
     }
 }
 
+impl From<Instant> for Value {
+    fn from(value: Instant) -> Self {
+        Value::Instant(value)
+    }
+}
+
 impl From<usize> for Value {
     fn from(value: usize) -> Self {
         Value::UnsignedInt(value as u128)
@@ -676,6 +738,15 @@ impl PartialEq<Value> for Attribute {
     }
 }
 
+impl PartialEq<Value> for Instant {
+    fn eq(&self, other: &Value) -> bool {
+        match other {
+            Value::Instant(instant) => self == instant,
+            _ => false,
+        }
+    }
+}
+
 impl PartialEq<Value> for String {
     fn eq(&self, other: &Value) -> bool {
         match other {
@@ -852,10 +923,13 @@ pub enum ValueDataType {
     SignedInt = 5,
     /// A floating point number
     Float = 6,
-    /// TBD structured data (flatbuffers?)
+    /// Structured data, read through a [`RecordFormat`](crate::RecordFormat)
+    /// (see [`Record`])
     Record = 7,
     /// A symbol type, used to distinguish attributes from other strings
     Symbol = 8,
+    /// A point in time (see [`Instant`])
+    Instant = 9,
 }
 
 impl ValueDataType {
@@ -866,7 +940,7 @@ impl ValueDataType {
 
     /// The largest [`ValueDataType`] in discriminant order
     pub fn max() -> Self {
-        ValueDataType::Symbol
+        ValueDataType::Instant
     }
 
     /// Check if the given value is of this type.
@@ -897,6 +971,7 @@ impl Display for ValueDataType {
             ValueDataType::Float => write!(f, "Float"),
             ValueDataType::Record => write!(f, "Record"),
             ValueDataType::Symbol => write!(f, "Symbol"),
+            ValueDataType::Instant => write!(f, "Instant"),
         }
     }
 }
@@ -919,6 +994,7 @@ impl From<&u8> for ValueDataType {
             6 => ValueDataType::Float,
             7 => ValueDataType::Record,
             8 => ValueDataType::Symbol,
+            9 => ValueDataType::Instant,
             // A lossy fallback for infallible callers; the key-parse path
             // rejects unknown discriminants before reaching this (see
             // `varkey::value_payload_len`), so no persisted-byte path relies
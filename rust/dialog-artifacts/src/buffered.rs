@@ -498,6 +498,26 @@ impl BufferedBatch {
         Ok(self)
     }
 
+    /// Removes `keys` from the open buffered tree, as ordinary buffered
+    /// deletes sealed with the rest of the batch.
+    ///
+    /// For machinery that retires entries outside the instruction semantics
+    /// (history compaction erasing the records a checkpoint replaces):
+    /// unlike a retraction, the removal records no history of its own.
+    #[tracing::instrument(skip_all, name = "buffer_erasures")]
+    pub async fn erase<S>(mut self, store: &S, keys: Vec<Key>) -> Result<Self, DialogArtifactsError>
+    where
+        S: StorageBackend<Key = Blake3Hash, Value = Vec<u8>, Error = DialogStorageError>
+            + Clone
+            + ConditionalSync,
+    {
+        let storage = ContentAddressedStorage::new(TreeStorageBridge(store.clone()));
+        for key in keys {
+            self.tree = self.tree.erase(&key, &storage).await?;
+        }
+        Ok(self)
+    }
+
     /// Seals the whole batch, data and record entries alike, into `delta` with
     /// a single persist, returning the resulting tree.
     ///
@@ -0,0 +1,462 @@
+//! The fact-level differential: what changed between two artifact trees.
+//!
+//! [`TreeDifference`] already names the entries that differ between two
+//! roots while reading only the nodes that differ. [`artifact_changes`]
+//! lifts that to facts: it walks the entity-ordered (EAV) region of the
+//! differential — each fact surfaces in all three orderings, so one is read
+//! — decodes every changed entry back into an [`Artifact`], and folds the
+//! entries of each `(entity, attribute)` into [`ArtifactChange`]s.
+//!
+//! # What counts as a replacement
+//!
+//! The trees hold state, not the instructions that produced it, so a change
+//! is classified by its shape: when the facts of one `(entity, attribute)`
+//! lose exactly one value and gain exactly one, that is reported as
+//! [`ArtifactChange::Replaced`]; any other mix is reported as the individual
+//! assertions and retractions. A many-valued attribute that dropped one value
+//! and gained another between the two trees therefore reads as a
+//! replacement — the two are indistinguishable without the history region,
+//! and the history region only orders revisions on one lineage, whereas the
+//! two trees here may be any two trees.
+//!
+//! An entry whose payload changed but whose fact did not — a claim version
+//! collapsing into an existing datum, say — surfaces in the differential as
+//! a remove and an add at the same key; the two cancel and report nothing.
+
+use async_stream::try_stream;
+use dialog_common::{ConditionalSend, ConditionalSync};
+use dialog_search_tree::{Change, ContentAddressedStorage, Manifest, TreeDifference};
+use dialog_storage::{Blake3Hash, DialogStorageError, StorageBackend};
+use futures_util::Stream;
+
+use crate::{
+    Artifact, ArtifactSelector, Attribute, DialogArtifactsError, Entity, EntityKey, Key,
+    KeyViewConstruct, SelectorMatch, State,
+    key::varkey::parse_key_ref,
+    match_selector_and_key_ref,
+    selector::Constrained,
+    tree::{ArtifactTree, TreeStorageBridge, fetch_spilled, selector_range},
+    value_predicates_admit,
+};
+
+/// A change to one fact between two tree versions.
+#[derive(Clone, Debug, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum ArtifactChange {
+    /// A fact present in the target tree but not the source.
+    Asserted(Artifact),
+    /// A fact present in the source tree but not the target.
+    Retracted(Artifact),
+    /// The single value an `(entity, attribute)` held in the source tree
+    /// gave way to a single different value in the target.
+    Replaced {
+        /// The fact as it stood in the source tree.
+        from: Artifact,
+        /// The fact as it stands in the target tree.
+        to: Artifact,
+    },
+}
+
+impl ArtifactChange {
+    /// The entity whose fact changed.
+    pub fn of(&self) -> &Entity {
+        match self {
+            ArtifactChange::Asserted(artifact) | ArtifactChange::Retracted(artifact) => {
+                &artifact.of
+            }
+            ArtifactChange::Replaced { to, .. } => &to.of,
+        }
+    }
+
+    /// The attribute whose fact changed.
+    pub fn the(&self) -> &Attribute {
+        match self {
+            ArtifactChange::Asserted(artifact) | ArtifactChange::Retracted(artifact) => {
+                &artifact.the
+            }
+            ArtifactChange::Replaced { to, .. } => &to.the,
+        }
+    }
+}
+
+/// Stream the fact-level changes that take `source` to `target`, in entity
+/// then attribute order.
+///
+/// With a `selector`, only changes to facts it matches are yielded; a
+/// replacement is yielded whole when either of its sides matches. A
+/// selector naming an entity also narrows the differential itself to that
+/// entity's range, so nothing outside it is read; any other selector
+/// filters as the EAV region is walked. Both trees, and the spilled value
+/// blocks of changed facts, must be readable from `store`.
+pub fn artifact_changes<'s, S>(
+    source: ArtifactTree,
+    target: ArtifactTree,
+    store: S,
+    selector: Option<ArtifactSelector<Constrained>>,
+) -> impl Stream<Item = Result<ArtifactChange, DialogArtifactsError>> + 's + ConditionalSend
+where
+    S: StorageBackend<Key = Blake3Hash, Value = Vec<u8>, Error = DialogStorageError>
+        + Clone
+        + ConditionalSync
+        + 's,
+{
+    // Keep the raw backend to fetch spilled value blocks by reference; the
+    // bridge below only reads tree nodes.
+    let raw_store = store.clone();
+    let storage = ContentAddressedStorage::new(TreeStorageBridge(store));
+    try_stream! {
+        // Each side's keys were built under that side's manifest, so a
+        // changed entry is matched against the selector under the manifest
+        // of the tree it came from.
+        let source_manifest = source.manifest(&storage).await?;
+        let target_manifest = target.manifest(&storage).await?;
+
+        // The scope is built from the entity alone: an entity range does
+        // not depend on how either manifest encodes values.
+        let scope = match selector.as_ref().and_then(|selector| selector.entity()) {
+            Some(entity) => vec![selector_range(
+                &ArtifactSelector::new().of(entity.clone()),
+                &target_manifest,
+            )],
+            None => vec![
+                <EntityKey<Key> as KeyViewConstruct>::min().into_key()
+                    ..=<EntityKey<Key> as KeyViewConstruct>::max().into_key(),
+            ],
+        };
+
+        let difference =
+            TreeDifference::compute_within(&source, &target, &storage, &storage, &scope).await?;
+        let changes = difference.changes_within(&scope);
+        tokio::pin!(changes);
+
+        // The region is entity-then-attribute ordered, so every entry of an
+        // `(entity, attribute)` arrives before the next one starts.
+        let mut group: Option<Group> = None;
+        for await change in changes {
+            let (entry, removed) = match change? {
+                Change::Add(entry) => (entry, false),
+                Change::Remove(entry) => (entry, true),
+            };
+            // Retraction deletes a fact's keys outright; a tombstone at a
+            // key carries no fact of its own.
+            let State::Added(datum) = &entry.value else {
+                continue;
+            };
+            let manifest = if removed { &source_manifest } else { &target_manifest };
+            let spilled = fetch_spilled(&raw_store, &entry.key).await?;
+            let artifact = Artifact::from_key_datum_with_value(&entry.key, datum, spilled)?;
+            let admitted = match &selector {
+                Some(selector) => admits(selector, &entry.key, &artifact, manifest)?,
+                None => true,
+            };
+
+            if let Some(current) = group.take_if(|current| !current.holds(&artifact)) {
+                for change in current.settle() {
+                    yield change;
+                }
+            }
+            group
+                .get_or_insert_with(Group::default)
+                .push(Side { artifact, admitted }, removed);
+        }
+        if let Some(current) = group {
+            for change in current.settle() {
+                yield change;
+            }
+        }
+    }
+}
+
+/// Whether `selector` matches the fact stored at `key`, decided from the
+/// key where it can be and from the decoded value where it cannot.
+fn admits(
+    selector: &ArtifactSelector<Constrained>,
+    key: &Key,
+    artifact: &Artifact,
+    manifest: &Manifest,
+) -> Result<bool, DialogArtifactsError> {
+    let parts = parse_key_ref(key.as_ref()).ok_or_else(|| {
+        DialogArtifactsError::InvalidKey("changed entry's key does not parse".to_string())
+    })?;
+    Ok(
+        match match_selector_and_key_ref(selector, &parts, manifest) {
+            SelectorMatch::Matches => true,
+            SelectorMatch::Excluded => false,
+            SelectorMatch::NeedsValue => value_predicates_admit(selector, &artifact.is),
+        },
+    )
+}
+
+/// One decoded fact on one side of the differential.
+struct Side {
+    artifact: Artifact,
+    admitted: bool,
+}
+
+/// The changed facts of one `(entity, attribute)`, gathered until the walk
+/// moves past it.
+#[derive(Default)]
+struct Group {
+    removed: Vec<Side>,
+    added: Vec<Side>,
+}
+
+impl Group {
+    /// Whether `artifact` belongs to this group's `(entity, attribute)`.
+    fn holds(&self, artifact: &Artifact) -> bool {
+        self.removed
+            .iter()
+            .chain(&self.added)
+            .next()
+            .is_none_or(|side| side.artifact.of == artifact.of && side.artifact.the == artifact.the)
+    }
+
+    fn push(&mut self, side: Side, removed: bool) {
+        if removed {
+            self.removed.push(side);
+        } else {
+            self.added.push(side);
+        }
+    }
+
+    /// Classify the group's net changes, keeping those the selector admits.
+    fn settle(mut self) -> Vec<ArtifactChange> {
+        // A fact on both sides only had its payload rewritten.
+        let added = &self.added;
+        self.removed.retain(|removed| {
+            !added
+                .iter()
+                .any(|added| added.artifact.is == removed.artifact.is)
+        });
+        let removed = &self.removed;
+        self.added.retain(|added| {
+            !removed
+                .iter()
+                .any(|removed| removed.artifact.is == added.artifact.is)
+        });
+
+        if let ([from], [to]) = (self.removed.as_slice(), self.added.as_slice()) {
+            return if from.admitted || to.admitted {
+                vec![ArtifactChange::Replaced {
+                    from: from.artifact.clone(),
+                    to: to.artifact.clone(),
+                }]
+            } else {
+                vec![]
+            };
+        }
+
+        let retracted = self
+            .removed
+            .into_iter()
+            .filter(|side| side.admitted)
+            .map(|side| ArtifactChange::Retracted(side.artifact));
+        let asserted = self
+            .added
+            .into_iter()
+            .filter(|side| side.admitted)
+            .map(|side| ArtifactChange::Asserted(side.artifact));
+        retracted.chain(asserted).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::tree::ArtifactTreeExt;
+    use crate::{Instruction, Value};
+    use dialog_search_tree::{Buffer, Delta};
+    use dialog_storage::MemoryStorageBackend;
+    use futures_util::{TryStreamExt, stream};
+
+    async fn flush(
+        store: &mut MemoryStorageBackend<Blake3Hash, Vec<u8>>,
+        delta: &mut Delta<dialog_common::Blake3Hash, Buffer>,
+    ) -> Result<(), DialogArtifactsError> {
+        for (_, buffer) in delta.flush() {
+            store
+                .set(*buffer.blake3_hash().as_bytes(), buffer.as_ref().to_vec())
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn fact(of: &str, the: &str, is: Value) -> Artifact {
+        Artifact {
+            the: the.parse().unwrap(),
+            of: of.parse().unwrap(),
+            is,
+            cause: None,
+        }
+    }
+
+    /// Compare changes on their facts alone: decoded artifacts carry the
+    /// cause their datum was written with.
+    fn facts(changes: Vec<ArtifactChange>) -> Vec<ArtifactChange> {
+        let bare = |artifact: Artifact| Artifact {
+            cause: None,
+            ..artifact
+        };
+        changes
+            .into_iter()
+            .map(|change| match change {
+                ArtifactChange::Asserted(artifact) => ArtifactChange::Asserted(bare(artifact)),
+                ArtifactChange::Retracted(artifact) => ArtifactChange::Retracted(bare(artifact)),
+                ArtifactChange::Replaced { from, to } => ArtifactChange::Replaced {
+                    from: bare(from),
+                    to: bare(to),
+                },
+            })
+            .collect()
+    }
+
+    #[dialog_common::test]
+    async fn it_reports_asserted_retracted_and_replaced_facts() -> Result<(), DialogArtifactsError>
+    {
+        let mut store = MemoryStorageBackend::<Blake3Hash, Vec<u8>>::default();
+        let mut delta = Delta::zero();
+
+        let alice_name = fact("user:alice", "user/name", Value::String("Alice".into()));
+        let bob_name = fact("user:bob", "user/name", Value::String("Bob".into()));
+        let bob_tag = fact("user:bob", "user/tag", Value::String("admin".into()));
+        let carol_name = fact("user:carol", "user/name", Value::String("Carol".into()));
+
+        let mut source = ArtifactTree::empty();
+        source
+            .apply(
+                &mut store,
+                &mut delta,
+                stream::iter(vec![
+                    Instruction::Assert(alice_name.clone()),
+                    Instruction::Assert(bob_name.clone()),
+                    Instruction::Assert(bob_tag.clone()),
+                ]),
+            )
+            .await?;
+        flush(&mut store, &mut delta).await?;
+
+        let renamed = fact("user:alice", "user/name", Value::String("Alicia".into()));
+        let mut target = source.clone();
+        target
+            .apply(
+                &mut store,
+                &mut delta,
+                stream::iter(vec![
+                    Instruction::Replace(renamed.clone()),
+                    Instruction::Retract(bob_tag.clone()),
+                    Instruction::Assert(carol_name.clone()),
+                ]),
+            )
+            .await?;
+        flush(&mut store, &mut delta).await?;
+
+        let changes: Vec<_> = artifact_changes(source.clone(), target.clone(), store.clone(), None)
+            .try_collect()
+            .await?;
+        assert_eq!(
+            facts(changes),
+            vec![
+                ArtifactChange::Replaced {
+                    from: alice_name.clone(),
+                    to: renamed.clone(),
+                },
+                ArtifactChange::Retracted(bob_tag.clone()),
+                ArtifactChange::Asserted(carol_name.clone()),
+            ]
+        );
+
+        let reversed: Vec<_> =
+            artifact_changes(target.clone(), source.clone(), store.clone(), None)
+                .try_collect()
+                .await?;
+        assert_eq!(
+            facts(reversed),
+            vec![
+                ArtifactChange::Replaced {
+                    from: renamed.clone(),
+                    to: alice_name.clone(),
+                },
+                ArtifactChange::Asserted(bob_tag.clone()),
+                ArtifactChange::Retracted(carol_name.clone()),
+            ]
+        );
+
+        let unchanged: Vec<_> = artifact_changes(target.clone(), target, store, None)
+            .try_collect()
+            .await?;
+        assert!(unchanged.is_empty());
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_filters_changes_by_selector() -> Result<(), DialogArtifactsError> {
+        let mut store = MemoryStorageBackend::<Blake3Hash, Vec<u8>>::default();
+        let mut delta = Delta::zero();
+
+        let alice_name = fact("user:alice", "user/name", Value::String("Alice".into()));
+        let renamed = fact("user:alice", "user/name", Value::String("Alicia".into()));
+        let alice_age = fact("user:alice", "user/age", Value::UnsignedInt(30));
+        let bob_name = fact("user:bob", "user/name", Value::String("Bob".into()));
+
+        let mut source = ArtifactTree::empty();
+        source
+            .apply(
+                &mut store,
+                &mut delta,
+                stream::iter(vec![Instruction::Assert(alice_name.clone())]),
+            )
+            .await?;
+        flush(&mut store, &mut delta).await?;
+
+        let mut target = source.clone();
+        target
+            .apply(
+                &mut store,
+                &mut delta,
+                stream::iter(vec![
+                    Instruction::Replace(renamed.clone()),
+                    Instruction::Assert(alice_age.clone()),
+                    Instruction::Assert(bob_name.clone()),
+                ]),
+            )
+            .await?;
+        flush(&mut store, &mut delta).await?;
+
+        let by_entity = ArtifactSelector::new().of(alice_age.of.clone());
+        let changes: Vec<_> = artifact_changes(
+            source.clone(),
+            target.clone(),
+            store.clone(),
+            Some(by_entity),
+        )
+        .try_collect()
+        .await?;
+        assert_eq!(
+            facts(changes),
+            vec![
+                ArtifactChange::Asserted(alice_age.clone()),
+                ArtifactChange::Replaced {
+                    from: alice_name.clone(),
+                    to: renamed.clone(),
+                },
+            ]
+        );
+
+        // Only the replacement's source side matches; it is yielded whole.
+        let by_value = ArtifactSelector::new().is(alice_name.is.clone());
+        let changes: Vec<_> = artifact_changes(source, target, store, Some(by_value))
+            .try_collect()
+            .await?;
+        assert_eq!(
+            facts(changes),
+            vec![ArtifactChange::Replaced {
+                from: alice_name,
+                to: renamed,
+            }]
+        );
+        Ok(())
+    }
+}
@@ -143,6 +143,29 @@ pub enum TypeError {
     /// Expected type and actual type mismatch.
     #[error("Type mismatch: expected {0}, got {1}")]
     TypeMismatch(ValueDataType, ValueDataType),
+
+    /// A record value did not decode as the requested record type.
+    #[error(transparent)]
+    InvalidRecord(#[from] RecordError),
+}
+
+/// Errors converting between a [`Record`](crate::Record) and the typed
+/// form a [`RecordFormat`](crate::RecordFormat) gives it.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RecordError {
+    /// The typed form could not be encoded into record bytes.
+    #[error("Could not encode record: {0}")]
+    Encode(String),
+
+    /// The record bytes could not be decoded into the typed form.
+    #[error("Could not decode record: {0}")]
+    Decode(String),
+}
+
+impl From<RecordError> for DialogArtifactsError {
+    fn from(error: RecordError) -> Self {
+        Self::InvalidValue(error.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -18,6 +18,8 @@
 //! - [`causality`] implements the tiered conflict detection over a
 //!   [`History`] index, determining whether two claims on the same
 //!   `(entity, attribute)` are causally ordered or concurrent.
+//! - [`Retention`] bounds how much of that history a branch keeps, and
+//!   [`Checkpoint`] is the signed summary left where it was compacted.
 
 // The identity and clock half of version control lives in dialog-capability
 // (Revision's fields are built from it, and dialog-artifacts depends on that
@@ -82,6 +84,12 @@ pub use log::*;
 mod revision_record;
 pub use revision_record::*;
 
+mod checkpoint;
+pub use checkpoint::*;
+
+mod retention;
+pub use retention::*;
+
 /// The attribute under which a repository's revision lineage claims are
 /// recorded. The claim's entity is the repository DID and its value is the
 /// content-addressed entity of the [`Revision`].
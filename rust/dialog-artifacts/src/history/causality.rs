@@ -4,7 +4,7 @@ use dialog_common::ConditionalSync;
 
 use crate::{Attribute, DialogArtifactsError, Entity};
 
-use super::{Claim, RevisionRecord, Version};
+use super::{Claim, Context, Edition, Origin, RevisionRecord, Version};
 
 /// The causal relationship between two claims on the same
 /// `(entity, attribute)`
@@ -63,6 +63,19 @@ pub trait History: ConditionalSync {
         &self,
         version: &Version,
     ) -> Result<Option<RevisionRecord>, DialogArtifactsError>;
+
+    /// The causal context of the history compacted out of this index —
+    /// the union of every verified [`Checkpoint`](super::Checkpoint) it
+    /// holds. A version this context observes had its records erased on
+    /// purpose; one it does not observe and whose records are absent has
+    /// simply not been replicated.
+    ///
+    /// Traversals only consult it when a read comes back empty, so
+    /// implementations need not memoize it. The default is an index
+    /// that was never compacted.
+    async fn compacted(&self) -> Result<Context, DialogArtifactsError> {
+        Ok(Context::new())
+    }
 }
 
 /// Determine the causal relationship between two claims on the same
@@ -91,6 +104,16 @@ pub trait History: ConditionalSync {
 /// [`DialogArtifactsError::IncompleteHistory`] error is returned: a partial
 /// replica does not have enough information to resolve conflicts it has not
 /// fully received yet.
+///
+/// A version whose claims were *compacted* away (see
+/// [`History::compacted`]) is not a hole, but the walk cannot descend
+/// through it either. Compaction settles the region it erases: every
+/// revision in it was merged into the compacting head, so whatever
+/// contests it held were decided there. A path that enters the region
+/// therefore reaches any target the checkpoint observes (the fine
+/// ordering inside the region is what compaction gives up), and no target
+/// it does not observe — the region is closed under ancestry, so nothing
+/// beneath it lies outside it — and that path is pruned.
 pub async fn causality<H: History>(
     (a, a_version): (&Claim, &Version),
     (b, b_version): (&Claim, &Version),
@@ -139,6 +162,7 @@ pub async fn causality<H: History>(
         }
     }
 
+    let mut compacted: Option<Context> = None;
     while let Some(version) = frontier.pop() {
         if version == *target {
             return Ok(relationship);
@@ -153,9 +177,22 @@ pub async fn causality<H: History>(
             .claims_at(&version, &higher.0.of, &higher.0.the)
             .await?;
         if claims.is_empty() {
-            return Err(DialogArtifactsError::IncompleteHistory(format!(
-                "{version}"
-            )));
+            if compacted.is_none() {
+                compacted = Some(history.compacted().await?);
+            }
+            let checkpoint = compacted.as_ref().expect("loaded above");
+            if !checkpoint.observes(&version) {
+                return Err(DialogArtifactsError::IncompleteHistory(format!(
+                    "{version}"
+                )));
+            }
+            // Compacted: everything beneath this version was compacted
+            // with it, so the path reaches the target iff the checkpoint
+            // settled the target as well.
+            if checkpoint.observes(target) {
+                return Ok(relationship);
+            }
+            continue;
         }
         for claim in claims {
             for cause in claim.cause.versions() {
@@ -185,6 +222,18 @@ pub async fn causality<H: History>(
 /// region a leap jumps over is therefore strictly linear and strictly
 /// above anything the other side can reach, so nothing the stepwise walk
 /// would have found is missed.
+///
+/// A version whose record was compacted away (see
+/// [`History::compacted`]) cannot be expanded, so the walk stops there.
+/// The sides still meet through it: one origin's revisions form a chain,
+/// so where both sides reached compacted revisions of the same origin,
+/// the lower of the two is an ancestor of both heads, and the answer is
+/// the greatest such meeting the retained history can vouch for (a
+/// single writer's compacted run always meets this way). When both sides
+/// entered the compacted region without meeting in it on one origin, the
+/// fork point was compacted beyond recovery and
+/// [`IncompleteHistory`](DialogArtifactsError::IncompleteHistory) is
+/// returned.
 pub async fn common_ancestor<H: History>(
     a: &Version,
     b: &Version,
@@ -209,16 +258,41 @@ pub async fn common_ancestor<H: History>(
     frontier.push((*a, 0b01u8));
     frontier.push((*b, 0b10u8));
 
+    // The compacted revisions each side stopped at, as the highest
+    // edition reached per origin and side, and the best meeting point
+    // they imply so far.
+    let mut compacted: Option<Context> = None;
+    let mut settled: HashMap<Origin, [Option<Edition>; 2]> = HashMap::new();
+    let mut meeting: Option<Version> = None;
+
     while let Some((version, side)) = frontier.pop() {
         let reachable = reached.get(&version).copied().unwrap_or(side);
         if reachable == 0b11 {
-            return Ok(Some(version));
+            return Ok(Some(
+                meeting.map_or(version, |meeting| meeting.max(version)),
+            ));
         }
 
         let Some(record) = history.revision_record(&version).await? else {
-            return Err(DialogArtifactsError::IncompleteHistory(format!(
-                "{version}"
-            )));
+            if compacted.is_none() {
+                compacted = Some(history.compacted().await?);
+            }
+            if !compacted.as_ref().expect("loaded above").observes(&version) {
+                return Err(DialogArtifactsError::IncompleteHistory(format!(
+                    "{version}"
+                )));
+            }
+            let reach = settled.entry(version.origin).or_default();
+            for (slot, bit) in [(0, 0b01), (1, 0b10)] {
+                if reachable & bit != 0 {
+                    reach[slot] = reach[slot].max(Some(version.edition));
+                }
+            }
+            if let [Some(ours), Some(theirs)] = *reach {
+                let candidate = Version::new(version.origin, ours.min(theirs));
+                meeting = meeting.max(Some(candidate));
+            }
+            continue;
         };
 
         // The farthest recorded leap that stays at or above the horizon.
@@ -254,5 +328,82 @@ pub async fn common_ancestor<H: History>(
         }
     }
 
-    Ok(None)
+    if meeting.is_none() {
+        let entered = |slot: usize| settled.values().any(|reach| reach[slot].is_some());
+        if entered(0) && entered(1) {
+            return Err(DialogArtifactsError::IncompleteHistory(format!(
+                "the lineages of {a} and {b} meet only in compacted history"
+            )));
+        }
+    }
+
+    Ok(meeting)
+}
+
+/// Whether the revision `ancestor` is `from` itself or one `from` descends
+/// from, found by walking `from`'s revision DAG backward through the
+/// history index.
+///
+/// As in [`causality`], editions strictly decrease along every causal
+/// path, so a path is pruned once it drops to the ancestor's edition, and
+/// skip links leap linear runs while the leap stays above that edition. A
+/// revision whose record was compacted away settles the walk the way it
+/// does there: the path reaches `ancestor` iff the checkpoint observes it.
+/// A revision neither recorded nor compacted is a hole, and
+/// [`IncompleteHistory`](DialogArtifactsError::IncompleteHistory) is
+/// returned rather than a guess.
+pub async fn descends<H: History>(
+    from: &Version,
+    ancestor: &Version,
+    history: &H,
+) -> Result<bool, DialogArtifactsError> {
+    let mut visited = HashSet::from([*from]);
+    let mut frontier = vec![*from];
+    let mut compacted: Option<Context> = None;
+
+    while let Some(version) = frontier.pop() {
+        if version == *ancestor {
+            return Ok(true);
+        }
+        if version.edition <= ancestor.edition {
+            continue;
+        }
+
+        let Some(record) = history.revision_record(&version).await? else {
+            if compacted.is_none() {
+                compacted = Some(history.compacted().await?);
+            }
+            let checkpoint = compacted.as_ref().expect("loaded above");
+            if !checkpoint.observes(&version) {
+                return Err(DialogArtifactsError::IncompleteHistory(format!(
+                    "{version}"
+                )));
+            }
+            if checkpoint.observes(ancestor) {
+                return Ok(true);
+            }
+            continue;
+        };
+
+        // A leap only spans a linear run above its target, so it cannot
+        // step over the ancestor as long as it lands at or above it.
+        let leap = record
+            .skips
+            .iter()
+            .filter(|target| target.edition >= ancestor.edition)
+            .min_by_key(|target| target.edition)
+            .copied();
+        let next: Vec<Version> = match leap {
+            Some(target) if record.parents.len() == 1 => vec![target],
+            Some(target) => record.parents.into_iter().chain([target]).collect(),
+            None => record.parents,
+        };
+        for version in next {
+            if visited.insert(version) {
+                frontier.push(version);
+            }
+        }
+    }
+
+    Ok(false)
 }
@@ -0,0 +1,186 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Artifact, Attribute, Datum, DialogArtifactsError, Entity, Key, Record, State, Value,
+    key::artifact_index_keys,
+};
+use dialog_search_tree::Manifest;
+
+use super::{Context, Edition, Version, verify_issuer_signature};
+
+/// The attribute under which history [`Checkpoint`]s are recorded.
+pub const CHECKPOINT_ATTRIBUTE: &str = "dialog.db/checkpoint";
+
+/// The entity every [`Checkpoint`] is recorded on. One well-known entity
+/// (rather than one per branch) keeps "every checkpoint in this tree" a
+/// single exact lookup; checkpoints merged in from other branches sit
+/// beside each other at distinct values.
+pub const CHECKPOINT_ENTITY: &str = "dialog:history/checkpoint";
+
+/// The current [`Checkpoint::format`]
+pub const CHECKPOINT_FORMAT: u8 = 0;
+
+/// The domain tag opening every checkpoint signing payload, disjoint from
+/// the head and revision-record tags the same session key signs under
+/// (see [`RECORD_SIGNING_DOMAIN`](super::RECORD_SIGNING_DOMAIN)).
+pub const CHECKPOINT_SIGNING_DOMAIN: &[u8] = b"dialog/history-checkpoint@1\n";
+
+/// What remains of history that was compacted away.
+///
+/// Compaction erases the [`RevisionRecord`](super::RevisionRecord)s and
+/// claim records of every revision at or below a horizon edition in a
+/// head's ancestry. The region it erases is closed under ancestry — an
+/// ancestor's edition is always lower — so it is summarized exactly by
+/// its causal [`Context`]: a version is compacted iff the context
+/// observes it (an origin's revisions form a chain, and the watermark
+/// cuts a prefix of it).
+///
+/// That is what lets the readers keep working across the gap. A walk
+/// that reaches a version whose record is gone asks the checkpoint
+/// whether it was compacted: if so the walk treats it as settled history
+/// instead of a replication hole (see [`causality`](super::causality),
+/// [`context_of`](super::context_of) and
+/// [`common_ancestor`](super::common_ancestor)).
+///
+/// Like a revision record, a checkpoint is a fact in the reserved
+/// `dialog.` namespace, signed by the issuer that compacted, and readers
+/// ignore one that does not verify: a forged checkpoint could otherwise
+/// make a replication hole look like settled history.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Encoding version of this checkpoint, for forward evolution
+    pub format: u8,
+    /// The branch entity whose history was compacted
+    pub branch: Entity,
+    /// DID of the operator that compacted, whose signature binds this
+    /// checkpoint
+    pub issuer: String,
+    /// DID of the profile the issuer claims authorized it (attribution
+    /// only, like [`RevisionRecord::authority`](super::RevisionRecord::authority))
+    pub authority: String,
+    /// The highest edition compacted
+    pub horizon: Edition,
+    /// The causal context of everything compacted, including what earlier
+    /// checkpoints this one replaces had compacted
+    pub context: Context,
+    /// The issuer's Ed25519 signature over [`Checkpoint::payload`]
+    #[serde(with = "serde_bytes")]
+    pub signature: Vec<u8>,
+}
+
+impl Checkpoint {
+    /// Encode this checkpoint into the bytes carried by its
+    /// [`Value::Record`]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, DialogArtifactsError> {
+        serde_ipld_dagcbor::to_vec(self)
+            .map_err(|error| DialogArtifactsError::InvalidValue(format!("{error}")))
+    }
+
+    /// Decode a checkpoint from the bytes of its [`Value::Record`]
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, DialogArtifactsError> {
+        serde_ipld_dagcbor::from_slice(bytes)
+            .map_err(|error| DialogArtifactsError::InvalidValue(format!("{error}")))
+    }
+
+    /// The canonical signing payload: [`CHECKPOINT_SIGNING_DOMAIN`]
+    /// followed by this checkpoint dag-cbor encoded with an empty
+    /// signature field
+    pub fn payload(&self) -> Result<Vec<u8>, DialogArtifactsError> {
+        let mut unsigned = self.clone();
+        unsigned.signature = Vec::new();
+        let mut payload = CHECKPOINT_SIGNING_DOMAIN.to_vec();
+        payload.extend_from_slice(&unsigned.to_bytes()?);
+        Ok(payload)
+    }
+
+    /// Verify the issuer's signature over this checkpoint
+    pub fn verify(&self) -> Result<(), DialogArtifactsError> {
+        verify_issuer_signature(&self.issuer, &self.payload()?, &self.signature)?;
+        Ok(())
+    }
+
+    /// Whether the revision identified by `version` was compacted
+    pub fn covers(&self, version: &Version) -> bool {
+        self.context.observes(version)
+    }
+
+    /// The fact carrying this checkpoint: an [`Artifact`] on
+    /// [`CHECKPOINT_ENTITY`] under [`CHECKPOINT_ATTRIBUTE`], valued with
+    /// the encoded checkpoint
+    pub fn to_artifact(&self) -> Result<Artifact, DialogArtifactsError> {
+        Ok(Artifact {
+            the: Attribute::from_str(CHECKPOINT_ATTRIBUTE)?,
+            of: Entity::from_str(CHECKPOINT_ENTITY)?,
+            is: Value::Record(Record::from(self.to_bytes()?)),
+            cause: None,
+        })
+    }
+
+    /// The tree entries carrying this checkpoint, at the entity- and
+    /// attribute-ordered keys (the value ordering is skipped for the same
+    /// reason [`RevisionRecord::entries`](super::RevisionRecord::entries)
+    /// skips it).
+    ///
+    /// The datum carries no version: a checkpoint is not a claim any
+    /// revision made, so a merge passes it through rather than screening
+    /// it by causal context.
+    pub fn entries(
+        &self,
+        manifest: &Manifest,
+    ) -> Result<Vec<(Key, State<Datum>)>, DialogArtifactsError> {
+        let artifact = self.to_artifact()?;
+        let (entity_key, attribute_key, _) = artifact_index_keys(&artifact, manifest);
+        let added = State::Added(Datum::for_artifact(&artifact));
+        Ok(vec![(entity_key, added.clone()), (attribute_key, added)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base58::ToBase58 as _;
+    use ed25519_dalek::Signer as _;
+
+    use super::*;
+    use crate::history::Origin;
+
+    fn did_key_of(key: &ed25519_dalek::SigningKey) -> String {
+        let mut bytes = vec![0xed, 0x01];
+        bytes.extend_from_slice(key.verifying_key().as_bytes());
+        format!("did:key:z{}", bytes.to_base58())
+    }
+
+    #[test]
+    fn it_signs_and_covers_the_compacted_prefix() -> anyhow::Result<()> {
+        let key = ed25519_dalek::SigningKey::from_bytes(&[9u8; 32]);
+        let origin = Origin::from([4u8; 32]);
+        let mut context = Context::new();
+        for edition in 0..=6 {
+            context.tally(Version::new(origin, Edition::new(edition)));
+        }
+        let mut checkpoint = Checkpoint {
+            format: CHECKPOINT_FORMAT,
+            branch: Entity::new()?,
+            issuer: did_key_of(&key),
+            authority: did_key_of(&key),
+            horizon: Edition::new(6),
+            context,
+            signature: Vec::new(),
+        };
+        checkpoint.signature = key.sign(&checkpoint.payload()?).to_bytes().to_vec();
+        checkpoint.verify()?;
+
+        assert!(checkpoint.covers(&Version::new(origin, Edition::new(3))));
+        assert!(!checkpoint.covers(&Version::new(origin, Edition::new(7))));
+        assert!(!checkpoint.covers(&Version::new(Origin::from([5u8; 32]), Edition::new(1))));
+
+        let decoded = Checkpoint::try_from_bytes(&checkpoint.to_bytes()?)?;
+        assert_eq!(decoded, checkpoint);
+
+        let mut tampered = checkpoint.clone();
+        tampered.horizon = Edition::new(2);
+        assert!(tampered.verify().is_err());
+        Ok(())
+    }
+}
@@ -16,6 +16,11 @@ use super::{History, Version};
 /// records it holds by construction (they arrived with the merges that
 /// produced the head).
 ///
+/// The one exception is history compacted away on purpose (see
+/// [`History::compacted`]): the checkpoint already summarizes the
+/// region beneath a compacted revision, so the walk folds the
+/// checkpoint's context in once and stops descending there.
+///
 /// The walk is O(ancestry). Callers on a hot path should memoize per
 /// head (the context of a fixed head never changes) or maintain the
 /// vector incrementally: `context(commit) = context(parent) + own
@@ -25,21 +30,31 @@ pub async fn context_of<H: History>(
     history: &H,
 ) -> Result<Context, DialogArtifactsError> {
     let mut context = Context::new();
+    let mut compacted: Option<Context> = None;
     let mut visited: HashSet<Version> = HashSet::new();
     let mut frontier: Vec<Version> = vec![*head];
     visited.insert(*head);
 
     while let Some(version) = frontier.pop() {
-        // `tally`, not `record`: the walk visits revisions in DAG order,
-        // not edition order, and every visited revision is distinct (the
-        // visited set guarantees it) — an advance-only fold would skip
-        // counting a revision reached below an already-raised watermark.
-        context.tally(version);
         let Some(record) = history.revision_record(&version).await? else {
+            if compacted.is_none() {
+                compacted = Some(history.compacted().await?);
+            }
+            if compacted
+                .as_ref()
+                .is_some_and(|compacted| compacted.observes(&version))
+            {
+                continue;
+            }
             return Err(DialogArtifactsError::IncompleteHistory(format!(
                 "{version}"
             )));
         };
+        // `tally`, not `record`: the walk visits revisions in DAG order,
+        // not edition order, and every visited revision is distinct (the
+        // visited set guarantees it) — an advance-only fold would skip
+        // counting a revision reached below an already-raised watermark.
+        context.tally(version);
         for parent in record.parents {
             if visited.insert(parent) {
                 frontier.push(parent);
@@ -47,7 +62,18 @@ pub async fn context_of<H: History>(
         }
     }
 
-    Ok(context)
+    // The compacted region is counted whole, by the checkpoint, and the
+    // retained revisions are news on top of it: an origin's compacted
+    // revisions are a prefix of its chain, so `absorb` counts every
+    // retained revision once and screens out the compacted ones the walk
+    // stopped at.
+    match compacted {
+        Some(mut whole) => {
+            whole.absorb(visited);
+            Ok(whole)
+        }
+        None => Ok(context),
+    }
 }
 
 /// Memoized causal contexts, keyed by head version.
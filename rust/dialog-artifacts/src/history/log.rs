@@ -17,6 +17,9 @@ use super::{History, RevisionRecord, Version};
 /// Replication holes truncate rather than fail: a parent whose record
 /// has not been replicated is skipped, along with everything reachable
 /// only through it — the log lists what this replica can vouch for.
+/// History compacted under a [`Retention`](super::Retention) policy ends
+/// the walk the same way, at the [`Checkpoint`](super::Checkpoint) that
+/// replaced it.
 /// And "vouch" is literal: [`History`] implementations over
 /// peer-supplied storage verify each record's signature and slot
 /// binding on read (see [`TreeHistory`](super::TreeHistory)), so a
@@ -3,7 +3,7 @@ use std::collections::BTreeMap;
 use crate::{Attribute, DialogArtifactsError, Entity};
 
 use super::{
-    Claim, History, HistoryKey, REVISION_RECORD_FORMAT, Revision, RevisionRecord, Version,
+    Claim, Context, History, HistoryKey, REVISION_RECORD_FORMAT, Revision, RevisionRecord, Version,
 };
 
 /// An in-memory [`History`] index, mapping
@@ -16,6 +16,7 @@ use super::{
 pub struct MemoryHistory {
     claims: BTreeMap<HistoryKey, Claim>,
     records: BTreeMap<Version, RevisionRecord>,
+    compacted: Context,
 }
 
 impl MemoryHistory {
@@ -34,6 +35,7 @@ impl MemoryHistory {
             authority: revision.authority().to_string(),
             parents: revision.cause().versions().to_vec(),
             skips: Vec::new(),
+            time: None,
             // A test double stores records as-is and never verifies them,
             // so the signature stays empty.
             signature: Vec::new(),
@@ -49,6 +51,17 @@ impl MemoryHistory {
         }
     }
 
+    /// Compact the given revisions away, as a checkpoint would: their
+    /// claims and records are dropped and the context summarizing them is
+    /// remembered in their place
+    pub fn compact(&mut self, versions: impl IntoIterator<Item = Version>) {
+        for version in versions {
+            self.records.remove(&version);
+            self.claims.retain(|key, _| key.version() != version);
+            self.compacted.tally(version);
+        }
+    }
+
     /// The number of recorded claims
     pub fn len(&self) -> usize {
         self.claims.len()
@@ -81,4 +94,7 @@ impl History for MemoryHistory {
     ) -> Result<Option<RevisionRecord>, DialogArtifactsError> {
         Ok(self.records.get(version).cloned())
     }
+    async fn compacted(&self) -> Result<Context, DialogArtifactsError> {
+        Ok(self.compacted.clone())
+    }
 }
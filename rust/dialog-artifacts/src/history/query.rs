@@ -10,11 +10,15 @@ use crate::history::VersionExt as _;
 use crate::tree::ArtifactTreeExt as _;
 use crate::tree::{ArtifactTree, SpillCache, TreeStorageBridge, fetch_spilled_cached, spill_cache};
 use crate::{
-    Attribute, DialogArtifactsError, Entity, State, history_claim_range, history_key_version,
-    history_region_range,
+    Attribute, DialogArtifactsError, Entity, Key, State, coverage_version_range,
+    history_claim_range, history_key_version, history_region_range, history_version_range,
 };
+use dialog_search_tree::Manifest;
 
-use super::{Claim, History, REVISION_ATTRIBUTE, Record, RevisionRecord, Version};
+use super::{
+    CHECKPOINT_ATTRIBUTE, CHECKPOINT_ENTITY, Checkpoint, Claim, Context, History,
+    REVISION_ATTRIBUTE, Record, RevisionRecord, Version,
+};
 
 /// Read access to the history region of an artifact tree.
 ///
@@ -113,6 +117,79 @@ where
         }
         Ok(records)
     }
+
+    /// The records the revision identified by `version` wrote, in key
+    /// order. Empty when the revision wrote none, was compacted, or has
+    /// not been replicated; [`History::revision_record`] and
+    /// [`checkpoints`](Self::checkpoints) tell those apart.
+    pub async fn records_at(&self, version: &Version) -> Result<Vec<Record>, DialogArtifactsError> {
+        let (min, max) = history_version_range(version);
+        let stream = self.tree.stream_range(min..=max, &self.storage);
+        tokio::pin!(stream);
+
+        let mut records = Vec::new();
+        while let Some(entry) = stream.try_next().await? {
+            if let State::Added(datum) = entry.value {
+                let spilled = fetch_spilled_cached(&self.store, &self.spill, &entry.key).await?;
+                records.push(Record::try_from_key_datum_with_value(
+                    &entry.key, datum, spilled,
+                )?);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Every key the revision identified by `version` occupies as history:
+    /// its claim records, their coverage mirrors, and the entries carrying
+    /// its [`RevisionRecord`] (when replicated). These are what compaction
+    /// erases once a [`Checkpoint`] summarizes the revision.
+    pub async fn revision_keys(&self, version: &Version) -> Result<Vec<Key>, DialogArtifactsError> {
+        let mut keys = Vec::new();
+        for (min, max) in [
+            history_version_range(version),
+            coverage_version_range(version),
+        ] {
+            let stream = self.tree.stream_range(min..=max, &self.storage);
+            tokio::pin!(stream);
+            while let Some(entry) = stream.try_next().await? {
+                keys.push(entry.key);
+            }
+        }
+        if let Some(record) = self.revision_record(version).await? {
+            let manifest = self.manifest().await?;
+            keys.extend(record.entries(&manifest)?.into_iter().map(|(key, _)| key));
+        }
+        Ok(keys)
+    }
+
+    /// The tree's format [`Manifest`], which entries written into it (a
+    /// new [`Checkpoint`], say) must be keyed under.
+    pub async fn manifest(&self) -> Result<Manifest, DialogArtifactsError> {
+        Ok(self.tree.manifest(&self.storage).await?)
+    }
+
+    /// Every verified [`Checkpoint`] recorded in the tree. A checkpoint
+    /// that fails to decode or verify is skipped rather than fatal: it
+    /// vouches for nothing, and treating the region it claims as
+    /// replication holes is the safe reading.
+    pub async fn checkpoints(&self) -> Result<Vec<Checkpoint>, DialogArtifactsError> {
+        let of = Entity::from_str(CHECKPOINT_ENTITY)?;
+        let the = Attribute::from_str(CHECKPOINT_ATTRIBUTE)?;
+        let candidates = self
+            .tree
+            .clone()
+            .select_record(self.store.clone(), &of, &the)
+            .await?;
+        Ok(candidates
+            .into_iter()
+            .filter_map(|artifact| match &artifact.is {
+                Value::Record(bytes) => Checkpoint::try_from_bytes(bytes.as_bytes())
+                    .ok()
+                    .filter(|checkpoint| checkpoint.verify().is_ok()),
+                _ => None,
+            })
+            .collect())
+    }
 }
 
 impl<S> History for TreeHistory<S>
@@ -186,7 +263,7 @@ where
         let mut failure = None;
         for artifact in candidates {
             if let Value::Record(bytes) = &artifact.is {
-                let verified = RevisionRecord::try_from_bytes(bytes)
+                let verified = RevisionRecord::try_from_bytes(bytes.as_bytes())
                     .and_then(|record| record.verify(version).map(|()| record));
                 match verified {
                     Ok(record) => {
@@ -202,4 +279,11 @@ where
             None => Ok(None),
         }
     }
+    async fn compacted(&self) -> Result<Context, DialogArtifactsError> {
+        let mut context = Context::new();
+        for checkpoint in self.checkpoints().await? {
+            context.merge(&checkpoint.context);
+        }
+        Ok(context)
+    }
 }
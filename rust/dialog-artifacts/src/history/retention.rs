@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Edition, RevisionRecord, Version};
+
+/// How much of a branch's history to keep before compacting the rest into
+/// a [`Checkpoint`](super::Checkpoint).
+///
+/// A policy picks a *horizon*: the highest edition to compact. Everything
+/// in the head's ancestry at or below it is compacted, which keeps the
+/// compacted region closed under ancestry (a parent's edition is always
+/// below its child's) — the property the checkpoint's readers rely on.
+/// The head itself is never compacted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Retention {
+    /// Keep the newest `n` editions below and including the head (at least
+    /// one: the head's own)
+    Editions(u64),
+    /// Keep every revision recorded within this long of now, and anything
+    /// above the oldest of them
+    Age(Duration),
+}
+
+impl Retention {
+    /// The horizon this policy sets for the history `log` walked from
+    /// `head` (newest first, as [`log`](super::log) yields it), at `now`
+    /// in milliseconds since the Unix epoch. `None` when there is nothing
+    /// to compact.
+    ///
+    /// Revision times are each minting replica's own clock, so
+    /// [`Retention::Age`] never trusts them to order history: the horizon
+    /// sits just below the lowest edition still within the window, and a
+    /// record with no time (minted before revisions were timestamped)
+    /// counts as expired.
+    pub fn horizon(
+        &self,
+        head: &Version,
+        log: &[(Version, RevisionRecord)],
+        now: u64,
+    ) -> Option<Edition> {
+        let ceiling = head.edition.value().checked_sub(1)?;
+        let horizon = match self {
+            Retention::Editions(count) => head.edition.value().checked_sub((*count).max(1))?,
+            Retention::Age(window) => {
+                let cutoff = now.saturating_sub(window.as_millis() as u64);
+                match log
+                    .iter()
+                    .filter(|(_, record)| record.time.is_some_and(|time| time >= cutoff))
+                    .map(|(version, _)| version.edition.value())
+                    .min()
+                {
+                    Some(oldest) => oldest.checked_sub(1)?,
+                    None => ceiling,
+                }
+            }
+        }
+        .min(ceiling);
+
+        log.iter()
+            .any(|(version, _)| version.edition.value() <= horizon)
+            .then(|| Edition::new(horizon))
+    }
+}
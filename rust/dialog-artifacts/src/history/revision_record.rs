@@ -2,9 +2,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::history::VersionExt as _;
 use crate::{
-    Artifact, Attribute, Datum, DialogArtifactsError, Entity, Key, State, Value,
+    Artifact, Attribute, Datum, DialogArtifactsError, Entity, Key, Record, State, Value,
     key::artifact_index_keys,
 };
+use dialog_capability::Did;
 use dialog_search_tree::Manifest;
 
 use super::{Edition, Origin, REVISION_ATTRIBUTE, Version, verify_issuer_signature};
@@ -62,6 +63,15 @@ pub struct RevisionRecord {
     /// [`carry_skips`](super::carry_skips)). Empty for genesis and merge
     /// revisions.
     pub skips: Vec<Version>,
+    /// When the minting replica recorded the revision, in milliseconds
+    /// since the Unix epoch, by its own clock. Vouched for by the issuer's
+    /// signature but not by anyone else: good enough to age history out
+    /// under a [`Retention`](super::Retention) policy, never to order it.
+    /// Absent on records minted before revisions were timestamped (the
+    /// field is omitted from the encoding when unset, so their signatures
+    /// still verify).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<u64>,
     /// The issuer's Ed25519 signature over [`RevisionRecord::payload`] —
     /// this record encoded with an empty signature field. The key is the
     /// one the issuer DID names (`did:key`).
@@ -145,13 +155,41 @@ impl RevisionRecord {
         Ok(())
     }
 
+    /// Verify this record as [`verify`](Self::verify) does, and that it was
+    /// committed on behalf of `principal` under its current key or one it
+    /// succeeded. `lineage` is the record authority's lineage — the
+    /// authority, then each successor in turn, as the succession records
+    /// resolve it — so a revision committed before a key rotation stays
+    /// attributed to whoever holds the key now.
+    pub fn verify_for(
+        &self,
+        version: &Version,
+        principal: &Did,
+        lineage: &[Did],
+    ) -> Result<(), DialogArtifactsError> {
+        self.verify(version)?;
+        if lineage.first().map(Did::to_string).as_deref() != Some(self.authority.as_str()) {
+            return Err(DialogArtifactsError::InvalidSignature(format!(
+                "Revision record for {} was checked against another principal's lineage",
+                self.authority
+            )));
+        }
+        if !lineage.contains(principal) {
+            return Err(DialogArtifactsError::InvalidSignature(format!(
+                "Revision record for {} was not committed on behalf of {principal}",
+                self.authority
+            )));
+        }
+        Ok(())
+    }
+
     /// The fact carrying this record: an [`Artifact`] on the revision
     /// entity under [`REVISION_ATTRIBUTE`], valued with the encoded record
     pub fn to_artifact(&self, version: &Version) -> Result<Artifact, DialogArtifactsError> {
         Ok(Artifact {
             the: Attribute::try_from(REVISION_ATTRIBUTE.to_string())?,
             of: version.entity(),
-            is: Value::Record(self.to_bytes()?),
+            is: Value::Record(Record::from(self.to_bytes()?)),
             cause: None,
         })
     }
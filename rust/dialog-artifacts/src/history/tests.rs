@@ -2,6 +2,7 @@
 
 use std::str::FromStr;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 
 use anyhow::Result;
 use dialog_storage::MemoryStorageBackend;
@@ -12,8 +13,9 @@ use crate::tree::{ArtifactTree, ArtifactTreeExt as _};
 use crate::{Artifact, Attribute, DialogArtifactsError, Entity, Instruction, Value, encode_bytes};
 
 use super::{
-    Authority, Causality, CausalityCache, Cause, Claim, Edition, History, MemoryHistory, Origin,
-    Revision, RevisionRecord, TreeHistory, Version, causality, common_ancestor, extend_skips, log,
+    Authority, Causality, CausalityCache, Cause, Claim, Context, Edition, History, MemoryHistory,
+    Origin, Retention, Revision, RevisionRecord, TreeHistory, Version, causality, common_ancestor,
+    context_of, descends, extend_skips, log,
 };
 
 #[cfg(target_arch = "wasm32")]
@@ -432,6 +434,13 @@ async fn it_forks_and_merges_across_repositories() -> Result<()> {
         Some(b2.version())
     );
 
+    // The merge descends from both lineages; neither pre-merge head
+    // descends from the other
+    assert!(descends(&a5.version(), &b3.version(), &history).await?);
+    assert!(descends(&a5.version(), &genesis.version(), &history).await?);
+    assert!(!descends(&a4.version(), &b3.version(), &history).await?);
+    assert!(!descends(&b2.version(), &a5.version(), &history).await?);
+
     // Every revision's record is retrievable and carries its parents
     let merged = history
         .revision_record(&a5.version())
@@ -666,6 +675,9 @@ impl History for CountingHistory<'_> {
         self.reads.fetch_add(1, Ordering::SeqCst);
         self.inner.revision_record(version).await
     }
+    async fn compacted(&self) -> Result<Context, DialogArtifactsError> {
+        self.inner.compacted().await
+    }
 }
 
 /// Skip links let `common_ancestor` leap over long linear runs: a head far
@@ -1461,6 +1473,7 @@ async fn it_verifies_signed_revision_records() -> Result<()> {
         authority: did_key.clone(),
         parents: vec![parent],
         skips: vec![parent],
+        time: None,
         signature: Vec::new(),
     };
     record.signature = key.sign(&record.payload()?).to_bytes().to_vec();
@@ -1504,6 +1517,51 @@ async fn it_verifies_signed_revision_records() -> Result<()> {
     Ok(())
 }
 
+/// A record is attributed along its authority's key lineage: to the
+/// authority itself and to every key that succeeded it, but to nobody else,
+/// and only through a lineage that starts at the authority.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn it_verifies_revision_records_along_a_key_lineage() -> Result<()> {
+    use base58::ToBase58 as _;
+    use dialog_capability::Did;
+    use ed25519_dalek::Signer as _;
+
+    let did_of = |seed: u8| -> Did {
+        let mut bytes = vec![0xed, 0x01];
+        bytes.extend_from_slice(signing_key(seed).verifying_key().as_bytes());
+        format!("did:key:z{}", bytes.to_base58())
+            .parse()
+            .expect("valid did")
+    };
+    let (old, new, stranger) = (did_of(7), did_of(8), did_of(9));
+
+    let mut record = RevisionRecord {
+        format: super::REVISION_RECORD_FORMAT,
+        branch: Entity::new()?,
+        issuer: old.to_string(),
+        authority: old.to_string(),
+        parents: Vec::new(),
+        skips: Vec::new(),
+        time: None,
+        signature: Vec::new(),
+    };
+    record.signature = signing_key(7).sign(&record.payload()?).to_bytes().to_vec();
+    let version = record.version();
+
+    let lineage = [old.clone(), new.clone()];
+    record.verify_for(&version, &old, &lineage)?;
+    record.verify_for(&version, &new, &lineage)?;
+    assert!(record.verify_for(&version, &stranger, &lineage).is_err());
+    assert!(
+        record
+            .verify_for(&version, &new, &[stranger.clone(), new.clone()])
+            .is_err()
+    );
+
+    Ok(())
+}
+
 /// The durable history reader refuses records that don't vouch for
 /// themselves: an unsigned (or badly signed) record planted in the tree at
 /// a revision entity errors out of `revision_record`, while a properly
@@ -1534,6 +1592,7 @@ async fn it_refuses_forged_revision_records_in_the_tree() -> Result<()> {
         authority: "did:web:example.com".to_string(),
         parents: Vec::new(),
         skips: Vec::new(),
+        time: None,
         signature: Vec::new(),
     };
     let forged = RevisionRecord {
@@ -1926,3 +1985,142 @@ async fn it_mirrors_covering_records_into_the_coverage_region() -> Result<()> {
 
     Ok(())
 }
+
+/// Compacting a settled prefix of history erases its claims and records,
+/// yet causality still orders a live claim after one it superseded inside
+/// the compacted region, the head's context is unchanged, and a genuine
+/// replication hole is still reported as one.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), tokio::test)]
+async fn it_keeps_causality_working_across_compacted_history() -> Result<()> {
+    let repo = Entity::new()?;
+    let entity = Entity::new()?;
+    let alice = signing_key(1);
+    let bob = signing_key(2);
+    let carol = signing_key(3);
+    let dave = signing_key(4);
+
+    let genesis = revise(&repo, &bob, &[], 0);
+    let a1 = revise(&repo, &alice, &[&genesis], 1);
+    let b2 = revise(&repo, &bob, &[&a1], 2);
+    let c3 = revise(&repo, &carol, &[&b2], 3);
+
+    let first = name_claim(&entity, "Alicia", Cause::genesis());
+    let second = name_claim(&entity, "Bob", Cause::from(a1.version()));
+    let third = name_claim(&entity, "Carol", Cause::from(b2.version()));
+
+    let mut history = MemoryHistory::default();
+    for revision in [&genesis, &a1, &b2, &c3] {
+        history.record_revision(revision)?;
+    }
+    history.record(&a1.version(), first.clone());
+    history.record(&b2.version(), second.clone());
+    history.record(&c3.version(), third.clone());
+
+    let before = context_of(&c3.version(), &history).await?;
+
+    // Compact everything up to edition 2, as a two-edition retention
+    // policy would with C:3 at the head.
+    history.compact([genesis.version(), a1.version(), b2.version()]);
+    assert!(history.revision_record(&a1.version()).await?.is_none());
+
+    assert_eq!(
+        causality((&third, &c3.version()), (&first, &a1.version()), &history).await?,
+        Causality::Supersedes,
+        "the walk enters the compacted region at B:2 and finds A:1 settled there"
+    );
+    assert_eq!(
+        context_of(&c3.version(), &history).await?,
+        before,
+        "the checkpoint stands in for the compacted ancestry"
+    );
+
+    // A claim citing a revision that was never replicated (nor compacted)
+    // is still a hole.
+    let stranger = Version::new(Origin::from([9u8; 32]), Edition::new(5));
+    let d6 = Version::new(Origin::from([8u8; 32]), Edition::new(6));
+    let orphan = name_claim(&entity, "Dave", Cause::from(stranger));
+    assert!(matches!(
+        causality((&orphan, &d6), (&first, &a1.version()), &history).await,
+        Err(DialogArtifactsError::IncompleteHistory(_))
+    ));
+
+    // A fork off the compacted run of one writer still meets it.
+    let mut run = vec![c3.clone()];
+    for seed in 5..8 {
+        let next = revise(&repo, &carol, &[run.last().expect("nonempty")], seed);
+        history.record_revision(&next)?;
+        run.push(next);
+    }
+    history.compact(run[..2].iter().map(|revision| revision.version()));
+    let late = revise(&repo, &dave, &[&run[0]], 8);
+    history.record_revision(&late)?;
+    assert_eq!(
+        common_ancestor(&run[3].version(), &late.version(), &history).await?,
+        Some(run[0].version()),
+        "both sides stop at compacted revisions of Carol's chain, which meet at the lower"
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn it_sets_retention_horizons() -> Result<()> {
+    let origin = Origin::from([1u8; 32]);
+    let record = |time: Option<u64>| RevisionRecord {
+        format: super::REVISION_RECORD_FORMAT,
+        branch: Entity::new().expect("entity"),
+        issuer: String::new(),
+        authority: String::new(),
+        parents: Vec::new(),
+        skips: Vec::new(),
+        time,
+        signature: Vec::new(),
+    };
+    // Editions 9 down to 0, one second apart, the head minted at t = 10s.
+    let entries: Vec<_> = (0..10u64)
+        .rev()
+        .map(|edition| {
+            (
+                Version::new(origin, Edition::new(edition)),
+                record(Some((edition + 1) * 1000)),
+            )
+        })
+        .collect();
+    let head = entries[0].0;
+
+    assert_eq!(
+        Retention::Editions(3).horizon(&head, &entries, 10_000),
+        Some(Edition::new(6))
+    );
+    assert_eq!(
+        Retention::Editions(0).horizon(&head, &entries, 10_000),
+        Some(Edition::new(8)),
+        "the head itself is always kept"
+    );
+    assert_eq!(
+        Retention::Editions(10).horizon(&head, &entries, 10_000),
+        None
+    );
+    assert_eq!(
+        Retention::Age(Duration::from_secs(4)).horizon(&head, &entries, 10_000),
+        Some(Edition::new(4)),
+        "revisions minted at 6s and later are within the window"
+    );
+    assert_eq!(
+        Retention::Age(Duration::from_secs(60)).horizon(&head, &entries, 10_000),
+        None
+    );
+
+    // An untimed record in the window's middle is expired, but it never
+    // drags the horizon above a timed record still within the window.
+    let mut skewed = entries.clone();
+    skewed[2].1.time = None;
+    assert_eq!(
+        Retention::Age(Duration::from_secs(4)).horizon(&head, &skewed, 10_000),
+        Some(Edition::new(4))
+    );
+
+    Ok(())
+}
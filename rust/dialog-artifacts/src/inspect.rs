@@ -39,7 +39,7 @@ use crate::key::varkey::{self, ValuePayload};
 use crate::{
     ATTRIBUTE_KEY_TAG, AttributeKey, BLOB_KEY_TAG, BlobRecord, COVERAGE_KEY_TAG, Datum,
     DialogArtifactsError, ENTITY_KEY_TAG, EntityKey, HISTORY_KEY_TAG, Key, KeyView, State,
-    VALUE_KEY_TAG, Value, ValueKey, decode_value,
+    TEXT_KEY_TAG, TextKey, VALUE_KEY_TAG, Value, ValueKey, decode_value,
 };
 
 /// The raw content hash a [`Load`] resolves: the same 32 bytes a
@@ -433,6 +433,7 @@ fn tag_name(tag: u8) -> &'static str {
         HISTORY_KEY_TAG => "history",
         BLOB_KEY_TAG => "blob",
         COVERAGE_KEY_TAG => "coverage",
+        TEXT_KEY_TAG => "text",
         _ => "unknown",
     }
 }
@@ -449,7 +450,8 @@ fn render_value(value: &Value) -> String {
         Value::SignedInt(number) => format!("{number:+}"),
         Value::Float(number) => format!("{number:?}"),
         Value::Bytes(bytes) => hex(bytes),
-        Value::Record(bytes) => format!("record:{}", hex(bytes)),
+        Value::Record(bytes) => format!("record:{}", hex(bytes.as_bytes())),
+        Value::Instant(instant) => format!("instant:{instant}"),
     }
 }
 
@@ -554,6 +556,38 @@ pub fn key_components(bytes: &[u8]) -> Vec<KeyComponent> {
                 hash.to_vec(),
             ));
         }
+        // Text: tag ‖ attribute ‖ token ‖ entity ‖ value hash, one posting.
+        TEXT_KEY_TAG => {
+            let posting = TextKey(key);
+            let (Some(attribute), Some(token), Some(entity), Some(hash)) = (
+                posting.attribute_name(),
+                posting.token(),
+                posting.entity(),
+                posting.value_hash(),
+            ) else {
+                return vec![KeyComponent::new("opaque", hex(bytes), bytes.to_vec())];
+            };
+            out.push(KeyComponent::new(
+                "attribute",
+                attribute.clone(),
+                attribute.into_bytes(),
+            ));
+            out.push(KeyComponent::new(
+                "token",
+                token.clone(),
+                token.into_bytes(),
+            ));
+            out.push(KeyComponent::new(
+                "entity",
+                entity.to_string(),
+                entity.as_str().as_bytes().to_vec(),
+            ));
+            out.push(KeyComponent::new(
+                "value",
+                format!("hash:{}", hex(&hash)),
+                hash.to_vec(),
+            ));
+        }
         _ => return vec![KeyComponent::new("opaque", hex(bytes), bytes.to_vec())],
     }
     out
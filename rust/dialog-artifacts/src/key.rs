@@ -43,6 +43,9 @@ pub use blob::*;
 mod history;
 pub use history::*;
 
+mod text;
+pub use text::*;
+
 mod part;
 pub use part::*;
 
@@ -168,7 +171,7 @@ fn encoded_len_lower_bound(value: &Value) -> usize {
         Value::Record(bytes) => bytes.len(),
         Value::String(string) => string.len(),
         Value::Entity(entity) => entity.as_str().len(),
-        Value::UnsignedInt(_) | Value::SignedInt(_) => 16,
+        Value::UnsignedInt(_) | Value::SignedInt(_) | Value::Instant(_) => 16,
         Value::Float(_) => 8,
         Value::Boolean(_) => 1,
         Value::Symbol(_) => 0,
@@ -398,6 +401,16 @@ const BLOB_SCHEMA: &[TreeComponent] = &[
     TreeComponent::arena_var(),
 ];
 
+/// The text index ordering (`TEXT_KEY_TAG ‖ attribute ‖ token ‖ entity ‖
+/// value_hash`): attributes repeat heavily, tokens less so, and the entity and
+/// value hash trailing them are distinct per posting, so they share one arena.
+const TEXT_SCHEMA: &[TreeComponent] = &[
+    TreeComponent::dictionary(TAG_LENGTH),
+    TreeComponent::dictionary_var(),
+    TreeComponent::arena_var(),
+    TreeComponent::arena_var(),
+];
+
 impl TreeKey for Key {
     fn try_from_bytes(bytes: &[u8]) -> Result<Self, DialogSearchTreeError> {
         Ok(Key(bytes.to_vec()))
@@ -432,6 +445,7 @@ impl TreeKey for Key {
             ATTRIBUTE_KEY_TAG => Schema::new(AEV_SCHEMA),
             VALUE_KEY_TAG => Schema::new(VAE_SCHEMA),
             BLOB_KEY_TAG => Schema::new(BLOB_SCHEMA),
+            TEXT_KEY_TAG => Schema::new(TEXT_SCHEMA),
             // History tag and any future ordering: opaque whole key.
             _ => Schema::opaque(),
         }
@@ -644,7 +658,9 @@ mod tests {
         AttributeKey, EntityKey, FromKey, KeyView, Manifest, ValueKey, default_manifest,
         value_payload,
     };
-    use crate::{Artifact, Attribute, Entity, Value, decode_value, key::varkey::ValuePayload};
+    use crate::{
+        Artifact, Attribute, Entity, Record, Value, decode_value, key::varkey::ValuePayload,
+    };
 
     #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
     wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
@@ -683,7 +699,7 @@ mod tests {
             Value::SignedInt(-1),
             Value::Float(-0.0),
             Value::Float(1783112056217.0),
-            Value::Record(vec![1, 2, 3]),
+            Value::Record(Record::from(vec![1, 2, 3])),
             Value::Symbol(Attribute::from_str("open/status")?),
         ];
         for value in values {
@@ -219,6 +219,27 @@ pub fn history_region_range() -> (Key, Key) {
     (Key::from(vec![HISTORY_KEY_TAG]), Key::from(max))
 }
 
+/// The inclusive bounds of the key range covering every history record
+/// written by the revision identified by `version`: one contiguous span,
+/// since the version prefix leads the key.
+pub fn history_version_range(version: &Version) -> (Key, Key) {
+    tagged_version_range(HISTORY_KEY_TAG, version)
+}
+
+/// The inclusive bounds of the key range covering every coverage entry
+/// mirroring a record written by the revision identified by `version`.
+pub fn coverage_version_range(version: &Version) -> (Key, Key) {
+    tagged_version_range(COVERAGE_KEY_TAG, version)
+}
+
+fn tagged_version_range(tag: u8, version: &Version) -> (Key, Key) {
+    let mut min = vec![tag];
+    min.extend_from_slice(&version_prefix(version));
+    let mut max = min.clone();
+    max.extend(repeat_n(u8::MAX, VALUE_TAIL_BOUND));
+    (Key::from(min), Key::from(max))
+}
+
 /// The [`Version`] component of a history region key
 pub fn history_key_version(key: &Key) -> Result<Version, crate::DialogArtifactsError> {
     use crate::history::{Edition, Origin};
@@ -273,7 +294,7 @@ mod tests {
             crate::Value::Bytes(vec![1, 2, 3]),
             crate::Value::Entity(Entity::from_str("test:other")?),
             crate::Value::Symbol(Attribute::from_str("some/symbol")?),
-            crate::Value::Record(vec![9, 9]),
+            crate::Value::Record(crate::Record::from(vec![9, 9])),
         ] {
             let key = coverage_key(&version(2, 7), &of, &the, &value);
             let parts = parse_key(key.as_ref()).unwrap_or_else(|| {
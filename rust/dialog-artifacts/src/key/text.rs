@@ -0,0 +1,196 @@
+//! Text-index key: the `TEXT`-tagged key layout.
+//!
+//! The text index is the inverted index behind full-text search over string
+//! attributes an application opts in. Each key is one *posting*: a token that
+//! occurs in one string value of one entity. Keys sort by attribute, then
+//! token, so every posting for a term (or for every term sharing a prefix) is
+//! one contiguous range, and within a term by entity, so a scan yields the
+//! candidate entities in order.
+//!
+//! The trailing value hash keeps the postings of distinct values apart: an
+//! entity with several values of a cardinality-many attribute holds one
+//! posting per value, and retracting one value erases only its postings.
+
+use std::iter::repeat_n;
+use std::ops::RangeInclusive;
+
+use dialog_storage::Blake3Hash;
+
+use crate::{Attribute, Entity, Key, decode_bytes_cow, encode_bytes};
+
+/// Tag byte identifying text-index keys (the seventh index).
+pub const TEXT_KEY_TAG: u8 = 6;
+
+/// Number of value-hash bytes trailing a text key.
+const TEXT_VALUE_HASH_LENGTH: usize = 32;
+
+/// Filler closing a term or prefix range. Longer than any entity a posting
+/// carries after the term, so every posting under the term sorts below it.
+const TEXT_RANGE_FILLER: usize = 512;
+
+/// A view over a [`Key`] in the text index.
+///
+/// Layout: `TEXT_KEY_TAG ‖ attribute ‖ token ‖ entity ‖ value_hash (32)`,
+/// with the three variable-length fields in the order-preserving byte-string
+/// encoding (escaped and terminated) the fact indexes use.
+#[repr(transparent)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TextKey(pub Key);
+
+impl TextKey {
+    /// Construct the posting key for `token` occurring in the string `value`
+    /// of `entity`'s `attribute`.
+    pub fn new(attribute: &Attribute, token: &str, entity: &Entity, value: &str) -> Self {
+        let mut bytes = term_prefix(attribute, token);
+        encode_bytes(entity.as_str().as_bytes(), &mut bytes);
+        bytes.extend_from_slice(blake3::hash(value.as_bytes()).as_bytes());
+        Self(Key::from(bytes))
+    }
+
+    /// The range holding every posting of exactly `token` under `attribute`.
+    pub fn term(attribute: &Attribute, token: &str) -> RangeInclusive<Key> {
+        span(term_prefix(attribute, token))
+    }
+
+    /// The range holding every posting of a token that starts with `stem`
+    /// under `attribute`.
+    ///
+    /// The stem is encoded like a token but left unterminated, so it is a
+    /// byte prefix of the encoding of every token it begins.
+    pub fn prefix(attribute: &Attribute, stem: &str) -> RangeInclusive<Key> {
+        let mut bytes = vec![TEXT_KEY_TAG];
+        encode_bytes(attribute.as_str().as_bytes(), &mut bytes);
+        encode_bytes(stem.as_bytes(), &mut bytes);
+        bytes.pop();
+        span(bytes)
+    }
+
+    /// The range holding every posting under `attribute`.
+    pub fn attribute(attribute: &Attribute) -> RangeInclusive<Key> {
+        let mut bytes = vec![TEXT_KEY_TAG];
+        encode_bytes(attribute.as_str().as_bytes(), &mut bytes);
+        span(bytes)
+    }
+
+    /// The lowest key in the text index: the tag byte alone.
+    pub fn min() -> Self {
+        Self(Key::from(vec![TEXT_KEY_TAG]))
+    }
+
+    /// The highest key in the text index: the tag followed by a filler no
+    /// posting reaches.
+    pub fn max() -> Self {
+        let mut bytes = vec![TEXT_KEY_TAG];
+        bytes.extend(repeat_n(u8::MAX, TEXT_RANGE_FILLER));
+        Self(Key::from(bytes))
+    }
+
+    /// The attribute, token and entity fields, or `None` if the key is
+    /// malformed.
+    fn fields(&self) -> Option<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+        let (&tag, rest) = self.0.as_ref().split_first()?;
+        if tag != TEXT_KEY_TAG {
+            return None;
+        }
+        let (attribute, rest) = decode_bytes_cow(rest)?;
+        let (token, rest) = decode_bytes_cow(rest)?;
+        let (entity, rest) = decode_bytes_cow(rest)?;
+        (rest.len() == TEXT_VALUE_HASH_LENGTH).then(|| {
+            (
+                attribute.into_owned(),
+                token.into_owned(),
+                entity.into_owned(),
+            )
+        })
+    }
+
+    /// The attribute this posting indexes.
+    pub fn attribute_name(&self) -> Option<String> {
+        String::from_utf8(self.fields()?.0).ok()
+    }
+
+    /// The token this posting records.
+    pub fn token(&self) -> Option<String> {
+        String::from_utf8(self.fields()?.1).ok()
+    }
+
+    /// The entity whose value holds the token.
+    pub fn entity(&self) -> Option<Entity> {
+        Entity::try_from(self.fields()?.2).ok()
+    }
+
+    /// The hash of the string value holding the token.
+    pub fn value_hash(&self) -> Option<Blake3Hash> {
+        let bytes: &[u8] = self.0.as_ref();
+        bytes
+            .len()
+            .checked_sub(TEXT_VALUE_HASH_LENGTH)
+            .and_then(|at| bytes[at..].try_into().ok())
+    }
+
+    /// Convert into the generic tree [`Key`].
+    pub fn into_key(self) -> Key {
+        self.0
+    }
+}
+
+/// `TEXT_KEY_TAG ‖ attribute ‖ token`, both terminated.
+fn term_prefix(attribute: &Attribute, token: &str) -> Vec<u8> {
+    let mut bytes = vec![TEXT_KEY_TAG];
+    encode_bytes(attribute.as_str().as_bytes(), &mut bytes);
+    encode_bytes(token.as_bytes(), &mut bytes);
+    bytes
+}
+
+/// The inclusive range of keys extending `prefix`.
+fn span(prefix: Vec<u8>) -> RangeInclusive<Key> {
+    let mut hi = prefix.clone();
+    hi.extend(repeat_n(u8::MAX, TEXT_RANGE_FILLER));
+    Key::from(prefix)..=Key::from(hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute() -> Attribute {
+        "note/body".parse().unwrap()
+    }
+
+    fn entity(name: &str) -> Entity {
+        format!("test:{name}").parse().unwrap()
+    }
+
+    #[test]
+    fn it_round_trips_the_posting_fields() {
+        let key = TextKey::new(&attribute(), "prolly", &entity("a"), "Prolly trees");
+        assert_eq!(key.0.tag(), TEXT_KEY_TAG);
+        assert_eq!(key.attribute_name().as_deref(), Some("note/body"));
+        assert_eq!(key.token().as_deref(), Some("prolly"));
+        assert_eq!(key.entity(), Some(entity("a")));
+        assert_eq!(
+            key.value_hash(),
+            Some(*blake3::hash(b"Prolly trees").as_bytes())
+        );
+    }
+
+    #[test]
+    fn it_scopes_a_term_and_a_prefix() {
+        let exact = TextKey::new(&attribute(), "tree", &entity("a"), "tree").0;
+        let longer = TextKey::new(&attribute(), "trees", &entity("a"), "trees").0;
+        let other = TextKey::new(&attribute(), "trek", &entity("a"), "trek").0;
+
+        let term = TextKey::term(&attribute(), "tree");
+        assert!(term.contains(&exact));
+        assert!(!term.contains(&longer));
+
+        let prefix = TextKey::prefix(&attribute(), "tree");
+        assert!(prefix.contains(&exact));
+        assert!(prefix.contains(&longer));
+        assert!(!prefix.contains(&other));
+
+        let whole = TextKey::min().0..=TextKey::max().0;
+        assert!(whole.contains(&other));
+        assert!(TextKey::min().0 > Key::max().set_tag(TEXT_KEY_TAG - 1));
+    }
+}
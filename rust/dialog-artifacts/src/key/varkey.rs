@@ -34,7 +34,7 @@ use std::borrow::Cow;
 use crate::history::VERSION_LENGTH;
 use crate::{
     ATTRIBUTE_KEY_TAG, BLOB_KEY_TAG, COVERAGE_KEY_TAG, ENTITY_KEY_TAG, HISTORY_KEY_TAG,
-    VALUE_KEY_TAG, ValueDataType, decode_bytes_cow, encode_bytes,
+    TEXT_KEY_TAG, VALUE_KEY_TAG, ValueDataType, decode_bytes_cow, encode_bytes,
 };
 
 /// The length of a spilled value's content-addressed reference.
@@ -175,15 +175,12 @@ impl KeyParts {
             attribute: vec![MAX_FILLER_BYTE; MAX_FILLER],
             value_type: ValueDataType::max(),
             // A parseable payload dominating every real value of the maximum
-            // type: `MAX_FILLER_BYTE` exceeds every UTF-8 byte, and real
-            // symbol names are UTF-8. (Like the entity/attribute fillers this
-            // is a bounded synthetic maximum; `set_value_*` replaces it with a
-            // real payload, this is only the unset bound.)
-            value: ValuePayload::Inline({
-                let mut payload = Vec::new();
-                encode_bytes(&[MAX_FILLER_BYTE; MAX_FILLER], &mut payload);
-                payload
-            }),
+            // type: instants are fixed-width, so sixteen `0xFF` bytes — the
+            // encoding of `i128::MAX` nanoseconds — is the largest slot there
+            // is, and it is read back by width rather than scanned for escapes.
+            // (Like the entity/attribute fillers this is a synthetic bound;
+            // `set_value_*` replaces it with a real payload.)
+            value: ValuePayload::Inline(vec![u8::MAX; 16]),
             version: None,
         }
     }
@@ -387,12 +384,13 @@ fn value_payload_len(type_byte: u8, bytes: &[u8], at: usize) -> Option<usize> {
     // An unknown discriminant is corruption: reject the key rather than
     // guessing a width (`ValueDataType::from` would silently default to
     // Bytes and misparse the tail).
-    if type_byte > u8::from(ValueDataType::Symbol) {
+    if type_byte > u8::from(ValueDataType::max()) {
         return None;
     }
     match ValueDataType::from(type_byte) {
-        // 128-bit integers: 16 big-endian bytes.
-        ValueDataType::UnsignedInt | ValueDataType::SignedInt => Some(16),
+        // 128-bit integers, and instants (nanoseconds as a signed 128-bit
+        // integer): 16 big-endian bytes.
+        ValueDataType::UnsignedInt | ValueDataType::SignedInt | ValueDataType::Instant => Some(16),
         // `f64`: 8 big-endian bytes (the order-preserving `encode_f64`), NOT 16
         // — reading 16 here over-runs the value tail into the following key
         // components, so the key splits into fewer parts than its schema.
@@ -497,6 +495,15 @@ pub fn split_components(bytes: &[u8]) -> Option<Vec<&[u8]>> {
             out.push(&bytes[at..]);
             at = bytes.len();
         }
+        TEXT_KEY_TAG => {
+            // The text index is `tag ++ attribute ++ token ++ entity ++
+            // 32-byte value hash`: the entity and hash share the trailing
+            // arena component, matching TEXT_SCHEMA.
+            push_var!(); // attribute
+            push_var!(); // token
+            out.push(&bytes[at..]);
+            at = bytes.len();
+        }
         _ => return None,
     }
 
@@ -658,6 +665,8 @@ pub fn parse_key_ref(bytes: &[u8]) -> Option<KeyRef<'_>> {
             let version: [u8; VERSION_LENGTH] = version.try_into().ok()?;
             (Some(version), entity, attribute, value_type, payload, rest)
         }
+        // Text postings carry no value slot: they are not facts.
+        TEXT_KEY_TAG => return None,
         // ENTITY_KEY_TAG and unknown tags share the EAV shape, a safe
         // self-delimiting default.
         _ => {
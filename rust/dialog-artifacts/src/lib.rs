@@ -77,12 +77,18 @@ pub use state::*;
 mod blob_index;
 pub use blob_index::*;
 
+mod text;
+pub use text::*;
+
 mod collection;
 pub use collection::*;
 
 mod spill;
 pub use spill::*;
 
+mod diff;
+pub use diff::*;
+
 mod constants;
 pub use constants::*;
 
@@ -35,7 +35,7 @@
 //! against the pre-merge snapshot), then the **data regions**
 //! ([`screen_data`]: R1). Region scoping rides the key tags: history
 //! keys sort under [`HISTORY_KEY_TAG`], data under the
-//! entity/attribute/value (and blob) tags.
+//! entity/attribute/value (and blob and text) tags.
 //!
 //! Every rule is O(1) per changed key, and the screen reads only the
 //! receiver's own snapshot and context — nothing about the sender's
@@ -62,7 +62,7 @@ use crate::tree::ArtifactTree;
 use crate::{
     Attribute, AttributeKey, AttributeKeyPart, BLOB_KEY_TAG, COVERAGE_KEY_TAG, Datum,
     ENTITY_KEY_TAG, Entity, EntityKey, EntityKeyPart, FromKey as _, HISTORY_KEY_TAG, Key,
-    KeyViewConstruct, KeyViewMut as _, State, VALUE_KEY_TAG, ValueKey,
+    KeyViewConstruct, KeyViewMut as _, State, TEXT_KEY_TAG, VALUE_KEY_TAG, ValueKey,
 };
 
 /// The full key span of one region tag.
@@ -140,13 +140,17 @@ pub fn spans_from_bounds(bounds: Vec<(Vec<u8>, Option<Vec<u8>>)>) -> Vec<RangeIn
         .collect()
 }
 
-/// The data regions' key ranges (EAV/AEV/VAE and the blob index), for
-/// scoping the second merge pass.
-pub fn data_scope() -> [RangeInclusive<Key>; 2] {
+/// The data regions' key ranges (EAV/AEV/VAE, the blob index and the text
+/// index), for scoping the second merge pass.
+pub fn data_scope() -> [RangeInclusive<Key>; 3] {
     let lo = vec![ENTITY_KEY_TAG];
     let mut hi = vec![VALUE_KEY_TAG];
     hi.extend(repeat_n(u8::MAX, KEY_SPAN_FILLER));
-    [Key::from(lo)..=Key::from(hi), tag_span(BLOB_KEY_TAG)]
+    [
+        Key::from(lo)..=Key::from(hi),
+        tag_span(BLOB_KEY_TAG),
+        tag_span(TEXT_KEY_TAG),
+    ]
 }
 
 /// The entity-ordered key span of the `(entity, attribute)` slot a
@@ -412,7 +416,7 @@ where
                             .to_string(),
                     ))?,
                 };
-                let record = RevisionRecord::try_from_bytes(&bytes).map_err(|error| {
+                let record = RevisionRecord::try_from_bytes(bytes.as_bytes()).map_err(|error| {
                     DialogSearchTreeError::Node(format!("revision record: {error}"))
                 })?;
                 observed
@@ -13,6 +13,7 @@ use base58::ToBase58;
 use serde::{Deserialize, Serialize};
 
 use dialog_capability::Did;
+use dialog_common::time::{UNIX_EPOCH, now};
 
 use crate::Entity;
 use crate::history::{
@@ -286,6 +287,9 @@ impl Revision {
     /// issuer — but the record keeps the attribution readable. The
     /// revision's tree root is deliberately not in the record: the record
     /// lives in that tree, so the root cannot appear inside itself.
+    ///
+    /// The record is stamped with this replica's wall clock (see
+    /// [`RevisionRecord::time`]), which retention policies age history by.
     pub fn record(
         &self,
         authority: &Did,
@@ -299,6 +303,10 @@ impl Revision {
             authority: authority.to_string(),
             parents,
             skips,
+            time: now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|elapsed| elapsed.as_millis() as u64),
             signature: Vec::new(),
         }
     }
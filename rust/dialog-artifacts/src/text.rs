@@ -0,0 +1,347 @@
+//! The text index: full-text search over string attributes.
+//!
+//! An application opts an attribute in (its descriptor is flagged
+//! searchable); the committing branch then keeps one posting per distinct
+//! token of each string value the attribute holds, under [`TextKey`].
+//! Postings live in their own tag range beside the fact indexes, so they
+//! replicate with the data and show up in tree differences like any other
+//! entry, but the fact scan never sees them.
+//!
+//! Tokens are maximal runs of alphanumeric characters, case-folded to
+//! lowercase. A [`TextQuery`] is a set of terms every match must contain; a
+//! term ending in `*` matches any token it begins. The index only narrows
+//! the candidates: a [`TextMatch`] is scored from the value itself, so a
+//! reader that verifies candidates against the facts never reports a stale
+//! posting.
+
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use dialog_capability::Command;
+use dialog_common::ConditionalSync;
+use dialog_search_tree::ContentAddressedStorage;
+use dialog_storage::{Blake3Hash, DialogStorageError, StorageBackend};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Attribute, Datum, DialogArtifactsError, Entity, Key, State, TextKey,
+    tree::{ArtifactTree, TreeStorageBridge},
+};
+
+/// Split `text` into its case-folded tokens, in order of occurrence.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// One term of a [`TextQuery`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TextTerm {
+    /// The case-folded token the term matches.
+    pub token: String,
+    /// Whether the term matches any token that starts with `token`.
+    pub prefix: bool,
+}
+
+impl TextTerm {
+    /// Whether `token` (already case-folded) satisfies this term.
+    pub fn matches(&self, token: &str) -> bool {
+        if self.prefix {
+            token.starts_with(&self.token)
+        } else {
+            token == self.token
+        }
+    }
+
+    /// The range of postings this term selects under `attribute`.
+    pub fn range(&self, attribute: &Attribute) -> RangeInclusive<Key> {
+        if self.prefix {
+            TextKey::prefix(attribute, &self.token)
+        } else {
+            TextKey::term(attribute, &self.token)
+        }
+    }
+}
+
+/// A parsed full-text query: the terms a matching value must all contain.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextQuery {
+    terms: BTreeSet<TextTerm>,
+}
+
+impl TextQuery {
+    /// Parse a query string. Words are tokenized like indexed text; a word
+    /// ending in `*` makes its last token a prefix term. A query with no
+    /// tokens matches nothing.
+    pub fn parse(query: &str) -> Self {
+        let mut terms = BTreeSet::new();
+        for word in query.split_whitespace() {
+            let prefix = word.ends_with('*');
+            let tokens: Vec<String> = tokenize(word).collect();
+            let last = tokens.len().saturating_sub(1);
+            for (at, token) in tokens.into_iter().enumerate() {
+                terms.insert(TextTerm {
+                    token,
+                    prefix: prefix && at == last,
+                });
+            }
+        }
+        Self { terms }
+    }
+
+    /// The query's terms, in token order.
+    pub fn terms(&self) -> impl Iterator<Item = &TextTerm> {
+        self.terms.iter()
+    }
+
+    /// Whether the query has no terms (and so matches nothing).
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Score `text` against the query: `None` unless every term matches
+    /// one of its tokens, otherwise the fraction of its tokens that match
+    /// some term — higher when the query covers more of the value.
+    pub fn score(&self, text: &str) -> Option<f64> {
+        if self.terms.is_empty() {
+            return None;
+        }
+        let tokens: Vec<String> = tokenize(text).collect();
+        let all = self
+            .terms
+            .iter()
+            .all(|term| tokens.iter().any(|token| term.matches(token)));
+        if !all {
+            return None;
+        }
+        let matched = tokens
+            .iter()
+            .filter(|token| self.terms.iter().any(|term| term.matches(token)))
+            .count();
+        Some(matched as f64 / tokens.len() as f64)
+    }
+}
+
+impl Display for TextQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let mut first = true;
+        for term in &self.terms {
+            if !first {
+                f.write_str(" ")?;
+            }
+            first = false;
+            f.write_str(&term.token)?;
+            if term.prefix {
+                f.write_str("*")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The posting keys for a string `value` of `entity`'s `attribute`: one per
+/// distinct token.
+pub fn postings(attribute: &Attribute, entity: &Entity, value: &str) -> BTreeSet<TextKey> {
+    tokenize(value)
+        .map(|token| TextKey::new(attribute, &token, entity, value))
+        .collect()
+}
+
+/// The tree entry recording a posting, for appending to an open batch
+/// alongside a commit's data.
+pub fn posting_entry(key: TextKey) -> (Key, State<Datum>) {
+    // Postings carry nothing beyond their key; text keys never reach the
+    // fact scan, so the reconstruction fields do not apply.
+    (
+        key.into_key(),
+        State::Added(Datum {
+            cause: None,
+            blob: None,
+            version: None,
+            collapsed: Vec::new(),
+            supersedes: Vec::new(),
+            retraction: false,
+        }),
+    )
+}
+
+/// A full-text search over one attribute.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextSearch {
+    /// The searchable attribute to search.
+    pub the: Attribute,
+    /// The query the attribute's values must match.
+    pub query: TextQuery,
+    /// When set, only this entity's values are considered.
+    pub of: Option<Entity>,
+}
+
+/// One value matching a [`TextSearch`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TextMatch {
+    /// The entity holding the value.
+    pub of: Entity,
+    /// The matching string value.
+    pub is: String,
+    /// The value's relevance (see [`TextQuery::score`]), in `(0, 1]`.
+    pub score: f64,
+}
+
+/// Command for running a full-text search against a source.
+///
+/// Matches are collected rather than streamed: the search intersects
+/// posting sets before it can report anything, and each candidate is then
+/// verified against the facts.
+pub struct Search;
+
+impl Command for Search {
+    type Input = TextSearch;
+    type Output = Result<Vec<TextMatch>, DialogArtifactsError>;
+}
+
+/// Text-index reads on an [`ArtifactTree`].
+///
+/// An extension trait for the same reason as
+/// [`BlobIndexExt`](crate::BlobIndexExt): `ArtifactTree` aliases a foreign
+/// type. Postings are written through a commit's batch (see
+/// [`posting_entry`]), not here.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait TextIndexExt {
+    /// The entities holding a posting for every term of `query` under
+    /// `attribute`. These are candidates only: a posting can outlive the
+    /// value it was derived from, so callers verify each against the facts.
+    async fn text_candidates<S>(
+        &self,
+        store: &S,
+        attribute: &Attribute,
+        query: &TextQuery,
+    ) -> Result<BTreeSet<Entity>, DialogArtifactsError>
+    where
+        S: StorageBackend<Key = Blake3Hash, Value = Vec<u8>, Error = DialogStorageError>
+            + Clone
+            + ConditionalSync;
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl TextIndexExt for ArtifactTree {
+    async fn text_candidates<S>(
+        &self,
+        store: &S,
+        attribute: &Attribute,
+        query: &TextQuery,
+    ) -> Result<BTreeSet<Entity>, DialogArtifactsError>
+    where
+        S: StorageBackend<Key = Blake3Hash, Value = Vec<u8>, Error = DialogStorageError>
+            + Clone
+            + ConditionalSync,
+    {
+        let storage = ContentAddressedStorage::new(TreeStorageBridge(store.clone()));
+        let mut candidates: Option<BTreeSet<Entity>> = None;
+        for term in query.terms() {
+            let mut found = BTreeSet::new();
+            let stream = self.stream_range(term.range(attribute), &storage);
+            futures_util::pin_mut!(stream);
+            while let Some(entry) = stream.next().await {
+                let entry = entry?;
+                if let State::Removed = entry.value {
+                    continue;
+                }
+                let entity = TextKey(entry.key).entity().ok_or_else(|| {
+                    DialogArtifactsError::MalformedIndex("malformed text posting".into())
+                })?;
+                if candidates
+                    .as_ref()
+                    .is_none_or(|previous| previous.contains(&entity))
+                {
+                    found.insert(entity);
+                }
+            }
+            if found.is_empty() {
+                return Ok(found);
+            }
+            candidates = Some(found);
+        }
+        Ok(candidates.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use dialog_common::Blake3Hash as NodeHash;
+    use dialog_search_tree::{Buffer, Delta};
+    use dialog_storage::MemoryStorageBackend;
+
+    fn attribute() -> Attribute {
+        "note/body".parse().unwrap()
+    }
+
+    fn entity(name: &str) -> Entity {
+        format!("test:{name}").parse().unwrap()
+    }
+
+    #[dialog_common::test]
+    fn it_tokenizes_with_case_folding() {
+        let tokens: Vec<String> = tokenize("Prolly-Trees, über ALLES!").collect();
+        assert_eq!(tokens, vec!["prolly", "trees", "über", "alles"]);
+    }
+
+    #[dialog_common::test]
+    fn it_scores_values_that_contain_every_term() {
+        let query = TextQuery::parse("Tree* prolly");
+        assert_eq!(query.to_string(), "prolly tree*");
+        assert_eq!(query.score("Prolly trees"), Some(1.0));
+        assert_eq!(query.score("prolly trees are balanced"), Some(0.5));
+        assert_eq!(query.score("binary trees"), None);
+        assert_eq!(TextQuery::parse("  ").score("anything"), None);
+    }
+
+    #[dialog_common::test]
+    async fn it_intersects_postings_across_terms() -> Result<(), DialogArtifactsError> {
+        let mut store = MemoryStorageBackend::<Blake3Hash, Vec<u8>>::default();
+        let storage = ContentAddressedStorage::new(TreeStorageBridge(store.clone()));
+        let mut delta: Delta<NodeHash, Buffer> = Delta::zero();
+        let mut edit = ArtifactTree::empty().edit();
+        for (name, text) in [
+            ("a", "Prolly trees"),
+            ("b", "binary trees"),
+            ("c", "prolly ever after"),
+        ] {
+            for key in postings(&attribute(), &entity(name), text) {
+                let (key, value) = posting_entry(key);
+                edit = edit.insert(key, value, &storage).await?;
+            }
+        }
+        let tree = edit.persist(&mut delta)?;
+        for (_, buffer) in delta.flush() {
+            store
+                .set(*buffer.blake3_hash().as_bytes(), buffer.as_ref().to_vec())
+                .await?;
+        }
+
+        let found = tree
+            .text_candidates(&store, &attribute(), &TextQuery::parse("prolly tre*"))
+            .await?;
+        assert_eq!(found, BTreeSet::from([entity("a")]));
+
+        let found = tree
+            .text_candidates(&store, &attribute(), &TextQuery::parse("TREES"))
+            .await?;
+        assert_eq!(found, BTreeSet::from([entity("a"), entity("b")]));
+
+        let found = tree
+            .text_candidates(&store, &attribute(), &TextQuery::parse("graph"))
+            .await?;
+        assert!(found.is_empty());
+        Ok(())
+    }
+}
@@ -1318,20 +1318,19 @@ where
         // records), written through [`ArtifactTreeExt::record`] or a
         // [`WriteScope::Machinery`] stream. At the library level such
         // facts therefore cannot be corrupted through the ordinary
-        // write path. Two prefixes are carved out of the application
-        // gate: `dialog.rule/*` (rule storage) and `dialog.concept/*`
-        // (concept markers), whose integrity is semantic rather than
-        // positional — rules are content-addressed, so a forged rule
-        // fact fails the hydration check upstream and is inert.
+        // write path. Three prefixes are carved out of the application
+        // gate: `dialog.rule/*` (rule storage), `dialog.concept/*`
+        // (concept markers) and `dialog.attribute/*` (registered
+        // attribute descriptors), whose integrity is semantic rather
+        // than positional — rules are content-addressed, so a forged
+        // rule fact fails the hydration check upstream and is inert,
+        // and a descriptor naming another attribute than the one it is
+        // registered under is ignored.
         if scope == WriteScope::Application {
             let (Instruction::Assert(artifact)
             | Instruction::Replace(artifact)
             | Instruction::Retract(artifact)) = &instruction;
-            let the = artifact.the.as_str();
-            if the.starts_with("dialog.")
-                && !the.starts_with("dialog.rule/")
-                && !the.starts_with("dialog.concept/")
-            {
+            if artifact.the.is_reserved() {
                 return Err(DialogArtifactsError::ReservedAttribute(
                     artifact.the.to_string(),
                 ));
@@ -42,10 +42,14 @@ use wasm_bindgen_futures::js_sys::{self, Object, Reflect, Symbol, Uint8Array};
 
 use crate::{
     Artifact, ArtifactSelector, ArtifactStore, ArtifactStoreMutExt, ArtifactViewStream as _,
-    Artifacts, Attribute, Cause, DialogArtifactsError, Entity, HASH_SIZE, Instruction, Value,
-    ValueDataType, artifacts::selector::Constrained,
+    Artifacts, Attribute, Cause, DialogArtifactsError, Entity, HASH_SIZE, Instant, Instruction,
+    Record, Value, ValueDataType, artifacts::selector::Constrained,
 };
 
+/// JS carries an [`Instant`] as milliseconds since the epoch (the unit of
+/// `Date`); this converts between that and the native nanoseconds.
+const NANOS_PER_MILLISECOND: f64 = 1_000_000.0;
+
 #[wasm_bindgen(typescript_custom_section)]
 const ARTIFACT_INTERFACE: &'static str = r#"
 /**
@@ -62,7 +66,8 @@ type Entity = Uint8Array;
 /**
  * The object of a semantic triple. It's internal representation will
  * vary based on the value of the `type` property. For more details,
- * see the documentation on `ValueDataType`.
+ * see the documentation on `ValueDataType`. An `Instant` is carried as
+ * milliseconds since the Unix epoch, as `Date.prototype.getTime` reports.
  */
 interface Value {
   type: ValueDataType,
@@ -420,6 +425,11 @@ impl TryFrom<Value> for JsValue {
                 JsValue::from(result)
             }
             Value::Symbol(attribute) => JsValue::from(attribute),
+            // Milliseconds since the epoch, as `Date.prototype.getTime`
+            // reports them; sub-millisecond precision survives as a fraction.
+            Value::Instant(instant) => {
+                JsValue::from_f64(instant.unix_nanos() as f64 / NANOS_PER_MILLISECOND)
+            }
         };
 
         let object = JsValue::from(Object::new());
@@ -603,7 +613,7 @@ impl TryFrom<(ValueDataType, JsValue)> for Value {
             }
             ValueDataType::Record => {
                 let byte_array: Uint8Array = value.dyn_into().map_err(js_value_to_error)?;
-                Value::Record(byte_array.to_vec())
+                Value::Record(Record::from(byte_array.to_vec()))
             }
             ValueDataType::Symbol => Value::Symbol(Attribute::try_from(
                 value.as_string().ok_or_else(|| {
@@ -612,6 +622,18 @@ impl TryFrom<(ValueDataType, JsValue)> for Value {
                     )
                 })?,
             )?),
+            ValueDataType::Instant => {
+                let Some(value) = value.as_f64() else {
+                    return Err(DialogArtifactsError::InvalidValue(
+                        "Value is not a numeric".into(),
+                    )
+                    .into());
+                };
+
+                Value::Instant(Instant::from_unix_nanos(
+                    (value * NANOS_PER_MILLISECOND) as i128,
+                ))
+            }
         };
 
         Ok(value)
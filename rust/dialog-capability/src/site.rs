@@ -79,6 +79,14 @@ pub trait SiteAddress:
 {
     /// The site type this address belongs to.
     type Site: Site<Address = Self>;
+
+    /// Whether what is exchanged with this address travels in the clear
+    /// where others on the path can read it: a plaintext transport that
+    /// leaves the machine. Sites reached over TLS or within this machine
+    /// are not, which is the default.
+    fn is_exposed(&self) -> bool {
+        false
+    }
 }
 
 impl<T> From<&T> for SiteId
@@ -0,0 +1,15 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+//! # Dialog Binary
+//!
+//! Creates and inspects Dialog repositories from a shell.
+
+use anyhow::Result;
+use clap::Parser;
+use dialog_cli::{DialogCli, run};
+
+/// Parse the command line, run the workflow it names and report to stdout.
+#[tokio::main]
+pub async fn main() -> Result<()> {
+    run(DialogCli::parse(), &mut tokio::io::stdout()).await
+}
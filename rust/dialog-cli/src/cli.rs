@@ -0,0 +1,243 @@
+//! Command-line interface definitions for the `dialog` tool.
+
+use std::path::PathBuf;
+use std::str::FromStr as _;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use dialog_artifacts::{Attribute, Entity, Value};
+use dialog_varsig::Did;
+
+/// Command-line arguments for the `dialog` tool.
+#[derive(Debug, Parser)]
+#[command(name = "dialog")]
+#[command(bin_name = "dialog")]
+#[command(about = "Create and inspect Dialog repositories", long_about = None)]
+pub struct DialogCli {
+    /// Where profiles and spaces live, and which ones to use
+    #[command(flatten)]
+    pub scope: Scope,
+
+    /// The workflow to run
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Selects the profile and space a command acts on.
+#[derive(Debug, Clone, Args)]
+pub struct Scope {
+    /// Keep profiles and spaces under this directory instead of the
+    /// platform profile directory and the working directory
+    #[arg(long, global = true)]
+    pub root: Option<PathBuf>,
+
+    /// The profile to act as
+    #[arg(long, global = true, default_value = "default")]
+    pub profile: String,
+
+    /// The space (repository) to act on
+    #[arg(short, long, global = true, default_value = "default")]
+    pub space: String,
+}
+
+/// The workflows the tool supports.
+///
+/// Parsed once per invocation, so the size of `select`'s pattern does not
+/// matter.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Manage the profile
+    #[command(subcommand)]
+    Profile(ProfileCommand),
+
+    /// Manage the space
+    #[command(subcommand)]
+    Space(SpaceCommand),
+
+    /// Manage branches
+    #[command(subcommand)]
+    Branch(BranchCommand),
+
+    /// Commit artifacts read from a JSON or CSV file
+    Commit {
+        /// The file to read artifacts from
+        file: PathBuf,
+
+        /// The branch to commit to
+        #[arg(short, long, default_value = "main")]
+        branch: String,
+
+        /// The file's format, inferred from its extension when omitted
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// Retract the artifacts instead of asserting them
+        #[arg(long)]
+        retract: bool,
+
+        /// Identify entities by this attribute's value: an entity whose
+        /// key value the branch already holds is merged into the one
+        /// holding it instead of being created anew (repeatable)
+        #[arg(long = "key", value_name = "ATTRIBUTE", conflicts_with = "retract")]
+        keys: Vec<Attribute>,
+    },
+
+    /// Print the artifacts that match a pattern
+    Select {
+        /// The branch to read from
+        #[arg(short, long, default_value = "main")]
+        branch: String,
+
+        /// Only artifacts with this attribute
+        #[arg(long)]
+        the: Option<Attribute>,
+
+        /// Only artifacts about this entity
+        #[arg(long)]
+        of: Option<Entity>,
+
+        /// Only artifacts with this value, as `type:value`
+        #[arg(long, value_parser = Value::from_str)]
+        is: Option<Value>,
+
+        /// How to print the artifacts
+        #[arg(short, long, default_value = "json")]
+        format: Format,
+    },
+
+    /// Print a branch's history, newest first
+    Log {
+        /// The branch to read
+        #[arg(short, long, default_value = "main")]
+        branch: String,
+
+        /// The most revisions to print
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Manage remotes
+    #[command(subcommand)]
+    Remote(RemoteCommand),
+
+    /// Publish a branch to its upstream
+    Push(SyncArgs),
+
+    /// Integrate a branch's upstream into it
+    Pull(SyncArgs),
+
+    /// Move a revision's content in and out of a file
+    #[command(subcommand)]
+    Snapshot(SnapshotCommand),
+}
+
+/// Profile subcommands.
+#[derive(Debug, Subcommand)]
+pub enum ProfileCommand {
+    /// Open the profile, creating it if it does not exist
+    Init,
+}
+
+/// Space subcommands.
+#[derive(Debug, Subcommand)]
+pub enum SpaceCommand {
+    /// Create the space and grant it to the profile
+    Init,
+}
+
+/// Branch subcommands.
+#[derive(Debug, Subcommand)]
+pub enum BranchCommand {
+    /// List the space's branches
+    List,
+
+    /// Create a branch
+    Create {
+        /// The new branch's name
+        name: String,
+
+        /// Start the branch at this branch's revision
+        #[arg(long)]
+        from: Option<String>,
+    },
+
+    /// Move a branch to another branch's revision
+    Reset {
+        /// The branch to move
+        name: String,
+
+        /// The branch whose revision to move to
+        #[arg(long)]
+        to: String,
+
+        /// Move the branch even when it is not a fast-forward
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Remote subcommands.
+#[derive(Debug, Subcommand)]
+pub enum RemoteCommand {
+    /// Add a remote backed by a local directory
+    Add {
+        /// The remote's name
+        name: String,
+
+        /// The directory backing the remote
+        path: PathBuf,
+
+        /// The repository the remote holds, when it is not this space
+        #[arg(long)]
+        subject: Option<Did>,
+
+        /// Prepare an empty directory to hold the repository
+        #[arg(long)]
+        init: bool,
+    },
+}
+
+/// Arguments shared by `push` and `pull`.
+#[derive(Debug, Args)]
+pub struct SyncArgs {
+    /// The branch to synchronize
+    #[arg(short, long, default_value = "main")]
+    pub branch: String,
+
+    /// Track the same-named branch on this remote first
+    #[arg(short, long)]
+    pub remote: Option<String>,
+}
+
+/// Snapshot subcommands.
+#[derive(Debug, Subcommand)]
+pub enum SnapshotCommand {
+    /// Write a branch's current revision and everything it references
+    Export {
+        /// The file to write
+        file: PathBuf,
+
+        /// The branch to export
+        #[arg(short, long, default_value = "main")]
+        branch: String,
+    },
+
+    /// Store a snapshot's content, optionally starting a branch at it
+    Import {
+        /// The file to read
+        file: PathBuf,
+
+        /// Start this (empty) branch at the snapshot's revision
+        #[arg(short, long)]
+        branch: Option<String>,
+    },
+}
+
+/// Artifact file formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// JSON: an array of artifacts, or one artifact per line
+    Json,
+    /// CSV with `the`, `of`, `as`, `is` and `cause` columns
+    Csv,
+}
@@ -0,0 +1,428 @@
+//! The workflows behind each subcommand.
+//!
+//! Every workflow writes what it reports to a caller-supplied writer rather
+//! than straight to stdout, so a test drives the tool exactly as a shell
+//! would and reads back what it printed.
+
+mod branch;
+mod commit;
+mod init;
+mod log;
+mod remote;
+mod select;
+mod snapshot;
+mod sync;
+
+use std::fmt::Display;
+
+use anyhow::Result;
+use dialog_common::ConditionalSend;
+use tokio::io::{AsyncWrite, AsyncWriteExt as _};
+
+use crate::{
+    BranchCommand, Command, DialogCli, ProfileCommand, RemoteCommand, Session, SnapshotCommand,
+    SpaceCommand,
+};
+
+/// Run the workflow `cli` names, reporting to `out`.
+pub async fn run<W>(cli: DialogCli, out: &mut W) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    let DialogCli { scope, command } = cli;
+    let create = matches!(command, Command::Profile(ProfileCommand::Init));
+    let session = Session::open(&scope, create).await?;
+
+    match command {
+        Command::Profile(ProfileCommand::Init) => init::profile(&session, out).await,
+        Command::Space(SpaceCommand::Init) => init::space(&session, &scope.space, out).await,
+        Command::Branch(BranchCommand::List) => branch::list(&session, out).await,
+        Command::Branch(BranchCommand::Create { name, from }) => {
+            branch::create(&session, &name, from.as_deref(), out).await
+        }
+        Command::Branch(BranchCommand::Reset { name, to, force }) => {
+            branch::reset(&session, &name, &to, force, out).await
+        }
+        Command::Commit {
+            file,
+            branch,
+            format,
+            retract,
+            keys,
+        } => commit::commit(&session, &branch, &file, format, retract, &keys, out).await,
+        Command::Select {
+            branch,
+            the,
+            of,
+            is,
+            format,
+        } => select::select(&session, &branch, the, of, is, format, out).await,
+        Command::Log { branch, limit } => log::log(&session, &branch, limit, out).await,
+        Command::Remote(RemoteCommand::Add {
+            name,
+            path,
+            subject,
+            init,
+        }) => remote::add(&session, &name, &path, subject, init, out).await,
+        Command::Push(sync) => sync::push(&session, sync, out).await,
+        Command::Pull(sync) => sync::pull(&session, sync, out).await,
+        Command::Snapshot(SnapshotCommand::Export { file, branch }) => {
+            snapshot::export(&session, &branch, &file, out).await
+        }
+        Command::Snapshot(SnapshotCommand::Import { file, branch }) => {
+            snapshot::import(&session, &file, branch.as_deref(), out).await
+        }
+    }?;
+
+    out.flush().await?;
+    Ok(())
+}
+
+/// Write one line of the report.
+async fn line<W>(out: &mut W, line: impl Display) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    out.write_all(format!("{line}\n").as_bytes()).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use anyhow::Result;
+    use clap::Parser;
+    use dialog_artifacts::Exporter as _;
+    use dialog_artifacts::{Artifact, Value};
+    use dialog_csv::CsvExporter;
+    use tempfile::TempDir;
+    use tokio::fs::File;
+
+    use super::run;
+    use crate::DialogCli;
+
+    /// Run `dialog --root <root> <args>` and return what it printed.
+    async fn dialog(root: &Path, args: &[&str]) -> Result<String> {
+        let root = root.to_string_lossy();
+        let cli = DialogCli::try_parse_from(
+            ["dialog", "--root", root.as_ref()]
+                .into_iter()
+                .chain(args.iter().copied()),
+        )?;
+        let mut out = Vec::new();
+        run(cli, &mut out).await?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    /// A root holding a profile and its default space.
+    async fn initialized() -> Result<TempDir> {
+        let root = tempfile::tempdir()?;
+        dialog(root.path(), &["profile", "init"]).await?;
+        dialog(root.path(), &["space", "init"]).await?;
+        Ok(root)
+    }
+
+    fn people() -> Vec<Artifact> {
+        vec![
+            artifact("user/name", "user:alice", "Alice"),
+            artifact("user/email", "user:alice", "alice@example.com"),
+            artifact("user/name", "user:bob", "Bob"),
+        ]
+    }
+
+    fn artifact(the: &str, of: &str, is: &str) -> Artifact {
+        Artifact {
+            the: the.parse().unwrap(),
+            of: of.parse().unwrap(),
+            is: Value::String(is.into()),
+            cause: None,
+        }
+    }
+
+    /// Write `artifacts` as a JSON array to `name` under `root`.
+    async fn json(root: &Path, name: &str, artifacts: &[Artifact]) -> Result<String> {
+        let path = root.join(name);
+        tokio::fs::write(&path, serde_json::to_vec(artifacts)?).await?;
+        Ok(path.to_string_lossy().into_owned())
+    }
+
+    /// Write `artifacts` as CSV rows to `name` under `root`.
+    async fn csv(root: &Path, name: &str, artifacts: &[Artifact]) -> Result<String> {
+        let path = root.join(name);
+        let mut exporter = CsvExporter::new(File::create(&path).await?);
+        for artifact in artifacts {
+            exporter.write(artifact).await?;
+        }
+        exporter.close().await?;
+        Ok(path.to_string_lossy().into_owned())
+    }
+
+    /// Parse the JSON lines `select` printed.
+    fn selected(output: &str) -> Result<Vec<Artifact>> {
+        Ok(output
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn values(artifacts: &[Artifact]) -> Vec<String> {
+        let mut values: Vec<_> = artifacts
+            .iter()
+            .map(|artifact| match &artifact.is {
+                Value::String(value) => value.clone(),
+                other => panic!("unexpected value {other:?}"),
+            })
+            .collect();
+        values.sort();
+        values
+    }
+
+    #[dialog_common::test]
+    async fn it_commits_json_and_selects_by_pattern() -> Result<()> {
+        let root = initialized().await?;
+        let file = json(root.path(), "people.json", &people()).await?;
+
+        let committed = dialog(root.path(), &["commit", &file]).await?;
+        assert!(committed.trim_end().ends_with("\t3"), "{committed}");
+
+        // An empty pattern prints the whole branch, including what the
+        // repository records about itself.
+        let everything = dialog(root.path(), &["select"]).await?;
+        let people = everything
+            .lines()
+            .filter(|line| line.contains(r#""the":"user/"#))
+            .count();
+        assert_eq!(people, 3, "{everything}");
+
+        let names = selected(&dialog(root.path(), &["select", "--the", "user/name"]).await?)?;
+        assert_eq!(values(&names), ["Alice", "Bob"]);
+
+        let alice = selected(&dialog(root.path(), &["select", "--of", "user:alice"]).await?)?;
+        assert_eq!(values(&alice), ["Alice", "alice@example.com"]);
+
+        let bob = selected(
+            &dialog(
+                root.path(),
+                &["select", "--the", "user/name", "--is", "string:Bob"],
+            )
+            .await?,
+        )?;
+        assert_eq!(values(&bob), ["Bob"]);
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_commits_csv_and_logs_history() -> Result<()> {
+        let root = initialized().await?;
+        let path = root.path().join("people.csv");
+        let mut exporter = CsvExporter::new(File::create(&path).await?);
+        for artifact in people() {
+            exporter.write(&artifact).await?;
+        }
+        exporter.close().await?;
+        let csv = path.to_string_lossy();
+
+        dialog(root.path(), &["commit", &csv]).await?;
+        let retraction = json(
+            root.path(),
+            "bob.json",
+            &[artifact("user/name", "user:bob", "Bob")],
+        )
+        .await?;
+        dialog(root.path(), &["commit", "--retract", &retraction]).await?;
+
+        let names = selected(&dialog(root.path(), &["select", "--the", "user/name"]).await?)?;
+        assert_eq!(values(&names), ["Alice"]);
+
+        let log = dialog(root.path(), &["log"]).await?;
+        assert_eq!(log.lines().count(), 2, "{log}");
+        let limited = dialog(root.path(), &["log", "-n", "1"]).await?;
+        assert_eq!(limited.lines().count(), 1, "{limited}");
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_upserts_csv_rows_by_key() -> Result<()> {
+        let root = initialized().await?;
+        // The same person, exported twice under different entity ids.
+        let first = csv(
+            root.path(),
+            "first.csv",
+            &[
+                artifact("user/name", "user:1", "Alice"),
+                artifact("user/email", "user:1", "alice@example.com"),
+            ],
+        )
+        .await?;
+        let second = csv(
+            root.path(),
+            "second.csv",
+            &[
+                artifact("user/title", "user:2", "Engineer"),
+                artifact("user/email", "user:2", "alice@example.com"),
+            ],
+        )
+        .await?;
+        dialog(root.path(), &["commit", &first, "--key", "user/email"]).await?;
+        dialog(root.path(), &["commit", &second, "--key", "user/email"]).await?;
+        dialog(root.path(), &["commit", &second, "--key", "user/email"]).await?;
+
+        let emails = selected(&dialog(root.path(), &["select", "--the", "user/email"]).await?)?;
+        assert_eq!(values(&emails), ["alice@example.com"]);
+        let alice = selected(&dialog(root.path(), &["select", "--of", "user:1"]).await?)?;
+        assert_eq!(values(&alice), ["Alice", "Engineer", "alice@example.com"]);
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_creates_lists_and_fast_forwards_branches() -> Result<()> {
+        let root = initialized().await?;
+        let first = json(root.path(), "first.json", &people()[..1]).await?;
+        let second = json(root.path(), "second.json", &people()[1..]).await?;
+
+        dialog(root.path(), &["commit", &first]).await?;
+        dialog(
+            root.path(),
+            &["branch", "create", "feature", "--from", "main"],
+        )
+        .await?;
+        assert!(
+            dialog(root.path(), &["branch", "create", "feature"])
+                .await
+                .is_err()
+        );
+
+        let listed = dialog(root.path(), &["branch", "list"]).await?;
+        let names: Vec<_> = listed
+            .lines()
+            .filter_map(|line| line.split('\t').next())
+            .collect();
+        assert!(
+            names.contains(&"main") && names.contains(&"feature"),
+            "{listed}"
+        );
+
+        dialog(root.path(), &["commit", &second]).await?;
+        let feature = selected(
+            &dialog(
+                root.path(),
+                &["select", "-b", "feature", "--the", "user/name"],
+            )
+            .await?,
+        )?;
+        assert_eq!(values(&feature), ["Alice"]);
+
+        dialog(root.path(), &["branch", "reset", "feature", "--to", "main"]).await?;
+        let feature = selected(
+            &dialog(
+                root.path(),
+                &["select", "-b", "feature", "--the", "user/name"],
+            )
+            .await?,
+        )?;
+        assert_eq!(values(&feature), ["Alice", "Bob"]);
+
+        // Moving main back to a revision it has already passed is not a
+        // fast-forward, and takes `--force`.
+        dialog(root.path(), &["branch", "create", "old", "--from", "main"]).await?;
+        dialog(root.path(), &["commit", &first, "--retract"]).await?;
+        assert!(
+            dialog(root.path(), &["branch", "reset", "main", "--to", "old"])
+                .await
+                .is_err()
+        );
+        dialog(
+            root.path(),
+            &["branch", "reset", "main", "--to", "old", "--force"],
+        )
+        .await?;
+        let main = selected(&dialog(root.path(), &["select", "--the", "user/name"]).await?)?;
+        assert_eq!(values(&main), ["Alice", "Bob"]);
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_pushes_and_pulls_through_a_directory_remote() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        dialog(root.path(), &["profile", "init"]).await?;
+        let origin = dialog(root.path(), &["space", "init"]).await?;
+        let origin = origin.trim_end();
+        let remote = root.path().join("remote");
+        tokio::fs::create_dir_all(&remote).await?;
+        let remote = remote.to_string_lossy();
+
+        let file = json(root.path(), "people.json", &people()).await?;
+        dialog(root.path(), &["commit", &file]).await?;
+        dialog(root.path(), &["remote", "add", "origin", &remote, "--init"]).await?;
+        let pushed = dialog(root.path(), &["push", "--remote", "origin"]).await?;
+        assert_ne!(pushed.trim_end(), "up to date");
+        // The branch now tracks the remote, so later pushes need no flag.
+        dialog(root.path(), &["push"]).await?;
+
+        // A second space tracks the first through the directory.
+        dialog(root.path(), &["space", "init", "-s", "replica"]).await?;
+        dialog(
+            root.path(),
+            &[
+                "remote",
+                "add",
+                "origin",
+                &remote,
+                "--subject",
+                origin,
+                "-s",
+                "replica",
+            ],
+        )
+        .await?;
+        dialog(
+            root.path(),
+            &["pull", "--remote", "origin", "-s", "replica"],
+        )
+        .await?;
+
+        let replica = selected(
+            &dialog(
+                root.path(),
+                &["select", "-s", "replica", "--the", "user/name"],
+            )
+            .await?,
+        )?;
+        assert_eq!(values(&replica), ["Alice", "Bob"]);
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_round_trips_a_snapshot() -> Result<()> {
+        let root = initialized().await?;
+        let file = json(root.path(), "people.json", &people()).await?;
+        dialog(root.path(), &["commit", &file]).await?;
+
+        let snapshot = root.path().join("main.snapshot");
+        let snapshot = snapshot.to_string_lossy();
+        let exported = dialog(root.path(), &["snapshot", "export", &snapshot]).await?;
+        let version = exported.split('\t').next().unwrap_or_default().to_owned();
+
+        dialog(root.path(), &["space", "init", "-s", "copy"]).await?;
+        let imported = dialog(
+            root.path(),
+            &["snapshot", "import", &snapshot, "-b", "main", "-s", "copy"],
+        )
+        .await?;
+        assert!(imported.starts_with(&version), "{imported}");
+
+        let copied =
+            selected(&dialog(root.path(), &["select", "-s", "copy", "--the", "user/name"]).await?)?;
+        assert_eq!(values(&copied), ["Alice", "Bob"]);
+        assert!(
+            dialog(
+                root.path(),
+                &["snapshot", "import", &snapshot, "-b", "main", "-s", "copy"],
+            )
+            .await
+            .is_err()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+//! `dialog branch list|create|reset`.
+
+use anyhow::{Context as _, Result, bail};
+use dialog_common::ConditionalSend;
+use dialog_effects::memory::prelude::{MemoryExt as _, MemorySubjectExt as _};
+use tokio::io::AsyncWrite;
+
+use super::line;
+use crate::Session;
+
+/// Print every branch with a revision cell, and the version it is at.
+pub(super) async fn list<W>(session: &Session, out: &mut W) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    let repository = session.repository().await?;
+    let cells = repository
+        .subject()
+        .memory()
+        .list("branch/")
+        .perform(session.operator())
+        .await?;
+    let mut names: Vec<&str> = cells
+        .iter()
+        .filter_map(|cell| cell.strip_prefix("branch/")?.strip_suffix("/revision"))
+        .collect();
+    names.sort_unstable();
+
+    for name in names {
+        let branch = session.branch(&repository, name).await?;
+        match branch.revision() {
+            Some(revision) => line(out, format!("{name}\t{}", revision.version())).await?,
+            None => line(out, name).await?,
+        }
+    }
+    Ok(())
+}
+
+/// Create `name`, empty or at the revision of `from`.
+pub(super) async fn create<W>(
+    session: &Session,
+    name: &str,
+    from: Option<&str>,
+    out: &mut W,
+) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    let repository = session.repository().await?;
+    let operator = session.operator();
+    if repository
+        .branch(name)
+        .load()
+        .perform(operator)
+        .await
+        .is_ok()
+    {
+        bail!("branch `{name}` already exists");
+    }
+    let branch = repository.branch(name).open().perform(operator).await?;
+    if let Some(from) = from {
+        let source = session.branch(&repository, from).await?;
+        if let Some(revision) = source.revision() {
+            branch.reset(revision).perform(operator).await?;
+        }
+    }
+    line(out, name).await
+}
+
+/// Move `name` to the revision of `to`. Unless `force`d, only a
+/// fast-forward is allowed: `to` must already descend from where `name` is.
+///
+/// Moving a branch backwards and committing on it re-mints versions peers
+/// have already seen (see [`Reset`](dialog_repository::Reset)), so the
+/// check is not a courtesy. Descent is decided through the history index,
+/// which meets compacted history at its checkpoints rather than walking
+/// the whole log.
+pub(super) async fn reset<W>(
+    session: &Session,
+    name: &str,
+    to: &str,
+    force: bool,
+    out: &mut W,
+) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    let repository = session.repository().await?;
+    let operator = session.operator();
+    let branch = session.branch(&repository, name).await?;
+    let target = session.branch(&repository, to).await?;
+    let Some(revision) = target.revision() else {
+        bail!("branch `{to}` has no revision to reset to");
+    };
+
+    if let Some(current) = branch.revision()
+        && !force
+    {
+        let current = current.version();
+        let ancestor = target
+            .causality()
+            .common_ancestor(&current, &revision.version(), &target.history(operator))
+            .await
+            .with_context(|| {
+                format!(
+                    "cannot tell whether `{to}` descends from `{name}`; \
+                     pass --force to move it anyway"
+                )
+            })?;
+        if ancestor != Some(current) {
+            bail!("`{to}` does not descend from `{name}`; pass --force to move `{name}` anyway");
+        }
+    }
+
+    let version = revision.version();
+    branch.reset(revision).perform(operator).await?;
+    line(out, format!("{name}\t{version}")).await
+}
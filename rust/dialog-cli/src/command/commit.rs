@@ -0,0 +1,102 @@
+//! `dialog commit`.
+
+use std::path::Path;
+
+use anyhow::{Context as _, Result, bail};
+use dialog_artifacts::{Artifact, Attribute, Instruction};
+use dialog_common::ConditionalSend;
+use dialog_csv::CsvImporter;
+use futures_util::{TryStreamExt as _, stream};
+use tokio::fs::File;
+use tokio::io::AsyncWrite;
+
+use super::line;
+use crate::{Format, Session};
+
+/// Commit the artifacts in `file` to `branch`, creating the branch if it
+/// does not exist yet. With `keys`, every artifact asserting one of the
+/// key attributes upserts its entity by that value.
+pub(super) async fn commit<W>(
+    session: &Session,
+    branch: &str,
+    file: &Path,
+    format: Option<Format>,
+    retract: bool,
+    keys: &[Attribute],
+    out: &mut W,
+) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    let artifacts = read(file, format).await?;
+    let count = artifacts.len();
+
+    let repository = session.repository().await?;
+    let operator = session.operator();
+    let branch = repository.branch(branch).open().perform(operator).await?;
+    let revision = if keys.is_empty() {
+        let instructions = artifacts.into_iter().map(|artifact| match retract {
+            true => Instruction::Retract(artifact),
+            false => Instruction::Assert(artifact),
+        });
+        branch
+            .commit(stream::iter(instructions))
+            .perform(operator)
+            .await?
+    } else {
+        let transaction = dialog_csv::upsert(branch.transaction(), artifacts, keys);
+        Box::pin(transaction.commit().perform(operator)).await?
+    };
+    line(out, format!("{}\t{count}", revision.version())).await
+}
+
+/// Read every artifact in `file`, failing on the first one that does not
+/// parse rather than committing part of the file.
+async fn read(file: &Path, format: Option<Format>) -> Result<Vec<Artifact>> {
+    let format = match format {
+        Some(format) => format,
+        None => infer(file)?,
+    };
+    match format {
+        Format::Csv => {
+            let reader = File::open(file)
+                .await
+                .with_context(|| format!("cannot open {}", file.display()))?;
+            Ok(CsvImporter::new(reader).try_collect().await?)
+        }
+        Format::Json => {
+            let bytes = tokio::fs::read(file)
+                .await
+                .with_context(|| format!("cannot read {}", file.display()))?;
+            parse_json(&bytes)
+        }
+    }
+}
+
+/// Parse a JSON array of artifacts, or one artifact per line.
+fn parse_json(bytes: &[u8]) -> Result<Vec<Artifact>> {
+    if let Ok(artifacts) = serde_json::from_slice(bytes) {
+        return Ok(artifacts);
+    }
+    bytes
+        .split(|byte| *byte == b'\n')
+        .enumerate()
+        .filter(|(_, line)| !line.trim_ascii().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_slice(line)
+                .with_context(|| format!("line {} is not an artifact", index + 1))
+        })
+        .collect()
+}
+
+/// The format a file's extension names.
+fn infer(file: &Path) -> Result<Format> {
+    match file.extension().and_then(|extension| extension.to_str()) {
+        Some("csv") => Ok(Format::Csv),
+        Some("json" | "jsonl" | "ndjson") => Ok(Format::Json),
+        _ => bail!(
+            "cannot tell the format of {}; pass --format",
+            file.display()
+        ),
+    }
+}
@@ -0,0 +1,37 @@
+//! `dialog profile init` and `dialog space init`.
+
+use anyhow::Result;
+use dialog_common::ConditionalSend;
+use dialog_repository::RepositoryExt as _;
+use tokio::io::AsyncWrite;
+
+use super::line;
+use crate::Session;
+
+/// Report the profile, which opening the session created if it was
+/// missing.
+pub(super) async fn profile<W>(session: &Session, out: &mut W) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    line(out, session.profile().did()).await
+}
+
+/// Create the space `name` and delegate it to the profile, so the
+/// operator can prove authority over it to a remote.
+pub(super) async fn space<W>(session: &Session, name: &str, out: &mut W) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    let profile = session.profile();
+    let operator = session.operator();
+    let repository = profile.repository(name).create().perform(operator).await?;
+    let chain = repository
+        .access()
+        .claim(&repository)
+        .delegate(profile.did())
+        .perform(operator)
+        .await?;
+    profile.access().save(chain).perform(operator).await?;
+    line(out, repository.did()).await
+}
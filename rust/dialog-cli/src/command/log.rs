@@ -0,0 +1,26 @@
+//! `dialog log`.
+
+use anyhow::Result;
+use dialog_common::ConditionalSend;
+use tokio::io::AsyncWrite;
+
+use super::line;
+use crate::Session;
+
+/// Print up to `limit` of the branch's revisions, newest first: version,
+/// recording time (milliseconds since the epoch, `-` when unrecorded) and
+/// the profile that claims the revision.
+pub(super) async fn log<W>(session: &Session, branch: &str, limit: usize, out: &mut W) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    let repository = session.repository().await?;
+    let branch = session.branch(&repository, branch).await?;
+    for (version, record) in branch.log(session.operator(), limit).await? {
+        let time = record
+            .time
+            .map_or_else(|| "-".to_string(), |time| time.to_string());
+        line(out, format!("{version}\t{time}\t{}", record.authority)).await?;
+    }
+    Ok(())
+}
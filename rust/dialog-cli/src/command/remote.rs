@@ -0,0 +1,71 @@
+//! `dialog remote add`.
+
+use std::path::{Path, absolute};
+use std::str::FromStr as _;
+
+use anyhow::{Result, bail};
+use dialog_common::ConditionalSend;
+use dialog_credentials::{Credential, Verifier};
+use dialog_effects::credential::SELF;
+use dialog_effects::credential::prelude::*;
+use dialog_effects::storage::Location;
+use dialog_remote_fs::FsAddress;
+use dialog_repository::SiteAddress;
+use dialog_storage::provider::FileSystem;
+use dialog_storage::resource::Resource as _;
+use dialog_varsig::{Did, Principal as _};
+use tokio::io::AsyncWrite;
+
+use super::line;
+use crate::Session;
+
+/// Add the directory at `path` as the remote `name`, holding `subject`
+/// (this space unless given).
+///
+/// A directory only serves as a remote once it names the space it holds.
+/// `init` makes it do so, recording the subject's public identity — never
+/// its key — so an empty directory can be pushed to.
+pub(super) async fn add<W>(
+    session: &Session,
+    name: &str,
+    path: &Path,
+    subject: Option<Did>,
+    init: bool,
+    out: &mut W,
+) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    let repository = session.repository().await?;
+    let subject = subject.unwrap_or_else(|| repository.did());
+    let path = absolute(path)?;
+    let location = Location::at(path.to_string_lossy());
+
+    if init {
+        let filesystem = FileSystem::open(&location).await?;
+        let identity = subject.clone().credential().key(SELF);
+        match identity.clone().load().perform(&filesystem).await {
+            Ok(existing) if existing.did() == subject => {}
+            Ok(existing) => bail!(
+                "{} already holds {}, not {subject}",
+                path.display(),
+                existing.did()
+            ),
+            Err(_) => {
+                let verifier = Verifier::from_str(subject.as_ref())?;
+                identity
+                    .save(Credential::from(verifier))
+                    .perform(&filesystem)
+                    .await?;
+            }
+        }
+    }
+
+    repository
+        .remote(name)
+        .create(SiteAddress::Fs(FsAddress::new(location)))
+        .subject(subject)
+        .perform(session.operator())
+        .await?;
+    line(out, format!("{name}\t{}", path.display())).await
+}
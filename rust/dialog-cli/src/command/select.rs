@@ -0,0 +1,115 @@
+//! `dialog select`.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use dialog_artifacts::selector::Constrained;
+use dialog_artifacts::{
+    Artifact, ArtifactSelector, Attribute, DialogArtifactsError, Entity, Exporter, Value,
+};
+use dialog_common::ConditionalSend;
+use dialog_csv::CsvExporter;
+use dialog_repository::Branch;
+use futures_util::{TryStreamExt as _, pin_mut};
+use tokio::io::{AsyncWrite, AsyncWriteExt as _};
+
+use crate::{Format, Session};
+
+/// Print the artifacts of `branch` matching the pattern — all of them when
+/// the pattern is empty.
+pub(super) async fn select<W>(
+    session: &Session,
+    branch: &str,
+    the: Option<Attribute>,
+    of: Option<Entity>,
+    is: Option<Value>,
+    format: Format,
+    out: &mut W,
+) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    let repository = session.repository().await?;
+    let branch = session.branch(&repository, branch).await?;
+    let selector = pattern(the, of, is);
+    match format {
+        Format::Json => print(session, &branch, selector, JsonLines(out)).await,
+        Format::Csv => print(session, &branch, selector, CsvExporter::new(out)).await,
+    }
+}
+
+/// The selector the pattern describes, or `None` for an empty pattern,
+/// which no selector can express.
+fn pattern(
+    the: Option<Attribute>,
+    of: Option<Entity>,
+    is: Option<Value>,
+) -> Option<ArtifactSelector<Constrained>> {
+    let mut selector = the.map(|the| ArtifactSelector::new().the(the));
+    if let Some(of) = of {
+        selector = Some(match selector {
+            Some(selector) => selector.of(of),
+            None => ArtifactSelector::new().of(of),
+        });
+    }
+    if let Some(is) = is {
+        selector = Some(match selector {
+            Some(selector) => selector.is(is),
+            None => ArtifactSelector::new().is(is),
+        });
+    }
+    selector
+}
+
+async fn print<E>(
+    session: &Session,
+    branch: &Branch,
+    selector: Option<ArtifactSelector<Constrained>>,
+    mut exporter: E,
+) -> Result<()>
+where
+    E: Exporter + ConditionalSend,
+{
+    let operator = session.operator();
+    let Some(selector) = selector else {
+        return Ok(branch.export(exporter).perform(operator).await?);
+    };
+    let artifacts = branch
+        .claims()
+        .select(selector)
+        .to_owned()
+        .perform(operator)
+        .await?;
+    pin_mut!(artifacts);
+    while let Some(artifact) = artifacts.try_next().await? {
+        exporter.write(&artifact).await?;
+    }
+    exporter.close().await?;
+    Ok(())
+}
+
+/// Exports artifacts as JSON, one per line — the same shape
+/// `dialog commit` reads back.
+struct JsonLines<'a, W>(&'a mut W);
+
+#[async_trait]
+impl<W> Exporter for JsonLines<'_, W>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    async fn write(&mut self, artifact: &Artifact) -> Result<(), DialogArtifactsError> {
+        let mut line = serde_json::to_vec(artifact)
+            .map_err(|error| DialogArtifactsError::Export(error.to_string()))?;
+        line.push(b'\n');
+        self.0
+            .write_all(&line)
+            .await
+            .map_err(|error| DialogArtifactsError::Export(error.to_string()))
+    }
+
+    async fn close(&mut self) -> Result<(), DialogArtifactsError> {
+        self.0
+            .flush()
+            .await
+            .map_err(|error| DialogArtifactsError::Export(error.to_string()))
+    }
+}
@@ -0,0 +1,228 @@
+//! `dialog snapshot export|import`.
+//!
+//! A snapshot file is a revision followed by the content it references:
+//!
+//! ```text
+//! "DIALOG-SNAPSHOT\x01"
+//! u32 length, DAG-CBOR revision
+//! b'B', 32-byte digest, u32 length, block bytes    (repeated)
+//! b'L', 32-byte digest, u64 size, blob bytes       (repeated)
+//! ```
+//!
+//! Integers are little-endian. Every block and blob carries the digest it
+//! must hash to, and the import verifies it, so a file is no more trusted
+//! than a remote would be.
+
+use std::fmt::Display;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use anyhow::{Context as _, Result, bail};
+use async_trait::async_trait;
+use dialog_common::{Blake3Hash, Buffer, ConditionalSend};
+use dialog_effects::blob::{BlobError, BlobSource};
+use dialog_repository::{Block, Item, Revision, SnapshotError};
+use futures_util::{Stream, TryStreamExt as _, pin_mut, stream};
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _, BufReader, BufWriter};
+
+use super::line;
+use crate::Session;
+
+/// Opens every snapshot file, naming the format and its version.
+const MAGIC: &[u8; 16] = b"DIALOG-SNAPSHOT\x01";
+
+/// Precedes a block record.
+const BLOCK: u8 = b'B';
+
+/// Precedes a blob record.
+const BLOB: u8 = b'L';
+
+/// Write the current revision of `branch`, and everything it references,
+/// to `file`.
+pub(super) async fn export<W>(
+    session: &Session,
+    branch: &str,
+    file: &Path,
+    out: &mut W,
+) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    let repository = session.repository().await?;
+    let branch = session.branch(&repository, branch).await?;
+    let Some(revision) = branch.revision() else {
+        bail!("branch `{}` has no revision to export", branch.name());
+    };
+
+    let mut writer = BufWriter::new(
+        File::create(file)
+            .await
+            .with_context(|| format!("cannot create {}", file.display()))?,
+    );
+    writer.write_all(MAGIC).await?;
+    let header = serde_ipld_dagcbor::to_vec(&revision)?;
+    writer.write_u32_le(u32::try_from(header.len())?).await?;
+    writer.write_all(&header).await?;
+
+    let (mut blocks, mut blobs) = (0u64, 0u64);
+    let items = repository
+        .snapshot(revision.clone())
+        .export()
+        .perform(session.operator());
+    pin_mut!(items);
+    while let Some(item) = items.try_next().await? {
+        match item {
+            Item::Block(block) => {
+                writer.write_u8(BLOCK).await?;
+                writer.write_all(block.digest.as_bytes()).await?;
+                writer
+                    .write_u32_le(u32::try_from(block.content.as_ref().len())?)
+                    .await?;
+                writer.write_all(block.content.as_ref()).await?;
+                blocks += 1;
+            }
+            Item::Blob {
+                digest,
+                size,
+                mut chunks,
+            } => {
+                writer.write_u8(BLOB).await?;
+                writer.write_all(digest.as_bytes()).await?;
+                writer.write_u64_le(size).await?;
+                let mut written = 0;
+                while let Some(chunk) = chunks.next().await? {
+                    written += chunk.len() as u64;
+                    writer.write_all(&chunk).await?;
+                }
+                if written != size {
+                    bail!("blob {digest} is {written} bytes, not the {size} it declares");
+                }
+                blobs += 1;
+            }
+        }
+    }
+    writer.flush().await?;
+
+    line(
+        out,
+        format!("{}\t{blocks} blocks\t{blobs} blobs", revision.version()),
+    )
+    .await
+}
+
+/// Store the content of the snapshot in `file`, and start `branch` at its
+/// revision when given. The branch must not have a revision yet: importing
+/// content never rewinds history.
+pub(super) async fn import<W>(
+    session: &Session,
+    file: &Path,
+    branch: Option<&str>,
+    out: &mut W,
+) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    let repository = session.repository().await?;
+    let operator = session.operator();
+    let target = match branch {
+        Some(name) => {
+            let branch = repository.branch(name).open().perform(operator).await?;
+            if branch.revision().is_some() {
+                bail!("branch `{name}` already has history; import into a new branch");
+            }
+            Some(branch)
+        }
+        None => None,
+    };
+
+    let mut reader = BufReader::new(
+        File::open(file)
+            .await
+            .with_context(|| format!("cannot open {}", file.display()))?,
+    );
+    let mut magic = [0u8; 16];
+    reader.read_exact(&mut magic).await?;
+    if &magic != MAGIC {
+        bail!("{} is not a dialog snapshot", file.display());
+    }
+    let mut header = vec![0u8; reader.read_u32_le().await? as usize];
+    reader.read_exact(&mut header).await?;
+    let revision: Revision = serde_ipld_dagcbor::from_slice(&header)?;
+
+    let imported = repository.import(items(reader)).perform(operator).await?;
+    let version = revision.version();
+    if let Some(branch) = target {
+        branch.reset(revision).perform(operator).await?;
+    }
+    line(
+        out,
+        format!(
+            "{version}\t{} blocks\t{} blobs",
+            imported.blocks, imported.blobs
+        ),
+    )
+    .await
+}
+
+/// The items that follow a snapshot's header. A blob is read whole before
+/// it is handed on, so the largest blob bounds the memory an import uses.
+fn items(
+    reader: BufReader<File>,
+) -> impl Stream<Item = Result<Item, SnapshotError>> + ConditionalSend + 'static {
+    stream::try_unfold(reader, |mut reader| async move {
+        let tag = match reader.read_u8().await {
+            Ok(tag) => tag,
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(error) => return Err(truncated(error)),
+        };
+        let mut digest = [0u8; 32];
+        reader.read_exact(&mut digest).await.map_err(truncated)?;
+        let digest = Blake3Hash::from(digest);
+        let item = match tag {
+            BLOCK => {
+                let length = reader.read_u32_le().await.map_err(truncated)?;
+                let mut content = vec![0u8; length as usize];
+                reader.read_exact(&mut content).await.map_err(truncated)?;
+                Item::Block(Block {
+                    digest,
+                    content: Buffer::from(content),
+                })
+            }
+            BLOB => {
+                let size = reader.read_u64_le().await.map_err(truncated)?;
+                let mut content = vec![0u8; usize::try_from(size).map_err(truncated)?];
+                reader.read_exact(&mut content).await.map_err(truncated)?;
+                Item::Blob {
+                    digest,
+                    size,
+                    chunks: Box::new(Bytes(Some(content))),
+                }
+            }
+            tag => {
+                return Err(corrupt(format!("unknown record {tag:#04x}")));
+            }
+        };
+        Ok(Some((item, reader)))
+    })
+}
+
+/// A snapshot that ended early.
+fn truncated(error: impl Display) -> SnapshotError {
+    corrupt(format!("truncated: {error}"))
+}
+
+/// A snapshot that cannot be read as one.
+fn corrupt(reason: String) -> SnapshotError {
+    SnapshotError::Blob(BlobError::Storage(format!("invalid snapshot: {reason}")))
+}
+
+/// A blob source over bytes already read into memory.
+struct Bytes(Option<Vec<u8>>);
+
+#[async_trait]
+impl BlobSource for Bytes {
+    async fn next(&mut self) -> Result<Option<Vec<u8>>, BlobError> {
+        Ok(self.0.take())
+    }
+}
@@ -0,0 +1,61 @@
+//! `dialog push` and `dialog pull`.
+
+use anyhow::{Result, bail};
+use dialog_common::ConditionalSend;
+use dialog_repository::Branch;
+use tokio::io::AsyncWrite;
+
+use super::line;
+use crate::{Session, SyncArgs};
+
+/// Publish the branch to its upstream.
+pub(super) async fn push<W>(session: &Session, sync: SyncArgs, out: &mut W) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    let branch = tracked(session, &sync, false).await?;
+    match branch.push().perform(session.operator()).await? {
+        Some(revision) => line(out, revision.version()).await,
+        None => line(out, "up to date").await,
+    }
+}
+
+/// Integrate the branch's upstream into it, creating the branch if this
+/// is its first pull.
+pub(super) async fn pull<W>(session: &Session, sync: SyncArgs, out: &mut W) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ConditionalSend,
+{
+    let branch = tracked(session, &sync, true).await?;
+    match branch.pull().perform(session.operator()).await? {
+        Some(revision) => line(out, revision.version()).await,
+        None => line(out, "up to date").await,
+    }
+}
+
+/// The branch `sync` names, tracking the same-named branch of `--remote`
+/// when one is given.
+async fn tracked(session: &Session, sync: &SyncArgs, create: bool) -> Result<Branch> {
+    let repository = session.repository().await?;
+    let operator = session.operator();
+    let branch = match create {
+        true => {
+            repository
+                .branch(&sync.branch)
+                .open()
+                .perform(operator)
+                .await?
+        }
+        false => session.branch(&repository, &sync.branch).await?,
+    };
+
+    if let Some(remote) = &sync.remote {
+        let remote = repository.remote(remote).load().perform(operator).await?;
+        let upstream = remote.branch(&sync.branch).open().perform(operator).await?;
+        branch.set_upstream(upstream).perform(operator).await?;
+    }
+    if branch.upstream().is_none() {
+        bail!("branch `{}` tracks no upstream; pass --remote", sync.branch);
+    }
+    Ok(branch)
+}
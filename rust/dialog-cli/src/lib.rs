@@ -0,0 +1,19 @@
+#![cfg(not(target_arch = "wasm32"))]
+#![warn(missing_docs)]
+
+//! The `dialog` command-line tool.
+//!
+//! Creates and inspects repositories from a shell: a profile and its
+//! spaces live on the native file system, branches are committed to from
+//! JSON or CSV files and read back with `select` and `log`, and branches
+//! travel to and from remotes backed by local directories, or as
+//! self-contained snapshot files.
+
+mod cli;
+pub use cli::*;
+
+mod command;
+pub use command::run;
+
+mod session;
+pub use session::*;
@@ -0,0 +1,109 @@
+//! The operating environment every command runs in.
+
+use anyhow::{Context as _, Result};
+use dialog_capability::Subject;
+use dialog_credentials::Credential;
+use dialog_effects::storage::Directory;
+use dialog_network::Network;
+use dialog_operator::{DeriveOperator as _, Operator, Profile};
+use dialog_repository::{Branch, Repository, RepositoryExt as _};
+use dialog_storage::provider::storage::{NativeSpace, Storage};
+
+use crate::Scope;
+
+/// The context seed the tool derives its operator key from.
+const OPERATOR_CONTEXT: &[u8] = b"dialog-cli";
+
+/// A profile opened on the native file system, with an operator derived
+/// from it that acts on the profile's behalf.
+pub struct Session {
+    scope: Scope,
+    profile: Profile,
+    operator: Operator<NativeSpace>,
+}
+
+impl Session {
+    /// Open the session `scope` names. With `create`, a missing profile is
+    /// created; otherwise it is an error.
+    pub async fn open(scope: &Scope, create: bool) -> Result<Self> {
+        let storage = Storage::<NativeSpace>::default();
+        let open = if create {
+            Profile::open(&scope.profile)
+        } else {
+            Profile::load(&scope.profile)
+        };
+        let profile = open
+            .at(profiles(scope))
+            .perform(&storage)
+            .await
+            .with_context(|| {
+                format!(
+                    "cannot open profile `{}` (run `dialog profile init` first)",
+                    scope.profile
+                )
+            })?;
+        let operator = profile
+            .derive(OPERATOR_CONTEXT)
+            .allow(Subject::any())
+            .base(spaces(scope))
+            .network(Network::default())
+            .build(storage)
+            .await?;
+        Ok(Self {
+            scope: scope.clone(),
+            profile,
+            operator,
+        })
+    }
+
+    /// The profile this session acts as.
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    /// The operator effects are performed through.
+    pub fn operator(&self) -> &Operator<NativeSpace> {
+        &self.operator
+    }
+
+    /// Load the space this session is scoped to.
+    pub async fn repository(&self) -> Result<Repository<Credential>> {
+        self.profile
+            .repository(&self.scope.space)
+            .load()
+            .perform(&self.operator)
+            .await
+            .with_context(|| {
+                format!(
+                    "cannot load space `{}` (run `dialog space init` first)",
+                    self.scope.space
+                )
+            })
+    }
+
+    /// Load an existing branch of `repository`.
+    pub async fn branch(&self, repository: &Repository<Credential>, name: &str) -> Result<Branch> {
+        repository
+            .branch(name)
+            .load()
+            .perform(&self.operator)
+            .await
+            .with_context(|| format!("no branch named `{name}`"))
+    }
+}
+
+/// Where the scope's profiles live.
+fn profiles(scope: &Scope) -> Directory {
+    match &scope.root {
+        Some(root) => Directory::At(root.join("profiles").to_string_lossy().into_owned()),
+        None => Directory::Profile,
+    }
+}
+
+/// Where the scope's spaces live.
+fn spaces(scope: &Scope) -> Directory {
+    match &scope.root {
+        Some(root) => Directory::At(root.join("spaces").to_string_lossy().into_owned()),
+        None => Directory::Current,
+    }
+}
@@ -60,6 +60,19 @@ impl Buffer {
             .get_or_init(|| Blake3Hash::hash(self.0.bytes.as_slice()))
     }
 
+    /// Creates a buffer over `bytes` that is addressed by `address` rather
+    /// than by the hash of its own contents.
+    ///
+    /// Only an encrypting layer has a reason to call this: it stores a
+    /// block's sealed form under the address of the plaintext it opens to,
+    /// so links between blocks keep resolving. Everything else must let the
+    /// address derive from the bytes.
+    pub fn addressed(bytes: &[u8], address: Blake3Hash) -> Self {
+        let buffer = Self::from(bytes);
+        let _ = buffer.0.hash.set(address);
+        buffer
+    }
+
     /// Returns this buffer's already-memoized decode of type `T`, or `None` if
     /// none is memoized yet — without ever decoding. A caller that wants to
     /// populate the memo uses [`memoize_decode`](Self::memoize_decode).
@@ -3,7 +3,9 @@
 //! CSV format support for the Dialog artifact exchange system.
 //!
 //! Provides [`CsvExporter`] and [`CsvImporter`] that implement the
-//! [`Exporter`] and [`Importer`] traits from `dialog-artifacts`.
+//! [`Exporter`] and [`Importer`] traits from `dialog-artifacts`, and
+//! [`upsert`] to commit an import by natural key rather than by the
+//! entities its rows name.
 //!
 //! Each CSV row represents a single artifact with columns:
 //! `the` (attribute), `of` (entity), `as` (value type), `is` (value),
@@ -17,11 +19,15 @@ pub use exporter::CsvExporter;
 mod importer;
 pub use importer::CsvImporter;
 
+mod upsert;
+pub use upsert::upsert;
+
 #[cfg(test)]
 mod tests {
     use dialog_artifacts::Exporter;
-    use dialog_artifacts::{Artifact, Cause, Value};
-    use futures_util::StreamExt;
+    use dialog_artifacts::{Artifact, ArtifactSelector, Attribute, Cause, Value};
+    use dialog_operator::helpers::{test_operator_with_profile, test_repo};
+    use futures_util::{StreamExt, TryStreamExt};
     use std::io::Cursor;
 
     use super::*;
@@ -196,6 +202,58 @@ mod tests {
         assert_eq!(imported[0].cause, Some(cause));
     }
 
+    #[dialog_common::test]
+    async fn it_upserts_a_reimported_row_by_its_key() -> anyhow::Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        let email: Attribute = "user/email".parse()?;
+
+        // The same person exported twice, each time under a fresh entity.
+        for of in ["user:first", "user:second"] {
+            let csv = export_artifacts(&[
+                Artifact {
+                    the: email.clone(),
+                    of: of.parse()?,
+                    is: Value::String("alice@example.com".into()),
+                    cause: None,
+                },
+                Artifact {
+                    the: "user/name".parse()?,
+                    of: of.parse()?,
+                    is: Value::String("Alice".into()),
+                    cause: None,
+                },
+            ])
+            .await;
+            let transaction = CsvImporter::from(Cursor::new(csv))
+                .upsert(branch.transaction(), std::slice::from_ref(&email))
+                .await?;
+            Box::pin(transaction.commit().perform(&operator)).await?;
+        }
+
+        let claims: Vec<Artifact> = branch
+            .claims()
+            .select(ArtifactSelector::new().of("user:first".parse()?))
+            .to_owned()
+            .perform(&operator)
+            .await?
+            .try_collect()
+            .await?;
+        assert_eq!(claims.len(), 2, "the re-import landed on the first entity");
+
+        let duplicates: Vec<Artifact> = branch
+            .claims()
+            .select(ArtifactSelector::new().of("user:second".parse()?))
+            .to_owned()
+            .perform(&operator)
+            .await?
+            .try_collect()
+            .await?;
+        assert!(duplicates.is_empty(), "got {duplicates:?}");
+        Ok(())
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     #[tokio::test]
     async fn it_roundtrips_via_file() {
@@ -0,0 +1,46 @@
+use dialog_artifacts::{Artifact, Attribute, Changes, DialogArtifactsError, Update as _};
+use dialog_repository::Transaction;
+use futures_util::TryStreamExt as _;
+
+use crate::CsvImporter;
+
+impl CsvImporter {
+    /// Read every row and stage it on `transaction` by natural key (see
+    /// [`upsert`]).
+    ///
+    /// Fails on the first row that does not parse rather than staging part
+    /// of the file.
+    pub async fn upsert<'a>(
+        self,
+        transaction: Transaction<'a>,
+        keys: &[Attribute],
+    ) -> Result<Transaction<'a>, DialogArtifactsError> {
+        let artifacts: Vec<Artifact> = self.try_collect().await?;
+        Ok(upsert(transaction, artifacts, keys))
+    }
+}
+
+/// Stage `artifacts` on `transaction`, upserting each entity by the
+/// artifacts that assert one of the `keys` attributes.
+///
+/// The entity a row names is provisional: when the branch already holds
+/// its key, every row for it lands on the entity holding the key, so
+/// importing the same data twice updates it rather than duplicating it.
+pub fn upsert<'a>(
+    mut transaction: Transaction<'a>,
+    artifacts: impl IntoIterator<Item = Artifact>,
+    keys: &[Attribute],
+) -> Transaction<'a> {
+    let mut changes = Changes::new();
+    for artifact in artifacts {
+        if keys.contains(&artifact.the) {
+            transaction = transaction.upsert(
+                artifact.the.clone(),
+                artifact.is.clone(),
+                artifact.of.clone(),
+            );
+        }
+        changes.associate(artifact.the, artifact.of, artifact.is);
+    }
+    transaction.integrate(changes)
+}
@@ -10,7 +10,9 @@
 //!         └── Catalog { catalog: String }
 //!               ├── Get { digest } → Effect → Result<Option<Bytes>, ArchiveError>
 //!               ├── Put { digest, content } → Effect → Result<(), ArchiveError>
-//!               └── Import { blocks } → Effect → Result<(), ArchiveError>
+//!               ├── Import { blocks } → Effect → Result<(), ArchiveError>
+//!               ├── List → Effect → Result<Vec<Blake3Hash>, ArchiveError>
+//!               └── Remove { digest } → Effect → Result<(), ArchiveError>
 //! ```
 
 use std::error::Error;
@@ -228,6 +230,50 @@ impl Effect for Import {
     type Output = Result<(), ArchiveError>;
 }
 
+/// List operation - enumerates the digests of every block a catalog holds.
+///
+/// Requires `Capability<Catalog>` access level.
+///
+/// The result is an inventory taken at one point in time: blocks put after
+/// the listing was produced are not in it. Garbage collection relies on
+/// exactly that — only blocks in an inventory taken *before* marking are
+/// sweep candidates, so a block written by a concurrent commit is never
+/// considered. Order is unspecified.
+#[derive(Debug, Clone, Serialize, Deserialize, Attenuate)]
+pub struct List;
+
+impl Effect for List {
+    type Of = Catalog;
+    type Output = Result<Vec<Blake3Hash>, ArchiveError>;
+}
+
+/// Remove operation - deletes a block by digest.
+///
+/// Requires `Capability<Catalog>` access level.
+///
+/// Removing a block that is not stored succeeds, so a sweep interrupted
+/// part way can simply be run again.
+#[derive(Debug, Clone, Serialize, Deserialize, Attenuate)]
+pub struct Remove {
+    /// The blake3 digest of the block to remove.
+    #[serde(with = "dialog_common::as_bytes")]
+    pub digest: Blake3Hash,
+}
+
+impl Remove {
+    /// Create a new Remove effect.
+    pub fn new(digest: impl Into<Blake3Hash>) -> Self {
+        Self {
+            digest: digest.into(),
+        }
+    }
+}
+
+impl Effect for Remove {
+    type Of = Catalog;
+    type Output = Result<(), ArchiveError>;
+}
+
 pub mod prelude;
 
 /// Errors that can occur during archive operations.
@@ -315,6 +361,26 @@ mod tests {
         assert_eq!(claim.ability(), "/archive/put");
     }
 
+    #[test]
+    fn it_builds_list_claim_path() {
+        let claim = Subject::from(did!("key:zSpace"))
+            .attenuate(Archive)
+            .attenuate(Catalog::new("index"))
+            .invoke(List);
+
+        assert_eq!(claim.ability(), "/archive/list");
+    }
+
+    #[test]
+    fn it_builds_remove_claim_path() {
+        let claim = Subject::from(did!("key:zSpace"))
+            .attenuate(Archive)
+            .attenuate(Catalog::new("index"))
+            .invoke(Remove::new([0u8; 32]));
+
+        assert_eq!(claim.ability(), "/archive/remove");
+    }
+
     #[dialog_common::test]
     fn it_roundtrips_import_payloads() {
         // Buffers serialize as bare bytes and deserialize realigned; the
@@ -8,7 +8,7 @@
 use dialog_capability::{Capability, Did, Policy, Subject};
 use dialog_common::{Blake3Hash, Buffer};
 
-use super::{Archive, Catalog, Get, Import, Put};
+use super::{Archive, Catalog, Get, Import, List, Put, Remove};
 
 /// Extension trait to start an archive capability chain.
 pub trait ArchiveSubjectExt {
@@ -55,18 +55,28 @@ pub trait CatalogExt {
     type Put;
     /// The resulting import chain type.
     type Import;
+    /// The resulting list chain type.
+    type List;
+    /// The resulting remove chain type.
+    type Remove;
     /// Get content by digest.
     fn get(self, digest: impl Into<Blake3Hash>) -> Self::Get;
     /// Put a single content-addressed block.
     fn put(self, block: impl Into<Buffer>) -> Self::Put;
     /// Import a batch of content-addressed blocks.
     fn import(self, blocks: impl IntoIterator<Item = impl Into<Buffer>>) -> Self::Import;
+    /// List the digests of every block in the catalog.
+    fn list(self) -> Self::List;
+    /// Remove a block by digest.
+    fn remove(self, digest: impl Into<Blake3Hash>) -> Self::Remove;
 }
 
 impl CatalogExt for Capability<Catalog> {
     type Get = Capability<Get>;
     type Put = Capability<Put>;
     type Import = Capability<Import>;
+    type List = Capability<List>;
+    type Remove = Capability<Remove>;
 
     fn get(self, digest: impl Into<Blake3Hash>) -> Capability<Get> {
         self.invoke(Get::new(digest))
@@ -79,6 +89,14 @@ impl CatalogExt for Capability<Catalog> {
     fn import(self, blocks: impl IntoIterator<Item = impl Into<Buffer>>) -> Capability<Import> {
         self.invoke(Import::new(blocks))
     }
+
+    fn list(self) -> Capability<List> {
+        self.invoke(List)
+    }
+
+    fn remove(self, digest: impl Into<Blake3Hash>) -> Capability<Remove> {
+        self.invoke(Remove::new(digest))
+    }
 }
 
 /// Field accessors on `Capability<Import>`.
@@ -140,3 +158,33 @@ impl PutExt for Capability<Put> {
         Put::of(self).block.as_ref()
     }
 }
+
+/// Field accessors on `Capability<List>`.
+pub trait ListExt {
+    /// Get the catalog name from the capability chain.
+    fn catalog(&self) -> &str;
+}
+
+impl ListExt for Capability<List> {
+    fn catalog(&self) -> &str {
+        &Catalog::of(self).catalog
+    }
+}
+
+/// Field accessors on `Capability<Remove>`.
+pub trait RemoveExt {
+    /// Get the catalog name from the capability chain.
+    fn catalog(&self) -> &str;
+    /// Get the digest from the capability chain.
+    fn digest(&self) -> &Blake3Hash;
+}
+
+impl RemoveExt for Capability<Remove> {
+    fn catalog(&self) -> &str {
+        &Catalog::of(self).catalog
+    }
+
+    fn digest(&self) -> &Blake3Hash {
+        &Remove::of(self).digest
+    }
+}
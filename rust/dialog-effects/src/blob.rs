@@ -14,7 +14,9 @@
 //!         └── Blob (/archive/blob)
 //!               ├── Write                          → BlobWriter  (ingest; finish → hash)
 //!               ├── Import { digest, size, chunks } → BlobWriter
-//!               └── Read { digest, range }          → BlobReader
+//!               ├── Read { digest, range }          → BlobReader
+//!               ├── List                            → Vec<Blake3Hash>
+//!               └── Remove { digest }               → ()
 //! ```
 //!
 //! Bytes never travel inside an effect: the signed capability carries only
@@ -169,6 +171,41 @@ impl Effect for Import {
     type Output = Result<BlobWriter, BlobError>;
 }
 
+/// Enumerate the digests of every committed blob in the store.
+///
+/// Blobs still being written are not committed and are not listed. Like
+/// [`archive::List`](crate::archive::List), the result is a point-in-time
+/// inventory; order is unspecified.
+#[derive(Debug, Clone, Serialize, Deserialize, Attenuate)]
+pub struct List;
+
+impl Effect for List {
+    type Of = Blob;
+    type Output = Result<Vec<Blake3Hash>, BlobError>;
+}
+
+/// Delete a committed blob by hash. Removing an absent blob succeeds.
+#[derive(Debug, Clone, Serialize, Deserialize, Attenuate)]
+pub struct Remove {
+    /// The content hash of the blob to remove.
+    #[serde(with = "dialog_common::as_bytes")]
+    pub digest: Blake3Hash,
+}
+
+impl Remove {
+    /// Remove the blob `digest`.
+    pub fn new(digest: impl Into<Blake3Hash>) -> Self {
+        Self {
+            digest: digest.into(),
+        }
+    }
+}
+
+impl Effect for Remove {
+    type Of = Blob;
+    type Output = Result<(), BlobError>;
+}
+
 pub mod prelude;
 
 mod error;
@@ -210,4 +247,22 @@ mod tests {
             .import([0u8; 32], 4096);
         assert_eq!(claim.ability(), "/archive/blob/import");
     }
+
+    #[dialog_common::test]
+    fn it_builds_blob_list_path() {
+        let claim = Subject::from(did!("key:zSpace"))
+            .attenuate(Archive)
+            .blob()
+            .list();
+        assert_eq!(claim.ability(), "/archive/blob/list");
+    }
+
+    #[dialog_common::test]
+    fn it_builds_blob_remove_path() {
+        let claim = Subject::from(did!("key:zSpace"))
+            .attenuate(Archive)
+            .blob()
+            .remove([0u8; 32]);
+        assert_eq!(claim.ability(), "/archive/blob/remove");
+    }
 }
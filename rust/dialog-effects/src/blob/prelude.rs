@@ -9,7 +9,7 @@ use dialog_common::Blake3Hash;
 
 use crate::archive::Archive;
 
-use super::{Blob, ByteRange, Import, Read, Write};
+use super::{Blob, ByteRange, Import, List, Read, Remove, Write};
 
 /// Scope an archive capability to its blob store.
 pub trait ArchiveBlobExt {
@@ -34,6 +34,10 @@ pub trait BlobExt {
     type Write;
     /// The resulting import chain type.
     type Import;
+    /// The resulting list chain type.
+    type List;
+    /// The resulting remove chain type.
+    type Remove;
 
     /// Read a blob by hash.
     fn read(self, digest: impl Into<Blake3Hash>) -> Self::Read;
@@ -41,12 +45,18 @@ pub trait BlobExt {
     fn write(self) -> Self::Write;
     /// Import a blob whose hash is already known.
     fn import(self, digest: impl Into<Blake3Hash>, size: u64) -> Self::Import;
+    /// List the hashes of every committed blob.
+    fn list(self) -> Self::List;
+    /// Remove a blob by hash.
+    fn remove(self, digest: impl Into<Blake3Hash>) -> Self::Remove;
 }
 
 impl BlobExt for Capability<Blob> {
     type Read = Capability<Read>;
     type Write = Capability<Write>;
     type Import = Capability<Import>;
+    type List = Capability<List>;
+    type Remove = Capability<Remove>;
 
     fn read(self, digest: impl Into<Blake3Hash>) -> Capability<Read> {
         self.invoke(Read::new(digest))
@@ -59,6 +69,14 @@ impl BlobExt for Capability<Blob> {
     fn import(self, digest: impl Into<Blake3Hash>, size: u64) -> Capability<Import> {
         self.invoke(Import::new(digest, size))
     }
+
+    fn list(self) -> Capability<List> {
+        self.invoke(List)
+    }
+
+    fn remove(self, digest: impl Into<Blake3Hash>) -> Capability<Remove> {
+        self.invoke(Remove::new(digest))
+    }
 }
 
 /// Field accessors on `Capability<Read>`.
@@ -102,3 +120,15 @@ impl BlobImportExt for Capability<Import> {
         &Import::of(self).chunks
     }
 }
+
+/// Field accessors on `Capability<Remove>`.
+pub trait BlobRemoveExt {
+    /// The blob digest from the capability chain.
+    fn digest(&self) -> &Blake3Hash;
+}
+
+impl BlobRemoveExt for Capability<Remove> {
+    fn digest(&self) -> &Blake3Hash {
+        &Remove::of(self).digest
+    }
+}
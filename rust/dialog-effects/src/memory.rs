@@ -7,6 +7,7 @@
 //! ```text
 //! Subject (repository DID)
 //!   └── Memory (ability: /memory)
+//!         ├── List { prefix } → Effect → Result<Vec<String>, MemoryError>
 //!         └── Space { space: String }
 //!               └── Cell { cell: String }
 //!                     ├── Resolve → Effect → Result<Option<Edition<Vec<u8>>>, MemoryError>
@@ -243,6 +244,33 @@ impl Effect for Retract {
     type Output = Result<(), MemoryError>;
 }
 
+/// List operation - enumerates the cells that currently hold content.
+///
+/// Each entry is the cell's address, `{space}/{cell}`, and only addresses
+/// starting with `prefix` are returned (an empty prefix lists everything).
+/// Spaces and cells may both contain `/`, and not every backend keeps the
+/// boundary between them, so callers that need it split by a naming
+/// convention they control. Order is unspecified.
+#[derive(Debug, Clone, Serialize, Deserialize, Attenuate)]
+pub struct List {
+    /// Only cells whose `{space}/{cell}` address starts with this are listed.
+    pub prefix: String,
+}
+
+impl List {
+    /// Create a new List effect.
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+}
+
+impl Effect for List {
+    type Of = Memory;
+    type Output = Result<Vec<String>, MemoryError>;
+}
+
 pub mod prelude;
 
 /// Errors that can occur during memory operations.
@@ -345,4 +373,13 @@ mod tests {
 
         assert_eq!(claim.ability(), "/memory/retract");
     }
+
+    #[test]
+    fn it_builds_list_claim_path() {
+        let claim = Subject::from(did!("key:zSpace"))
+            .attenuate(Memory)
+            .invoke(List::new("branch/"));
+
+        assert_eq!(claim.ability(), "/memory/list");
+    }
 }
@@ -7,7 +7,7 @@
 
 use dialog_capability::{Capability, Did, Policy, Subject};
 
-use super::{Cell, Memory, Publish, Resolve, Retract, Space, Version};
+use super::{Cell, List, Memory, Publish, Resolve, Retract, Space, Version};
 
 /// Extension trait to start a memory capability chain.
 pub trait MemorySubjectExt {
@@ -35,15 +35,24 @@ impl MemorySubjectExt for Did {
 pub trait MemoryExt {
     /// The resulting space chain type.
     type Space;
+    /// The resulting list chain type.
+    type List;
     /// Scope to a named space.
     fn space(self, name: impl Into<String>) -> Self::Space;
+    /// List the addresses of cells starting with `prefix`.
+    fn list(self, prefix: impl Into<String>) -> Self::List;
 }
 
 impl MemoryExt for Capability<Memory> {
     type Space = Capability<Space>;
+    type List = Capability<List>;
     fn space(self, name: impl Into<String>) -> Capability<Space> {
         self.attenuate(Space::new(name))
     }
+
+    fn list(self, prefix: impl Into<String>) -> Capability<List> {
+        self.invoke(List::new(prefix))
+    }
 }
 
 /// Extension methods for scoping a space to a named cell.
@@ -168,3 +177,15 @@ impl RetractExt for Capability<Retract> {
         &Retract::of(self).when
     }
 }
+
+/// Field accessors on `Capability<List>`.
+pub trait MemoryListExt {
+    /// Get the address prefix from the capability chain.
+    fn prefix(&self) -> &str;
+}
+
+impl MemoryListExt for Capability<List> {
+    fn prefix(&self) -> &str {
+        &List::of(self).prefix
+    }
+}
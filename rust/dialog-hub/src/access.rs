@@ -0,0 +1,267 @@
+//! The hub's access service: UCAN invocations in, presigned permits out.
+//!
+//! Peers reach a hub the way they reach any UCAN site: they `POST` a signed
+//! invocation container to the access endpoint and get back a [`Permit`]
+//! naming the request to make. The permit is presigned with a credential
+//! only the hub knows, so the [data plane](crate::store) accepts exactly the
+//! requests this service authorized.
+//!
+//! Each effect family gets its own bucket, because the layouts
+//! [`dialog_remote_s3`] gives them overlap — an archive catalog named `blob`
+//! and the blob store share a prefix, as do a memory space and a catalog of
+//! the same name.
+//!
+//! Every invocation is checked against the revocations and successions on
+//! its subject's [hosted access branch](crate::hosted), so a delegation its
+//! owners revoked stops working here once the revocation is pushed.
+//!
+//! Refusals are answered with the JSON the client's UCAN site reads back
+//! into a typed error: an [`AuthorizeError`] when the invocation does not
+//! hold, and a [`Rejection`] when the hub declines for another reason.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use dialog_capability::Did;
+use dialog_capability::access::AuthorizeError;
+use dialog_effects::Rejection;
+use dialog_remote_s3::{Address, Permit, S3Credential, S3Error};
+use dialog_remote_ucan_s3::{InvocationChain, UcanAuthorizer};
+use dialog_storage::provider::{FileSystem, FileSystemHandle};
+use hyper::StatusCode;
+use rand::Rng as _;
+use rand::distributions::Alphanumeric;
+use url::Url;
+
+use crate::hosted::HostedSpace;
+
+/// The region permits are signed for. Only the hub checks them, so any
+/// fixed region works.
+const REGION: &str = "us-east-1";
+
+/// The effect family an invocation belongs to, and so the bucket its
+/// permit is minted for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Family {
+    Archive,
+    Blob,
+    Memory,
+}
+
+impl Family {
+    const ALL: [Family; 3] = [Family::Archive, Family::Blob, Family::Memory];
+
+    /// The family of a command, by its ability segments.
+    fn of(command: &[&str]) -> Option<Self> {
+        match command {
+            ["archive", "blob", ..] => Some(Family::Blob),
+            ["archive", ..] => Some(Family::Archive),
+            ["memory", ..] => Some(Family::Memory),
+            _ => None,
+        }
+    }
+
+    /// The family a bucket serves.
+    pub(crate) fn from_bucket(bucket: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|family| family.bucket() == bucket)
+    }
+
+    pub(crate) const fn bucket(self) -> &'static str {
+        match self {
+            Family::Archive => "archive",
+            Family::Blob => "blob",
+            Family::Memory => "memory",
+        }
+    }
+}
+
+/// The hub's own S3 credential: signs every permit it hands out and is
+/// the only identity the data plane accepts.
+///
+/// Generated afresh each time the hub starts, so permits do not outlive
+/// the process that minted them.
+pub(crate) fn credential() -> S3Credential {
+    let secret: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect();
+    S3Credential::new("dialog-hub", secret)
+}
+
+/// What the access endpoint answers: a status and a body.
+pub(crate) struct Answer {
+    pub(crate) status: StatusCode,
+    pub(crate) content_type: &'static str,
+    pub(crate) body: Bytes,
+}
+
+impl Answer {
+    fn permit(permit: &Permit) -> Self {
+        match serde_ipld_dagcbor::to_vec(permit) {
+            Ok(body) => Self {
+                status: StatusCode::OK,
+                content_type: "application/cbor",
+                body: body.into(),
+            },
+            Err(error) => Self::rejection(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Rejection::Unclassified {
+                    detail: format!("could not encode the permit: {error}"),
+                },
+            ),
+        }
+    }
+
+    fn refusal(error: AuthorizeError) -> Self {
+        let status = match error {
+            AuthorizeError::Unavailable { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::FORBIDDEN,
+        };
+        Self::json(status, serde_json::to_vec(&error))
+    }
+
+    fn rejection(status: StatusCode, rejection: Rejection) -> Self {
+        Self::json(status, serde_json::to_vec(&rejection))
+    }
+
+    fn json(status: StatusCode, body: Result<Vec<u8>, serde_json::Error>) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: body.unwrap_or_default().into(),
+        }
+    }
+}
+
+/// Verifies invocations on hosted subjects and mints permits for them.
+#[derive(Debug, Clone)]
+pub(crate) struct AccessService {
+    root: FileSystemHandle,
+    hosted: Arc<HashSet<Did>>,
+    archive: UcanAuthorizer,
+    blob: UcanAuthorizer,
+    memory: UcanAuthorizer,
+}
+
+impl AccessService {
+    /// Mint permits for requests to `endpoint`, signed with `credential`,
+    /// reading each subject's access branch from its directory under `root`.
+    pub(crate) fn new(
+        endpoint: &Url,
+        credential: &S3Credential,
+        root: FileSystemHandle,
+        hosted: Arc<HashSet<Did>>,
+    ) -> Result<Self, S3Error> {
+        let authorizer = |family: Family| -> Result<UcanAuthorizer, S3Error> {
+            let address = Address::builder(endpoint.as_str())
+                .region(REGION)
+                .bucket(family.bucket())
+                .path_style(true)
+                .build()?;
+            Ok(UcanAuthorizer::new(address, Some(credential.clone())))
+        };
+        Ok(Self {
+            root,
+            hosted,
+            archive: authorizer(Family::Archive)?,
+            blob: authorizer(Family::Blob)?,
+            memory: authorizer(Family::Memory)?,
+        })
+    }
+
+    /// Authorize the invocation `container` carries.
+    pub(crate) async fn authorize(&self, container: &[u8]) -> Answer {
+        let chain = match InvocationChain::try_from(container) {
+            Ok(chain) => chain,
+            Err(error) => {
+                return Answer::refusal(AuthorizeError::Malformed {
+                    detail: error.to_string(),
+                });
+            }
+        };
+
+        // Refuse subjects the hub does not host before verifying anything,
+        // so a stranger learns nothing from how far their chain got.
+        if !self.hosted.contains(chain.subject()) {
+            return Answer::rejection(
+                StatusCode::NOT_FOUND,
+                Rejection::Unclassified {
+                    detail: format!("{} is not hosted here", chain.subject()),
+                },
+            );
+        }
+
+        let command: Vec<&str> = chain.command().0.iter().map(String::as_str).collect();
+        let authorizer = match Family::of(&command) {
+            Some(Family::Archive) => &self.archive,
+            Some(Family::Blob) => &self.blob,
+            Some(Family::Memory) => &self.memory,
+            None => {
+                return Answer::refusal(AuthorizeError::Malformed {
+                    detail: format!("no effect answers to /{}", command.join("/")),
+                });
+            }
+        };
+
+        let space = match self.root.resolve(chain.subject().as_ref()) {
+            Ok(space) => HostedSpace::new(FileSystem::from(space)),
+            Err(error) => {
+                return Answer::refusal(AuthorizeError::Unavailable {
+                    detail: format!("could not open the subject's space: {error}"),
+                });
+            }
+        };
+        let (revocations, successions) = match space.checkers(chain.subject().clone()).await {
+            Ok(checkers) => checkers,
+            Err(error) => {
+                return Answer::refusal(AuthorizeError::Unavailable {
+                    detail: format!("could not read the subject's access branch: {error}"),
+                });
+            }
+        };
+        let authorizer = authorizer
+            .clone()
+            .with_revocations(revocations)
+            .with_successions(successions);
+
+        match authorizer.authorize(container).await {
+            Ok(permit) => Answer::permit(&permit),
+            Err(S3Error::Authorization(error)) => Answer::refusal(error),
+            Err(S3Error::Rejected(rejection)) => {
+                Answer::rejection(StatusCode::SERVICE_UNAVAILABLE, rejection)
+            }
+            // The chain held but its arguments do not describe an effect.
+            Err(error) => Answer::refusal(AuthorizeError::Malformed {
+                detail: error.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_routes_commands_to_their_family() {
+        assert_eq!(Family::of(&["archive", "get"]), Some(Family::Archive));
+        assert_eq!(
+            Family::of(&["archive", "blob", "import"]),
+            Some(Family::Blob)
+        );
+        assert_eq!(Family::of(&["memory", "publish"]), Some(Family::Memory));
+        assert_eq!(Family::of(&["credential", "load"]), None);
+    }
+
+    #[test]
+    fn it_round_trips_families_through_buckets() {
+        for family in Family::ALL {
+            assert_eq!(Family::from_bucket(family.bucket()), Some(family));
+        }
+        assert_eq!(Family::from_bucket("test-bucket"), None);
+    }
+}
@@ -0,0 +1,25 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+//! # Dialog Hub Binary
+//!
+//! Serves Dialog repositories to peers holding UCAN delegations for them.
+
+use anyhow::Result;
+use clap::Parser;
+use dialog_hub::{Hub, HubConfig};
+use tracing_subscriber::EnvFilter;
+
+/// Parse the command line and serve until interrupted.
+#[tokio::main]
+pub async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()))
+        .init();
+
+    let hub = Hub::start(HubConfig::parse()).await?;
+    tracing::info!(access = %hub.access_url(), "hub listening");
+
+    tokio::signal::ctrl_c().await?;
+    hub.stop();
+    Ok(())
+}
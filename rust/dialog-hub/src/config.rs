@@ -0,0 +1,53 @@
+//! Command-line configuration for the hub.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use clap::Parser;
+use dialog_capability::Did;
+use url::Url;
+
+/// How a hub listens, where it stores spaces and which subjects it serves.
+#[derive(Debug, Clone, Parser)]
+#[command(name = "dialog-hub")]
+#[command(bin_name = "dialog-hub")]
+#[command(about = "Serve Dialog repositories to their UCAN-holding peers", long_about = None)]
+pub struct HubConfig {
+    /// The address to listen on
+    #[arg(long, default_value = "127.0.0.1:7878")]
+    pub listen: SocketAddr,
+
+    /// The URL peers reach the hub at, when it sits behind a proxy; the
+    /// listen address when omitted. Presigned permits point here.
+    #[arg(long)]
+    pub public_url: Option<Url>,
+
+    /// The directory hosted spaces are stored under, one directory per
+    /// subject
+    #[arg(long)]
+    pub root: PathBuf,
+
+    /// A subject (repository DID) to host (repeatable). Invocations on any
+    /// other subject are refused.
+    #[arg(long = "host", value_name = "DID")]
+    pub hosted: Vec<Did>,
+}
+
+impl HubConfig {
+    /// A hub storing spaces under `root`, listening on an ephemeral local
+    /// port and hosting no subjects yet.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            listen: SocketAddr::from(([127, 0, 0, 1], 0)),
+            public_url: None,
+            root: root.into(),
+            hosted: Vec::new(),
+        }
+    }
+
+    /// Host `subject` in addition to those already configured.
+    pub fn host(mut self, subject: Did) -> Self {
+        self.hosted.push(subject);
+        self
+    }
+}
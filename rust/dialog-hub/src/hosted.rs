@@ -0,0 +1,101 @@
+//! A hosted subject's access branch, read as the hub's revocation and
+//! succession source.
+//!
+//! Peers push a repository's [`ACCESS_BRANCH`] to the hub like any other
+//! branch, so the revocations and successions its owners record there land
+//! in the subject's directory. The access service opens that branch for
+//! every invocation it verifies, and consults the facts on it through the
+//! checkers `dialog-repository` provides: a revocation takes effect at the
+//! hub as soon as the push carrying it has published.
+
+use dialog_capability::{Command, Did, Fork, Provider, Subject};
+use dialog_effects::archive::{Get, Put};
+use dialog_effects::blob::{BlobError, Import as BlobImport, Read as BlobRead};
+use dialog_effects::memory::Resolve;
+use dialog_repository::{
+    ACCESS_BRANCH, Branch, RemoteSite, RepositoryMemoryExt as _, ResolveError, RetainedRevocations,
+    RetainedSuccessions,
+};
+use dialog_storage::provider::FileSystem;
+
+/// A hosted subject's space, as the environment its access branch is read
+/// through.
+///
+/// Local effects go to the subject's directory. The remote forks report
+/// nothing: the hub is where the subject replicates to, so there is no
+/// further remote to fetch missing content from.
+#[derive(Debug, Clone)]
+pub(crate) struct HostedSpace {
+    space: FileSystem,
+}
+
+impl HostedSpace {
+    pub(crate) fn new(space: FileSystem) -> Self {
+        Self { space }
+    }
+
+    /// Open `subject`'s access branch as last pushed, and the checkers
+    /// over the revocations and successions recorded on it.
+    pub(crate) async fn checkers(
+        self,
+        subject: Did,
+    ) -> Result<(RetainedRevocations<Self>, RetainedSuccessions<Self>), ResolveError> {
+        let branch: Branch = Subject::from(subject)
+            .branch(ACCESS_BRANCH)
+            .open()
+            .perform(&self)
+            .await?;
+        Ok((
+            branch.delegations().revocations(self.clone()),
+            branch.delegations().successions(self),
+        ))
+    }
+}
+
+macro_rules! hosted_local {
+    ($($effect:ty),+ $(,)?) => {$(
+        #[async_trait::async_trait]
+        impl Provider<$effect> for HostedSpace {
+            async fn execute(
+                &self,
+                input: <$effect as Command>::Input,
+            ) -> <$effect as Command>::Output {
+                Provider::<$effect>::execute(&self.space, input).await
+            }
+        }
+    )+};
+}
+
+hosted_local!(Get, Put, Resolve, BlobRead, BlobImport);
+
+#[async_trait::async_trait]
+impl Provider<Fork<RemoteSite, Get>> for HostedSpace {
+    async fn execute(
+        &self,
+        _input: <Fork<RemoteSite, Get> as Command>::Input,
+    ) -> <Fork<RemoteSite, Get> as Command>::Output {
+        Ok(None)
+    }
+}
+
+#[async_trait::async_trait]
+impl Provider<Fork<RemoteSite, Resolve>> for HostedSpace {
+    async fn execute(
+        &self,
+        _input: <Fork<RemoteSite, Resolve> as Command>::Input,
+    ) -> <Fork<RemoteSite, Resolve> as Command>::Output {
+        Ok(None)
+    }
+}
+
+#[async_trait::async_trait]
+impl Provider<Fork<RemoteSite, BlobRead>> for HostedSpace {
+    async fn execute(
+        &self,
+        _input: <Fork<RemoteSite, BlobRead> as Command>::Input,
+    ) -> <Fork<RemoteSite, BlobRead> as Command>::Output {
+        Err(BlobError::NotFound(
+            "a hub has no remote to read missing content from".to_string(),
+        ))
+    }
+}
@@ -0,0 +1,27 @@
+#![cfg(not(target_arch = "wasm32"))]
+#![warn(missing_docs)]
+
+//! A self-hostable sync hub for Dialog repositories.
+//!
+//! A hub is a UCAN site backed by the native file system: peers configure it
+//! as a remote with the [access URL](Hub::access_url), and push and pull
+//! through it exactly as they would through any other UCAN-fronted store.
+//! Every request is authorized by a delegation chain rooted in the
+//! repository it targets, so the hub holds no accounts of its own — hosting
+//! a subject only means agreeing to store it. Revocations and key
+//! successions recorded on a hosted repository's access branch are
+//! enforced as soon as they are pushed.
+//!
+//! Each hosted subject lives in its own directory under the hub root, laid
+//! out like any local space, so a hosted repository can be backed up, moved
+//! or served from disk with ordinary tools.
+
+mod access;
+mod hosted;
+mod store;
+
+mod config;
+pub use config::*;
+
+mod server;
+pub use server::*;
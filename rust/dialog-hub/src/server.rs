@@ -0,0 +1,192 @@
+//! The running hub: one listener serving the access endpoint and the data
+//! plane side by side.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use bytes::Bytes;
+use dialog_storage::provider::FileSystemHandle;
+use http_body_util::{BodyExt as _, Limited};
+use hyper::body::Incoming;
+use hyper::header::{self, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use s3s::service::{S3Service, S3ServiceBuilder};
+use s3s::{Body, HttpError};
+use tokio::net::TcpListener;
+use url::Url;
+
+use crate::access::{AccessService, Answer, credential};
+use crate::config::HubConfig;
+use crate::store::HubStore;
+
+/// The path peers `POST` invocation containers to.
+pub const ACCESS_PATH: &str = "/access";
+
+/// The largest invocation container the access endpoint reads. Delegation
+/// chains are a handful of signed envelopes; anything near this is not one.
+const MAX_CONTAINER_BYTES: usize = 1 << 20;
+
+/// A running hub.
+///
+/// Serves until [`stop`](Self::stop) is called or the handle is dropped.
+pub struct Hub {
+    /// The URL the hub is reachable at.
+    pub endpoint: Url,
+    shutdown_tx: tokio::sync::oneshot::Sender<()>,
+}
+
+impl Hub {
+    /// Bind `config.listen` and start serving the subjects `config` hosts.
+    pub async fn start(config: HubConfig) -> anyhow::Result<Self> {
+        tokio::fs::create_dir_all(&config.root)
+            .await
+            .with_context(|| format!("could not create {}", config.root.display()))?;
+        let root = tokio::fs::canonicalize(&config.root).await?;
+        let root = Url::from_directory_path(&root)
+            .map_err(|_| anyhow::anyhow!("{} is not an absolute path", root.display()))?;
+        let root = FileSystemHandle::try_from(root)?;
+
+        let listener = TcpListener::bind(config.listen).await?;
+        let endpoint = match config.public_url {
+            Some(url) => url,
+            None => Url::parse(&format!("http://{}", listener.local_addr()?))?,
+        };
+
+        let hosted = Arc::new(config.hosted.into_iter().collect::<HashSet<_>>());
+        let credential = credential();
+        let access = Arc::new(AccessService::new(
+            &endpoint,
+            &credential,
+            root.clone(),
+            hosted.clone(),
+        )?);
+
+        let mut builder = S3ServiceBuilder::new(HubStore::new(root, hosted));
+        builder.set_auth(s3s::auth::SimpleAuth::from_single(
+            credential.access_key_id(),
+            credential.secret_access_key(),
+        ));
+        let store = builder.build();
+
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    result = listener.accept() => {
+                        if let Ok((stream, _)) = result {
+                            let access = access.clone();
+                            let store = store.clone();
+                            tokio::spawn(async move {
+                                let service = hyper::service::service_fn(move |req| {
+                                    let access = access.clone();
+                                    let store = store.clone();
+                                    async move { handle_request(req, &access, &store).await }
+                                });
+                                let _ = http1::Builder::new()
+                                    .serve_connection(TokioIo::new(stream), service)
+                                    .await;
+                            });
+                        }
+                    }
+                }
+            }
+        });
+
+        tracing::info!(%endpoint, "hub listening");
+        Ok(Hub {
+            endpoint,
+            shutdown_tx,
+        })
+    }
+
+    /// The URL of the access endpoint, which is what a UCAN remote is
+    /// configured with.
+    pub fn access_url(&self) -> String {
+        let mut url = self.endpoint.clone();
+        url.set_path(ACCESS_PATH);
+        url.to_string()
+    }
+
+    /// Stop accepting connections.
+    pub fn stop(self) {
+        let _ = self.shutdown_tx.send(());
+    }
+}
+
+async fn handle_request(
+    req: Request<Incoming>,
+    access: &AccessService,
+    store: &S3Service,
+) -> Result<Response<Body>, HttpError> {
+    let mut response = if req.method() == Method::OPTIONS {
+        Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Body::empty())
+            .expect("static response")
+    } else if req.uri().path() == ACCESS_PATH {
+        authorize(req, access).await
+    } else {
+        store.call(req.map(Body::from)).await?
+    };
+    add_cors_headers(response.headers_mut());
+    Ok(response)
+}
+
+async fn authorize(req: Request<Incoming>, access: &AccessService) -> Response<Body> {
+    if req.method() != Method::POST {
+        return Response::builder()
+            .status(StatusCode::METHOD_NOT_ALLOWED)
+            .body(Body::from(Bytes::from_static(b"Method not allowed")))
+            .expect("static response");
+    }
+
+    let answer = match Limited::new(req.into_body(), MAX_CONTAINER_BYTES)
+        .collect()
+        .await
+    {
+        Ok(collected) => access.authorize(&collected.to_bytes()).await,
+        Err(error) => {
+            return Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(Body::from(format!("Failed to read body: {error}")))
+                .expect("static response");
+        }
+    };
+
+    let Answer {
+        status,
+        content_type,
+        body,
+    } = answer;
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(Body::from(body))
+        .expect("static response")
+}
+
+/// Let browser peers reach the hub, and read the ETags that carry memory
+/// versions.
+fn add_cors_headers(headers: &mut hyper::HeaderMap) {
+    for (name, value) in [
+        (header::ACCESS_CONTROL_ALLOW_ORIGIN, "*"),
+        (
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            "GET, PUT, POST, DELETE, OPTIONS",
+        ),
+        (header::ACCESS_CONTROL_ALLOW_HEADERS, "*"),
+        (
+            header::ACCESS_CONTROL_EXPOSE_HEADERS,
+            "ETag, Content-Length, Content-Type",
+        ),
+        (header::ACCESS_CONTROL_MAX_AGE, "86400"),
+        (header::CACHE_CONTROL, "no-store"),
+    ] {
+        headers.insert(name, HeaderValue::from_static(value));
+    }
+}
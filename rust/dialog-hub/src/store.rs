@@ -0,0 +1,403 @@
+//! The hub's data plane: presigned S3-style requests performed against the
+//! native [`FileSystem`] provider.
+//!
+//! A permit minted by the [access service](crate::access) is a presigned URL
+//! of the form `/{family}/{subject}/{path}`, where the bucket names the effect
+//! family it was minted for and `path` is the layout
+//! [`dialog_remote_s3`] gives that family:
+//!
+//! ```text
+//! /archive/{subject}/{catalog}/{base58(digest)}
+//! /blob/{subject}/blob/{base58(digest)}
+//! /memory/{subject}/{space}/{cell}
+//! ```
+//!
+//! [`s3s`] checks the presigned signature, so a request reaching [`HubStore`]
+//! carries exactly the method, path and preconditions the access service
+//! authorized. The store turns it back into the effect and performs it on
+//! the subject's directory under the hub root — `{root}/{subject}/` — laid
+//! out like any other space.
+//!
+//! Memory versions travel as ETags: the base58 of the provider's
+//! [`Version`] bytes, decoded again from `If-Match`.
+
+use crate::access::Family;
+use base58::{FromBase58 as _, ToBase58 as _};
+use bytes::Bytes;
+use dialog_capability::{Did, Subject};
+use dialog_common::{Blake3Hash, Buffer};
+use dialog_effects::archive::ArchiveError;
+use dialog_effects::blob::{BlobError, BlobReader, Read};
+use dialog_effects::memory::{MemoryError, Version};
+use dialog_effects::prelude::*;
+use dialog_storage::provider::{FileSystem, FileSystemHandle};
+use futures_util::StreamExt as _;
+use s3s::dto::{
+    DeleteObjectInput, DeleteObjectOutput, ETag, ETagCondition, GetObjectInput, GetObjectOutput,
+    PutObjectInput, PutObjectOutput, Range, StreamingBlob,
+};
+use s3s::{S3, S3Error, S3Request, S3Response, S3Result, s3_error};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio_stream::wrappers::ReceiverStream;
+
+/// Chunks buffered between a blob reader and the response body.
+const BLOB_CHUNKS_IN_FLIGHT: usize = 4;
+
+/// [`S3`] backend that stores each hosted subject in its own directory.
+#[derive(Debug, Clone)]
+pub(crate) struct HubStore {
+    root: FileSystemHandle,
+    hosted: Arc<HashSet<Did>>,
+}
+
+impl HubStore {
+    pub(crate) fn new(root: FileSystemHandle, hosted: Arc<HashSet<Did>>) -> Self {
+        Self { root, hosted }
+    }
+
+    /// Split `{subject}/{path}` and open the subject's space.
+    fn open<'a>(&self, key: &'a str) -> S3Result<(Subject, FileSystem, &'a str)> {
+        let (subject, path) = key
+            .split_once('/')
+            .ok_or_else(|| s3_error!(InvalidArgument, "key names no subject"))?;
+        let did: Did = subject
+            .parse()
+            .map_err(|_| s3_error!(InvalidArgument, "key does not start with a DID"))?;
+        if !self.hosted.contains(&did) {
+            return Err(s3_error!(AccessDenied, "subject is not hosted here"));
+        }
+        let space = self.root.resolve(did.as_ref()).map_err(internal)?;
+        Ok((Subject::from(did), FileSystem::from(space), path))
+    }
+}
+
+fn family(bucket: &str) -> S3Result<Family> {
+    Family::from_bucket(bucket).ok_or_else(|| s3_error!(NoSuchBucket))
+}
+
+fn digest(encoded: &str) -> S3Result<Blake3Hash> {
+    let bytes: [u8; 32] = encoded
+        .from_base58()
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| s3_error!(InvalidArgument, "key does not end in a digest"))?;
+    Ok(Blake3Hash::from(bytes))
+}
+
+/// The digest of a blob path, `blob/{digest}`.
+fn blob_digest(path: &str) -> S3Result<Blake3Hash> {
+    let encoded = path
+        .strip_prefix("blob/")
+        .ok_or_else(|| s3_error!(InvalidArgument, "key is not a blob path"))?;
+    digest(encoded)
+}
+
+/// Split a path at its last segment: `{catalog}/{digest}` or
+/// `{space}/{cell}`.
+///
+/// Spaces and cells may both contain `/`. Any split of a memory path names
+/// the same file, since the provider lays a cell out at
+/// `memory/{space}/{cell}`, so splitting at the last segment is as good as
+/// the split the client used.
+fn last_segment(path: &str) -> S3Result<(&str, &str)> {
+    path.rsplit_once('/')
+        .ok_or_else(|| s3_error!(InvalidArgument, "key is missing a segment"))
+}
+
+fn etag(version: &Version) -> ETag {
+    ETag::Strong(version.as_bytes().to_base58())
+}
+
+fn version(condition: &ETagCondition) -> S3Result<Version> {
+    let etag = condition
+        .as_etag()
+        .ok_or_else(|| s3_error!(InvalidArgument, "expected a version, not a wildcard"))?;
+    etag.value()
+        .from_base58()
+        .map(Version::from)
+        .map_err(|_| s3_error!(PreconditionFailed))
+}
+
+fn internal(error: impl std::fmt::Display) -> S3Error {
+    s3_error!(InternalError, "{error}")
+}
+
+impl From<HubError> for S3Error {
+    fn from(error: HubError) -> Self {
+        match error {
+            HubError::Archive(error) => internal(error),
+            HubError::Blob(BlobError::NotFound(_)) => s3_error!(NoSuchKey),
+            HubError::Blob(BlobError::DigestMismatch { .. }) => s3_error!(BadDigest),
+            HubError::Blob(error) => internal(error),
+            HubError::Memory(MemoryError::VersionMismatch { .. }) => {
+                s3_error!(PreconditionFailed)
+            }
+            HubError::Memory(error) => internal(error),
+        }
+    }
+}
+
+/// A provider failure, classified by the effect family that raised it.
+#[derive(Debug, thiserror::Error)]
+enum HubError {
+    #[error(transparent)]
+    Archive(#[from] ArchiveError),
+    #[error(transparent)]
+    Blob(#[from] BlobError),
+    #[error(transparent)]
+    Memory(#[from] MemoryError),
+}
+
+/// Translate an inclusive HTTP byte range into the blob effect's
+/// offset and length.
+fn blob_range(digest: Blake3Hash, range: Option<Range>) -> S3Result<Read> {
+    match range {
+        None => Ok(Read::new(digest)),
+        Some(Range::Int { first, last }) => Ok(Read::range(
+            digest,
+            first,
+            last.map(|last| last.saturating_sub(first) + 1),
+        )),
+        Some(Range::Suffix { .. }) => {
+            Err(s3_error!(InvalidRange, "suffix ranges are not supported"))
+        }
+    }
+}
+
+/// Stream a blob reader as a response body.
+///
+/// The reader is not `Sync`, which a response body must be, so a task pumps
+/// it into a channel the body drains.
+fn blob_body(mut reader: BlobReader) -> StreamingBlob {
+    let (sender, receiver) = tokio::sync::mpsc::channel(BLOB_CHUNKS_IN_FLIGHT);
+    tokio::spawn(async move {
+        loop {
+            let chunk = match reader.next().await {
+                Ok(Some(chunk)) => Ok(Bytes::from(chunk)),
+                Ok(None) => break,
+                Err(error) => Err(error),
+            };
+            let failed = chunk.is_err();
+            if sender.send(chunk).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+    StreamingBlob::wrap(ReceiverStream::new(receiver))
+}
+
+async fn collect(body: Option<StreamingBlob>) -> S3Result<Vec<u8>> {
+    let mut content = Vec::new();
+    if let Some(mut body) = body {
+        while let Some(chunk) = body.next().await {
+            content.extend_from_slice(&chunk.map_err(internal)?);
+        }
+    }
+    Ok(content)
+}
+
+#[async_trait::async_trait]
+impl S3 for HubStore {
+    async fn get_object(
+        &self,
+        req: S3Request<GetObjectInput>,
+    ) -> S3Result<S3Response<GetObjectOutput>> {
+        let input = req.input;
+        let (subject, space, path) = self.open(&input.key)?;
+
+        let output = match family(&input.bucket)? {
+            Family::Archive => {
+                let (catalog, encoded) = last_segment(path)?;
+                let content = subject
+                    .archive()
+                    .catalog(catalog)
+                    .get(digest(encoded)?)
+                    .perform(&space)
+                    .await
+                    .map_err(HubError::from)?
+                    .ok_or_else(|| s3_error!(NoSuchKey))?;
+                GetObjectOutput {
+                    content_length: Some(content.len() as i64),
+                    body: Some(StreamingBlob::from(s3s::Body::from(content))),
+                    ..Default::default()
+                }
+            }
+            Family::Blob => {
+                let read = blob_range(blob_digest(path)?, input.range)?;
+                let reader = subject
+                    .archive()
+                    .blob()
+                    .invoke(read)
+                    .perform(&space)
+                    .await
+                    .map_err(HubError::from)?;
+                GetObjectOutput {
+                    body: Some(blob_body(reader)),
+                    ..Default::default()
+                }
+            }
+            Family::Memory => {
+                let (name, cell) = last_segment(path)?;
+                let edition = subject
+                    .memory()
+                    .space(name)
+                    .cell(cell)
+                    .resolve()
+                    .perform(&space)
+                    .await
+                    .map_err(HubError::from)?
+                    .ok_or_else(|| s3_error!(NoSuchKey))?;
+                GetObjectOutput {
+                    content_length: Some(edition.content.len() as i64),
+                    e_tag: Some(etag(&edition.version)),
+                    body: Some(StreamingBlob::from(s3s::Body::from(edition.content))),
+                    ..Default::default()
+                }
+            }
+        };
+        Ok(S3Response::new(output))
+    }
+
+    async fn put_object(
+        &self,
+        req: S3Request<PutObjectInput>,
+    ) -> S3Result<S3Response<PutObjectOutput>> {
+        let input = req.input;
+        let (subject, space, path) = self.open(&input.key)?;
+
+        let output = match family(&input.bucket)? {
+            Family::Archive => {
+                let (catalog, encoded) = last_segment(path)?;
+                let expected = digest(encoded)?;
+                let content = collect(input.body).await?;
+                if Blake3Hash::hash(&content) != expected {
+                    return Err(s3_error!(BadDigest));
+                }
+                subject
+                    .archive()
+                    .catalog(catalog)
+                    .put(Buffer::from(content))
+                    .perform(&space)
+                    .await
+                    .map_err(HubError::from)?;
+                PutObjectOutput::default()
+            }
+            Family::Blob => {
+                let size = input
+                    .content_length
+                    .ok_or_else(|| s3_error!(MissingContentLength))?;
+                let mut sink = subject
+                    .archive()
+                    .blob()
+                    .import(blob_digest(path)?, size as u64)
+                    .perform(&space)
+                    .await
+                    .map_err(HubError::from)?;
+                if let Some(mut body) = input.body {
+                    while let Some(chunk) = body.next().await {
+                        sink.write_all(&chunk.map_err(internal)?)
+                            .await
+                            .map_err(HubError::from)?;
+                    }
+                }
+                sink.finish().await.map_err(HubError::from)?;
+                PutObjectOutput::default()
+            }
+            Family::Memory => {
+                let (name, cell) = last_segment(path)?;
+                // A publish carries `If-Match` to replace a version, or
+                // `If-None-Match: *` to create the cell.
+                let when = input.if_match.as_ref().map(version).transpose()?;
+                let content = collect(input.body).await?;
+                let version = subject
+                    .memory()
+                    .space(name)
+                    .cell(cell)
+                    .publish(content, when)
+                    .perform(&space)
+                    .await
+                    .map_err(HubError::from)?;
+                PutObjectOutput {
+                    e_tag: Some(etag(&version)),
+                    ..Default::default()
+                }
+            }
+        };
+        Ok(S3Response::new(output))
+    }
+
+    async fn delete_object(
+        &self,
+        req: S3Request<DeleteObjectInput>,
+    ) -> S3Result<S3Response<DeleteObjectOutput>> {
+        let input = req.input;
+        let (subject, space, path) = self.open(&input.key)?;
+
+        // Only a memory retract deletes; archives and blobs are append-only
+        // over the wire.
+        if family(&input.bucket)? != Family::Memory {
+            return Err(s3_error!(MethodNotAllowed));
+        }
+        let (name, cell) = last_segment(path)?;
+        let when = input
+            .if_match
+            .as_ref()
+            .ok_or_else(|| s3_error!(InvalidArgument, "retract requires If-Match"))
+            .and_then(version)?;
+        subject
+            .memory()
+            .space(name)
+            .cell(cell)
+            .retract(when)
+            .perform(&space)
+            .await
+            .map_err(HubError::from)?;
+        Ok(S3Response::new(DeleteObjectOutput::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dialog_effects::blob::ByteRange;
+
+    #[test]
+    fn it_splits_memory_paths_at_the_last_segment() {
+        assert_eq!(
+            last_segment("branch/main/revision").unwrap(),
+            ("branch/main", "revision")
+        );
+        assert!(last_segment("revision").is_err());
+    }
+
+    #[test]
+    fn it_round_trips_versions_through_etags() {
+        let version = Version::from(Blake3Hash::hash(b"edition"));
+        let ETag::Strong(tag) = etag(&version) else {
+            panic!("expected a strong etag");
+        };
+        let condition = ETagCondition::ETag(ETag::Strong(tag));
+        assert_eq!(super::version(&condition).unwrap(), version);
+    }
+
+    #[test]
+    fn it_translates_inclusive_ranges() {
+        let digest = Blake3Hash::hash(b"blob");
+        let read = blob_range(
+            digest,
+            Some(Range::Int {
+                first: 10,
+                last: Some(18),
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            read.range,
+            Some(ByteRange {
+                offset: 10,
+                length: Some(9)
+            })
+        );
+    }
+}
@@ -0,0 +1,188 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+//! End-to-end Operator -> UCAN site -> hub tests.
+//!
+//! These mirror `dialog-repository`'s UCAN push/pull tests with a hub as the
+//! remote instead of a test access service in front of an in-memory S3: the
+//! invocation is verified by the hub's access endpoint and the presigned
+//! request lands in the hub's directory for the repository.
+
+use anyhow::Result;
+use dialog_artifacts::{Artifact, ArtifactSelector, Instruction, Value};
+use dialog_credentials::{Signer, SignerCredential};
+use dialog_hub::{Hub, HubConfig};
+use dialog_operator::helpers::{test_operator_with_profile, unique_name};
+use dialog_operator::{Operator, Profile};
+use dialog_remote_ucan_s3::UcanAddress;
+use dialog_repository::{Branch, Repository, RepositoryExt as _, SiteAddress};
+use dialog_storage::provider::storage::VolatileSpace;
+use dialog_ucan_core::Revocation;
+use futures_util::{StreamExt as _, stream};
+
+/// Create a repository and delegate it to the profile, as a peer would
+/// before syncing it anywhere.
+async fn create_repository(
+    operator: &Operator<VolatileSpace>,
+    profile: &Profile,
+    name: &str,
+) -> Result<Repository<SignerCredential>> {
+    let repo = profile
+        .repository(unique_name(name))
+        .create()
+        .perform(operator)
+        .await?;
+    let chain = repo
+        .access()
+        .claim(&repo)
+        .delegate(profile.did())
+        .perform(operator)
+        .await?;
+    profile.access().save(chain).perform(operator).await?;
+    Ok(repo)
+}
+
+/// Open `main` tracking `main` on a UCAN remote at `hub`.
+async fn track(
+    operator: &Operator<VolatileSpace>,
+    repo: &Repository<SignerCredential>,
+    hub: &Hub,
+) -> Result<Branch> {
+    let origin = repo
+        .remote("origin")
+        .create(SiteAddress::Ucan(UcanAddress::new(hub.access_url())))
+        .perform(operator)
+        .await?;
+    let branch = repo.branch("main").open().perform(operator).await?;
+    let upstream = origin.branch("main").open().perform(operator).await?;
+    branch.set_upstream(upstream).perform(operator).await?;
+    Ok(branch)
+}
+
+async fn commit_name(operator: &Operator<VolatileSpace>, branch: &Branch) -> Result<()> {
+    branch
+        .commit(stream::iter(vec![Instruction::Assert(Artifact {
+            the: "user/name".parse()?,
+            of: "user:hub-test".parse()?,
+            is: Value::String("Hub User".into()),
+            cause: None,
+        })]))
+        .perform(operator)
+        .await?;
+    Ok(())
+}
+
+#[dialog_common::test]
+async fn it_pushes_and_pulls_through_a_hub() -> Result<()> {
+    let (operator, profile) = test_operator_with_profile().await;
+    let repo = create_repository(&operator, &profile, "hub-repo").await?;
+
+    let root = tempfile::tempdir()?;
+    let hub = Hub::start(HubConfig::new(root.path()).host(repo.did())).await?;
+    let branch = track(&operator, &repo, &hub).await?;
+
+    commit_name(&operator, &branch).await?;
+    let pushed = branch.push().perform(&operator).await?;
+    assert!(pushed.is_some(), "push through the hub should succeed");
+
+    let pulled = branch.pull().perform(&operator).await?;
+    assert!(pulled.is_none(), "pull after push should find no changes");
+
+    let results: Vec<_> = branch
+        .claims()
+        .select(ArtifactSelector::new().the("user/name".parse()?))
+        .to_owned()
+        .perform(&operator)
+        .await?
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(results.len(), 1, "should have the pushed artifact");
+    assert_eq!(results[0].is, Value::String("Hub User".into()));
+
+    // The hub keeps the repository in a directory of its own.
+    let space = std::fs::read_dir(root.path())?
+        .map(|entry| entry.map(|entry| entry.file_name()))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        space,
+        vec![std::ffi::OsString::from(repo.did().to_string())]
+    );
+
+    hub.stop();
+    Ok(())
+}
+
+#[dialog_common::test]
+async fn it_refuses_subjects_it_does_not_host() -> Result<()> {
+    let (operator, profile) = test_operator_with_profile().await;
+    let repo = create_repository(&operator, &profile, "hub-stranger").await?;
+
+    let root = tempfile::tempdir()?;
+    let hub = Hub::start(HubConfig::new(root.path())).await?;
+    let branch = track(&operator, &repo, &hub).await?;
+
+    commit_name(&operator, &branch).await?;
+    assert!(
+        branch.push().perform(&operator).await.is_err(),
+        "a hub must not store a subject it was not asked to host"
+    );
+    assert_eq!(std::fs::read_dir(root.path())?.count(), 0);
+
+    hub.stop();
+    Ok(())
+}
+
+#[dialog_common::test]
+async fn it_refuses_a_delegation_revoked_on_the_access_branch() -> Result<()> {
+    let (operator, profile) = test_operator_with_profile().await;
+    let repo = profile
+        .repository(unique_name("hub-revoked"))
+        .create()
+        .perform(&operator)
+        .await?;
+    let chain = repo
+        .access()
+        .claim(&repo)
+        .delegate(profile.did())
+        .perform(&operator)
+        .await?;
+    profile
+        .access()
+        .save(chain.clone())
+        .perform(&operator)
+        .await?;
+
+    let root = tempfile::tempdir()?;
+    let hub = Hub::start(HubConfig::new(root.path()).host(repo.did())).await?;
+    let branch = track(&operator, &repo, &hub).await?;
+
+    // The repository withdraws the grant it made its profile. The push
+    // carrying the revocation is verified against the branch the hub held
+    // before it, so it is the last the grant authorizes.
+    let revocation = Revocation::issue(
+        Signer::from(repo.credential().clone()),
+        chain.chain().proof_cids()[0],
+    )
+    .await?;
+    branch
+        .delegations()
+        .revoke(revocation)
+        .perform(&operator)
+        .await?;
+    assert!(branch.push().perform(&operator).await?.is_some());
+
+    commit_name(&operator, &branch).await?;
+    let error = branch
+        .push()
+        .perform(&operator)
+        .await
+        .expect_err("a hub must refuse a delegation revoked on the subject's access branch");
+    assert!(
+        format!("{error:?}").contains("Revoked"),
+        "refused for another reason: {error:?}"
+    );
+
+    hub.stop();
+    Ok(())
+}
@@ -314,6 +314,36 @@ mod tests {
         assert!(matches!(result, Err(CredentialError::NotFound(_))));
     }
 
+    #[dialog_common::test]
+    async fn it_rotates_and_recovers_its_key() {
+        let storage = Storage::volatile();
+        let profile = Profile::open("erin").perform(&storage).await.unwrap();
+        let backup = Profile::open("erin-backup")
+            .perform(&storage)
+            .await
+            .unwrap();
+        let successor = Profile::open("erin-next").perform(&storage).await.unwrap();
+
+        let rotation = profile.access().rotate(&successor.did()).await.unwrap();
+        assert_eq!(rotation.predecessor(), &profile.did());
+        assert_eq!(rotation.successor(), &successor.did());
+        assert!(!rotation.is_recovery());
+
+        let registration = profile
+            .access()
+            .register_recovery(&backup.did())
+            .await
+            .unwrap();
+        let recovery = backup
+            .access()
+            .recover(registration, &successor.did())
+            .await
+            .unwrap();
+        assert_eq!(recovery.predecessor(), &profile.did());
+        assert_eq!(recovery.issuer(), &backup.did());
+        assert!(recovery.is_recovery());
+    }
+
     #[dialog_common::test]
     async fn it_fails_to_create_duplicate() {
         let storage = Storage::volatile();
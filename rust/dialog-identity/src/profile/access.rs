@@ -2,6 +2,8 @@
 //!
 //! Provides a fluent builder chain for claiming authority and delegating.
 
+use std::collections::BTreeMap;
+
 use super::SaveDelegation;
 use dialog_capability::access::{self, Authorization as _, AuthorizeError, Proof as _};
 use dialog_capability::{Ability, Capability, Constraint, Provider, Subject};
@@ -10,7 +12,9 @@ use dialog_credentials::SignerCredential;
 use dialog_ucan::Scope;
 use dialog_ucan::{Ucan, UcanDelegation, UcanProof};
 use dialog_ucan_core::time::Timestamp;
-use dialog_varsig::{Did, Principal};
+use dialog_ucan_core::{Delegation, Succession, SuccessionError};
+use dialog_varsig::{AnySignature, Did, Principal};
+use ipld_core::ipld::Ipld;
 
 /// Access handle scoped to a profile's credential.
 ///
@@ -63,6 +67,38 @@ impl<'a> Access<'a> {
             chain,
         }
     }
+
+    /// Retire this profile's key in favour of `successor`, signed by the
+    /// key being retired.
+    ///
+    /// Record the result on the space's branch for verifiers to honour it.
+    pub async fn rotate(
+        &self,
+        successor: &impl Principal,
+    ) -> Result<Succession<AnySignature>, SuccessionError> {
+        Succession::rotate(signer_of(self.credential), successor).await
+    }
+
+    /// Register `backup` as able to [`recover`](Self::recover) this
+    /// profile's authority should its key be lost or compromised.
+    ///
+    /// Keep the returned registration with the backup key.
+    pub async fn register_recovery(
+        &self,
+        backup: &impl Principal,
+    ) -> Result<Delegation<AnySignature>, SuccessionError> {
+        Succession::register_recovery(signer_of(self.credential), backup).await
+    }
+
+    /// Hand the authority of `registration`'s issuer to `successor`, signed
+    /// by this profile's key as the backup the registration names.
+    pub async fn recover(
+        &self,
+        registration: Delegation<AnySignature>,
+        successor: &impl Principal,
+    ) -> Result<Succession<AnySignature>, SuccessionError> {
+        Succession::recover(signer_of(self.credential), registration, successor).await
+    }
 }
 
 /// A claimed capability with optional time bounds.
@@ -95,6 +131,7 @@ impl<'a, C: Constraint> Claim<'a, C> {
         Delegate {
             claim: self,
             audience: audience.into(),
+            meta: BTreeMap::new(),
         }
     }
 
@@ -174,6 +211,16 @@ fn signer_of(credential: &SignerCredential) -> dialog_credentials::Signer {
 pub struct Delegate<'a, C: Constraint> {
     claim: Claim<'a, C>,
     audience: Did,
+    meta: BTreeMap<String, Ipld>,
+}
+
+impl<C: Constraint> Delegate<'_, C> {
+    /// Carry `meta` on the delegation, such as the subject's encryption
+    /// key sealed to the audience.
+    pub fn meta(mut self, meta: BTreeMap<String, Ipld>) -> Self {
+        self.meta.extend(meta);
+        self
+    }
 }
 
 impl<C: Constraint> Delegate<'_, C>
@@ -195,7 +242,7 @@ where
         if let Some(exp) = duration.expiration {
             authorization = authorization.expires(exp)?;
         }
-        authorization.delegate(self.audience).await
+        authorization.delegate_with(self.audience, self.meta).await
     }
 }
 
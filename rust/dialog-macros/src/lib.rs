@@ -369,6 +369,47 @@ pub fn derive_attribute(input: TokenStream) -> TokenStream {
     query::attribute::derive(input)
 }
 
+/// Derive macro that makes a `RecordFormat` type a typed term value stored
+/// as a `Value::Record`.
+///
+/// The type must implement `RecordFormat` (which decides its byte encoding)
+/// along with `Clone` and `Debug`. The derive implements `Typed` (with the
+/// `Record` descriptor), `Scalar`, `From<T> for Value`,
+/// `From<T> for Term<T>` and `TryFrom<Value> for T`, so the type can be wrapped by an
+/// `#[derive(Attribute)]` newtype and used as a concept field. Reading a
+/// value back realizes the record, decoding its bytes once per record.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[derive(Clone, Debug, PartialEq, Record)]
+/// pub struct GeoPoint {
+///     pub lat: f64,
+///     pub lon: f64,
+/// }
+///
+/// impl RecordFormat for GeoPoint {
+///     fn decode(bytes: &[u8]) -> Result<Self, RecordError> {
+///         let fields = RecordFields::decode(bytes)?;
+///         /* read "lat" and "lon" */
+///     }
+///
+///     fn encode(&self) -> Result<Vec<u8>, RecordError> {
+///         RecordFields::new().with("lat", self.lat).with("lon", self.lon).encode()
+///     }
+/// }
+///
+/// mod place {
+///     /// Where the place is.
+///     #[derive(Attribute, Clone, PartialEq)]
+///     pub struct Location(pub super::GeoPoint);
+/// }
+/// ```
+#[proc_macro_derive(Record)]
+pub fn derive_record(input: TokenStream) -> TokenStream {
+    query::record::derive(input)
+}
+
 /// Derive macro that generates an `Attenuate` trait impl for effect types.
 ///
 /// For types with no `#[attenuate(into = ...)]` annotations,
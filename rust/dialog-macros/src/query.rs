@@ -1,7 +1,8 @@
-//! Query derive macros for dialog-query concepts, formulas, and attributes
+//! Query derive macros for dialog-query concepts, formulas, attributes, and records
 
 mod helpers;
 
 pub mod attribute;
 pub mod concept;
 pub mod formula;
+pub mod record;
@@ -406,6 +406,21 @@ pub fn derive(input: TokenStream) -> TokenStream {
                 application.evaluate(selection, env)
             }
 
+            // Ordering reads through the concept query, which knows
+            // when its plan already yields rows in the requested order.
+            fn evaluate_ordered<'__a, __Env>(
+                self,
+                input: dialog_query::Match,
+                order: dialog_query::Order,
+                env: &'__a __Env,
+            ) -> impl dialog_query::Selection + '__a
+            where
+                __Env: dialog_query::Scope<'__a>,
+            {
+                let application: dialog_query::ConceptQuery = self.into();
+                application.evaluate_ordered(input, order, env)
+            }
+
             fn realize(&self, source: dialog_query::Match) -> std::result::Result<Self::Conclusion, dialog_query::EvaluationError> {
                 Ok(#struct_name {
                     this: dialog_query::Entity::try_from(
@@ -473,6 +488,25 @@ pub fn derive(input: TokenStream) -> TokenStream {
             {
                 dialog_query::Application::perform(self, env)
             }
+
+            /// Return this query's results sorted on `by` in `direction`
+            pub fn order_by(
+                self,
+                by: impl Into<dialog_query::Term<dialog_query::types::Any>>,
+                direction: dialog_query::Direction,
+            ) -> dialog_query::Ordered<Self> {
+                dialog_query::Ordered::from(self).order_by(by, direction)
+            }
+
+            /// Return at most `limit` of this query's results
+            pub fn limit(self, limit: usize) -> dialog_query::Ordered<Self> {
+                dialog_query::Ordered::from(self).limit(limit)
+            }
+
+            /// Skip the first `offset` of this query's results
+            pub fn offset(self, offset: usize) -> dialog_query::Ordered<Self> {
+                dialog_query::Ordered::from(self).offset(offset)
+            }
         }
 
         // Implement From<Query> for Parameters
@@ -0,0 +1,90 @@
+//! Record derive macro implementation
+//!
+//! Makes a [`RecordFormat`] type usable as a typed term value, so that it
+//! can be the inner type of an `#[derive(Attribute)]` newtype (and hence a
+//! concept field) and be stored atomically as one `Value::Record`.
+//!
+//! # Example input
+//!
+//! ```rust,ignore
+//! #[derive(Clone, Debug, PartialEq, Record)]
+//! struct GeoPoint { lat: f64, lon: f64 }
+//!
+//! impl RecordFormat for GeoPoint { /* decode / encode */ }
+//! ```
+//!
+//! # Generated output (simplified)
+//!
+//! ```rust,ignore
+//! impl Typed for GeoPoint { type Descriptor = types::Record; }
+//! impl Scalar for GeoPoint {}
+//!
+//! // Encodes through `RecordFormat::encode`, keeping the form cached.
+//! impl From<GeoPoint> for Value { /* Value::Record(RecordValue::encode(..)) */ }
+//! impl From<GeoPoint> for Term<GeoPoint> { /* a constant */ }
+//!
+//! // Realizes the record as `GeoPoint` (decoding once per record).
+//! impl TryFrom<Value> for GeoPoint { type Error = ArtifactTypeError; /* ... */ }
+//! ```
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, parse_macro_input};
+
+pub fn derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let type_name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &input.generics,
+            "Record can only be derived for types without generic parameters",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let expanded = quote! {
+        impl dialog_query::Typed for #type_name {
+            type Descriptor = dialog_query::types::Record;
+        }
+
+        impl dialog_query::Scalar for #type_name {}
+
+        // `Into<Value>` is infallible, so a form that cannot encode is a
+        // bug in its `RecordFormat` impl rather than a recoverable error.
+        impl ::std::convert::From<#type_name> for dialog_query::artifact::Value {
+            fn from(form: #type_name) -> Self {
+                let record = dialog_query::artifact::RecordValue::encode(form).unwrap_or_else(
+                    |error| panic!("{} failed to encode as a record: {}", stringify!(#type_name), error),
+                );
+                dialog_query::artifact::Value::Record(record)
+            }
+        }
+
+        impl ::std::convert::From<#type_name> for dialog_query::Term<#type_name> {
+            fn from(form: #type_name) -> Self {
+                dialog_query::Term::Constant(form.into())
+            }
+        }
+
+        impl ::std::convert::TryFrom<dialog_query::artifact::Value> for #type_name {
+            type Error = dialog_query::artifact::ArtifactTypeError;
+
+            fn try_from(value: dialog_query::artifact::Value) -> Result<Self, Self::Error> {
+                match value {
+                    dialog_query::artifact::Value::Record(record) => {
+                        let form = record.realize::<#type_name>()?;
+                        Ok(::std::sync::Arc::unwrap_or_clone(form))
+                    }
+                    other => Err(dialog_query::artifact::ArtifactTypeError::TypeMismatch(
+                        dialog_query::artifact::Type::Record,
+                        other.data_type(),
+                    )),
+                }
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
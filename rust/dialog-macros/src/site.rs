@@ -21,7 +21,8 @@
 //! - `NetworkAuthorization` enum (from `<Site>::Authorization`)
 //! - `NetworkFork<Fx>` enum (from `<Site>::Fork<Fx>`)
 //! - `impl Site for Network`
-//! - `impl SiteAddress for NetworkAddress`
+//! - `impl SiteAddress for NetworkAddress`, asking each variant's address
+//!   whether it [is exposed](dialog_capability::SiteAddress::is_exposed)
 //! - `From<VariantAddr>` impls via a `FromSiteAddress` helper trait (a bare
 //!   `impl From<<S as Site>::Address> for Address` would hit cross-crate
 //!   coherence issues with associated-type projections)
@@ -244,6 +245,20 @@ fn generate_site(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
         })
         .collect();
 
+    let exposed_arms: Vec<_> = fields
+        .iter()
+        .map(|f| {
+            let cfgs = &f.cfg_attrs;
+            let vname = &f.variant_name;
+            quote! {
+                #(#cfgs)*
+                #address_enum_name::#vname(addr) => {
+                    ::dialog_capability::SiteAddress::is_exposed(addr)
+                }
+            }
+        })
+        .collect();
+
     // From<Fork<Self, Fx>> for the composite fork enum -- dispatch on address.
     let fork_from_arms: Vec<_> = fields
         .iter()
@@ -380,6 +395,12 @@ fn generate_site(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
 
         impl ::dialog_capability::SiteAddress for #address_enum_name {
             type Site = #struct_name;
+
+            fn is_exposed(&self) -> bool {
+                match self {
+                    #(#exposed_arms)*
+                }
+            }
         }
 
         /// Composite authorization material.
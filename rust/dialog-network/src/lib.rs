@@ -13,13 +13,15 @@
 //!
 //! This crate exposes [`Network`], the composite
 //! [`Site`](dialog_capability::Site) that dispatches fork invocations to
-//! the appropriate transport (S3, UCAN-over-S3, ...). The associated
+//! the appropriate transport (S3, UCAN-over-S3, a filesystem, another
+//! peer, ...). The associated
 //! [`NetworkAddress`], [`NetworkAuthorization`], and `NetworkFork` types
 //! are generated from the struct fields by `#[derive(Site)]` in
 //! `dialog-capability`.
 
 use dialog_capability::Site;
 use dialog_remote_fs::Fs;
+use dialog_remote_peer::Peer;
 use dialog_remote_s3::S3;
 use dialog_remote_ucan_s3::UcanSite;
 
@@ -38,6 +40,7 @@ pub struct Network {
     s3: S3,
     ucan: UcanSite,
     fs: Fs,
+    peer: Peer,
 }
 
 #[cfg(test)]
@@ -49,6 +52,7 @@ mod tests {
     use dialog_capability::{Site, SiteAddress};
     use dialog_effects::storage::Location;
     use dialog_remote_fs::FsAddress;
+    use dialog_remote_peer::PeerAddress;
     use dialog_remote_s3::Address as S3Address;
     use dialog_remote_ucan_s3::UcanAddress;
 
@@ -68,6 +72,10 @@ mod tests {
         FsAddress::new(Location::temp("test-vault"))
     }
 
+    fn peer_address() -> PeerAddress {
+        PeerAddress::tcp("127.0.0.1:7879")
+    }
+
     /// `NetworkAddress` is a public enum with one variant per field. Variant
     /// names are field names converted to PascalCase.
     #[test]
@@ -75,6 +83,7 @@ mod tests {
         let _: NetworkAddress = NetworkAddress::S3(s3_address());
         let _: NetworkAddress = NetworkAddress::Ucan(ucan_address());
         let _: NetworkAddress = NetworkAddress::Fs(fs_address());
+        let _: NetworkAddress = NetworkAddress::Peer(peer_address());
     }
 
     /// `From<VariantAddress> for NetworkAddress` is generated for each
@@ -89,6 +98,9 @@ mod tests {
 
         let net: NetworkAddress = fs_address().into();
         assert!(matches!(net, NetworkAddress::Fs(_)));
+
+        let net: NetworkAddress = peer_address().into();
+        assert!(matches!(net, NetworkAddress::Peer(_)));
     }
 
     /// `Network` implements `Site` (with the generated enums as associated
@@ -4,18 +4,20 @@
 
 mod access;
 mod builder;
+mod encryption;
 mod fork;
 mod space;
 #[cfg(test)]
 mod test;
 
 pub use builder::{DeriveOperator, OperatorBuilder, OperatorError};
+pub use encryption::ENCRYPTION_KEY_META;
 
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::{Arc, OnceLock};
 
-use dialog_repository::Branch;
+use dialog_repository::{Branch, RetainedReceipts};
 use dialog_ucan::UcanCertificate;
 use parking_lot::Mutex;
 
@@ -28,6 +30,7 @@ use dialog_effects::storage as storage_fx;
 use dialog_effects::{archive, blob, credential, memory};
 use dialog_identity::Authority;
 use dialog_network::Network;
+use dialog_storage::provider::Encrypted;
 use dialog_storage::provider::storage::Storage;
 use dialog_varsig::{Did, Principal};
 
@@ -75,6 +78,8 @@ pub(crate) struct WalkReach {
 /// Composes:
 /// - Authority credentials (identity)
 /// - [`Storage`] for DID-routed effects
+/// - An [`Encrypted`] layer over the same storage for archive and blob
+///   content
 /// - Base directory for resolving space names to storage locations
 /// - Remote for fork invocations
 #[derive(Provider, Clone)]
@@ -87,9 +92,19 @@ pub struct Operator<S: Clone> {
         archive::Get,
         archive::Put,
         archive::Import,
+        archive::List,
+        archive::Remove,
         blob::Read,
         blob::Write,
         blob::Import,
+        blob::List,
+        blob::Remove
+    )]
+    /// The same storage behind the encrypting layer — seals the archive and
+    /// blob content of every space unlocked in its keyring.
+    vault: Encrypted<Storage<S>>,
+
+    #[provide(
         credential::Load<Credential>,
         credential::Save<Credential>,
         credential::Load<Secret>,
@@ -97,7 +112,8 @@ pub struct Operator<S: Clone> {
         credential::Retract<Secret>,
         memory::Resolve,
         memory::Publish,
-        memory::Retract
+        memory::Retract,
+        memory::List
     )]
     /// Storage — routes DID-based effects.
     storage: Storage<S>,
@@ -141,6 +157,13 @@ impl<S: Clone> Operator<S> {
             })
     }
 
+    /// The access branch as a receipt store: hand it to an executor
+    /// running beside this operator (a peer responder, an authorizer) and
+    /// the receipts it signs are kept with the delegations they ran under.
+    pub fn receipts(&self) -> Result<RetainedReceipts<Self>, AuthorizeError> {
+        Ok(self.delegations()?.delegations().receipts(self.clone()))
+    }
+
     /// The operator's DID (the ephemeral/derived session key).
     pub fn did(&self) -> Did {
         self.authority.operator_did()
@@ -406,6 +406,14 @@ where
         const RETRY_LIMIT: usize = 3;
 
         let delegation = Retain::<Ucan>::of(&input).delegation.clone();
+        // A delegation sharing its subject's encryption key with this
+        // profile unlocks the space here; one whose key does not open is
+        // refused before anything is retained.
+        self.accept_shared_key(&delegation)
+            .await
+            .map_err(|error| AuthorizeError::Malformed {
+                detail: format!("failed to accept the shared encryption key: {error}"),
+            })?;
         let env = AccessEnv {
             operator: self.clone(),
         };
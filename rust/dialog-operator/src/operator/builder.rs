@@ -15,6 +15,7 @@ use dialog_network::Network;
 use dialog_repository::{ACCESS_BRANCH, RemoteSite};
 use dialog_storage::provider::space::SpaceProvider;
 use dialog_storage::provider::storage::Storage;
+use dialog_storage::provider::{Encrypted, Keyring};
 use dialog_ucan::{Scope, UcanCertificate};
 use dialog_ucan_core::DelegationBuilder;
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
@@ -126,6 +127,7 @@ impl OperatorBuilder {
 
         let operator = Operator {
             authority: credentials,
+            vault: Encrypted::new(storage.clone(), Keyring::default()),
             storage,
             directory: self.directory,
             network: self.network,
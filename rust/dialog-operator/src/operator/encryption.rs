@@ -0,0 +1,251 @@
+//! Encryption keys for Operator.
+//!
+//! Every space's archive and blob content is sealed under a key of its own
+//! (see [`EncryptionKey`]), kept wrapped in the space's memory for each
+//! principal allowed to unlock it. A device holding the space's credential
+//! [`unlock`](Operator::unlock)s the space itself, generating the key the
+//! first time, and [`hand_on_key`](Operator::hand_on_key) wraps it for the
+//! successor of a rotation. Anyone else receives the key sealed to their
+//! profile in the `meta` of the delegation that grants them access
+//! ([`share_key`](Operator::share_key) builds it for [`Delegate::meta`]),
+//! and the operator [`accept_key`](Operator::accept_key)s it when the
+//! delegation is saved.
+//!
+//! [`Delegate::meta`]: dialog_identity::access::Delegate::meta
+
+use std::collections::BTreeMap;
+
+use super::Operator;
+use dialog_capability::Provider;
+use dialog_common::ConditionalSync;
+use dialog_credentials::Signer;
+use dialog_effects::memory;
+use dialog_storage::provider::Keyring;
+use dialog_storage::provider::storage::Storage;
+use dialog_storage::{EncryptionError, EncryptionKey};
+use dialog_ucan::UcanDelegation;
+use dialog_ucan_core::subject::Subject as UcanSubject;
+use dialog_varsig::{Did, Principal};
+use ipld_core::ipld::Ipld;
+
+/// The delegation `meta` entry carrying the subject's encryption key,
+/// sealed to the delegation's audience.
+pub const ENCRYPTION_KEY_META: &str = "dialog/encryption-key";
+
+impl<S: Clone> Operator<S> {
+    /// The keys this operator seals space content with.
+    pub fn keyring(&self) -> &Keyring {
+        self.vault.keyring()
+    }
+
+    /// Unlock the space `signer` is the credential of, and seal its content
+    /// from now on. Content written in the clear before reads back only
+    /// while the space is [migrating](Keyring::migrate).
+    pub async fn unlock(&self, signer: &Signer) -> Result<EncryptionKey, EncryptionError>
+    where
+        Storage<S>: Provider<memory::Resolve> + Provider<memory::Publish> + ConditionalSync,
+    {
+        self.unlock_as(&signer.did(), signer).await
+    }
+
+    /// Unlock `subject`'s space with `signer`, a credential its key has
+    /// been [handed on](Self::hand_on_key) to.
+    pub async fn unlock_as(
+        &self,
+        subject: &Did,
+        signer: &Signer,
+    ) -> Result<EncryptionKey, EncryptionError>
+    where
+        Storage<S>: Provider<memory::Resolve> + Provider<memory::Publish> + ConditionalSync,
+    {
+        self.keyring().unlock(subject, signer, &self.storage).await
+    }
+
+    /// Wrap the unlocked key of `subject`'s space for `successor`, so the
+    /// space still unlocks with the successor's credential once a rotation
+    /// retires the current one.
+    pub async fn hand_on_key(&self, subject: &Did, successor: &Did) -> Result<(), EncryptionError>
+    where
+        Storage<S>: Provider<memory::Resolve> + Provider<memory::Publish> + ConditionalSync,
+    {
+        self.keyring()
+            .hand_on(subject, successor, &self.storage)
+            .await
+    }
+
+    /// The delegation `meta` that shares `subject`'s encryption key with
+    /// `audience`, for [`DelegationBuilder::meta`].
+    ///
+    /// [`DelegationBuilder::meta`]: dialog_ucan_core::DelegationBuilder::meta
+    pub fn share_key(
+        &self,
+        subject: &Did,
+        audience: &Did,
+    ) -> Result<BTreeMap<String, Ipld>, EncryptionError> {
+        let key = self
+            .keyring()
+            .get(subject)
+            .ok_or_else(|| EncryptionError::Locked(subject.to_string()))?;
+        Ok(BTreeMap::from([(
+            ENCRYPTION_KEY_META.to_string(),
+            Ipld::Bytes(key.seal_for(audience)?),
+        )]))
+    }
+
+    /// Accept `subject`'s encryption key from the `meta` of a delegation
+    /// addressed to this operator's profile. Returns `false` when the
+    /// delegation shares no key.
+    pub async fn accept_key(
+        &self,
+        subject: Did,
+        meta: &BTreeMap<String, Ipld>,
+    ) -> Result<bool, EncryptionError> {
+        let Some(Ipld::Bytes(sealed)) = meta.get(ENCRYPTION_KEY_META) else {
+            return Ok(false);
+        };
+        let recipient = self
+            .authority
+            .profile_signer()
+            .as_ed25519()
+            .ok_or_else(|| {
+                EncryptionError::UnsupportedKey(self.authority.profile_signer().did().to_string())
+            })?;
+        let key = EncryptionKey::unseal(sealed, recipient).await?;
+        self.keyring().insert(subject, key);
+        Ok(true)
+    }
+
+    /// Accept the key `delegation` shares, if its last link grants a
+    /// specific subject to this operator's profile and carries one.
+    pub(crate) async fn accept_shared_key(
+        &self,
+        delegation: &UcanDelegation,
+    ) -> Result<bool, EncryptionError> {
+        let Some(grant) = delegation.chain().proofs().last() else {
+            return Ok(false);
+        };
+        let UcanSubject::Specific(subject) = grant.subject() else {
+            return Ok(false);
+        };
+        if *grant.audience() != self.profile_did() {
+            return Ok(false);
+        }
+        self.accept_key(subject.clone(), grant.meta()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Operator;
+    use crate::helpers::{test_operator_with_profile, unique_name};
+    use dialog_credentials::Ed25519Signer;
+    use dialog_identity::Profile;
+    use dialog_repository::RepositoryExt as _;
+    use dialog_storage::provider::storage::VolatileSpace;
+
+    /// Create a repository `operator` can write, and return the signer of
+    /// its space.
+    async fn space(operator: &Operator<VolatileSpace>, profile: &Profile) -> Signer {
+        let repo = profile
+            .repository(unique_name("space"))
+            .create()
+            .perform(operator)
+            .await
+            .unwrap();
+        repo.access().signer().signer().clone()
+    }
+
+    #[dialog_common::test]
+    async fn it_shares_a_space_key_with_another_profile() {
+        let (alice, alice_profile) = test_operator_with_profile().await;
+        let (bob, _) = test_operator_with_profile().await;
+        let space = space(&alice, &alice_profile).await;
+
+        let key = alice.unlock(&space).await.unwrap();
+        let meta = alice.share_key(&space.did(), &bob.profile_did()).unwrap();
+
+        assert!(bob.accept_key(space.did(), &meta).await.unwrap());
+        assert_eq!(bob.keyring().get(&space.did()), Some(key));
+    }
+
+    #[dialog_common::test]
+    async fn it_refuses_to_share_a_locked_key() {
+        let (alice, _) = test_operator_with_profile().await;
+        let space = Signer::from(Ed25519Signer::generate().await.unwrap());
+
+        assert!(matches!(
+            alice.share_key(&space.did(), &alice.profile_did()),
+            Err(EncryptionError::Locked(_))
+        ));
+        assert!(
+            !alice
+                .accept_key(space.did(), &BTreeMap::new())
+                .await
+                .unwrap()
+        );
+    }
+
+    #[dialog_common::test]
+    async fn it_cannot_accept_a_key_sealed_to_someone_else() {
+        let (alice, alice_profile) = test_operator_with_profile().await;
+        let (bob, _) = test_operator_with_profile().await;
+        let (carol, _) = test_operator_with_profile().await;
+        let space = space(&alice, &alice_profile).await;
+
+        alice.unlock(&space).await.unwrap();
+        let meta = alice.share_key(&space.did(), &bob.profile_did()).unwrap();
+
+        assert!(carol.accept_key(space.did(), &meta).await.is_err());
+        assert_eq!(carol.keyring().get(&space.did()), None);
+    }
+
+    #[dialog_common::test]
+    async fn it_unlocks_a_space_shared_through_a_delegation() {
+        let (alice, alice_profile) = test_operator_with_profile().await;
+        let (bob, bob_profile) = test_operator_with_profile().await;
+        let repo = alice_profile
+            .repository(unique_name("shared"))
+            .create()
+            .perform(&alice)
+            .await
+            .unwrap();
+        let key = alice.unlock(repo.access().signer().signer()).await.unwrap();
+
+        let chain = repo
+            .access()
+            .claim(&repo)
+            .delegate(bob_profile.did())
+            .meta(alice.share_key(&repo.did(), &bob_profile.did()).unwrap())
+            .perform(&alice)
+            .await
+            .unwrap();
+        bob_profile
+            .access()
+            .save(chain)
+            .perform(&bob)
+            .await
+            .unwrap();
+
+        assert_eq!(bob.keyring().get(&repo.did()), Some(key));
+    }
+
+    #[dialog_common::test]
+    async fn it_hands_a_space_key_on_to_a_successor() {
+        let (alice, alice_profile) = test_operator_with_profile().await;
+        let space = space(&alice, &alice_profile).await;
+        let successor = Signer::from(Ed25519Signer::generate().await.unwrap());
+
+        let key = alice.unlock(&space).await.unwrap();
+        alice
+            .hand_on_key(&space.did(), &successor.did())
+            .await
+            .unwrap();
+        alice.keyring().remove(&space.did());
+
+        assert_eq!(
+            alice.unlock_as(&space.did(), &successor).await.unwrap(),
+            key
+        );
+    }
+}
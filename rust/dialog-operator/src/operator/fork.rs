@@ -3,12 +3,22 @@
 //! Calls [`Fork::authorize`] to produce a [`ForkInvocation`], then
 //! delegates execution to the network layer. The site's own fork
 //! wrapper fetches identity from the env via `authority::Identify`.
+//!
+//! Content is sealed for the subject's key *before* authorization: a
+//! site may bind the exact payload (a blob import's digest and size)
+//! into the authorization it mints, so it must see what it will store.
+//! An address that [is exposed](SiteAddress::is_exposed) is refused for
+//! a space that is not unlocked, whose content would cross it in the
+//! clear.
 
 use crate::Operator;
 use dialog_capability::access::AuthorizeError;
-use dialog_capability::{Effect, Fork, ForkInvocation, Provider, Site, SiteFork};
+use dialog_capability::{
+    Ability, Constraint, Fork, ForkInvocation, Provider, Site, SiteAddress, SiteFork, SiteId,
+};
 use dialog_common::{ConditionalSend, ConditionalSync};
 use dialog_network::Network;
+use dialog_storage::provider::Conceal;
 
 /// Helper trait for effect outputs that can absorb authorization errors.
 ///
@@ -35,7 +45,10 @@ where
     // Site's own fork wrapper carries the Authorize impl that fetches
     // identity from the env and produces a ForkInvocation.
     At::Fork<Fx>: SiteFork<Self, Site = At, Effect = Fx> + ConditionalSend,
-    Fx: Effect + 'static,
+    // Seals the payload for spaces unlocked in the operator's keyring
+    Fx: Conceal + ConditionalSend + 'static,
+    <Fx as Constraint>::Capability: Ability + ConditionalSend,
+    At::Address: SiteAddress + ConditionalSend,
     // Needed to flatten AuthorizeError into effect error via FromAuthError
     Fx::Output: FromAuthError,
     // Required by async_trait for Send futures
@@ -46,9 +59,33 @@ where
     Self: ConditionalSend + ConditionalSync,
 {
     async fn execute(&self, input: Fork<At, Fx>) -> Fx::Output {
-        match input.authorize(self).await {
-            Ok(invocation) => invocation.perform(&self.network).await,
-            Err(e) => FromAuthError::from_auth_error(e),
+        let (capability, address) = input.into_parts();
+        // Memory cells travel as they are either way, but archive and blob
+        // content is only sealed once the space is unlocked.
+        if address.is_exposed() && self.vault.keyring().get(capability.subject()).is_none() {
+            return FromAuthError::from_auth_error(AuthorizeError::Unavailable {
+                detail: format!(
+                    "{} is locked, and its content would cross {} in the clear; \
+                     unlock it first",
+                    capability.subject(),
+                    SiteId::from(&address)
+                ),
+            });
         }
+        self.vault
+            .keyring()
+            .conceal(capability, move |capability| {
+                let address = address.clone();
+                async move {
+                    match Fork::<At, Fx>::new(capability, address)
+                        .authorize(self)
+                        .await
+                    {
+                        Ok(invocation) => invocation.perform(&self.network).await,
+                        Err(e) => FromAuthError::from_auth_error(e),
+                    }
+                }
+            })
+            .await
     }
 }
@@ -1,9 +1,9 @@
 pub use dialog_artifacts::selector::Constrained;
 pub use dialog_artifacts::{
     Artifact, ArtifactSelector, ArtifactStore, ArtifactStoreMut, ArtifactStoreMutExt, Artifacts,
-    Attribute as ArtifactsAttribute, Cause, DialogArtifactsError, Entity, Instruction, NameShape,
-    Select, TypeError as ArtifactTypeError, Value, ValueDataType as Type, decode_value,
-    encode_value_owned,
+    Attribute as ArtifactsAttribute, Cause, DialogArtifactsError, Entity, Instant, Instruction,
+    NameShape, Record as RecordValue, RecordError, RecordFields, RecordFormat, RecordList, Select,
+    TypeError as ArtifactTypeError, Value, ValueDataType as Type, decode_value, encode_value_owned,
 };
 pub use futures_util::stream::Stream;
 
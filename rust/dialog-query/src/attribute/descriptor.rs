@@ -42,6 +42,10 @@ pub struct AttributeDescriptor {
     cardinality: Cardinality,
     #[serde(rename = "as", default, skip_serializing_if = "Option::is_none")]
     content_type: Option<Type>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    unique: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    searchable: bool,
 }
 
 impl AttributeDescriptor {
@@ -57,9 +61,27 @@ impl AttributeDescriptor {
             description: description.into(),
             cardinality,
             content_type,
+            unique: false,
+            searchable: false,
         }
     }
 
+    /// This descriptor, flagged as a unique identity: no two entities may
+    /// hold the same value for the attribute, so a value names at most
+    /// one entity — the natural key an upsert resolves by.
+    pub fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
+    /// This descriptor, flagged as searchable: the branch keeps a full-text
+    /// index over the attribute's string values, which the `text/matches`
+    /// premise queries.
+    pub fn searchable(mut self) -> Self {
+        self.searchable = true;
+        self
+    }
+
     /// Returns a relation identifier comprised of the attribute's domain and name.
     pub fn the(&self) -> &The {
         &self.the
@@ -85,6 +107,16 @@ impl AttributeDescriptor {
         self.cardinality
     }
 
+    /// Whether the attribute is a unique identity (see [`unique`](Self::unique)).
+    pub fn is_unique(&self) -> bool {
+        self.unique
+    }
+
+    /// Whether the attribute is full-text indexed (see [`searchable`](Self::searchable)).
+    pub fn is_searchable(&self) -> bool {
+        self.searchable
+    }
+
     /// Returns the expected value type, or `None` if any type is accepted.
     pub fn content_type(&self) -> Option<Type> {
         self.content_type
@@ -197,6 +229,25 @@ impl AttributeDescriptor {
         ))
     }
 
+    /// The canonical dag-cbor encoding of the whole descriptor, description
+    /// included — the body a registered descriptor is stored as (see
+    /// [`descriptor_attr`](crate::attribute::descriptor_attr)).
+    pub fn encode(&self) -> Vec<u8> {
+        serde_ipld_dagcbor::to_vec(self).expect("CBOR encoding should not fail")
+    }
+
+    /// Decode a descriptor from its [`encode`](Self::encode)d form.
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        serde_ipld_dagcbor::from_slice(bytes).map_err(|e| format!("dag-cbor decode failed: {e}"))
+    }
+
+    /// Whether `value` has this attribute's content type. An attribute
+    /// without one admits any value.
+    pub fn admits(&self, value: &Value) -> bool {
+        self.content_type()
+            .is_none_or(|expected| value.data_type() == expected)
+    }
+
     /// Encode this attribute descriptor as CBOR for hashing
     ///
     /// Creates a CBOR-encoded representation with fields:
@@ -205,7 +256,9 @@ impl AttributeDescriptor {
     /// - cardinality: cardinality
     /// - type: content_type
     ///
-    /// Description is excluded from the encoding.
+    /// Description and the unique-identity and searchable flags are
+    /// excluded from the encoding: they describe how the attribute is
+    /// used, not which relation it is.
     pub fn to_cbor_bytes(&self) -> Vec<u8> {
         use serde::Serialize;
 
@@ -298,6 +351,50 @@ mod tests {
         assert_eq!(json["as"], "Text");
     }
 
+    #[dialog_common::test]
+    fn it_serializes_the_unique_flag_only_when_set() {
+        let attr = AttributeDescriptor::new(
+            the!("user/email"),
+            "Email address",
+            Cardinality::One,
+            Some(Type::String),
+        );
+        let json: serde_json::Value = serde_json::to_value(&attr).unwrap();
+        assert!(json.get("unique").is_none());
+
+        let unique = attr.clone().unique();
+        let json: serde_json::Value = serde_json::to_value(&unique).unwrap();
+        assert_eq!(json["unique"], true);
+        assert!(
+            AttributeDescriptor::decode(&unique.encode())
+                .unwrap()
+                .is_unique()
+        );
+        assert_eq!(unique.to_uri(), attr.to_uri(), "the relation is unchanged");
+    }
+
+    #[dialog_common::test]
+    fn it_serializes_the_searchable_flag_only_when_set() {
+        let attr = AttributeDescriptor::new(
+            the!("note/body"),
+            "Note text",
+            Cardinality::One,
+            Some(Type::String),
+        );
+        let json: serde_json::Value = serde_json::to_value(&attr).unwrap();
+        assert!(json.get("searchable").is_none());
+
+        let searchable = attr.clone().searchable();
+        let json: serde_json::Value = serde_json::to_value(&searchable).unwrap();
+        assert_eq!(json["searchable"], true);
+        assert!(
+            AttributeDescriptor::decode(&searchable.encode())
+                .unwrap()
+                .is_searchable()
+        );
+        assert_eq!(searchable.to_uri(), attr.to_uri());
+    }
+
     #[dialog_common::test]
     fn it_serializes_many_cardinality() {
         let attr = AttributeDescriptor::new(
@@ -485,4 +582,18 @@ mod tests {
             "should reject non-string cardinality value"
         );
     }
+
+    #[dialog_common::test]
+    fn it_round_trips_through_its_stored_encoding() {
+        let attr = AttributeDescriptor::new(
+            the!("io.gozala.person/age"),
+            "Age of the person",
+            Cardinality::One,
+            Some(Type::UnsignedInt),
+        );
+        let decoded = AttributeDescriptor::decode(&attr.encode()).unwrap();
+        assert_eq!(decoded, attr);
+        assert!(attr.admits(&Value::UnsignedInt(42)));
+        assert!(!attr.admits(&Value::String("42".into())));
+    }
 }
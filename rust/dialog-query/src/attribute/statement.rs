@@ -1,9 +1,10 @@
 use crate::artifact::{Entity, Value};
-use crate::attribute::The;
 use crate::attribute::expression::dynamic::DynamicAttributeExpression;
+use crate::attribute::{AttributeDescriptor, The};
 use crate::schema::Cardinality;
 use crate::statement::Statement;
-use dialog_artifacts::Update;
+use crate::the;
+use dialog_artifacts::{Attribute, Update};
 
 /// A type-erased, attribute statement.
 ///
@@ -34,3 +35,50 @@ impl Statement for AttributeStatement {
         update.dissociate(self.the.into(), self.of, self.is);
     }
 }
+
+/// The `dialog.attribute/descriptor` attribute: the canonical dag-cbor
+/// [`AttributeDescriptor`] (a `Value::Bytes`) registered for an
+/// attribute, stored `of` its [`descriptor_entity`]. Cardinality-one: a
+/// later registration replaces the earlier one.
+pub fn descriptor_attr() -> Attribute {
+    the!("dialog.attribute/descriptor").into()
+}
+
+/// The `attribute:<domain>/<name>` entity an attribute's descriptor is
+/// registered under. Derivable from a runtime instruction alone, so a
+/// writer can look up the descriptor of any attribute it is about to
+/// assert without a scan.
+pub fn descriptor_entity(attribute: &Attribute) -> Option<Entity> {
+    format!("attribute:{attribute}").parse().ok()
+}
+
+/// Asserting an [`AttributeDescriptor`] registers it as the attribute's
+/// `dialog.attribute/descriptor` fact, replacing any earlier
+/// registration; retracting the same descriptor unregisters it. A
+/// registered descriptor is what a strict commit validates assertions
+/// against.
+impl Statement for &AttributeDescriptor {
+    fn assert(self, update: &mut impl Update) {
+        let attribute = Attribute::from(self.the());
+        if let Some(entity) = descriptor_entity(&attribute) {
+            update.associate_unique(descriptor_attr(), entity, Value::Bytes(self.encode()));
+        }
+    }
+
+    fn retract(self, update: &mut impl Update) {
+        let attribute = Attribute::from(self.the());
+        if let Some(entity) = descriptor_entity(&attribute) {
+            update.dissociate(descriptor_attr(), entity, Value::Bytes(self.encode()));
+        }
+    }
+}
+
+impl Statement for AttributeDescriptor {
+    fn assert(self, update: &mut impl Update) {
+        (&self).assert(update);
+    }
+
+    fn retract(self, update: &mut impl Update) {
+        (&self).retract(update);
+    }
+}
@@ -387,6 +387,7 @@ mod tests {
     use crate::query::Output;
 
     use crate::Concept;
+    use crate::Direction;
     use crate::attribute::query::AttributeQuery;
     use crate::session::RuleRegistry;
     use crate::source::test::TestEnv;
@@ -754,6 +755,42 @@ mod tests {
         Ok(())
     }
 
+    /// A derived query orders and pages its results without going
+    /// through the untyped `ConceptQuery`.
+    #[dialog_common::test]
+    async fn it_orders_a_derived_concept_query() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let mut transaction = branch.transaction();
+        for (name, age) in [("Alice", 31), ("Bob", 45), ("Carol", 27), ("Dave", 38)] {
+            transaction = transaction.assert(Person {
+                this: Entity::new()?,
+                name: person::Name(name.to_string()),
+                age: person::Age(age),
+            });
+        }
+        transaction.commit().perform(&operator).await?;
+        let source = TestEnv::new(&branch, &operator, RuleRegistry::new());
+
+        let people = Query::<Person> {
+            this: Term::var("this"),
+            name: Term::var("name"),
+            age: Term::var("age"),
+        }
+        .order_by(Term::<u32>::var("age"), Direction::Descending)
+        .limit(3)
+        .offset(1)
+        .perform(&source)
+        .try_vec()
+        .await?;
+
+        let names: Vec<String> = people.into_iter().map(|person| person.name.0).collect();
+        assert_eq!(names, vec!["Dave", "Alice", "Carol"]);
+        Ok(())
+    }
+
     #[dialog_common::test]
     async fn it_negates_concept_with_not_operator() -> Result<()> {
         let (operator, profile) = test_operator_with_profile().await;
@@ -1081,6 +1118,115 @@ mod tests {
         Ok(())
     }
 
+    /// A compound value stored atomically as one record.
+    #[derive(crate::Record, Debug, Clone, PartialEq)]
+    pub struct GeoPoint {
+        pub lat: f64,
+        pub lon: f64,
+    }
+
+    impl crate::RecordFormat for GeoPoint {
+        fn decode(bytes: &[u8]) -> Result<Self, crate::RecordError> {
+            let fields = crate::RecordFields::decode(bytes)?;
+            match (fields.get("lat"), fields.get("lon")) {
+                (Some(Value::Float(lat)), Some(Value::Float(lon))) => Ok(GeoPoint {
+                    lat: *lat,
+                    lon: *lon,
+                }),
+                _ => Err(crate::RecordError::Decode("not a geo point".into())),
+            }
+        }
+
+        fn encode(&self) -> Result<Vec<u8>, crate::RecordError> {
+            crate::RecordFields::new()
+                .with("lat", self.lat)
+                .with("lon", self.lon)
+                .encode()
+        }
+    }
+
+    mod place {
+        use crate::Attribute;
+
+        /// Name of the place.
+        #[derive(Attribute, Clone, PartialEq)]
+        pub struct Name(pub String);
+
+        /// Where the place is.
+        #[derive(Attribute, Clone, PartialEq)]
+        pub struct Location(pub super::GeoPoint);
+    }
+
+    #[derive(Concept, Debug, Clone, PartialEq)]
+    pub struct Place {
+        pub this: Entity,
+        pub name: place::Name,
+        pub location: place::Location,
+    }
+
+    #[dialog_common::test]
+    async fn it_round_trips_record_valued_fields() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let landmark = GeoPoint {
+            lat: 37.8199,
+            lon: -122.4783,
+        };
+        let bridge = Place {
+            this: Entity::new()?,
+            name: place::Name("Golden Gate".to_string()),
+            location: place::Location(landmark.clone()),
+        };
+        let park = Place {
+            this: Entity::new()?,
+            name: place::Name("Presidio".to_string()),
+            location: place::Location(GeoPoint {
+                lat: 37.7989,
+                lon: -122.4662,
+            }),
+        };
+
+        branch
+            .transaction()
+            .assert(bridge.clone())
+            .assert(park)
+            .commit()
+            .perform(&operator)
+            .await?;
+
+        assert_eq!(
+            place::Location::descriptor().content_type(),
+            Some(ValueType::Record)
+        );
+
+        let source = TestEnv::new(&branch, &operator, RuleRegistry::new());
+        let all: Vec<Place> = Query::<Place> {
+            this: Term::var("place"),
+            name: Term::var("name"),
+            location: Term::var("location"),
+        }
+        .perform(&source)
+        .try_collect()
+        .await?;
+        assert_eq!(all.len(), 2);
+        assert!(all.contains(&bridge));
+
+        // A record constant selects by its bytes through the value index.
+        let found: Vec<Place> = Query::<Place> {
+            this: Term::var("place"),
+            name: Term::var("name"),
+            location: Term::from(landmark),
+        }
+        .perform(&source)
+        .try_collect()
+        .await?;
+        assert_eq!(found, vec![bridge]);
+
+        Ok(())
+    }
+
     #[dialog_common::test]
     async fn it_queries_concept_with_constant_term() -> Result<()> {
         let (operator, profile) = test_operator_with_profile().await;
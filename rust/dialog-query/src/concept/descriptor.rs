@@ -9,6 +9,7 @@ use crate::attribute::{AttributeDescriptor, Attribution};
 use crate::concept::query::ConceptQuery;
 use crate::concept::{Concept, Conclusion};
 use crate::error::TypeError;
+use crate::order::Order;
 use crate::query::{Application, Restriction};
 use crate::selection::{Match, Selection};
 use crate::statement::Retraction;
@@ -549,6 +550,18 @@ impl Application for ConceptQuery {
         ConceptQuery::evaluate(self, selection, env)
     }
 
+    fn evaluate_ordered<'a, Env>(
+        self,
+        input: Match,
+        order: Order,
+        env: &'a Env,
+    ) -> impl Selection + 'a
+    where
+        Env: crate::Scope<'a>,
+    {
+        ConceptQuery::evaluate_ordered(self, input, order, env)
+    }
+
     fn restrict(&self, entity: &Entity) -> Restriction<Self> {
         match self.terms.get("this") {
             Some(Term::Constant(Value::Entity(this))) if this == entity => {
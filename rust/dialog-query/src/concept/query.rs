@@ -15,15 +15,21 @@ pub use rules::ConceptRules;
 use std::fmt;
 
 use crate::concept::descriptor::{ConceptDescriptor, ConceptFieldDescriptor};
-use crate::planner::Disjunction;
+use crate::planner::{
+    Conjunction, Derivation, Disjunction, ExplainedStep, Explanation, Measurement, Meter, Planner,
+    alternatives, record,
+};
 use crate::rule::deductive::DeductiveRule;
 use crate::schema::CONCEPT_OVERHEAD;
 use crate::selection::Selection;
 use crate::source::SelectRules;
+use crate::types::Any;
 use crate::{
-    Binding, Cardinality, Environment, EvaluationError, Match, Parameters, Schema, Term, try_stream,
+    Binding, Cardinality, Direction, Environment, EvaluationError, Match, Order, Ordered,
+    Parameters, Premise, Schema, Term, try_stream,
 };
 use dialog_capability::Provider;
+use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
@@ -250,6 +256,107 @@ impl ConceptQuery {
         self.predicate.schema()
     }
 
+    /// Explain how this query evaluates: the plan of each rule that
+    /// derives the concept, ordered for the query's bindings.
+    pub async fn explain<'a, Env>(&self, env: &'a Env) -> Result<Explanation, EvaluationError>
+    where
+        Env: crate::Scope<'a>,
+    {
+        self.plan()?.explain(env).await
+    }
+
+    /// Run this query and explain it with what each step did: the rows
+    /// it produced, the node fetches `meter` counted and the time
+    /// spent.
+    ///
+    /// Rules evaluated top-down are run one after another rather than
+    /// concurrently, so each of their steps is measured on its own. A
+    /// recursive concept's fixpoint is measured as a whole.
+    pub async fn analyze<'a, Env>(
+        self,
+        env: &'a Env,
+        meter: &'a impl Meter,
+    ) -> Result<Explanation, EvaluationError>
+    where
+        Env: crate::Scope<'a>,
+    {
+        let plan = self.plan()?;
+        let mut explanation = plan.explain(env).await?;
+        let Some(ExplainedStep {
+            actual,
+            derivation: Some(Derivation::TopDown { rules, reducing }),
+            ..
+        }) = explanation.steps.first_mut()
+        else {
+            return plan.analyze(Match::new().seed(), env, meter).await;
+        };
+
+        let found = Provider::<SelectRules>::execute(env, self.predicate.clone()).await?;
+        let mut total = Measurement::default();
+        let mut measure = |run: Measurement, rows: u64| {
+            total.rows += rows;
+            total.fetches += run.fetches;
+            total.elapsed += run.elapsed;
+        };
+
+        let initial_match = extract_parameters(&Match::new(), &self.terms)
+            .map_err(|e| EvaluationError::Store(e.to_string()))?;
+        let disjunction = found.plan_within(&self.terms, &Environment::new());
+        for (rule, explained) in alternatives(&disjunction).into_iter().zip(rules) {
+            let (output, tallies) =
+                rule.clone()
+                    .instrument(initial_match.clone().seed(), env, meter);
+            output.try_for_each(|_| async { Ok(()) }).await?;
+            let run = record(explained, &tallies);
+            measure(run, run.rows);
+        }
+
+        for (rule, explained) in found.reducing().zip(reducing) {
+            let Some(reducer) = rule.reducer() else {
+                continue;
+            };
+            let (body, tallies) =
+                rule.plan(&Environment::new())
+                    .instrument(Match::new().seed(), env, meter);
+            let folded = reducer.fold(body).await?;
+            let mut rows = 0;
+            for matched in &folded {
+                let row = fixpoint::project(rule.conclusion(), matched);
+                if fixpoint::join(&Match::new(), &self.terms, &row)?.is_some() {
+                    rows += 1;
+                }
+            }
+            measure(record(explained, &tallies), rows);
+        }
+
+        *actual = Some(total);
+        Ok(explanation)
+    }
+
+    /// Return this query's results sorted on `by` in `direction`.
+    pub fn order_by(self, by: impl Into<Term<Any>>, direction: Direction) -> Ordered<Self> {
+        Ordered::from(self).order_by(by, direction)
+    }
+
+    /// Return at most `limit` of this query's results.
+    pub fn limit(self, limit: usize) -> Ordered<Self> {
+        Ordered::from(self).limit(limit)
+    }
+
+    /// Skip the first `offset` of this query's results.
+    pub fn offset(self, offset: usize) -> Ordered<Self> {
+        Ordered::from(self).offset(offset)
+    }
+
+    /// This query as a one-step plan, the shape evaluation gives it.
+    fn plan(&self) -> Result<Conjunction, EvaluationError> {
+        Planner::from(vec![Premise::from(self.clone())])
+            .plan(&Environment::new())
+            .map_err(|error| EvaluationError::Planning {
+                message: error.to_string(),
+            })
+    }
+
     /// Evaluates this concept application within the given context, producing
     /// a selection stream.
     ///
@@ -343,6 +450,39 @@ impl ConceptQuery {
     }
 }
 
+impl ConceptQuery {
+    /// Evaluates this concept application over a single input row,
+    /// returning its rows in `order`.
+    ///
+    /// When the concept derives through a single rule that neither
+    /// recurses nor reduces, its plan reports the variable its rows come
+    /// back sorted on. If the order leads with that variable ascending,
+    /// reading stops once the page is full instead of sorting every row.
+    pub fn evaluate_ordered<'a, Env>(
+        self,
+        input: Match,
+        order: Order,
+        env: &'a Env,
+    ) -> impl Selection + 'a
+    where
+        Env: crate::Scope<'a>,
+    {
+        try_stream! {
+            let rules = Provider::<SelectRules>::execute(env, self.predicate.clone()).await?;
+            let presorted = rules.recursion().is_none()
+                && rules.reducing().next().is_none()
+                && rules
+                    .plan(&self.terms, &input)
+                    .sorted_on()
+                    .and_then(|parameter| self.terms.get(&parameter).and_then(Term::name))
+                    .is_some_and(|variable| order.leads_with(variable));
+            for await row in order.select(self.evaluate(input.seed(), env), presorted) {
+                yield row?;
+            }
+        }
+    }
+}
+
 /// Evaluate one reducing rule to its folded conclusion rows: the
 /// body plans and evaluates at *empty* scope (the fold must see the
 /// full relation, never a caller-restricted slice), the [`Reduce`]
@@ -397,6 +537,7 @@ mod tests {
     use crate::types::Any;
     use std::collections::{BTreeSet, HashSet};
 
+    use crate::query::Application;
     use crate::session::RuleRegistry;
     use crate::source::test::TestEnv;
     use crate::{
@@ -411,6 +552,92 @@ mod tests {
     // with deeply nested async streams. The functionality is tested indirectly through integration
     // tests and the planning tests above verify the core logic.
 
+    /// An ordered concept query sorts on any of its variables and pages
+    /// through the result; ordered on the entity its plan already reads
+    /// in order, it returns the same page.
+    #[dialog_common::test]
+    async fn it_pages_an_ordered_concept_query() -> anyhow::Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let mut people = Vec::new();
+        let mut transaction = branch.transaction();
+        for (name, age) in [
+            ("ann", 41u32),
+            ("bob", 23),
+            ("cat", 35),
+            ("dan", 29),
+            ("eve", 52),
+        ] {
+            let person = Entity::new()?;
+            transaction = transaction
+                .assert(the!("person/name").of(person.clone()).is(name.to_string()))
+                .assert(the!("person/age").of(person.clone()).is(age));
+            people.push(person);
+        }
+        transaction.commit().perform(&operator).await?;
+        let source = TestEnv::new(&branch, &operator, RuleRegistry::new());
+
+        let predicate = ConceptDescriptor::try_from(vec![
+            (
+                "name",
+                AttributeDescriptor::new(
+                    the!("person/name"),
+                    "",
+                    Cardinality::One,
+                    Some(Type::String),
+                ),
+            ),
+            (
+                "age",
+                AttributeDescriptor::new(
+                    the!("person/age"),
+                    "",
+                    Cardinality::One,
+                    Some(Type::UnsignedInt),
+                ),
+            ),
+        ])?;
+        let mut terms = Parameters::new();
+        terms.insert("this".to_string(), Term::var("person"));
+        terms.insert("name".to_string(), Term::var("name"));
+        terms.insert("age".to_string(), Term::var("age"));
+        let query = ConceptQuery { terms, predicate };
+
+        let oldest = query
+            .clone()
+            .order_by(Term::<u32>::var("age"), Direction::Descending)
+            .offset(1)
+            .limit(2);
+        let rows: Vec<Match> = Application::evaluate(oldest, Match::new().seed(), &source)
+            .try_collect()
+            .await?;
+        let names = rows
+            .iter()
+            .map(|row| row.lookup(&Term::var("name"))?.content())
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            names,
+            vec![Value::String("ann".into()), Value::String("cat".into())]
+        );
+
+        let first = query
+            .order_by(Term::<Entity>::var("person"), Direction::Ascending)
+            .limit(2);
+        let rows: Vec<Match> = Application::evaluate(first, Match::new().seed(), &source)
+            .try_collect()
+            .await?;
+        let found = rows
+            .iter()
+            .map(|row| row.lookup(&Term::var("person"))?.content())
+            .collect::<Result<Vec<_>, _>>()?;
+        people.sort();
+        let expected: Vec<Value> = people[..2].iter().cloned().map(Value::Entity).collect();
+        assert_eq!(found, expected);
+        Ok(())
+    }
+
     #[dialog_common::test]
     async fn it_executes_concept_query() -> anyhow::Result<()> {
         let (operator, profile) = test_operator_with_profile().await;
@@ -1579,6 +1806,82 @@ mod tests {
             Ok(())
         }
 
+        /// The extended folds evaluate end to end: `arg-max` reports
+        /// the employee entity bound beside the greatest salary, and
+        /// `median` interpolates within each department.
+        #[dialog_common::test]
+        async fn it_evaluates_arg_max_and_median() -> anyhow::Result<()> {
+            let (operator, profile) = test_operator_with_profile().await;
+            let repo = test_repo(&operator, &profile).await;
+            let branch = repo.branch("main").open().perform(&operator).await?;
+
+            let dept: Entity = "id:dept-a".parse()?;
+            let alice: Entity = "id:alice".parse()?;
+            let bob: Entity = "id:bob".parse()?;
+
+            branch
+                .transaction()
+                .assert(the!("org.employee/dept").of(alice.clone()).is(dept.clone()))
+                .assert(the!("org.employee/salary").of(alice.clone()).is(100u32))
+                .assert(the!("org.employee/dept").of(bob.clone()).is(dept.clone()))
+                .assert(the!("org.employee/salary").of(bob.clone()).is(50u32))
+                .commit()
+                .perform(&operator)
+                .await?;
+
+            let rule = compile(serde_json::json!({
+                "deduce": { "with": {
+                    "top": { "the": "org.dept/top-earner", "as": "Entity" },
+                    "middle": { "the": "org.dept/median-salary", "as": "Float" }
+                }},
+                "when": [{
+                    "assert": { "with": {
+                        "dept": { "the": "org.employee/dept", "as": "Entity" },
+                        "salary": { "the": "org.employee/salary", "as": "UnsignedInteger" }
+                    }},
+                    "where": {
+                        "this": { "?": { "name": "employee" } },
+                        "dept": { "?": { "name": "this" } },
+                        "salary": { "?": { "name": "salary" } }
+                    }
+                }],
+                "reduce": {
+                    "top": {
+                        "apply": { "arg-max": { "?": { "name": "employee" } } },
+                        "of": { "?": { "name": "salary" } }
+                    },
+                    "middle": { "apply": "median", "of": { "?": { "name": "salary" } } }
+                }
+            }));
+            let conclusion = rule.conclusion().clone();
+            let mut registry = RuleRegistry::new();
+            registry.register(rule)?;
+            let source = TestEnv::new(&branch, &operator, registry);
+
+            let mut terms = Parameters::new();
+            terms.insert("this".into(), Term::var("dept"));
+            terms.insert("top".into(), Term::var("top"));
+            terms.insert("middle".into(), Term::var("middle"));
+            let rows = ConceptQuery {
+                terms,
+                predicate: conclusion,
+            }
+            .evaluate(Match::new().seed(), &source)
+            .try_vec()
+            .await?;
+
+            assert_eq!(rows.len(), 1);
+            assert_eq!(
+                rows[0].lookup(&Term::var("top"))?.content()?,
+                Value::Entity(alice)
+            );
+            assert_eq!(
+                rows[0].lookup(&Term::var("middle"))?.content()?,
+                Value::Float(75.0)
+            );
+            Ok(())
+        }
+
         /// A caller arriving with a grouping field bound joins into
         /// the folded output; the fold itself still ran over the full
         /// relation, so the dept-bound total equals the unrestricted
@@ -57,6 +57,24 @@ impl Adornment {
         Adornment(bits)
     }
 
+    /// Derive an adornment from a concept's terms and the variables
+    /// bound when it runs, without a concrete match. Agrees with
+    /// [`derive`](Self::derive) on any match binding exactly `bound`.
+    pub fn within(terms: &Parameters, bound: &Environment) -> Self {
+        let mut sorted_keys: Vec<&String> = terms.keys().collect();
+        sorted_keys.sort();
+
+        let mut bits: u64 = 0;
+        for (i, key) in sorted_keys.iter().enumerate() {
+            debug_assert!(i < 64, "Adornment supports at most 64 parameters");
+            if terms.get(key).is_some_and(|param| param.is_bound(bound)) {
+                bits |= 1 << i;
+            }
+        }
+
+        Adornment(bits)
+    }
+
     /// Reconstruct an `Environment` from this adornment and the concept's terms.
     ///
     /// Bridges the adornment back to the planner's `Environment` type so
@@ -14,6 +14,7 @@ use super::adornment::Adornment;
 use super::fixpoint::Continuation;
 use super::plan_cache::PlanCache;
 use crate::DeductiveRule;
+use crate::Environment;
 use crate::concept::descriptor::ConceptDescriptor;
 use crate::parameters::Parameters;
 use crate::planner::Disjunction;
@@ -159,8 +160,18 @@ impl ConceptRules {
     /// adornment, so a repeated identical call on the *same*
     /// `ConceptRules` skips even the (cheap) re-assembly.
     pub fn plan(&self, terms: &Parameters, matched: &Match) -> Arc<Disjunction> {
-        let adornment = Adornment::derive(terms, matched);
+        self.plan_adorned(terms, Adornment::derive(terms, matched))
+    }
+
+    /// The plan [`plan`](Self::plan) would use for a match binding
+    /// exactly the variables in `bound`. Explaining a query plans
+    /// ahead of any match, so it derives the binding pattern from the
+    /// planner's environment instead.
+    pub fn plan_within(&self, terms: &Parameters, bound: &Environment) -> Arc<Disjunction> {
+        self.plan_adorned(terms, Adornment::within(terms, bound))
+    }
 
+    fn plan_adorned(&self, terms: &Parameters, adornment: Adornment) -> Arc<Disjunction> {
         if let Some(plan) = self.plans.read().unwrap().get(&adornment) {
             return plan.clone();
         }
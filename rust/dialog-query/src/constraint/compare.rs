@@ -1,7 +1,7 @@
 //! Range predicates: `<`, `<=`, `>`, `>=` as premises.
 //!
 //! Four constraints over the COMPARABLE kinds (numbers, strings,
-//! symbols, entities, bytes), sharing one comparison core. Like
+//! symbols, entities, bytes, instants), sharing one comparison core. Like
 //! every scalar premise they *filter*: a row whose sides cannot be
 //! ordered — a non-comparable value, a mixed-type pair, a NaN — is
 //! a non-match, never an error and never a coercion. The same
@@ -17,7 +17,10 @@
 //! Non-numeric comparables order within one type by their
 //! ORDER-PRESERVING encoding — the same order the value index sorts
 //! by, so a pushed scan range and this residual filter can never
-//! disagree about a row.
+//! disagree about a row. Instants encode as their signed
+//! nanosecond count, so a time-window filter (`?at >= start`,
+//! `?at < end`) is chronological and pushes down into a value-index
+//! range scan like any other.
 //!
 //! Inference: both slots carry the COMPARABLE bound, narrowing the
 //! variables on use. A constant side additionally proves an
@@ -275,7 +278,7 @@ mod tests {
     wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
 
     use super::*;
-    use crate::artifact::{ArtifactsAttribute, Type as ValueType, decode_value};
+    use crate::artifact::{ArtifactsAttribute, Instant, Type as ValueType, decode_value};
     use crate::rule::TypeEnv;
     use crate::selection::Match;
     use crate::types::Scalar;
@@ -398,6 +401,41 @@ mod tests {
         Ok(())
     }
 
+    /// Instants order chronologically, across the epoch, and a
+    /// constant instant proves an interval for the scan to push down.
+    #[dialog_common::test]
+    async fn it_orders_instants() -> anyhow::Result<()> {
+        let epoch = Instant::UNIX_EPOCH;
+        assert_eq!(
+            count(below(epoch), Value::Instant(Instant::from_unix_nanos(-1))).await?,
+            1,
+            "an instant before the epoch precedes it"
+        );
+        assert_eq!(
+            count(below(epoch), Value::Instant(Instant::from_unix_seconds(60))).await?,
+            0
+        );
+        assert_eq!(
+            count(below(epoch), Value::SignedInt(-1)).await?,
+            0,
+            "an integer never orders against an instant"
+        );
+
+        let premises = vec![Term::<Any>::var("at").less_than(Term::constant(epoch))];
+        let env = TypeEnv::infer(&premises)?;
+        let interval = env
+            .get("at")
+            .expect("inferred")
+            .refinement()
+            .expect("refined")
+            .interval
+            .clone()
+            .expect("interval recorded");
+        assert_eq!(interval.value_type, ValueType::Instant);
+        assert!(!interval.upper.expect("upper bound").inclusive);
+        Ok(())
+    }
+
     /// A string constant proves an interval, same as a numeric one.
     #[dialog_common::test]
     fn it_stamps_string_interval_refinements() -> anyhow::Result<()> {
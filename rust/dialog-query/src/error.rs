@@ -11,6 +11,7 @@ pub use crate::proposition::Proposition;
 use crate::reduce::Aggregator;
 pub use crate::rule::Rule;
 pub use crate::rule::deductive::DeductiveRule;
+use crate::syntax::Span;
 use crate::term::Term;
 use crate::type_system::Type as Kind;
 use crate::types::Any;
@@ -30,6 +31,11 @@ pub enum TypeError {
     #[error("An inductive rule must have an `assert!` or `retract!` head")]
     MissingHead,
 
+    /// A rule definition carries a raw attribute premise, which has no
+    /// formal notation.
+    #[error("An attribute premise has no formal notation; look the attribute up through a concept")]
+    InexpressiblePremise,
+
     /// A binding has the wrong type.
     #[error("Expected binding \"{binding}\" with {expected} type, instead got {actual}")]
     TypeMismatch {
@@ -306,13 +312,27 @@ pub enum TypeError {
         /// The reduce output field being declared.
         field: String,
         /// The aggregator that cannot consume the input.
-        aggregator: Aggregator,
+        aggregator: Box<Aggregator>,
         /// The aggregator's input requirement.
         required: Box<Kind>,
         /// The declared or inferred type of the input.
         actual: Box<Kind>,
     },
 
+    /// A reduce entry's aggregator carries a parameter the fold
+    /// cannot use, such as a percentile outside `0.0 ..= 1.0`.
+    /// Raised at construction by
+    /// [`ReduceEntry::try_new`](crate::reduce::ReduceEntry::try_new).
+    #[error("Reduce field \"{field}\" applies {aggregator}, but {reason}")]
+    ReduceParameter {
+        /// The reduce output field being declared.
+        field: String,
+        /// The aggregator with the unusable parameter.
+        aggregator: Box<Aggregator>,
+        /// What is wrong with the parameter.
+        reason: String,
+    },
+
     /// A `reduce` block names an output field the rule's head
     /// (`deduce`) does not declare. The map is keyed by head field
     /// name — a key with no head field has nothing to bind. Raised
@@ -351,7 +371,7 @@ pub enum TypeError {
         /// The reduce output field.
         field: String,
         /// The aggregator whose output mismatches.
-        aggregator: Aggregator,
+        aggregator: Box<Aggregator>,
         /// The fold's output type.
         output: Box<Kind>,
         /// The head field's declared type.
@@ -582,6 +602,17 @@ pub enum EvaluationError {
         reason: String,
     },
 
+    /// Two rows could not be ordered on a key of a query's
+    /// [`Order`](crate::Order): their values are of different types,
+    /// or one of them is NaN.
+    #[error("Cannot order rows on {variable}: {reason}")]
+    Order {
+        /// The key variable the rows were compared by.
+        variable: String,
+        /// Why the values could not be ordered.
+        reason: String,
+    },
+
     /// The queried concept's dependency closure contains a cycle
     /// through negation: some rule concluding `concept` negates
     /// `negated` inside the same dependency cycle, so the negation
@@ -663,8 +694,14 @@ impl From<Infallible> for EvaluationError {
 
 impl From<ArtifactTypeError> for EvaluationError {
     fn from(error: ArtifactTypeError) -> Self {
-        let ArtifactTypeError::TypeMismatch(expected, actual) = error;
-        EvaluationError::TypeMismatch { expected, actual }
+        match error {
+            ArtifactTypeError::TypeMismatch(expected, actual) => {
+                EvaluationError::TypeMismatch { expected, actual }
+            }
+            ArtifactTypeError::InvalidRecord(error) => EvaluationError::Serialization {
+                message: error.to_string(),
+            },
+        }
     }
 }
 
@@ -857,3 +894,66 @@ pub enum AnalysisError {
     #[error("{0}")]
     Reduce(Box<TypeError>),
 }
+
+/// Errors raised while reading or writing the textual notation
+/// ([`syntax`](crate::syntax)). Reading errors carry the [`Span`] of
+/// the source text they concern.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum SyntaxError {
+    /// The source has a token the grammar does not allow here.
+    #[error("{span}: expected {expected}, found {found}")]
+    Unexpected {
+        /// What the grammar allows at this point.
+        expected: String,
+        /// The token that was found instead.
+        found: String,
+        /// Where it was found.
+        span: Span,
+    },
+    /// A string literal is not closed before the end of its line.
+    #[error("{span}: unterminated string")]
+    UnterminatedString {
+        /// Where the string starts.
+        span: Span,
+    },
+    /// A string or number literal is not valid JSON.
+    #[error("{span}: invalid literal: {reason}")]
+    InvalidLiteral {
+        /// Why the literal was rejected.
+        reason: String,
+        /// Where the literal appears.
+        span: Span,
+    },
+    /// A concept is referenced by a name nothing defines.
+    #[error("{span}: unknown concept `{name}`")]
+    UnknownConcept {
+        /// The undefined name.
+        name: String,
+        /// Where it is referenced.
+        span: Span,
+    },
+    /// A name is defined twice in the same scope: a concept, a field,
+    /// an argument, a rule head or a reduced field.
+    #[error("{span}: `{name}` is defined more than once")]
+    Duplicate {
+        /// The repeated name.
+        name: String,
+        /// Where it is repeated.
+        span: Span,
+    },
+    /// The source is well formed, but the formal notation it reads as
+    /// is rejected (an unknown formula, a malformed concept, a reduce
+    /// entry naming no head field, and so on).
+    #[error("{span}: {reason}")]
+    Invalid {
+        /// Why the formal notation was rejected.
+        reason: String,
+        /// The construct that reads as the rejected notation.
+        span: Span,
+    },
+    /// A descriptor has no textual form, such as an attribute
+    /// proposition (which has no formal notation either) or an
+    /// inductive rule without a head.
+    #[error("cannot be written in the textual notation: {0}")]
+    Inexpressible(String),
+}
@@ -4,6 +4,8 @@
 //! - String operations (concatenate, length, uppercase, lowercase)
 //! - Type conversions (to_string, parse_number)
 //! - Boolean logic (and, or, not)
+//! - Temporal operations (parse, format, truncate, add, difference)
+//! - Record projections (field)
 
 /// Bindings for reading/writing values during formula evaluation.
 pub mod bindings;
@@ -31,11 +33,15 @@ pub mod logic;
 pub mod math;
 /// String manipulation formulas (concatenate, length, uppercase, lowercase, like)
 pub mod string;
+/// Temporal formulas over instants (parse, format, truncate-day, add, difference)
+pub mod time;
 
 /// Index-key decomposition formulas (key-part, separator-part)
 pub mod key;
 /// Fractional-position formulas for ordered relations
 pub mod position;
+/// Record field projection (field)
+pub mod record;
 /// Version-control revision record projections (revision, revision-parent)
 pub mod revision;
 
@@ -44,8 +50,10 @@ pub use key::{KeyPart as KeyPartFormula, SeparatorPart as SeparatorPartFormula};
 pub use logic::{And, Not, Or};
 pub use math::{Difference, Modulo, Product, Quotient, Sum};
 pub use position::{Position as PositionFormula, PositionParts as PositionPartsFormula};
+pub use record::RecordField;
 pub use revision::{Revision as RevisionFormula, RevisionParent as RevisionParentFormula};
 pub use string::{Concatenate, Length, Like, Lowercase, Uppercase};
+pub use time::{AddDuration, Elapsed, FormatInstant, ParseInstant, TruncateDay};
 
 use crate::Parameters;
 use crate::Predicate;
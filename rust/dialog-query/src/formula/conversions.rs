@@ -28,6 +28,9 @@ impl ToString {
             Value::Symbol(s) => s.to_string(),
             Value::Bytes(bytes) => format!("Bytes({} bytes)", bytes.len()),
             Value::Record(record) => format!("Record({} bytes)", record.len()),
+            Value::Instant(instant) => {
+                super::time::format_rfc3339(instant).unwrap_or_else(|| instant.to_string())
+            }
         };
 
         vec![ToString {
@@ -111,6 +111,14 @@ define_formulas! {
     "signed-integer/parse"    => ParseSignedInteger(ParseSignedInteger, conversions::ParseSignedIntegerQuery),
     "float/parse"             => ParseFloat(ParseFloat, conversions::ParseFloatQuery),
 
+    "time/parse"              => ParseInstant(super::time::ParseInstant, super::time::ParseInstantQuery),
+    "time/format"             => FormatInstant(super::time::FormatInstant, super::time::FormatInstantQuery),
+    "time/truncate-day"       => TruncateDay(super::time::TruncateDay, super::time::TruncateDayQuery),
+    "time/add"                => AddDuration(super::time::AddDuration, super::time::AddDurationQuery),
+    "time/difference"         => Elapsed(super::time::Elapsed, super::time::ElapsedQuery),
+
+    "record/field"            => RecordField(super::record::RecordField, super::record::RecordFieldQuery),
+
     "dialog/revision"         => Revision(super::revision::Revision, super::revision::RevisionQuery),
     "dialog/revision-parent"  => RevisionParent(super::revision::RevisionParent, super::revision::RevisionParentQuery),
 
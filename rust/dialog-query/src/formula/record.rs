@@ -0,0 +1,84 @@
+//! Formulas projecting the fields of record values.
+//!
+//! A record is one atomic value (see [`RecordValue`]); the query engine
+//! carries it without looking inside. Records written in the
+//! self-describing [`RecordFields`] format can still be taken apart in a
+//! query: bind the record with an attribute scan, then apply
+//! [`RecordField`] to read one of its fields.
+//!
+//! ```text
+//! place/location(of: ?place, is: ?location)
+//!   ⋈ record/field(of: ?location, field: "lat", is: ?lat)
+//! ```
+//!
+//! The decoded fields are cached on the record, so projecting several
+//! fields of one record decodes it once.
+
+use crate::artifact::{RecordFields, RecordValue};
+use crate::formula::Input;
+use crate::{Formula, Value};
+
+/// Projects one named field out of a [`RecordFields`] record. A record
+/// in another format, or one without the field, projects nothing.
+#[derive(Debug, Clone, Formula)]
+pub struct RecordField {
+    /// The record to read.
+    pub of: RecordValue,
+    /// The name of the field to project.
+    pub field: String,
+    /// The field's value.
+    #[output]
+    pub is: Value,
+}
+
+impl RecordField {
+    /// Realize the record's fields and project the named one.
+    pub fn compute(input: Input<Self>) -> Vec<Self> {
+        let Ok(fields) = input.of.realize::<RecordFields>() else {
+            return Vec::new();
+        };
+        let Some(is) = fields.get(&input.field).cloned() else {
+            return Vec::new();
+        };
+        vec![RecordField {
+            of: input.of,
+            field: input.field,
+            is,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point() -> RecordValue {
+        RecordValue::encode(RecordFields::new().with("lat", 37.77).with("lon", -122.42))
+            .expect("fields encode")
+    }
+
+    #[test]
+    fn it_projects_a_named_field() {
+        let rows = RecordField::compute(RecordFieldInput {
+            of: point(),
+            field: "lon".into(),
+        });
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].is, Value::Float(-122.42));
+    }
+
+    #[test]
+    fn it_projects_nothing_for_a_missing_field_or_foreign_format() {
+        let missing = RecordField::compute(RecordFieldInput {
+            of: point(),
+            field: "alt".into(),
+        });
+        assert!(missing.is_empty());
+
+        let opaque = RecordField::compute(RecordFieldInput {
+            of: RecordValue::from(vec![0xff, 0x00, 0x13]),
+            field: "lat".into(),
+        });
+        assert!(opaque.is_empty());
+    }
+}
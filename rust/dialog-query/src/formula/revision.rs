@@ -184,6 +184,7 @@ mod tests {
             authority: did_key_of(&key),
             parents,
             skips: Vec::new(),
+            time: None,
             signature: Vec::new(),
         };
         record.signature = key
@@ -240,7 +241,10 @@ mod tests {
 
         let mut matched = Match::new();
         matched
-            .bind(&Term::<Any>::var("record"), Value::Record(of.0.clone()))
+            .bind(
+                &Term::<Any>::var("record"),
+                Value::Record(of.0.clone().into()),
+            )
             .expect("record binds");
         matched
             .bind(&Term::<Any>::var("parent"), Value::Entity(first.entity()))
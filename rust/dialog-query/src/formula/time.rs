@@ -0,0 +1,271 @@
+//! Temporal formulas over [`Instant`] values.
+//!
+//! An instant is nanoseconds since the Unix epoch in UTC (see
+//! [`dialog_artifacts::Instant`]); a *duration* is a plain signed
+//! integer count of nanoseconds, so the existing `math/*` formulas
+//! and comparisons apply to durations without a dedicated type.
+//!
+//! - [`ParseInstant`] (`time/parse`) reads an RFC 3339 timestamp;
+//!   any offset is normalized to UTC.
+//! - [`FormatInstant`] (`time/format`) renders an instant as RFC 3339
+//!   in UTC (`Z`), with only as many fractional digits as it needs.
+//! - [`TruncateDay`] (`time/truncate-day`) rounds down to the start
+//!   of the UTC day — the grouping key for per-day aggregates.
+//! - [`AddDuration`] (`time/add`) shifts an instant by a duration.
+//! - [`Elapsed`] (`time/difference`) is the duration between two
+//!   instants.
+//!
+//! Every formula is pure per-row computation; input that cannot be
+//! read, or arithmetic that overflows, projects nothing.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use dialog_artifacts::NANOS_PER_SECOND;
+
+use crate::Formula;
+use crate::artifact::Instant;
+use crate::formula::Input;
+
+/// Read an RFC 3339 timestamp as an [`Instant`].
+pub(crate) fn parse_rfc3339(text: &str) -> Option<Instant> {
+    let parsed = DateTime::parse_from_rfc3339(text.trim()).ok()?;
+    let nanos = i128::from(parsed.timestamp()) * NANOS_PER_SECOND
+        + i128::from(parsed.timestamp_subsec_nanos());
+    Some(Instant::from_unix_nanos(nanos))
+}
+
+/// Render an [`Instant`] as RFC 3339 in UTC, or `None` when it lies
+/// outside the calendar range chrono can represent.
+pub(crate) fn format_rfc3339(instant: &Instant) -> Option<String> {
+    let nanos = instant.unix_nanos();
+    let seconds = i64::try_from(nanos.div_euclid(NANOS_PER_SECOND)).ok()?;
+    let subsec = u32::try_from(nanos.rem_euclid(NANOS_PER_SECOND)).ok()?;
+    let time: DateTime<Utc> = DateTime::from_timestamp(seconds, subsec)?;
+    Some(time.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+}
+
+/// Parse an RFC 3339 timestamp into an instant. Registered as
+/// `time/parse`.
+#[derive(Debug, Clone, Formula)]
+pub struct ParseInstant {
+    /// Timestamp text, e.g. `2024-03-01T12:30:00+02:00`
+    pub text: String,
+    /// The instant it denotes
+    #[output(cost = 2)]
+    pub is: Instant,
+}
+
+impl ParseInstant {
+    /// Parse `text`, returning empty when it is not RFC 3339
+    pub fn compute(input: Input<Self>) -> Vec<Self> {
+        match parse_rfc3339(&input.text) {
+            Some(is) => vec![ParseInstant {
+                text: input.text,
+                is,
+            }],
+            None => vec![],
+        }
+    }
+}
+
+/// Format an instant as an RFC 3339 timestamp in UTC. Registered as
+/// `time/format`.
+#[derive(Debug, Clone, Formula)]
+pub struct FormatInstant {
+    /// Instant to format
+    pub of: Instant,
+    /// RFC 3339 text
+    #[output(cost = 2)]
+    pub is: String,
+}
+
+impl FormatInstant {
+    /// Format `of`, returning empty when it has no calendar form
+    pub fn compute(input: Input<Self>) -> Vec<Self> {
+        match format_rfc3339(&input.of) {
+            Some(is) => vec![FormatInstant { of: input.of, is }],
+            None => vec![],
+        }
+    }
+}
+
+/// Truncate an instant to the start of its UTC day. Registered as
+/// `time/truncate-day`.
+#[derive(Debug, Clone, Formula)]
+pub struct TruncateDay {
+    /// Instant to truncate
+    pub of: Instant,
+    /// Midnight (UTC) of the day containing `of`
+    #[output]
+    pub is: Instant,
+}
+
+impl TruncateDay {
+    /// Round `of` down to midnight UTC, returning empty when that
+    /// midnight is out of range
+    pub fn compute(input: Input<Self>) -> Vec<Self> {
+        match input.of.truncate_to_day() {
+            Some(is) => vec![TruncateDay { of: input.of, is }],
+            None => vec![],
+        }
+    }
+}
+
+/// Shift an instant by a duration in nanoseconds. Registered as
+/// `time/add`.
+#[derive(Debug, Clone, Formula)]
+pub struct AddDuration {
+    /// Instant to shift
+    pub of: Instant,
+    /// Signed duration in nanoseconds
+    pub duration: i128,
+    /// The shifted instant
+    #[output(cost = 2)]
+    pub is: Instant,
+}
+
+impl AddDuration {
+    /// Compute `of + duration`, returning empty on overflow
+    pub fn compute(input: Input<Self>) -> Vec<Self> {
+        match input.of.checked_add(input.duration) {
+            Some(is) => vec![AddDuration {
+                of: input.of,
+                duration: input.duration,
+                is,
+            }],
+            None => vec![],
+        }
+    }
+}
+
+/// Duration from one instant to another, in nanoseconds: `is = of -
+/// since`, negative when `of` precedes `since`. Registered as
+/// `time/difference`.
+#[derive(Debug, Clone, Formula)]
+pub struct Elapsed {
+    /// The later instant
+    pub of: Instant,
+    /// The earlier instant
+    pub since: Instant,
+    /// Signed duration in nanoseconds
+    #[output(cost = 2)]
+    pub is: i128,
+}
+
+impl Elapsed {
+    /// Compute `of - since`, returning empty on overflow
+    pub fn compute(input: Input<Self>) -> Vec<Self> {
+        match input.of.checked_since(&input.since) {
+            Some(is) => vec![Elapsed {
+                of: input.of,
+                since: input.since,
+                is,
+            }],
+            None => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::formula::query::FormulaQuery;
+    use crate::{Parameters, Term, Value, selection::Match};
+
+    fn lookup(row: &Match, name: &str) -> Option<Value> {
+        row.lookup(&Term::var(name))
+            .ok()
+            .and_then(|binding| binding.content().ok())
+    }
+
+    #[dialog_common::test]
+    fn it_round_trips_rfc3339() {
+        let instant = parse_rfc3339("2024-03-01T12:30:00.25+02:00").unwrap();
+        assert_eq!(
+            instant.unix_nanos(),
+            1_709_289_000 * NANOS_PER_SECOND + 250_000_000
+        );
+        assert_eq!(
+            format_rfc3339(&instant).as_deref(),
+            Some("2024-03-01T10:30:00.250Z")
+        );
+
+        let before_epoch = parse_rfc3339("1969-12-31T23:59:59.5Z").unwrap();
+        assert_eq!(before_epoch.unix_nanos(), -500_000_000);
+        assert_eq!(
+            format_rfc3339(&before_epoch).as_deref(),
+            Some("1969-12-31T23:59:59.500Z")
+        );
+
+        assert!(parse_rfc3339("yesterday").is_none());
+    }
+
+    #[dialog_common::test]
+    fn it_parses_and_truncates_to_day() {
+        let mut terms = Parameters::new();
+        terms.insert("text".to_string(), Term::var("text"));
+        terms.insert("is".to_string(), Term::var("at"));
+        let parse: FormulaQuery = ParseInstant::apply(terms).unwrap().into();
+
+        let mut terms = Parameters::new();
+        terms.insert("of".to_string(), Term::var("at"));
+        terms.insert("is".to_string(), Term::var("day"));
+        let truncate: FormulaQuery = TruncateDay::apply(terms).unwrap().into();
+
+        let mut input = Match::new();
+        input
+            .bind(
+                &Term::var("text"),
+                "2024-03-01T23:59:59Z".to_string().into(),
+            )
+            .unwrap();
+
+        let parsed = parse.compute(input).unwrap();
+        assert_eq!(parsed.len(), 1);
+        let truncated = truncate.compute(parsed[0].clone()).unwrap();
+        assert_eq!(
+            lookup(&truncated[0], "day"),
+            parse_rfc3339("2024-03-01T00:00:00Z").map(Value::Instant)
+        );
+
+        let mut malformed = Match::new();
+        malformed
+            .bind(&Term::var("text"), "not a time".to_string().into())
+            .unwrap();
+        assert!(parse.compute(malformed).unwrap().is_empty());
+    }
+
+    #[dialog_common::test]
+    fn it_adds_durations_and_measures_differences() {
+        let start = Instant::from_unix_seconds(1_700_000_000);
+        let hour = 3_600 * NANOS_PER_SECOND;
+
+        let mut terms = Parameters::new();
+        terms.insert("of".to_string(), Term::var("start"));
+        terms.insert("duration".to_string(), Term::var("duration"));
+        terms.insert("is".to_string(), Term::var("end"));
+        let add: FormulaQuery = AddDuration::apply(terms).unwrap().into();
+
+        let mut terms = Parameters::new();
+        terms.insert("of".to_string(), Term::var("start"));
+        terms.insert("since".to_string(), Term::var("end"));
+        terms.insert("is".to_string(), Term::var("elapsed"));
+        let elapsed: FormulaQuery = Elapsed::apply(terms).unwrap().into();
+
+        let mut input = Match::new();
+        input.bind(&Term::var("start"), start.into()).unwrap();
+        input.bind(&Term::var("duration"), hour.into()).unwrap();
+
+        let added = add.compute(input).unwrap();
+        assert_eq!(
+            lookup(&added[0], "end"),
+            start.checked_add(hour).map(Value::Instant)
+        );
+
+        let measured = elapsed.compute(added[0].clone()).unwrap();
+        assert_eq!(
+            lookup(&measured[0], "elapsed"),
+            Some(Value::SignedInt(-hour))
+        );
+    }
+}
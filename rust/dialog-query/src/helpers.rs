@@ -30,7 +30,7 @@ use dialog_artifacts::inspect::Load;
 use dialog_artifacts::selector::Constrained;
 use dialog_artifacts::{
     Artifact, ArtifactSelector, ArtifactStream, Attribute, DialogArtifactsError, Instruction,
-    Select, Value,
+    Search, Select, TextMatch, TextSearch, Value,
 };
 use dialog_capability::{Fork, Provider, Subject};
 use dialog_common::ConditionalSync;
@@ -464,6 +464,29 @@ where
     }
 }
 
+// Full-text search for `text/matches` premises: the branch's own
+// search command (index candidates verified against the facts).
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<Env> Provider<Search> for JoinEnv<'_, Env>
+where
+    Env: Provider<Get>
+        + Provider<Put>
+        + Provider<Resolve>
+        + Provider<Fork<RemoteSite, Get>>
+        + Provider<Fork<RemoteSite, Resolve>>
+        + ConditionalSync
+        + 'static,
+{
+    async fn execute(&self, input: TextSearch) -> Result<Vec<TextMatch>, DialogArtifactsError> {
+        self.branch
+            .claims()
+            .search(input)
+            .perform(self.operator)
+            .await
+    }
+}
+
 /// Minimal RFC-4180 CSV parse: quoted fields with embedded commas, doubled
 /// quotes, and newlines. The fixtures carry multi-line free text (post bodies,
 /// bug descriptions), so field splitting on `,` silently shreds them. Kept here
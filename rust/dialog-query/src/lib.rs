@@ -50,6 +50,8 @@ pub mod helpers;
 pub mod negation;
 /// Left-join projection realizing optional (`maybe`) concept fields.
 pub mod optional;
+/// Result ordering and paging for queries.
+pub mod order;
 /// Named parameter bindings for rule and formula applications.
 pub mod parameters;
 /// Query planner that compiles premises into execution plans.
@@ -69,6 +71,8 @@ pub mod resolver;
 pub mod rule;
 /// Schema system for describing parameter signatures.
 pub mod schema;
+/// Search premises over the full-text index.
+pub mod search;
 
 /// The evaluation scope (provider bundle).
 pub mod scope;
@@ -82,6 +86,8 @@ pub mod source;
 pub mod statement;
 /// Stream utilities for async query result iteration.
 pub mod stream;
+/// Textual notation for rules and premises.
+pub mod syntax;
 /// Term types for pattern matching with variables and constants.
 pub mod term;
 
@@ -107,6 +113,7 @@ pub use error::*;
 pub use formula::*;
 pub use negation::*;
 pub use optional::OptionalAttributeQuery;
+pub use order::{Direction, Order, OrderKey, Ordered};
 pub use parameters::*;
 pub use planner::*;
 pub use predicate::*;
@@ -118,10 +125,12 @@ pub use resolver::*;
 pub use rule::*;
 pub use schema::*;
 pub use scope::*;
+pub use search::*;
 pub use selection::*;
 pub use session::*;
 pub use statement::*;
 pub use stream::*;
+pub use syntax::{Program, Syntax};
 pub use term::*;
 pub use types::*;
 
@@ -129,4 +138,4 @@ pub use async_stream::try_stream;
 pub use dialog_capability::Provider;
 pub use dialog_common::ConditionalSync;
 pub use dialog_effects::archive;
-pub use dialog_macros::{Attribute, Concept, Formula};
+pub use dialog_macros::{Attribute, Concept, Formula, Record};
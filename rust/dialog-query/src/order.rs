@@ -0,0 +1,484 @@
+//! Result ordering and paging: the `order`, `limit` and `offset` a
+//! query's rows come back with.
+//!
+//! An [`Order`] sorts rows on one or more keys, each a variable read
+//! ascending or descending, then skips `offset` rows and returns at
+//! most `limit` of the rest. [`Ordered`] attaches an order to any
+//! [`Application`], and serializes as the query's own formal notation
+//! with the order's keys alongside:
+//!
+//! ```json
+//! {
+//!   "assert": { "with": { "name": { "the": "person/name", "as": "Text" } } },
+//!   "where": { "this": { "?": { "name": "person" } }, "name": { "?": { "name": "name" } } },
+//!   "order": [{ "by": { "?": { "name": "name" } }, "direction": "descending" }],
+//!   "limit": 10,
+//!   "offset": 20
+//! }
+//! ```
+//!
+//! # Semantics
+//!
+//! - **Keys** compare with the range-predicate ordering that
+//!   [`Aggregator::Min`](crate::Aggregator::Min) and
+//!   [`Max`](crate::Aggregator::Max) use: same-variant numeric
+//!   comparison, and the natural order of strings, symbols, bytes and
+//!   entities. Two present values that cannot be ordered against each
+//!   other (mixed types, NaN) are an [`EvaluationError::Order`].
+//! - **Absent** bindings sort after every present value, so they come
+//!   last ascending and first descending. A key variable the row does
+//!   not bind at all is an [`EvaluationError::UnboundVariable`].
+//! - **Ties** on every key keep the order the query produced them in,
+//!   which for a scan is index key order.
+//! - **Scope**: an order applies to the rows produced for each input
+//!   row. A query performed on its own has a single input row, so the
+//!   order covers all of its results.
+//!
+//! # Early termination
+//!
+//! Without keys, `limit` stops reading the query once the page is
+//! full. With keys every row has to be seen before the first can be
+//! returned, unless the rows already arrive sorted on the leading key:
+//! a scan yields its rows in index order, and a plan keeps the order
+//! of its first scan (see [`Conjunction::sorted_on`]). When the
+//! leading key is that variable, ascending, reading stops as soon as
+//! the page is full and the key moves past its last row. A query
+//! reports that order through
+//! [`Application::evaluate_ordered`].
+//!
+//! [`Conjunction::sorted_on`]: crate::Conjunction::sorted_on
+
+use core::cmp::Ordering;
+use core::pin::Pin;
+
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::error::EvaluationError;
+use crate::query::{Application, Output};
+use crate::reduce::order;
+use crate::selection::{Binding, Match, Selection};
+use crate::term::Term;
+use crate::try_stream;
+use crate::types::Any;
+
+/// Which way an [`OrderKey`] sorts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Direction {
+    /// Smallest first.
+    #[default]
+    Ascending,
+    /// Largest first.
+    Descending,
+}
+
+impl Direction {
+    fn is_ascending(&self) -> bool {
+        matches!(self, Direction::Ascending)
+    }
+}
+
+/// One key rows are sorted on: a variable and a direction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderKey {
+    /// The variable whose binding the rows are compared by.
+    pub by: Term<Any>,
+    /// Which way the key sorts. Ascending when omitted.
+    #[serde(default, skip_serializing_if = "Direction::is_ascending")]
+    pub direction: Direction,
+}
+
+impl OrderKey {
+    /// Compare two bindings of this key, in its direction.
+    fn compare(&self, left: &Binding, right: &Binding) -> Result<Ordering, EvaluationError> {
+        let ordering = match (left, right) {
+            (Binding::Present(left), Binding::Present(right)) => {
+                order(left, right).ok_or_else(|| EvaluationError::Order {
+                    variable: self.by.to_string(),
+                    reason: format!("cannot order {left:?} against {right:?}"),
+                })?
+            }
+            (Binding::Present(_), Binding::Absent) => Ordering::Less,
+            (Binding::Absent, Binding::Present(_)) => Ordering::Greater,
+            (Binding::Absent, Binding::Absent) => Ordering::Equal,
+        };
+        Ok(match self.direction {
+            Direction::Ascending => ordering,
+            Direction::Descending => ordering.reverse(),
+        })
+    }
+}
+
+/// The order, limit and offset a query's rows are returned with.
+///
+/// The default order is the identity: no keys, no limit, no offset.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Order {
+    /// The keys rows are sorted on, most significant first.
+    #[serde(rename = "order", default, skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<OrderKey>,
+    /// The most rows to return.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// How many sorted rows to skip before the first one returned.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub offset: usize,
+}
+
+fn is_zero(offset: &usize) -> bool {
+    *offset == 0
+}
+
+impl Order {
+    /// The identity order.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sort on `by` in `direction`, after any keys already given.
+    pub fn by(mut self, by: impl Into<Term<Any>>, direction: Direction) -> Self {
+        self.keys.push(OrderKey {
+            by: by.into(),
+            direction,
+        });
+        self
+    }
+
+    /// Return at most `limit` rows.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skip the first `offset` rows.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Whether this order returns every row as it comes.
+    pub fn is_identity(&self) -> bool {
+        self.keys.is_empty() && self.limit.is_none() && self.offset == 0
+    }
+
+    /// Whether the leading key reads `variable` ascending, so rows
+    /// already sorted on `variable` are sorted on it too.
+    pub fn leads_with(&self, variable: &str) -> bool {
+        self.keys
+            .first()
+            .is_some_and(|key| key.direction.is_ascending() && key.by.name() == Some(variable))
+    }
+
+    /// Apply this order to `rows`. When `presorted`, the rows must
+    /// arrive ascending on the leading key, and reading stops once no
+    /// later row can land on the page.
+    pub fn select<'a>(self, rows: impl Selection + 'a, presorted: bool) -> impl Selection + 'a {
+        try_stream! {
+            let mut rows: Pin<Box<dyn Selection + 'a>> = Box::pin(rows);
+            let end = self.limit.map(|limit| self.offset.saturating_add(limit));
+            if self.keys.is_empty() {
+                let mut index = 0;
+                while end.is_none_or(|end| index < end) {
+                    let Some(row) = rows.try_next().await? else {
+                        break;
+                    };
+                    if index >= self.offset {
+                        yield row;
+                    }
+                    index += 1;
+                }
+            } else {
+                let mut sorted: Vec<(Vec<Binding>, Match)> = Vec::new();
+                while let Some(row) = rows.try_next().await? {
+                    let key = self.key(&row)?;
+                    // Rows arrive ascending on the leading key, so once it
+                    // moves past the last row of a full page no later row
+                    // can sort before it.
+                    if presorted
+                        && let Some(end) = end
+                        && sorted.len() >= end
+                        && let Some((last, _)) = sorted.last()
+                        && self.keys[0].compare(&key[0], &last[0])? == Ordering::Greater
+                    {
+                        break;
+                    }
+                    sorted.push((key, row));
+                }
+
+                let mut failure = None;
+                sorted.sort_by(|(left, _), (right, _)| {
+                    self.compare(left, right).unwrap_or_else(|error| {
+                        failure.get_or_insert(error);
+                        Ordering::Equal
+                    })
+                });
+                if let Some(error) = failure {
+                    Err(error)?;
+                }
+
+                let page = sorted
+                    .into_iter()
+                    .skip(self.offset)
+                    .take(self.limit.unwrap_or(usize::MAX));
+                for (_, row) in page {
+                    yield row;
+                }
+            }
+        }
+    }
+
+    /// The bindings of each key in `row`.
+    fn key(&self, row: &Match) -> Result<Vec<Binding>, EvaluationError> {
+        self.keys.iter().map(|key| row.lookup(&key.by)).collect()
+    }
+
+    /// Compare two rows' keys, most significant first.
+    fn compare(&self, left: &[Binding], right: &[Binding]) -> Result<Ordering, EvaluationError> {
+        for ((key, left), right) in self.keys.iter().zip(left).zip(right) {
+            match key.compare(left, right)? {
+                Ordering::Equal => continue,
+                ordering => return Ok(ordering),
+            }
+        }
+        Ok(Ordering::Equal)
+    }
+}
+
+/// A query whose results are returned in an [`Order`].
+///
+/// Serializes as the query's formal notation with the order's `order`,
+/// `limit` and `offset` keys added to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ordered<Q> {
+    /// The query being ordered.
+    #[serde(flatten)]
+    pub query: Q,
+    /// The order its results are returned in.
+    #[serde(flatten)]
+    pub order: Order,
+}
+
+impl<Q> Ordered<Q> {
+    /// Return `query`'s results in `order`.
+    pub fn new(query: Q, order: Order) -> Self {
+        Self { query, order }
+    }
+
+    /// Sort on `by` in `direction`, after any keys already given.
+    pub fn order_by(mut self, by: impl Into<Term<Any>>, direction: Direction) -> Self {
+        self.order = self.order.by(by, direction);
+        self
+    }
+
+    /// Return at most `limit` results.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.order = self.order.limit(limit);
+        self
+    }
+
+    /// Skip the first `offset` results.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.order = self.order.offset(offset);
+        self
+    }
+}
+
+impl<Q: Application> Ordered<Q> {
+    /// Execute this query against the given environment.
+    pub fn perform<'a, Env>(self, env: &'a Env) -> impl Output<Q::Conclusion> + 'a
+    where
+        Env: crate::Scope<'a>,
+    {
+        Application::perform(self, env)
+    }
+}
+
+impl<Q> From<Q> for Ordered<Q> {
+    fn from(query: Q) -> Self {
+        Self::new(query, Order::new())
+    }
+}
+
+// Neither `restrict` nor `concept` is forwarded: a page of the results
+// is not the union of per-entity pages, so incremental maintainers
+// re-evaluate an ordered query whole.
+impl<Q: Application> Application for Ordered<Q> {
+    type Conclusion = Q::Conclusion;
+
+    fn evaluate<'a, Env, M: Selection + 'a>(self, selection: M, env: &'a Env) -> impl Selection + 'a
+    where
+        Env: crate::Scope<'a>,
+    {
+        let Ordered { query, order } = self;
+        try_stream! {
+            for await input in selection {
+                let input = input?;
+                for await row in query.clone().evaluate_ordered(input, order.clone(), env) {
+                    yield row?;
+                }
+            }
+        }
+    }
+
+    fn realize(&self, input: Match) -> Result<Self::Conclusion, EvaluationError> {
+        self.query.realize(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::artifact::Value;
+    use core::sync::atomic::{AtomicUsize, Ordering as Atomic};
+    use futures_util::StreamExt;
+    use futures_util::stream::iter;
+
+    /// A row binding each `(name, value)` pair Present and each
+    /// `(name, None)` Absent.
+    fn row(bindings: &[(&str, Option<Value>)]) -> Match {
+        let mut row = Match::new();
+        for (name, value) in bindings {
+            let term: Term<Any> = Term::var(*name);
+            match value {
+                Some(value) => row.bind(&term, value.clone()).unwrap(),
+                None => row.bind_absent(&term).unwrap(),
+            }
+        }
+        row
+    }
+
+    fn var(name: &str) -> Term<Any> {
+        Term::var(name)
+    }
+
+    fn text(value: &str) -> Option<Value> {
+        Some(Value::String(value.to_string()))
+    }
+
+    fn number(value: u128) -> Option<Value> {
+        Some(Value::UnsignedInt(value))
+    }
+
+    async fn names(order: Order, rows: Vec<Match>) -> Result<Vec<Value>, EvaluationError> {
+        let selection = iter(rows.into_iter().map(Ok));
+        order
+            .select(selection, false)
+            .map(|row| row?.lookup(&Term::var("name"))?.content())
+            .try_collect()
+            .await
+    }
+
+    fn people() -> Vec<Match> {
+        vec![
+            row(&[
+                ("name", text("ann")),
+                ("dept", text("ops")),
+                ("pay", number(3)),
+            ]),
+            row(&[
+                ("name", text("bob")),
+                ("dept", text("dev")),
+                ("pay", number(5)),
+            ]),
+            row(&[("name", text("cat")), ("dept", text("ops")), ("pay", None)]),
+            row(&[
+                ("name", text("dan")),
+                ("dept", text("dev")),
+                ("pay", number(7)),
+            ]),
+            row(&[
+                ("name", text("eve")),
+                ("dept", text("ops")),
+                ("pay", number(9)),
+            ]),
+        ]
+    }
+
+    fn listed(names: &[&str]) -> Vec<Value> {
+        names.iter().map(|name| text(name).unwrap()).collect()
+    }
+
+    /// Keys apply most significant first, each in its own direction,
+    /// and an Absent binding sorts after every present value.
+    #[dialog_common::test]
+    async fn it_orders_on_several_keys() -> anyhow::Result<()> {
+        let order = Order::new()
+            .by(var("dept"), Direction::Ascending)
+            .by(var("pay"), Direction::Descending);
+        assert_eq!(
+            names(order, people()).await?,
+            listed(&["dan", "bob", "cat", "eve", "ann"])
+        );
+
+        let order = Order::new().by(var("pay"), Direction::Ascending);
+        assert_eq!(
+            names(order, people()).await?,
+            listed(&["ann", "bob", "dan", "eve", "cat"])
+        );
+        Ok(())
+    }
+
+    /// `offset` skips sorted rows and `limit` caps the rest; without
+    /// keys rows keep the order they came in.
+    #[dialog_common::test]
+    async fn it_pages_through_rows() -> anyhow::Result<()> {
+        let order = Order::new()
+            .by(var("name"), Direction::Descending)
+            .offset(1)
+            .limit(2);
+        assert_eq!(names(order, people()).await?, listed(&["dan", "cat"]));
+
+        let order = Order::new().offset(3).limit(5);
+        assert_eq!(names(order, people()).await?, listed(&["dan", "eve"]));
+        Ok(())
+    }
+
+    /// A presorted stream is read only until its leading key moves past
+    /// the last row of a full page; rows tied with that row still count.
+    #[dialog_common::test]
+    async fn it_stops_reading_a_presorted_stream_once_the_page_is_full() -> anyhow::Result<()> {
+        let read = AtomicUsize::new(0);
+        let rows = [1, 2, 2, 3, 4, 5]
+            .map(|rank| row(&[("rank", number(rank)), ("name", text(&format!("r{rank}")))]));
+        let selection = iter(rows).map(|row| {
+            read.fetch_add(1, Atomic::Relaxed);
+            Ok(row)
+        });
+
+        let order = Order::new()
+            .by(var("rank"), Direction::Ascending)
+            .by(var("name"), Direction::Descending)
+            .limit(2);
+        let page: Vec<Match> = order.select(selection, true).try_collect().await?;
+
+        let ranks = page
+            .iter()
+            .map(|row| row.lookup(&Term::var("rank"))?.content())
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(ranks, vec![number(1).unwrap(), number(2).unwrap()]);
+        assert_eq!(
+            read.load(Atomic::Relaxed),
+            4,
+            "stops at the first rank past 2"
+        );
+        Ok(())
+    }
+
+    /// Values that cannot be ordered against each other are an error
+    /// rather than an arbitrary order.
+    #[dialog_common::test]
+    async fn it_rejects_values_it_cannot_order() -> anyhow::Result<()> {
+        let rows = vec![row(&[("name", text("ann"))]), row(&[("name", number(1))])];
+        let order = Order::new().by(var("name"), Direction::Ascending);
+        let result = names(order, rows).await;
+        assert!(
+            matches!(result, Err(EvaluationError::Order { .. })),
+            "got {result:?}"
+        );
+        Ok(())
+    }
+}
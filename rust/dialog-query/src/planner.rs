@@ -1,11 +1,16 @@
 mod conjunction;
 mod disjunction;
+mod explain;
 mod feasibility;
+mod merge;
 mod plan;
 
 pub use conjunction::*;
 pub use disjunction::*;
+pub use explain::*;
 pub(crate) use feasibility::categorize;
+pub use merge::MergeJoin;
+pub(crate) use merge::stages;
 pub use plan::*;
 
 use crate::error::{Infeasible, TypeError};
@@ -85,17 +90,20 @@ impl Planner {
             let premise = remaining.remove(index);
             // The variables already bound when this step runs, before
             // it contributes its own binds.
-            let step_env = bound.clone();
-            cost += step_cost;
-            bound.extend(&binds);
-            steps.push(Plan::lower(
-                premise,
-                Header {
-                    cost: step_cost,
-                    binds,
-                    env: step_env,
-                },
-            ));
+            let header = Header {
+                cost: step_cost,
+                binds,
+                env: bound.clone(),
+            };
+            // A scan sorted like the run before it may be cheaper to
+            // merge with that run than to probe for each of its rows.
+            let step = match plan::merge(&steps, &premise, &header) {
+                Some(merged) => merged,
+                None => Plan::lower(premise, header),
+            };
+            cost += step.cost();
+            bound.extend(step.binds());
+            steps.push(step);
         }
 
         let mut binds = Environment::new();
@@ -540,7 +548,9 @@ mod plan_ordering {
                 Plan::Constraint(..) => "constraint",
                 Plan::Concept(..) => "concept",
                 Plan::Resolver(..) => "resolver",
+                Plan::Search(..) => "search",
                 Plan::Negate(..) => "negate",
+                Plan::Merge(..) => "merge",
             })
             .collect()
     }
@@ -790,4 +800,78 @@ mod plan_ordering {
         let replanned = Planner::from(premises).plan(&scope).unwrap();
         assert_eq!(kinds(&plan), kinds(&replanned));
     }
+
+    /// Two scans that both read the attribute index in entity order
+    /// merge on the entity: the second scan is read once alongside the
+    /// first instead of probed for every row the first yields.
+    #[dialog_common::test]
+    fn it_merges_scans_sorted_on_their_shared_variable() {
+        let premises = vec![
+            attribute(Term::from(the!("person/name")), "person", "name"),
+            attribute(Term::from(the!("person/email")), "person", "email"),
+            attribute(Term::from(the!("person/phone")), "person", "phone"),
+        ];
+        let plan = Planner::from(premises.clone())
+            .plan(&Environment::new())
+            .unwrap();
+
+        assert_eq!(kinds(&plan), vec!["scan", "merge", "merge"]);
+        let Plan::Merge(header, join) = &plan.steps[1] else {
+            unreachable!();
+        };
+        assert_eq!(join.on, "person");
+        assert!(
+            join.scope.is_empty(),
+            "merged scans read with the run's scope"
+        );
+        assert!(
+            header.env.contains("person"),
+            "later steps still see the join variable bound"
+        );
+
+        // The merged reads are spread over the rows they join, so the
+        // plan costs less than reading each attribute whole.
+        assert!(plan.cost < 3 * plan.steps[0].cost());
+    }
+
+    /// A plan's rows keep the order of its first scan, but only an
+    /// entity or attribute order is one rows can be paged by.
+    #[dialog_common::test]
+    fn it_reports_the_entity_order_of_its_first_scan() {
+        let premises = vec![
+            attribute(Term::from(the!("person/name")), "person", "name"),
+            attribute(Term::from(the!("person/email")), "person", "email"),
+        ];
+        let plan = Planner::from(premises.clone())
+            .plan(&Environment::new())
+            .unwrap();
+        assert_eq!(plan.sorted_on().as_deref(), Some("person"));
+
+        // With the entity bound the scan is sorted on the value, whose
+        // key encoding is not the order values compare in.
+        let mut bound = Environment::new();
+        bound.add("person");
+        let plan = Planner::from(premises).plan(&bound).unwrap();
+        assert_eq!(plan.sorted_on(), None);
+    }
+
+    /// A scan led by a bound value yields few rows, so probing for each
+    /// of them is cheaper than reading a whole attribute to merge with.
+    #[dialog_common::test]
+    fn it_keeps_the_nested_loop_when_probing_is_cheaper() {
+        let alice: Premise = AttributeQuery::new(
+            Term::from(the!("person/name")),
+            Term::<Entity>::var("person"),
+            Term::from("Alice".to_string()).into(),
+            Term::var("cause"),
+            Some(Cardinality::One),
+        )
+        .into();
+        let email = attribute(Term::from(the!("person/email")), "person", "email");
+
+        let plan = Planner::from(vec![email, alice])
+            .plan(&Environment::new())
+            .unwrap();
+        assert_eq!(kinds(&plan), vec!["scan", "scan"]);
+    }
 }
@@ -1,6 +1,6 @@
-use super::Plan;
-use crate::Environment;
+use super::{Plan, stages};
 use crate::selection::Selection;
+use crate::{Environment, SortOrder};
 use core::pin::Pin;
 
 /// An ordered sequence of [`Plan`] steps produced by the query planner.
@@ -32,6 +32,7 @@ pub struct Conjunction {
 impl Conjunction {
     /// Evaluate this conjunction by executing all steps in order.
     /// Each step feeds its output as input to the next, building up bindings.
+    /// A scan and the merge steps after it run together, as one merge join.
     ///
     /// Returns `Pin<Box<...>>` because each step's output type depends on the
     /// previous step. Boxing erases the nesting from the type and keeps each
@@ -44,11 +45,33 @@ impl Conjunction {
     where
         Env: crate::Scope<'a>,
     {
-        self.steps.into_iter().fold(
+        stages(self.steps).into_iter().fold(
             Box::pin(selection) as Pin<Box<dyn Selection + 'a>>,
-            |selection, plan| Box::pin(plan.evaluate(selection, env)),
+            |selection, stage| stage.evaluate(selection, env),
         )
     }
+
+    /// The variable this plan's rows come back sorted on, ascending, for
+    /// a single input row: the sort order of its first step when that
+    /// step is a scan. Every later step extends the rows it receives one
+    /// at a time, in the order they arrive, so the order is kept.
+    ///
+    /// Only an entity or attribute order is reported: those sort in the
+    /// index the way they compare, while a value's key encoding need not.
+    pub fn sorted_on(&self) -> Option<String> {
+        let Some(Plan::Scan(header, query)) = self.steps.first() else {
+            return None;
+        };
+        match query.access(&header.env).1 {
+            SortOrder::On(name)
+                if query.of().name() == Some(name.as_str())
+                    || query.the().name() == Some(name.as_str()) =>
+            {
+                Some(name)
+            }
+            _ => None,
+        }
+    }
 }
 
 /// End-to-end regression tests for the optionality bug family: each
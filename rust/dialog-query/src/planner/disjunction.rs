@@ -41,6 +41,16 @@ impl Disjunction {
         }
     }
 
+    /// The variable a single alternative's rows come back sorted on,
+    /// as [`Conjunction::sorted_on`]. Several alternatives run
+    /// concurrently and interleave their rows, so they carry no order.
+    pub fn sorted_on(&self) -> Option<String> {
+        match self {
+            Self::Solo(join) => join.sorted_on(),
+            _ => None,
+        }
+    }
+
     /// Evaluate all alternatives, merging their result streams.
     ///
     /// Returns `Pin<Box<...>>` because Disjunction is recursive — Or holds a
@@ -0,0 +1,573 @@
+//! What the planner chose, and what running it did.
+//!
+//! [`Conjunction::explain`] renders an ordered plan step by step: the
+//! premise, the planner's cost estimate, and for fact scans the index
+//! the scan reads and the variable its output is sorted on. Concept
+//! steps expand into the plans of the rules that derive them, planned
+//! for the bindings the step runs with, exactly as evaluation would.
+//!
+//! [`Conjunction::analyze`] runs the plan as well and records, per
+//! step, the rows it produced, the node fetches counted by a
+//! [`Meter`], and the time spent. Steps are nested streams (each one
+//! polls the one before it), so a step's measurement is taken around
+//! its own polls and the previous step's share is subtracted. A merge
+//! join reads its scans side by side, so the scans it merges are
+//! measured together, on its last step.
+
+use core::fmt::{self, Display, Formatter};
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use dialog_capability::Provider;
+use dialog_common::ConditionalSync;
+use dialog_common::time::now;
+use futures_util::{Stream, TryStreamExt};
+
+use super::{Conjunction, Disjunction, Plan, stages};
+use crate::attribute::The;
+use crate::error::EvaluationError;
+use crate::selection::{Match, Selection};
+use crate::source::SelectRules;
+use crate::syntax::{sketch, sketch_term};
+use crate::{
+    ConceptDescriptor, ConceptRules, DynamicAttributeQuery, Entity, Environment, IndexOrder,
+    Premise, SortOrder, Term,
+};
+
+/// Counts the node fetches an evaluation performs, so
+/// [`Conjunction::analyze`] can attribute them to the steps that
+/// caused them.
+pub trait Meter: ConditionalSync {
+    /// Node fetches performed so far. Only differences are read, so
+    /// the count may start anywhere.
+    fn fetches(&self) -> u64;
+}
+
+/// A meter that counts nothing, for analyzing rows and time alone.
+impl Meter for () {
+    fn fetches(&self) -> u64 {
+        0
+    }
+}
+
+/// An ordered plan as the planner chose it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// The planner's total cost estimate.
+    pub cost: usize,
+    /// The steps, in execution order.
+    pub steps: Vec<ExplainedStep>,
+}
+
+/// One step of an [`Explanation`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplainedStep {
+    /// The premise this step evaluates, in the textual notation where
+    /// it has one.
+    pub premise: String,
+    /// The planner's cost estimate for the step.
+    pub estimate: usize,
+    /// The index a fact scan reads; `None` for steps that do not scan.
+    pub index: Option<IndexOrder>,
+    /// The variable the step's output is sorted on.
+    pub order: SortOrder,
+    /// The variable a merge step joins the steps before it on; `None`
+    /// for a step that runs per input row.
+    pub merged_on: Option<String>,
+    /// The variables the step binds.
+    pub binds: Environment,
+    /// How a concept step's rules are evaluated.
+    pub derivation: Option<Derivation>,
+    /// What running the step did, once analyzed.
+    pub actual: Option<Measurement>,
+}
+
+/// How the rules behind a concept step are evaluated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Derivation {
+    /// Top-down, each rule body planned for the bindings the step runs
+    /// with. Reducing rules read their whole body relation, so their
+    /// bodies are planned with nothing bound and folded before the
+    /// step's bindings join them.
+    TopDown {
+        /// The plans of the ordinary rules, unioned.
+        rules: Vec<Explanation>,
+        /// The plans of the reducing rules' bodies.
+        reducing: Vec<Explanation>,
+    },
+    /// The concept sits on a dependency cycle, so its component is
+    /// computed by semi-naive fixpoint and the step joins the result.
+    Fixpoint,
+}
+
+/// What evaluating a step did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Measurement {
+    /// Rows the step produced.
+    pub rows: u64,
+    /// Node fetches performed while the step was running, excluding
+    /// those of earlier steps.
+    pub fetches: u64,
+    /// Time spent in the step, excluding earlier steps.
+    pub elapsed: Duration,
+}
+
+impl Measurement {
+    /// This measurement without the share of a step nested inside it.
+    fn without(self, inner: &Measurement) -> Self {
+        Self {
+            rows: self.rows,
+            fetches: self.fetches.saturating_sub(inner.fetches),
+            elapsed: self.elapsed.saturating_sub(inner.elapsed),
+        }
+    }
+}
+
+impl Conjunction {
+    /// Explain this plan, expanding concept steps into the plans of
+    /// their rules. Rules are discovered through `env`, as evaluation
+    /// discovers them.
+    pub async fn explain<'a, Env>(&self, env: &'a Env) -> Result<Explanation, EvaluationError>
+    where
+        Env: crate::Scope<'a>,
+    {
+        // Expanding a concept can reveal further concepts, so discovery
+        // repeats until every concept the plan reaches has its rules.
+        let mut rules = HashMap::new();
+        loop {
+            let mut missing = Vec::new();
+            let explanation = describe(self, &rules, &mut missing);
+            if missing.is_empty() {
+                return Ok(explanation);
+            }
+            for predicate in missing {
+                let this = predicate.this();
+                let found = Provider::<SelectRules>::execute(env, predicate).await?;
+                rules.insert(this, found);
+            }
+        }
+    }
+
+    /// Run this plan over `selection` and explain it with what each
+    /// step did. Concept steps are measured as a whole; their rules are
+    /// explained but not measured individually.
+    pub async fn analyze<'a, Env, M>(
+        self,
+        selection: M,
+        env: &'a Env,
+        meter: &'a impl Meter,
+    ) -> Result<Explanation, EvaluationError>
+    where
+        Env: crate::Scope<'a>,
+        M: Selection + 'a,
+    {
+        let mut explanation = self.explain(env).await?;
+        let (output, tallies) = self.instrument(selection, env, meter);
+        output.try_for_each(|_| async { Ok(()) }).await?;
+        record(&mut explanation, &tallies);
+        Ok(explanation)
+    }
+
+    /// Evaluate this plan with each stage's output metered, returning
+    /// the output and a tally per step: `None` for a step measured with
+    /// the merge after it. Each tally includes the earlier steps'
+    /// share; [`measurements`] separates them.
+    pub(crate) fn instrument<'a, Env, M>(
+        self,
+        selection: M,
+        env: &'a Env,
+        meter: &'a impl Meter,
+    ) -> (Pin<Box<dyn Selection + 'a>>, Vec<Option<Tally>>)
+    where
+        Env: crate::Scope<'a>,
+        M: Selection + 'a,
+    {
+        let mut tallies = Vec::with_capacity(self.steps.len());
+        let output = stages(self.steps).into_iter().fold(
+            Box::pin(selection) as Pin<Box<dyn Selection + 'a>>,
+            |selection, stage| {
+                let tally = Tally::default();
+                tallies.extend((1..stage.len()).map(|_| None));
+                tallies.push(Some(tally.clone()));
+                Box::pin(Metered {
+                    inner: stage.evaluate(selection, env),
+                    meter,
+                    tally,
+                })
+            },
+        );
+        (output, tallies)
+    }
+}
+
+/// The running measurement of one metered step, shared with its stream.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Tally(Arc<Mutex<Measurement>>);
+
+impl Tally {
+    fn read(&self) -> Measurement {
+        *self.0.lock().unwrap()
+    }
+}
+
+/// Per-step measurements from the tallies of an instrumented plan.
+pub(crate) fn measurements(tallies: &[Option<Tally>]) -> Vec<Option<Measurement>> {
+    let mut previous = Measurement::default();
+    tallies
+        .iter()
+        .map(|tally| {
+            let inclusive = tally.as_ref()?.read();
+            let own = inclusive.without(&previous);
+            previous = inclusive;
+            Some(own)
+        })
+        .collect()
+}
+
+/// Attach the measurements of an instrumented run to the steps of its
+/// explanation, returning the measurement of the run as a whole: the
+/// rows of its last step and the fetches and time of all of them.
+pub(crate) fn record(explanation: &mut Explanation, tallies: &[Option<Tally>]) -> Measurement {
+    for (step, actual) in explanation.steps.iter_mut().zip(measurements(tallies)) {
+        step.actual = actual;
+    }
+    tallies
+        .last()
+        .and_then(Option::as_ref)
+        .map(Tally::read)
+        .unwrap_or_default()
+}
+
+/// A step's output stream, measured around each poll.
+struct Metered<'a, M> {
+    inner: Pin<Box<dyn Selection + 'a>>,
+    meter: &'a M,
+    tally: Tally,
+}
+
+impl<M: Meter> Stream for Metered<'_, M> {
+    type Item = Result<Match, EvaluationError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let fetches = this.meter.fetches();
+        let started = now();
+        let poll = this.inner.as_mut().poll_next(cx);
+        let elapsed = now().duration_since(started).unwrap_or_default();
+        let mut tally = this.tally.0.lock().unwrap();
+        tally.fetches += this.meter.fetches().saturating_sub(fetches);
+        tally.elapsed += elapsed;
+        if let Poll::Ready(Some(Ok(_))) = &poll {
+            tally.rows += 1;
+        }
+        poll
+    }
+}
+
+/// Explain `plan` with the rules found so far, noting in `missing` the
+/// concepts whose rules have yet to be discovered.
+fn describe(
+    plan: &Conjunction,
+    rules: &HashMap<Entity, ConceptRules>,
+    missing: &mut Vec<ConceptDescriptor>,
+) -> Explanation {
+    let steps = plan
+        .steps
+        .iter()
+        .map(|step| {
+            let (index, order) = match step.access() {
+                Some((index, order)) => (Some(index), order),
+                None => (None, SortOrder::None),
+            };
+            let derivation = match step {
+                Plan::Concept(header, query) => match rules.get(&query.predicate.this()) {
+                    None => {
+                        missing.push(query.predicate.clone());
+                        None
+                    }
+                    Some(found) if found.recursion().is_some() => Some(Derivation::Fixpoint),
+                    Some(found) => Some(Derivation::TopDown {
+                        rules: alternatives(&found.plan_within(&query.terms, &header.env))
+                            .into_iter()
+                            .map(|rule| describe(rule, rules, missing))
+                            .collect(),
+                        reducing: found
+                            .reducing()
+                            .map(|rule| describe(&rule.plan(&Environment::new()), rules, missing))
+                            .collect(),
+                    }),
+                },
+                _ => None,
+            };
+            ExplainedStep {
+                premise: text(step),
+                estimate: step.cost(),
+                index,
+                order,
+                merged_on: match step {
+                    Plan::Merge(_, join) => Some(join.on.clone()),
+                    _ => None,
+                },
+                binds: step.binds().clone(),
+                derivation,
+                actual: None,
+            }
+        })
+        .collect();
+    Explanation {
+        cost: plan.cost,
+        steps,
+    }
+}
+
+/// The alternatives of a disjunction, in evaluation order.
+pub(crate) fn alternatives(plan: &Disjunction) -> Vec<&Conjunction> {
+    match plan {
+        Disjunction::Empty => Vec::new(),
+        Disjunction::Solo(only) => vec![only],
+        Disjunction::Duet(left, right) => vec![left, right],
+        Disjunction::Or(left, right) => {
+            let mut all = alternatives(left);
+            all.push(right);
+            all
+        }
+    }
+}
+
+/// A step's premise as an explanation prints it: in the textual
+/// notation without variable types, or as it displays when the
+/// notation has no form for it.
+fn text(step: &Plan) -> String {
+    match step {
+        Plan::Scan(_, query) => scan(query),
+        Plan::Merge(_, join) => scan(&join.query),
+        Plan::OptionalScan(_, query) => format!("optional {}", scan(query.query())),
+        Plan::Negate(_, inner) => format!("unless {}", text(inner)),
+        _ => match step.as_premise() {
+            Premise::Assert(proposition) => {
+                sketch(&proposition).unwrap_or_else(|| proposition.to_string())
+            }
+            premise => premise.to_string(),
+        },
+    }
+}
+
+/// `the of ?entity is ?value`
+fn scan(query: &DynamicAttributeQuery) -> String {
+    let the = match query.the() {
+        Term::Constant(the) => The::try_from(the.clone())
+            .map(|the| the.to_string())
+            .unwrap_or_else(|_| sketch_term(query.the())),
+        variable => sketch_term(variable),
+    };
+    format!(
+        "{the} of {} is {}",
+        sketch_term(query.of()),
+        sketch_term(query.is())
+    )
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "plan (cost {})", self.cost)?;
+        self.render(f, 1)
+    }
+}
+
+impl Explanation {
+    fn render(&self, f: &mut Formatter<'_>, depth: usize) -> fmt::Result {
+        let pad = "  ".repeat(depth);
+        for (position, step) in self.steps.iter().enumerate() {
+            let premise = step.premise.replace('\n', &format!("\n{pad}   "));
+            writeln!(f, "{pad}{}. {premise}", position + 1)?;
+            write!(f, "{pad}   estimate {}", step.estimate)?;
+            if let Some(index) = step.index {
+                write!(f, ", {index} index")?;
+                match step.order.variable() {
+                    Some(variable) => write!(f, ", sorted on ?{variable}")?,
+                    None => write!(f, ", unordered")?,
+                }
+            }
+            if let Some(variable) = &step.merged_on {
+                write!(f, ", merge join on ?{variable}")?;
+            }
+            if !step.binds.is_empty() {
+                write!(f, ", binds {}", step.binds)?;
+            }
+            writeln!(f)?;
+            if let Some(actual) = &step.actual {
+                writeln!(
+                    f,
+                    "{pad}   actual {} rows, {} fetches, {:?}",
+                    actual.rows, actual.fetches, actual.elapsed
+                )?;
+            }
+            match &step.derivation {
+                None => {}
+                Some(Derivation::Fixpoint) => {
+                    writeln!(f, "{pad}   derived by fixpoint over its dependency cycle")?;
+                }
+                Some(Derivation::TopDown { rules, reducing }) => {
+                    for (number, rule) in rules.iter().enumerate() {
+                        writeln!(f, "{pad}   rule {} (cost {})", number + 1, rule.cost)?;
+                        rule.render(f, depth + 2)?;
+                    }
+                    for (number, rule) in reducing.iter().enumerate() {
+                        writeln!(
+                            f,
+                            "{pad}   reducing rule {} (cost {})",
+                            number + 1,
+                            rule.cost
+                        )?;
+                        rule.render(f, depth + 2)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::attribute::query::AttributeQuery;
+    use crate::planner::Planner;
+    use crate::session::RuleRegistry;
+    use crate::source::test::TestEnv;
+    use crate::{Cardinality, Term, the};
+    use dialog_operator::helpers::{test_operator_with_profile, test_repo};
+
+    fn people() -> Conjunction {
+        let name = AttributeQuery::new(
+            Term::from(the!("person/name")),
+            Term::<Entity>::var("person"),
+            Term::<String>::var("name").into(),
+            Term::blank(),
+            Some(Cardinality::One),
+        );
+        let age = AttributeQuery::new(
+            Term::from(the!("person/age")),
+            Term::<Entity>::var("person"),
+            Term::<u32>::var("age").into(),
+            Term::blank(),
+            Some(Cardinality::One),
+        );
+        Planner::from(vec![Premise::from(name), Premise::from(age)])
+            .plan(&Environment::new())
+            .unwrap()
+    }
+
+    #[dialog_common::test]
+    async fn it_explains_the_index_and_order_of_each_scan() -> anyhow::Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        let source = TestEnv::new(&branch, &operator, RuleRegistry::new());
+
+        let alice = AttributeQuery::new(
+            Term::from(the!("person/name")),
+            Term::<Entity>::var("person"),
+            Term::from("Alice".to_string()).into(),
+            Term::blank(),
+            Some(Cardinality::One),
+        );
+        let age = AttributeQuery::new(
+            Term::from(the!("person/age")),
+            Term::<Entity>::var("person"),
+            Term::<u32>::var("age").into(),
+            Term::blank(),
+            Some(Cardinality::One),
+        );
+        let plan = Planner::from(vec![Premise::from(age), Premise::from(alice)])
+            .plan(&Environment::new())?;
+        let explanation = plan.explain(&source).await?;
+
+        // The name lookup has a value to go on; the age scan then runs
+        // with the entity it bound.
+        let [first, second] = explanation.steps.as_slice() else {
+            panic!("expected two steps, got {explanation}");
+        };
+        assert_eq!(first.index, Some(IndexOrder::Vae));
+        assert_eq!(first.order, SortOrder::On("person".into()));
+        assert_eq!(second.index, Some(IndexOrder::Eav));
+        assert!(second.order.variable().is_some_and(|name| name != "person"));
+        assert_eq!(second.merged_on, None);
+        assert_eq!(explanation.cost, first.estimate + second.estimate);
+
+        let rendered = explanation.to_string();
+        assert!(
+            rendered.contains("VAE index, sorted on ?person"),
+            "{rendered}"
+        );
+        assert!(rendered.contains("EAV index"), "{rendered}");
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_explains_a_merge_join() -> anyhow::Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        let source = TestEnv::new(&branch, &operator, RuleRegistry::new());
+
+        let explanation = people().explain(&source).await?;
+
+        // Both scans read the attribute index in entity order, so the
+        // second is merged with the first rather than probed per row.
+        let [first, second] = explanation.steps.as_slice() else {
+            panic!("expected two steps, got {explanation}");
+        };
+        assert_eq!(first.index, Some(IndexOrder::Aev));
+        assert_eq!(second.index, Some(IndexOrder::Aev));
+        assert_eq!(second.order, SortOrder::On("person".into()));
+        assert_eq!(second.merged_on.as_deref(), Some("person"));
+
+        let rendered = explanation.to_string();
+        assert!(
+            rendered.contains("AEV index, sorted on ?person, merge join on ?person"),
+            "{rendered}"
+        );
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_analyzes_the_rows_each_step_produced() -> anyhow::Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        let alice = Entity::new()?;
+        let bob = Entity::new()?;
+        branch
+            .transaction()
+            .assert(
+                the!("person/name")
+                    .of(alice.clone())
+                    .is("Alice".to_string()),
+            )
+            .assert(the!("person/age").of(alice.clone()).is(30u32))
+            .assert(the!("person/name").of(bob.clone()).is("Bob".to_string()))
+            .commit()
+            .perform(&operator)
+            .await?;
+        let source = TestEnv::new(&branch, &operator, RuleRegistry::new());
+
+        let explanation = people().analyze(Match::new().seed(), &source, &()).await?;
+
+        // The merged scans are measured together, on the merge step.
+        let rows: Vec<Option<u64>> = explanation
+            .steps
+            .iter()
+            .map(|step| step.actual.map(|actual| actual.rows))
+            .collect();
+        assert_eq!(rows, vec![None, Some(1)], "{explanation}");
+        assert!(explanation.to_string().contains("actual 1 rows"));
+        Ok(())
+    }
+}
@@ -0,0 +1,303 @@
+//! Merge joins over scans that read their index in the same order.
+//!
+//! A nested-loop join probes the index once per row it receives. When a
+//! scan and the run of steps before it both yield their rows sorted on
+//! the variable they share (see [`SortOrder`](crate::SortOrder)), the
+//! planner lowers the scan to a [`Plan::Merge`] instead, and the two are
+//! read side by side in a single pass, matching rows with equal keys.
+//!
+//! The rows of a run are only sorted within the rows produced for one
+//! input, so the merge restarts both sides per input row, exactly as the
+//! nested loop resolves its probes per input row.
+
+use core::cmp::Ordering;
+use core::pin::Pin;
+
+use futures_util::TryStreamExt;
+
+use super::Plan;
+use crate::query::Application;
+use crate::selection::{Match, Selection};
+use crate::types::Any;
+use crate::{DynamicAttributeQuery, Environment, EvaluationError, Term, Type, Value, try_stream};
+
+/// A scan merged with the sorted run of steps before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeJoin {
+    /// The scan, read whole for each input row of the run.
+    pub query: DynamicAttributeQuery,
+    /// The variable both sides are sorted on and matched by.
+    pub on: String,
+    /// The variables bound when the run starts. The scan is read with
+    /// only these bound, so it reads the index in the order it was
+    /// planned for whatever else the input row carries.
+    pub scope: Environment,
+}
+
+/// Steps that evaluate together: a step on its own, or a scan and the
+/// merge joins that read alongside it.
+// Stages are built and consumed once per evaluation, like the plans
+// they hold; boxing the common case would buy nothing.
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum Stage {
+    /// A step evaluated over the output of the stage before it.
+    Step(Plan),
+    /// A scan and the scans merged with it on its sort order.
+    Merge(Box<DynamicAttributeQuery>, Vec<MergeJoin>),
+}
+
+impl Stage {
+    /// How many plan steps the stage covers.
+    pub fn len(&self) -> usize {
+        match self {
+            Stage::Step(_) => 1,
+            Stage::Merge(_, joins) => 1 + joins.len(),
+        }
+    }
+
+    /// Evaluate the stage over `selection`.
+    pub fn evaluate<'a, Env, M: Selection + 'a>(
+        self,
+        selection: M,
+        env: &'a Env,
+    ) -> Pin<Box<dyn Selection + 'a>>
+    where
+        Env: crate::Scope<'a>,
+    {
+        match self {
+            Stage::Step(plan) => Box::pin(plan.evaluate(selection, env)),
+            Stage::Merge(scan, joins) => Box::pin(merge_join(*scan, joins, selection, env)),
+        }
+    }
+}
+
+/// Group plan steps into stages: each scan followed by merge steps
+/// becomes one stage, every other step a stage of its own.
+pub(crate) fn stages(steps: Vec<Plan>) -> Vec<Stage> {
+    let mut stages: Vec<Stage> = Vec::with_capacity(steps.len());
+    for step in steps {
+        match step {
+            Plan::Merge(header, join) => match stages.pop() {
+                Some(Stage::Step(Plan::Scan(_, scan))) => {
+                    stages.push(Stage::Merge(scan, vec![*join]));
+                }
+                Some(Stage::Merge(scan, mut joins)) => {
+                    joins.push(*join);
+                    stages.push(Stage::Merge(scan, joins));
+                }
+                // The planner only merges after a scan; anything else
+                // runs the step as the nested loop it replaced.
+                other => {
+                    stages.extend(other);
+                    stages.push(Stage::Step(Plan::Merge(header, join)));
+                }
+            },
+            step => stages.push(Stage::Step(step)),
+        }
+    }
+    stages
+}
+
+/// For each input row, read `scan` and every joined scan once and
+/// merge them on their shared sort variables.
+fn merge_join<'a, Env, M: Selection + 'a>(
+    scan: DynamicAttributeQuery,
+    joins: Vec<MergeJoin>,
+    selection: M,
+    env: &'a Env,
+) -> impl Selection + 'a
+where
+    Env: crate::Scope<'a>,
+{
+    try_stream! {
+        for await input in selection {
+            let input = input?;
+            let mut rows: Pin<Box<dyn Selection + 'a>> =
+                Box::pin(Application::evaluate(scan.clone(), input.clone().seed(), env));
+            for join in &joins {
+                let other: Pin<Box<dyn Selection + 'a>> = Box::pin(Application::evaluate(
+                    join.query.clone(),
+                    input.within(&join.scope).seed(),
+                    env,
+                ));
+                rows = Box::pin(merge(rows, other, Term::var(join.on.as_str())));
+            }
+            for await row in rows {
+                yield row?;
+            }
+        }
+    }
+}
+
+/// Join two streams sorted on `on`, holding only the right side's rows
+/// for the current key.
+fn merge<'a>(
+    mut left: Pin<Box<dyn Selection + 'a>>,
+    mut right: Pin<Box<dyn Selection + 'a>>,
+    on: Term<Any>,
+) -> impl Selection + 'a {
+    try_stream! {
+        let mut next = right.try_next().await?;
+        let mut group: Vec<Match> = Vec::new();
+        let mut group_key: Option<Vec<u8>> = None;
+        while let Some(row) = left.try_next().await? {
+            let key = ordinal(&row, &on)?;
+            if group_key.as_ref() != Some(&key) {
+                group.clear();
+                while let Some(candidate) = next.take() {
+                    match ordinal(&candidate, &on)?.cmp(&key) {
+                        Ordering::Less => next = right.try_next().await?,
+                        Ordering::Equal => {
+                            group.push(candidate);
+                            next = right.try_next().await?;
+                        }
+                        Ordering::Greater => {
+                            next = Some(candidate);
+                            break;
+                        }
+                    }
+                }
+                // Keys only grow, so once the right side is spent no
+                // later row can match.
+                if group.is_empty() && next.is_none() {
+                    break;
+                }
+                group_key = Some(key);
+            }
+            for candidate in &group {
+                if let Some(joined) = row.join(candidate) {
+                    yield joined;
+                }
+            }
+        }
+    }
+}
+
+/// The bytes a row's `on` binding sorts by in the index: the entity or
+/// attribute name, which index keys carry in order-preserving form.
+fn ordinal(row: &Match, on: &Term<Any>) -> Result<Vec<u8>, EvaluationError> {
+    match row.lookup(on)?.content()? {
+        Value::Entity(entity) => Ok(entity.as_str().as_bytes().to_vec()),
+        Value::Symbol(attribute) => Ok(attribute.as_str().as_bytes().to_vec()),
+        other => Err(EvaluationError::TypeMismatch {
+            expected: Type::Entity,
+            actual: other.data_type(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::attribute::query::AttributeQuery;
+    use crate::planner::{Conjunction, Planner};
+    use crate::session::RuleRegistry;
+    use crate::source::test::TestEnv;
+    use crate::{Cardinality, Entity, Premise, the};
+    use dialog_operator::helpers::{test_operator_with_profile, test_repo};
+    use futures_util::stream;
+
+    fn contacts() -> Conjunction {
+        let name = AttributeQuery::new(
+            Term::from(the!("person/name")),
+            Term::<Entity>::var("person"),
+            Term::<String>::var("name").into(),
+            Term::blank(),
+            Some(Cardinality::Many),
+        );
+        let email = AttributeQuery::new(
+            Term::from(the!("person/email")),
+            Term::<Entity>::var("person"),
+            Term::<String>::var("email").into(),
+            Term::blank(),
+            Some(Cardinality::Many),
+        );
+        Planner::from(vec![Premise::from(name), Premise::from(email)])
+            .plan(&Environment::new())
+            .unwrap()
+    }
+
+    fn pairs(rows: Vec<Match>) -> anyhow::Result<Vec<(Value, Value)>> {
+        let mut pairs = rows
+            .iter()
+            .map(|row| {
+                Ok((
+                    row.lookup(&Term::var("name"))?.content()?,
+                    row.lookup(&Term::var("email"))?.content()?,
+                ))
+            })
+            .collect::<Result<Vec<_>, EvaluationError>>()?;
+        pairs.sort_by(|left, right| format!("{left:?}").cmp(&format!("{right:?}")));
+        Ok(pairs)
+    }
+
+    /// A merge join yields exactly the rows the nested loop it replaces
+    /// would: entities missing either attribute drop out, and an entity
+    /// with several values on either side pairs each of them, once per
+    /// input row.
+    #[dialog_common::test]
+    async fn it_merges_to_the_rows_of_the_nested_loop() -> anyhow::Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let mut transaction = branch.transaction();
+        for index in 0..6 {
+            let person = Entity::new()?;
+            if index != 2 {
+                transaction = transaction.assert(
+                    the!("person/name")
+                        .of(person.clone())
+                        .is(format!("p{index}")),
+                );
+            }
+            if index != 4 {
+                transaction = transaction.assert(
+                    the!("person/email")
+                        .of(person.clone())
+                        .is(format!("p{index}@home")),
+                );
+            }
+            if index % 2 == 0 {
+                transaction = transaction.assert(
+                    the!("person/email")
+                        .of(person.clone())
+                        .is(format!("p{index}@work")),
+                );
+            }
+        }
+        transaction.commit().perform(&operator).await?;
+        let source = TestEnv::new(&branch, &operator, RuleRegistry::new());
+
+        let plan = contacts();
+        assert!(
+            matches!(plan.steps[1], Plan::Merge(..)),
+            "the email scan merges with the name scan"
+        );
+
+        let inputs = || stream::iter(vec![Ok(Match::new()), Ok(Match::new())]);
+        let merged: Vec<Match> = plan
+            .clone()
+            .evaluate(inputs(), &source)
+            .try_collect()
+            .await?;
+        let nested: Vec<Match> = plan
+            .steps
+            .into_iter()
+            .fold(
+                Box::pin(inputs()) as Pin<Box<dyn Selection + '_>>,
+                |selection, step| Box::pin(step.evaluate(selection, &source)),
+            )
+            .try_collect()
+            .await?;
+
+        // Person 2 has no name and person 4 no home email; 0 and 4 have
+        // a work email too, so five named people make six pairs.
+        assert_eq!(merged.len(), 2 * 6, "two input rows of six pairs each");
+        assert_eq!(pairs(merged)?, pairs(nested)?);
+        Ok(())
+    }
+}
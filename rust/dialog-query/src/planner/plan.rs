@@ -4,13 +4,16 @@ use crate::constraint::Constraint;
 use crate::formula::query::FormulaQuery;
 use crate::negation::Negation;
 use crate::optional::OptionalAttributeQuery;
+use crate::planner::MergeJoin;
 use crate::proposition::Proposition;
 use crate::query::Application;
 use crate::resolver::ResolverQuery;
 use crate::rule::types::TypeEnv;
+use crate::schema::{LOOKUP_COST, SEEK_COST};
+use crate::search::SearchQuery;
 use crate::selection::Selection;
 use crate::try_stream;
-use crate::{Environment, Parameters, Premise, Term};
+use crate::{Environment, IndexOrder, Parameters, Premise, SortOrder, Term};
 use auto_enums::auto_enum;
 use core::pin::Pin;
 use futures_util::TryStreamExt;
@@ -72,9 +75,16 @@ pub enum Plan {
     /// Resolver application: per input row, perform the resolver's
     /// idempotent effect through the environment and project rows.
     Resolver(Header, ResolverQuery),
+    /// Search application: per input row, perform the full-text
+    /// search through the environment and project one row per match.
+    Search(Header, SearchQuery),
     /// Negation as a filter: a match passes only if evaluating the
     /// inner plan against it produces no rows.
     Negate(Header, Box<Plan>),
+    /// Positive attribute lookup read in a single pass alongside the
+    /// scan before it, both sorted on the variable they join on. The
+    /// rows are those of a [`Scan`](Plan::Scan) of the same query.
+    Merge(Header, Box<MergeJoin>),
 }
 
 impl Plan {
@@ -87,7 +97,9 @@ impl Plan {
             Plan::Constraint(header, _) => header,
             Plan::Concept(header, _) => header,
             Plan::Resolver(header, _) => header,
+            Plan::Search(header, _) => header,
             Plan::Negate(header, _) => header,
+            Plan::Merge(header, _) => header,
         }
     }
 
@@ -110,6 +122,10 @@ impl Plan {
                 Premise::Assert(Proposition::Constraint(constraint.clone()))
             }
             Plan::Resolver(_, query) => Premise::Assert(Proposition::Resolver(query.clone())),
+            Plan::Search(_, query) => Premise::Assert(Proposition::Search(query.clone())),
+            Plan::Merge(_, join) => {
+                Premise::Assert(Proposition::Attribute(Box::new(join.query.clone())))
+            }
             Plan::Negate(_, inner) => match inner.as_premise() {
                 Premise::Assert(proposition) => Premise::Unless(Negation(proposition)),
                 // The inner plan is always lowered from a positive
@@ -135,6 +151,19 @@ impl Plan {
         &self.header().env
     }
 
+    /// The index a scan step reads and the variable its output is
+    /// sorted on, given the variables bound before it runs. `None` for
+    /// steps that do not read the fact index themselves.
+    pub fn access(&self) -> Option<(IndexOrder, SortOrder)> {
+        match self {
+            Plan::Scan(header, query) => Some(query.access(&header.env)),
+            Plan::OptionalScan(header, query) => Some(query.query().access(&header.env)),
+            Plan::Negate(_, inner) => inner.access(),
+            Plan::Merge(_, join) => Some(join.query.access(&join.scope)),
+            _ => None,
+        }
+    }
+
     /// Lower a syntactic premise into the matching compiled `Plan`
     /// variant, attaching the planning metadata `header`.
     pub(crate) fn lower(premise: Premise, header: Header) -> Self {
@@ -157,6 +186,7 @@ impl Plan {
             Proposition::Formula(query) => Plan::Formula(header, query),
             Proposition::Constraint(constraint) => Plan::Constraint(header, constraint),
             Proposition::Resolver(query) => Plan::Resolver(header, query),
+            Proposition::Search(query) => Plan::Search(header, query),
         }
     }
 
@@ -183,11 +213,78 @@ impl Plan {
             Plan::Formula(_, query) => query.evaluate(selection),
             Plan::Constraint(_, constraint) => constraint.evaluate(selection),
             Plan::Resolver(_, query) => query.evaluate(env, selection),
+            Plan::Search(_, query) => query.evaluate(env, selection),
             Plan::Negate(_, inner) => negate(*inner, selection, env),
+            // Outside the stage of its scan a merge step has nothing
+            // sorted to read alongside, so it probes per row.
+            Plan::Merge(_, join) => Application::evaluate(join.query, selection, env),
         }
     }
 }
 
+/// Rows a step with estimate `cost` is taken to yield per input row.
+/// Estimates carry no statistics, so the cost is read as a multiple of a
+/// single-segment lookup, which yields about one row.
+fn fanout(cost: usize) -> usize {
+    cost.div_ceil(LOOKUP_COST).max(1)
+}
+
+/// A merge join of `premise` with the run of steps ending `steps`, when
+/// the premise is a scan sorted on the same variable as the scan that
+/// leads the run and merging costs less than probing the index for it
+/// per row. `header` is the premise planned as that probe.
+///
+/// A nested loop pays a seek and the probe for every row the run
+/// yields; a merge reads the scan's whole range once, with the
+/// bindings the run started from. The merge step's cost spreads the
+/// read over the rows it joins, so it adds to the plan's total on the
+/// same per-row footing as the probe it replaces.
+pub(crate) fn merge(steps: &[Plan], premise: &Premise, header: &Header) -> Option<Plan> {
+    let Premise::Assert(Proposition::Attribute(query)) = premise else {
+        return None;
+    };
+    let start = steps
+        .iter()
+        .rposition(|step| !matches!(step, Plan::Merge(..)))?;
+    let Plan::Scan(lead, driving) = &steps[start] else {
+        return None;
+    };
+    let (_, SortOrder::On(on)) = driving.access(&lead.env) else {
+        return None;
+    };
+    if query.access(&lead.env).1 != SortOrder::On(on.clone()) {
+        return None;
+    }
+    // Entities and attribute names sort alike in every index they lead,
+    // but only the same slot on both sides carries the same encoding.
+    let slot = |query: &DynamicAttributeQuery| {
+        (
+            query.of().name() == Some(on.as_str()),
+            query.the().name() == Some(on.as_str()),
+        )
+    };
+    if slot(driving) != slot(query) {
+        return None;
+    }
+
+    let rows = fanout(lead.cost);
+    let nested = rows.saturating_mul(header.cost.saturating_add(SEEK_COST));
+    let merged = query.estimate(&lead.env)?.saturating_add(SEEK_COST);
+    (merged < nested).then(|| {
+        Plan::Merge(
+            Header {
+                cost: merged.div_ceil(rows),
+                ..header.clone()
+            },
+            Box::new(MergeJoin {
+                query: (**query).clone(),
+                on,
+                scope: lead.env.clone(),
+            }),
+        )
+    })
+}
+
 /// Filter a selection by a negated plan: keep each incoming match only
 /// when evaluating `inner` against it yields no rows.
 fn negate<'a, Env, M: Selection + 'a>(
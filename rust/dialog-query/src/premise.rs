@@ -6,6 +6,7 @@
 //! Note: Premises are only used in rule conditions (the "when" part), not in conclusions.
 
 pub use super::negation::Negation;
+use crate::concept::query::ConceptQuery;
 use crate::constraint::Constraint;
 use crate::environment::Environment;
 pub use crate::error::{AnalyzerError, QueryResult};
@@ -89,6 +90,12 @@ impl From<Constraint> for Premise {
     }
 }
 
+impl From<ConceptQuery> for Premise {
+    fn from(query: ConceptQuery) -> Self {
+        Premise::Assert(Proposition::Concept(query))
+    }
+}
+
 impl From<FormulaQuery> for Premise {
     fn from(application: FormulaQuery) -> Self {
         Premise::Assert(Proposition::Formula(application))
@@ -9,6 +9,7 @@ pub use crate::formula::query::FormulaQuery;
 use crate::optional::OptionalAttributeQuery;
 pub use crate::premise::{Negation, Premise};
 pub use crate::resolver::ResolverQuery;
+pub use crate::search::SearchQuery;
 pub use crate::{Environment, Parameters, Schema};
 use serde::de;
 use serde::ser;
@@ -48,9 +49,23 @@ pub enum Proposition {
     /// an idempotent effect through the evaluation environment (e.g.
     /// the `tree/*` inspection family).
     Resolver(ResolverQuery),
+    /// Search application: the values of a searchable attribute that
+    /// match a full-text query (the `text/*` family).
+    Search(SearchQuery),
 }
 
 impl Proposition {
+    /// Whether this proposition has a formal notation. Attribute lookups
+    /// are what concepts compile into; only a rule built from them
+    /// directly carries one, and such a rule has no descriptor to read
+    /// or print.
+    pub fn is_expressible(&self) -> bool {
+        !matches!(
+            self,
+            Proposition::Attribute(_) | Proposition::OptionalAttribute(_)
+        )
+    }
+
     /// Estimate the cost of this application given the current environment.
     /// Each application type knows how to calculate its cost based on what's bound.
     /// Returns None if the application cannot be executed without more constraints.
@@ -62,6 +77,7 @@ impl Proposition {
             Proposition::Formula(application) => application.estimate(env),
             Proposition::Constraint(constraint) => constraint.estimate(env),
             Proposition::Resolver(application) => application.estimate(env),
+            Proposition::Search(application) => application.estimate(env),
         }
     }
 
@@ -74,6 +90,7 @@ impl Proposition {
             Proposition::Formula(application) => application.parameters(),
             Proposition::Constraint(constraint) => constraint.parameters(),
             Proposition::Resolver(application) => application.parameters(),
+            Proposition::Search(application) => application.parameters(),
         }
     }
 
@@ -86,6 +103,7 @@ impl Proposition {
             Proposition::Formula(application) => application.schema(),
             Proposition::Constraint(constraint) => constraint.schema(),
             Proposition::Resolver(application) => application.schema(),
+            Proposition::Search(application) => application.schema(),
         }
     }
 
@@ -113,6 +131,12 @@ impl From<ResolverQuery> for Proposition {
     }
 }
 
+impl From<SearchQuery> for Proposition {
+    fn from(application: SearchQuery) -> Self {
+        Proposition::Search(application)
+    }
+}
+
 impl Display for Proposition {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -122,6 +146,7 @@ impl Display for Proposition {
             Proposition::Formula(application) => Display::fmt(application, f),
             Proposition::Constraint(constraint) => Display::fmt(constraint, f),
             Proposition::Resolver(application) => Display::fmt(application, f),
+            Proposition::Search(application) => Display::fmt(application, f),
         }
     }
 }
@@ -139,6 +164,7 @@ impl Serialize for Proposition {
             Proposition::Formula(fq) => fq.serialize(serializer),
             Proposition::Constraint(c) => c.serialize(serializer),
             Proposition::Resolver(pq) => pq.serialize(serializer),
+            Proposition::Search(sq) => sq.serialize(serializer),
             Proposition::Attribute(_) => Err(ser::Error::custom(
                 "Attribute propositions cannot be serialized in formal notation",
             )),
@@ -164,15 +190,17 @@ impl<'de> Deserialize<'de> for Proposition {
                 let cq: ConceptQuery = serde_json::from_value(raw).map_err(de::Error::custom)?;
                 Ok(Proposition::Concept(cq))
             }
-            // String → Constraint, resolver, or formula. Constraint
-            // and resolver names are fixed sets, tried in that order;
-            // FormulaQuery is the catchall fallback for any other
-            // formula name.
+            // String → Constraint, resolver, search, or formula.
+            // Constraint, resolver and search names are fixed sets,
+            // tried in that order; FormulaQuery is the catchall
+            // fallback for any other formula name.
             serde_json::Value::String(_) => {
                 if let Ok(constraint) = serde_json::from_value::<Constraint>(raw.clone()) {
                     Ok(Proposition::Constraint(constraint))
                 } else if let Ok(pq) = serde_json::from_value::<ResolverQuery>(raw.clone()) {
                     Ok(Proposition::Resolver(pq))
+                } else if let Ok(sq) = serde_json::from_value::<SearchQuery>(raw.clone()) {
+                    Ok(Proposition::Search(sq))
                 } else {
                     let fq: FormulaQuery =
                         serde_json::from_value(raw).map_err(de::Error::custom)?;
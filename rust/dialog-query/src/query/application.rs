@@ -4,6 +4,7 @@ use dialog_common::ConditionalSend;
 
 use crate::concept::descriptor::ConceptDescriptor;
 use crate::error::EvaluationError;
+use crate::order::Order;
 use crate::selection;
 use crate::selection::Match;
 
@@ -75,6 +76,23 @@ pub trait Application: Clone + ConditionalSend + 'static {
         None
     }
 
+    /// Evaluate this query over a single input row, returning its rows
+    /// in `order`. The default reads every row and sorts them; a query
+    /// whose plan yields rows already sorted on the leading key
+    /// overrides it to stop reading once the page is full.
+    fn evaluate_ordered<'a, Env>(
+        self,
+        input: Match,
+        order: Order,
+        env: &'a Env,
+    ) -> impl selection::Selection + 'a
+    where
+        Env: crate::Scope<'a>,
+        Self: Sized,
+    {
+        order.select(self.evaluate(input.seed(), env), false)
+    }
+
     /// Execute this query against an environment, returning a stream of typed results.
     fn perform<'a, Env>(self, env: &'a Env) -> impl Output<Self::Conclusion> + 'a
     where
@@ -47,7 +47,7 @@
 //!   comparison via [`Numeric::compare`] for numeric values —
 //!   without literal adaptation, because fold inputs are data —
 //!   extended across the COMPARABLE primitive set (strings, symbols,
-//!   bytes, entities order by their natural `Ord`). Any incomparable
+//!   bytes, entities, instants order by their natural `Ord`). Any incomparable
 //!   pair inside a group (mixed variants, NaN) is an error; A2 makes
 //!   that unconstructable statically, this is the runtime backstop.
 //!   Compare-equal but distinct representations (`-0.0` vs `0.0`)
@@ -56,11 +56,22 @@
 //! - **`avg`** is the `f64` mean of the present numeric values and
 //!   always returns [`Value::Float`]; because every input converts
 //!   to `f64` anyway, mixed numeric variants are permitted here.
+//!   `median`, `percentile`, `variance` and `std-dev` fold the same
+//!   `f64` samples: quantiles interpolate linearly between the
+//!   closest ranks, dispersion is the population statistic.
+//! - **`collect-set`** returns the distinct present values as a
+//!   [`Value::Record`] holding their dag-cbor array, ordered by each
+//!   value's bytes; **`string-agg`** joins the present strings in
+//!   string order. Both have an identity (the empty set, the empty
+//!   string).
+//! - **`arg-min`/`arg-max`** pair each present value with the
+//!   witness entity bound on the same row and report the entity at
+//!   the extreme; a tie resolves to the least entity.
 //! - **Empty groups do not exist**: groups arise from rows, so an
 //!   empty input stream yields zero output rows even with no
 //!   grouping fields. A group whose fold inputs are all Absent
-//!   yields the identity for `count`/`sum` and binds the output
-//!   field Absent for `min`/`max`/`avg`.
+//!   yields the identity for the folds that have one and binds the
+//!   output field Absent for the rest.
 //! - **Determinism**: groups are held in a `BTreeMap` over key bytes
 //!   and every fold buffers its column and folds it in a canonical
 //!   order (integers sort numerically, floats by `total_cmp`), so
@@ -86,7 +97,7 @@
 //! ```
 
 use std::cmp::Ordering;
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fmt::Display;
 use std::pin::pin;
@@ -94,7 +105,7 @@ use std::pin::pin;
 use futures_util::TryStreamExt;
 
 use crate::artifact::Type as ValueType;
-use crate::artifact::Value;
+use crate::artifact::{Entity, RecordList, RecordValue, Value};
 use crate::error::{EvaluationError, TypeError};
 use crate::formula::number::Numeric;
 use crate::selection::{Binding, Match, Selection};
@@ -105,10 +116,15 @@ use crate::types::Any;
 
 /// The fold applied to a reduced field's inputs, one group at a time.
 ///
-/// The phase-1 set from `notes/aggregation.md`. `median`, `variance`,
-/// `stddev` are addable behind this same enum; `rand`/`sample` are
-/// permanently excluded (nondeterministic in a convergent system).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+/// The phase-1 set from `notes/aggregation.md` plus the order
+/// statistics, dispersion, collection and witness folds.
+/// `rand`/`sample` are permanently excluded (nondeterministic in a
+/// convergent system).
+///
+/// Parameterized folds serialize externally tagged, so the notation
+/// reads `"apply": "median"` for a plain fold and
+/// `"apply": { "percentile": 0.9 }` for a parameterized one.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum Aggregator {
     /// Number of present bindings in the group.
@@ -125,6 +141,27 @@ pub enum Aggregator {
     Max,
     /// `f64` mean of the present numeric values.
     Avg,
+    /// The 50th percentile: [`Aggregator::Percentile`] at `0.5`.
+    Median,
+    /// The `p`-th quantile (`0.0 ..= 1.0`) of the present numeric
+    /// values, linearly interpolated between the closest ranks.
+    Percentile(f64),
+    /// Population variance of the present numeric values.
+    Variance,
+    /// Population standard deviation: the square root of
+    /// [`Aggregator::Variance`].
+    StdDev,
+    /// The distinct present values as a [`Value::Record`]: a
+    /// dag-cbor array ordered by each value's dag-cbor bytes.
+    CollectSet,
+    /// The present strings joined by the separator, in string order.
+    StringAgg(String),
+    /// The entity bound to the given term on the row holding the
+    /// least present value.
+    ArgMin(Term<Any>),
+    /// The entity bound to the given term on the row holding the
+    /// greatest present value.
+    ArgMax(Term<Any>),
 }
 
 impl Display for Aggregator {
@@ -136,6 +173,16 @@ impl Display for Aggregator {
             Aggregator::Min => "min",
             Aggregator::Max => "max",
             Aggregator::Avg => "avg",
+            Aggregator::Median => "median",
+            Aggregator::Percentile(p) => return write!(f, "percentile({p})"),
+            Aggregator::Variance => "variance",
+            Aggregator::StdDev => "std-dev",
+            Aggregator::CollectSet => "collect-set",
+            Aggregator::StringAgg(separator) => {
+                return write!(f, "string-agg({separator:?})");
+            }
+            Aggregator::ArgMin(witness) => return write!(f, "arg-min({witness})"),
+            Aggregator::ArgMax(witness) => return write!(f, "arg-max({witness})"),
         };
         write!(f, "{name}")
     }
@@ -144,21 +191,55 @@ impl Display for Aggregator {
 impl Aggregator {
     /// The type this fold's inputs must inhabit when present.
     ///
-    /// `sum`/`avg` consume the numeric band; `min`/`max` the
-    /// COMPARABLE set — exactly the range-predicate ordering they
-    /// fold with; `count`/`count-distinct` accept anything present.
-    /// Expressed as a [`Type`] so compatibility is a meet: an input
-    /// type can feed this fold iff the meet of its present part with
-    /// this requirement is non-empty.
-    pub fn input_requirement(self) -> Type {
+    /// `sum`/`avg` and the order statistics and dispersion folds
+    /// consume the numeric band; `min`/`max` and `arg-min`/`arg-max`
+    /// the COMPARABLE set — exactly the range-predicate ordering
+    /// they fold with; `string-agg` consumes strings;
+    /// `count`/`count-distinct`/`collect-set` accept anything
+    /// present. Expressed as a [`Type`] so compatibility is a meet:
+    /// an input type can feed this fold iff the meet of its present
+    /// part with this requirement is non-empty.
+    pub fn input_requirement(&self) -> Type {
         let primitive = match self {
-            Aggregator::Count | Aggregator::CountDistinct => Primitive::ALL,
-            Aggregator::Sum | Aggregator::Avg => Primitive::NUMERIC,
-            Aggregator::Min | Aggregator::Max => Primitive::COMPARABLE,
+            Aggregator::Count | Aggregator::CountDistinct | Aggregator::CollectSet => {
+                Primitive::ALL
+            }
+            Aggregator::Sum
+            | Aggregator::Avg
+            | Aggregator::Median
+            | Aggregator::Percentile(_)
+            | Aggregator::Variance
+            | Aggregator::StdDev => Primitive::NUMERIC,
+            Aggregator::Min | Aggregator::Max | Aggregator::ArgMin(_) | Aggregator::ArgMax(_) => {
+                Primitive::COMPARABLE
+            }
+            Aggregator::StringAgg(_) => Primitive::singleton(ValueType::String),
         };
         Type::from(primitive)
     }
 
+    /// The term whose binding `arg-min`/`arg-max` report: the
+    /// fold's second input, looked up on the same row as its value.
+    /// `None` for every other fold.
+    pub fn witness(&self) -> Option<&Term<Any>> {
+        match self {
+            Aggregator::ArgMin(witness) | Aggregator::ArgMax(witness) => Some(witness),
+            _ => None,
+        }
+    }
+
+    /// Why this fold's parameter is unusable, if it is: a
+    /// percentile outside `0.0 ..= 1.0` (NaN included) names no
+    /// rank.
+    pub fn parameter_fault(&self) -> Option<String> {
+        match self {
+            Aggregator::Percentile(p) if !(0.0..=1.0).contains(p) => {
+                Some(format!("percentile {p} is outside 0.0 ..= 1.0"))
+            }
+            _ => None,
+        }
+    }
+
     /// The output type of this fold over an input of the given type
     /// — the algebra from `notes/aggregation.md` — or `None` when
     /// the input cannot feed this fold at all: no present shape of
@@ -167,30 +248,42 @@ impl Aggregator {
     ///
     /// - `count`/`count-distinct` produce `UnsignedInt`, never
     ///   optional: the identity 0 exists, so even an optional input
-    ///   yields a present output.
+    ///   yields a present output. `collect-set` (identity: the
+    ///   empty set) produces `Record` and `string-agg` (identity:
+    ///   the empty string) produces `String` on the same terms.
     /// - `sum` produces the input's numeric band, never optional
     ///   (identity 0). The integer band stays integral — the `i128`
     ///   accumulator narrows per group to `UnsignedInt` or
     ///   `SignedInt`, so an input touching either integer type
     ///   admits both — and floats stay `Float`.
     /// - `min`/`max` produce the input type itself (the result is
-    ///   one of the inputs, so a refinement rides along); `avg`
-    ///   produces `Float`. These three have no identity: an
-    ///   optional input propagates `Nothing` into the output type.
-    ///   That propagation is what lets the existing
-    ///   `RequiredHeadFromOptional` check enforce — with no new
-    ///   analyzer rule — that a head field fed by `min`/`max`/`avg`
-    ///   over an optional input must itself be declared optional.
-    pub fn output_type(self, input: &Type) -> Option<Type> {
+    ///   one of the inputs, so a refinement rides along);
+    ///   `arg-min`/`arg-max` produce `Entity`; `avg`, `median`,
+    ///   `percentile`, `variance` and `std-dev` produce `Float`.
+    ///   These have no identity: an optional input propagates
+    ///   `Nothing` into the output type. That propagation is what
+    ///   lets the existing `RequiredHeadFromOptional` check enforce
+    ///   — with no new analyzer rule — that a head field fed by an
+    ///   identity-less fold over an optional input must itself be
+    ///   declared optional.
+    pub fn output_type(&self, input: &Type) -> Option<Type> {
         // What the fold consumes: the present shapes of the input
         // that meet the requirement.
         let consumed = input
             .clone()
             .required()
             .intersect(&self.input_requirement())?;
-        let optional = input.is_optional();
+        let identityless = |output: Type| {
+            if input.is_optional() {
+                output.optional()
+            } else {
+                output
+            }
+        };
         Some(match self {
             Aggregator::Count | Aggregator::CountDistinct => Type::from(ValueType::UnsignedInt),
+            Aggregator::CollectSet => Type::from(ValueType::Record),
+            Aggregator::StringAgg(_) => Type::from(ValueType::String),
             Aggregator::Sum => {
                 let integers = Primitive::singleton(ValueType::UnsignedInt)
                     .union(Primitive::singleton(ValueType::SignedInt));
@@ -204,17 +297,15 @@ impl Aggregator {
                 }
                 Type::from(band)
             }
-            Aggregator::Min | Aggregator::Max => {
-                if optional {
-                    consumed.optional()
-                } else {
-                    consumed
-                }
-            }
-            Aggregator::Avg => {
-                let mean = Type::from(ValueType::Float);
-                if optional { mean.optional() } else { mean }
+            Aggregator::Min | Aggregator::Max => identityless(consumed),
+            Aggregator::ArgMin(_) | Aggregator::ArgMax(_) => {
+                identityless(Type::from(ValueType::Entity))
             }
+            Aggregator::Avg
+            | Aggregator::Median
+            | Aggregator::Percentile(_)
+            | Aggregator::Variance
+            | Aggregator::StdDev => identityless(Type::from(ValueType::Float)),
         })
     }
 }
@@ -239,7 +330,7 @@ pub struct ReduceSpec {
 impl From<&ReduceEntry> for ReduceSpec {
     fn from(entry: &ReduceEntry) -> Self {
         ReduceSpec {
-            apply: entry.aggregator,
+            apply: entry.aggregator.clone(),
             of: entry.input.clone(),
         }
     }
@@ -283,7 +374,9 @@ impl ReduceEntry {
     /// of `input_type` meets the aggregator's requirement: `sum`
     /// over a `String` input, `min` over a non-comparable input, or
     /// any fold over a `Nothing`-only input is unwriteable here, at
-    /// construction, rather than a fold-time failure.
+    /// construction, rather than a fold-time failure. A parameter
+    /// the fold cannot use (a percentile outside `0.0 ..= 1.0`)
+    /// fails with [`TypeError::ReduceParameter`].
     pub fn try_new(
         field: impl Into<String>,
         aggregator: Aggregator,
@@ -291,11 +384,18 @@ impl ReduceEntry {
         input_type: &Type,
     ) -> Result<Self, TypeError> {
         let field = field.into();
+        if let Some(reason) = aggregator.parameter_fault() {
+            return Err(TypeError::ReduceParameter {
+                field,
+                aggregator: Box::new(aggregator),
+                reason,
+            });
+        }
         if aggregator.output_type(input_type).is_none() {
             return Err(TypeError::ReduceInput {
                 field,
-                aggregator,
                 required: Box::new(aggregator.input_requirement()),
+                aggregator: Box::new(aggregator),
                 actual: Box::new(input_type.clone()),
             });
         }
@@ -314,7 +414,7 @@ impl ReduceEntry {
             .output_type(input_type)
             .ok_or_else(|| TypeError::ReduceInput {
                 field: self.field.clone(),
-                aggregator: self.aggregator,
+                aggregator: Box::new(self.aggregator.clone()),
                 required: Box::new(self.aggregator.input_requirement()),
                 actual: Box::new(input_type.clone()),
             })
@@ -336,10 +436,13 @@ pub struct Reduce {
 }
 
 /// Buffered per-group state: the decoded key tuple and one column of
-/// present input values per reduce entry.
+/// present input values per reduce entry. An entry whose aggregator
+/// has a [witness](Aggregator::witness) buffers the witness beside
+/// each value, and only rows where both are present.
 struct Group {
     key: Vec<Option<Value>>,
     columns: Vec<Vec<Value>>,
+    witnesses: Vec<Vec<Value>>,
 }
 
 impl Reduce {
@@ -378,12 +481,24 @@ impl Reduce {
             let group = table.entry(key_bytes).or_insert_with(|| Group {
                 key,
                 columns: vec![Vec::new(); entries.len()],
+                witnesses: vec![Vec::new(); entries.len()],
             });
-            for (column, entry) in group.columns.iter_mut().zip(&entries) {
-                match row.lookup(&entry.input)? {
-                    Binding::Present(value) => column.push(value),
-                    Binding::Absent => {}
+            for ((column, witnesses), entry) in group
+                .columns
+                .iter_mut()
+                .zip(group.witnesses.iter_mut())
+                .zip(&entries)
+            {
+                let Binding::Present(value) = row.lookup(&entry.input)? else {
+                    continue;
+                };
+                if let Some(witness) = entry.aggregator.witness() {
+                    let Binding::Present(witness) = row.lookup(witness)? else {
+                        continue;
+                    };
+                    witnesses.push(witness);
                 }
+                column.push(value);
             }
         }
 
@@ -396,9 +511,11 @@ impl Reduce {
                     None => row.bind_absent(term)?,
                 }
             }
-            for (entry, column) in entries.iter().zip(group.columns) {
+            for ((entry, column), witnesses) in
+                entries.iter().zip(group.columns).zip(group.witnesses)
+            {
                 let out: Term<Any> = Term::var(entry.field.as_str());
-                match fold_column(entry, column)? {
+                match fold_column(entry, column, witnesses)? {
                     Some(value) => row.bind(&out, value)?,
                     None => row.bind_absent(&out)?,
                 }
@@ -437,25 +554,57 @@ fn fault(entry: &ReduceEntry, reason: impl Into<String>) -> EvaluationError {
     }
 }
 
-/// Fold one group's column of present values. `None` means the
-/// output field binds Absent (identity-less fold, no present input).
-fn fold_column(entry: &ReduceEntry, values: Vec<Value>) -> Result<Option<Value>, EvaluationError> {
-    match entry.aggregator {
+/// Fold one group's column of present values, with the witnesses
+/// paired to them for `arg-min`/`arg-max`. `None` means the output
+/// field binds Absent (identity-less fold, no present input).
+fn fold_column(
+    entry: &ReduceEntry,
+    values: Vec<Value>,
+    witnesses: Vec<Value>,
+) -> Result<Option<Value>, EvaluationError> {
+    if let Some(reason) = entry.aggregator.parameter_fault() {
+        return Err(fault(entry, reason));
+    }
+    match &entry.aggregator {
         Aggregator::Count => Ok(Some(Value::UnsignedInt(values.len() as u128))),
-        Aggregator::CountDistinct => {
-            let mut distinct = BTreeSet::new();
-            for value in &values {
-                distinct.insert(encode(value)?);
-            }
-            Ok(Some(Value::UnsignedInt(distinct.len() as u128)))
-        }
+        Aggregator::CountDistinct => Ok(Some(Value::UnsignedInt(distinct(values)?.len() as u128))),
         Aggregator::Sum => fold_sum(entry, values).map(Some),
         Aggregator::Min => fold_extremum(entry, values, Ordering::Less),
         Aggregator::Max => fold_extremum(entry, values, Ordering::Greater),
         Aggregator::Avg => fold_avg(entry, values),
+        Aggregator::Median => fold_percentile(entry, values, 0.5),
+        Aggregator::Percentile(p) => fold_percentile(entry, values, *p),
+        Aggregator::Variance => fold_variance(entry, values),
+        Aggregator::StdDev => Ok(
+            fold_variance(entry, values)?.map(|variance| match variance {
+                Value::Float(variance) => Value::Float(variance.sqrt()),
+                other => other,
+            }),
+        ),
+        Aggregator::CollectSet => {
+            let members: RecordList = distinct(values)?.into_values().collect();
+            let set =
+                RecordValue::encode(members).map_err(|error| EvaluationError::Serialization {
+                    message: error.to_string(),
+                })?;
+            Ok(Some(Value::Record(set)))
+        }
+        Aggregator::StringAgg(separator) => fold_join(entry, values, separator).map(Some),
+        Aggregator::ArgMin(_) => fold_witness(entry, values, witnesses, Ordering::Less),
+        Aggregator::ArgMax(_) => fold_witness(entry, values, witnesses, Ordering::Greater),
     }
 }
 
+/// The distinct values of a column keyed by their dag-cbor bytes —
+/// the identity `count-distinct` counts and `collect-set` collects.
+fn distinct(values: Vec<Value>) -> Result<BTreeMap<Vec<u8>, Value>, EvaluationError> {
+    let mut distinct = BTreeMap::new();
+    for value in values {
+        distinct.insert(encode(&value)?, value);
+    }
+    Ok(distinct)
+}
+
 /// Checked sum: integers in `i128`, floats in `f64`, never mixed.
 /// Folds in sorted order so overflow behavior and float rounding are
 /// independent of row order.
@@ -501,13 +650,9 @@ fn fold_sum(entry: &ReduceEntry, values: Vec<Value>) -> Result<Value, Evaluation
     }
 }
 
-/// `f64` mean of the present numeric values; `None` (Absent) over an
-/// empty column. Folds in `total_cmp` order for row-order-independent
-/// rounding.
-fn fold_avg(entry: &ReduceEntry, values: Vec<Value>) -> Result<Option<Value>, EvaluationError> {
-    if values.is_empty() {
-        return Ok(None);
-    }
+/// The present numeric values as `f64` samples, in `total_cmp`
+/// order so every fold over them rounds independently of row order.
+fn samples(entry: &ReduceEntry, values: Vec<Value>) -> Result<Vec<f64>, EvaluationError> {
     let mut samples = Vec::with_capacity(values.len());
     for value in values {
         match value {
@@ -520,21 +665,94 @@ fn fold_avg(entry: &ReduceEntry, values: Vec<Value>) -> Result<Option<Value>, Ev
         }
     }
     samples.sort_unstable_by(|a, b| a.total_cmp(b));
+    Ok(samples)
+}
+
+/// `f64` mean of the present numeric values; `None` (Absent) over an
+/// empty column.
+fn fold_avg(entry: &ReduceEntry, values: Vec<Value>) -> Result<Option<Value>, EvaluationError> {
+    let samples = samples(entry, values)?;
+    if samples.is_empty() {
+        return Ok(None);
+    }
     let count = samples.len() as f64;
     let total: f64 = samples.into_iter().sum();
     Ok(Some(Value::Float(total / count)))
 }
 
+/// The `p`-th quantile, linearly interpolated between the two
+/// closest ranks of the sorted samples (`median` is `p = 0.5`, so an
+/// even-sized group yields the mean of its middle pair). `None`
+/// (Absent) over an empty column.
+fn fold_percentile(
+    entry: &ReduceEntry,
+    values: Vec<Value>,
+    p: f64,
+) -> Result<Option<Value>, EvaluationError> {
+    let samples = samples(entry, values)?;
+    if samples.is_empty() {
+        return Ok(None);
+    }
+    let rank = p * (samples.len() - 1) as f64;
+    let below = samples[rank.floor() as usize];
+    let above = samples[rank.ceil() as usize];
+    Ok(Some(Value::Float(below + (above - below) * rank.fract())))
+}
+
+/// Population variance: the mean squared deviation from the mean.
+/// `None` (Absent) over an empty column; a single sample has
+/// variance `0.0`.
+fn fold_variance(
+    entry: &ReduceEntry,
+    values: Vec<Value>,
+) -> Result<Option<Value>, EvaluationError> {
+    let samples = samples(entry, values)?;
+    if samples.is_empty() {
+        return Ok(None);
+    }
+    let count = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / count;
+    let mut deviations: Vec<f64> = samples
+        .into_iter()
+        .map(|sample| (sample - mean) * (sample - mean))
+        .collect();
+    deviations.sort_unstable_by(|a, b| a.total_cmp(b));
+    Ok(Some(Value::Float(
+        deviations.into_iter().sum::<f64>() / count,
+    )))
+}
+
+/// The present strings in string order, joined by `separator`; the
+/// empty string for an all-absent group.
+fn fold_join(
+    entry: &ReduceEntry,
+    values: Vec<Value>,
+    separator: &str,
+) -> Result<Value, EvaluationError> {
+    let mut strings = Vec::with_capacity(values.len());
+    for value in values {
+        match value {
+            Value::String(string) => strings.push(string),
+            other => {
+                return Err(fault(entry, format!("non-string input {other:?}")));
+            }
+        }
+    }
+    strings.sort_unstable();
+    Ok(Value::String(strings.join(separator)))
+}
+
 /// The range-predicate ordering over data: same-variant numeric
 /// comparison via [`Numeric::compare`] (no literal adaptation — fold
 /// inputs are data), extended to the rest of the COMPARABLE set by
 /// each variant's natural order. `None` is an incomparable pair.
-fn order(a: &Value, b: &Value) -> Option<Ordering> {
+pub(crate) fn order(a: &Value, b: &Value) -> Option<Ordering> {
     match (a, b) {
         (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
         (Value::Symbol(a), Value::Symbol(b)) => Some(a.cmp(b)),
         (Value::Bytes(a), Value::Bytes(b)) => Some(a.cmp(b)),
         (Value::Entity(a), Value::Entity(b)) => Some(a.cmp(b)),
+        (Value::Instant(a), Value::Instant(b)) => Some(a.cmp(b)),
         _ => {
             let a = Numeric::try_from(a.clone()).ok()?;
             let b = Numeric::try_from(b.clone()).ok()?;
@@ -577,6 +795,44 @@ fn fold_extremum(
     Ok(Some(best))
 }
 
+/// `arg-min` (`target = Less`) / `arg-max` (`target = Greater`): the
+/// entity paired with the extreme value. Several rows tying on the
+/// extreme resolve to the least entity, so the pick is independent
+/// of row order. A witness that is not an entity, or an incomparable
+/// pair of values, errors.
+fn fold_witness(
+    entry: &ReduceEntry,
+    values: Vec<Value>,
+    witnesses: Vec<Value>,
+    target: Ordering,
+) -> Result<Option<Value>, EvaluationError> {
+    let mut best: Option<(Value, Entity)> = None;
+    for (candidate, witness) in values.into_iter().zip(witnesses) {
+        let Value::Entity(witness) = witness else {
+            return Err(fault(
+                entry,
+                format!("witness {witness:?} is not an entity"),
+            ));
+        };
+        let Some((value, entity)) = &best else {
+            best = Some((candidate, witness));
+            continue;
+        };
+        match order(&candidate, value) {
+            Some(Ordering::Equal) if witness < *entity => best = Some((candidate, witness)),
+            Some(ordering) if ordering == target => best = Some((candidate, witness)),
+            Some(_) => {}
+            None => {
+                return Err(fault(
+                    entry,
+                    format!("cannot order {candidate:?} against {value:?}"),
+                ));
+            }
+        }
+    }
+    Ok(best.map(|(_, entity)| Value::Entity(entity)))
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(target_arch = "wasm32")]
@@ -934,6 +1190,9 @@ mod tests {
                 ReduceEntry::new("greatest", Aggregator::Max, Term::var("x")),
                 ReduceEntry::new("n", Aggregator::Count, Term::var("x")),
                 ReduceEntry::new("kinds", Aggregator::CountDistinct, Term::var("x")),
+                ReduceEntry::new("middle", Aggregator::Median, Term::var("x")),
+                ReduceEntry::new("spread", Aggregator::Variance, Term::var("x")),
+                ReduceEntry::new("set", Aggregator::CollectSet, Term::var("x")),
             ]
         };
 
@@ -1077,6 +1336,167 @@ mod tests {
         assert_eq!(present(&rows[0], "n"), Value::UnsignedInt(2));
     }
 
+    #[dialog_common::test]
+    async fn it_folds_order_statistics() {
+        let reduce = Reduce::new(
+            vec![],
+            vec![
+                ReduceEntry::new("middle", Aggregator::Median, Term::var("x")),
+                ReduceEntry::new("p75", Aggregator::Percentile(0.75), Term::var("x")),
+                ReduceEntry::new("p0", Aggregator::Percentile(0.0), Term::var("x")),
+            ],
+        );
+        let rows = reduce
+            .fold(selection(
+                [4, 1, 3, 2]
+                    .into_iter()
+                    .map(|x| row(&[("x", Some(Value::UnsignedInt(x)))]))
+                    .collect(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            present(&rows[0], "middle"),
+            Value::Float(2.5),
+            "an even group interpolates its middle pair"
+        );
+        assert_eq!(present(&rows[0], "p75"), Value::Float(3.25));
+        assert_eq!(present(&rows[0], "p0"), Value::Float(1.0));
+    }
+
+    #[dialog_common::test]
+    async fn it_folds_population_variance_and_std_dev() {
+        let reduce = Reduce::new(
+            vec![],
+            vec![
+                ReduceEntry::new("variance", Aggregator::Variance, Term::var("x")),
+                ReduceEntry::new("deviation", Aggregator::StdDev, Term::var("x")),
+            ],
+        );
+        let rows = reduce
+            .fold(selection(
+                [2, 4, 4, 4, 5, 5, 7, 9]
+                    .into_iter()
+                    .map(|x| row(&[("x", Some(Value::SignedInt(x)))]))
+                    .chain([row(&[("x", None)])])
+                    .collect(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(present(&rows[0], "variance"), Value::Float(4.0));
+        assert_eq!(present(&rows[0], "deviation"), Value::Float(2.0));
+    }
+
+    /// `collect-set` and `string-agg` have identities: the all-absent
+    /// group yields the empty set and the empty string.
+    #[dialog_common::test]
+    async fn it_collects_and_joins_group_values() {
+        let entries = || {
+            vec![
+                ReduceEntry::new("set", Aggregator::CollectSet, Term::var("x")),
+                ReduceEntry::new(
+                    "joined",
+                    Aggregator::StringAgg(", ".to_string()),
+                    Term::var("x"),
+                ),
+            ]
+        };
+        let name = |name: &str| Some(Value::String(name.to_string()));
+        let rows = Reduce::new(vec![], entries())
+            .fold(selection(vec![
+                row(&[("x", name("pear"))]),
+                row(&[("x", name("apple"))]),
+                row(&[("x", name("pear"))]),
+                row(&[("x", None)]),
+            ]))
+            .await
+            .unwrap();
+        // Members order by dag-cbor bytes, where the shorter string's
+        // length header sorts first.
+        let members = RecordList::from(vec![
+            Value::String("pear".to_string()),
+            Value::String("apple".to_string()),
+        ]);
+        let Value::Record(set) = present(&rows[0], "set") else {
+            panic!("collect-set yields a record");
+        };
+        assert_eq!(*set.realize::<RecordList>().unwrap(), members);
+        assert_eq!(
+            present(&rows[0], "joined"),
+            Value::String("apple, pear, pear".to_string())
+        );
+
+        let rows = Reduce::new(vec![], entries())
+            .fold(selection(vec![row(&[("x", None)])]))
+            .await
+            .unwrap();
+        assert_eq!(
+            present(&rows[0], "set"),
+            Value::Record(RecordValue::encode(RecordList::new()).unwrap())
+        );
+        assert_eq!(present(&rows[0], "joined"), Value::String(String::new()));
+    }
+
+    /// `arg-min`/`arg-max` report the witness on the extreme row;
+    /// ties resolve to the least entity and rows with an Absent
+    /// witness are skipped.
+    #[dialog_common::test]
+    async fn it_reports_the_entity_at_the_extreme() {
+        let mut entities: [Entity; 4] = [(); 4].map(|_| Entity::new().unwrap());
+        entities.sort();
+        let [least, middle, greatest, skipped] = entities.map(|entity| Some(Value::Entity(entity)));
+        let reduce = Reduce::new(
+            vec![],
+            vec![
+                ReduceEntry::new(
+                    "cheapest",
+                    Aggregator::ArgMin(Term::var("e")),
+                    Term::var("x"),
+                ),
+                ReduceEntry::new(
+                    "dearest",
+                    Aggregator::ArgMax(Term::var("e")),
+                    Term::var("x"),
+                ),
+            ],
+        );
+        let rows = reduce
+            .fold(selection(vec![
+                row(&[("e", greatest.clone()), ("x", Some(Value::UnsignedInt(9)))]),
+                row(&[("e", middle.clone()), ("x", Some(Value::UnsignedInt(1)))]),
+                row(&[("e", least.clone()), ("x", Some(Value::UnsignedInt(9)))]),
+                row(&[("e", None), ("x", Some(Value::UnsignedInt(0)))]),
+                row(&[("e", skipped), ("x", None)]),
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(Some(present(&rows[0], "cheapest")), middle);
+        assert_eq!(
+            Some(present(&rows[0], "dearest")),
+            least,
+            "a tie on the extreme resolves to the least entity"
+        );
+
+        let reduce = Reduce::new(
+            vec![],
+            vec![ReduceEntry::new(
+                "dearest",
+                Aggregator::ArgMax(Term::var("e")),
+                Term::var("x"),
+            )],
+        );
+        let result = reduce
+            .fold(selection(vec![row(&[
+                ("e", Some(Value::UnsignedInt(1))),
+                ("x", Some(Value::UnsignedInt(1))),
+            ])]))
+            .await;
+        assert!(
+            matches!(result, Err(EvaluationError::Reduce { .. })),
+            "a non-entity witness must error, got {result:?}"
+        );
+    }
+
     fn typed(vt: ValueType) -> Type {
         Type::from(vt)
     }
@@ -1106,7 +1526,8 @@ mod tests {
             (Aggregator::CountDistinct, Type::nothing()),
         ];
         for (aggregator, input_type) in rejected {
-            let result = ReduceEntry::try_new("out", aggregator, Term::var("x"), &input_type);
+            let result =
+                ReduceEntry::try_new("out", aggregator.clone(), Term::var("x"), &input_type);
             match result {
                 Err(TypeError::ReduceInput {
                     field,
@@ -1115,7 +1536,7 @@ mod tests {
                     actual,
                 }) => {
                     assert_eq!(field, "out");
-                    assert_eq!(reported, aggregator);
+                    assert_eq!(*reported, aggregator);
                     assert_eq!(*required, aggregator.input_requirement());
                     assert_eq!(*actual, input_type);
                 }
@@ -1136,7 +1557,8 @@ mod tests {
                 typed(ValueType::Record).optional(),
             ] {
                 assert!(
-                    ReduceEntry::try_new("n", aggregator, Term::var("x"), &input_type).is_ok(),
+                    ReduceEntry::try_new("n", aggregator.clone(), Term::var("x"), &input_type)
+                        .is_ok(),
                     "{aggregator} over {input_type} must construct"
                 );
             }
@@ -1243,6 +1665,70 @@ mod tests {
         }
     }
 
+    /// The extended folds type like their phase-1 siblings: the
+    /// order statistics and dispersion folds need numeric input and
+    /// produce an identity-less `Float`; `string-agg` needs strings;
+    /// `collect-set` and `string-agg` have identities; `arg-min` /
+    /// `arg-max` produce an identity-less `Entity`.
+    #[dialog_common::test]
+    fn it_types_the_extended_folds() {
+        for aggregator in [
+            Aggregator::Median,
+            Aggregator::Percentile(0.5),
+            Aggregator::Variance,
+            Aggregator::StdDev,
+        ] {
+            assert!(aggregator.output_type(&typed(ValueType::String)).is_none());
+            let output = aggregator
+                .output_type(&typed(ValueType::UnsignedInt).optional())
+                .unwrap();
+            assert_eq!(output, typed(ValueType::Float).optional());
+        }
+
+        let separated = Aggregator::StringAgg(",".to_string());
+        assert!(
+            separated
+                .output_type(&typed(ValueType::UnsignedInt))
+                .is_none()
+        );
+        assert_eq!(
+            separated
+                .output_type(&typed(ValueType::String).optional())
+                .unwrap(),
+            typed(ValueType::String)
+        );
+        assert_eq!(
+            Aggregator::CollectSet
+                .output_type(&typed(ValueType::Boolean).optional())
+                .unwrap(),
+            typed(ValueType::Record)
+        );
+
+        let pick = Aggregator::ArgMax(Term::var("e"));
+        assert!(pick.output_type(&typed(ValueType::Record)).is_none());
+        assert_eq!(
+            pick.output_type(&typed(ValueType::Float).optional())
+                .unwrap(),
+            typed(ValueType::Entity).optional()
+        );
+    }
+
+    #[dialog_common::test]
+    fn it_rejects_an_out_of_range_percentile_at_construction() {
+        for p in [-0.1, 1.5, f64::NAN] {
+            let result = ReduceEntry::try_new(
+                "p",
+                Aggregator::Percentile(p),
+                Term::var("x"),
+                &typed(ValueType::Float),
+            );
+            assert!(
+                matches!(result, Err(TypeError::ReduceParameter { .. })),
+                "percentile {p} must be rejected, got {result:?}"
+            );
+        }
+    }
+
     /// A well-typed entry constructs through the checked path and
     /// still folds correctly through the A1 engine; the folded
     /// values inhabit the statically computed output types.
@@ -827,7 +827,10 @@ impl ResolverQuery {
 /// whose value disagrees, or a pre-bound variable that conflicts,
 /// filters the row (`None`) — the membership-test semantics shared
 /// with formulas; any other bind failure is a genuine error.
-fn project(base: &Match, fields: &[(&Term<Any>, Value)]) -> Result<Option<Match>, EvaluationError> {
+pub(crate) fn project(
+    base: &Match,
+    fields: &[(&Term<Any>, Value)],
+) -> Result<Option<Match>, EvaluationError> {
     let mut row = base.clone();
     for (term, value) in fields {
         match term {
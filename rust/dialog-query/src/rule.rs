@@ -10,6 +10,10 @@
 //!   reactor's fixpoint loop; trigger facts on `effect:system` are
 //!   ephemeral.
 //!
+//! A [`ConstraintRule`] reuses the deductive analysis for an integrity
+//! invariant: its body describes a violation, and a commit whose state
+//! would match it is rejected.
+//!
 //! The [`Rule`] enum carries either variant and is what compile-time
 //! analysis errors (in [`TypeError`](crate::TypeError) and
 //! [`AnalyzerError`](crate::AnalyzerError)) reference, so error
@@ -25,6 +29,8 @@ use std::fmt::{Display, Formatter, Result as FmtResult};
 
 /// Rule analysis: inference and dependency graph over premises.
 pub mod analyzer;
+/// Constraint rule definitions: integrity invariants checked at commit.
+pub mod constraint;
 /// Deductive rule definitions for deriving new facts.
 pub mod deductive;
 /// Inductive rule definitions (a.k.a. effects).
@@ -39,6 +45,8 @@ pub mod types;
 pub mod when;
 
 pub use analyzer::{AnalyzedRule, analyze};
+pub use constraint::ConstraintRule;
+pub use constraint::descriptor::ConstraintRuleDescriptor;
 pub use deductive::DeductiveRule;
 pub use deductive::descriptor::DeductiveRuleDescriptor;
 pub use inductive::InductiveRule;
@@ -256,7 +264,8 @@ pub(crate) fn compile_rule<T: Compile>(
             analysis
                 .reduce
                 .iter()
-                .filter_map(|entry| entry.input.name())
+                .flat_map(|entry| [Some(&entry.input), entry.aggregator.witness()])
+                .filter_map(|term| term?.name())
                 .find(|name| !join.binds.contains(name))
                 .map(String::from)
         });
@@ -27,6 +27,7 @@
 //! [`DeductiveRule::apply`](crate::DeductiveRule), which binds
 //! concrete values into the head.
 
+use crate::artifact::Type as ValueType;
 use crate::concept::descriptor::ConceptDescriptor;
 use crate::constraint::Constraint;
 use crate::error::{AnalysisError, TypeError};
@@ -38,6 +39,7 @@ use crate::rule::RuleKind;
 use crate::rule::types::TypeEnv;
 use crate::type_system::unifier::Context;
 use crate::type_system::{Primitive, Type as Kind};
+use crate::types::Any;
 use crate::{Entity, Environment, Premise, Term};
 use std::collections::BTreeSet;
 use std::sync::Arc;
@@ -270,6 +272,9 @@ impl AnalyzedRule {
             Premise::Assert(Proposition::OptionalAttribute(query)) => of_is_this(query.of()),
             Premise::Assert(Proposition::Constraint(_)) => true,
             Premise::Assert(Proposition::Formula(_)) => true,
+            Premise::Assert(Proposition::Search(query)) => {
+                matches!(query.of(), Term::Variable { name: Some(name), .. } if name == "this")
+            }
             Premise::Unless(Negation(Proposition::Attribute(query))) => of_is_this(query.of()),
             Premise::Unless(Negation(Proposition::Constraint(_))) => true,
             _ => false,
@@ -345,16 +350,38 @@ pub fn analyze_with(
         // variable. A constant input carries its own kind; an input
         // the body never binds falls back to "any present value" —
         // the grounding check after planning rejects it as unbound.
-        let input_type = match spec.of.name() {
-            Some(name) => types.get(name).cloned(),
-            None => spec.of.kind(),
-        }
-        .unwrap_or_else(|| Kind::from(Primitive::ALL));
+        let type_of = |term: &Term<Any>| {
+            match term.name() {
+                Some(name) => types.get(name).cloned(),
+                None => term.kind(),
+            }
+            .unwrap_or_else(|| Kind::from(Primitive::ALL))
+        };
+        let input_type = type_of(&spec.of);
         let entry = ReduceEntry::try_new(field.clone(), spec.apply, spec.of, &input_type)
             .map_err(|error| AnalysisError::Reduce(Box::new(error)))?;
-        let output = entry
+        let mut output = entry
             .output_type(&input_type)
             .expect("a checked entry has an output type by construction");
+        // `arg-min`/`arg-max` read a second input, the witness: it
+        // must be able to hold an entity, and an optional witness
+        // makes the pick optional (a group may pair no value with a
+        // present witness).
+        if let Some(witness) = entry.aggregator.witness() {
+            let witness_type = type_of(witness);
+            let entity = Kind::from(ValueType::Entity);
+            if witness_type.clone().required().intersect(&entity).is_none() {
+                return Err(AnalysisError::Reduce(Box::new(TypeError::ReduceInput {
+                    field,
+                    aggregator: Box::new(entry.aggregator),
+                    required: Box::new(entity),
+                    actual: Box::new(witness_type),
+                })));
+            }
+            if witness_type.is_optional() {
+                output = output.optional();
+            }
+        }
         // The output's present shapes must inhabit the head field's
         // declared type. The raw output (optionality included) is
         // what enters the TypeEnv, so the required-head check below
@@ -366,7 +393,7 @@ pub fn analyze_with(
         {
             return Err(AnalysisError::Reduce(Box::new(TypeError::ReduceOutput {
                 field,
-                aggregator: entry.aggregator,
+                aggregator: Box::new(entry.aggregator),
                 output: Box::new(output),
                 declared: Box::new(declared),
             })));
@@ -463,7 +490,6 @@ mod tests {
     use crate::optional::OptionalAttributeQuery;
     use crate::premise::Negation;
     use crate::the;
-    use crate::types::Any;
     use crate::{Cardinality, Parameters, Premise, Term};
 
     fn person_with_name() -> ConceptDescriptor {
@@ -0,0 +1,236 @@
+//! Constraint rules: reject a commit when the body matches.
+//!
+//! A constraint rule states an integrity invariant negatively: its body
+//! describes what must *not* hold ("a task with two owners", "two users
+//! sharing an email"), and every binding it produces is a violation.
+//! It has the same shape as a [`DeductiveRule`] — same analysis, same
+//! planning, same-instant semantics — but its head is never derived or
+//! asserted: the head's fields name the bindings a rejected commit
+//! reports.
+//!
+//! Constraints are checked at commit, after induction, against the
+//! state the commit would publish. Like inductive rules they are
+//! dispatched by the attributes a commit touches, so a commit is only
+//! checked against the constraints whose bodies it could have affected.
+
+/// Serializable constraint-rule descriptor.
+pub mod descriptor;
+
+use crate::Environment;
+use crate::artifact::Entity;
+use crate::concept::descriptor::ConceptDescriptor;
+use crate::error::TypeError;
+use crate::planner::Conjunction;
+use crate::premise::Premise;
+use crate::rule::DeductiveRule;
+use crate::rule::analyzer::AnalyzedRule;
+use crate::rule::fmt_rule_schema;
+use descriptor::ConstraintRuleDescriptor;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// A constraint rule that has passed analysis. Violation-shaped sibling
+/// of [`DeductiveRule`], whose analysis it shares.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstraintRule {
+    /// The body, analyzed as a deductive rule concluding the violation.
+    rule: DeductiveRule,
+}
+
+impl ConstraintRule {
+    /// Analyze a constraint from a violation head and body premises.
+    /// Runs the deductive analysis pipeline: every head field must be
+    /// bound by the body, so a violation always reports its bindings.
+    pub fn new(violation: ConceptDescriptor, premises: Vec<Premise>) -> Result<Self, TypeError> {
+        Ok(ConstraintRule {
+            rule: DeductiveRule::new(violation, premises)?,
+        })
+    }
+
+    /// The violation a match of the body reports.
+    pub fn conclusion(&self) -> &ConceptDescriptor {
+        self.rule.conclusion()
+    }
+
+    /// Returns this rule's analysis (narrowed premises, inferred
+    /// types, dependency graph).
+    pub fn analysis(&self) -> &AnalyzedRule {
+        self.rule.analysis()
+    }
+
+    /// Plan this rule's premises against a scope, producing a concrete
+    /// execution plan ([`Conjunction`]) ordered for the given bindings.
+    pub fn plan(&self, scope: &Environment) -> Conjunction {
+        self.rule.plan(scope)
+    }
+
+    /// Canonical dag-cbor encoding of this rule's descriptor, if the
+    /// body is expressible in formal notation. Mirrors
+    /// [`DeductiveRule::try_encode`].
+    pub fn try_encode(&self) -> Option<Vec<u8>> {
+        serde_ipld_dagcbor::to_vec(&self.descriptor()).ok()
+    }
+
+    /// This rule's content-addressed identity, if it has a canonical
+    /// encoding: `rule:<base58(blake3(dag-cbor(descriptor)))>`. The
+    /// `forbid` head field is part of the encoding, so a constraint never
+    /// collides with the deductive rule of the same body.
+    pub fn try_this(&self) -> Option<Entity> {
+        use base58::ToBase58;
+        let hash = blake3::hash(&self.try_encode()?);
+        let encoded = hash.as_bytes().as_ref().to_base58();
+        format!("rule:{encoded}").parse().ok()
+    }
+
+    /// Canonical dag-cbor encoding, panicking if the rule has no
+    /// encodable body. Use on the storage path; prefer
+    /// [`try_encode`](Self::try_encode) otherwise.
+    pub fn encode(&self) -> Vec<u8> {
+        self.try_encode()
+            .expect("rule body must encode in formal notation")
+    }
+
+    /// Content-addressed identity, panicking if the rule has no
+    /// encodable body. Use on the storage path; prefer
+    /// [`try_this`](Self::try_this) otherwise.
+    pub fn this(&self) -> Entity {
+        self.try_this()
+            .expect("storable rule must have a content-addressed identity")
+    }
+
+    /// Rebuild a rule from its canonical dag-cbor [`encode`](Self::encode)
+    /// bytes. `Err` carries a human-readable reason — either the cbor
+    /// decode failed or the decoded descriptor didn't compile.
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let descriptor: ConstraintRuleDescriptor = serde_ipld_dagcbor::from_slice(bytes)
+            .map_err(|e| format!("dag-cbor decode failed: {e}"))?;
+        descriptor.compile().map_err(|e| e.to_string())
+    }
+
+    /// Round-trip this rule back to its serializable form.
+    pub fn descriptor(&self) -> ConstraintRuleDescriptor {
+        let descriptor = self.rule.descriptor();
+        ConstraintRuleDescriptor {
+            description: None,
+            forbid: descriptor.deduce,
+            when: descriptor.when,
+            unless: descriptor.unless,
+        }
+    }
+}
+
+impl Serialize for ConstraintRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.descriptor().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ConstraintRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let definition = ConstraintRuleDescriptor::deserialize(deserializer)?;
+        definition.compile().map_err(D::Error::custom)
+    }
+}
+
+impl Display for ConstraintRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        fmt_rule_schema(self.conclusion(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// "No two users share an email": two distinct users holding the
+    /// same address.
+    fn shared_email() -> serde_json::Value {
+        json!({
+            "description": "No two users share an email",
+            "forbid": {
+                "with": {
+                    "email": { "the": "user/email", "as": "Text" }
+                }
+            },
+            "when": [
+                {
+                    "assert": { "with": { "email": { "the": "user/email", "as": "Text" } } },
+                    "where": {
+                        "this": { "?": { "name": "this" } },
+                        "email": { "?": { "name": "email" } }
+                    }
+                },
+                {
+                    "assert": { "with": { "email": { "the": "user/email", "as": "Text" } } },
+                    "where": {
+                        "this": { "?": { "name": "other" } },
+                        "email": { "?": { "name": "email" } }
+                    }
+                }
+            ],
+            "unless": [
+                {
+                    "assert": "==",
+                    "where": {
+                        "this": { "?": { "name": "this" } },
+                        "is": { "?": { "name": "other" } }
+                    }
+                }
+            ]
+        })
+    }
+
+    #[dialog_common::test]
+    fn it_round_trips_through_its_stored_encoding() {
+        let rule: ConstraintRule = serde_json::from_value(shared_email()).unwrap();
+
+        let serialized = serde_json::to_value(&rule).unwrap();
+        assert!(serialized["forbid"]["with"].is_object());
+        assert_eq!(serialized["unless"].as_array().unwrap().len(), 1);
+
+        let decoded = ConstraintRule::decode(&rule.encode()).unwrap();
+        assert_eq!(decoded.encode(), rule.encode());
+        assert_eq!(decoded.this(), rule.this());
+    }
+
+    #[dialog_common::test]
+    fn it_does_not_share_an_address_with_the_deductive_rule_of_its_body() {
+        let rule: ConstraintRule = serde_json::from_value(shared_email()).unwrap();
+
+        let mut deductive = shared_email();
+        let head = deductive["forbid"].take();
+        deductive["deduce"] = head;
+        deductive.as_object_mut().unwrap().remove("forbid");
+        let deductive: DeductiveRule = serde_json::from_value(deductive).unwrap();
+
+        assert_ne!(rule.this(), deductive.this());
+        assert!(DeductiveRule::decode(&rule.encode()).is_err());
+    }
+
+    #[dialog_common::test]
+    fn it_rejects_a_violation_the_body_does_not_bind() {
+        let json = json!({
+            "forbid": {
+                "with": {
+                    "owner": { "the": "task/owner", "as": "Entity" }
+                }
+            },
+            "when": [
+                {
+                    "assert": { "with": { "title": { "the": "task/title", "as": "Text" } } },
+                    "where": {
+                        "this": { "?": { "name": "this" } },
+                        "title": { "?": { "name": "title" } }
+                    }
+                }
+            ]
+        });
+        let result: Result<ConstraintRule, _> = serde_json::from_value(json);
+        assert!(
+            result.is_err(),
+            "an unbound violation field must be rejected"
+        );
+    }
+}
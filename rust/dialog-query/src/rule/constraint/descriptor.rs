@@ -0,0 +1,59 @@
+use crate::concept::descriptor::ConceptDescriptor;
+use crate::error::TypeError;
+use crate::negation::Negation;
+use crate::premise::Premise;
+use crate::proposition::Proposition;
+use serde::{Deserialize, Serialize};
+
+use super::ConstraintRule;
+
+/// A constraint-rule definition in the formal notation, suitable for
+/// serialization.
+///
+/// Mirrors [`DeductiveRuleDescriptor`](crate::rule::DeductiveRuleDescriptor)
+/// modulo the head field: a constraint's head is named `forbid`, because
+/// every binding its body produces is a *violation* — the head's fields
+/// are the bindings a rejected commit reports. The distinct head field
+/// also keeps a constraint's content address apart from the deductive
+/// rule with the same body.
+///
+/// ```json
+/// {
+///   "description": "...",
+///   "forbid": { "with": { ... } },
+///   "when":   [ { "assert": ..., "where": ... }, ... ],
+///   "unless": [ { "assert": ..., "where": ... }, ... ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConstraintRuleDescriptor {
+    /// Human-readable description of the invariant.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// The violation: the bindings reported for every match of the body.
+    pub forbid: ConceptDescriptor,
+
+    /// Conjunction of positive premises. A binding satisfying all of
+    /// them (and none of `unless`) violates the constraint.
+    pub when: Vec<Proposition>,
+
+    /// Negative premises. If any can be satisfied, the binding is not a
+    /// violation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unless: Vec<Proposition>,
+}
+
+impl ConstraintRuleDescriptor {
+    /// Compile this definition into a [`ConstraintRule`] ready for
+    /// evaluation.
+    pub fn compile(self) -> Result<ConstraintRule, TypeError> {
+        let mut premises: Vec<Premise> = self.when.into_iter().map(Premise::Assert).collect();
+
+        for proposition in self.unless {
+            premises.push(Premise::Unless(Negation::not(proposition)));
+        }
+
+        ConstraintRule::new(self.forbid, premises)
+    }
+}
@@ -103,8 +103,17 @@ impl DeductiveRuleDescriptor {
     /// their execution order, and validates that every conclusion variable
     /// is grounded by a positive premise (reduced fields are defined by
     /// their folds instead — see
-    /// [`DeductiveRule::with_reduce`]).
+    /// [`DeductiveRule::with_reduce`]). A raw attribute premise, which
+    /// the formal notation has no form for, is refused.
     pub fn compile(self) -> Result<DeductiveRule, TypeError> {
+        if !self
+            .when
+            .iter()
+            .chain(&self.unless)
+            .all(Proposition::is_expressible)
+        {
+            return Err(TypeError::InexpressiblePremise);
+        }
         let mut premises: Vec<Premise> = self.when.into_iter().map(Premise::Assert).collect();
 
         for proposition in self.unless {
@@ -1019,6 +1028,42 @@ mod tests {
         }
     }
 
+    /// `arg-max` reads a witness beside its input: the witness must
+    /// be able to hold an entity, and it must be bound by the body
+    /// just like the input.
+    #[dialog_common::test]
+    fn it_checks_the_arg_max_witness() {
+        use crate::error::TypeError;
+
+        let top = |witness: &str| {
+            let mut rule = dept_total_json();
+            rule["deduce"] = json!({ "with": {
+                "top": { "the": "org.dept/top-earner", "as": "Entity" }
+            }});
+            rule["reduce"] = json!({
+                "top": {
+                    "apply": { "arg-max": { "?": { "name": witness } } },
+                    "of": { "?": { "name": "salary" } }
+                }
+            });
+            serde_json::from_value::<DeductiveRuleDescriptor>(rule).unwrap()
+        };
+
+        let rule = top("employee")
+            .compile()
+            .expect("an entity witness compiles");
+        assert_eq!(rule.reduce().len(), 1);
+
+        match top("salary").compile() {
+            Err(TypeError::ReduceInput { field, .. }) => assert_eq!(field, "top"),
+            other => panic!("expected ReduceInput for a non-entity witness, got {other:?}"),
+        }
+        match top("boss").compile() {
+            Err(TypeError::UnboundVariable { variable, .. }) => assert_eq!(variable, "boss"),
+            other => panic!("expected UnboundVariable, got {other:?}"),
+        }
+    }
+
     /// The fold's input variable must be bound by the body; reduced
     /// fields themselves are exempt from grounding (the fold defines
     /// them).
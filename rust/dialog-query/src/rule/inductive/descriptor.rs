@@ -27,6 +27,7 @@ use super::InductiveRule;
 /// }
 /// ```
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawDescriptor")]
 pub struct InductiveRuleDescriptor {
     /// Human-readable description of the rule.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -53,11 +54,53 @@ pub struct InductiveRuleDescriptor {
     pub unless: Vec<Proposition>,
 }
 
+/// The unvalidated wire shape of [`InductiveRuleDescriptor`]:
+/// deserialization goes through this mirror and [`TryFrom`], so a rule
+/// with neither head never constructs a descriptor.
+#[derive(Deserialize)]
+struct RawDescriptor {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(rename = "assert!", default)]
+    assert: Option<ConceptDescriptor>,
+    #[serde(rename = "retract!", default)]
+    retract: Option<ConceptDescriptor>,
+    when: Vec<Proposition>,
+    #[serde(default)]
+    unless: Vec<Proposition>,
+}
+
+impl TryFrom<RawDescriptor> for InductiveRuleDescriptor {
+    type Error = TypeError;
+
+    fn try_from(raw: RawDescriptor) -> Result<Self, TypeError> {
+        if raw.assert.is_none() && raw.retract.is_none() {
+            return Err(TypeError::MissingHead);
+        }
+        Ok(InductiveRuleDescriptor {
+            description: raw.description,
+            assert: raw.assert,
+            retract: raw.retract,
+            when: raw.when,
+            unless: raw.unless,
+        })
+    }
+}
+
 impl InductiveRuleDescriptor {
     /// Compile this definition into an [`InductiveRule`] ready for
     /// evaluation. Fails unless exactly one of `assert!` / `retract!`
-    /// is present.
+    /// is present, or on a raw attribute premise, which the formal
+    /// notation has no form for.
     pub fn compile(self) -> Result<InductiveRule, TypeError> {
+        if !self
+            .when
+            .iter()
+            .chain(&self.unless)
+            .all(Proposition::is_expressible)
+        {
+            return Err(TypeError::InexpressiblePremise);
+        }
         let mut premises: Vec<Premise> = self.when.into_iter().map(Premise::Assert).collect();
 
         for proposition in self.unless {
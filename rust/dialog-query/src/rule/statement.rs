@@ -1,8 +1,8 @@
 //! Rules as [`Statement`]s: the `dialog.rule/*` storage vocabulary and
 //! the lowering that installs a rule by plain assertion.
 //!
-//! A rule *is* its facts: asserting a [`DeductiveRule`] or an
-//! [`InductiveRule`] into any [`Update`] target stages the
+//! A rule *is* its facts: asserting a [`DeductiveRule`], an
+//! [`InductiveRule`] or a [`ConstraintRule`] into any [`Update`] target stages the
 //! `dialog.rule/*` claims that persist it, and retracting the same rule
 //! erases them — install and uninstall are ordinary writes, no dedicated
 //! API. The kind decides the fact shape:
@@ -11,7 +11,9 @@
 //!   and the `reads` reverse index over the body's attributes;
 //! - inductive: the shared `source` body, the `induces` head index,
 //!   and the `on` trigger index commit-time dispatch probes by touched
-//!   attribute.
+//!   attribute;
+//! - constraint: the shared `source` body and the `checks` trigger
+//!   index, the constraint sibling of `on`.
 //!
 //! The rule entity is the content address of its canonical body, which
 //! is what makes these facts safe to accept from the ordinary write
@@ -21,7 +23,7 @@
 use std::collections::BTreeSet;
 
 use crate::artifact::{Entity, Value};
-use crate::rule::{DeductiveRule, InductiveRule, Rule};
+use crate::rule::{ConstraintRule, DeductiveRule, InductiveRule, Rule};
 use crate::{Proposition, Statement, Update, the};
 use dialog_artifacts::Attribute;
 
@@ -52,6 +54,14 @@ pub fn on_attr() -> Attribute {
     the!("dialog.rule/on").into()
 }
 
+/// The `dialog.rule/checks` trigger-index attribute for constraint rules:
+/// one claim per attribute the constraint's concept premises name, valued
+/// `on:<domain>/<name>`. Kept apart from `dialog.rule/on` so induction
+/// never hydrates (and discards) a constraint, and vice versa.
+pub fn checks_attr() -> Attribute {
+    the!("dialog.rule/checks").into()
+}
+
 /// The `dialog.rule/reads` reverse index for *deductive* rules: one claim
 /// per attribute the rule's body names, valued `on:<domain>/<name>`.
 /// Commit-time dispatch composes these at probe time to close the
@@ -100,6 +110,15 @@ pub fn on_entities(rule: &InductiveRule) -> BTreeSet<Entity> {
     premise_trigger_entities(descriptor.when.iter().chain(descriptor.unless.iter()))
 }
 
+/// The trigger-index entities for a constraint rule: one per attribute
+/// named by any concept premise. `unless` premises are indexed for the
+/// same reason as an inductive rule's: a retraction can newly produce a
+/// violation.
+pub fn checks_entities(rule: &ConstraintRule) -> BTreeSet<Entity> {
+    let descriptor = rule.descriptor();
+    premise_trigger_entities(descriptor.when.iter().chain(descriptor.unless.iter()))
+}
+
 /// The reverse-index entities for a deductive rule's body: one per
 /// attribute any concept premise names. Stored as `dialog.rule/reads` so
 /// dispatch can walk base attribute → deductive rules reading it →
@@ -220,6 +239,46 @@ impl Statement for InductiveRule {
     }
 }
 
+/// Asserting a [`ConstraintRule`] installs it as `dialog.rule/*` facts:
+/// the shared `source` body and the `checks` trigger index commit-time
+/// checking probes by touched attribute. Retracting the same rule erases
+/// those facts, uninstalling it.
+impl Statement for &ConstraintRule {
+    fn assert(self, update: &mut impl Update) {
+        let rule_entity = self.this();
+        update.associate(
+            source_attr(),
+            rule_entity.clone(),
+            Value::Bytes(self.encode()),
+        );
+        for checks in checks_entities(self) {
+            update.associate(checks_attr(), rule_entity.clone(), Value::Entity(checks));
+        }
+    }
+
+    fn retract(self, update: &mut impl Update) {
+        let rule_entity = self.this();
+        update.dissociate(
+            source_attr(),
+            rule_entity.clone(),
+            Value::Bytes(self.encode()),
+        );
+        for checks in checks_entities(self) {
+            update.dissociate(checks_attr(), rule_entity.clone(), Value::Entity(checks));
+        }
+    }
+}
+
+impl Statement for ConstraintRule {
+    fn assert(self, update: &mut impl Update) {
+        (&self).assert(update);
+    }
+
+    fn retract(self, update: &mut impl Update) {
+        (&self).retract(update);
+    }
+}
+
 /// A kind-erased [`Rule`] lowers as whichever variant it carries.
 impl Statement for &Rule {
     fn assert(self, update: &mut impl Update) {
@@ -97,6 +97,11 @@ pub const INDEX_SCAN_COST: usize = 5_000;
 /// Cost of a secondary verification lookup (e.g. VAE winner check for Cardinality::One).
 pub const VERIFICATION_COST: usize = 100;
 
+/// Cost of descending an index to the start of a read: paid by every
+/// probe of a nested-loop join, and once by a merge join that reads its
+/// whole range in a single pass.
+pub const SEEK_COST: usize = 50;
+
 /// Overhead cost for concept queries due to potential rule evaluation.
 /// Concepts may have associated deductive rules that need to be checked and evaluated.
 pub const CONCEPT_OVERHEAD: usize = 1_000;
@@ -0,0 +1,306 @@
+//! Search premises: full-text matches over searchable string attributes.
+//!
+//! A search is a scan, not a resolver: it reads the mutable head —
+//! the branch's text index narrows the candidates, and every candidate
+//! is verified against the attribute's current facts — so the
+//! environment records demand for the searched attribute exactly as a
+//! fact scan over it would. Subscriptions therefore re-evaluate a
+//! search whenever a value of the attribute changes.
+//!
+//! ```text
+//! text/matches(the: "note/body", query: "prolly tree*", of: ?note, score: ?score)
+//!   ⋈ note/title(of: ?note, is: ?title)
+//! ```
+//!
+//! The attribute and the query are inputs. `of` is either: bound, the
+//! search checks that one entity's values; free, it enumerates every
+//! matching entity through the index. Each matching value is one row,
+//! carrying the value itself (`is`) and its relevance (`score`, the
+//! fraction of the value's tokens the query matched).
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::sync::LazyLock;
+
+use dialog_artifacts::{Attribute, Search, TextQuery, TextSearch};
+use dialog_capability::Provider;
+use serde::{Deserialize, Serialize};
+
+use crate::artifact::Type as ValueType;
+use crate::error::EvaluationError;
+use crate::formula::cell::Cells;
+use crate::query::Application;
+use crate::resolver::project;
+use crate::schema::{LOOKUP_COST, RANGE_READ_COST};
+use crate::selection::{Match, Selection};
+use crate::term::Term;
+use crate::type_system::Type as Kind;
+use crate::types::Any;
+use crate::{Binding, Environment, Parameters, Schema, Scope, Value, try_stream};
+
+/// Serde default for omitted parameter slots.
+fn blank() -> Term<Any> {
+    Term::blank()
+}
+
+/// The `text/matches` search: the values of a searchable attribute
+/// that contain every term of a query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextMatchesQuery {
+    /// The searchable attribute — required.
+    #[serde(default = "blank")]
+    pub the: Term<Any>,
+    /// The query: words every match must contain, a trailing `*`
+    /// matching any word it begins — required.
+    #[serde(default = "blank")]
+    pub query: Term<Any>,
+    /// The entity holding the matching value.
+    #[serde(default = "blank")]
+    pub of: Term<Any>,
+    /// The matching value.
+    #[serde(default = "blank")]
+    pub is: Term<Any>,
+    /// The value's relevance, in `(0, 1]`.
+    #[serde(default = "blank")]
+    pub score: Term<Any>,
+}
+
+/// A search premise bound to specific term arguments.
+///
+/// Serializes as `{"assert": "<name>", "where": <params>}`, the tagged
+/// form formulas and resolvers use.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "assert", content = "where")]
+pub enum SearchQuery {
+    /// Search `text/matches`.
+    #[serde(rename = "text/matches")]
+    TextMatches(TextMatchesQuery),
+}
+
+static TEXT_MATCHES_CELLS: LazyLock<Cells> = LazyLock::new(|| {
+    Cells::define(|builder| {
+        builder
+            .cell(
+                "the",
+                Some(Kind::from(ValueType::Symbol).union(&Kind::from(ValueType::String))),
+            )
+            .the("The searchable attribute.")
+            .required();
+        builder
+            .cell("query", Some(Kind::from(ValueType::String)))
+            .the("Words every match must contain; a trailing * matches a prefix.")
+            .required();
+        builder
+            .cell("of", Some(Kind::from(ValueType::Entity)))
+            .the("The entity holding the matching value.");
+        builder
+            .cell("is", Some(Kind::from(ValueType::String)))
+            .the("The matching value.");
+        builder
+            .cell("score", Some(Kind::from(ValueType::Float)))
+            .the("The value's relevance, in (0, 1].");
+    })
+});
+
+impl SearchQuery {
+    /// Returns the formal notation name (e.g. `"text/matches"`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TextMatches(_) => "text/matches",
+        }
+    }
+
+    /// Returns the static cell definitions for this search.
+    pub(crate) fn cells(&self) -> &'static Cells {
+        match self {
+            Self::TextMatches(_) => &TEXT_MATCHES_CELLS,
+        }
+    }
+
+    /// Returns the schema for this search.
+    pub fn schema(&self) -> Schema {
+        self.cells().into()
+    }
+
+    /// The entity term, for the rule analyzer's locality check.
+    pub fn of(&self) -> &Term<Any> {
+        match self {
+            Self::TextMatches(query) => &query.of,
+        }
+    }
+
+    /// Estimate the cost of this search given the environment.
+    ///
+    /// `None` until the attribute and the query are bound: there is no
+    /// index to enumerate without them. A bound entity checks that
+    /// entity's values alone; a free one reads the query's postings.
+    pub fn estimate(&self, env: &Environment) -> Option<usize> {
+        let Self::TextMatches(query) = self;
+        if !query.the.is_bound(env) || !query.query.is_bound(env) {
+            return None;
+        }
+        if query.of.is_bound(env) {
+            Some(LOOKUP_COST)
+        } else {
+            Some(RANGE_READ_COST)
+        }
+    }
+
+    /// Returns the parameters for this search application.
+    pub fn parameters(&self) -> Parameters {
+        let Self::TextMatches(query) = self;
+        let mut params = Parameters::new();
+        params.insert("the".into(), query.the.clone());
+        params.insert("query".into(), query.query.clone());
+        params.insert("of".into(), query.of.clone());
+        params.insert("is".into(), query.is.clone());
+        params.insert("score".into(), query.score.clone());
+        params
+    }
+
+    /// Evaluate this search over the incoming selection.
+    ///
+    /// Per input row: resolve the attribute, the query and (when bound)
+    /// the entity, perform the [`Search`] effect through the
+    /// environment, and project one row per matching value. A row whose
+    /// inputs do not resolve — an attribute that does not parse, a
+    /// query that is not a string, an entity slot bound to a
+    /// non-entity — contributes nothing.
+    pub fn evaluate<'a, Env, M: Selection + 'a>(
+        self,
+        env: &'a Env,
+        selection: M,
+    ) -> impl Selection + 'a
+    where
+        Env: Scope<'a>,
+    {
+        let Self::TextMatches(search) = self;
+        try_stream! {
+            for await candidate in selection {
+                let base = candidate?;
+                let the = match resolve(&search.the, &base) {
+                    Some(Value::Symbol(attribute)) => attribute,
+                    Some(Value::String(name)) => match name.parse::<Attribute>() {
+                        Ok(attribute) => attribute,
+                        Err(_) => continue,
+                    },
+                    _ => continue,
+                };
+                let Some(Value::String(query)) = resolve(&search.query, &base) else {
+                    continue;
+                };
+                let of = match resolve(&search.of, &base) {
+                    None => None,
+                    Some(Value::Entity(entity)) => Some(entity),
+                    Some(_) => continue,
+                };
+                let input = TextSearch {
+                    the,
+                    query: TextQuery::parse(&query),
+                    of,
+                };
+                for found in Provider::<Search>::execute(env, input).await? {
+                    let row = project(&base, &[
+                        (&search.of, Value::Entity(found.of)),
+                        (&search.is, Value::String(found.is)),
+                        (&search.score, Value::Float(found.score)),
+                    ])?;
+                    if let Some(row) = row {
+                        yield row;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The value a term holds in `base`: a constant, or a bound variable.
+fn resolve(term: &Term<Any>, base: &Match) -> Option<Value> {
+    match term {
+        Term::Constant(value) => Some(value.clone()),
+        term => match base.lookup(term) {
+            Ok(Binding::Present(value)) => Some(value),
+            _ => None,
+        },
+    }
+}
+
+/// A realized search row: the value each named slot bound.
+/// Constant slots are echoed; slots the row did not bind are absent.
+pub type SearchConclusion = BTreeMap<String, Value>;
+
+impl Application for SearchQuery {
+    type Conclusion = SearchConclusion;
+
+    fn evaluate<'a, Env, M: Selection + 'a>(self, selection: M, env: &'a Env) -> impl Selection + 'a
+    where
+        Env: Scope<'a>,
+    {
+        self.evaluate(env, selection)
+    }
+
+    fn realize(&self, input: Match) -> Result<Self::Conclusion, EvaluationError> {
+        let mut row = BTreeMap::new();
+        for (slot, term) in self.parameters().iter() {
+            match term {
+                Term::Constant(value) => {
+                    row.insert(slot.clone(), value.clone());
+                }
+                term => {
+                    if let Ok(Binding::Present(value)) = input.lookup(term) {
+                        row.insert(slot.clone(), value);
+                    }
+                }
+            }
+        }
+        Ok(row)
+    }
+}
+
+impl Display for SearchQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self::TextMatches(query) = self;
+        write!(
+            f,
+            "{}(the: {}, query: {})",
+            self.name(),
+            query.the,
+            query.query
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches() -> SearchQuery {
+        SearchQuery::TextMatches(TextMatchesQuery {
+            the: Term::from(Value::String("note/body".into())).into(),
+            query: Term::var("query"),
+            of: Term::var("this"),
+            is: Term::var("body"),
+            score: blank(),
+        })
+    }
+
+    #[dialog_common::test]
+    fn it_waits_for_the_attribute_and_the_query() {
+        let search = matches();
+        let mut env = Environment::new();
+        assert_eq!(search.estimate(&env), None);
+        env.add("query");
+        assert_eq!(search.estimate(&env), Some(RANGE_READ_COST));
+        env.add("this");
+        assert_eq!(search.estimate(&env), Some(LOOKUP_COST));
+    }
+
+    #[dialog_common::test]
+    fn it_round_trips_through_formal_notation() {
+        let search = matches();
+        let json = serde_json::to_value(&search).unwrap();
+        assert_eq!(json["assert"], "text/matches");
+        let restored: crate::Proposition = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, crate::Proposition::Search(search));
+    }
+}
@@ -1,12 +1,12 @@
 use futures_util::stream::once;
 use std::sync::Arc;
 
-use crate::Claim;
 use crate::artifact::Value;
 use crate::error::EvaluationError;
 use crate::term::Term;
 use crate::types::Any;
 use crate::types::Record;
+use crate::{Claim, Environment};
 
 use super::Selection;
 
@@ -123,9 +123,10 @@ pub struct Match {
     /// table and re-allocated every `String` key, and every probe paid
     /// a SipHash of the name before comparing anything.
     bindings: Vec<(Arc<str>, Binding)>,
-    // TODO: Once Value::Record supports the RecordFormat trait proposed in
-    // https://github.com/dialog-db/dialog-db/pull/221 claims can be stored
-    // directly as Value::Record in bindings, eliminating this separate list.
+    // TODO: Value::Record now carries RecordFormat forms (see
+    // https://github.com/dialog-db/dialog-db/pull/221), so claims could be
+    // stored directly as Value::Record in bindings, eliminating this
+    // separate list.
     claims: Vec<(Arc<str>, Arc<Claim>)>,
 }
 
@@ -326,6 +327,46 @@ impl Match {
         }
     }
 
+    /// This row cut down to the variables in `scope`: the bindings and
+    /// claims a step planned against `scope` would have been given.
+    pub(crate) fn within(&self, scope: &Environment) -> Match {
+        let keep = |name: &Arc<str>| scope.contains(name);
+        Match {
+            bindings: self
+                .bindings
+                .iter()
+                .filter(|(name, _)| keep(name))
+                .cloned()
+                .collect(),
+            claims: self
+                .claims
+                .iter()
+                .filter(|(name, _)| keep(name))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Combine two rows that extend the same input, as a join does:
+    /// the bindings and claims of both, or `None` when they bind a
+    /// shared variable differently.
+    pub fn join(&self, other: &Match) -> Option<Match> {
+        let mut joined = self.clone();
+        for (name, binding) in &other.bindings {
+            match probe(&joined.bindings, name) {
+                Some(existing) if existing != binding => return None,
+                Some(_) => {}
+                None => joined.bindings.push((name.clone(), binding.clone())),
+            }
+        }
+        for (name, claim) in &other.claims {
+            if probe(&joined.claims, name).is_none() {
+                joined.claims.push((name.clone(), claim.clone()));
+            }
+        }
+        Some(joined)
+    }
+
     /// Look up the binding for a term.
     ///
     /// For named variables, returns the binding (Present or
@@ -589,4 +630,26 @@ mod model_tests {
         assert_ne!(forward, subset, "missing bindings must break equality");
         assert_ne!(subset, forward);
     }
+
+    #[dialog_common::test]
+    fn join_unions_rows_that_agree_on_shared_variables() {
+        let shared = Term::var("person");
+        let mut left = Match::new();
+        left.bind(&shared, Value::UnsignedInt(1)).unwrap();
+        left.bind(&Term::var("name"), Value::String("Alice".into()))
+            .unwrap();
+        let mut right = Match::new();
+        right.bind(&shared, Value::UnsignedInt(1)).unwrap();
+        right
+            .bind(&Term::var("age"), Value::UnsignedInt(25))
+            .unwrap();
+
+        let joined = left.join(&right).expect("rows agree on ?person");
+        assert!(joined.is_present(&Term::var("name")));
+        assert!(joined.is_present(&Term::var("age")));
+
+        let mut other = Match::new();
+        other.bind(&shared, Value::UnsignedInt(2)).unwrap();
+        assert_eq!(left.join(&other), None, "rows disagree on ?person");
+    }
 }
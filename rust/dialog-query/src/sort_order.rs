@@ -11,12 +11,17 @@
 //!
 //! [`SortOrder`] names that variable. It is the physical property a merge join
 //! needs: two scans can be merge-joined on a variable only if both yield their
-//! output sorted on it. Nothing consumes it yet; it is surfaced so the planner
-//! can, without changing how any scan runs today.
+//! output sorted on it. The planner reads it to decide when a scan can run as a
+//! [`Plan::Merge`](crate::Plan::Merge) alongside the scan before it instead of
+//! probing the index once per row.
 
-use crate::Term;
+use core::fmt::{self, Display, Formatter};
+
+use crate::attribute::The;
+use crate::attribute::query::DynamicAttributeQuery;
 use crate::attribute::query::all::AttributeQueryAll;
-use crate::types::Typed;
+use crate::types::{Any, Typed};
+use crate::{Entity, Environment, Term};
 
 /// The variable a scan's output is sorted on, or that it carries no useful
 /// order (a fully-constrained point lookup, or an ordering led by a variable
@@ -59,20 +64,99 @@ impl SortOrder {
     }
 }
 
-/// The variable name a term will bind, if it is a named unbound variable.
+/// One of the three orderings every fact is indexed under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndexOrder {
+    /// Entity, then attribute, then value.
+    Eav,
+    /// Attribute, then entity, then value.
+    Aev,
+    /// Value, then attribute, then entity.
+    Vae,
+}
+
+impl Display for IndexOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexOrder::Eav => write!(f, "EAV"),
+            IndexOrder::Aev => write!(f, "AEV"),
+            IndexOrder::Vae => write!(f, "VAE"),
+        }
+    }
+}
+
+/// The variable name a term will bind, if it is a named variable not
+/// already in `bound`.
 ///
 /// A constant contributes no sort variable (it fixes its component). An
 /// anonymous variable (`name: None`) carries no name to join on, so it is
 /// treated the same as a constant here: it names no sort dimension. A variable
 /// already bound upstream will have been resolved to a constant before
 /// evaluation, so only a still-free named variable names a sort dimension.
-fn free_variable<T: Typed>(term: &Term<T>) -> Option<String> {
+fn free_variable<T: Typed>(term: &Term<T>, bound: &Environment) -> Option<String> {
     match term {
-        Term::Variable { name, .. } => name.clone(),
-        Term::Constant(_) => None,
+        Term::Variable {
+            name: Some(name), ..
+        } if !bound.contains(name) => Some(name.clone()),
+        _ => None,
     }
 }
 
+/// The index a scan over `(the, of, is)` reads and the order of its output,
+/// treating the variables in `bound` as resolved to constants.
+///
+/// This is the exact priority `selector_range` applies: entity index unless
+/// entity is free and something else is bound, then value, then attribute.
+fn access(
+    the: &Term<The>,
+    of: &Term<Entity>,
+    is: &Term<Any>,
+    bound: &Environment,
+) -> (IndexOrder, SortOrder) {
+    let entity_bound = of.is_bound(bound);
+    let value_bound = is.is_bound(bound);
+    let attribute_bound = the.is_bound(bound);
+
+    // The component sequence of the chosen ordering, most significant first.
+    let (index, sequence) = if entity_bound || (!value_bound && !attribute_bound) {
+        (
+            IndexOrder::Eav,
+            [
+                free_variable(of, bound),
+                free_variable(the, bound),
+                free_variable(is, bound),
+            ],
+        )
+    } else if value_bound {
+        (
+            IndexOrder::Vae,
+            [
+                free_variable(is, bound),
+                free_variable(the, bound),
+                free_variable(of, bound),
+            ],
+        )
+    } else {
+        (
+            IndexOrder::Aev,
+            [
+                free_variable(the, bound),
+                free_variable(of, bound),
+                free_variable(is, bound),
+            ],
+        )
+    };
+
+    // The leading free dimension is the first sequence entry that is a
+    // variable. Bound leading components are fixed across the scan, so the
+    // order is decided by the first free one.
+    let order = match sequence.into_iter().flatten().next() {
+        Some(name) => SortOrder::On(name),
+        None => SortOrder::None,
+    };
+    (index, order)
+}
+
 impl AttributeQueryAll {
     /// The variable this scan's output stream is sorted on.
     ///
@@ -91,54 +175,29 @@ impl AttributeQueryAll {
     /// Returns [`SortOrder::None`] when the leading dimensions are all bound to
     /// constants (a point lookup carries no join-useful order).
     pub fn sort_order(&self) -> SortOrder {
-        let entity_bound = matches!(self.of(), Term::Constant(_));
-        let value_bound = matches!(self.is(), Term::Constant(_));
-        let attribute_bound = matches!(self.the(), Term::Constant(_));
-
-        // The component sequence of the ordering the storage layer will pick,
-        // most significant first. This is the exact priority `selector_range`
-        // applies: entity index unless entity is free and something else is
-        // bound, then value, then attribute.
-        let sequence: [Option<String>; 3] = if entity_bound || (!value_bound && !attribute_bound) {
-            // EAV: entity, attribute, value.
-            [
-                free_variable(self.of()),
-                free_variable(self.the()),
-                free_variable(self.is()),
-            ]
-        } else if value_bound {
-            // VAE: value, attribute, entity.
-            [
-                free_variable(self.is()),
-                free_variable(self.the()),
-                free_variable(self.of()),
-            ]
-        } else {
-            // AEV: attribute, entity, value.
-            [
-                free_variable(self.the()),
-                free_variable(self.of()),
-                free_variable(self.is()),
-            ]
-        };
-
-        // The leading free dimension is the first sequence entry that is a
-        // variable. Bound leading components are fixed across the scan, so the
-        // order is decided by the first free one.
-        match sequence.into_iter().flatten().next() {
-            Some(name) => SortOrder::On(name),
-            None => SortOrder::None,
-        }
+        access(self.the(), self.of(), self.is(), &Environment::new()).1
+    }
+}
+
+impl DynamicAttributeQuery {
+    /// The index this scan reads and the variable its output is sorted on,
+    /// once the variables in `bound` have been bound by earlier steps.
+    ///
+    /// With an empty `bound` this agrees with
+    /// [`AttributeQueryAll::sort_order`].
+    pub fn access(&self, bound: &Environment) -> (IndexOrder, SortOrder) {
+        access(self.the(), self.of(), self.is(), bound)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::SortOrder;
+    use super::{IndexOrder, SortOrder};
     use crate::attribute::The;
+    use crate::attribute::query::DynamicAttributeQuery;
     use crate::attribute::query::all::AttributeQueryAll;
     use crate::types::{Any, Typed};
-    use crate::{Entity, Term, Value};
+    use crate::{Entity, Environment, Term, Value};
 
     #[cfg(target_arch = "wasm32")]
     wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
@@ -206,6 +265,23 @@ mod tests {
         assert_eq!(query.sort_order(), SortOrder::None);
     }
 
+    #[dialog_common::test]
+    fn it_treats_variables_bound_upstream_as_constants() {
+        // `of` is free in the scan but bound by an earlier step, so the scan
+        // reads EAV with entity and attribute fixed and sorts on value.
+        let query = DynamicAttributeQuery::All(scan(constant(), Term::var("of"), Term::var("is")));
+        let mut bound = Environment::new();
+        bound.add("of");
+        assert_eq!(
+            query.access(&bound),
+            (IndexOrder::Eav, SortOrder::On("is".to_string()))
+        );
+        assert_eq!(
+            query.access(&Environment::new()),
+            (IndexOrder::Aev, SortOrder::On("of".to_string()))
+        );
+    }
+
     #[dialog_common::test]
     fn it_merges_only_matching_variables() {
         assert!(SortOrder::On("x".to_string()).merges_with(&SortOrder::On("x".to_string())));
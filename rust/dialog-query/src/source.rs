@@ -21,7 +21,10 @@ pub(crate) mod test {
     use crate::session::RuleRegistry;
     use dialog_artifacts::inspect::Load;
     use dialog_artifacts::selector::Constrained;
-    use dialog_artifacts::{ArtifactSelector, ArtifactStream, DialogArtifactsError, Select};
+    use dialog_artifacts::{
+        ArtifactSelector, ArtifactStream, DialogArtifactsError, Search, Select, TextMatch,
+        TextSearch,
+    };
     use dialog_capability::Provider;
     use dialog_operator::Operator as DialogOperator;
     use dialog_repository::{Branch, NetworkedIndex, RepositoryArchiveExt as _};
@@ -73,6 +76,19 @@ pub(crate) mod test {
         }
     }
 
+    // Full-text search through the branch's own search command.
+    #[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+    #[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+    impl Provider<Search> for TestEnv<'_> {
+        async fn execute(&self, input: TextSearch) -> Result<Vec<TextMatch>, DialogArtifactsError> {
+            self.branch
+                .claims()
+                .search(input)
+                .perform(self.operator)
+                .await
+        }
+    }
+
     // Raw node loads for resolver premises, local archive only.
     #[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
     #[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
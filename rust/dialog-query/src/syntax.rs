@@ -0,0 +1,599 @@
+//! A textual notation for rules and premises.
+//!
+//! The formal notation (`notes/notation.md`) is explicit but verbose
+//! JSON; this is a compact surface syntax for writing the same rules by
+//! hand. Text reads as the formal notation and is validated by its
+//! deserializers, so the two accept exactly the same rules, and
+//! printing a descriptor back to text is lossless: reading the printed
+//! text yields an equal descriptor.
+//!
+//! ```text
+//! # Concepts can be defined once and referenced by name.
+//! concept Employee "A person on the payroll" {
+//!   name: org.employee/name as Text,
+//!   dept: org.employee/dept as Entity,
+//!   salary: org.employee/salary as UnsignedInteger,
+//!   tags?: org.employee/tag as Text many "Free-form labels",
+//! }
+//!
+//! "Total salary per department"
+//! deduce DeptTotal {
+//!   total: org.dept-total/total as UnsignedInteger,
+//! }
+//! when
+//!   Employee(?e, dept: ?this, salary: ?salary),
+//!   ?salary > 0
+//! unless
+//!   {retired: org.employee/retired as Boolean}(?e, retired: true)
+//! reduce
+//!   total: sum(?salary)
+//! ```
+//!
+//! # Grammar
+//!
+//! Whitespace is insignificant and `#` starts a comment that runs to
+//! the end of the line.
+//!
+//! ```text
+//! program    := (definition | rule)*
+//! definition := "concept" Name concept-body
+//! rule       := String? head "when" premises ("unless" premises)? ("reduce" reductions)?
+//! head       := "deduce" concept | (("assert!" | "retract!") concept)+
+//! concept    := Name | Name? concept-body
+//! concept-body := String? "{" (field ("," field)* ","?)? "}"
+//! field      := key "?"? ":" path ("as" Type)? ("many" | "one")? ("conforms" concept)? String?
+//! premises   := (premise ("," premise)*)?
+//! premise    := concept arguments | (Word | String) arguments | term operator term
+//! arguments  := "(" (term ","?)? (key ":" term ("," key ":" term)* ","?)? ")"
+//! operator   := "==" | "<" | "<=" | ">" | ">="
+//! reductions := reduction ("," reduction)*
+//! reduction  := key ":" Aggregator "(" term ("," term)? ")"
+//! term       := "?" Name | "_" | JSON
+//! ```
+//!
+//! - A premise's leading argument without a key binds `this`.
+//! - `?` after a field's key makes the field optional; `many` sets its
+//!   cardinality. The trailing string is the field's description.
+//! - `Name body` both defines `Name` and uses the concept, wherever a
+//!   concept can appear. Names must be defined before they are used.
+//! - A premise named by a word that is not a defined concept applies
+//!   the formula, constraint or resolver of that name (`math/sum`,
+//!   `starts-with`, `tree/span`). A quoted name always does.
+//! - `a == b` is the equality constraint over `this` and `is`;
+//!   `a < b` and the other orderings are range constraints over `of`
+//!   and `with`.
+//! - `reduce` is only allowed on `deduce` rules; each entry applies an
+//!   aggregator (`count`, `count-distinct`, `sum`, `min`, `max`, `avg`,
+//!   `median`, `variance`, `std-dev`, `collect-set`). A parameterized
+//!   aggregator takes its parameter second: `percentile(?x, 0.9)`,
+//!   `string-agg(?x, ", ")`, and `arg-min` / `arg-max` with the term
+//!   holding the entity to report, `arg-max(?salary, ?e)`.
+//! - Terms that are not variables are JSON literals. A typed variable
+//!   is written in its formal notation, `{"?": {"name": .., "type": ..}}`.
+//!
+//! Every rule that can be read can be printed. An inductive rule
+//! without a head does not deserialize, and a raw attribute premise,
+//! which has neither notation, only comes from a rule built in code:
+//! its descriptor does not [compile](DeductiveRuleDescriptor::compile),
+//! and printing it fails with [`SyntaxError::Inexpressible`].
+
+mod lexer;
+mod parser;
+mod printer;
+
+use std::fmt::{self, Display, Formatter};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::concept::descriptor::ConceptDescriptor;
+use crate::error::SyntaxError;
+use crate::proposition::Proposition;
+use crate::rule::{DeductiveRuleDescriptor, InductiveRuleDescriptor};
+use parser::Parser;
+use printer::Printer;
+
+/// Words that cannot name a concept.
+const RESERVED: [&str; 11] = [
+    "concept", "deduce", "when", "unless", "reduce", "assert!", "retract!", "true", "false",
+    "null", "_",
+];
+
+/// A region of source text: byte offsets, and the line and column
+/// (both counted from 1) where it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// Byte offset of the first character.
+    pub start: usize,
+    /// Byte offset just past the last character.
+    pub end: usize,
+    /// Line of the first character.
+    pub line: usize,
+    /// Column of the first character, in characters.
+    pub column: usize,
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Conversion between a descriptor and the textual notation.
+pub trait Syntax: Sized {
+    /// Read `source` as this descriptor.
+    fn parse(source: &str) -> Result<Self, SyntaxError>;
+
+    /// Write this descriptor in the textual notation.
+    fn to_syntax(&self) -> Result<String, SyntaxError>;
+}
+
+/// One top-level item of a [`Program`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Definition {
+    /// A named concept, defined on its own with `concept` or inline
+    /// where a rule uses it.
+    Concept {
+        /// The name the rest of the program refers to it by.
+        name: String,
+        /// The concept.
+        concept: ConceptDescriptor,
+    },
+    /// A `deduce` rule.
+    Deductive(DeductiveRuleDescriptor),
+    /// An `assert!` or `retract!` rule.
+    Inductive(InductiveRuleDescriptor),
+}
+
+/// A sequence of concept definitions and rules, in source order.
+///
+/// A concept defined inline (`deduce Name { .. }`) is listed just
+/// before the rule that defines it, so printing a program spells out
+/// its concepts once and refers to them by name from then on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Program {
+    /// The program's items.
+    pub definitions: Vec<Definition>,
+}
+
+impl Program {
+    /// The program's deductive rules.
+    pub fn deductive_rules(&self) -> impl Iterator<Item = &DeductiveRuleDescriptor> {
+        self.definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Deductive(rule) => Some(rule),
+                _ => None,
+            })
+    }
+
+    /// The program's inductive rules.
+    pub fn inductive_rules(&self) -> impl Iterator<Item = &InductiveRuleDescriptor> {
+        self.definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Inductive(rule) => Some(rule),
+                _ => None,
+            })
+    }
+}
+
+impl Syntax for Program {
+    fn parse(source: &str) -> Result<Self, SyntaxError> {
+        let definitions = Parser::new(source)?.program()?;
+        Ok(Self {
+            definitions: definitions
+                .into_iter()
+                .map(|(definition, _)| definition)
+                .collect(),
+        })
+    }
+
+    fn to_syntax(&self) -> Result<String, SyntaxError> {
+        let mut names = Vec::new();
+        let mut items = Vec::with_capacity(self.definitions.len());
+        for definition in &self.definitions {
+            let printer = Printer { names: &names };
+            match definition {
+                Definition::Concept { name, concept } => {
+                    let concept = formal(concept)?;
+                    items.push(printer.concept_definition(name, &concept)?);
+                    names.push((name.clone(), concept));
+                }
+                Definition::Deductive(rule) => items.push(printer.deductive(&formal(rule)?)?),
+                Definition::Inductive(rule) => items.push(printer.inductive(&formal(rule)?)?),
+            }
+        }
+        Ok(items
+            .into_iter()
+            .map(|item| item + "\n")
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+impl Syntax for DeductiveRuleDescriptor {
+    /// Read the one `deduce` rule in `source`, which may also define
+    /// the concepts it uses.
+    fn parse(source: &str) -> Result<Self, SyntaxError> {
+        single(
+            source,
+            "a single `deduce` rule",
+            |definition| match definition {
+                Definition::Deductive(rule) => Some(rule),
+                _ => None,
+            },
+        )
+    }
+
+    fn to_syntax(&self) -> Result<String, SyntaxError> {
+        Printer { names: &[] }.deductive(&formal(self)?)
+    }
+}
+
+impl Syntax for InductiveRuleDescriptor {
+    /// Read the one `assert!` or `retract!` rule in `source`, which may
+    /// also define the concepts it uses.
+    fn parse(source: &str) -> Result<Self, SyntaxError> {
+        single(
+            source,
+            "a single `assert!` or `retract!` rule",
+            |definition| match definition {
+                Definition::Inductive(rule) => Some(rule),
+                _ => None,
+            },
+        )
+    }
+
+    fn to_syntax(&self) -> Result<String, SyntaxError> {
+        Printer { names: &[] }.inductive(&formal(self)?)
+    }
+}
+
+impl Syntax for Proposition {
+    /// Read a single premise. Concepts must be written inline, as
+    /// there is nowhere to define a name.
+    fn parse(source: &str) -> Result<Self, SyntaxError> {
+        Parser::new(source)?.proposition()
+    }
+
+    fn to_syntax(&self) -> Result<String, SyntaxError> {
+        Printer { names: &[] }.premise(&formal(self)?, 0)
+    }
+}
+
+/// The one rule in `source` that `pick` accepts, alongside any number
+/// of concept definitions.
+fn single<T>(
+    source: &str,
+    expected: &str,
+    pick: impl Fn(Definition) -> Option<T>,
+) -> Result<T, SyntaxError> {
+    let mut found = None;
+    let mut end = None;
+    for (definition, span) in Parser::new(source)?.program()? {
+        end = Some(span);
+        if matches!(definition, Definition::Concept { .. }) {
+            continue;
+        }
+        match (pick(definition), &found) {
+            (Some(rule), None) => found = Some(rule),
+            _ => {
+                return Err(SyntaxError::Unexpected {
+                    expected: expected.into(),
+                    found: "another rule".into(),
+                    span,
+                });
+            }
+        }
+    }
+    found.ok_or_else(|| SyntaxError::Unexpected {
+        expected: expected.into(),
+        found: "no rule".into(),
+        span: end.unwrap_or(Span {
+            start: 0,
+            end: 0,
+            line: 1,
+            column: 1,
+        }),
+    })
+}
+
+/// `value` in the formal notation.
+fn formal(value: &impl Serialize) -> Result<Value, SyntaxError> {
+    serde_json::to_value(value).map_err(|error| SyntaxError::Inexpressible(error.to_string()))
+}
+
+/// A premise as text with variable types left out, for display only:
+/// the text does not read back as the same premise.
+pub(crate) fn sketch(proposition: &Proposition) -> Option<String> {
+    let mut value = formal(proposition).ok()?;
+    untype(&mut value);
+    Printer { names: &[] }.premise(&value, 0).ok()
+}
+
+/// A term as [`sketch`] prints it.
+pub(crate) fn sketch_term(term: &impl Serialize) -> String {
+    let mut value = formal(term).unwrap_or_default();
+    untype(&mut value);
+    printer::term(&value)
+}
+
+/// Drop the type annotations from every variable in `value`.
+fn untype(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::Object(variable)) = map.get_mut("?") {
+                variable.remove("type");
+            }
+            map.values_mut().for_each(untype);
+        }
+        Value::Array(items) => items.iter_mut().for_each(untype),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::Type;
+    use crate::attribute::The;
+    use crate::attribute::query::AttributeQuery;
+    use crate::constraint::Constraint;
+    use crate::error::TypeError;
+    use crate::term::Term;
+    use crate::types::Any;
+    use serde_json::json;
+
+    const PAYROLL: &str = r#"
+        # Concepts can be defined once and referenced by name.
+        concept Employee "A person on the payroll" {
+          name: org.employee/name as Text,
+          dept: org.employee/dept as Entity,
+          salary: org.employee/salary as UnsignedInteger,
+          tags?: org.employee/tag as Text many "Free-form labels",
+        }
+
+        "Total salary per department"
+        deduce DeptTotal {
+          total: org.dept-total/total as UnsignedInteger,
+        }
+        when
+          Employee(?e, dept: ?this, salary: ?salary),
+          ?salary > 0
+        unless
+          {retired: org.employee/retired as Boolean}(?e, retired: true)
+        reduce
+          total: sum(?salary)
+    "#;
+
+    fn variable(name: &str) -> serde_json::Value {
+        json!({ "?": { "name": name } })
+    }
+
+    fn payroll() -> DeductiveRuleDescriptor {
+        serde_json::from_value(json!({
+            "description": "Total salary per department",
+            "deduce": {
+                "with": {
+                    "total": { "the": "org.dept-total/total", "as": "UnsignedInteger" }
+                }
+            },
+            "when": [
+                {
+                    "assert": {
+                        "description": "A person on the payroll",
+                        "with": {
+                            "name": { "the": "org.employee/name", "as": "Text" },
+                            "dept": { "the": "org.employee/dept", "as": "Entity" },
+                            "salary": { "the": "org.employee/salary", "as": "UnsignedInteger" },
+                            "tags": {
+                                "the": "org.employee/tag",
+                                "as": "Text",
+                                "cardinality": "many",
+                                "optional": true,
+                                "description": "Free-form labels"
+                            }
+                        }
+                    },
+                    "where": {
+                        "this": variable("e"),
+                        "dept": variable("this"),
+                        "salary": variable("salary")
+                    }
+                },
+                { "assert": ">", "where": { "of": variable("salary"), "with": 0 } }
+            ],
+            "unless": [
+                {
+                    "assert": {
+                        "with": { "retired": { "the": "org.employee/retired", "as": "Boolean" } }
+                    },
+                    "where": { "this": variable("e"), "retired": true }
+                }
+            ],
+            "reduce": { "total": { "apply": "sum", "of": variable("salary") } }
+        }))
+        .unwrap()
+    }
+
+    #[dialog_common::test]
+    fn it_reads_a_rule_as_its_formal_notation() {
+        let rule = DeductiveRuleDescriptor::parse(PAYROLL).unwrap();
+        assert_eq!(rule, payroll());
+        rule.compile().unwrap();
+    }
+
+    #[dialog_common::test]
+    fn it_prints_rules_that_read_back_the_same() {
+        let rule = payroll();
+        let text = rule.to_syntax().unwrap();
+        assert_eq!(DeductiveRuleDescriptor::parse(&text).unwrap(), rule);
+        assert!(text.starts_with("\"Total salary per department\"\ndeduce {\n"));
+        assert!(text.contains("\n  ?salary > 0\n"), "{text}");
+        assert!(text.ends_with("reduce\n  total: sum(?salary)"), "{text}");
+    }
+
+    #[dialog_common::test]
+    fn it_reads_and_prints_parameterized_aggregators() {
+        let text = "deduce {dept: org.employee/dept, p90: org.pay/p90, names: org.pay/names, \
+                    top: org.pay/top} when\n  \
+                    {dept: org.employee/dept, salary: org.employee/salary, name: org.employee/name}\
+                    (?e, dept: ?dept, salary: ?salary, name: ?name)\n\
+                    reduce\n  names: string-agg(?name, \", \"),\n  \
+                    p90: percentile(?salary, 0.9),\n  top: arg-max(?salary, ?e)";
+        let rule = DeductiveRuleDescriptor::parse(text).unwrap();
+        assert_eq!(
+            serde_json::to_value(&rule).unwrap()["reduce"],
+            json!({
+                "names": { "apply": { "string-agg": ", " }, "of": variable("name") },
+                "p90": { "apply": { "percentile": 0.9 }, "of": variable("salary") },
+                "top": { "apply": { "arg-max": variable("e") }, "of": variable("salary") }
+            })
+        );
+        let printed = rule.to_syntax().unwrap();
+        assert!(
+            printed.ends_with(
+                "reduce\n  names: string-agg(?name, \", \"),\n  \
+                 p90: percentile(?salary, 0.9),\n  top: arg-max(?salary, ?e)"
+            ),
+            "{printed}"
+        );
+        assert_eq!(DeductiveRuleDescriptor::parse(&printed).unwrap(), rule);
+    }
+
+    #[dialog_common::test]
+    fn it_prints_programs_with_their_concept_names() {
+        let program = Program::parse(PAYROLL).unwrap();
+        let names: Vec<_> = program
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Concept { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, ["Employee", "DeptTotal"]);
+        assert_eq!(program.deductive_rules().count(), 1);
+
+        let text = program.to_syntax().unwrap();
+        assert!(text.contains("deduce DeptTotal\nwhen"), "{text}");
+        assert!(text.contains("Employee(?e, dept: ?this, salary: ?salary)"));
+        assert_eq!(Program::parse(&text).unwrap(), program);
+    }
+
+    #[dialog_common::test]
+    fn it_reads_inductive_rules() {
+        let rule = InductiveRuleDescriptor::parse(
+            r#"
+            concept Task { title: todo.task/title as Text, done: todo.task/done as Boolean }
+            retract! Task
+            when Task(?this, title: ?title, done: ?done), ?done == true
+            "#,
+        )
+        .unwrap();
+        assert!(rule.assert.is_none());
+        assert_eq!(rule.when.len(), 2);
+        rule.clone().compile().unwrap();
+
+        let text = rule.to_syntax().unwrap();
+        assert!(text.starts_with("retract! {\n"), "{text}");
+        assert_eq!(InductiveRuleDescriptor::parse(&text).unwrap(), rule);
+    }
+
+    #[dialog_common::test]
+    fn it_reads_formulas_constraints_and_literals() {
+        let sum = Proposition::parse("math/sum(of: ?x, with: 10, is: ?total)").unwrap();
+        assert!(matches!(sum, Proposition::Formula(_)));
+
+        let equality = Proposition::parse(r#"?name == "Alice""#).unwrap();
+        let Proposition::Constraint(Constraint::Equality(_)) = &equality else {
+            panic!("expected equality, got {equality:?}");
+        };
+        assert_eq!(equality.to_syntax().unwrap(), r#"?name == "Alice""#);
+
+        let prefix = Proposition::parse(r#""=="(this: ?name, is: "Alice")"#).unwrap();
+        assert_eq!(prefix, equality);
+
+        let concept = Proposition::parse(
+            r#"{n: person/name, a?: person/age as SignedInteger}(_, n: ?n, a: -3.5)"#,
+        )
+        .unwrap();
+        let Proposition::Concept(query) = &concept else {
+            panic!("expected a concept, got {concept:?}");
+        };
+        assert_eq!(query.terms.get("this"), Some(&Term::<Any>::blank()));
+        assert_eq!(
+            Proposition::parse(&concept.to_syntax().unwrap()).unwrap(),
+            concept
+        );
+    }
+
+    #[dialog_common::test]
+    fn it_prints_typed_variables_as_formal_notation() {
+        let x: Term<Any> = Term::Variable {
+            name: Some("x".into()),
+            descriptor: Some(Type::String).into(),
+        };
+        let typed: Proposition = serde_json::from_value(json!({
+            "assert": "==",
+            "where": { "this": x, "is": "a" }
+        }))
+        .unwrap();
+        let text = typed.to_syntax().unwrap();
+        assert!(text.starts_with(r#""=="({"?":"#), "{text}");
+        assert_eq!(Proposition::parse(&text).unwrap(), typed);
+    }
+
+    #[dialog_common::test]
+    fn it_reports_where_the_source_is_wrong() {
+        let error = Program::parse("deduce {\n  a: x/a\n} when\n  Person(?p)").unwrap_err();
+        assert_eq!(error.to_string(), "4:3: unknown concept `Person`");
+
+        let error = Proposition::parse("math/sum(of: ?x, of: ?y)").unwrap_err();
+        assert!(matches!(&error, SyntaxError::Duplicate { name, .. } if name == "of"));
+        assert_eq!(error.to_string(), "1:18: `of` is defined more than once");
+
+        let error = Proposition::parse("?x ~ 1").unwrap_err();
+        assert_eq!(error.to_string(), "1:4: expected a token, found `~`");
+
+        let error = Proposition::parse("?x == \"open").unwrap_err();
+        assert!(matches!(error, SyntaxError::UnterminatedString { span } if span.column == 7));
+
+        let error =
+            Program::parse("deduce {a: x/a} when\n  ?a == 1\nreduce\n  b: sum(?a)").unwrap_err();
+        assert!(
+            matches!(&error, SyntaxError::Invalid { span, .. } if span.line == 1 && span.column == 1),
+            "{error}"
+        );
+
+        let error = Program::parse("deduce {a: x/a} when ?a == 1\nreduce a: rand(?a)").unwrap_err();
+        assert!(
+            matches!(&error, SyntaxError::Invalid { span, .. } if span.line == 2 && span.column == 11),
+            "{error}"
+        );
+    }
+
+    #[dialog_common::test]
+    fn it_refuses_rules_it_cannot_print() {
+        let headless: Result<InductiveRuleDescriptor, _> =
+            serde_json::from_value(json!({ "when": [] }));
+        assert!(headless.is_err());
+
+        let of = Term::var("person");
+        let attribute = Proposition::Attribute(Box::new(AttributeQuery::new(
+            Term::from("person/name".parse::<The>().unwrap()),
+            of,
+            Term::var("name"),
+            Term::blank(),
+            None,
+        )));
+        assert!(matches!(
+            attribute.to_syntax(),
+            Err(SyntaxError::Inexpressible(_))
+        ));
+        let mut rule = payroll();
+        rule.when.push(attribute);
+        assert_eq!(rule.compile().unwrap_err(), TypeError::InexpressiblePremise);
+    }
+}
@@ -0,0 +1,250 @@
+//! Splits source text into [`Lexeme`]s.
+//!
+//! String and number literals are decoded with `serde_json`, so they
+//! follow JSON's rules exactly and re-encode to the same JSON.
+
+use serde_json::Number;
+
+use super::Span;
+use crate::error::SyntaxError;
+
+/// A token of the surface syntax.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Token {
+    /// A bare word: a keyword, name, attribute path or aggregator
+    /// (`deduce`, `Employee`, `org.employee/name`, `assert!`).
+    Word(String),
+    /// A named variable, `?name`, without its sigil.
+    Variable(String),
+    /// A JSON string literal, decoded.
+    Text(String),
+    /// A JSON number literal.
+    Number(Number),
+    /// Punctuation or an infix operator.
+    Symbol(&'static str),
+    /// The end of the input.
+    End,
+}
+
+impl Token {
+    /// How the token reads in an error message.
+    pub(super) fn describe(&self) -> String {
+        match self {
+            Token::Word(word) => format!("`{word}`"),
+            Token::Variable(name) => format!("`?{name}`"),
+            Token::Text(text) => format!("{text:?}"),
+            Token::Number(number) => format!("`{number}`"),
+            Token::Symbol(symbol) => format!("`{symbol}`"),
+            Token::End => "end of input".into(),
+        }
+    }
+}
+
+/// A token and where it appears in the source.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Lexeme {
+    pub token: Token,
+    pub span: Span,
+}
+
+/// Symbols in the order they are matched, longest first.
+const SYMBOLS: [&str; 14] = [
+    "==", "<=", ">=", "<", ">", "{", "}", "(", ")", "[", "]", ",", ":", "?",
+];
+
+/// Split `source` into lexemes, ending with [`Token::End`].
+pub(super) fn tokenize(source: &str) -> Result<Vec<Lexeme>, SyntaxError> {
+    let mut lexer = Lexer {
+        source,
+        offset: 0,
+        line: 1,
+        column: 1,
+    };
+    let mut lexemes = Vec::new();
+    loop {
+        let lexeme = lexer.next()?;
+        let end = lexeme.token == Token::End;
+        lexemes.push(lexeme);
+        if end {
+            return Ok(lexemes);
+        }
+    }
+}
+
+struct Lexer<'a> {
+    source: &'a str,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Lexer<'_> {
+    fn rest(&self) -> &str {
+        &self.source[self.offset..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.rest().chars().nth(1)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let next = self.peek()?;
+        self.offset += next.len_utf8();
+        if next == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(next)
+    }
+
+    fn bump_while(&mut self, accept: impl Fn(char) -> bool) {
+        while self.peek().is_some_and(&accept) {
+            self.bump();
+        }
+    }
+
+    /// A zero-width span at the current position.
+    fn here(&self) -> Span {
+        Span {
+            start: self.offset,
+            end: self.offset,
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn skip_trivia(&mut self) {
+        loop {
+            match self.peek() {
+                Some(next) if next.is_whitespace() => {
+                    self.bump();
+                }
+                Some('#') => self.bump_while(|next| next != '\n'),
+                _ => return,
+            }
+        }
+    }
+
+    fn next(&mut self) -> Result<Lexeme, SyntaxError> {
+        self.skip_trivia();
+        let mut span = self.here();
+        let token = match self.peek() {
+            None => Token::End,
+            Some('"') => self.string(span)?,
+            Some(next) if next.is_ascii_digit() => self.number(span)?,
+            Some('-') if self.peek_second().is_some_and(|next| next.is_ascii_digit()) => {
+                self.number(span)?
+            }
+            Some('?') if self.peek_second().is_some_and(is_variable_char) => {
+                self.bump();
+                let start = self.offset;
+                self.bump_while(is_variable_char);
+                Token::Variable(self.source[start..self.offset].into())
+            }
+            Some(next) if is_word_start(next) => {
+                self.bump_while(is_word_char);
+                let mut word = self.source[span.start..self.offset].to_string();
+                if matches!(word.as_str(), "assert" | "retract") && self.peek() == Some('!') {
+                    self.bump();
+                    word.push('!');
+                }
+                Token::Word(word)
+            }
+            Some(next) => {
+                let symbol = SYMBOLS
+                    .into_iter()
+                    .find(|symbol| self.rest().starts_with(symbol))
+                    .ok_or_else(|| SyntaxError::Unexpected {
+                        expected: "a token".into(),
+                        found: format!("`{next}`"),
+                        span,
+                    })?;
+                for _ in symbol.chars() {
+                    self.bump();
+                }
+                Token::Symbol(symbol)
+            }
+        };
+        span.end = self.offset;
+        Ok(Lexeme { token, span })
+    }
+
+    fn string(&mut self, span: Span) -> Result<Token, SyntaxError> {
+        self.bump();
+        loop {
+            match self.bump() {
+                Some('"') => break,
+                Some('\\') => {
+                    self.bump();
+                }
+                Some('\n') | None => return Err(SyntaxError::UnterminatedString { span }),
+                Some(_) => {}
+            }
+        }
+        let literal = &self.source[span.start..self.offset];
+        serde_json::from_str(literal)
+            .map(Token::Text)
+            .map_err(|error| SyntaxError::InvalidLiteral {
+                reason: error.to_string(),
+                span: Span {
+                    end: self.offset,
+                    ..span
+                },
+            })
+    }
+
+    fn number(&mut self, span: Span) -> Result<Token, SyntaxError> {
+        if self.peek() == Some('-') {
+            self.bump();
+        }
+        self.bump_while(|next| next.is_ascii_digit());
+        if self.peek() == Some('.') && self.peek_second().is_some_and(|next| next.is_ascii_digit())
+        {
+            self.bump();
+            self.bump_while(|next| next.is_ascii_digit());
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.bump();
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.bump();
+            }
+            self.bump_while(|next| next.is_ascii_digit());
+        }
+        let literal = &self.source[span.start..self.offset];
+        serde_json::from_str(literal)
+            .map(Token::Number)
+            .map_err(|error| SyntaxError::InvalidLiteral {
+                reason: error.to_string(),
+                span: Span {
+                    end: self.offset,
+                    ..span
+                },
+            })
+    }
+}
+
+fn is_word_start(next: char) -> bool {
+    next.is_ascii_alphabetic() || next == '_'
+}
+
+/// Words may contain the separators of attribute paths and formula
+/// names, so `org.employee/name` and `math/sum` are single words.
+pub(super) fn is_word_char(next: char) -> bool {
+    next.is_ascii_alphanumeric() || matches!(next, '_' | '.' | '-' | '/')
+}
+
+pub(super) fn is_variable_char(next: char) -> bool {
+    next.is_ascii_alphanumeric() || matches!(next, '_' | '-')
+}
+
+/// Whether `text` lexes back as a single [`Token::Word`].
+pub(super) fn is_word(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(is_word_start) && chars.all(is_word_char)
+}
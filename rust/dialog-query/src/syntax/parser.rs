@@ -0,0 +1,593 @@
+//! Reads [`Lexeme`]s into the formal notation.
+//!
+//! Every construct is built as its formal-notation JSON and handed to
+//! that notation's own deserializer, so the textual notation accepts
+//! exactly what the formal one does. Each construct is checked as soon
+//! as it is complete, so a rejection points at the smallest span that
+//! contains the problem.
+
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+
+use super::lexer::{Lexeme, Token, tokenize};
+use super::{Definition, RESERVED, Span};
+use crate::concept::descriptor::ConceptDescriptor;
+use crate::error::SyntaxError;
+use crate::proposition::Proposition;
+use crate::reduce::ReduceSpec;
+use crate::rule::{DeductiveRuleDescriptor, InductiveRuleDescriptor};
+
+/// The names of the constraints, which unlike formula and resolver
+/// names carry no `domain/` prefix.
+const CONSTRAINTS: [&str; 8] = [
+    "==",
+    "<",
+    "<=",
+    ">",
+    ">=",
+    "coalesce",
+    "type",
+    "starts-with",
+];
+
+/// Words that start a rule.
+const RULE_HEADS: [&str; 3] = ["deduce", "assert!", "retract!"];
+
+pub(super) struct Parser {
+    lexemes: Vec<Lexeme>,
+    position: usize,
+    /// Concepts defined so far, by name, in formal notation.
+    concepts: BTreeMap<String, Value>,
+    /// Everything defined so far, in source order.
+    definitions: Vec<(Definition, Span)>,
+}
+
+impl Parser {
+    pub fn new(source: &str) -> Result<Self, SyntaxError> {
+        Ok(Self {
+            lexemes: tokenize(source)?,
+            position: 0,
+            concepts: BTreeMap::new(),
+            definitions: Vec::new(),
+        })
+    }
+
+    /// Read concept definitions and rules up to the end of the input.
+    pub fn program(mut self) -> Result<Vec<(Definition, Span)>, SyntaxError> {
+        while *self.peek() != Token::End {
+            match self.peek() {
+                Token::Word(word) if word == "concept" => self.concept_definition()?,
+                Token::Word(word) if RULE_HEADS.contains(&word.as_str()) => self.rule()?,
+                Token::Text(_) if self.starts_rule(1) => self.rule()?,
+                _ => {
+                    return Err(self.unexpected("`concept`, `deduce`, `assert!` or `retract!`"));
+                }
+            }
+        }
+        Ok(self.definitions)
+    }
+
+    /// Read a single premise that makes up the whole input.
+    pub fn proposition(mut self) -> Result<Proposition, SyntaxError> {
+        let (proposition, span) = self.premise()?;
+        if *self.peek() != Token::End {
+            return Err(self.unexpected("end of input"));
+        }
+        decode(proposition, span)
+    }
+
+    fn peek(&self) -> &Token {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, ahead: usize) -> &Token {
+        let index = (self.position + ahead).min(self.lexemes.len() - 1);
+        &self.lexemes[index].token
+    }
+
+    /// The span of the next lexeme.
+    fn span(&self) -> Span {
+        self.lexemes[self.position].span
+    }
+
+    /// The span from `start` to the end of the last lexeme read.
+    fn since(&self, start: Span) -> Span {
+        let end = self.position.saturating_sub(1);
+        Span {
+            end: self.lexemes[end].span.end.max(start.start),
+            ..start
+        }
+    }
+
+    fn advance(&mut self) -> Lexeme {
+        let lexeme = self.lexemes[self.position].clone();
+        if lexeme.token != Token::End {
+            self.position += 1;
+        }
+        lexeme
+    }
+
+    fn unexpected(&self, expected: &str) -> SyntaxError {
+        SyntaxError::Unexpected {
+            expected: expected.into(),
+            found: self.peek().describe(),
+            span: self.span(),
+        }
+    }
+
+    fn is_symbol(&self, ahead: usize, symbol: &str) -> bool {
+        matches!(self.peek_at(ahead), Token::Symbol(found) if *found == symbol)
+    }
+
+    fn is_word(&self, ahead: usize, word: &str) -> bool {
+        matches!(self.peek_at(ahead), Token::Word(found) if found == word)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = self.is_symbol(0, symbol);
+        if found {
+            self.advance();
+        }
+        found
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let found = self.is_word(0, word);
+        if found {
+            self.advance();
+        }
+        found
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), SyntaxError> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{symbol}`")))
+        }
+    }
+
+    fn eat_text(&mut self) -> Option<String> {
+        match self.peek() {
+            Token::Text(text) => {
+                let text = text.clone();
+                self.advance();
+                Some(text)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the lexeme `ahead` starts a rule head.
+    fn starts_rule(&self, ahead: usize) -> bool {
+        RULE_HEADS.iter().any(|head| self.is_word(ahead, head))
+    }
+
+    /// A field key, argument key or path: a word or a string.
+    fn name(&mut self, expected: &str) -> Result<(String, Span), SyntaxError> {
+        let span = self.span();
+        match self.peek() {
+            Token::Word(word) => {
+                let word = word.clone();
+                self.advance();
+                Ok((word, span))
+            }
+            Token::Text(text) => {
+                let text = text.clone();
+                self.advance();
+                Ok((text, span))
+            }
+            _ => Err(self.unexpected(expected)),
+        }
+    }
+
+    /// `concept Name "description"? { fields }`
+    fn concept_definition(&mut self) -> Result<(), SyntaxError> {
+        self.advance();
+        let Token::Word(name) = self.peek().clone() else {
+            return Err(self.unexpected("a concept name"));
+        };
+        self.defined_concept(name)?;
+        Ok(())
+    }
+
+    /// `Name "description"? { fields }`, registering the concept under
+    /// `Name`.
+    fn defined_concept(&mut self, name: String) -> Result<Value, SyntaxError> {
+        let start = self.span();
+        if RESERVED.contains(&name.as_str()) {
+            return Err(self.unexpected("a concept name"));
+        }
+        if self.concepts.contains_key(&name) {
+            return Err(SyntaxError::Duplicate { name, span: start });
+        }
+        self.advance();
+        let concept = self.concept_body()?;
+        let span = self.since(start);
+        let descriptor = decode(concept.clone(), span)?;
+        self.concepts.insert(name.clone(), concept.clone());
+        self.definitions.push((
+            Definition::Concept {
+                name,
+                concept: descriptor,
+            },
+            span,
+        ));
+        Ok(concept)
+    }
+
+    /// A concept by name, or inline: `Name`, `Name "description"? {
+    /// fields }` (which also defines `Name`), or `"description"? {
+    /// fields }`.
+    fn concept(&mut self) -> Result<Value, SyntaxError> {
+        match self.peek().clone() {
+            Token::Word(name)
+                if self.is_symbol(1, "{")
+                    || matches!(self.peek_at(1), Token::Text(_)) && self.is_symbol(2, "{") =>
+            {
+                self.defined_concept(name)
+            }
+            Token::Word(name) if !RESERVED.contains(&name.as_str()) => {
+                let span = self.span();
+                let concept = self
+                    .concepts
+                    .get(&name)
+                    .cloned()
+                    .ok_or(SyntaxError::UnknownConcept { name, span })?;
+                self.advance();
+                Ok(concept)
+            }
+            Token::Text(_) | Token::Symbol("{") => {
+                let start = self.span();
+                let concept = self.concept_body()?;
+                decode::<ConceptDescriptor>(concept.clone(), self.since(start))?;
+                Ok(concept)
+            }
+            _ => Err(self.unexpected("a concept")),
+        }
+    }
+
+    /// `"description"? { field, ... }`
+    fn concept_body(&mut self) -> Result<Value, SyntaxError> {
+        let mut concept = Map::new();
+        if let Some(description) = self.eat_text() {
+            concept.insert("description".into(), description.into());
+        }
+        self.expect_symbol("{")?;
+        let mut fields = Map::new();
+        while !self.eat_symbol("}") {
+            let (key, span) = self.name("a field name or `}`")?;
+            if fields.contains_key(&key) {
+                return Err(SyntaxError::Duplicate { name: key, span });
+            }
+            let field = self.field()?;
+            fields.insert(key, field);
+            if !self.eat_symbol(",") {
+                self.expect_symbol("}")?;
+                break;
+            }
+        }
+        concept.insert("with".into(), fields.into());
+        Ok(concept.into())
+    }
+
+    /// `?: path (as Type)? (many | one)? (conforms concept)? "description"?`,
+    /// after the field's key.
+    fn field(&mut self) -> Result<Value, SyntaxError> {
+        let mut field = Map::new();
+        if self.eat_symbol("?") {
+            field.insert("optional".into(), true.into());
+        }
+        self.expect_symbol(":")?;
+        let (the, _) = self.name("an attribute such as `domain/name`")?;
+        field.insert("the".into(), the.into());
+        if self.eat_word("as") {
+            let (kind, _) = self.name("a value type")?;
+            field.insert("as".into(), kind.into());
+        }
+        for cardinality in ["many", "one"] {
+            if self.eat_word(cardinality) {
+                field.insert("cardinality".into(), cardinality.into());
+                break;
+            }
+        }
+        if self.eat_word("conforms") {
+            let concept = self.concept()?;
+            field.insert("conforms".into(), concept);
+        }
+        if let Some(description) = self.eat_text() {
+            field.insert("description".into(), description.into());
+        }
+        Ok(field.into())
+    }
+
+    /// `"description"? head when premises (unless premises)? (reduce
+    /// entries)?`, where the head is `deduce concept` or one or more
+    /// of `assert! concept` and `retract! concept`.
+    fn rule(&mut self) -> Result<(), SyntaxError> {
+        let start = self.span();
+        let mut rule = Map::new();
+        if let Some(description) = self.eat_text() {
+            rule.insert("description".into(), description.into());
+        }
+        let deductive = self.eat_word("deduce");
+        if deductive {
+            let head = self.concept()?;
+            rule.insert("deduce".into(), head);
+        } else {
+            while let Token::Word(head) = self.peek().clone() {
+                if head != "assert!" && head != "retract!" {
+                    break;
+                }
+                if rule.contains_key(&head) {
+                    return Err(SyntaxError::Duplicate {
+                        name: head,
+                        span: self.span(),
+                    });
+                }
+                self.advance();
+                let concept = self.concept()?;
+                rule.insert(head, concept);
+            }
+        }
+
+        if !self.eat_word("when") {
+            return Err(self.unexpected("`when`"));
+        }
+        rule.insert("when".into(), self.premises()?.into());
+        if self.eat_word("unless") {
+            rule.insert("unless".into(), self.premises()?.into());
+        }
+        if deductive && self.eat_word("reduce") {
+            rule.insert("reduce".into(), self.reduce()?);
+        }
+
+        let span = self.since(start);
+        let rule = Value::from(rule);
+        let definition = if deductive {
+            Definition::Deductive(decode::<DeductiveRuleDescriptor>(rule, span)?)
+        } else {
+            Definition::Inductive(decode::<InductiveRuleDescriptor>(rule, span)?)
+        };
+        self.definitions.push((definition, span));
+        Ok(())
+    }
+
+    /// Whether the next lexeme can start a premise rather than end a
+    /// clause.
+    fn starts_premise(&self) -> bool {
+        match self.peek() {
+            Token::Word(word) => !RESERVED.contains(&word.as_str()) || is_literal(word),
+            Token::Text(_) => !self.starts_rule(1),
+            Token::Variable(_) | Token::Number(_) => true,
+            Token::Symbol(symbol) => matches!(*symbol, "{" | "["),
+            Token::End => false,
+        }
+    }
+
+    /// `premise, ...`, possibly none.
+    fn premises(&mut self) -> Result<Vec<Value>, SyntaxError> {
+        let mut premises = Vec::new();
+        if !self.starts_premise() {
+            return Ok(premises);
+        }
+        loop {
+            let (premise, span) = self.premise()?;
+            decode::<Proposition>(premise.clone(), span)?;
+            premises.push(premise);
+            if !self.eat_symbol(",") {
+                return Ok(premises);
+            }
+        }
+    }
+
+    /// A concept applied to arguments, a named formula, constraint or
+    /// resolver applied to arguments, or an infix comparison.
+    fn premise(&mut self) -> Result<(Value, Span), SyntaxError> {
+        let start = self.span();
+        let premise = match (self.peek().clone(), self.is_symbol(1, "(")) {
+            (Token::Word(name), true) if !self.concepts.contains_key(&name) => {
+                if RESERVED.contains(&name.as_str()) {
+                    return Err(self.unexpected("a premise"));
+                }
+                self.advance();
+                let arguments = self.arguments()?;
+                let premise = json!({ "assert": name, "where": arguments });
+                return self.named(premise, name, start);
+            }
+            (Token::Text(name), true) => {
+                self.advance();
+                let arguments = self.arguments()?;
+                let premise = json!({ "assert": name, "where": arguments });
+                return self.named(premise, name, start);
+            }
+            (Token::Word(word), _) if !is_literal(&word) => self.applied()?,
+            (Token::Symbol("{"), _) => self.applied()?,
+            (Token::Text(_), _) if self.is_symbol(1, "{") => self.applied()?,
+            _ => self.comparison()?,
+        };
+        Ok((premise, self.since(start)))
+    }
+
+    /// A named proposition, reporting an undefined concept rather than
+    /// an unknown formula when the name looks like a concept's.
+    fn named(
+        &mut self,
+        premise: Value,
+        name: String,
+        start: Span,
+    ) -> Result<(Value, Span), SyntaxError> {
+        let span = self.since(start);
+        match serde_json::from_value::<Proposition>(premise.clone()) {
+            Ok(_) => Ok((premise, span)),
+            Err(_) if !name.contains('/') && !CONSTRAINTS.contains(&name.as_str()) => {
+                Err(SyntaxError::UnknownConcept { name, span: start })
+            }
+            Err(error) => Err(SyntaxError::Invalid {
+                reason: error.to_string(),
+                span,
+            }),
+        }
+    }
+
+    /// `concept(arguments)`
+    fn applied(&mut self) -> Result<Value, SyntaxError> {
+        let concept = self.concept()?;
+        let arguments = self.arguments()?;
+        Ok(json!({ "assert": concept, "where": arguments }))
+    }
+
+    /// `term op term`, where `==` reads as the equality constraint and
+    /// `<`, `<=`, `>` and `>=` as range constraints.
+    fn comparison(&mut self) -> Result<Value, SyntaxError> {
+        let left = self.term()?;
+        let operator = match self.peek() {
+            Token::Symbol(operator @ ("==" | "<" | "<=" | ">" | ">=")) => *operator,
+            _ => return Err(self.unexpected("a comparison operator")),
+        };
+        self.advance();
+        let right = self.term()?;
+        Ok(if operator == "==" {
+            json!({ "assert": operator, "where": { "this": left, "is": right } })
+        } else {
+            json!({ "assert": operator, "where": { "of": left, "with": right } })
+        })
+    }
+
+    /// `(term?, key: term, ...)`, where a leading term without a key
+    /// binds `this`.
+    fn arguments(&mut self) -> Result<Value, SyntaxError> {
+        self.expect_symbol("(")?;
+        let mut arguments = Map::new();
+        while !self.eat_symbol(")") {
+            let span = self.span();
+            let keyed =
+                matches!(self.peek(), Token::Word(_) | Token::Text(_)) && self.is_symbol(1, ":");
+            let key = if keyed {
+                let (key, _) = self.name("an argument name")?;
+                self.advance();
+                key
+            } else if arguments.is_empty() {
+                "this".into()
+            } else {
+                return Err(self.unexpected("an argument such as `name: ?name`"));
+            };
+            if arguments.contains_key(&key) {
+                return Err(SyntaxError::Duplicate { name: key, span });
+            }
+            arguments.insert(key, self.term()?);
+            if !self.eat_symbol(",") {
+                self.expect_symbol(")")?;
+                break;
+            }
+        }
+        Ok(arguments.into())
+    }
+
+    /// `?name`, `_` (a blank variable), or a JSON literal.
+    fn term(&mut self) -> Result<Value, SyntaxError> {
+        match self.peek().clone() {
+            Token::Variable(name) => {
+                self.advance();
+                Ok(json!({ "?": { "name": name } }))
+            }
+            Token::Word(word) if word == "_" => {
+                self.advance();
+                Ok(json!({ "?": {} }))
+            }
+            _ => self.literal("a term"),
+        }
+    }
+
+    /// A JSON value.
+    fn literal(&mut self, expected: &str) -> Result<Value, SyntaxError> {
+        let value = match self.peek().clone() {
+            Token::Text(text) => text.into(),
+            Token::Number(number) => number.into(),
+            Token::Word(word) if word == "true" => true.into(),
+            Token::Word(word) if word == "false" => false.into(),
+            Token::Word(word) if word == "null" => Value::Null,
+            Token::Symbol("[") => {
+                self.advance();
+                let mut items = Vec::new();
+                while !self.eat_symbol("]") {
+                    items.push(self.literal("a JSON value")?);
+                    if !self.eat_symbol(",") {
+                        self.expect_symbol("]")?;
+                        break;
+                    }
+                }
+                return Ok(items.into());
+            }
+            Token::Symbol("{") => {
+                self.advance();
+                let mut entries = Map::new();
+                while !self.eat_symbol("}") {
+                    let Some(key) = self.eat_text() else {
+                        return Err(self.unexpected("a string key"));
+                    };
+                    self.expect_symbol(":")?;
+                    entries.insert(key, self.literal("a JSON value")?);
+                    if !self.eat_symbol(",") {
+                        self.expect_symbol("}")?;
+                        break;
+                    }
+                }
+                return Ok(entries.into());
+            }
+            _ => return Err(self.unexpected(expected)),
+        };
+        self.advance();
+        Ok(value)
+    }
+
+    /// `field: aggregator(term), ...`, where a parameterized
+    /// aggregator takes its parameter as a second argument:
+    /// `percentile(?x, 0.9)`, `string-agg(?x, ", ")`,
+    /// `arg-max(?x, ?entity)`.
+    fn reduce(&mut self) -> Result<Value, SyntaxError> {
+        let mut entries = Map::new();
+        loop {
+            let (field, span) = self.name("a reduced field")?;
+            if entries.contains_key(&field) {
+                return Err(SyntaxError::Duplicate { name: field, span });
+            }
+            self.expect_symbol(":")?;
+            let start = self.span();
+            let Token::Word(aggregator) = self.peek().clone() else {
+                return Err(self.unexpected("an aggregator such as `sum`"));
+            };
+            self.advance();
+            self.expect_symbol("(")?;
+            let of = self.term()?;
+            let apply = if self.eat_symbol(",") {
+                json!({ aggregator: self.term()? })
+            } else {
+                aggregator.into()
+            };
+            self.expect_symbol(")")?;
+            let entry = json!({ "apply": apply, "of": of });
+            decode::<ReduceSpec>(entry.clone(), self.since(start))?;
+            entries.insert(field, entry);
+            if !self.eat_symbol(",") {
+                return Ok(entries.into());
+            }
+        }
+    }
+}
+
+/// Words that read as JSON literals or a blank variable.
+fn is_literal(word: &str) -> bool {
+    matches!(word, "true" | "false" | "null" | "_")
+}
+
+/// Deserialize `value` from the formal notation, blaming `span` if it
+/// is rejected.
+fn decode<T: DeserializeOwned>(value: Value, span: Span) -> Result<T, SyntaxError> {
+    serde_json::from_value(value).map_err(|error| SyntaxError::Invalid {
+        reason: error.to_string(),
+        span,
+    })
+}
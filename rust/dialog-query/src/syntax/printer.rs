@@ -0,0 +1,338 @@
+//! Writes the formal notation as text.
+//!
+//! The printer works from the formal-notation JSON that descriptors
+//! serialize to, and refuses any key it has no syntax for rather than
+//! dropping it, so whatever it prints reads back as the same
+//! descriptor.
+
+use serde_json::{Map, Value};
+
+use super::RESERVED;
+use super::lexer::{is_variable_char, is_word};
+use crate::error::SyntaxError;
+
+/// The widest inline concept printed on a single line.
+const LINE_WIDTH: usize = 64;
+
+/// Prints the formal notation, naming the concepts in `names` wherever
+/// an identical concept appears.
+pub(super) struct Printer<'a> {
+    pub names: &'a [(String, Value)],
+}
+
+impl Printer<'_> {
+    /// `concept Name "description"? { fields }`
+    pub fn concept_definition(&self, name: &str, concept: &Value) -> Result<String, SyntaxError> {
+        if !is_word(name) || RESERVED.contains(&name) {
+            return Err(SyntaxError::Inexpressible(format!(
+                "`{name}` is not a valid concept name"
+            )));
+        }
+        Ok(format!("concept {name} {}", self.body(concept, 0, true)?))
+    }
+
+    pub fn deductive(&self, rule: &Value) -> Result<String, SyntaxError> {
+        let rule = object(
+            rule,
+            "rule",
+            &["description", "deduce", "when", "unless", "reduce"],
+        )?;
+        let mut text = description(rule);
+        text.push_str("deduce ");
+        text.push_str(&self.head(field(rule, "deduce")?)?);
+        self.body_clauses(rule, &mut text)?;
+        if let Some(reduce) = rule.get("reduce") {
+            text.push_str("\nreduce");
+            let entries = object(reduce, "reduce clause", &[])?;
+            for (index, (name, entry)) in entries.iter().enumerate() {
+                let entry = object(entry, "reduce entry", &["apply", "of"])?;
+                let of = term(field(entry, "of")?);
+                let application = match entry.get("apply") {
+                    Some(Value::String(aggregator)) => format!("{aggregator}({of})"),
+                    Some(Value::Object(apply)) => match Vec::from_iter(apply)[..] {
+                        [(aggregator, parameter)] => {
+                            format!("{aggregator}({of}, {})", term(parameter))
+                        }
+                        _ => return Err(inexpressible("reduce entry without an aggregator")),
+                    },
+                    _ => return Err(inexpressible("reduce entry without an aggregator")),
+                };
+                text.push_str(if index == 0 { "\n  " } else { ",\n  " });
+                text.push_str(&format!("{}: {application}", key(name)));
+            }
+        }
+        Ok(text)
+    }
+
+    pub fn inductive(&self, rule: &Value) -> Result<String, SyntaxError> {
+        let rule = object(
+            rule,
+            "rule",
+            &["description", "assert!", "retract!", "when", "unless"],
+        )?;
+        let mut text = description(rule);
+        let mut headless = true;
+        for head in ["assert!", "retract!"] {
+            if let Some(concept) = rule.get(head) {
+                if !headless {
+                    text.push('\n');
+                }
+                text.push_str(&format!("{head} {}", self.head(concept)?));
+                headless = false;
+            }
+        }
+        if headless {
+            return Err(inexpressible(
+                "an inductive rule needs an `assert!` or `retract!` head",
+            ));
+        }
+        self.body_clauses(rule, &mut text)?;
+        Ok(text)
+    }
+
+    /// The `when` and `unless` clauses.
+    fn body_clauses(
+        &self,
+        rule: &Map<String, Value>,
+        text: &mut String,
+    ) -> Result<(), SyntaxError> {
+        for clause in ["when", "unless"] {
+            let premises = match rule.get(clause) {
+                Some(Value::Array(premises)) => premises,
+                Some(_) => return Err(inexpressible(&format!("`{clause}` is not a list"))),
+                None if clause == "when" => return Err(inexpressible("rule without `when`")),
+                None => continue,
+            };
+            text.push('\n');
+            text.push_str(clause);
+            for (index, premise) in premises.iter().enumerate() {
+                text.push_str(if index == 0 { "\n  " } else { ",\n  " });
+                text.push_str(&self.premise(premise, 2)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// A rule's head, by name or spread over several lines.
+    fn head(&self, concept: &Value) -> Result<String, SyntaxError> {
+        match self.name_of(concept) {
+            Some(name) => Ok(name.into()),
+            None => self.body(concept, 0, true),
+        }
+    }
+
+    pub fn premise(&self, premise: &Value, indent: usize) -> Result<String, SyntaxError> {
+        let premise = object(premise, "premise", &["assert", "where"])?;
+        let arguments = object(field(premise, "where")?, "arguments", &[])?;
+        match field(premise, "assert")? {
+            Value::String(name) => {
+                if let Some(comparison) = comparison(name, arguments) {
+                    return Ok(comparison);
+                }
+                let named = is_word(name)
+                    && !RESERVED.contains(&name.as_str())
+                    && self.names.iter().all(|(concept, _)| concept != name);
+                let name = if named {
+                    name.clone()
+                } else {
+                    Value::from(name.as_str()).to_string()
+                };
+                Ok(format!("{name}{}", self.arguments(arguments)))
+            }
+            concept => Ok(format!(
+                "{}{}",
+                self.concept(concept, indent)?,
+                self.arguments(arguments)
+            )),
+        }
+    }
+
+    /// A concept by name, on one line when short, or else spread over
+    /// several lines indented past `indent`.
+    fn concept(&self, concept: &Value, indent: usize) -> Result<String, SyntaxError> {
+        if let Some(name) = self.name_of(concept) {
+            return Ok(name.into());
+        }
+        let inline = self.body(concept, indent, false)?;
+        if inline.len() <= LINE_WIDTH && !inline.contains('\n') {
+            Ok(inline)
+        } else {
+            self.body(concept, indent, true)
+        }
+    }
+
+    fn name_of(&self, concept: &Value) -> Option<&str> {
+        self.names
+            .iter()
+            .find(|(_, named)| named == concept)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// `"description"? { fields }`
+    fn body(&self, concept: &Value, indent: usize, multiline: bool) -> Result<String, SyntaxError> {
+        let concept = object(concept, "concept", &["description", "with"])?;
+        let mut text = match concept.get("description") {
+            Some(Value::String(description)) => format!("{} ", Value::from(description.as_str())),
+            Some(_) => return Err(inexpressible("concept description is not a string")),
+            None => String::new(),
+        };
+        let fields = object(field(concept, "with")?, "concept fields", &[])?;
+        let mut rendered = Vec::with_capacity(fields.len());
+        for (name, field) in fields {
+            rendered.push(self.field(name, field, indent + 2)?);
+        }
+        if multiline {
+            let padding = " ".repeat(indent + 2);
+            text.push_str("{\n");
+            for field in rendered {
+                text.push_str(&format!("{padding}{field},\n"));
+            }
+            text.push_str(&format!("{}}}", " ".repeat(indent)));
+        } else {
+            text.push_str(&format!("{{{}}}", rendered.join(", ")));
+        }
+        Ok(text)
+    }
+
+    /// `key?: path as Type many conforms concept "description"`
+    fn field(&self, name: &str, field: &Value, indent: usize) -> Result<String, SyntaxError> {
+        let field = object(
+            field,
+            "concept field",
+            &[
+                "the",
+                "as",
+                "cardinality",
+                "optional",
+                "conforms",
+                "description",
+            ],
+        )?;
+        let mut text = key(name);
+        match field.get("optional") {
+            Some(Value::Bool(true)) => text.push('?'),
+            Some(Value::Bool(false)) | None => {}
+            Some(_) => return Err(inexpressible("`optional` is not a boolean")),
+        }
+        let Value::String(the) = self::field(field, "the")? else {
+            return Err(inexpressible("attribute `the` is not a string"));
+        };
+        text.push_str(&format!(": {}", key(the)));
+        if let Some(kind) = field.get("as") {
+            let Value::String(kind) = kind else {
+                return Err(inexpressible("attribute type is not a string"));
+            };
+            text.push_str(&format!(" as {}", key(kind)));
+        }
+        match field.get("cardinality") {
+            Some(Value::String(cardinality)) if cardinality == "many" => text.push_str(" many"),
+            Some(Value::String(cardinality)) if cardinality == "one" => {}
+            None => {}
+            Some(_) => return Err(inexpressible("unknown cardinality")),
+        }
+        if let Some(concept) = field.get("conforms") {
+            text.push_str(&format!(" conforms {}", self.concept(concept, indent)?));
+        }
+        match field.get("description") {
+            Some(Value::String(description)) if description.is_empty() => {}
+            Some(Value::String(description)) => {
+                text.push_str(&format!(" {}", Value::from(description.as_str())));
+            }
+            None => {}
+            Some(_) => return Err(inexpressible("attribute description is not a string")),
+        }
+        Ok(text)
+    }
+
+    /// `(this, key: term, ...)`
+    fn arguments(&self, arguments: &Map<String, Value>) -> String {
+        let this = arguments.get("this").map(term);
+        let keyed = arguments
+            .iter()
+            .filter(|(name, _)| *name != "this")
+            .map(|(name, value)| format!("{}: {}", key(name), term(value)));
+        let arguments: Vec<String> = this.into_iter().chain(keyed).collect();
+        format!("({})", arguments.join(", "))
+    }
+}
+
+/// A constraint as an infix comparison, when its arguments are exactly
+/// the operator's two operands and the left one reads as a term.
+fn comparison(name: &str, arguments: &Map<String, Value>) -> Option<String> {
+    let (left, right) = match name {
+        "==" => ("this", "is"),
+        "<" | "<=" | ">" | ">=" => ("of", "with"),
+        _ => return None,
+    };
+    if arguments.len() != 2 {
+        return None;
+    }
+    let left = term(arguments.get(left)?);
+    let right = term(arguments.get(right)?);
+    (!left.starts_with('{')).then(|| format!("{left} {name} {right}"))
+}
+
+/// A variable by its sigil where the lexer allows, or else as JSON.
+pub(super) fn term(value: &Value) -> String {
+    if let Value::Object(term) = value
+        && term.len() == 1
+        && let Some(Value::Object(variable)) = term.get("?")
+    {
+        match variable.get("name") {
+            None if variable.is_empty() => return "_".into(),
+            Some(Value::String(name))
+                if variable.len() == 1
+                    && !name.is_empty()
+                    && name.chars().all(is_variable_char) =>
+            {
+                return format!("?{name}");
+            }
+            _ => {}
+        }
+    }
+    value.to_string()
+}
+
+/// A name as a bare word where the lexer allows, or else as a string.
+fn key(name: &str) -> String {
+    if is_word(name) {
+        name.into()
+    } else {
+        Value::from(name).to_string()
+    }
+}
+
+/// A rule's description on a line of its own.
+fn description(rule: &Map<String, Value>) -> String {
+    match rule.get("description") {
+        Some(description) => format!("{description}\n"),
+        None => String::new(),
+    }
+}
+
+fn object<'a>(
+    value: &'a Value,
+    what: &str,
+    keys: &[&str],
+) -> Result<&'a Map<String, Value>, SyntaxError> {
+    let Value::Object(map) = value else {
+        return Err(inexpressible(&format!("{what} is not an object")));
+    };
+    if !keys.is_empty()
+        && let Some(unknown) = map.keys().find(|key| !keys.contains(&key.as_str()))
+    {
+        return Err(inexpressible(&format!(
+            "{what} has unknown key `{unknown}`"
+        )));
+    }
+    Ok(map)
+}
+
+fn field<'a>(map: &'a Map<String, Value>, key: &str) -> Result<&'a Value, SyntaxError> {
+    map.get(key)
+        .ok_or_else(|| inexpressible(&format!("missing `{key}`")))
+}
+
+fn inexpressible(reason: &str) -> SyntaxError {
+    SyntaxError::Inexpressible(reason.into())
+}
@@ -17,7 +17,7 @@ use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::Environment;
 use crate::Premise;
-use crate::artifact::{ArtifactsAttribute, Entity, Type, Value};
+use crate::artifact::{ArtifactsAttribute, Entity, RecordValue, Type, Value};
 use crate::attribute::The;
 use crate::constraint::{Coalesce, Constraint, Equality};
 use crate::error::{FieldTypeError, TypeError};
@@ -317,6 +317,12 @@ impl From<The> for Term<The> {
     }
 }
 
+impl From<RecordValue> for Term<RecordValue> {
+    fn from(record: RecordValue) -> Self {
+        Term::Constant(Value::Record(record))
+    }
+}
+
 impl From<String> for Term<String> {
     fn from(value: String) -> Self {
         Term::Constant(Value::from(value))
@@ -38,13 +38,15 @@ use std::fmt::{Display, Formatter, Result as FmtResult};
 /// declare per-variable constraints, and unification intersects
 /// them. The `Nothing` bit (position 9) is a synthetic atom with
 /// no corresponding [`ValueType`]; it marks an admissible absent
-/// value at the row layer.
+/// value at the row layer. `Instant`, added after `Nothing` was
+/// laid out, sits at position 10 so existing encodings keep their
+/// meaning.
 #[derive(
     Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
 )]
 pub struct Primitive {
-    /// One bit per [`ValueType`] variant (positions 0-8), plus a
-    /// `Nothing` bit at position 9.
+    /// One bit per [`ValueType`] variant (positions 0-8 and 10),
+    /// plus a `Nothing` bit at position 9.
     bits: u16,
 }
 
@@ -56,7 +58,7 @@ impl Display for Primitive {
         if self.required() == Self::ALL {
             write!(f, "Value")?;
         } else {
-            const ATOMS: [ValueType; 9] = [
+            const ATOMS: [ValueType; 10] = [
                 ValueType::String,
                 ValueType::Boolean,
                 ValueType::UnsignedInt,
@@ -66,6 +68,7 @@ impl Display for Primitive {
                 ValueType::Entity,
                 ValueType::Symbol,
                 ValueType::Record,
+                ValueType::Instant,
             ];
             let mut first = true;
             for atom in ATOMS {
@@ -102,7 +105,7 @@ impl Primitive {
     /// variable that "accepts anything" still demands a Present
     /// value.
     pub const ALL: Self = Self {
-        bits: 0b1_1111_1111,
+        bits: 0b101_1111_1111,
     };
 
     /// Every shape including the `Nothing` atom: the broadest
@@ -136,12 +139,14 @@ impl Primitive {
         bits: Self::STRING_LIKE.bits | Self::bit_for(ValueType::Entity),
     };
 
-    /// Comparable primitives: numeric, string-like, entity, bytes.
+    /// Comparable primitives: numeric, string-like, entity, bytes,
+    /// instant.
     pub const COMPARABLE: Self = Self {
         bits: Self::NUMERIC.bits
             | Self::STRING_LIKE.bits
             | Self::bit_for(ValueType::Entity)
-            | Self::bit_for(ValueType::Bytes),
+            | Self::bit_for(ValueType::Bytes)
+            | Self::bit_for(ValueType::Instant),
     };
 
     /// Construct a singleton set from a single `ValueType`.
@@ -164,6 +169,7 @@ impl Primitive {
             ValueType::Entity => 1 << 6,
             ValueType::Symbol => 1 << 7,
             ValueType::Record => 1 << 8,
+            ValueType::Instant => 1 << 10,
         }
     }
 
@@ -229,7 +235,7 @@ impl Primitive {
     /// Does **not** yield the `Nothing` atom.
     pub fn iter(self) -> impl Iterator<Item = ValueType> {
         let bits = self.bits;
-        (0..9u32).filter_map(move |pos| {
+        (0..11u32).filter_map(move |pos| {
             if (bits & (1 << pos)) != 0 {
                 value_type_for_bit(pos)
             } else {
@@ -250,6 +256,7 @@ fn value_type_for_bit(pos: u32) -> Option<ValueType> {
         6 => ValueType::Entity,
         7 => ValueType::Symbol,
         8 => ValueType::Record,
+        10 => ValueType::Instant,
         _ => return None,
     })
 }
@@ -25,7 +25,7 @@ use std::hash::Hash;
 use std::marker::PhantomData;
 
 use crate::artifact::ArtifactTypeError;
-pub use crate::artifact::{ArtifactsAttribute, Cause, Entity, Type, Value};
+pub use crate::artifact::{ArtifactsAttribute, Cause, Entity, Instant, RecordValue, Type, Value};
 use crate::attribute::The;
 
 /// Trait implemented by type descriptors: named ZSTs that
@@ -170,6 +170,11 @@ define_descriptor!(
     Record, Type::Record
 );
 
+define_descriptor!(
+    /// Descriptor for points in time ([`Instant`]).
+    InstantType, Type::Instant
+);
+
 /// Descriptor for dynamically-typed values: carries an optional
 /// runtime type kind. `Term<Any>` is the type-erased term whose
 /// kind is decided at runtime.
@@ -240,9 +245,11 @@ impl_typed!(f64, Float);
 impl_typed!(f32, Float);
 impl_typed!(Vec<u8>, Bytes);
 impl_typed!(Entity, EntityType);
+impl_typed!(Instant, InstantType);
 impl_typed!(ArtifactsAttribute, Symbol);
 impl_typed!(The, Symbol);
 impl_typed!(Cause, Bytes);
+impl_typed!(RecordValue, Record);
 impl_typed!(Value, Any);
 
 /// `Option<U>: Typed` for any [`Scalar`] `U`. Maps to
@@ -278,10 +285,12 @@ impl_scalar!(
     f32,
     f64,
     Entity,
+    Instant,
     ArtifactsAttribute,
     Vec<u8>,
     Cause,
     The,
+    RecordValue,
     RecordBytes
 );
 
@@ -299,7 +308,7 @@ impl_typed!(RecordBytes, Record);
 
 impl From<RecordBytes> for Value {
     fn from(value: RecordBytes) -> Self {
-        Value::Record(value.0)
+        Value::Record(value.0.into())
     }
 }
 
@@ -308,7 +317,7 @@ impl TryFrom<Value> for RecordBytes {
 
     fn try_from(value: Value) -> Result<Self, Self::Error> {
         match value {
-            Value::Record(bytes) => Ok(RecordBytes(bytes)),
+            Value::Record(record) => Ok(RecordBytes(record.to_vec())),
             _ => Err(Self::Error::TypeMismatch(Type::Record, value.data_type())),
         }
     }
@@ -623,6 +623,77 @@ fn it_parses_conjunction_premise_array() {
     assert!(matches!(&props[2], Proposition::Concept(_)));
 }
 
+// Ordered query (a concept query with order, limit and offset)
+
+#[dialog_common::test]
+fn it_parses_ordered_concept_query() {
+    let json = json!({
+        "assert": {
+            "with": {
+                "name": { "the": "diy.cook/ingredient-name", "as": "Text" }
+            }
+        },
+        "where": {
+            "this": { "?": { "name": "this" } },
+            "name": { "?": { "name": "name" } }
+        },
+        "order": [
+            { "by": { "?": { "name": "name" } }, "direction": "descending" },
+            { "by": { "?": { "name": "this" } } }
+        ],
+        "limit": 10,
+        "offset": 20
+    });
+
+    let query: dialog_query::Ordered<dialog_query::ConceptQuery> =
+        serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(
+        query.query.terms.get("name"),
+        Some(&Term::<Any>::var("name"))
+    );
+    assert_eq!(
+        query.order,
+        dialog_query::Order::new()
+            .by(
+                Term::<Any>::var("name"),
+                dialog_query::Direction::Descending
+            )
+            .by(Term::<Any>::var("this"), dialog_query::Direction::Ascending)
+            .limit(10)
+            .offset(20)
+    );
+
+    let reserialized = serde_json::to_value(&query).unwrap();
+    assert_eq!(reserialized["order"], json["order"]);
+    assert_eq!(reserialized["limit"], 10);
+    assert_eq!(reserialized["offset"], 20);
+    assert!(reserialized["where"]["this"].is_object());
+}
+
+#[dialog_common::test]
+fn it_omits_an_identity_order_from_the_notation() {
+    let json = json!({
+        "assert": {
+            "with": {
+                "name": { "the": "diy.cook/ingredient-name", "as": "Text" }
+            }
+        },
+        "where": {
+            "this": { "?": { "name": "this" } },
+            "name": { "?": { "name": "name" } }
+        }
+    });
+
+    let query: dialog_query::Ordered<dialog_query::ConceptQuery> =
+        serde_json::from_value(json).unwrap();
+    assert!(query.order.is_identity());
+
+    let reserialized = serde_json::to_value(&query).unwrap();
+    assert!(reserialized.get("order").is_none());
+    assert!(reserialized.get("limit").is_none());
+    assert!(reserialized.get("offset").is_none());
+}
+
 // Full rule structure (deduce + when)
 
 #[dialog_common::test]
@@ -0,0 +1,62 @@
+//! Direct peer-to-peer replication for dialog-db.
+//!
+//! This crate provides the [`Peer`] site type for syncing a repository with
+//! another peer directly — two laptops on a network, say — with no object
+//! store between them. A [`PeerAddress`] names the endpoint the other peer
+//! [listens](Listener) at; at authorize time the [`Peer`] fork obtains the
+//! same signed UCAN invocation a UCAN site would, and the provider sends it
+//! over a byte stream to the peer, whose [`Responder`] verifies the chain
+//! and performs the effect against its own storage.
+//!
+//! Because the site is reached through the same archive, memory and blob
+//! effects as every other remote, `push` and `pull` work over it unchanged:
+//! branch heads are exchanged by resolving and publishing the branch's
+//! memory cell, and a push sends exactly the blocks
+//! `TreeDifference::novel_nodes` finds the peer missing. Syncing in both
+//! directions is two peers each running a [`Responder`] and each tracking
+//! the other as a remote; each side authorizes what the other asks of it
+//! against the delegations the asker presents.
+//!
+//! # Trust model
+//!
+//! A responder acts only for the subjects it [hosts](Responder::host), and
+//! only on invocations whose delegation chain verifies back to that subject.
+//! The bytes an effect moves — a block, a cell's content, a blob — travel
+//! beside the signed invocation, and are checked against the digest or
+//! checksum its arguments carry before the responder acts on them.
+//!
+//! The transport is not authenticated or encrypted: anyone on the path can
+//! read what is exchanged, though a tampered request still fails
+//! verification. Archive and blob content is sealed only once its space
+//! has been [unlocked](https://docs.rs/dialog-operator) on the operator
+//! sending it; until then it travels as plaintext. Memory cells, branch
+//! heads among them, are never sealed. So a TCP endpoint beyond loopback
+//! [is exposed](dialog_capability::SiteAddress::is_exposed), and an
+//! operator refuses to sync a space it has not unlocked over one. Run
+//! peers over a trusted network or a tunnel when cell contents or access
+//! patterns matter.
+//!
+//! # Transports
+//!
+//! The [protocol](peer::protocol) needs only an ordered, reliable byte
+//! stream. The endpoint's scheme picks one:
+//!
+//! ```no_run
+//! # fn example() {
+//! use dialog_remote_peer::PeerAddress;
+//!
+//! // Another machine, over TCP.
+//! let _laptop = PeerAddress::tcp("192.168.1.20:7879");
+//! // A listener in this process, over an in-memory pipe.
+//! let _local = PeerAddress::memory("test-peer");
+//! # }
+//! ```
+//!
+//! A responder can also [`serve`](Responder::serve) any stream it accepts
+//! by other means, such as an upgraded WebSocket.
+
+#![warn(missing_docs)]
+
+pub mod peer;
+
+pub use peer::*;
@@ -0,0 +1,234 @@
+//! Peer site type and the initiating side of the protocol.
+//!
+//! Mirrors the shape of [`dialog_remote_fs::fs`](https://docs.rs/dialog-remote-fs).
+//! The site marker is [`Peer`]; the site-bound fork is [`PeerFork<Fx>`].
+//! The [`provider`] impls send each authorized effect to the peer over a
+//! pooled [`Connection`] and read back what it produced; the [`responder`]
+//! is the other end, run by the peer being synced with.
+
+mod address;
+mod authorization;
+mod lease;
+pub mod protocol;
+pub mod provider;
+mod responder;
+mod transport;
+
+pub use address::PeerAddress;
+pub use authorization::PeerAuthorization;
+pub use lease::{ArchiveLease, UnleasedArchive};
+pub use responder::Responder;
+pub use transport::{Connection, Duplex, Listener, connect};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use dialog_capability::{Effect, Fork, Site};
+use parking_lot::Mutex;
+
+use protocol::{FrameError, Request, Response, read_frame, write_frame};
+
+/// One connection slot per peer: empty until first used, and emptied
+/// again when the connection fails.
+type Slot = Arc<tokio::sync::Mutex<Option<Connection>>>;
+
+/// Whether a request may be sent again when a connection fails before its
+/// answer arrives, leaving unknown whether the peer performed it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Replay {
+    /// Performing the request twice is the same as performing it once:
+    /// reads, and writes addressed by their content.
+    Safe,
+    /// A second attempt could apply the request again, or fail because
+    /// the first one was applied: a compare-and-swap on a branch head.
+    Unsafe,
+}
+
+/// Site for syncing with another peer directly, with no store between.
+///
+/// Holds a connection per peer address, opened on first use and reused for
+/// every effect after it; a peer answers one request at a time per
+/// connection, so effects forked at the same peer take turns. Clones share
+/// the connections.
+#[derive(Clone, Default)]
+pub struct Peer {
+    connections: Arc<Mutex<HashMap<PeerAddress, Slot>>>,
+}
+
+impl std::fmt::Debug for Peer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Peer")
+            .field("connections", &self.connections.lock().len())
+            .finish()
+    }
+}
+
+impl Peer {
+    /// Ask the peer at `address` to perform the invocation `authorization`
+    /// carries, sending `payload` alongside it.
+    pub(crate) async fn exchange(
+        &self,
+        address: &PeerAddress,
+        authorization: &PeerAuthorization,
+        payload: Option<Vec<u8>>,
+        replay: Replay,
+    ) -> Response {
+        let request = Request {
+            invocation: authorization.container()?,
+            payload,
+        };
+        self.send(address, &request, replay).await
+    }
+
+    /// Send `request` to the peer at `address` over its pooled connection,
+    /// opening one when there is none.
+    async fn send(&self, address: &PeerAddress, request: &Request, replay: Replay) -> Response {
+        let slot = self
+            .connections
+            .lock()
+            .entry(address.clone())
+            .or_default()
+            .clone();
+        let mut held = slot.lock().await;
+
+        // A pooled connection may have been closed by the peer since it was
+        // last used, so a failure on one is retried once on a fresh
+        // connection — when the request is safe to replay. One that is not
+        // goes out on a fresh connection to begin with, so the one failure
+        // it can meet is the answer, as a failure on a fresh connection
+        // always is.
+        if let Some(mut connection) = held.take().filter(|_| replay == Replay::Safe)
+            && let Ok(response) = round_trip(&mut connection, request).await
+        {
+            *held = Some(connection);
+            return response;
+        }
+        let mut connection = connect(address).await?;
+        let response = round_trip(&mut connection, request).await?;
+        *held = Some(connection);
+        response
+    }
+}
+
+/// Send one request and read its response.
+async fn round_trip(
+    connection: &mut Connection,
+    request: &Request,
+) -> Result<Response, FrameError> {
+    write_frame(connection, request).await?;
+    read_frame(connection).await?.ok_or_else(|| {
+        FrameError::Io(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "peer closed the connection before answering",
+        ))
+    })
+}
+
+/// Site-owned fork wrapper for [`Peer`].
+///
+/// Thin newtype around [`Fork<Peer, Fx>`] that carries the site-specific
+/// [`SiteFork`](dialog_capability::SiteFork) impl: the same UCAN
+/// authorization a UCAN site performs, redeemed by the peer itself rather
+/// than by an access service.
+pub struct PeerFork<Fx: Effect>(Fork<Peer, Fx>);
+
+impl<Fx: Effect> From<Fork<Peer, Fx>> for PeerFork<Fx> {
+    fn from(fork: Fork<Peer, Fx>) -> Self {
+        Self(fork)
+    }
+}
+
+impl Site for Peer {
+    type Authorization = PeerAuthorization;
+    type Address = PeerAddress;
+    type Fork<Fx: Effect> = PeerFork<Fx>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dialog_storage::unique_name;
+    use protocol::Reply;
+
+    /// Send two requests, the second under `replay`, to a peer that answers
+    /// the first and then takes the second on that connection and dies
+    /// without answering. Returns whether the second reached the dead
+    /// connection, and what the sender was told.
+    async fn after_a_dead_connection(replay: Replay) -> (bool, Response) {
+        let address = PeerAddress::memory(unique_name("peer"));
+        let mut listener = Listener::bind(&address).await.unwrap();
+        let request = Request {
+            invocation: vec![1, 2, 3],
+            payload: None,
+        };
+        let peer = Peer::default();
+
+        let responder = async {
+            let mut pooled = listener.accept().await.unwrap();
+            let _: Request = read_frame(&mut pooled).await.unwrap().unwrap();
+            write_frame(&mut pooled, &Response::Ok(Reply::Done))
+                .await
+                .unwrap();
+            let lost = read_frame::<_, Request>(&mut pooled).await.unwrap();
+            drop(pooled);
+
+            let mut fresh = listener.accept().await.unwrap();
+            let _: Request = read_frame(&mut fresh).await.unwrap().unwrap();
+            write_frame(&mut fresh, &Response::Ok(Reply::Done))
+                .await
+                .unwrap();
+            lost.is_some()
+        };
+        let sender = async {
+            assert_eq!(
+                peer.send(&address, &request, Replay::Safe).await,
+                Ok(Reply::Done)
+            );
+            peer.send(&address, &request, replay).await
+        };
+        futures_util::join!(responder, sender)
+    }
+
+    #[dialog_common::test]
+    async fn it_replays_a_safe_request_on_a_fresh_connection() {
+        let (lost, response) = after_a_dead_connection(Replay::Safe).await;
+        assert!(lost, "the pooled connection was tried first");
+        assert_eq!(response, Ok(Reply::Done));
+    }
+
+    #[dialog_common::test]
+    async fn it_sends_an_unsafe_request_only_once() {
+        let (lost, response) = after_a_dead_connection(Replay::Unsafe).await;
+        assert!(!lost, "the pooled connection was never used");
+        assert_eq!(response, Ok(Reply::Done));
+    }
+
+    #[dialog_common::test]
+    fn it_builds_addresses_for_each_transport() {
+        assert_eq!(
+            PeerAddress::tcp("127.0.0.1:7879").endpoint(),
+            "tcp://127.0.0.1:7879"
+        );
+        assert_eq!(PeerAddress::memory("laptop").endpoint(), "memory://laptop");
+    }
+
+    #[dialog_common::test]
+    fn it_exposes_only_tcp_beyond_loopback() {
+        use dialog_capability::SiteAddress as _;
+
+        assert!(PeerAddress::tcp("192.168.1.20:7879").is_exposed());
+        assert!(PeerAddress::tcp("laptop.local:7879").is_exposed());
+        assert!(!PeerAddress::tcp("127.0.0.1:7879").is_exposed());
+        assert!(!PeerAddress::tcp("[::1]:7879").is_exposed());
+        assert!(!PeerAddress::tcp("localhost:7879").is_exposed());
+        assert!(!PeerAddress::memory("laptop").is_exposed());
+    }
+
+    #[dialog_common::test]
+    fn it_roundtrips_address_through_serde() {
+        let address = PeerAddress::tcp("192.168.1.20:7879");
+        let json = serde_json::to_string(&address).unwrap();
+        let parsed: PeerAddress = serde_json::from_str(&json).unwrap();
+        assert_eq!(address, parsed);
+    }
+}
@@ -0,0 +1,69 @@
+//! Peer address types.
+
+use std::fmt::Display;
+use std::net::IpAddr;
+
+use dialog_capability::{SiteAddress, SiteId};
+use serde::{Deserialize, Serialize};
+
+use super::Peer;
+
+/// Address of a peer: the endpoint its [`Listener`](crate::Listener)
+/// accepts connections at.
+///
+/// The endpoint's scheme names the transport — `tcp://host:port` or
+/// `memory://name` — so the address is serializable into the repository's
+/// remote configuration like any other site's.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PeerAddress {
+    /// The endpoint the peer listens at.
+    pub endpoint: String,
+}
+
+impl PeerAddress {
+    /// Create an address for `endpoint`.
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+        }
+    }
+
+    /// The address of a peer listening on TCP at `authority` (`host:port`).
+    pub fn tcp(authority: impl Display) -> Self {
+        Self::new(format!("tcp://{authority}"))
+    }
+
+    /// The address of a peer listening in this process under `name`.
+    pub fn memory(name: impl Display) -> Self {
+        Self::new(format!("memory://{name}"))
+    }
+
+    /// The endpoint the peer listens at.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+}
+
+impl SiteAddress for PeerAddress {
+    type Site = Peer;
+
+    /// The peer protocol runs in the clear, so a TCP endpoint is exposed
+    /// unless it is a loopback address. An in-process pipe never is.
+    fn is_exposed(&self) -> bool {
+        let Some(authority) = self.endpoint.strip_prefix("tcp://") else {
+            return false;
+        };
+        let host = authority
+            .rsplit_once(':')
+            .map_or(authority, |(host, _port)| host);
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        !(host.eq_ignore_ascii_case("localhost")
+            || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback()))
+    }
+}
+
+impl From<PeerAddress> for SiteId {
+    fn from(address: PeerAddress) -> Self {
+        address.endpoint.into()
+    }
+}
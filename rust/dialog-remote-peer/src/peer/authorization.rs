@@ -0,0 +1,76 @@
+//! Peer authorization material.
+//!
+//! A peer verifies every request itself, so the authorization is exactly
+//! what a UCAN site redeems at an access service: the signed invocation and
+//! the delegation chain that proves it. The fork is authorized the same
+//! way — identify the session, then invoke UCAN's `Authorize` for the
+//! effect's scope — and the container travels as the head of each
+//! [request](super::protocol::Request).
+
+use dialog_capability::access::{
+    Access, Authorization as _, Authorize as AuthorizeEffect, AuthorizeError, FromCapability,
+    Protocol,
+};
+use dialog_capability::{
+    Ability, Capability, Constraint, Effect, ForkInvocation, Provider, SiteFork, Subject,
+};
+use dialog_common::{ConditionalSend, ConditionalSync};
+use dialog_effects::authority::{self, OperatorExt};
+use dialog_ucan::{Ucan, UcanInvocation};
+
+use super::{Peer, PeerFork};
+
+/// Peer authorization material: the signed invocation the peer verifies.
+#[derive(Debug, Clone)]
+pub struct PeerAuthorization(UcanInvocation);
+
+impl PeerAuthorization {
+    /// The invocation container: the signed invocation and its proofs.
+    pub fn container(&self) -> Result<Vec<u8>, AuthorizeError> {
+        self.0
+            .to_bytes()
+            .map_err(|detail| AuthorizeError::Malformed { detail })
+    }
+}
+
+impl From<UcanInvocation> for PeerAuthorization {
+    fn from(invocation: UcanInvocation) -> Self {
+        Self(invocation)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl<Fx, Env> SiteFork<Env> for PeerFork<Fx>
+where
+    Fx: Effect + Clone + ConditionalSend + ConditionalSync + 'static,
+    Fx::Of: Constraint<Capability: ConditionalSend + ConditionalSync>,
+    Capability<Fx>: Ability + ConditionalSend + ConditionalSync,
+    Env: Provider<AuthorizeEffect<Ucan>> + Provider<authority::Identify> + ConditionalSync,
+{
+    type Site = Peer;
+    type Effect = Fx;
+
+    async fn authorize(self, env: &Env) -> Result<ForkInvocation<Peer, Fx>, AuthorizeError> {
+        let identity =
+            authority::Identify
+                .perform(env)
+                .await
+                .map_err(|e| AuthorizeError::Malformed {
+                    detail: e.to_string(),
+                })?;
+        let profile = identity.profile().clone();
+        let operator = identity.did();
+
+        let scope = <Ucan as Protocol>::Access::from_capability(self.0.capability());
+
+        let authorization = Subject::from(profile)
+            .attenuate(Access)
+            .invoke(AuthorizeEffect::<Ucan>::new(operator, scope))
+            .perform(env)
+            .await?;
+
+        let invocation = authorization.invoke().await?;
+        Ok(self.0.attest(PeerAuthorization::from(invocation)))
+    }
+}
@@ -0,0 +1,65 @@
+//! Holding a hosted subject's archive while a peer writes to it.
+//!
+//! A push lands as a run of requests: the blocks and blobs a revision
+//! names, then the publish of the head that names them. Until that publish
+//! nothing in the hosted repository reaches what arrived, and a garbage
+//! collection running meanwhile would remove it. The responder keeps one
+//! off through an [`ArchiveLease`]: it claims a subject's archive before a
+//! connection's first write to it, and withdraws the claim once a publish
+//! or retraction on that subject has run, or the connection closes.
+
+use std::convert::Infallible;
+use std::error::Error;
+use std::future::Future;
+
+use dialog_capability::Did;
+use dialog_common::{ConditionalSend, ConditionalSync};
+
+/// Claims a hosted subject's archive against a garbage collection.
+pub trait ArchiveLease {
+    /// A claim held on one subject's archive.
+    type Claim: ConditionalSend;
+
+    /// Error type for a claim that could not be taken or withdrawn,
+    /// including one refused because a collection holds the archive.
+    type Error: Error + ConditionalSend + ConditionalSync + 'static;
+
+    /// Claim `subject`'s archive for a write.
+    fn acquire(&self, subject: &Did) -> impl Future<Output = Result<Self::Claim, Self::Error>>;
+
+    /// Withdraw `claim`.
+    fn release(&self, claim: Self::Claim) -> impl Future<Output = Result<(), Self::Error>>;
+}
+
+impl<T: ArchiveLease> ArchiveLease for &T {
+    type Claim = T::Claim;
+    type Error = T::Error;
+
+    fn acquire(&self, subject: &Did) -> impl Future<Output = Result<Self::Claim, Self::Error>> {
+        (**self).acquire(subject)
+    }
+
+    fn release(&self, claim: Self::Claim) -> impl Future<Output = Result<(), Self::Error>> {
+        (**self).release(claim)
+    }
+}
+
+/// Claims nothing: for storage no collection runs against.
+///
+/// The default for a responder that has not been given a lease, so that
+/// one that has is a choice made where the responder is built.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnleasedArchive;
+
+impl ArchiveLease for UnleasedArchive {
+    type Claim = ();
+    type Error = Infallible;
+
+    async fn acquire(&self, _subject: &Did) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn release(&self, _claim: ()) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
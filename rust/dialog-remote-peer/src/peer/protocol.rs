@@ -0,0 +1,344 @@
+//! The peer wire protocol.
+//!
+//! A connection carries a sequence of exchanges, one at a time: the
+//! initiator writes a [`Request`] frame and reads back one [`Response`]
+//! frame. Every frame is a 4-byte big-endian length followed by that many
+//! bytes of DAG-CBOR.
+//!
+//! A request is the signed UCAN invocation container — the same bytes a
+//! UCAN site posts to an access service — plus the bytes the effect moves
+//! when it moves any: the block of an archive put, the content of a memory
+//! publish, the body of a blob import. Those bytes are not part of the
+//! signed invocation, whose arguments carry only their checksums, so the
+//! responder checks them against the arguments before acting on them.
+//!
+//! A response is either what the effect produced or a [`Refusal`] carrying
+//! the reason itself, so an [`AuthorizeError`] built by the responder
+//! arrives as the same value.
+
+use dialog_capability::access::AuthorizeError;
+use dialog_effects::Rejection;
+use dialog_effects::archive::ArchiveError;
+use dialog_effects::blob::BlobError;
+use dialog_effects::memory::{Edition, MemoryError, Version};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
+
+/// The largest frame either side reads. A frame holds at most one block,
+/// cell or blob, so this is also the largest blob a peer will exchange.
+pub const MAX_FRAME_BYTES: usize = 64 << 20;
+
+/// One effect the initiator asks the responder to perform.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Request {
+    /// The invocation container: the signed invocation and its proofs.
+    #[serde(with = "serde_bytes")]
+    pub invocation: Vec<u8>,
+    /// The bytes the effect moves, when it moves any.
+    #[serde(with = "serde_bytes")]
+    pub payload: Option<Vec<u8>>,
+}
+
+/// What performing a request produced, shaped by the effect family.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Reply {
+    /// The effect completed and produced nothing.
+    Done,
+    /// An archive block, or `None` when the responder does not hold it.
+    Block(#[serde(with = "serde_bytes")] Option<Vec<u8>>),
+    /// A memory cell's content and version, or `None` when it is empty.
+    Edition(Option<Cell>),
+    /// The version a publish produced.
+    Version(Version),
+    /// The bytes of a blob (or of the range read from it).
+    Blob(#[serde(with = "serde_bytes")] Vec<u8>),
+}
+
+/// A memory cell as it travels: [`Edition`] with its content as bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cell {
+    /// The cell's content.
+    #[serde(with = "serde_bytes")]
+    pub content: Vec<u8>,
+    /// The version of that content.
+    pub version: Version,
+}
+
+impl From<Edition<Vec<u8>>> for Cell {
+    fn from(edition: Edition<Vec<u8>>) -> Self {
+        Self {
+            content: edition.content,
+            version: edition.version,
+        }
+    }
+}
+
+impl From<Cell> for Edition<Vec<u8>> {
+    fn from(cell: Cell) -> Self {
+        Self {
+            content: cell.content,
+            version: cell.version,
+        }
+    }
+}
+
+/// Why a request was not carried out, as it travels.
+///
+/// Mirrors the variants the effect errors share, so each side reads back
+/// the error the other side produced rather than its rendering.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, Serialize, Deserialize)]
+pub enum Refusal {
+    /// The invocation did not authorize the effect.
+    #[error(transparent)]
+    Authorization(AuthorizeError),
+    /// The effect was not carried out for a reason that is not an access
+    /// decision.
+    #[error(transparent)]
+    Rejected(Rejection),
+    /// A memory compare-and-swap found another version.
+    #[error("Version mismatch: expected {expected:?}, got {actual:?}")]
+    VersionMismatch {
+        /// The version the request expected.
+        expected: Option<Version>,
+        /// The version the responder holds.
+        actual: Option<Version>,
+    },
+    /// The blob is not held by the responder.
+    #[error("Blob not found: {0}")]
+    NotFound(String),
+    /// The bytes sent do not hash to the digest the invocation names.
+    #[error("Digest mismatch: expected {expected}, got {actual}")]
+    DigestMismatch {
+        /// The digest the invocation names.
+        expected: String,
+        /// The digest of the bytes sent.
+        actual: String,
+    },
+    /// The responder's storage failed.
+    #[error("Storage error: {0}")]
+    Storage(String),
+}
+
+impl Refusal {
+    /// A refusal for a response that does not fit the effect it answers.
+    pub(crate) fn unexpected(reply: &Reply) -> Self {
+        Refusal::Rejected(Rejection::Unclassified {
+            detail: format!("peer answered with an unexpected reply: {reply:?}"),
+        })
+    }
+}
+
+impl From<AuthorizeError> for Refusal {
+    fn from(error: AuthorizeError) -> Self {
+        Refusal::Authorization(error)
+    }
+}
+
+impl From<Rejection> for Refusal {
+    fn from(rejection: Rejection) -> Self {
+        Refusal::Rejected(rejection)
+    }
+}
+
+impl From<ArchiveError> for Refusal {
+    fn from(error: ArchiveError) -> Self {
+        match error {
+            ArchiveError::Authorization(error) => Refusal::Authorization(error),
+            ArchiveError::Rejected(rejection) => Refusal::Rejected(rejection),
+            ArchiveError::Storage(detail) => Refusal::Storage(detail),
+        }
+    }
+}
+
+impl From<Refusal> for ArchiveError {
+    fn from(refusal: Refusal) -> Self {
+        match refusal {
+            Refusal::Authorization(error) => ArchiveError::Authorization(error),
+            Refusal::Rejected(rejection) => ArchiveError::Rejected(rejection),
+            Refusal::Storage(detail) => ArchiveError::Storage(detail),
+            other => ArchiveError::Storage(other.to_string()),
+        }
+    }
+}
+
+impl From<MemoryError> for Refusal {
+    fn from(error: MemoryError) -> Self {
+        match error {
+            MemoryError::VersionMismatch { expected, actual } => {
+                Refusal::VersionMismatch { expected, actual }
+            }
+            MemoryError::Storage(detail) => Refusal::Storage(detail),
+            MemoryError::Rejected(rejection) => Refusal::Rejected(rejection),
+            MemoryError::Authorization(error) => Refusal::Authorization(error),
+        }
+    }
+}
+
+impl From<Refusal> for MemoryError {
+    fn from(refusal: Refusal) -> Self {
+        match refusal {
+            Refusal::Authorization(error) => MemoryError::Authorization(error),
+            Refusal::Rejected(rejection) => MemoryError::Rejected(rejection),
+            Refusal::VersionMismatch { expected, actual } => {
+                MemoryError::VersionMismatch { expected, actual }
+            }
+            Refusal::Storage(detail) => MemoryError::Storage(detail),
+            other => MemoryError::Storage(other.to_string()),
+        }
+    }
+}
+
+impl From<BlobError> for Refusal {
+    fn from(error: BlobError) -> Self {
+        match error {
+            BlobError::NotFound(digest) => Refusal::NotFound(digest),
+            BlobError::DigestMismatch { expected, actual } => {
+                Refusal::DigestMismatch { expected, actual }
+            }
+            BlobError::Authorization(error) => Refusal::Authorization(error),
+            BlobError::Rejected(rejection) => Refusal::Rejected(rejection),
+            BlobError::Storage(detail) => Refusal::Storage(detail),
+        }
+    }
+}
+
+impl From<Refusal> for BlobError {
+    fn from(refusal: Refusal) -> Self {
+        match refusal {
+            Refusal::Authorization(error) => BlobError::Authorization(error),
+            Refusal::Rejected(rejection) => BlobError::Rejected(rejection),
+            Refusal::NotFound(digest) => BlobError::NotFound(digest),
+            Refusal::DigestMismatch { expected, actual } => {
+                BlobError::DigestMismatch { expected, actual }
+            }
+            Refusal::Storage(detail) => BlobError::Storage(detail),
+            other => BlobError::Storage(other.to_string()),
+        }
+    }
+}
+
+/// The answer to one [`Request`].
+pub type Response = Result<Reply, Refusal>;
+
+/// Errors reading or writing frames.
+#[derive(Debug, thiserror::Error)]
+pub enum FrameError {
+    /// The stream failed or closed mid-frame.
+    #[error("peer connection failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The frame announced more bytes than [`MAX_FRAME_BYTES`].
+    #[error("peer frame of {0} bytes exceeds the limit")]
+    TooLarge(usize),
+
+    /// The frame did not hold what this side expected.
+    #[error("peer frame did not decode: {0}")]
+    Codec(String),
+}
+
+impl From<FrameError> for Refusal {
+    fn from(error: FrameError) -> Self {
+        Refusal::Rejected(Rejection::Unavailable {
+            reason: error.to_string(),
+        })
+    }
+}
+
+/// Write `message` as one frame.
+pub async fn write_frame<W, T>(stream: &mut W, message: &T) -> Result<(), FrameError>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let body = serde_ipld_dagcbor::to_vec(message).map_err(|e| FrameError::Codec(e.to_string()))?;
+    if body.len() > MAX_FRAME_BYTES {
+        return Err(FrameError::TooLarge(body.len()));
+    }
+    let length = u32::try_from(body.len()).map_err(|_| FrameError::TooLarge(body.len()))?;
+    stream.write_all(&length.to_be_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// Read one frame, or `None` when the stream ends cleanly between frames.
+pub async fn read_frame<R, T>(stream: &mut R) -> Result<Option<T>, FrameError>
+where
+    R: AsyncRead + Unpin,
+    T: DeserializeOwned,
+{
+    let mut prefix = [0u8; 4];
+    match stream.read_exact(&mut prefix).await {
+        Ok(_) => {}
+        Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error.into()),
+    }
+    let length = u32::from_be_bytes(prefix) as usize;
+    if length > MAX_FRAME_BYTES {
+        return Err(FrameError::TooLarge(length));
+    }
+    let mut body = vec![0u8; length];
+    stream.read_exact(&mut body).await?;
+    serde_ipld_dagcbor::from_slice(&body)
+        .map(Some)
+        .map_err(|e| FrameError::Codec(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[dialog_common::test]
+    async fn it_round_trips_requests_through_frames() {
+        let (mut near, mut far) = tokio::io::duplex(1024);
+        let request = Request {
+            invocation: vec![1, 2, 3],
+            payload: Some(b"block".to_vec()),
+        };
+        write_frame(&mut near, &request).await.unwrap();
+        let read: Request = read_frame(&mut far).await.unwrap().unwrap();
+        assert_eq!(read.invocation, request.invocation);
+        assert_eq!(read.payload, request.payload);
+    }
+
+    // The reason travels as itself: whatever the responder built arrives
+    // as the same value, down to the kind of authorization failure.
+    #[dialog_common::test]
+    async fn it_carries_refusals_as_themselves() {
+        let (mut near, mut far) = tokio::io::duplex(1024);
+        for response in [
+            Err(Refusal::Authorization(AuthorizeError::Malformed {
+                detail: "no chain".into(),
+            })),
+            Err(Refusal::VersionMismatch {
+                expected: Some(Version::from("a")),
+                actual: None,
+            }),
+            Ok(Reply::Edition(Some(Cell {
+                content: b"head".to_vec(),
+                version: Version::from("v1"),
+            }))),
+        ] {
+            write_frame(&mut near, &response).await.unwrap();
+            let read: Response = read_frame(&mut far).await.unwrap().unwrap();
+            assert_eq!(read, response);
+        }
+    }
+
+    #[dialog_common::test]
+    async fn it_ends_cleanly_between_frames() {
+        let (near, mut far) = tokio::io::duplex(1024);
+        drop(near);
+        let read: Option<Request> = read_frame(&mut far).await.unwrap();
+        assert!(read.is_none());
+    }
+
+    #[dialog_common::test]
+    async fn it_refuses_oversized_frames() {
+        let (mut near, mut far) = tokio::io::duplex(1024);
+        near.write_all(&u32::MAX.to_be_bytes()).await.unwrap();
+        let read = read_frame::<_, Request>(&mut far).await;
+        assert!(matches!(read, Err(FrameError::TooLarge(_))));
+    }
+}
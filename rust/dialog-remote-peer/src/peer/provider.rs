@@ -0,0 +1,11 @@
+//! Provider implementations for the [`Peer`](super::Peer) site.
+//!
+//! By the time these run, [`authorize`](super::PeerFork) has produced the
+//! signed invocation for the effect. Each
+//! [`Provider<ForkInvocation<Peer, Fx>>`](dialog_capability::Provider) impl
+//! sends it to the peer with whatever bytes the effect moves, and turns the
+//! [reply](super::protocol::Reply) back into the effect's output.
+
+pub mod archive;
+pub mod blob;
+pub mod memory;
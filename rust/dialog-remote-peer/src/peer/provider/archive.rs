@@ -0,0 +1,48 @@
+//! Archive providers for the peer site.
+//!
+//! A put sends its block beside the invocation, whose arguments carry only
+//! the block's digest and checksum; the peer checks the block against them
+//! before storing it.
+
+use dialog_capability::{ForkInvocation, Provider};
+use dialog_effects::archive::*;
+
+use crate::peer::protocol::{Refusal, Reply};
+use crate::peer::{Peer, Replay};
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Provider<ForkInvocation<Peer, Get>> for Peer {
+    async fn execute(
+        &self,
+        input: ForkInvocation<Peer, Get>,
+    ) -> Result<Option<Vec<u8>>, ArchiveError> {
+        match self
+            .exchange(&input.address, &input.authorization, None, Replay::Safe)
+            .await?
+        {
+            Reply::Block(block) => Ok(block),
+            other => Err(Refusal::unexpected(&other).into()),
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Provider<ForkInvocation<Peer, Put>> for Peer {
+    async fn execute(&self, input: ForkInvocation<Peer, Put>) -> Result<(), ArchiveError> {
+        let block = Put::of(&input.capability).block.as_ref().to_vec();
+        match self
+            .exchange(
+                &input.address,
+                &input.authorization,
+                Some(block),
+                Replay::Safe,
+            )
+            .await?
+        {
+            Reply::Done => Ok(()),
+            other => Err(Refusal::unexpected(&other).into()),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Blob providers for the peer site.
+//!
+//! A blob travels as one frame: a read returns a reader over the bytes the
+//! peer sent back, and an import returns a writer that gathers the bytes and
+//! sends them with the invocation when it is finished. The peer verifies
+//! them against the digest the invocation names, so a finished import is
+//! one the peer has committed.
+
+use dialog_capability::{ForkInvocation, Policy, Provider};
+use dialog_common::Blake3Hash;
+use dialog_effects::blob::{BlobError, BlobReader, BlobSink, BlobSource, BlobWriter, Import, Read};
+
+use crate::peer::protocol::{MAX_FRAME_BYTES, Refusal, Reply};
+use crate::peer::{Peer, PeerAddress, PeerAuthorization, Replay};
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Provider<ForkInvocation<Peer, Read>> for Peer {
+    async fn execute(&self, input: ForkInvocation<Peer, Read>) -> Result<BlobReader, BlobError> {
+        match self
+            .exchange(&input.address, &input.authorization, None, Replay::Safe)
+            .await?
+        {
+            Reply::Blob(bytes) => Ok(Box::new(Received(Some(bytes)))),
+            other => Err(Refusal::unexpected(&other).into()),
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Provider<ForkInvocation<Peer, Import>> for Peer {
+    async fn execute(&self, input: ForkInvocation<Peer, Import>) -> Result<BlobWriter, BlobError> {
+        let import = Import::of(&input.capability);
+        Ok(Box::new(Upload {
+            peer: self.clone(),
+            address: input.address,
+            authorization: input.authorization,
+            digest: import.digest.clone(),
+            bytes: Vec::new(),
+        }))
+    }
+}
+
+/// The bytes of a blob read from a peer, yielded as one chunk.
+struct Received(Option<Vec<u8>>);
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl BlobSource for Received {
+    async fn next(&mut self) -> Result<Option<Vec<u8>>, BlobError> {
+        Ok(self.0.take())
+    }
+}
+
+/// A blob on its way to a peer: gathered here, sent on finish.
+struct Upload {
+    peer: Peer,
+    address: PeerAddress,
+    authorization: PeerAuthorization,
+    digest: Blake3Hash,
+    bytes: Vec<u8>,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl BlobSink for Upload {
+    async fn write_all(&mut self, bytes: &[u8]) -> Result<(), BlobError> {
+        if self.bytes.len() + bytes.len() > MAX_FRAME_BYTES {
+            return Err(BlobError::Storage(format!(
+                "blob {} is larger than a peer frame",
+                self.digest
+            )));
+        }
+        self.bytes.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    async fn finish(self: Box<Self>) -> Result<Blake3Hash, BlobError> {
+        let Upload {
+            peer,
+            address,
+            authorization,
+            digest,
+            bytes,
+        } = *self;
+        match peer
+            .exchange(&address, &authorization, Some(bytes), Replay::Safe)
+            .await?
+        {
+            Reply::Done => Ok(digest),
+            other => Err(Refusal::unexpected(&other).into()),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Memory providers for the peer site.
+//!
+//! These are how peers exchange branch heads: resolving a cell reads the
+//! peer's head, publishing one compare-and-swaps it against the version the
+//! caller last saw. A publish sends the content beside the invocation,
+//! whose arguments carry only its checksum. Neither write is replayed when
+//! a connection fails under it: the swap may already have happened.
+
+use dialog_capability::{ForkInvocation, Policy, Provider};
+use dialog_effects::memory::{Edition, MemoryError, Publish, Resolve, Retract, Version};
+
+use crate::peer::protocol::{Refusal, Reply};
+use crate::peer::{Peer, Replay};
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Provider<ForkInvocation<Peer, Resolve>> for Peer {
+    async fn execute(
+        &self,
+        input: ForkInvocation<Peer, Resolve>,
+    ) -> Result<Option<Edition<Vec<u8>>>, MemoryError> {
+        match self
+            .exchange(&input.address, &input.authorization, None, Replay::Safe)
+            .await?
+        {
+            Reply::Edition(cell) => Ok(cell.map(Edition::from)),
+            other => Err(Refusal::unexpected(&other).into()),
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Provider<ForkInvocation<Peer, Publish>> for Peer {
+    async fn execute(&self, input: ForkInvocation<Peer, Publish>) -> Result<Version, MemoryError> {
+        let content = Publish::of(&input.capability).content.clone();
+        match self
+            .exchange(
+                &input.address,
+                &input.authorization,
+                Some(content),
+                Replay::Unsafe,
+            )
+            .await?
+        {
+            Reply::Version(version) => Ok(version),
+            other => Err(Refusal::unexpected(&other).into()),
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Provider<ForkInvocation<Peer, Retract>> for Peer {
+    async fn execute(&self, input: ForkInvocation<Peer, Retract>) -> Result<(), MemoryError> {
+        match self
+            .exchange(&input.address, &input.authorization, None, Replay::Unsafe)
+            .await?
+        {
+            Reply::Done => Ok(()),
+            other => Err(Refusal::unexpected(&other).into()),
+        }
+    }
+}
@@ -0,0 +1,561 @@
+//! The responding side of the protocol.
+//!
+//! A [`Responder`] is what a peer runs to let others sync with it. For each
+//! [request](super::protocol::Request) it does what an access service does
+//! for a UCAN site — parse the invocation container, verify the chain, and
+//! rebuild the effect from the invocation's command and arguments — and
+//! then, where an access service would presign a request for someone else
+//! to perform, performs the effect itself against its own storage.
+//!
+//! The bytes an effect moves arrive beside the invocation rather than in
+//! it, so before acting on them the responder projects the effect it
+//! rebuilt into its attenuation and checks that it is the one the
+//! invocation's arguments name: a block must hash to the signed digest,
+//! published content to the signed checksum.
+//!
+//! A responder given an executor [signs a receipt](Responder::with_receipts)
+//! for each invocation it performs once the effect has run, recording what
+//! it produced in a [`ReceiptStore`], so promises awaiting that invocation
+//! can resolve.
+//!
+//! A responder given an [`ArchiveLease`](Responder::with_lease) claims a
+//! subject's archive before a connection first writes to it and holds the
+//! claim until a publish on that subject has run, so a garbage collection
+//! cannot remove the blocks of a push before its head names them.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use dialog_capability::access::AuthorizeError;
+use dialog_capability::{Attenuate, Capability, Did, Effect, Provider, Subject};
+use dialog_common::ConditionalSync;
+use dialog_credentials::Signer;
+use dialog_did_web::{CachingResolver, PerformingResolver, WebResolver};
+use dialog_effects::Rejection;
+use dialog_effects::{archive, blob, memory};
+use dialog_ucan_core::promise::Promised;
+use dialog_ucan_core::{
+    ContainerError, Environment, InvocationChain, Outcome, ReceiptStore, Receipts,
+    RevocationChecker, SuccessionChecker, UnrecordedReceipts, UnverifiedRevocations,
+    UnverifiedSuccessions, VerificationContext,
+};
+use dialog_varsig::AnySignature;
+use futures_util::StreamExt as _;
+use futures_util::stream::FuturesUnordered;
+use ipld_core::ipld::Ipld;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use super::Listener;
+use super::lease::{ArchiveLease, UnleasedArchive};
+use super::protocol::{
+    Cell, FrameError, MAX_FRAME_BYTES, Refusal, Reply, Request, Response, read_frame, write_frame,
+};
+
+type Args = BTreeMap<String, Promised>;
+
+/// Serves the subjects it hosts to peers that hold delegations for them.
+///
+/// `Env` performs the effects: any environment that provides the archive,
+/// memory and blob effects for the hosted subjects, such as the storage a
+/// repository lives in. Resolution of issuer DIDs uses the same default as
+/// [`UcanAuthorizer`](https://docs.rs/dialog-remote-ucan-s3): `did:key`
+/// locally, `did:web` over the network, cached. The `Revocations` type
+/// parameter is the [`RevocationChecker`] consulted for every link of the
+/// chain; it defaults to [`UnverifiedRevocations`], which looks nothing up.
+/// Likewise `Successions`, the [`SuccessionChecker`] consulted for every
+/// issuer, defaults to [`UnverifiedSuccessions`], and `Store`, the
+/// [`ReceiptStore`] receipts are kept in, to [`UnrecordedReceipts`], and
+/// `Lease`, the [`ArchiveLease`] writes hold, to [`UnleasedArchive`].
+pub struct Responder<
+    Env,
+    Revocations = UnverifiedRevocations,
+    Successions = UnverifiedSuccessions,
+    Store = UnrecordedReceipts,
+    Lease = UnleasedArchive,
+> {
+    env: Arc<Env>,
+    hosted: Arc<HashSet<Did>>,
+    resolver: Arc<CachingResolver<WebResolver>>,
+    revocations: Arc<Revocations>,
+    successions: Arc<Successions>,
+    receipts: Option<Arc<Receipts<Signer, Store>>>,
+    lease: Arc<Lease>,
+}
+
+impl<Env, Revocations, Successions, Store, Lease> Clone
+    for Responder<Env, Revocations, Successions, Store, Lease>
+{
+    fn clone(&self) -> Self {
+        Self {
+            env: self.env.clone(),
+            hosted: self.hosted.clone(),
+            resolver: self.resolver.clone(),
+            revocations: self.revocations.clone(),
+            successions: self.successions.clone(),
+            receipts: self.receipts.clone(),
+            lease: self.lease.clone(),
+        }
+    }
+}
+
+impl<Env, Revocations, Successions, Store, Lease> std::fmt::Debug
+    for Responder<Env, Revocations, Successions, Store, Lease>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Responder")
+            .field("hosted", &self.hosted)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Env> Responder<Env> {
+    /// A responder performing effects against `env`, hosting no subjects
+    /// yet.
+    pub fn new(env: Env) -> Self {
+        Self {
+            env: Arc::new(env),
+            hosted: Arc::default(),
+            resolver: Arc::new(CachingResolver::new(WebResolver::new())),
+            revocations: Arc::new(UnverifiedRevocations),
+            successions: Arc::new(UnverifiedSuccessions),
+            receipts: None,
+            lease: Arc::new(UnleasedArchive),
+        }
+    }
+}
+
+impl<Env, Revocations, Successions, Store, Lease>
+    Responder<Env, Revocations, Successions, Store, Lease>
+{
+    /// Host `subject` in addition to those already hosted. Requests on any
+    /// other subject are refused before their chain is verified.
+    pub fn host(mut self, subject: Did) -> Self {
+        Arc::make_mut(&mut self.hosted).insert(subject);
+        self
+    }
+
+    /// Consult `revocations` for every link of the chains this responder
+    /// verifies.
+    pub fn with_revocations<Checker>(
+        self,
+        revocations: Checker,
+    ) -> Responder<Env, Checker, Successions, Store, Lease> {
+        Responder {
+            env: self.env,
+            hosted: self.hosted,
+            resolver: self.resolver,
+            revocations: Arc::new(revocations),
+            successions: self.successions,
+            receipts: self.receipts,
+            lease: self.lease,
+        }
+    }
+
+    /// Consult `successions` for every issuer in the chains this responder
+    /// verifies, refusing links out of a rotated key that do not carry its
+    /// authority to its successor.
+    pub fn with_successions<Checker>(
+        self,
+        successions: Checker,
+    ) -> Responder<Env, Revocations, Checker, Store, Lease> {
+        Responder {
+            env: self.env,
+            hosted: self.hosted,
+            resolver: self.resolver,
+            revocations: self.revocations,
+            successions: Arc::new(successions),
+            receipts: self.receipts,
+            lease: self.lease,
+        }
+    }
+
+    /// Sign a receipt as `executor` for every invocation this responder
+    /// performs, and record it in `store` once the effect has run.
+    ///
+    /// The receipt names the invocation by CID and records what performing
+    /// it produced: the reply, or why the effect failed. Invocations refused
+    /// before their chain verified never ran, and get none. One whose
+    /// receipt cannot be recorded is answered with a refusal saying so,
+    /// though its effect has run. A branch's
+    /// [retained receipts](https://docs.rs/dialog-repository) keep them
+    /// beside the delegations, where later invocations can await them.
+    pub fn with_receipts<Keep>(
+        self,
+        executor: Signer,
+        store: Keep,
+    ) -> Responder<Env, Revocations, Successions, Keep, Lease> {
+        Responder {
+            env: self.env,
+            hosted: self.hosted,
+            resolver: self.resolver,
+            revocations: self.revocations,
+            successions: self.successions,
+            receipts: Some(Arc::new(Receipts::new(executor, store))),
+            lease: self.lease,
+        }
+    }
+
+    /// Claim a subject's archive through `lease` before a connection first
+    /// writes to it, holding the claim until a publish or retraction on
+    /// that subject has run or the connection closes. While a garbage
+    /// collection holds the archive, writes are refused.
+    pub fn with_lease<Holder>(
+        self,
+        lease: Holder,
+    ) -> Responder<Env, Revocations, Successions, Store, Holder> {
+        Responder {
+            env: self.env,
+            hosted: self.hosted,
+            resolver: self.resolver,
+            revocations: self.revocations,
+            successions: self.successions,
+            receipts: self.receipts,
+            lease: Arc::new(lease),
+        }
+    }
+}
+
+impl<Env, Revocations, Successions, Store, Lease>
+    Responder<Env, Revocations, Successions, Store, Lease>
+where
+    Env: Provider<archive::Get>
+        + Provider<archive::Put>
+        + Provider<memory::Resolve>
+        + Provider<memory::Publish>
+        + Provider<memory::Retract>
+        + Provider<blob::Read>
+        + Provider<blob::Import>
+        + ConditionalSync,
+    Revocations: RevocationChecker,
+    Successions: SuccessionChecker,
+    Store: ReceiptStore<AnySignature>,
+    Lease: ArchiveLease,
+{
+    /// Answer requests arriving on `stream` until the initiator closes it.
+    pub async fn serve<S>(&self, mut stream: S) -> Result<(), FrameError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let mut claims = HashMap::new();
+        let served = async {
+            while let Some(request) = read_frame::<_, Request>(&mut stream).await? {
+                let response = self.answer(request, &mut claims).await;
+                write_frame(&mut stream, &response).await?;
+            }
+            Ok(())
+        }
+        .await;
+        for claim in claims.into_values() {
+            self.release(claim).await;
+        }
+        served
+    }
+
+    /// Verify `request`'s invocation and perform the effect it names.
+    pub async fn respond(&self, request: Request) -> Response {
+        let mut claims = HashMap::new();
+        let response = self.answer(request, &mut claims).await;
+        for claim in claims.into_values() {
+            self.release(claim).await;
+        }
+        response
+    }
+
+    /// [`respond`](Self::respond) to one request of a connection holding
+    /// `claims`, one per subject it has written to since that subject's
+    /// last publish.
+    async fn answer(&self, request: Request, claims: &mut HashMap<Did, Lease::Claim>) -> Response {
+        let chain = InvocationChain::try_from(request.invocation.as_slice()).map_err(|e| {
+            AuthorizeError::Malformed {
+                detail: e.to_string(),
+            }
+        })?;
+
+        // Refuse subjects this peer does not host before verifying
+        // anything, so a stranger learns nothing from how far their chain
+        // got.
+        if !self.hosted.contains(chain.subject()) {
+            return Err(Rejection::Unclassified {
+                detail: format!("{} is not hosted here", chain.subject()),
+            }
+            .into());
+        }
+        self.verify(&chain).await?;
+
+        let command: Vec<&str> = chain.command().0.iter().map(String::as_str).collect();
+        let writes = matches!(
+            command.as_slice(),
+            ["memory", "publish" | "retract"] | ["archive", "put"] | ["archive", "blob", "import"]
+        );
+        if writes && !claims.contains_key(chain.subject()) {
+            let claim =
+                self.lease
+                    .acquire(chain.subject())
+                    .await
+                    .map_err(|e| Rejection::Unclassified {
+                        detail: format!("the archive of {} is held: {e}", chain.subject()),
+                    })?;
+            claims.insert(chain.subject().clone(), claim);
+        }
+
+        let response = self.perform(&chain, request.payload).await;
+        // A publish or retraction is what names the blocks written before
+        // it, so it ends the claim they were written under.
+        if let ["memory", "publish" | "retract"] = command.as_slice()
+            && let Some(claim) = claims.remove(chain.subject())
+        {
+            self.release(claim).await;
+        }
+        match &self.receipts {
+            Some(receipts) => issue(receipts, &chain, response).await,
+            None => response,
+        }
+    }
+
+    /// Withdraw `claim`. A claim that cannot be withdrawn lapses on its
+    /// own, so the failure holds up nothing but the next collection.
+    async fn release(&self, claim: Lease::Claim) {
+        if let Err(error) = self.lease.release(claim).await {
+            tracing::warn!(%error, "could not withdraw an archive claim; it will lapse");
+        }
+    }
+
+    /// Perform the effect the verified invocation in `chain` names, on the
+    /// bytes `sent` beside it.
+    async fn perform(
+        &self,
+        chain: &InvocationChain<AnySignature>,
+        sent: Option<Vec<u8>>,
+    ) -> Response {
+        let subject = chain.subject().clone();
+        let args = chain.arguments();
+        let command: Vec<&str> = chain.command().0.iter().map(String::as_str).collect();
+        let env = self.env.as_ref();
+
+        match command.as_slice() {
+            ["memory", "resolve"] => {
+                let capability = cell(&subject, args, memory::Resolve)?;
+                let edition = Provider::<memory::Resolve>::execute(env, capability).await?;
+                Ok(Reply::Edition(edition.map(Cell::from)))
+            }
+            ["memory", "publish"] => {
+                let claimed: memory::PublishAttenuation = argument(args)?;
+                let publish = memory::Publish::new(payload(sent)?, claimed.when);
+                carried(&publish, args)?;
+                let capability = cell(&subject, args, publish)?;
+                let version = Provider::<memory::Publish>::execute(env, capability).await?;
+                Ok(Reply::Version(version))
+            }
+            ["memory", "retract"] => {
+                let capability = cell(&subject, args, argument::<memory::Retract>(args)?)?;
+                Provider::<memory::Retract>::execute(env, capability).await?;
+                Ok(Reply::Done)
+            }
+            ["archive", "get"] => {
+                let capability = catalog(&subject, args, argument::<archive::Get>(args)?)?;
+                let block = Provider::<archive::Get>::execute(env, capability).await?;
+                Ok(Reply::Block(block))
+            }
+            ["archive", "put"] => {
+                let put = archive::Put::new(payload(sent)?);
+                carried(&put, args)?;
+                let capability = catalog(&subject, args, put)?;
+                Provider::<archive::Put>::execute(env, capability).await?;
+                Ok(Reply::Done)
+            }
+            ["archive", "blob", "read"] => {
+                let capability = store(&subject, argument::<blob::Read>(args)?);
+                let mut reader = Provider::<blob::Read>::execute(env, capability).await?;
+                let mut bytes = Vec::new();
+                while let Some(chunk) = reader.next().await? {
+                    bytes.extend_from_slice(&chunk);
+                    if bytes.len() > MAX_FRAME_BYTES {
+                        return Err(Rejection::Unclassified {
+                            detail: "blob is larger than a peer frame".into(),
+                        }
+                        .into());
+                    }
+                }
+                Ok(Reply::Blob(bytes))
+            }
+            ["archive", "blob", "import"] => {
+                let import: blob::Import = argument(args)?;
+                let bytes = payload(sent)?;
+                if bytes.len() as u64 != import.size {
+                    return Err(malformed(format!(
+                        "blob of {} bytes sent for an import of {}",
+                        bytes.len(),
+                        import.size
+                    )));
+                }
+                let capability = store(&subject, import);
+                let mut writer = Provider::<blob::Import>::execute(env, capability).await?;
+                writer.write_all(&bytes).await?;
+                writer.finish().await?;
+                Ok(Reply::Done)
+            }
+            _ => Err(malformed(format!(
+                "no effect answers to /{}",
+                command.join("/")
+            ))),
+        }
+    }
+
+    /// Serve every connection `listener` accepts until accepting fails.
+    ///
+    /// Connections are served concurrently on the task driving this future,
+    /// each answering its requests in turn.
+    pub async fn listen(&self, mut listener: Listener) -> std::io::Result<()> {
+        let mut connections = FuturesUnordered::new();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => connections.push(self.serve(accepted?)),
+                // A connection that fails ends on its own; the others and
+                // the listener carry on.
+                Some(_) = connections.next(), if !connections.is_empty() => {}
+            }
+        }
+    }
+
+    /// Verify the invocation chain, mapping failures the way
+    /// [`UcanAuthorizer`](https://docs.rs/dialog-remote-ucan-s3) does.
+    async fn verify(&self, chain: &InvocationChain<AnySignature>) -> Result<(), AuthorizeError> {
+        let resolver = PerformingResolver::new(self.resolver.as_ref());
+        let environment =
+            Environment::new(chain.proof_store(), resolver, self.revocations.as_ref());
+        let context = VerificationContext::new(&environment);
+        let verified = async {
+            chain.verify(&context).await?;
+            chain.verify_successions(self.successions.as_ref()).await
+        }
+        .await;
+        verified.map_err(|e| match e {
+            ContainerError::InvalidDelegationSignature { issuer, .. } => {
+                AuthorizeError::InvalidSignature { issuer }
+            }
+            ContainerError::Revoked { .. } | ContainerError::Superseded { .. } => {
+                AuthorizeError::Revoked {
+                    subject: chain.subject().clone(),
+                }
+            }
+            ContainerError::Invocation(detail) => AuthorizeError::Malformed {
+                detail: format!("invocation chain did not verify: {detail}"),
+            },
+            ContainerError::Configuration(detail) => AuthorizeError::Unavailable {
+                detail: format!("could not verify the invocation chain: {detail}"),
+            },
+        })
+    }
+}
+
+/// Sign and record a receipt for `chain`'s invocation over `response`, and
+/// answer with it. The effect has already run, so a receipt that cannot be
+/// encoded, signed or recorded does not undo it; the initiator is told
+/// instead, with a [`Refusal::Storage`] in place of the response, and can
+/// read the effect back to learn what it did.
+async fn issue<Store>(
+    receipts: &Receipts<Signer, Store>,
+    chain: &InvocationChain<AnySignature>,
+    response: Response,
+) -> Response
+where
+    Store: ReceiptStore<AnySignature>,
+{
+    let ran = chain.invocation.to_cid();
+    let issued = match Outcome::from_result(&response) {
+        Ok(out) => receipts.issue::<AnySignature>(ran, out).await.map(|_| ()),
+        Err(error) => Err(error),
+    };
+    match issued {
+        Ok(()) => response,
+        Err(error) => Err(Refusal::Storage(format!(
+            "performed invocation {ran} but could not receipt it: {error}"
+        ))),
+    }
+}
+
+fn malformed(detail: String) -> Refusal {
+    AuthorizeError::Malformed { detail }.into()
+}
+
+/// Deserialize a typed value from the invocation's arguments via an IPLD
+/// round trip. Unknown fields are ignored, so this reads any layer of the
+/// capability chain out of the flat argument map.
+fn argument<T: DeserializeOwned>(args: &Args) -> Result<T, Refusal> {
+    let map: BTreeMap<String, Ipld> = args
+        .iter()
+        .map(|(key, value)| {
+            Ipld::try_from(value)
+                .map(|ipld| (key.clone(), ipld))
+                .map_err(|e| malformed(format!("unresolved promise for '{key}': {e}")))
+        })
+        .collect::<Result<_, _>>()?;
+    ipld_core::serde::from_ipld(Ipld::Map(map))
+        .map_err(|e| malformed(format!("invocation arguments do not decode: {e}")))
+}
+
+/// The bytes an effect moves, which a request for it must carry.
+fn payload(payload: Option<Vec<u8>>) -> Result<Vec<u8>, Refusal> {
+    payload.ok_or_else(|| malformed("the request carries no payload for its effect".into()))
+}
+
+/// Check that `effect`, rebuilt around the bytes that arrived, is the one
+/// the invocation's arguments name.
+fn carried<Fx>(effect: &Fx, args: &Args) -> Result<(), Refusal>
+where
+    Fx: Attenuate + Clone,
+{
+    let claimed: Fx::Attenuation = argument(args)?;
+    if ipld(&claimed)? != ipld(&effect.clone().into_attenuation())? {
+        return Err(malformed(
+            "the payload is not the one the invocation names".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn ipld<T: Serialize>(value: &T) -> Result<Ipld, Refusal> {
+    ipld_core::serde::to_ipld(value).map_err(|e| malformed(e.to_string()))
+}
+
+/// `Subject -> Memory -> Space -> Cell -> effect`, the space and cell read
+/// from the arguments.
+fn cell<Fx>(subject: &Did, args: &Args, effect: Fx) -> Result<Capability<Fx>, Refusal>
+where
+    Fx: Effect<Of = memory::Cell>,
+{
+    let space: memory::Space = argument(args)?;
+    let cell: memory::Cell = argument(args)?;
+    Ok(Subject::from(subject.clone())
+        .attenuate(memory::Memory)
+        .attenuate(space)
+        .attenuate(cell)
+        .invoke(effect))
+}
+
+/// `Subject -> Archive -> Catalog -> effect`, the catalog read from the
+/// arguments.
+fn catalog<Fx>(subject: &Did, args: &Args, effect: Fx) -> Result<Capability<Fx>, Refusal>
+where
+    Fx: Effect<Of = archive::Catalog>,
+{
+    let catalog: archive::Catalog = argument(args)?;
+    Ok(Subject::from(subject.clone())
+        .attenuate(archive::Archive)
+        .attenuate(catalog)
+        .invoke(effect))
+}
+
+/// `Subject -> Archive -> Blob -> effect`. `Blob` is a unit segment, so
+/// nothing is read from the arguments.
+fn store<Fx>(subject: &Did, effect: Fx) -> Capability<Fx>
+where
+    Fx: Effect<Of = blob::Blob>,
+{
+    Subject::from(subject.clone())
+        .attenuate(archive::Archive)
+        .attenuate(blob::Blob)
+        .invoke(effect)
+}
@@ -0,0 +1,243 @@
+//! Byte streams a peer is reached over.
+//!
+//! The [protocol](super::protocol) only needs an ordered, reliable byte
+//! stream, so a transport is anything that opens one. The endpoint of a
+//! [`PeerAddress`] names which:
+//!
+//! - `tcp://host:port` — a TCP connection (native only).
+//! - `memory://name` — an in-process duplex pipe to a [`Listener`] bound
+//!   under `name` in this process. Tests use it to connect two operators
+//!   without a socket.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use dialog_common::ConditionalSend;
+use dialog_effects::Rejection;
+use parking_lot::Mutex;
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream};
+use tokio::sync::mpsc;
+
+use super::PeerAddress;
+
+/// How much a memory pipe buffers in each direction before the writer
+/// waits for the reader.
+const MEMORY_PIPE_BYTES: usize = 64 * 1024;
+
+/// An ordered, reliable byte stream to a peer.
+pub trait Duplex: AsyncRead + AsyncWrite + Unpin + ConditionalSend {}
+
+impl<T> Duplex for T where T: AsyncRead + AsyncWrite + Unpin + ConditionalSend {}
+
+/// An open connection to a peer, whatever carries it.
+pub type Connection = Box<dyn Duplex>;
+
+/// The memory listeners bound in this process, by name.
+static MEMORY: LazyLock<Mutex<HashMap<String, mpsc::UnboundedSender<DuplexStream>>>> =
+    LazyLock::new(Mutex::default);
+
+/// The transport an endpoint names.
+enum Transport<'a> {
+    Tcp(&'a str),
+    Memory(&'a str),
+}
+
+impl<'a> Transport<'a> {
+    fn of(address: &'a PeerAddress) -> Result<Self, Rejection> {
+        let endpoint = address.endpoint();
+        if let Some(authority) = endpoint.strip_prefix("tcp://") {
+            Ok(Transport::Tcp(authority))
+        } else if let Some(name) = endpoint.strip_prefix("memory://") {
+            Ok(Transport::Memory(name))
+        } else {
+            Err(Rejection::Unclassified {
+                detail: format!("{endpoint} does not name a peer transport"),
+            })
+        }
+    }
+}
+
+/// Open a connection to the peer at `address`.
+pub async fn connect(address: &PeerAddress) -> Result<Connection, Rejection> {
+    match Transport::of(address)? {
+        Transport::Tcp(authority) => tcp::connect(authority).await,
+        Transport::Memory(name) => {
+            let listener = MEMORY.lock().get(name).cloned();
+            let Some(listener) = listener else {
+                return Err(Rejection::Unavailable {
+                    reason: format!("no peer listens at {}", address.endpoint()),
+                });
+            };
+            let (near, far) = tokio::io::duplex(MEMORY_PIPE_BYTES);
+            listener.send(far).map_err(|_| Rejection::Unavailable {
+                reason: format!("the peer at {} stopped listening", address.endpoint()),
+            })?;
+            Ok(Box::new(near))
+        }
+    }
+}
+
+/// Accepts connections from peers.
+pub struct Listener {
+    address: PeerAddress,
+    incoming: Incoming,
+}
+
+enum Incoming {
+    #[cfg(not(target_arch = "wasm32"))]
+    Tcp(tokio::net::TcpListener),
+    Memory(mpsc::UnboundedReceiver<DuplexStream>),
+}
+
+impl Listener {
+    /// Listen at `address`.
+    ///
+    /// A TCP address with port `0` binds an ephemeral port;
+    /// [`address`](Self::address) names the one bound. A memory name can
+    /// be bound by one listener at a time.
+    pub async fn bind(address: &PeerAddress) -> std::io::Result<Self> {
+        let transport = Transport::of(address).map_err(std::io::Error::other)?;
+        match transport {
+            Transport::Tcp(authority) => tcp::bind(authority).await,
+            Transport::Memory(name) => {
+                let mut listeners = MEMORY.lock();
+                if listeners
+                    .get(name)
+                    .is_some_and(|sender| !sender.is_closed())
+                {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::AddrInUse,
+                        format!("{} is already bound", address.endpoint()),
+                    ));
+                }
+                let (sender, receiver) = mpsc::unbounded_channel();
+                listeners.insert(name.to_string(), sender);
+                Ok(Self {
+                    address: address.clone(),
+                    incoming: Incoming::Memory(receiver),
+                })
+            }
+        }
+    }
+
+    /// The address peers reach this listener at.
+    pub fn address(&self) -> &PeerAddress {
+        &self.address
+    }
+
+    /// Wait for the next peer to connect.
+    pub async fn accept(&mut self) -> std::io::Result<Connection> {
+        match &mut self.incoming {
+            #[cfg(not(target_arch = "wasm32"))]
+            Incoming::Tcp(listener) => {
+                let (stream, _) = listener.accept().await?;
+                stream.set_nodelay(true)?;
+                Ok(Box::new(stream))
+            }
+            Incoming::Memory(receiver) => match receiver.recv().await {
+                Some(stream) => Ok(Box::new(stream)),
+                None => Err(std::io::ErrorKind::BrokenPipe.into()),
+            },
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        if let (Incoming::Memory(_), Ok(Transport::Memory(name))) =
+            (&self.incoming, Transport::of(&self.address))
+        {
+            MEMORY.lock().remove(name);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod tcp {
+    use dialog_effects::Rejection;
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::{Connection, Incoming, Listener};
+    use crate::PeerAddress;
+
+    pub(super) async fn connect(authority: &str) -> Result<Connection, Rejection> {
+        let stream = TcpStream::connect(authority)
+            .await
+            .map_err(|e| Rejection::Unavailable {
+                reason: format!("could not reach the peer at {authority}: {e}"),
+            })?;
+        stream
+            .set_nodelay(true)
+            .map_err(|e| Rejection::Unavailable {
+                reason: e.to_string(),
+            })?;
+        Ok(Box::new(stream))
+    }
+
+    pub(super) async fn bind(authority: &str) -> std::io::Result<Listener> {
+        let listener = TcpListener::bind(authority).await?;
+        Ok(Listener {
+            address: PeerAddress::tcp(listener.local_addr()?),
+            incoming: Incoming::Tcp(listener),
+        })
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod tcp {
+    use dialog_effects::Rejection;
+
+    use super::{Connection, Listener};
+
+    pub(super) async fn connect(authority: &str) -> Result<Connection, Rejection> {
+        Err(Rejection::Unavailable {
+            reason: format!("TCP peers such as {authority} are not reachable from the browser"),
+        })
+    }
+
+    pub(super) async fn bind(_authority: &str) -> std::io::Result<Listener> {
+        Err(std::io::ErrorKind::Unsupported.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dialog_storage::unique_name;
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    #[dialog_common::test]
+    async fn it_connects_through_a_memory_listener() {
+        let address = PeerAddress::memory(unique_name("transport"));
+        let mut listener = Listener::bind(&address).await.unwrap();
+
+        let mut near = connect(&address).await.unwrap();
+        let mut far = listener.accept().await.unwrap();
+        near.write_all(b"hello").await.unwrap();
+        let mut read = [0u8; 5];
+        far.read_exact(&mut read).await.unwrap();
+        assert_eq!(&read, b"hello");
+    }
+
+    #[dialog_common::test]
+    async fn it_forgets_a_memory_listener_once_dropped() {
+        let address = PeerAddress::memory(unique_name("transport"));
+        let listener = Listener::bind(&address).await.unwrap();
+        assert!(Listener::bind(&address).await.is_err(), "name is taken");
+        drop(listener);
+
+        assert!(matches!(
+            connect(&address).await,
+            Err(Rejection::Unavailable { .. })
+        ));
+        assert!(Listener::bind(&address).await.is_ok(), "name is free again");
+    }
+
+    #[dialog_common::test]
+    async fn it_refuses_endpoints_without_a_transport() {
+        assert!(matches!(
+            connect(&PeerAddress::new("https://peer.example.com")).await,
+            Err(Rejection::Unclassified { .. })
+        ));
+    }
+}
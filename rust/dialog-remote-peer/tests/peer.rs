@@ -0,0 +1,405 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+//! End-to-end Operator -> Peer site -> Responder tests.
+//!
+//! These mirror `dialog-remote-fs`'s e2e tests with another peer as the
+//! remote instead of a directory: the invocation is verified by the peer's
+//! [`Responder`], which performs the effect against a [`FileSystem`] of its
+//! own. The peer is reached over an in-memory pipe unless a test says
+//! otherwise.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use anyhow::Result;
+use dialog_artifacts::{Artifact, ArtifactSelector, Instruction, Value};
+use dialog_capability::access::AuthorizeError;
+use dialog_credentials::{Ed25519Signer, SignerCredential};
+use dialog_effects::storage::Location;
+use dialog_operator::helpers::{test_operator_with_profile, unique_name};
+use dialog_operator::{Operator, Profile};
+use dialog_remote_peer::protocol::{Refusal, Reply, Request, Response, read_frame, write_frame};
+use dialog_remote_peer::{ArchiveLease, Listener, PeerAddress, Responder, connect};
+use dialog_repository::{Branch, RECEIPT_RAN, Repository, RepositoryExt as _, SiteAddress};
+use dialog_storage::provider::FileSystem;
+use dialog_storage::provider::storage::VolatileSpace;
+use dialog_storage::resource::Resource as _;
+use dialog_ucan_core::promise::Promised;
+use dialog_varsig::{Did, Principal};
+use futures_util::{StreamExt as _, stream};
+use ipld_core::cid::Cid;
+use ipld_core::ipld::Ipld;
+use parking_lot::Mutex;
+
+/// Start a peer hosting `hosted` out of a fresh directory, listening at
+/// `address`, and return the address it is reachable at.
+async fn start_peer(address: PeerAddress, hosted: &[Did]) -> Result<PeerAddress> {
+    let filesystem = FileSystem::open(&Location::temp(unique_name("peer-vault"))).await?;
+    let responder = hosted
+        .iter()
+        .cloned()
+        .fold(Responder::new(filesystem), Responder::host);
+    let listener = Listener::bind(&address).await?;
+    let address = listener.address().clone();
+    tokio::spawn(async move { responder.listen(listener).await });
+    Ok(address)
+}
+
+/// A peer reachable over an in-memory pipe.
+async fn memory_peer(hosted: &[Did]) -> Result<PeerAddress> {
+    start_peer(PeerAddress::memory(unique_name("peer")), hosted).await
+}
+
+/// Create a repository and delegate it to the profile, as a peer would
+/// before syncing it anywhere.
+async fn create_repository(
+    operator: &Operator<VolatileSpace>,
+    profile: &Profile,
+    name: &str,
+) -> Result<Repository<SignerCredential>> {
+    let repo = profile
+        .repository(unique_name(name))
+        .create()
+        .perform(operator)
+        .await?;
+    let chain = repo
+        .access()
+        .claim(&repo)
+        .delegate(profile.did())
+        .perform(operator)
+        .await?;
+    profile.access().save(chain).perform(operator).await?;
+    Ok(repo)
+}
+
+/// Open `main` in `repo`, tracking `main` of `subject` at the peer.
+async fn track<C: Principal>(
+    operator: &Operator<VolatileSpace>,
+    repo: &Repository<C>,
+    subject: Did,
+    peer: &PeerAddress,
+) -> Result<Branch> {
+    let origin = repo
+        .remote("origin")
+        .create(SiteAddress::Peer(peer.clone()))
+        .subject(subject)
+        .perform(operator)
+        .await?;
+    let branch = repo.branch("main").open().perform(operator).await?;
+    let upstream = origin.branch("main").open().perform(operator).await?;
+    branch.set_upstream(upstream).perform(operator).await?;
+    Ok(branch)
+}
+
+async fn commit_name(
+    operator: &Operator<VolatileSpace>,
+    branch: &Branch,
+    of: &str,
+    name: &str,
+) -> Result<()> {
+    branch
+        .commit(stream::iter(vec![Instruction::Assert(Artifact {
+            the: "user/name".parse()?,
+            of: of.parse()?,
+            is: Value::String(name.into()),
+            cause: None,
+        })]))
+        .perform(operator)
+        .await?;
+    Ok(())
+}
+
+async fn names(operator: &Operator<VolatileSpace>, branch: &Branch) -> Result<Vec<Value>> {
+    let mut names: Vec<Value> = branch
+        .claims()
+        .select(ArtifactSelector::new().the("user/name".parse()?))
+        .to_owned()
+        .perform(operator)
+        .await?
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .map(|artifact| artifact.map(|artifact| artifact.is))
+        .collect::<Result<_, _>>()?;
+    names.sort_by_key(|name| format!("{name:?}"));
+    Ok(names)
+}
+
+#[dialog_common::test]
+async fn it_pushes_and_pulls_through_a_peer() -> Result<()> {
+    let (operator, profile) = test_operator_with_profile().await;
+    let repo = create_repository(&operator, &profile, "peer-repo").await?;
+    let peer = memory_peer(&[repo.did()]).await?;
+    let branch = track(&operator, &repo, repo.did(), &peer).await?;
+
+    commit_name(&operator, &branch, "user:alice", "Alice").await?;
+    let pushed = branch.push().perform(&operator).await?;
+    assert!(pushed.is_some(), "push to the peer should succeed");
+
+    let pulled = branch.pull().perform(&operator).await?;
+    assert!(pulled.is_none(), "pull after push should find no changes");
+    assert_eq!(
+        names(&operator, &branch).await?,
+        vec![Value::String("Alice".into())]
+    );
+    Ok(())
+}
+
+/// An archive lease that logs every claim taken and withdrawn.
+#[derive(Clone, Default)]
+struct Logged(Arc<Mutex<Vec<&'static str>>>);
+
+impl ArchiveLease for Logged {
+    type Claim = ();
+    type Error = Infallible;
+
+    async fn acquire(&self, _subject: &Did) -> Result<(), Infallible> {
+        self.0.lock().push("claim");
+        Ok(())
+    }
+
+    async fn release(&self, _claim: ()) -> Result<(), Infallible> {
+        self.0.lock().push("release");
+        Ok(())
+    }
+}
+
+#[dialog_common::test]
+async fn it_holds_the_archive_from_a_pushed_block_to_the_head_naming_it() -> Result<()> {
+    let (operator, profile) = test_operator_with_profile().await;
+    let repo = create_repository(&operator, &profile, "peer-lease").await?;
+
+    let lease = Logged::default();
+    let filesystem = FileSystem::open(&Location::temp(unique_name("peer-vault"))).await?;
+    let responder = Responder::new(filesystem)
+        .host(repo.did())
+        .with_lease(lease.clone());
+    let listener = Listener::bind(&PeerAddress::memory(unique_name("peer"))).await?;
+    let peer = listener.address().clone();
+    tokio::spawn(async move { responder.listen(listener).await });
+    let branch = track(&operator, &repo, repo.did(), &peer).await?;
+
+    commit_name(&operator, &branch, "user:alice", "Alice").await?;
+    branch.push().perform(&operator).await?;
+
+    // Each claim covers the writes up to the publish after them, and
+    // nothing is left held once the cells name what was written.
+    let log = lease.0.lock().clone();
+    assert!(!log.is_empty(), "the push claimed the archive");
+    assert!(
+        log.chunks(2).all(|pair| pair == ["claim", "release"]),
+        "claims and releases alternate: {log:?}"
+    );
+    Ok(())
+}
+
+#[dialog_common::test]
+async fn it_receipts_each_invocation_it_performs() -> Result<()> {
+    let (operator, profile) = test_operator_with_profile().await;
+    let repo = create_repository(&operator, &profile, "peer-receipts").await?;
+
+    // The peer keeps its receipts on a branch of its own.
+    let executor = Ed25519Signer::generate().await?;
+    let kept = repo.branch("receipts").open().perform(&operator).await?;
+    let filesystem = FileSystem::open(&Location::temp(unique_name("peer-vault"))).await?;
+    let responder = Responder::new(filesystem).host(repo.did()).with_receipts(
+        executor.clone().into(),
+        kept.delegations().receipts(operator.clone()),
+    );
+    let listener = Listener::bind(&PeerAddress::memory(unique_name("peer"))).await?;
+    let peer = listener.address().clone();
+    tokio::spawn(async move { responder.listen(listener).await });
+    let branch = track(&operator, &repo, repo.did(), &peer).await?;
+
+    commit_name(&operator, &branch, "user:alice", "Alice").await?;
+    branch.push().perform(&operator).await?;
+
+    let ran: Vec<Cid> = kept
+        .claims()
+        .select(ArtifactSelector::new().the(RECEIPT_RAN.parse()?))
+        .to_owned()
+        .perform(&operator)
+        .await?
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .map(|artifact| match artifact?.is {
+            Value::String(cid) => Ok(Cid::try_from(cid.as_str())?),
+            other => anyhow::bail!("receipt facts name an invocation by CID, not {other:?}"),
+        })
+        .collect::<Result<_>>()?;
+    assert!(!ran.is_empty(), "every performed invocation is receipted");
+    let distinct = ran.iter().collect::<std::collections::HashSet<_>>();
+    assert_eq!(distinct.len(), ran.len(), "one receipt per invocation");
+
+    // Each one resolves a promise awaiting it, on the executor's word.
+    let awaiting = ran
+        .iter()
+        .enumerate()
+        .map(|(at, cid)| (at.to_string(), Promised::WaitAny(*cid)))
+        .collect();
+    let outcomes = kept
+        .delegations()
+        .resolve(awaiting, vec![executor.did()])
+        .perform(&operator)
+        .await?;
+    let published = outcomes.values().any(|outcome| {
+        let Ipld::Map(outcome) = outcome else {
+            return false;
+        };
+        outcome
+            .get("ok")
+            .and_then(|out| ipld_core::serde::from_ipld::<Reply>(out.clone()).ok())
+            .is_some_and(|reply| matches!(reply, Reply::Version(_)))
+    });
+    assert!(
+        published,
+        "the publish is receipted with the version it produced"
+    );
+    Ok(())
+}
+
+#[dialog_common::test]
+async fn it_syncs_two_profiles_in_both_directions() -> Result<()> {
+    let (alice_operator, alice_profile) = test_operator_with_profile().await;
+    let alice_repo = create_repository(&alice_operator, &alice_profile, "peer-alice").await?;
+    let peer = memory_peer(&[alice_repo.did()]).await?;
+    let alice_branch = track(&alice_operator, &alice_repo, alice_repo.did(), &peer).await?;
+
+    commit_name(&alice_operator, &alice_branch, "user:alice", "Alice").await?;
+    alice_branch.push().perform(&alice_operator).await?;
+
+    // Alice delegates the repository to Bob, who syncs the same subject
+    // through the same peer from a repository of his own.
+    let (bob_operator, bob_profile) = test_operator_with_profile().await;
+    let delegation = alice_profile
+        .access()
+        .claim(&alice_repo)
+        .delegate(bob_profile.did())
+        .perform(&alice_operator)
+        .await?;
+    bob_profile
+        .access()
+        .save(delegation)
+        .perform(&bob_operator)
+        .await?;
+    let bob_repo = bob_profile
+        .repository(unique_name("peer-bob"))
+        .open()
+        .perform(&bob_operator)
+        .await?;
+    let bob_branch = track(&bob_operator, &bob_repo, alice_repo.did(), &peer).await?;
+
+    let pulled = bob_branch.pull().perform(&bob_operator).await?;
+    assert!(pulled.is_some(), "Bob's pull should find Alice's data");
+    assert_eq!(
+        names(&bob_operator, &bob_branch).await?,
+        vec![Value::String("Alice".into())]
+    );
+
+    commit_name(&bob_operator, &bob_branch, "user:bob", "Bob").await?;
+    bob_branch.push().perform(&bob_operator).await?;
+
+    let pulled = alice_branch.pull().perform(&alice_operator).await?;
+    assert!(pulled.is_some(), "Alice's pull should find Bob's data");
+    assert_eq!(
+        names(&alice_operator, &alice_branch).await?,
+        vec![Value::String("Alice".into()), Value::String("Bob".into())]
+    );
+    Ok(())
+}
+
+#[dialog_common::test]
+async fn it_syncs_over_tcp() -> Result<()> {
+    let (operator, profile) = test_operator_with_profile().await;
+    let repo = create_repository(&operator, &profile, "peer-tcp").await?;
+    let peer = start_peer(PeerAddress::tcp("127.0.0.1:0"), &[repo.did()]).await?;
+    let branch = track(&operator, &repo, repo.did(), &peer).await?;
+
+    commit_name(&operator, &branch, "user:alice", "Alice").await?;
+    let pushed = branch.push().perform(&operator).await?;
+    assert!(pushed.is_some(), "push over TCP should succeed");
+    let pulled = branch.pull().perform(&operator).await?;
+    assert!(pulled.is_none(), "pull after push should find no changes");
+    Ok(())
+}
+
+#[dialog_common::test]
+async fn it_refuses_to_send_a_locked_space_beyond_loopback() -> Result<()> {
+    let (operator, profile) = test_operator_with_profile().await;
+    let repo = create_repository(&operator, &profile, "peer-exposed").await?;
+
+    // Nothing listens at this documentation address; the push is refused
+    // before a connection is tried.
+    let synced = async {
+        let branch = track(
+            &operator,
+            &repo,
+            repo.did(),
+            &PeerAddress::tcp("192.0.2.1:7879"),
+        )
+        .await?;
+        commit_name(&operator, &branch, "user:alice", "Alice").await?;
+        branch.push().perform(&operator).await?;
+        Ok::<_, anyhow::Error>(())
+    }
+    .await;
+    let error = synced.expect_err("a locked space must not cross the network in the clear");
+    assert!(format!("{error:?}").contains("is locked"), "{error:?}");
+    Ok(())
+}
+
+#[dialog_common::test]
+async fn it_refuses_subjects_it_does_not_host() -> Result<()> {
+    let (operator, profile) = test_operator_with_profile().await;
+    let repo = create_repository(&operator, &profile, "peer-stranger").await?;
+    let peer = memory_peer(&[]).await?;
+    let branch = track(&operator, &repo, repo.did(), &peer).await?;
+
+    commit_name(&operator, &branch, "user:alice", "Alice").await?;
+    assert!(
+        branch.push().perform(&operator).await.is_err(),
+        "a peer must not store a subject it was not asked to host"
+    );
+    Ok(())
+}
+
+#[dialog_common::test]
+async fn it_refuses_an_invocation_without_a_delegation() -> Result<()> {
+    let (alice_operator, alice_profile) = test_operator_with_profile().await;
+    let alice_repo = create_repository(&alice_operator, &alice_profile, "peer-owner").await?;
+    let peer = memory_peer(&[alice_repo.did()]).await?;
+
+    // Mallory names Alice's repository but holds no delegation for it, so
+    // the chain her operator presents does not reach the subject.
+    let (mallory_operator, mallory_profile) = test_operator_with_profile().await;
+    let mallory_repo = mallory_profile
+        .repository(unique_name("peer-mallory"))
+        .open()
+        .perform(&mallory_operator)
+        .await?;
+    let branch = track(&mallory_operator, &mallory_repo, alice_repo.did(), &peer).await?;
+
+    let refused = branch.fetch().perform(&mallory_operator).await;
+    assert!(refused.is_err(), "a peer must verify the chain it is shown");
+    Ok(())
+}
+
+#[dialog_common::test]
+async fn it_reads_back_the_refusal_the_peer_sent() -> Result<()> {
+    let peer = memory_peer(&[]).await?;
+    let mut connection = connect(&peer).await?;
+    let request = Request {
+        invocation: b"not a container".to_vec(),
+        payload: None,
+    };
+    write_frame(&mut connection, &request).await?;
+    let response: Response = read_frame(&mut connection)
+        .await?
+        .expect("the peer answers");
+    assert!(matches!(
+        response,
+        Err(Refusal::Authorization(AuthorizeError::Malformed { .. }))
+    ));
+    Ok(())
+}
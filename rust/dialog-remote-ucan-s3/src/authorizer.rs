@@ -11,6 +11,7 @@
 //! 2. Verifies the invocation and delegation chain
 //! 3. Extracts the command and arguments from the invocation
 //! 4. Delegates to wrapped credentials to get a presigned URL
+//! 5. Signs a receipt for the outcome, when given an executor
 //!
 //! # Container Format
 //!
@@ -53,6 +54,7 @@
 //! ```
 
 use dialog_capability::access::AuthorizeError;
+use dialog_credentials::Signer;
 use dialog_ucan_core::ContainerError;
 use std::collections::BTreeMap;
 
@@ -61,7 +63,13 @@ use dialog_did_web::{CachingResolver, PerformingResolver, Resolve, WebResolver};
 use dialog_effects::{archive, blob, memory};
 use dialog_remote_s3::{Address, Permit, S3Credential, S3Error};
 use dialog_ucan_core::promise::Promised;
-use dialog_ucan_core::{Environment, InvocationChain, VerificationContext};
+use dialog_ucan_core::{
+    Environment, InvocationChain, Outcome, ReceiptStore, Receipts, RevocationChecker,
+    SuccessionChecker, UnrecordedReceipts, UnverifiedRevocations, UnverifiedSuccessions,
+    VerificationContext,
+};
+use dialog_varsig::AnySignature;
+use ipld_core::cid::Cid;
 use ipld_core::ipld::Ipld;
 use serde::de::DeserializeOwned;
 
@@ -260,13 +268,40 @@ macro_rules! dispatch {
 /// result. Inject a different provider with [`UcanAuthorizer::with_resolver`] to
 /// change the resolution policy (for example, `did:key`-only, or a custom
 /// fetcher).
-pub struct UcanAuthorizer<Resolver = CachingResolver<WebResolver>> {
+///
+/// The `Revocations` type parameter is the [`RevocationChecker`] consulted for
+/// every link of the delegation chain. It defaults to [`UnverifiedRevocations`],
+/// which looks nothing up; install a real store with
+/// [`UcanAuthorizer::with_revocations`] so that revoking any link denies the
+/// invocations that rely on it.
+///
+/// The `Successions` type parameter is the [`SuccessionChecker`] consulted
+/// for every issuer in the chain. It defaults to [`UnverifiedSuccessions`];
+/// install one with [`UcanAuthorizer::with_successions`] so that a rotated
+/// key's delegations stop counting, except those carrying its authority to
+/// its successor.
+///
+/// The `Store` type parameter is the [`ReceiptStore`] the receipts an
+/// executor set with [`UcanAuthorizer::with_receipts`] signs are kept in.
+/// Without an executor no receipts are issued, and it stays the default
+/// [`UnrecordedReceipts`].
+pub struct UcanAuthorizer<
+    Resolver = CachingResolver<WebResolver>,
+    Revocations = UnverifiedRevocations,
+    Successions = UnverifiedSuccessions,
+    Store = UnrecordedReceipts,
+> {
     address: Address,
     credential: Option<S3Credential>,
     resolver: std::sync::Arc<Resolver>,
+    revocations: std::sync::Arc<Revocations>,
+    successions: std::sync::Arc<Successions>,
+    receipts: Option<std::sync::Arc<Receipts<Signer, Store>>>,
 }
 
-impl<Resolver: std::fmt::Debug> std::fmt::Debug for UcanAuthorizer<Resolver> {
+impl<Resolver, Revocations, Successions, Store> std::fmt::Debug
+    for UcanAuthorizer<Resolver, Revocations, Successions, Store>
+{
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("UcanAuthorizer")
             .field("address", &self.address)
@@ -275,12 +310,17 @@ impl<Resolver: std::fmt::Debug> std::fmt::Debug for UcanAuthorizer<Resolver> {
     }
 }
 
-impl<Resolver> Clone for UcanAuthorizer<Resolver> {
+impl<Resolver, Revocations, Successions, Store> Clone
+    for UcanAuthorizer<Resolver, Revocations, Successions, Store>
+{
     fn clone(&self) -> Self {
         Self {
             address: self.address.clone(),
             credential: self.credential.clone(),
             resolver: self.resolver.clone(),
+            revocations: self.revocations.clone(),
+            successions: self.successions.clone(),
+            receipts: self.receipts.clone(),
         }
     }
 }
@@ -315,13 +355,92 @@ impl<Resolver> UcanAuthorizer<Resolver> {
             address,
             credential,
             resolver: std::sync::Arc::new(resolver),
+            revocations: std::sync::Arc::new(UnverifiedRevocations),
+            successions: std::sync::Arc::new(UnverifiedSuccessions),
+            receipts: None,
         }
     }
 }
 
-impl<Resolver> UcanAuthorizer<Resolver>
+impl<Resolver, Revocations, Successions, Store>
+    UcanAuthorizer<Resolver, Revocations, Successions, Store>
+{
+    /// Consult `revocations` for every link of the chains this authorizer
+    /// verifies.
+    ///
+    /// A link revoked by a principal entitled to revoke it fails
+    /// authorization with [`AuthorizeError::Revoked`]; a checker that
+    /// cannot answer fails it as unavailable rather than letting the chain
+    /// stand. Wrap the checker with
+    /// [`tolerate_unavailable`](RevocationChecker::tolerate_unavailable) to
+    /// opt out of that strictness.
+    pub fn with_revocations<Checker>(
+        self,
+        revocations: Checker,
+    ) -> UcanAuthorizer<Resolver, Checker, Successions, Store> {
+        UcanAuthorizer {
+            address: self.address,
+            credential: self.credential,
+            resolver: self.resolver,
+            revocations: std::sync::Arc::new(revocations),
+            successions: self.successions,
+            receipts: self.receipts,
+        }
+    }
+
+    /// Consult `successions` for every issuer in the chains this authorizer
+    /// verifies.
+    ///
+    /// A link issued by a superseded key to anyone but its successor fails
+    /// authorization with [`AuthorizeError::Revoked`]: rotating a key
+    /// withdraws what it granted, just as revoking each grant would. A
+    /// checker that cannot answer fails it as unavailable.
+    pub fn with_successions<Checker>(
+        self,
+        successions: Checker,
+    ) -> UcanAuthorizer<Resolver, Revocations, Checker, Store> {
+        UcanAuthorizer {
+            address: self.address,
+            credential: self.credential,
+            resolver: self.resolver,
+            revocations: self.revocations,
+            successions: std::sync::Arc::new(successions),
+            receipts: self.receipts,
+        }
+    }
+
+    /// Sign a receipt as `executor` for every invocation this authorizer
+    /// verifies, and record it in `store`.
+    ///
+    /// The receipt names the invocation by CID and records the [`Permit`]
+    /// minted for it, or why the chain's arguments named no effect.
+    /// Invocations whose chain does not verify are refused before they
+    /// run, and get none. A permit whose receipt cannot be signed or
+    /// recorded is withheld, and [`authorize`](Self::authorize) fails with
+    /// [`AuthorizeError::Unavailable`] instead.
+    pub fn with_receipts<Keep>(
+        self,
+        executor: Signer,
+        store: Keep,
+    ) -> UcanAuthorizer<Resolver, Revocations, Successions, Keep> {
+        UcanAuthorizer {
+            address: self.address,
+            credential: self.credential,
+            resolver: self.resolver,
+            revocations: self.revocations,
+            successions: self.successions,
+            receipts: Some(std::sync::Arc::new(Receipts::new(executor, store))),
+        }
+    }
+}
+
+impl<Resolver, Revocations, Successions, Store>
+    UcanAuthorizer<Resolver, Revocations, Successions, Store>
 where
     Resolver: dialog_capability::Provider<Resolve> + dialog_common::ConditionalSync,
+    Revocations: RevocationChecker,
+    Successions: SuccessionChecker,
+    Store: ReceiptStore<AnySignature>,
 {
     /// Authorize a UCAN container.
     ///
@@ -353,15 +472,19 @@ where
         // fetches the DID document; a cache sits in front. The chain verify path
         // only sees a varsig resolver.
         let resolver = PerformingResolver::new(self.resolver.as_ref());
-        // No revocation store exists yet, so nothing is looked up. Named for
-        // what it does: it establishes nothing about revocation status.
-        let environment = Environment::new(
-            chain.proof_store(),
-            resolver,
-            dialog_ucan_core::UnverifiedRevocations,
-        );
+        // Every link is looked up in the configured revocation checker, with
+        // the chain's own issuers as the principals entitled to revoke it.
+        let environment =
+            Environment::new(chain.proof_store(), resolver, self.revocations.as_ref());
         let context = VerificationContext::new(&environment);
-        chain.verify(&context).await.map_err(|e| {
+        let verified = async {
+            chain.verify(&context).await?;
+            // A chain that holds up is still refused when a key it relies
+            // on has been rotated away.
+            chain.verify_successions(self.successions.as_ref()).await
+        }
+        .await;
+        verified.map_err(|e| {
             // Two different failures arrive here: their material not
             // verifying, and our own setup being unable to check it (for
             // example, an unreachable did:web host). Only the first is a
@@ -375,9 +498,11 @@ where
                 }
                 // The authority was withdrawn rather than never held or
                 // forged, so retrying with the same proof is pointless.
-                ContainerError::Revoked { .. } => AuthorizeError::Revoked {
-                    subject: chain.subject().clone(),
-                },
+                ContainerError::Revoked { .. } | ContainerError::Superseded { .. } => {
+                    AuthorizeError::Revoked {
+                        subject: chain.subject().clone(),
+                    }
+                }
                 ContainerError::Invocation(detail) => AuthorizeError::Malformed {
                     detail: format!("invocation chain did not verify: {detail}"),
                 },
@@ -396,15 +521,47 @@ where
 
         let command_segments: Vec<&str> = command.0.iter().map(|s| s.as_str()).collect();
 
-        dispatch!(self, subject_did, args, command_segments.as_slice(), {
-            ["memory", "resolve"]  => dialog_effects::memory::Resolve,
-            ["memory", "publish"]  => dialog_effects::memory::Publish,
-            ["memory", "retract"]  => dialog_effects::memory::Retract,
-            ["archive", "get"]     => dialog_effects::archive::Get,
-            ["archive", "put"]     => dialog_effects::archive::Put,
-            ["archive", "blob", "read"]   => dialog_effects::blob::Read,
-            ["archive", "blob", "import"] => dialog_effects::blob::Import,
-        })
+        let result = async {
+            dispatch!(self, subject_did, args, command_segments.as_slice(), {
+                ["memory", "resolve"]  => dialog_effects::memory::Resolve,
+                ["memory", "publish"]  => dialog_effects::memory::Publish,
+                ["memory", "retract"]  => dialog_effects::memory::Retract,
+                ["archive", "get"]     => dialog_effects::archive::Get,
+                ["archive", "put"]     => dialog_effects::archive::Put,
+                ["archive", "blob", "read"]   => dialog_effects::blob::Read,
+                ["archive", "blob", "import"] => dialog_effects::blob::Import,
+            })
+        }
+        .await;
+
+        // Nothing has been done with the permit yet, so one that cannot be
+        // receipted is withheld rather than handed out unaccounted for.
+        if let Some(receipts) = &self.receipts {
+            let ran = chain.invocation.to_cid();
+            let out = outcome(&result).map_err(|error| unreceipted(ran, error))?;
+            receipts
+                .issue::<AnySignature>(ran, out)
+                .await
+                .map_err(|error| unreceipted(ran, error))?;
+        }
+        result
+    }
+}
+
+/// The failure to receipt the invocation `ran`: our own setup's, not a
+/// statement about the request.
+fn unreceipted(ran: Cid, error: dialog_ucan_core::ReceiptError) -> S3Error {
+    S3Error::Authorization(AuthorizeError::Unavailable {
+        detail: format!("could not receipt invocation {ran}: {error}"),
+    })
+}
+
+/// Encode an authorization result as a receipt outcome: the permit, or the
+/// message of the failure.
+fn outcome(result: &Result<Permit, S3Error>) -> Result<Outcome, dialog_ucan_core::ReceiptError> {
+    match result {
+        Ok(permit) => Outcome::from_result::<_, ()>(&Ok(permit)),
+        Err(error) => Outcome::from_result::<(), _>(&Err(error.to_string())),
     }
 }
 
@@ -422,6 +579,7 @@ mod tests {
     use dialog_ucan_core::InvocationBuilder;
     use dialog_ucan_core::InvocationChain;
     use dialog_ucan_core::subject::Subject as DelegatedSubject;
+    use dialog_varsig::AnySignature;
     use std::collections::BTreeMap;
 
     /// Helper to create a test signer
@@ -545,6 +703,272 @@ mod tests {
         }
     }
 
+    /// Revocations held in memory, matched the way a store would: by the
+    /// revoked delegation and the entitled principals.
+    struct Revoked(Vec<dialog_ucan_core::Revocation<dialog_varsig::AnySignature>>);
+
+    impl RevocationChecker for Revoked {
+        type Error = dialog_ucan_core::revocation::Never;
+
+        async fn query(
+            &self,
+            selector: dialog_ucan_core::RevocationSelector<'_>,
+        ) -> Result<Option<dialog_ucan_core::RevocationMatch>, Self::Error> {
+            Ok(self
+                .0
+                .iter()
+                .find(|revocation| {
+                    revocation.revokes() == selector.delegation
+                        && selector.by.contains(revocation.issuer())
+                })
+                .map(|revocation| dialog_ucan_core::RevocationMatch {
+                    revocation: revocation.to_cid(),
+                    principal: revocation.issuer().clone(),
+                }))
+        }
+    }
+
+    #[dialog_common::test]
+    async fn it_denies_an_invocation_whose_delegation_was_revoked() {
+        let subject_signer = test_signer().await;
+        let operator_signer = Ed25519Signer::import(&[1u8; 32]).await.unwrap();
+        let bystander_signer = Ed25519Signer::import(&[9u8; 32]).await.unwrap();
+
+        let address = Address::builder("https://s3.us-east-1.amazonaws.com")
+            .region("us-east-1")
+            .bucket("test-bucket")
+            .build()
+            .unwrap();
+        let credentials = S3Credential::new("access-key-id", "secret-access-key");
+
+        let mut args = BTreeMap::new();
+        args.insert("catalog".to_string(), Promised::String("blobs".to_string()));
+        args.insert("digest".to_string(), Promised::Bytes([0u8; 32].to_vec()));
+
+        let container = build_test_container(
+            &subject_signer,
+            &operator_signer,
+            vec!["archive".to_string(), "get".to_string()],
+            args,
+        )
+        .await;
+        let delegation = InvocationChain::try_from(container.as_slice())
+            .unwrap()
+            .proofs()[0];
+
+        // A revocation by someone outside the chain does not count.
+        let unentitled = dialog_ucan_core::Revocation::issue(
+            dialog_credentials::Signer::from(bystander_signer),
+            delegation,
+        )
+        .await
+        .unwrap();
+        let authorizer = UcanAuthorizer::new(address.clone(), Some(credentials.clone()))
+            .with_revocations(Revoked(vec![unentitled]));
+        assert!(authorizer.authorize(&container).await.is_ok());
+
+        // The delegation's own issuer withdrawing it does.
+        let revocation = dialog_ucan_core::Revocation::issue(
+            dialog_credentials::Signer::from(subject_signer.clone()),
+            delegation,
+        )
+        .await
+        .unwrap();
+        let authorizer = UcanAuthorizer::new(address, Some(credentials))
+            .with_revocations(Revoked(vec![revocation]));
+        match authorizer.authorize(&container).await {
+            Err(S3Error::Authorization(AuthorizeError::Revoked { subject })) => {
+                assert_eq!(subject, subject_signer.did());
+            }
+            other => panic!("expected Revoked, got {other:?}"),
+        }
+    }
+
+    /// One succession held in memory, reported for its predecessor.
+    struct Rotated(dialog_ucan_core::Succession<AnySignature>);
+
+    impl SuccessionChecker for Rotated {
+        type Error = dialog_ucan_core::revocation::Never;
+
+        async fn query(
+            &self,
+            principal: &Did,
+        ) -> Result<Option<dialog_ucan_core::SuccessionMatch>, Self::Error> {
+            Ok(
+                (principal == self.0.predecessor()).then(|| dialog_ucan_core::SuccessionMatch {
+                    succession: self.0.to_cid(),
+                    predecessor: self.0.predecessor().clone(),
+                    successor: Some(self.0.successor().clone()),
+                    issuer: self.0.issuer().clone(),
+                }),
+            )
+        }
+    }
+
+    #[dialog_common::test]
+    async fn it_denies_an_invocation_relying_on_a_rotated_key() {
+        let subject_signer = test_signer().await;
+        let operator_signer = Ed25519Signer::import(&[1u8; 32]).await.unwrap();
+        let successor_signer = Ed25519Signer::import(&[7u8; 32]).await.unwrap();
+
+        let address = Address::builder("https://s3.us-east-1.amazonaws.com")
+            .region("us-east-1")
+            .bucket("test-bucket")
+            .build()
+            .unwrap();
+        let credentials = S3Credential::new("access-key-id", "secret-access-key");
+
+        let mut args = BTreeMap::new();
+        args.insert("catalog".to_string(), Promised::String("blobs".to_string()));
+        args.insert("digest".to_string(), Promised::Bytes([0u8; 32].to_vec()));
+        let container = build_test_container(
+            &subject_signer,
+            &operator_signer,
+            vec!["archive".to_string(), "get".to_string()],
+            args,
+        )
+        .await;
+
+        let authorizer = UcanAuthorizer::new(address, Some(credentials));
+        assert!(authorizer.authorize(&container).await.is_ok());
+
+        // The subject's key handing over to a successor withdraws what the
+        // old key granted the operator.
+        let rotation = dialog_ucan_core::Succession::rotate(
+            dialog_credentials::Signer::from(subject_signer.clone()),
+            &successor_signer,
+        )
+        .await
+        .unwrap();
+        let authorizer = authorizer.with_successions(Rotated(rotation));
+        match authorizer.authorize(&container).await {
+            Err(S3Error::Authorization(AuthorizeError::Revoked { subject })) => {
+                assert_eq!(subject, subject_signer.did());
+            }
+            other => panic!("expected Revoked, got {other:?}"),
+        }
+    }
+
+    /// Receipts kept in memory, in the order they were recorded.
+    #[derive(Default)]
+    struct Kept(std::sync::Mutex<Vec<dialog_ucan_core::Receipt<AnySignature>>>);
+
+    impl ReceiptStore<AnySignature> for Kept {
+        type Error = dialog_ucan_core::revocation::Never;
+
+        async fn record(
+            &self,
+            receipt: dialog_ucan_core::Receipt<AnySignature>,
+        ) -> Result<(), Self::Error> {
+            self.0.lock().unwrap().push(receipt);
+            Ok(())
+        }
+    }
+
+    #[dialog_common::test]
+    async fn it_receipts_each_invocation_it_authorizes() {
+        let subject_signer = test_signer().await;
+        let operator_signer = Ed25519Signer::import(&[1u8; 32]).await.unwrap();
+        let executor = Signer::from(Ed25519Signer::import(&[5u8; 32]).await.unwrap());
+
+        let address = Address::builder("https://s3.us-east-1.amazonaws.com")
+            .region("us-east-1")
+            .bucket("test-bucket")
+            .build()
+            .unwrap();
+        let credentials = S3Credential::new("access-key-id", "secret-access-key");
+
+        let mut args = BTreeMap::new();
+        args.insert("catalog".to_string(), Promised::String("blobs".to_string()));
+        args.insert("digest".to_string(), Promised::Bytes([0u8; 32].to_vec()));
+        let container = build_test_container(
+            &subject_signer,
+            &operator_signer,
+            vec!["archive".to_string(), "get".to_string()],
+            args,
+        )
+        .await;
+        let chain = InvocationChain::try_from(container.as_slice()).unwrap();
+
+        let kept = Kept::default();
+        let authorizer = UcanAuthorizer::new(address.clone(), Some(credentials.clone()))
+            .with_receipts(executor.clone(), &kept);
+        let permit = authorizer.authorize(&container).await.expect("authorized");
+        {
+            let receipts = kept.0.lock().unwrap();
+            let [receipt] = receipts.as_slice() else {
+                panic!("one receipt expected, got {}", receipts.len());
+            };
+            assert_eq!(receipt.ran(), chain.invocation.to_cid());
+            assert_eq!(receipt.issuer(), &executor.did());
+            assert_eq!(
+                receipt.out(),
+                &Outcome::Ok(ipld_core::serde::to_ipld(&permit).unwrap())
+            );
+        }
+
+        // A chain that does not verify never ran, and gets no receipt.
+        let revocation = dialog_ucan_core::Revocation::issue(
+            dialog_credentials::Signer::from(subject_signer),
+            chain.proofs()[0],
+        )
+        .await
+        .unwrap();
+        let authorizer = UcanAuthorizer::new(address, Some(credentials))
+            .with_revocations(Revoked(vec![revocation]))
+            .with_receipts(executor, &kept);
+        assert!(authorizer.authorize(&container).await.is_err());
+        assert_eq!(kept.0.lock().unwrap().len(), 1);
+    }
+
+    /// A store that keeps nothing and says so.
+    struct Refusing;
+
+    impl ReceiptStore<AnySignature> for Refusing {
+        type Error = std::io::Error;
+
+        async fn record(
+            &self,
+            _receipt: dialog_ucan_core::Receipt<AnySignature>,
+        ) -> Result<(), Self::Error> {
+            Err(std::io::Error::other("the receipt store is full"))
+        }
+    }
+
+    #[dialog_common::test]
+    async fn it_withholds_a_permit_it_cannot_receipt() {
+        let subject_signer = test_signer().await;
+        let operator_signer = Ed25519Signer::import(&[1u8; 32]).await.unwrap();
+        let executor = Signer::from(Ed25519Signer::import(&[5u8; 32]).await.unwrap());
+
+        let address = Address::builder("https://s3.us-east-1.amazonaws.com")
+            .region("us-east-1")
+            .bucket("test-bucket")
+            .build()
+            .unwrap();
+        let credentials = S3Credential::new("access-key-id", "secret-access-key");
+
+        let mut args = BTreeMap::new();
+        args.insert("catalog".to_string(), Promised::String("blobs".to_string()));
+        args.insert("digest".to_string(), Promised::Bytes([0u8; 32].to_vec()));
+        let container = build_test_container(
+            &subject_signer,
+            &operator_signer,
+            vec!["archive".to_string(), "get".to_string()],
+            args,
+        )
+        .await;
+
+        let authorizer =
+            UcanAuthorizer::new(address, Some(credentials)).with_receipts(executor, Refusing);
+        match authorizer.authorize(&container).await {
+            Err(S3Error::Authorization(AuthorizeError::Unavailable { detail })) => {
+                assert!(detail.contains("the receipt store is full"), "{detail}");
+            }
+            other => panic!("expected Unavailable, got {other:?}"),
+        }
+    }
+
     #[dialog_common::test]
     async fn it_acquires_and_performs_memory_resolve() {
         let subject_signer = test_signer().await;
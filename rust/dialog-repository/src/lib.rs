@@ -35,6 +35,9 @@
 mod repository;
 pub use repository::*;
 
+mod counting;
+pub(crate) use counting::Counting;
+
 /// Streaming-merge and tombstone helpers used by the query-session
 /// composition layer.
 pub(crate) mod layer;
@@ -55,5 +58,3 @@ pub use dialog_artifacts::{Exporter, Importer};
 /// Test helpers for setting up profiles, operators, repositories, and test data.
 #[cfg(any(test, feature = "helpers"))]
 pub mod helpers;
-#[cfg(any(test, feature = "helpers"))]
-pub use helpers::*;
@@ -28,6 +28,9 @@ pub use create::*;
 mod error;
 pub use error::*;
 
+mod garbage;
+pub use garbage::*;
+
 mod load;
 pub use load::*;
 
@@ -43,6 +46,9 @@ pub use remote::*;
 mod snapshot;
 pub use snapshot::*;
 
+mod tag;
+pub use tag::*;
+
 // `Revision` and `TreeReference` moved to `dialog-capability` (the
 // light crate that owns `Did`) so engine-free clients can name them
 // without linking `dialog-query` or the storage/transport stack.
@@ -92,6 +98,14 @@ impl<C: Principal> Repository<C> {
     pub fn remote(&self, name: impl Into<String>) -> RemoteReference {
         self.subject().remote(name)
     }
+
+    /// Get a tag reference for the given name.
+    ///
+    /// Call `.create(revision)`, `.load()` or `.delete()` on the returned
+    /// reference.
+    pub fn tag(&self, name: impl Into<String>) -> TagReference {
+        self.subject().tag(name)
+    }
 }
 
 impl<C: Principal> Principal for Repository<C> {
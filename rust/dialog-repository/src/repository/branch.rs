@@ -11,7 +11,7 @@ use dialog_artifacts::DialogArtifactsError;
 use dialog_artifacts::Entity;
 use dialog_artifacts::history::Origin;
 use dialog_artifacts::history::{
-    CausalityCache, ContextCache, RevisionRecord, TreeHistory, Version, log,
+    CausalityCache, ContextCache, Retention, RevisionRecord, TreeHistory, Version, log,
 };
 use dialog_artifacts::tree::SpillCache;
 use dialog_artifacts::{Exporter, Importer};
@@ -25,18 +25,33 @@ use dialog_query::query::Application;
 use dialog_search_tree::{Buffer, Cache};
 use std::sync::{Arc, Mutex};
 
+mod as_of;
+pub use as_of::*;
+
 mod blob;
 pub use blob::*;
 
+mod cherry_pick;
+pub use cherry_pick::*;
+
 mod claims;
 pub use claims::*;
 
 mod commit;
 pub use commit::*;
 
+mod compact;
+pub use compact::*;
+
+mod conflict;
+pub use conflict::*;
+
 mod delegation;
 pub use delegation::*;
 
+mod diff;
+pub use diff::*;
+
 mod download;
 pub use download::*;
 
@@ -72,6 +87,12 @@ pub use reference::*;
 mod reset;
 pub use reset::*;
 
+mod revert;
+pub use revert::*;
+
+mod search;
+pub use search::*;
+
 mod select;
 pub use select::*;
 
@@ -81,6 +102,9 @@ pub use session::*;
 mod subscription;
 pub use subscription::*;
 
+mod set_retention;
+pub use set_retention::*;
+
 mod set_upstream;
 pub use set_upstream::*;
 
@@ -90,6 +114,11 @@ pub use transaction::*;
 mod upstream;
 pub use upstream::*;
 
+mod registry;
+pub(crate) use registry::RegistryCache;
+
+mod validate;
+
 // Either feature: `integration-tests` runs these natively, and
 // `web-integration-tests` runs the same tests as wasm subprocesses. The
 // `dialog_common::test` macro emits a variant per mode, so gating the
@@ -128,6 +157,10 @@ pub struct Branch {
     /// `None` (never induced) adopts the current head *without*
     /// retroactive firing.
     induction: Cell<Revision>,
+    /// The history [`Retention`] policy [`compact`](Branch::compact)
+    /// applies when given none. Replica-local, like the induction
+    /// watermark.
+    retention: Cell<Retention>,
     /// Shared node cache for tree reads. Created once per opened branch and
     /// carried (as a shared handle) into every `Select`'s tree, so blocks read
     /// by one query stay warm for the next instead of being re-fetched from
@@ -171,11 +204,18 @@ pub struct Branch {
     /// See [`ContextCache`].
     context_cache: ContextCache,
     /// Shared memo of verified revision records, keyed by version. A
-    /// version's record is immutable, so entries never invalidate; a
+    /// version's record is immutable, so entries never invalidate (only
+    /// [`compact`](Branch::compact) evicts the records it erases); a
     /// hit spares the tree read, the decode, and the Ed25519
     /// verification that otherwise run on every ancestry step (skip
     /// extension, context walks, causality).
     record_cache: dialog_search_tree::Cache<Version, RevisionRecord>,
+    /// Shared memo of the attribute descriptors registered at the head,
+    /// keyed by its tree root. Commit validation and the text index read
+    /// the registry through it, and a commit that leaves the registry
+    /// alone carries the entry over to the head it publishes. See
+    /// [`RegistryCache`].
+    registry_cache: RegistryCache,
     /// Carries the live buffered spine between this branch's commits (keyed
     /// by the tree root it was persisted as, so any out-of-band head change
     /// safely misses), sparing every commit the root-frame decode and
@@ -228,6 +268,12 @@ impl Branch {
         self.upstream.content().unwrap_or_default()
     }
 
+    /// Returns the history retention policy set for this branch, or `None`
+    /// if it keeps its whole history.
+    pub fn retention(&self) -> Option<Retention> {
+        self.retention.content()
+    }
+
     /// Re-resolve this handle's head and upstream from storage, updating its
     /// caches to the current versions.
     ///
@@ -245,6 +291,7 @@ impl Branch {
     {
         self.revision.resolve().perform(env).await?;
         self.upstream.resolve().perform(env).await?;
+        self.retention.resolve().perform(env).await?;
         Ok(())
     }
 
@@ -0,0 +1,602 @@
+//! Reading a branch as it stood at an earlier point in its history.
+//!
+//! [`Branch::locate`] walks the revision DAG from the head (see
+//! [`dialog_artifacts::history::log`]) to the revision current as of an
+//! [`AsOf`] — an edition, or a wall-clock instant — and
+//! [`Branch::as_of`] turns what it finds into a queryable [`Snapshot`].
+//!
+//! # Why a past state is replayed rather than reopened
+//!
+//! A head carries its tree root, but the revision records in the history
+//! region do not: a record lives inside the tree it would have to name.
+//! So nothing a replica holds maps an earlier [`Version`] back to the
+//! root it was published with. What the history region *does* hold is
+//! every claim each revision made, with the versions it superseded or
+//! withdrew, and that is enough to rebuild the facts standing at any
+//! version under the same observed-remove rules a merge applies: a claim
+//! stands at `V` when `V`'s ancestry asserted it and nothing in that
+//! ancestry withdrew it. The replay reads the records of each revision in
+//! that ancestry by its version range, not the whole history region.
+//!
+//! The rebuilt state is never written to the archive. It is held in the
+//! snapshot's [`Overlay`](crate::Overlay), over an unsigned [`Revision`]
+//! that carries the historical version and names the empty tree: queries
+//! see the facts, while nothing lands that a garbage collection would
+//! have to know to keep. So the snapshot is a view to read, not a head to
+//! reset a branch to, publish, or export.
+//!
+//! # Across a checkpoint
+//!
+//! History a [`Retention`] policy compacted into a checkpoint no longer
+//! holds the claims beneath it, but the head still does: a claim of a
+//! compacted revision that stands at the head carries that revision's
+//! version, and one withdrawn since is named by the retraction that
+//! withdrew it. A version whose ancestry takes in the whole compacted
+//! region starts its replay from those. Two things stay out of reach and
+//! fail with [`IncompleteHistory`](DialogArtifactsError::IncompleteHistory)
+//! rather than answer with a state that silently lacks claims: a version
+//! concurrent with or beneath the checkpoint, and one a later replacement
+//! of a compacted value hides (the replacement names the value it wrote,
+//! not the one it superseded). Ancestry this replica never replicated
+//! fails the same way.
+//!
+//! [`Retention`]: dialog_artifacts::history::Retention
+
+use std::collections::{HashMap, HashSet};
+
+use dialog_artifacts::history::{
+    Context, Edition, History as _, Record, RevisionRecord, TreeHistory, Version, log,
+};
+use dialog_artifacts::tree::{TreeStorageBridge, fetch_spilled};
+use dialog_artifacts::{
+    Artifact, Attribute, Changes, DialogArtifactsError, Entity, EntityKey, Instant, Key,
+    KeyViewConstruct, State, Update as _, Value,
+};
+use dialog_capability::{Did, Fork, Provider};
+use dialog_common::Blake3Hash as NodeHash;
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::{Get, Put};
+use dialog_search_tree::ContentAddressedStorage as TreeStorage;
+use futures_util::TryStreamExt as _;
+
+use crate::{
+    Branch, EMPTY_TREE_HASH, Index, NetworkedIndex, RemoteSite, RepositoryArchiveExt as _,
+    Revision, Snapshot, TreeReference,
+};
+
+/// Nanoseconds in a millisecond, the unit [`RevisionRecord::time`] is
+/// stamped in.
+const NANOS_PER_MILLISECOND: i128 = 1_000_000;
+
+/// A point in a branch's history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsOf {
+    /// The newest revision whose edition is at most this one.
+    Edition(Edition),
+    /// The newest revision its minting replica stamped at or before this
+    /// instant. Stamps come from each replica's own clock, so this is a
+    /// convenience for "last Tuesday", not an ordering: a revision with a
+    /// lagging clock can be found before a causally earlier one.
+    Time(Instant),
+}
+
+impl From<Edition> for AsOf {
+    fn from(edition: Edition) -> Self {
+        Self::Edition(edition)
+    }
+}
+
+impl From<Instant> for AsOf {
+    fn from(instant: Instant) -> Self {
+        Self::Time(instant)
+    }
+}
+
+impl AsOf {
+    /// Whether the revision at `version`, described by `record`, lies at
+    /// or before this point. A record without a time stamp never matches
+    /// a time.
+    fn admits(&self, version: &Version, record: &RevisionRecord) -> bool {
+        match self {
+            AsOf::Edition(edition) => version.edition <= *edition,
+            AsOf::Time(instant) => record.time.is_some_and(|time| {
+                i128::from(time) * NANOS_PER_MILLISECOND <= instant.unix_nanos()
+            }),
+        }
+    }
+}
+
+impl Branch {
+    /// The revision current as of `at`: the first entry of
+    /// [`log`](Self::log) — newest first — that lies at or before it.
+    /// `None` when the branch has no commits, or none that early.
+    pub async fn locate<Env>(
+        &self,
+        env: &Env,
+        at: impl Into<AsOf>,
+    ) -> Result<Option<(Version, RevisionRecord)>, DialogArtifactsError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Fork<RemoteSite, Get>>
+            + ConditionalSync
+            + 'static,
+    {
+        let at = at.into();
+        Ok(self
+            .log(env, usize::MAX)
+            .await?
+            .into_iter()
+            .find(|(version, record)| at.admits(version, record)))
+    }
+
+    /// A [`Snapshot`] of this branch as it stood as of `at`, resolved by
+    /// [`locate`](Self::locate). At the head this is just the head; an
+    /// earlier revision is replayed from the history region into the
+    /// snapshot's overlay (see the [module docs](self)). `None` when no
+    /// revision lies that early.
+    ///
+    /// Queries against the snapshot read through this branch's upstream,
+    /// like the branch's own (see [`Branch::snapshot`]).
+    pub async fn as_of<Env>(
+        &self,
+        env: &Env,
+        at: impl Into<AsOf>,
+    ) -> Result<Option<Snapshot<'static>>, DialogArtifactsError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Fork<RemoteSite, Get>>
+            + ConditionalSync
+            + 'static,
+    {
+        let Some(head) = self.revision() else {
+            return Ok(None);
+        };
+        let Some((version, record)) = self.locate(env, at).await? else {
+            return Ok(None);
+        };
+        if version == head.version() {
+            return Ok(Some(self.snapshot(head)));
+        }
+
+        let facts = self.replay(env, &version).await?;
+        let issuer = record
+            .issuer
+            .parse::<Did>()
+            .map_err(|error| DialogArtifactsError::InvalidRevision(error.to_string()))?;
+        let revision = Revision {
+            branch: record.branch,
+            issuer,
+            tree: TreeReference::from(EMPTY_TREE_HASH),
+            edition: version.edition,
+            context: None,
+            signature: Vec::new(),
+        };
+        debug_assert_eq!(revision.version(), version);
+
+        let mut changes = Changes::new();
+        for fact in facts {
+            changes.associate(fact.the, fact.of, fact.is);
+        }
+        let snapshot = self.snapshot(revision);
+        snapshot.view().overlay().assert(changes);
+        Ok(Some(snapshot))
+    }
+
+    /// The facts standing at `version`: every claim its ancestry asserted
+    /// that nothing in its ancestry withdrew.
+    async fn replay<Env>(
+        &self,
+        env: &Env,
+        version: &Version,
+    ) -> Result<Vec<Artifact>, DialogArtifactsError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Fork<RemoteSite, Get>>
+            + ConditionalSync
+            + 'static,
+    {
+        let history = self.history(env);
+        let compacted = history.compacted().await?;
+        let ancestry = log(version, &history, usize::MAX).await?;
+        let reached: HashSet<Version> = ancestry.iter().map(|(version, _)| *version).collect();
+
+        // The walk stops at compacted parents as it does at missing ones;
+        // only the former are settled history. What the ancestry takes in,
+        // compacted revisions included, must cover the whole checkpoint.
+        let mut observed = reached.clone();
+        for (_, record) in &ancestry {
+            for parent in &record.parents {
+                if reached.contains(parent) {
+                    continue;
+                }
+                if !compacted.observes(parent) {
+                    return Err(DialogArtifactsError::IncompleteHistory(parent.to_string()));
+                }
+                observed.insert(*parent);
+            }
+        }
+        let mut context = Context::new();
+        context.absorb(observed);
+        if !context.includes(&compacted) {
+            return Err(DialogArtifactsError::IncompleteHistory(format!(
+                "{version}: not after the history compacted into a checkpoint"
+            )));
+        }
+
+        // A retraction withdraws exactly the claims of its value its cause
+        // names; a replacement supersedes the claims of every *other* value
+        // on its (entity, attribute) that its cause names.
+        let mut asserted = if compacted.is_empty() {
+            Vec::new()
+        } else {
+            self.settled(env, &history, &compacted, &reached).await?
+        };
+        let mut retracted: HashSet<(Entity, Attribute, Value, Version)> = HashSet::new();
+        let mut superseded: HashMap<(Entity, Attribute), Vec<(Version, Value)>> = HashMap::new();
+        for (at, _) in &ancestry {
+            for record in history.records_at(at).await? {
+                let is_assertion = record.is_assertion();
+                let claim = match record {
+                    Record::Assert(claim) | Record::Retract(claim) => claim,
+                };
+                if is_assertion {
+                    if !claim.cause.is_genesis() {
+                        let withdrawn = superseded
+                            .entry((claim.of.clone(), claim.the.clone()))
+                            .or_default();
+                        for cause in claim.cause.versions() {
+                            withdrawn.push((*cause, claim.is.clone()));
+                        }
+                    }
+                    asserted.push((
+                        *at,
+                        Artifact {
+                            the: claim.the,
+                            of: claim.of,
+                            is: claim.is,
+                            cause: None,
+                        },
+                    ));
+                } else {
+                    for cause in claim.cause.versions() {
+                        retracted.insert((
+                            claim.of.clone(),
+                            claim.the.clone(),
+                            claim.is.clone(),
+                            *cause,
+                        ));
+                    }
+                }
+            }
+        }
+
+        let mut standing = Vec::new();
+        let mut seen = HashSet::new();
+        for (at, artifact) in asserted {
+            let key = (
+                artifact.of.clone(),
+                artifact.the.clone(),
+                artifact.is.clone(),
+            );
+            let is_retracted =
+                retracted.contains(&(key.0.clone(), key.1.clone(), key.2.clone(), at));
+            let is_superseded =
+                superseded
+                    .get(&(key.0.clone(), key.1.clone()))
+                    .is_some_and(|withdrawn| {
+                        withdrawn
+                            .iter()
+                            .any(|(cause, kept)| *cause == at && *kept != artifact.is)
+                    });
+            if !is_retracted && !is_superseded && seen.insert(key) {
+                standing.push(artifact);
+            }
+        }
+        Ok(standing)
+    }
+
+    /// The claims of `compacted` revisions that stood when they were
+    /// compacted, each paired with the version that made it: those still
+    /// standing at the head, and those a retraction outside `reached`
+    /// withdrew since. A replacement outside `reached` of a compacted
+    /// claim leaves the value it superseded nowhere to be read, so it
+    /// fails the replay.
+    ///
+    /// Reads every fact at the head, so this is paid only on a branch
+    /// that has a checkpoint.
+    async fn settled<Env>(
+        &self,
+        env: &Env,
+        history: &TreeHistory<NetworkedIndex<'_, Env>>,
+        compacted: &Context,
+        reached: &HashSet<Version>,
+    ) -> Result<Vec<(Version, Artifact)>, DialogArtifactsError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Fork<RemoteSite, Get>>
+            + ConditionalSync
+            + 'static,
+    {
+        let Some(head) = self.revision() else {
+            return Ok(Vec::new());
+        };
+        let mut settled = Vec::new();
+
+        let store = NetworkedIndex::new(env, self.archive().index(), None);
+        let tree = Index::from_hash(NodeHash::from(*head.tree.hash()));
+        let range = <EntityKey<Key> as KeyViewConstruct>::min().into_key()
+            ..=<EntityKey<Key> as KeyViewConstruct>::max().into_key();
+        let raw_store = store.clone();
+        let tree_store = TreeStorage::new(TreeStorageBridge(store));
+        let stream = tree.stream_range(range, &tree_store);
+        tokio::pin!(stream);
+        while let Some(entry) = stream.try_next().await? {
+            let State::Added(datum) = &entry.value else {
+                continue;
+            };
+            let versions: Vec<Version> = datum
+                .versions()
+                .filter(|version| compacted.observes(version))
+                .copied()
+                .collect();
+            if versions.is_empty() {
+                continue;
+            }
+            let spilled = fetch_spilled(&raw_store, &entry.key).await?;
+            let artifact = Artifact::from_key_datum_with_value(&entry.key, datum, spilled)?;
+            for version in versions {
+                settled.push((
+                    version,
+                    Artifact {
+                        cause: None,
+                        ..artifact.clone()
+                    },
+                ));
+            }
+        }
+
+        for (at, _) in log(&head.version(), history, usize::MAX).await? {
+            if reached.contains(&at) {
+                continue;
+            }
+            for record in history.records_at(&at).await? {
+                let is_assertion = record.is_assertion();
+                let claim = match record {
+                    Record::Assert(claim) | Record::Retract(claim) => claim,
+                };
+                for cause in claim.cause.versions() {
+                    if !compacted.observes(cause) {
+                        continue;
+                    }
+                    if is_assertion {
+                        return Err(DialogArtifactsError::IncompleteHistory(format!(
+                            "{cause}: its claim on {} {} was compacted into a checkpoint and replaced at {at}",
+                            claim.of, claim.the
+                        )));
+                    }
+                    settled.push((
+                        *cause,
+                        Artifact {
+                            the: claim.the.clone(),
+                            of: claim.of.clone(),
+                            is: claim.is.clone(),
+                            cause: None,
+                        },
+                    ));
+                }
+            }
+        }
+        Ok(settled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::helpers::test_repo;
+    use dialog_artifacts::history::Retention;
+    use dialog_effects::authority::Identify;
+    use dialog_effects::memory::Resolve;
+    use dialog_operator::helpers::test_operator_with_profile;
+    use dialog_query::concept::descriptor::{ConceptConclusion, ConceptDescriptor};
+    use dialog_query::concept::query::ConceptQuery;
+    use dialog_query::query::Output as _;
+    use dialog_query::rule::DeductiveRuleDescriptor;
+    use dialog_query::{DeductiveRule, Parameters, Term, the};
+
+    /// `employee`: anyone with an `org/person-name`, derived by a rule.
+    fn employee_query() -> (ConceptQuery, DeductiveRule) {
+        let predicate: ConceptDescriptor = serde_json::from_value(serde_json::json!({
+            "with": { "name": { "the": "org/employee-name", "as": "Text" } }
+        }))
+        .expect("employee descriptor parses");
+        let rule: DeductiveRuleDescriptor = serde_json::from_value(serde_json::json!({
+            "deduce": { "with": { "name": { "the": "org/employee-name", "as": "Text" } } },
+            "when": [{
+                "assert": { "with": { "name": { "the": "org/person-name", "as": "Text" } } },
+                "where": {
+                    "this": { "?": { "name": "this" } },
+                    "name": { "?": { "name": "name" } }
+                }
+            }]
+        }))
+        .expect("rule descriptor parses");
+        let mut terms = Parameters::new();
+        terms.insert("this".into(), Term::var("this"));
+        terms.insert("name".into(), Term::var("name"));
+        (
+            ConceptQuery { predicate, terms },
+            rule.compile().expect("rule compiles"),
+        )
+    }
+
+    async fn employees<Env>(snapshot: &Snapshot<'_>, env: &Env) -> anyhow::Result<Vec<String>>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Identify>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let (query, _) = employee_query();
+        let rows: Vec<ConceptConclusion> = snapshot.select(query).perform(env).try_vec().await?;
+        let mut names: Vec<String> = rows.iter().map(|row| row.entity().to_string()).collect();
+        names.sort();
+        Ok(names)
+    }
+
+    #[dialog_common::test]
+    async fn it_queries_a_branch_as_of_an_earlier_edition() -> anyhow::Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        let (_, rule) = employee_query();
+        let alice = the!("org/person-name")
+            .of("id:alice".parse::<Entity>()?)
+            .is("Alice".to_string());
+
+        branch
+            .transaction()
+            .assert(rule)
+            .assert(alice.clone())
+            .commit()
+            .perform(&operator)
+            .await?;
+        branch
+            .transaction()
+            .assert(
+                the!("org/person-name")
+                    .of("id:bob".parse::<Entity>()?)
+                    .is("Bob".to_string()),
+            )
+            .commit()
+            .perform(&operator)
+            .await?;
+        branch
+            .transaction()
+            .retract(alice)
+            .commit()
+            .perform(&operator)
+            .await?;
+
+        let head = branch.as_of(&operator, Edition::new(2)).await?.unwrap();
+        assert_eq!(Some(head.revision()), branch.revision().as_ref());
+        assert_eq!(employees(&head, &operator).await?, vec!["id:bob"]);
+
+        let before = branch.as_of(&operator, Edition::new(1)).await?.unwrap();
+        assert_eq!(before.revision().edition, Edition::new(1));
+        assert_eq!(
+            employees(&before, &operator).await?,
+            vec!["id:alice", "id:bob"],
+            "the rule and both people stood at edition 1"
+        );
+
+        let genesis = branch.as_of(&operator, Edition::GENESIS).await?.unwrap();
+        assert_eq!(employees(&genesis, &operator).await?, vec!["id:alice"]);
+
+        // The rebuilt state lives in the snapshot, not in the archive.
+        assert_eq!(*before.revision().tree.hash(), EMPTY_TREE_HASH);
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_queries_a_compacted_branch_after_its_checkpoint() -> anyhow::Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        let (_, rule) = employee_query();
+        let person = |id: &str, name: &str| -> anyhow::Result<_> {
+            Ok(the!("org/person-name")
+                .of(id.parse::<Entity>()?)
+                .is(name.to_string()))
+        };
+
+        branch
+            .transaction()
+            .assert(rule)
+            .assert(person("id:alice", "Alice")?)
+            .commit()
+            .perform(&operator)
+            .await?;
+        for (id, name) in [
+            ("id:bob", "Bob"),
+            ("id:carol", "Carol"),
+            ("id:dave", "Dave"),
+        ] {
+            branch
+                .transaction()
+                .assert(person(id, name)?)
+                .commit()
+                .perform(&operator)
+                .await?;
+        }
+        branch
+            .set_retention(Retention::Editions(2))
+            .perform(&operator)
+            .await?;
+        let compaction = branch.compact().perform(&operator).await?.unwrap();
+        assert_eq!(compaction.horizon, Edition::new(1));
+        branch
+            .transaction()
+            .retract(person("id:alice", "Alice")?)
+            .commit()
+            .perform(&operator)
+            .await?;
+
+        // The rule and Alice were compacted; the rule still stands at the
+        // head, and Alice's retraction names her.
+        let after = branch.as_of(&operator, Edition::new(2)).await?.unwrap();
+        assert_eq!(
+            employees(&after, &operator).await?,
+            vec!["id:alice", "id:bob", "id:carol"]
+        );
+        assert!(
+            branch.as_of(&operator, Edition::GENESIS).await?.is_none(),
+            "the log ends at the checkpoint"
+        );
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_locates_revisions_by_time() -> anyhow::Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        assert!(branch.locate(&operator, Edition::GENESIS).await?.is_none());
+
+        branch
+            .transaction()
+            .assert(
+                the!("org/person-name")
+                    .of("id:alice".parse::<Entity>()?)
+                    .is("Alice".to_string()),
+            )
+            .commit()
+            .perform(&operator)
+            .await?;
+        let head = branch.revision().unwrap().version();
+
+        let later = Instant::from_unix_seconds(i64::from(u32::MAX));
+        let located = branch.locate(&operator, later).await?;
+        assert_eq!(located.map(|(version, _)| version), Some(head));
+
+        assert!(
+            branch
+                .locate(&operator, Instant::UNIX_EPOCH)
+                .await?
+                .is_none(),
+            "nothing was committed before the epoch"
+        );
+        Ok(())
+    }
+}
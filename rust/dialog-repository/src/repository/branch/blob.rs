@@ -66,6 +66,7 @@
 use crate::{
     Branch, CommitError, EMPTY_TREE_HASH, Index, NetworkedIndex, RemoteFallback, RemoteSite,
     RepositoryArchiveExt as _, RepositoryMemoryExt as _, Revision, TreeReference, Upstream,
+    WriteLease,
 };
 use dialog_artifacts::history::{Context, TreeHistory, context_of, extend_skips};
 use dialog_artifacts::tree::ArtifactTreeExt as _;
@@ -406,6 +407,31 @@ where
     {
         let branch = self.archive.branch;
 
+        // Hold the archive against a garbage collection from before the
+        // bytes land until the index entry naming them is published.
+        let lease = WriteLease::acquire(branch.subject(), env).await?;
+        let written = self.write(branch, env).await;
+        let released = lease.release(env).await;
+        let entity = written?;
+        released?;
+        Ok(entity)
+    }
+
+    async fn write<Env>(&mut self, branch: &Branch, env: &Env) -> Result<Entity, CommitError>
+    where
+        Env: Provider<BlobWrite>
+            + Provider<Get>
+            + Provider<Put>
+            + Provider<Import>
+            + Provider<Resolve>
+            + Provider<Publish>
+            + Provider<Identify>
+            + Provider<Attest>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
         // 1. Stream the bytes into the local blob store. The hash is discovered
         //    as the bytes are written; the size is counted alongside. The bytes
         //    are durable once `finish` returns, before any revision points at
@@ -633,14 +659,18 @@ impl RetractBlob<'_> {
         if index_size(branch, &hash, env).await?.is_none() {
             return Ok(());
         }
-        advance_blob_index(
+        let lease = WriteLease::acquire(branch.subject(), env).await?;
+        let retracted = advance_blob_index(
             branch,
             env,
             BlobIndexEdit::Retract {
                 hash: *hash.as_bytes(),
             },
         )
-        .await
+        .await;
+        let released = lease.release(env).await;
+        retracted?;
+        released
     }
 }
 
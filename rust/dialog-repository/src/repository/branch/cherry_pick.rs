@@ -0,0 +1,221 @@
+use dialog_artifacts::Instruction;
+use dialog_artifacts::history::{History as _, Record, TreeHistory};
+use dialog_capability::{Fork, Provider};
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::prelude::ArchiveSubjectExt as _;
+use dialog_effects::archive::{Get, Import, Put};
+use dialog_effects::authority::{Attest, Identify};
+use dialog_effects::memory::{Publish, Resolve};
+use futures_util::stream;
+
+use super::revert::artifact;
+use crate::{
+    Branch, CherryPickError, NetworkedIndex, RemoteFallback, RemoteRepository, RemoteSite,
+    RepositoryArchiveExt as _, RepositoryMemoryExt as _, Revision,
+};
+
+/// Command that replays one revision's changes onto a branch's head.
+///
+/// Created by [`Branch::cherry_pick`]. Execute with `.perform(&env)`.
+///
+/// The revision can come from any branch, local or remote: its changes
+/// are the records it wrote to its own tree's history region, which is
+/// all the pick reads. Each record is replayed as the instruction that
+/// wrote it — an assertion as an assertion, a replacement as a
+/// replacement, a retraction as a retraction — and committed on top of
+/// the head as an ordinary commit.
+///
+/// Replaying the *instruction* rather than the revision's claims is what
+/// keeps the pick within the observed-remove rules (see
+/// [`dialog_artifacts::merge`]): the picked revision's causes name claims
+/// on its own lineage, which this branch may never have observed, so
+/// they are not carried over. The replayed retractions and replacements
+/// instead cover the claims standing at this branch's head — what the
+/// author of the pick observed — and a retraction of a fact the head
+/// does not hold is a no-op.
+pub struct CherryPick<'a> {
+    branch: &'a Branch,
+    revision: Revision,
+    remote: Option<RemoteRepository>,
+}
+
+impl Branch {
+    /// Create a command to replay `revision`'s changes onto this branch.
+    ///
+    /// The revision's tree is read through this branch's upstream, if it
+    /// has a remote one; chain [`CherryPick::download`] to read it through
+    /// another remote instead.
+    pub fn cherry_pick(&self, revision: &Revision) -> CherryPick<'_> {
+        CherryPick {
+            branch: self,
+            revision: revision.clone(),
+            remote: None,
+        }
+    }
+}
+
+impl CherryPick<'_> {
+    /// Hydrate read-misses on the picked revision's tree from `remote`.
+    pub fn download(mut self, remote: RemoteRepository) -> Self {
+        self.remote = Some(remote);
+        self
+    }
+
+    /// Execute the cherry-pick, returning the branch's new head.
+    pub async fn perform<Env>(self, env: &Env) -> Result<Revision, CherryPickError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Import>
+            + Provider<Resolve>
+            + Provider<Publish>
+            + Provider<Identify>
+            + Provider<Attest>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let branch = self.branch;
+        let version = self.revision.version();
+
+        let remote = match (self.remote, branch.upstreams().remote_name()) {
+            (Some(remote), _) => RemoteFallback::Remote(remote),
+            (None, Some(name)) => {
+                let loaded = branch
+                    .subject()
+                    .remote(name.to_string())
+                    .load()
+                    .perform(env)
+                    .await;
+                RemoteFallback::from_load(name, loaded)
+            }
+            (None, None) => RemoteFallback::None,
+        };
+        let store = NetworkedIndex::new(env, branch.subject().archive().index(), remote);
+        let history = TreeHistory::from_root_with_cache(
+            self.revision.tree.hash(),
+            store,
+            branch.node_cache(),
+        );
+
+        if history.revision_record(&version).await?.is_none() {
+            return Err(CherryPickError::NotReplicated {
+                version: version.to_string(),
+            });
+        }
+        let records = history.records_at(&version).await?;
+        if let Some(record) = records
+            .iter()
+            .find(|record| record.claim().the.is_reserved())
+        {
+            return Err(CherryPickError::Reserved {
+                version: version.to_string(),
+                attribute: record.claim().the.to_string(),
+            });
+        }
+
+        // Retractions go first, so one never withdraws a value the same
+        // revision asserted.
+        let (retractions, assertions): (Vec<_>, Vec<_>) = records
+            .into_iter()
+            .partition(|record| !record.is_assertion());
+        let instructions = retractions
+            .into_iter()
+            .chain(assertions)
+            .map(|record| match record {
+                Record::Retract(claim) => Instruction::Retract(artifact(claim)),
+                Record::Assert(claim) if claim.cause.is_genesis() => {
+                    Instruction::Assert(artifact(claim))
+                }
+                Record::Assert(claim) => Instruction::Replace(artifact(claim)),
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Box::pin(branch.commit(stream::iter(instructions)).perform(env)).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::helpers::test_repo;
+    use anyhow::Result;
+    use dialog_artifacts::{Artifact, ArtifactSelector, Value};
+    use dialog_operator::helpers::test_operator_with_profile;
+    use futures_util::TryStreamExt as _;
+
+    fn name(of: &str, is: &str) -> Artifact {
+        Artifact {
+            the: "user/name".parse().unwrap(),
+            of: of.parse().unwrap(),
+            is: Value::String(is.to_string()),
+            cause: None,
+        }
+    }
+
+    #[dialog_common::test]
+    async fn it_cherry_picks_a_revision_from_another_branch() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let main = repo.branch("main").open().perform(&operator).await?;
+        let feature = repo.branch("feature").open().perform(&operator).await?;
+
+        main.commit(stream::iter(vec![
+            Instruction::Assert(name("id:alice", "Alice")),
+            Instruction::Assert(name("id:bob", "Bob")),
+        ]))
+        .perform(&operator)
+        .await?;
+
+        feature
+            .commit(stream::iter(vec![
+                Instruction::Assert(name("id:alice", "Alice")),
+                Instruction::Assert(name("id:bob", "Bob")),
+            ]))
+            .perform(&operator)
+            .await?;
+        let picked = feature
+            .commit(stream::iter(vec![
+                Instruction::Replace(name("id:bob", "Robert")),
+                Instruction::Assert(name("id:carol", "Carol")),
+                Instruction::Retract(name("id:alice", "Alice")),
+            ]))
+            .perform(&operator)
+            .await?;
+
+        let head = main.cherry_pick(&picked).perform(&operator).await?;
+        assert_ne!(
+            head.version(),
+            picked.version(),
+            "the pick is a new revision"
+        );
+
+        let rows: Vec<Artifact> = main
+            .claims()
+            .select(ArtifactSelector::new().the("user/name".parse()?))
+            .to_owned()
+            .perform(&operator)
+            .await?
+            .try_collect()
+            .await?;
+        let mut names: Vec<_> = rows
+            .into_iter()
+            .map(|row| (row.of.to_string(), row.is))
+            .collect();
+        names.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            names,
+            vec![
+                ("id:bob".to_string(), Value::String("Robert".into())),
+                ("id:carol".to_string(), Value::String("Carol".into())),
+            ],
+            "the replacement and retraction cover main's own claims, which \
+             the picked revision never observed"
+        );
+        Ok(())
+    }
+}
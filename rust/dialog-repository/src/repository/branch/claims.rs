@@ -1,5 +1,5 @@
-use crate::{Branch, Select};
-use dialog_artifacts::{ArtifactSelector, selector::Constrained};
+use crate::{Branch, Search, Select};
+use dialog_artifacts::{ArtifactSelector, TextSearch, selector::Constrained};
 
 /// The branch's artifact index with remote fallback.
 ///
@@ -14,6 +14,12 @@ impl<'a> BranchClaims<'a> {
     pub fn select(self, selector: ArtifactSelector<Constrained>) -> Select<'a> {
         Select::new(self.branch, selector)
     }
+
+    /// Search a searchable attribute's values for those matching a
+    /// full-text query.
+    pub fn search(self, search: TextSearch) -> Search<'a> {
+        Search::new(self.branch, search)
+    }
 }
 
 impl Branch {
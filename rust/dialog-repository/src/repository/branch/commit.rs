@@ -1,6 +1,9 @@
+use super::search::TextTap;
+use super::validate::Checks;
 use crate::{
     Branch, CommitError, EMPTY_TREE_HASH, Index, NetworkedIndex, PublishError, RemoteFallback,
     RemoteSite, RepositoryArchiveExt as _, RepositoryMemoryExt, Revision, TreeReference,
+    WriteLease,
 };
 use dialog_artifacts::history::{Context, Edition, TreeHistory, Version, context_of, extend_skips};
 use dialog_artifacts::tree::WriteScope;
@@ -12,8 +15,9 @@ use dialog_effects::archive::prelude::CatalogExt as _;
 use dialog_effects::archive::{Get, Import, Put};
 use dialog_effects::authority::{Attest, Identify, OperatorExt};
 use dialog_effects::memory::{Publish, Resolve};
+use dialog_query::attribute::descriptor_attr;
 use dialog_search_tree::Delta;
-use futures_util::Stream;
+use futures_util::{Stream, StreamExt as _, stream};
 
 /// Command that commits a stream of changes (assert/retract) to a branch.
 ///
@@ -24,7 +28,12 @@ pub struct Commit<'a, Changes> {
     allow_empty: bool,
     canonicalize: bool,
     scope: WriteScope,
+    strict: bool,
+    /// Whether the change stream has already been checked against the
+    /// branch's descriptors.
+    checked: bool,
     entries: Vec<(Key, State<Datum>)>,
+    erasures: Vec<Key>,
 }
 
 impl<'a, Changes> Commit<'a, Changes> {
@@ -35,7 +44,10 @@ impl<'a, Changes> Commit<'a, Changes> {
             allow_empty: false,
             canonicalize: false,
             scope: WriteScope::Application,
+            strict: false,
+            checked: false,
             entries: Vec::new(),
+            erasures: Vec::new(),
         }
     }
 
@@ -57,6 +69,14 @@ impl<'a, Changes> Commit<'a, Changes> {
         self
     }
 
+    /// Erase the given keys in the same batch, ahead of the machinery
+    /// entries (history compaction replacing the records a checkpoint
+    /// summarizes). Like entries, erasures make the commit non-empty.
+    pub(crate) fn with_erasures(mut self, erasures: Vec<Key>) -> Self {
+        self.erasures = erasures;
+        self
+    }
+
     /// Flush the write buffers to the leaves before publishing, so the
     /// revision names the *canonical* tree for its fact set.
     ///
@@ -107,6 +127,31 @@ impl<'a, Changes> Commit<'a, Changes> {
         self.allow_empty = true;
         self
     }
+
+    /// Validate the change stream against the attribute descriptors
+    /// registered on the branch before writing anything.
+    ///
+    /// An attribute's descriptor is registered by asserting the
+    /// [`AttributeDescriptor`](dialog_query::AttributeDescriptor) itself
+    /// (see [`descriptor_attr`](dialog_query::attribute::descriptor_attr));
+    /// one registered in the same change stream applies to it. Every
+    /// assertion to a registered attribute must carry a value of its
+    /// content type, and a cardinality-one attribute must end up with a
+    /// single value per entity: a plain assertion next to another value —
+    /// asserted alongside it, or already standing and not retracted —
+    /// is rejected, while a replacement is not. The first violation fails
+    /// the commit with [`CommitError::SchemaViolation`]. Attributes
+    /// without a descriptor, and the `dialog.*` namespaces rules and
+    /// descriptors are stored under, are not checked.
+    ///
+    /// Uniqueness is not opt-in: a value of a
+    /// [unique](dialog_query::AttributeDescriptor::unique) attribute may
+    /// be held by one entity only, and every commit, strict or not, is
+    /// rejected for giving it to a second.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
 }
 
 impl Branch {
@@ -142,7 +187,46 @@ where
             + 'static,
     {
         let branch = self.branch;
-        let changes = self.changes;
+        // Every commit is held to the unique attributes registered on the
+        // branch; a strict one to everything its descriptors say. The
+        // batch below buffers the whole change stream until it seals, so
+        // collecting it first costs no more than the commit already does.
+        if !self.checked {
+            let instructions: Vec<Instruction> = self.changes.collect().await;
+            let checks = if self.strict {
+                Checks::All
+            } else {
+                Checks::Unique
+            };
+            branch.validate(env, &instructions, checks).await?;
+            let checked = Commit {
+                changes: stream::iter(instructions),
+                strict: self.strict,
+                checked: true,
+                branch,
+                allow_empty: self.allow_empty,
+                canonicalize: self.canonicalize,
+                scope: self.scope,
+                entries: self.entries,
+                erasures: self.erasures,
+            };
+            // Hold the archive against a garbage collection from the first
+            // block written until the revision naming it is published.
+            let lease = Box::pin(WriteLease::acquire(branch.subject(), env)).await?;
+            let committed = Box::pin(checked.perform(env)).await;
+            let released = Box::pin(lease.release(env)).await;
+            let revision = committed?;
+            released?;
+            return Ok(revision);
+        }
+        // Keep the instructions the text index may need to follow as the
+        // batch drains them; the index edits are derived once the batch is
+        // known to change anything.
+        let tap = TextTap::default();
+        let changes = self.changes.inspect({
+            let tap = tap.clone();
+            move |instruction| tap.observe(instruction)
+        });
         // Checkpoint the head: capture the version we build this commit on top
         // of, so the publish below CAS's against it. A concurrent commit or
         // pull that advances the head while we apply changes then makes this
@@ -241,8 +325,8 @@ where
         )
         .await?;
         // Machinery entries count as changes: a commit carrying only a
-        // blob-index edit still advances the head.
-        let changed = batch.changed() || !self.entries.is_empty();
+        // blob-index edit (or a compaction) still advances the head.
+        let changed = batch.changed() || !self.entries.is_empty() || !self.erasures.is_empty();
 
         // A batch that left the indexes untouched (e.g. a transaction
         // re-asserting metadata that is already in place) is a no-op:
@@ -275,6 +359,18 @@ where
             return Ok(base);
         }
 
+        // Text-index postings follow the data, so they never make a commit
+        // non-empty on their own.
+        let observed = tap.take();
+        let registry = descriptor_attr();
+        let registers = observed.iter().any(|instruction| {
+            let (Instruction::Assert(artifact)
+            | Instruction::Replace(artifact)
+            | Instruction::Retract(artifact)) = instruction;
+            artifact.the == registry
+        });
+        let (postings, unindexed) = branch.index_text(env, observed).await?;
+
         // Mint the revision (the placeholder tree root is replaced below,
         // after its own records are in the tree) and record its DAG edge on
         // the branch lineage entity, its skip links, plus its attribute
@@ -347,10 +443,15 @@ where
         // they ride the same buffered write as the data, so the record costs
         // a buffer append instead of a second canonical spine-to-leaf edit.
         let entries = record.entries(batch.manifest())?;
-        // The caller's machinery entries (blob-index edits) ride the same
-        // batch as the revision record, so one seal covers data, record,
-        // and entries together.
-        let batch = batch.record(&store, self.entries).await?;
+        // The caller's machinery entries (blob-index edits) and the text
+        // index's postings ride the same batch as the revision record, so
+        // one seal covers data, record, and entries together.
+        let mut erasures = self.erasures;
+        erasures.extend(unindexed);
+        let mut machinery = self.entries;
+        machinery.extend(postings);
+        let batch = batch.erase(&store, erasures).await?;
+        let batch = batch.record(&store, machinery).await?;
         let batch = batch.record(&store, entries).await?;
         // Seed the verified-record memo with what we just minted. The next
         // commit's skip-table walk starts at this very record, so without this
@@ -388,6 +489,14 @@ where
 
         head.publish(revision.clone(), env).await?;
 
+        // Carry the registry memo over to the new head, unless this commit
+        // changed what it holds.
+        branch.registries().advance(
+            &TreeReference::from(base_tree_hash),
+            revision.tree.clone(),
+            registers,
+        );
+
         // Advance the branch memo so later pulls through this handle
         // answer the context from memory.
         contexts.insert(revision.version(), context);
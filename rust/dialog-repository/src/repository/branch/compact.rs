@@ -0,0 +1,288 @@
+use dialog_artifacts::history::{
+    CHECKPOINT_FORMAT, Checkpoint, Edition, History as _, Retention, log,
+};
+use dialog_capability::{Fork, Provider};
+use dialog_common::ConditionalSync;
+use dialog_common::time::{UNIX_EPOCH, now};
+use dialog_effects::archive::{Get, Import, Put};
+use dialog_effects::authority::{Attest, Identify, OperatorExt as _};
+use dialog_effects::memory::{Publish, Resolve};
+use futures_util::stream;
+
+use crate::{Branch, CompactError, RemoteSite, Revision};
+
+/// Command that compacts a branch's history under a [`Retention`] policy.
+///
+/// Created by [`Branch::compact`]. Execute with `.perform(&env)`.
+///
+/// Every revision in the head's ancestry at or below the policy's horizon
+/// has its revision record, claim records, and coverage entries erased,
+/// and a signed [`Checkpoint`] summarizing their causal context takes
+/// their place (folding in, and replacing, any checkpoint an earlier
+/// compaction left). The erasure and the checkpoint land as one commit,
+/// so the compaction is itself a revision that syncs like any other: a
+/// replica pulling it drops the same records and adopts the checkpoint.
+///
+/// Causality checks, context derivation, and the merge's observed-remove
+/// screen keep working across the erased region by consulting the
+/// checkpoint (see [`History::compacted`](dialog_artifacts::history::History::compacted)).
+/// What compaction gives up is the fine ordering *inside* the region —
+/// [`log`](Branch::log) ends at the horizon — and the blocks it frees are
+/// reclaimed by the next [`collect_garbage`](crate::Repository::collect_garbage).
+pub struct Compact<'a> {
+    branch: &'a Branch,
+    retention: Option<Retention>,
+}
+
+/// What a [`Compact`] did.
+#[derive(Debug, Clone)]
+pub struct Compaction {
+    /// The highest edition compacted
+    pub horizon: Edition,
+    /// How many revisions were compacted
+    pub revisions: usize,
+    /// The revision that recorded the compaction, now the branch head
+    pub revision: Revision,
+}
+
+impl Branch {
+    /// Create a command to compact this branch's history, by the branch's
+    /// own [`retention`](Branch::retention) policy unless the command is
+    /// given one with [`Compact::retain`].
+    pub fn compact(&self) -> Compact<'_> {
+        Compact {
+            branch: self,
+            retention: None,
+        }
+    }
+}
+
+impl Compact<'_> {
+    /// Compact by `retention` instead of the branch's own policy.
+    pub fn retain(mut self, retention: Retention) -> Self {
+        self.retention = Some(retention);
+        self
+    }
+
+    /// Execute the compaction, returning what was compacted, or `None`
+    /// when the policy keeps everything the branch holds.
+    pub async fn perform<Env>(self, env: &Env) -> Result<Option<Compaction>, CompactError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Import>
+            + Provider<Resolve>
+            + Provider<Publish>
+            + Provider<Identify>
+            + Provider<Attest>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let branch = self.branch;
+        let retention = self
+            .retention
+            .or_else(|| branch.retention())
+            .ok_or_else(|| CompactError::NoRetention {
+                branch: branch.name().to_string(),
+            })?;
+        let Some(head) = branch.revision() else {
+            return Ok(None);
+        };
+
+        let history = branch.history(env);
+        let entries = log(&head.version(), &history, usize::MAX).await?;
+        let time = now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+        let Some(horizon) = retention.horizon(&head.version(), &entries, time) else {
+            return Ok(None);
+        };
+        let compacted: Vec<_> = entries
+            .into_iter()
+            .map(|(version, _)| version)
+            .filter(|version| version.edition <= horizon)
+            .collect();
+
+        let mut erasures = Vec::new();
+        for version in &compacted {
+            erasures.extend(history.revision_keys(version).await?);
+        }
+
+        // The new checkpoint replaces every earlier one: it carries their
+        // context forward, and their entries are erased with the records.
+        let manifest = history.manifest().await?;
+        let mut context = history.compacted().await?;
+        let mut highest = horizon;
+        for earlier in history.checkpoints().await? {
+            highest = highest.max(earlier.horizon);
+            erasures.extend(earlier.entries(&manifest)?.into_iter().map(|(key, _)| key));
+        }
+        context.absorb(compacted.iter().copied());
+
+        let authority = Identify.perform(env).await?;
+        let mut checkpoint = Checkpoint {
+            format: CHECKPOINT_FORMAT,
+            branch: head.branch.clone(),
+            issuer: authority.did().to_string(),
+            authority: authority.profile().to_string(),
+            horizon: highest,
+            context,
+            signature: Vec::new(),
+        };
+        checkpoint.signature = Attest::new(checkpoint.payload()?).perform(env).await?;
+
+        let revision = Box::pin(
+            branch
+                .commit(stream::empty())
+                .with_erasures(erasures)
+                .with_entries(checkpoint.entries(&manifest)?)
+                .perform(env),
+        )
+        .await?;
+
+        // The branch memo would keep serving the erased records to this
+        // handle's walks; evict them so the log ends at the horizon here
+        // just as it does for a freshly opened handle.
+        for version in &compacted {
+            branch.records().remove(version);
+        }
+
+        Ok(Some(Compaction {
+            horizon: highest,
+            revisions: compacted.len(),
+            revision,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use anyhow::Result;
+    use dialog_artifacts::history::{
+        Causality, Cause, Claim, Edition, History as _, Retention, causality, context_of,
+    };
+    use dialog_artifacts::{Artifact, Instruction, Value};
+    use futures_util::stream;
+
+    use crate::CompactError;
+    use crate::helpers::test_repo;
+    use dialog_operator::helpers::test_operator_with_profile;
+
+    fn name(of: &str, value: &str) -> Result<Instruction> {
+        Ok(Instruction::Replace(Artifact {
+            the: "person/name".parse()?,
+            of: of.parse()?,
+            is: Value::String(value.to_string()),
+            cause: None,
+        }))
+    }
+
+    #[dialog_common::test]
+    async fn it_requires_a_retention_policy() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let result = branch.compact().perform(&operator).await;
+        assert!(matches!(result, Err(CompactError::NoRetention { .. })));
+        Ok(())
+    }
+
+    /// Compaction erases the old records, truncates the log at the
+    /// horizon, and leaves the history usable: the head's context is
+    /// unchanged, a later write still supersedes a claim whose record was
+    /// compacted, and a second compaction folds in the first checkpoint.
+    #[dialog_common::test]
+    async fn it_compacts_history_into_a_checkpoint() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        let alice = "did:key:z6MkQmQKzPsjyUz49pvaxYdiiZEuQXyNqeBkS88GTrvqnov";
+
+        let first = branch
+            .commit(stream::iter(vec![name(alice, "Alicia")?]))
+            .perform(&operator)
+            .await?;
+        for value in ["Ali", "Al", "Alice"] {
+            branch
+                .commit(stream::iter(vec![name(alice, value)?]))
+                .perform(&operator)
+                .await?;
+        }
+        let head = branch.revision().expect("committed");
+        let context = branch
+            .contexts()
+            .context_of(&head.version(), &branch.history(&operator))
+            .await?;
+        assert_eq!(branch.log(&operator, usize::MAX).await?.len(), 4);
+
+        branch
+            .set_retention(Retention::Editions(2))
+            .perform(&operator)
+            .await?;
+        let compaction = branch
+            .compact()
+            .perform(&operator)
+            .await?
+            .expect("two editions lie beyond the retained window");
+        assert_eq!(compaction.horizon, Edition::new(1));
+        assert_eq!(compaction.revisions, 2);
+
+        // The log ends at the horizon: the compaction's own revision and
+        // the two retained ones.
+        let history = branch.history(&operator);
+        let log = branch.log(&operator, usize::MAX).await?;
+        assert_eq!(log.len(), 3);
+        assert!(history.revision_record(&first.version()).await?.is_none());
+        assert!(history.compacted().await?.observes(&first.version()));
+
+        // The walked context still covers the whole ancestry.
+        let walked = context_of(&head.version(), &history).await?;
+        assert_eq!(walked, context);
+
+        // A claim made after compaction supersedes the compacted original.
+        let later = branch
+            .commit(stream::iter(vec![name(alice, "Alice B.")?]))
+            .perform(&operator)
+            .await?;
+        let history = branch.history(&operator);
+        let the = "person/name".parse()?;
+        let of = alice.parse()?;
+        let newest = history.claims_at(&later.version(), &of, &the).await?;
+        let oldest = Claim {
+            the,
+            of,
+            is: Value::String("Alicia".to_string()),
+            cause: Cause::genesis(),
+        };
+        assert_eq!(
+            causality(
+                (&newest[0], &later.version()),
+                (&oldest, &first.version()),
+                &history
+            )
+            .await?,
+            Causality::Supersedes
+        );
+
+        // Compacting again replaces the first checkpoint.
+        let again = branch
+            .compact()
+            .perform(&operator)
+            .await?
+            .expect("the first compaction's neighbours have aged out");
+        assert!(again.horizon > compaction.horizon);
+        let history = branch.history(&operator);
+        assert_eq!(history.checkpoints().await?.len(), 1);
+        assert!(history.compacted().await?.observes(&first.version()));
+
+        Ok(())
+    }
+}
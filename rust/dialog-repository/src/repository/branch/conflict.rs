@@ -0,0 +1,619 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::mem;
+use std::sync::Arc;
+
+use dialog_artifacts::history::{Causality, Claim, History, TreeHistory, Version, causality};
+use dialog_artifacts::{
+    Artifact, ArtifactChange, ArtifactSelector, Attribute, Entity, Instruction, Value,
+    artifact_changes,
+};
+use dialog_capability::{Fork, Provider};
+use dialog_common::Blake3Hash as NodeHash;
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::prelude::ArchiveSubjectExt as _;
+use dialog_effects::archive::{Get, Import, Put};
+use dialog_effects::authority::{Attest, Identify};
+use dialog_effects::blob::{Import as BlobImport, Read as BlobRead};
+use dialog_effects::memory::{Publish, Resolve};
+use futures_util::{StreamExt as _, stream};
+
+use crate::{
+    Branch, Index, NetworkedIndex, Pull, PullError, RemoteFallback, RemoteSite,
+    RepositoryArchiveExt as _, RepositoryMemoryExt as _, Revision, TreeReference, Upstream,
+};
+
+/// A custom resolver: given a conflict, the values that should stand.
+#[cfg(not(target_arch = "wasm32"))]
+pub type Resolver = Arc<dyn Fn(&Conflict) -> Vec<Value> + Send + Sync>;
+
+/// A custom resolver: given a conflict, the values that should stand.
+#[cfg(target_arch = "wasm32")]
+pub type Resolver = Arc<dyn Fn(&Conflict) -> Vec<Value>>;
+
+/// How a pull settles the concurrent values of one attribute.
+///
+/// A merge never drops a side on its own: when two replicas write
+/// different values to the same `(entity, attribute)` without seeing each
+/// other, both stand afterwards (see [`Conflict`]). A resolution narrows
+/// them down, committed on top of the merge as an ordinary commit — so
+/// the values it drops are retracted by claims that observed them, and
+/// every replica that pulls the resolution converges on it.
+#[derive(Clone, Default)]
+pub enum Resolution {
+    /// Leave every concurrent value standing (multi-value). The default.
+    #[default]
+    KeepAll,
+    /// Keep the value of the latest claim: the highest edition, ties
+    /// broken by origin (see [`Conflict::latest`]).
+    LastWriterWins,
+    /// Keep whatever the resolver returns. Values it returns that are not
+    /// among the contenders are asserted, so a resolver can merge the
+    /// contenders into a new value; returning nothing retracts them all.
+    Custom(Resolver),
+}
+
+impl Resolution {
+    /// A [`Resolution::Custom`] running `resolver`.
+    pub fn custom(resolver: impl Fn(&Conflict) -> Vec<Value> + ConditionalSync + 'static) -> Self {
+        Resolution::Custom(Arc::new(resolver))
+    }
+
+    fn keeps_all(&self) -> bool {
+        matches!(self, Resolution::KeepAll)
+    }
+
+    fn apply(&self, conflict: &Conflict) -> Vec<Value> {
+        match self {
+            Resolution::KeepAll => conflict.kept.clone(),
+            Resolution::LastWriterWins => conflict
+                .latest()
+                .map(|contender| vec![contender.is.clone()])
+                .unwrap_or_default(),
+            Resolution::Custom(resolver) => resolver(conflict),
+        }
+    }
+}
+
+impl fmt::Debug for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Resolution::KeepAll => f.write_str("KeepAll"),
+            Resolution::LastWriterWins => f.write_str("LastWriterWins"),
+            Resolution::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// One value standing in a conflicted `(entity, attribute)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contender {
+    /// The value.
+    pub is: Value,
+    /// The versions whose claims keep the value standing, ascending.
+    pub versions: Vec<Version>,
+    /// Whether the value came in with the pull, rather than standing
+    /// locally before it.
+    pub incoming: bool,
+}
+
+/// Concurrent values a pull found standing in one `(entity, attribute)`.
+///
+/// A conflict is reported when the merge leaves more than one value
+/// standing and at least one incoming value is concurrent — by the
+/// recorded lineage, see [`causality`] — with one that stood locally.
+/// Values one side wrote after seeing the other's are ordered, not
+/// conflicting, and are not reported: a cardinality-many attribute that
+/// both sides added to in turn has nothing to resolve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// The entity.
+    pub of: Entity,
+    /// The attribute.
+    pub the: Attribute,
+    /// Every value standing after the merge.
+    pub contenders: Vec<Contender>,
+    /// The values standing once the attribute's [`Resolution`] ran. A
+    /// resolver sees every contender's value here.
+    pub kept: Vec<Value>,
+}
+
+impl Conflict {
+    /// The contender whose latest claim has the highest version — edition
+    /// first, then origin, so every replica picks the same one.
+    pub fn latest(&self) -> Option<&Contender> {
+        self.contenders
+            .iter()
+            .max_by_key(|contender| contender.versions.last())
+    }
+
+    /// Whether the resolution dropped or added a value.
+    pub fn is_resolved(&self) -> bool {
+        self.kept.len() != self.contenders.len()
+            || self
+                .kept
+                .iter()
+                .any(|value| !self.contenders.iter().any(|c| &c.is == value))
+    }
+
+    /// The instructions that take the contenders to the kept values.
+    fn instructions(&self) -> Vec<Instruction> {
+        let fact = |is: &Value| Artifact {
+            the: self.the.clone(),
+            of: self.of.clone(),
+            is: is.clone(),
+            cause: None,
+        };
+        if let [value] = self.kept.as_slice() {
+            return vec![Instruction::Replace(fact(value))];
+        }
+        let retractions = self
+            .contenders
+            .iter()
+            .filter(|contender| !self.kept.contains(&contender.is))
+            .map(|contender| Instruction::Retract(fact(&contender.is)));
+        let assertions = self
+            .kept
+            .iter()
+            .filter(|value| !self.contenders.iter().any(|c| &c.is == *value))
+            .map(|value| Instruction::Assert(fact(value)));
+        retractions.chain(assertions).collect()
+    }
+}
+
+/// What a pull did, as returned by [`Pull::report`].
+#[derive(Debug, Clone, Default)]
+pub struct PullReport {
+    /// The branch head after the pull and any resolution commit, or `None`
+    /// when there was nothing to pull.
+    pub revision: Option<Revision>,
+    /// The conflicts the merge surfaced, each with what its attribute's
+    /// resolution kept.
+    pub conflicts: Vec<Conflict>,
+}
+
+impl<'a> Pull<'a> {
+    /// Settle concurrent values of `the` by `resolution` after the merge.
+    ///
+    /// Attributes without a resolution keep every concurrent value
+    /// ([`Resolution::KeepAll`]). Resolutions run on
+    /// [`perform`](Self::perform) and [`report`](Self::report); the
+    /// two-phase [`prepare`](Self::prepare) path lands the merge alone.
+    pub fn resolve(mut self, the: Attribute, resolution: Resolution) -> Self {
+        self.resolutions.insert(the, resolution);
+        self
+    }
+
+    /// Execute the pull like [`perform`](Self::perform), and report the
+    /// concurrent conflicts the merge surfaced and how each was resolved.
+    pub async fn report<Env>(self, env: &Env) -> Result<PullReport, PullError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Import>
+            + Provider<Resolve>
+            + Provider<Publish>
+            + Provider<Identify>
+            + Provider<Attest>
+            + Provider<BlobRead>
+            + Provider<BlobImport>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + Provider<Fork<RemoteSite, BlobRead>>
+            + ConditionalSync
+            + 'static,
+    {
+        Box::pin(self.settle(env, true)).await
+    }
+
+    /// Pull, then look for conflicts when asked to report them or when a
+    /// resolution may have something to do, and commit the resolutions.
+    pub(super) async fn settle<Env>(
+        mut self,
+        env: &Env,
+        report: bool,
+    ) -> Result<PullReport, PullError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Import>
+            + Provider<Resolve>
+            + Provider<Publish>
+            + Provider<Identify>
+            + Provider<Attest>
+            + Provider<BlobRead>
+            + Provider<BlobImport>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + Provider<Fork<RemoteSite, BlobRead>>
+            + ConditionalSync
+            + 'static,
+    {
+        let branch = self.branch();
+        let resolutions = mem::take(&mut self.resolutions);
+        let inspect = report || resolutions.values().any(|policy| !policy.keeps_all());
+
+        let prior = branch.revision();
+        let prepared = Box::pin(self.prepare(env)).await?;
+        let merge = prepared
+            .merge()
+            .map(|(base, sync)| (base.clone(), sync.clone()));
+        let revision = prepared.commit(env).await?;
+
+        // Only a merge revision can hold values neither side has seen
+        // both of: an adopted upstream head or a kept local one already
+        // settled whatever it carries.
+        let (Some(head), Some((base, sync)), true) = (&revision, merge, inspect) else {
+            return Ok(PullReport {
+                revision,
+                conflicts: Vec::new(),
+            });
+        };
+        if &head.tree == sync.tree() || prior.as_ref() == Some(head) {
+            return Ok(PullReport {
+                revision,
+                conflicts: Vec::new(),
+            });
+        }
+
+        let mut conflicts = Box::pin(branch.conflicts(env, head, &base, &sync)).await?;
+        let mut instructions = Vec::new();
+        for conflict in &mut conflicts {
+            let resolution = resolutions.get(&conflict.the).cloned().unwrap_or_default();
+            conflict.kept = resolution.apply(conflict);
+            if conflict.is_resolved() {
+                instructions.extend(conflict.instructions());
+            }
+        }
+
+        let revision = if instructions.is_empty() {
+            revision
+        } else {
+            Some(Box::pin(branch.commit(stream::iter(instructions)).perform(env)).await?)
+        };
+        Ok(PullReport {
+            revision,
+            conflicts,
+        })
+    }
+}
+
+impl Branch {
+    /// The conflicts standing at the merge revision `head`: the
+    /// `(entity, attribute)`s the upstream changed since the sync `base`
+    /// whose standing values include an incoming one concurrent with a
+    /// local one.
+    async fn conflicts<Env>(
+        &self,
+        env: &Env,
+        head: &Revision,
+        base: &TreeReference,
+        sync: &Upstream,
+    ) -> Result<Vec<Conflict>, PullError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        // The merged tree references the upstream's blocks by hash; read
+        // through the remote the pull came from when it was one.
+        let remote = match sync {
+            Upstream::Remote { remote: name, .. } => {
+                let loaded = self
+                    .subject()
+                    .remote(name.clone())
+                    .load()
+                    .perform(env)
+                    .await;
+                RemoteFallback::from_load(name, loaded)
+            }
+            Upstream::Local { .. } => RemoteFallback::None,
+        };
+        let store = NetworkedIndex::new(env, self.subject().archive().index(), remote);
+
+        // The values the upstream wrote since the sync base, by slot. The
+        // walk is entity-then-attribute ordered, so a slot's changes are
+        // adjacent.
+        let mut incoming: Vec<(Entity, Attribute, Vec<Value>)> = Vec::new();
+        {
+            let changes = artifact_changes(
+                Index::from_hash_with_cache(NodeHash::from(*base.hash()), self.node_cache()),
+                Index::from_hash_with_cache(NodeHash::from(*sync.tree().hash()), self.node_cache()),
+                store.clone(),
+                None,
+            );
+            futures_util::pin_mut!(changes);
+            while let Some(change) = changes.next().await {
+                let written = match change? {
+                    ArtifactChange::Asserted(artifact) => artifact,
+                    ArtifactChange::Replaced { to, .. } => to,
+                    ArtifactChange::Retracted(_) => continue,
+                };
+                if written.the.is_reserved() {
+                    continue;
+                }
+                match incoming.last_mut() {
+                    Some((of, the, values)) if *of == written.of && *the == written.the => {
+                        values.push(written.is);
+                    }
+                    _ => incoming.push((written.of, written.the, vec![written.is])),
+                }
+            }
+        }
+
+        let history = TreeHistory::from_root_with_cache(head.tree.hash(), store, self.node_cache());
+        let mut conflicts = Vec::new();
+        for (of, the, values) in incoming {
+            let mut contenders = Vec::new();
+            {
+                let selector = ArtifactSelector::new().of(of.clone()).the(the.clone());
+                let rows = self.claims().select(selector).perform(env).await?;
+                futures_util::pin_mut!(rows);
+                while let Some(row) = rows.next().await.transpose()? {
+                    let is = row.value()?;
+                    let mut versions: Vec<Version> = row
+                        .datum()
+                        .map(|datum| datum.versions().copied().collect())
+                        .unwrap_or_default();
+                    versions.sort();
+                    contenders.push(Contender {
+                        incoming: values.contains(&is),
+                        is,
+                        versions,
+                    });
+                }
+            }
+            if contenders.len() < 2 {
+                continue;
+            }
+
+            let mut claims = HashMap::new();
+            for (index, contender) in contenders.iter().enumerate() {
+                if let Some(claim) = latest_claim(&history, &of, &the, contender).await? {
+                    claims.insert(index, claim);
+                }
+            }
+            let mut concurrent = false;
+            'pairs: for (ours, local) in contenders.iter().enumerate() {
+                if local.incoming {
+                    continue;
+                }
+                for (theirs, upstream) in contenders.iter().enumerate() {
+                    if !upstream.incoming {
+                        continue;
+                    }
+                    let (Some((a, a_version)), Some((b, b_version))) =
+                        (claims.get(&ours), claims.get(&theirs))
+                    else {
+                        continue;
+                    };
+                    if causality((a, a_version), (b, b_version), &history).await?
+                        == Causality::Concurrent
+                    {
+                        concurrent = true;
+                        break 'pairs;
+                    }
+                }
+            }
+            if concurrent {
+                conflicts.push(Conflict {
+                    kept: contenders.iter().map(|c| c.is.clone()).collect(),
+                    of,
+                    the,
+                    contenders,
+                });
+            }
+        }
+        Ok(conflicts)
+    }
+}
+
+/// The most recent recorded claim keeping `contender` standing, with the
+/// version that wrote it; `None` when none of its versions' claims are
+/// recorded (compacted away).
+async fn latest_claim<H: History>(
+    history: &H,
+    of: &Entity,
+    the: &Attribute,
+    contender: &Contender,
+) -> Result<Option<(Claim, Version)>, PullError> {
+    for version in contender.versions.iter().rev() {
+        let claim = history
+            .claims_at(version, of, the)
+            .await?
+            .into_iter()
+            .find(|claim| claim.is == contender.is);
+        if let Some(claim) = claim {
+            return Ok(Some((claim, *version)));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::Repository;
+    use crate::helpers::test_repo;
+    use anyhow::Result;
+    use dialog_operator::helpers::test_operator_with_profile;
+    use futures_util::TryStreamExt as _;
+
+    fn title(is: &str) -> Artifact {
+        Artifact {
+            the: "post/title".parse().unwrap(),
+            of: "post:1".parse().unwrap(),
+            is: Value::String(is.to_string()),
+            cause: None,
+        }
+    }
+
+    fn text(value: &Value) -> String {
+        match value {
+            Value::String(text) => text.clone(),
+            other => other.to_utf8(),
+        }
+    }
+
+    /// `main` and `feature` share title "Base", then each replaces it
+    /// without seeing the other.
+    async fn diverged<Env>(repo: &Repository, env: &Env) -> Result<(Branch, Branch)>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Import>
+            + Provider<Resolve>
+            + Provider<Publish>
+            + Provider<Identify>
+            + Provider<Attest>
+            + Provider<BlobRead>
+            + Provider<BlobImport>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + Provider<Fork<RemoteSite, BlobRead>>
+            + ConditionalSync
+            + 'static,
+    {
+        let main = repo.branch("main").open().perform(env).await?;
+        main.commit(stream::iter(vec![Instruction::Assert(title("Base"))]))
+            .perform(env)
+            .await?;
+        let feature = repo.branch("feature").open().perform(env).await?;
+        feature.set_upstream(&main).perform(env).await?;
+        feature.pull().perform(env).await?;
+
+        main.commit(stream::iter(vec![Instruction::Replace(title("MainSide"))]))
+            .perform(env)
+            .await?;
+        feature
+            .commit(stream::iter(vec![Instruction::Replace(title(
+                "FeatureSide",
+            ))]))
+            .perform(env)
+            .await?;
+        Ok((main, feature))
+    }
+
+    /// Every title standing at the branch head, sorted.
+    async fn titles<Env>(branch: &Branch, env: &Env) -> Result<Vec<Value>>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let rows: Vec<Artifact> = branch
+            .claims()
+            .select(ArtifactSelector::new().the("post/title".parse()?))
+            .to_owned()
+            .perform(env)
+            .await?
+            .try_collect()
+            .await?;
+        let mut values: Vec<_> = rows.into_iter().map(|row| row.is).collect();
+        values.sort_by_key(|value| value.to_utf8());
+        Ok(values)
+    }
+
+    #[dialog_common::test]
+    async fn it_reports_concurrent_values_and_keeps_them_by_default() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let (_, feature) = diverged(&repo, &operator).await?;
+
+        let report = feature.pull().report(&operator).await?;
+        assert_eq!(report.revision, feature.revision());
+        let [conflict] = report.conflicts.as_slice() else {
+            panic!("one conflict expected: {:?}", report.conflicts);
+        };
+        assert_eq!(conflict.of, "post:1".parse::<Entity>()?);
+        assert_eq!(conflict.the, "post/title".parse::<Attribute>()?);
+        let mut contenders: Vec<_> = conflict
+            .contenders
+            .iter()
+            .map(|contender| (text(&contender.is), contender.incoming))
+            .collect();
+        contenders.sort();
+        assert_eq!(
+            contenders,
+            vec![
+                ("FeatureSide".to_string(), false),
+                ("MainSide".to_string(), true)
+            ]
+        );
+        assert!(!conflict.is_resolved());
+        assert_eq!(
+            titles(&feature, &operator).await?,
+            vec![
+                Value::String("FeatureSide".into()),
+                Value::String("MainSide".into())
+            ]
+        );
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_settles_concurrent_values_by_last_writer_wins() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let (main, feature) = diverged(&repo, &operator).await?;
+
+        let report = feature
+            .pull()
+            .resolve("post/title".parse()?, Resolution::LastWriterWins)
+            .report(&operator)
+            .await?;
+        let [conflict] = report.conflicts.as_slice() else {
+            panic!("one conflict expected: {:?}", report.conflicts);
+        };
+        let winner = conflict.latest().expect("contenders").is.clone();
+        assert_eq!(conflict.kept, vec![winner.clone()]);
+        assert!(conflict.is_resolved());
+        assert_eq!(report.revision, feature.revision());
+        assert_eq!(titles(&feature, &operator).await?, vec![winner.clone()]);
+
+        // The resolution observed both values, so the other side adopts
+        // it without a conflict of its own.
+        let report = main.pull().from(&feature).report(&operator).await?;
+        assert!(report.conflicts.is_empty(), "{:?}", report.conflicts);
+        assert_eq!(titles(&main, &operator).await?, vec![winner]);
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_settles_concurrent_values_with_a_custom_resolver() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let (_, feature) = diverged(&repo, &operator).await?;
+
+        let joined = Resolution::custom(|conflict| {
+            let mut values: Vec<_> = conflict
+                .contenders
+                .iter()
+                .map(|contender| text(&contender.is))
+                .collect();
+            values.sort();
+            vec![Value::String(values.join(" / "))]
+        });
+        feature
+            .pull()
+            .resolve("post/title".parse()?, joined)
+            .perform(&operator)
+            .await?;
+        assert_eq!(
+            titles(&feature, &operator).await?,
+            vec![Value::String("FeatureSide / MainSide".into())]
+        );
+        Ok(())
+    }
+}
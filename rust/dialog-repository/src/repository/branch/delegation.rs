@@ -19,6 +19,15 @@
 //! reference tombstoned in one commit; the bytes stay in the blob store
 //! (reclaiming unreferenced bytes is the deferred GC concern).
 //!
+//! Signed revocations are recorded the same way, beside the delegations
+//! they withdraw (see [`Delegations::revoke`]), and read back by a
+//! [`RetainedRevocations`] checker that chain verification consults.
+//! So are the receipts of executed invocations (see [`Delegations::record`]),
+//! which promised arguments resolve against ([`Delegations::resolve`]).
+//! So are key successions (see [`Delegations::succeed`]), read back by a
+//! [`RetainedSuccessions`] checker and acted on by
+//! [`Delegations::migrate`].
+//!
 //! The surface is a [`Delegations`] handle on the branch:
 //!
 //! ```no_run
@@ -58,7 +67,13 @@
 //! ```
 
 mod prove;
+mod receipt;
+mod revoke;
+mod succession;
 pub use prove::*;
+pub use receipt::*;
+pub use revoke::*;
+pub use succession::*;
 
 use crate::repository::branch::blob::index_store;
 use crate::{Branch, CommitError, Index, RemoteSite};
@@ -411,7 +426,19 @@ mod tests {
         UcanDelegation::new(DelegationChain::new(delegation))
     }
 
-    async fn open_branch(name: &str) -> Result<(crate::Branch, Operator<VolatileSpace>)> {
+    /// Open a `main` branch in a fresh volatile repository, alongside the
+    /// operator that opened it. Shared by the delegation submodule tests.
+    pub(super) async fn open_branch(
+        name: &str,
+    ) -> Result<(crate::Branch, Operator<VolatileSpace>)> {
+        let (branch, operator, _) = open_branch_with_profile(name).await?;
+        Ok((branch, operator))
+    }
+
+    /// [`open_branch`], also returning the profile the operator acts for.
+    pub(super) async fn open_branch_with_profile(
+        name: &str,
+    ) -> Result<(crate::Branch, Operator<VolatileSpace>, Profile)> {
         let storage = Storage::volatile();
         let profile = Profile::open(unique_name(name)).perform(&storage).await?;
         let operator = profile
@@ -426,7 +453,7 @@ mod tests {
             .perform(&operator)
             .await?;
         let branch = repo.branch("main").open().perform(&operator).await?;
-        Ok((branch, operator))
+        Ok((branch, operator, profile))
     }
 
     async fn drain(mut reader: BlobReader) -> Vec<u8> {
@@ -0,0 +1,448 @@
+//! UCAN receipts as data beside the retained delegations.
+//!
+//! A receipt is stored like a delegation or a revocation: its signed
+//! envelope as a blob, plus slim facts on the entity `blob:<hash>` naming the
+//! invocation it ran and who ran it, in one commit. Recorded receipts are
+//! what promised arguments resolve against: an argument awaiting
+//! `{"ucan/await/ok": <cid>}` becomes the `ok` value of the receipt for
+//! `<cid>`, so "publish once the put succeeded" is an invocation whose
+//! precondition, and the evidence it was met, both live in the tree.
+//!
+//! As with revocations, the facts only route the lookup. A receipt resolves
+//! a promise only once its envelope decodes, names the invocation its facts
+//! do, and carries a valid signature from one of the executors the caller
+//! trusts to have run it.
+//!
+//! [`RetainedReceipts`] is the branch as a [`ReceiptStore`]: hand it to an
+//! executor and every receipt it signs is recorded here as it is issued.
+
+use dialog_artifacts::{Artifact, ArtifactSelector, Entity, Instruction, Value};
+use dialog_artifacts::{BlobIndexExt as _, BlobRecord, DialogArtifactsError};
+use dialog_capability::{Did, Fork, Provider};
+use dialog_common::Blake3Hash as NodeHash;
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::{Get, Import, Put};
+use dialog_effects::authority::{Attest, Identify};
+use dialog_effects::blob::prelude::{ArchiveBlobExt as _, BlobExt as _};
+use dialog_effects::blob::{Import as BlobImport, Read as BlobRead, Write as BlobWrite};
+use dialog_effects::memory::{Publish, Resolve};
+use dialog_ucan_core::promise::Promised;
+use dialog_ucan_core::{Outcome, Receipt, ReceiptStore};
+use dialog_varsig::AnySignature;
+use futures_util::{StreamExt as _, stream};
+use ipld_core::cid::Cid;
+use ipld_core::ipld::Ipld;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+
+use super::{Delegations, field};
+use crate::repository::branch::blob::index_store;
+use crate::{Blob, Branch, CommitError, Index, RemoteSite, ResolvePromiseError, Select};
+
+/// Attribute carrying the CID of the invocation a receipt reports on.
+pub const RECEIPT_RAN: &str = "dialog.ucan/ran";
+/// Attribute naming who issued (signed) the receipt: the executor.
+pub const RECEIPT_ISSUER: &str = "dialog.ucan/executor";
+
+impl<'a> Delegations<'a> {
+    /// Record a signed receipt beside the retained delegations: its
+    /// `dialog.ucan/*` facts plus its envelope blob, in one commit.
+    pub fn record(self, receipt: Receipt<AnySignature>) -> RecordReceipt<'a> {
+        RecordReceipt {
+            branch: self.branch,
+            receipt,
+        }
+    }
+
+    /// Resolve promised invocation `arguments` against the recorded
+    /// receipts, admitting only receipts signed by one of `executors`.
+    pub fn resolve(
+        self,
+        arguments: BTreeMap<String, Promised>,
+        executors: Vec<Did>,
+    ) -> ResolvePromises<'a> {
+        ResolvePromises {
+            branch: self.branch,
+            arguments,
+            executors,
+        }
+    }
+
+    /// The branch as a [`ReceiptStore`], recording each receipt through
+    /// [`record`](Self::record) on `env`.
+    ///
+    /// The store holds its own handle on the branch, so an executor built
+    /// with it keeps recording as the branch advances.
+    pub fn receipts<Env>(self, env: Env) -> RetainedReceipts<Env> {
+        RetainedReceipts {
+            branch: self.branch.clone(),
+            env,
+        }
+    }
+}
+
+/// Record a receipt as one commit. Created by [`Delegations::record`].
+pub struct RecordReceipt<'a> {
+    branch: &'a Branch,
+    receipt: Receipt<AnySignature>,
+}
+
+impl RecordReceipt<'_> {
+    /// Execute the record, returning the receipt's entity, or `None` when
+    /// this receipt was already recorded (a no-op that mints no revision).
+    pub async fn perform<Env>(self, env: &Env) -> Result<Option<Entity>, CommitError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Import>
+            + Provider<Resolve>
+            + Provider<Publish>
+            + Provider<Identify>
+            + Provider<Attest>
+            + Provider<BlobWrite>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let branch = self.branch;
+        let bytes = self.receipt.to_bytes().map_err(|error| {
+            CommitError::Artifact(DialogArtifactsError::InvalidValue(error.to_string()))
+        })?;
+
+        let mut sink = branch.archive().blob().write().perform(env).await?;
+        sink.write_all(&bytes).await?;
+        let hash = sink.finish().await?;
+        let index_hash: dialog_storage::Blake3Hash = *hash.as_bytes();
+
+        if let Some(revision) = branch.revision() {
+            let store = index_store(branch, env).await;
+            let tree = Index::from_hash(NodeHash::from(*revision.tree.hash()));
+            if tree.get_blob(&store, &index_hash).await?.is_some() {
+                return Ok(None);
+            }
+        }
+
+        let entity = Entity::from_blob(&index_hash)?;
+        let instructions = vec![
+            Instruction::Assert(field(
+                &entity,
+                RECEIPT_RAN,
+                Value::String(self.receipt.ran().to_string()),
+            )?),
+            Instruction::Assert(field(
+                &entity,
+                RECEIPT_ISSUER,
+                Value::String(self.receipt.issuer().to_string()),
+            )?),
+        ];
+
+        Box::pin(
+            branch
+                .commit(stream::iter(instructions))
+                .machinery()
+                .with_entries(vec![BlobRecord::new(bytes.len() as u64).entry(&index_hash)])
+                .perform(env),
+        )
+        .await?;
+
+        Ok(Some(entity))
+    }
+}
+
+/// The receipts recorded on a branch, as a [`ReceiptStore`]. Created by
+/// [`Delegations::receipts`].
+#[derive(Clone)]
+pub struct RetainedReceipts<Env> {
+    branch: Branch,
+    env: Env,
+}
+
+impl<Env> ReceiptStore<AnySignature> for RetainedReceipts<Env>
+where
+    Env: Provider<Get>
+        + Provider<Put>
+        + Provider<Import>
+        + Provider<Resolve>
+        + Provider<Publish>
+        + Provider<Identify>
+        + Provider<Attest>
+        + Provider<BlobWrite>
+        + Provider<Fork<RemoteSite, Get>>
+        + Provider<Fork<RemoteSite, Resolve>>
+        + ConditionalSync
+        + 'static,
+{
+    type Error = CommitError;
+
+    async fn record(&self, receipt: Receipt<AnySignature>) -> Result<(), Self::Error> {
+        self.branch
+            .delegations()
+            .record(receipt)
+            .perform(&self.env)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Resolve promised arguments against recorded receipts. Created by
+/// [`Delegations::resolve`].
+pub struct ResolvePromises<'a> {
+    branch: &'a Branch,
+    arguments: BTreeMap<String, Promised>,
+    executors: Vec<Did>,
+}
+
+fn lookup(context: &str, error: impl Display) -> ResolvePromiseError {
+    ResolvePromiseError::Lookup(format!("{context}: {error}"))
+}
+
+impl ResolvePromises<'_> {
+    /// Execute the resolution, returning the arguments with every promise
+    /// replaced by the outcome it awaited.
+    pub async fn perform<Env>(
+        self,
+        env: &Env,
+    ) -> Result<BTreeMap<String, Ipld>, ResolvePromiseError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<BlobRead>
+            + Provider<BlobImport>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + Provider<Fork<RemoteSite, BlobRead>>
+            + ConditionalSync
+            + 'static,
+    {
+        let mut awaited = Vec::new();
+        for value in self.arguments.values() {
+            for cid in value.awaits() {
+                if !awaited.contains(&cid) {
+                    awaited.push(cid);
+                }
+            }
+        }
+
+        let mut outcomes = HashMap::new();
+        for ran in awaited {
+            if let Some(receipt) = self.receipt(env, ran).await? {
+                outcomes.insert(ran, receipt.out().clone());
+            }
+        }
+
+        let outcome = |cid: &Cid| -> Option<&Outcome> { outcomes.get(cid) };
+        self.arguments
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), value.resolve(&outcome)?)))
+            .collect()
+    }
+
+    /// The first admissible receipt recorded for the invocation `ran`.
+    async fn receipt<Env>(
+        &self,
+        env: &Env,
+        ran: Cid,
+    ) -> Result<Option<Receipt<AnySignature>>, ResolvePromiseError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<BlobRead>
+            + Provider<BlobImport>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + Provider<Fork<RemoteSite, BlobRead>>
+            + ConditionalSync
+            + 'static,
+    {
+        let branch = self.branch;
+        let store = index_store(branch, env).await;
+        let candidates = Select::new(
+            branch,
+            ArtifactSelector::new()
+                .the(RECEIPT_RAN.parse().expect("valid attribute"))
+                .is(Value::String(ran.to_string())),
+        )
+        .execute(store)
+        .await
+        .map_err(|error| lookup("receipt scan failed", error))?;
+        futures_util::pin_mut!(candidates);
+
+        while let Some(item) = candidates.next().await {
+            let fact: Artifact = item
+                .and_then(|view| view.to_owned())
+                .map_err(|error| lookup("receipt fact undecodable", error))?;
+            let entity = fact.of;
+
+            let mut bytes = Vec::new();
+            let mut reader = Blob::from(entity.clone())
+                .read(branch.into())
+                .perform(env)
+                .await
+                .map_err(|error| lookup("receipt envelope unavailable", error))?;
+            while let Some(chunk) = reader
+                .next()
+                .await
+                .map_err(|error| lookup("receipt envelope unreadable", error))?
+            {
+                bytes.extend(chunk);
+            }
+
+            let receipt = match Receipt::<AnySignature>::try_from(bytes.as_slice()) {
+                Ok(receipt) => receipt,
+                Err(error) => {
+                    tracing::warn!(%entity, %error, "receipt envelope undecodable; ignoring it");
+                    continue;
+                }
+            };
+            if receipt.ran() != ran || !self.executors.contains(receipt.issuer()) {
+                continue;
+            }
+            if let Err(error) = receipt
+                .verify_signature(&dialog_credentials::DidKeyResolver)
+                .await
+            {
+                tracing::warn!(
+                    %entity,
+                    issuer = %receipt.issuer(),
+                    %error,
+                    "receipt envelope signature does not verify; ignoring it"
+                );
+                continue;
+            }
+            return Ok(Some(receipt));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::repository::branch::delegation::tests::open_branch;
+    use anyhow::Result;
+    use dialog_credentials::{Ed25519Signer, Signer};
+    use dialog_ucan_core::cid::to_dagcbor_cid;
+    use dialog_ucan_core::promise::Unresolved;
+    use dialog_varsig::Principal as _;
+
+    async fn receipt(executor: &Ed25519Signer, ran: Cid, out: Outcome) -> Receipt<AnySignature> {
+        Receipt::issue(Signer::from(executor.clone()), ran, out)
+            .await
+            .unwrap()
+    }
+
+    #[dialog_common::test]
+    async fn it_records_a_receipt_beside_the_delegations() -> Result<()> {
+        let (branch, operator) = open_branch("receipt-record").await?;
+        let executor = Ed25519Signer::generate().await?;
+        let ran = to_dagcbor_cid(&"put");
+        let receipt = receipt(&executor, ran, Outcome::Ok(Ipld::Bool(true))).await;
+
+        let entity = branch
+            .delegations()
+            .record(receipt.clone())
+            .perform(&operator)
+            .await?
+            .expect("a new receipt is recorded");
+
+        let facts: Vec<_> = branch
+            .claims()
+            .select(ArtifactSelector::new().of(entity))
+            .perform(&operator)
+            .await?
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .map(|item| item.and_then(|view| view.to_owned()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let get = |attribute: &str| {
+            facts
+                .iter()
+                .find(|fact| fact.the.as_str() == attribute)
+                .map(|fact| fact.is.clone())
+        };
+        assert_eq!(get(RECEIPT_RAN), Some(Value::String(ran.to_string())));
+        assert_eq!(
+            get(RECEIPT_ISSUER),
+            Some(Value::String(executor.did().to_string()))
+        );
+
+        // Recording it again is a no-op that mints no revision.
+        let head = branch.revision().map(|revision| revision.version());
+        let again = branch
+            .delegations()
+            .record(receipt)
+            .perform(&operator)
+            .await?;
+        assert!(again.is_none());
+        assert_eq!(branch.revision().map(|revision| revision.version()), head);
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_resolves_a_publish_awaiting_a_recorded_put() -> Result<()> {
+        let (branch, operator) = open_branch("receipt-resolve").await?;
+        let executor = Ed25519Signer::generate().await?;
+        let stranger = Ed25519Signer::generate().await?;
+        let put = to_dagcbor_cid(&"put");
+
+        let arguments = BTreeMap::from([
+            ("after".to_string(), Promised::WaitOk(put)),
+            ("cell".to_string(), Promised::String("main".to_string())),
+        ]);
+        let executors = vec![executor.did()];
+
+        // Nothing ran yet: the publish is still waiting on the put.
+        let pending = branch
+            .delegations()
+            .resolve(arguments.clone(), executors.clone())
+            .perform(&operator)
+            .await;
+        assert!(matches!(
+            pending,
+            Err(ResolvePromiseError::Unresolved(Unresolved::Waiting(_)))
+        ));
+
+        // A receipt from an executor the caller does not trust is ignored.
+        let stored = Outcome::Ok(Ipld::String("stored".to_string()));
+        branch
+            .delegations()
+            .record(receipt(&stranger, put, stored.clone()).await)
+            .perform(&operator)
+            .await?;
+        let untrusted = branch
+            .delegations()
+            .resolve(arguments.clone(), executors.clone())
+            .perform(&operator)
+            .await;
+        assert!(matches!(
+            untrusted,
+            Err(ResolvePromiseError::Unresolved(Unresolved::Waiting(_)))
+        ));
+
+        branch
+            .delegations()
+            .record(receipt(&executor, put, stored).await)
+            .perform(&operator)
+            .await?;
+        let resolved = branch
+            .delegations()
+            .resolve(arguments, executors)
+            .perform(&operator)
+            .await?;
+        assert_eq!(
+            resolved,
+            BTreeMap::from([
+                ("after".to_string(), Ipld::String("stored".to_string())),
+                ("cell".to_string(), Ipld::String("main".to_string())),
+            ])
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,513 @@
+//! UCAN revocations as data beside the retained delegations.
+//!
+//! A revocation is stored the way a delegation is: its signed envelope (a
+//! `/ucan/revoke` [`Revocation`]) as a blob, plus slim facts on the entity
+//! `blob:<hash>` naming the revoked delegation's CID and the revoker, all in
+//! one commit. Unlike a delegation it has no retract: withdrawing authority
+//! is final, and a replica that merged the revocation must keep refusing.
+//!
+//! [`RetainedRevocations`] answers the verifier's question over those facts.
+//! The facts only route the lookup; the envelope is the authority. A match
+//! is reported only once its envelope decodes, names the same delegation
+//! and revoker as its facts, and carries a valid signature from that
+//! revoker, so a peer with push access cannot revoke on anyone's behalf.
+
+use dialog_artifacts::{Artifact, ArtifactSelector, Entity, Instruction, Value};
+use dialog_artifacts::{BlobIndexExt as _, BlobRecord, DialogArtifactsError};
+use dialog_capability::access::AuthorizeError;
+use dialog_capability::{Did, Fork, Provider};
+use dialog_common::Blake3Hash as NodeHash;
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::{Get, Import, Put};
+use dialog_effects::authority::{Attest, Identify};
+use dialog_effects::blob::prelude::{ArchiveBlobExt as _, BlobExt as _};
+use dialog_effects::blob::{Import as BlobImport, Read as BlobRead, Write as BlobWrite};
+use dialog_effects::memory::{Publish, Resolve};
+use dialog_ucan_core::{Revocation, RevocationChecker, RevocationMatch, RevocationSelector};
+use dialog_varsig::AnySignature;
+use futures_util::{StreamExt as _, stream};
+use std::fmt::Display;
+
+use super::{Delegations, field};
+use crate::repository::branch::blob::index_store;
+use crate::{Blob, Branch, CommitError, Index, RemoteSite, Select};
+
+/// Attribute carrying the CID of the delegation a revocation withdraws.
+pub const REVOCATION_REVOKES: &str = "dialog.ucan/revokes";
+/// Attribute naming who issued (signed) the revocation.
+pub const REVOCATION_ISSUER: &str = "dialog.ucan/revoker";
+
+impl<'a> Delegations<'a> {
+    /// Record a signed revocation beside the retained delegations: its
+    /// `dialog.ucan/*` facts plus its envelope blob, in one commit.
+    ///
+    /// Issue one with [`Revocation::issue`], signed by a principal entitled
+    /// to revoke the delegation (an issuer at or above it in the chains it
+    /// appears in). An unentitled revocation is stored like any other but
+    /// never counts.
+    pub fn revoke(self, revocation: Revocation<AnySignature>) -> RevokeDelegation<'a> {
+        RevokeDelegation {
+            branch: self.branch,
+            revocation,
+        }
+    }
+
+    /// A [`RevocationChecker`] over the revocations recorded on this branch,
+    /// reading through `env`.
+    ///
+    /// The checker holds its own handle on the branch, so it sees
+    /// revocations committed after it was created.
+    pub fn revocations<Env>(self, env: Env) -> RetainedRevocations<Env> {
+        RetainedRevocations {
+            branch: self.branch.clone(),
+            env,
+        }
+    }
+}
+
+/// Record a revocation as one commit. Created by [`Delegations::revoke`].
+pub struct RevokeDelegation<'a> {
+    branch: &'a Branch,
+    revocation: Revocation<AnySignature>,
+}
+
+impl RevokeDelegation<'_> {
+    /// Execute the revoke, returning the revocation's entity, or `None` when
+    /// this revocation was already recorded (a no-op that mints no
+    /// revision).
+    pub async fn perform<Env>(self, env: &Env) -> Result<Option<Entity>, CommitError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Import>
+            + Provider<Resolve>
+            + Provider<Publish>
+            + Provider<Identify>
+            + Provider<Attest>
+            + Provider<BlobWrite>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let branch = self.branch;
+        let bytes = self.revocation.to_bytes().map_err(|error| {
+            CommitError::Artifact(DialogArtifactsError::InvalidValue(error.to_string()))
+        })?;
+
+        // Durable before the revision below references it; the sink's digest
+        // is the entity, exactly as for a retained delegation.
+        let mut sink = branch.archive().blob().write().perform(env).await?;
+        sink.write_all(&bytes).await?;
+        let hash = sink.finish().await?;
+        let index_hash: dialog_storage::Blake3Hash = *hash.as_bytes();
+
+        if let Some(revision) = branch.revision() {
+            let store = index_store(branch, env).await;
+            let tree = Index::from_hash(NodeHash::from(*revision.tree.hash()));
+            if tree.get_blob(&store, &index_hash).await?.is_some() {
+                return Ok(None);
+            }
+        }
+
+        let entity = Entity::from_blob(&index_hash)?;
+        let instructions = vec![
+            Instruction::Assert(field(
+                &entity,
+                REVOCATION_REVOKES,
+                Value::String(self.revocation.revokes().to_string()),
+            )?),
+            Instruction::Assert(field(
+                &entity,
+                REVOCATION_ISSUER,
+                Value::String(self.revocation.issuer().to_string()),
+            )?),
+        ];
+
+        Box::pin(
+            branch
+                .commit(stream::iter(instructions))
+                .machinery()
+                .with_entries(vec![BlobRecord::new(bytes.len() as u64).entry(&index_hash)])
+                .perform(env),
+        )
+        .await?;
+
+        Ok(Some(entity))
+    }
+}
+
+/// The revocations recorded on a branch, as a [`RevocationChecker`].
+/// Created by [`Delegations::revocations`].
+#[derive(Clone)]
+pub struct RetainedRevocations<Env> {
+    branch: Branch,
+    env: Env,
+}
+
+fn unavailable(context: &str, error: impl Display) -> AuthorizeError {
+    AuthorizeError::Unavailable {
+        detail: format!("{context}: {error}"),
+    }
+}
+
+impl<Env> RetainedRevocations<Env>
+where
+    Env: Provider<Get>
+        + Provider<Put>
+        + Provider<Resolve>
+        + Provider<BlobRead>
+        + Provider<BlobImport>
+        + Provider<Fork<RemoteSite, Get>>
+        + Provider<Fork<RemoteSite, Resolve>>
+        + Provider<Fork<RemoteSite, BlobRead>>
+        + ConditionalSync
+        + 'static,
+{
+    /// Read the revoker a revocation entity's facts name.
+    async fn revoker<S>(&self, store: &S, entity: &Entity) -> Result<Option<Did>, AuthorizeError>
+    where
+        S: dialog_storage::StorageBackend<
+                Key = dialog_storage::Blake3Hash,
+                Value = Vec<u8>,
+                Error = dialog_storage::DialogStorageError,
+            > + Clone
+            + ConditionalSync,
+    {
+        let facts = Select::new(
+            &self.branch,
+            ArtifactSelector::new()
+                .of(entity.clone())
+                .the(REVOCATION_ISSUER.parse().expect("valid attribute")),
+        )
+        .execute(store.clone())
+        .await
+        .map_err(|error| unavailable("revocation read failed", error))?;
+        futures_util::pin_mut!(facts);
+
+        while let Some(item) = facts.next().await {
+            let fact: Artifact = item
+                .and_then(|view| view.to_owned())
+                .map_err(|error| unavailable("revocation fact undecodable", error))?;
+            if let Value::String(did) = fact.is
+                && let Ok(did) = did.parse()
+            {
+                return Ok(Some(did));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Fetch a revocation's envelope. Failing to read it leaves the question
+    /// unanswered, so it is an error rather than "not revoked".
+    async fn envelope(&self, entity: &Entity) -> Result<Vec<u8>, AuthorizeError> {
+        let mut reader = Blob::from(entity.clone())
+            .read((&self.branch).into())
+            .perform(&self.env)
+            .await
+            .map_err(|error| unavailable("revocation envelope unavailable", error))?;
+        let mut bytes = Vec::new();
+        while let Some(chunk) = reader
+            .next()
+            .await
+            .map_err(|error| unavailable("revocation envelope unreadable", error))?
+        {
+            bytes.extend(chunk);
+        }
+        Ok(bytes)
+    }
+
+    /// Admit a candidate whose facts matched: the envelope must decode and
+    /// agree with the facts, and its signature must be the revoker's.
+    async fn admit(
+        &self,
+        entity: &Entity,
+        selector: &RevocationSelector<'_>,
+        revoker: &Did,
+    ) -> Result<Option<RevocationMatch>, AuthorizeError> {
+        let bytes = self.envelope(entity).await?;
+        let revocation = match Revocation::<AnySignature>::try_from(bytes.as_slice()) {
+            Ok(revocation) => revocation,
+            Err(error) => {
+                tracing::warn!(%entity, %error, "revocation envelope undecodable; ignoring it");
+                return Ok(None);
+            }
+        };
+        if revocation.revokes() != selector.delegation || revocation.issuer() != revoker {
+            tracing::warn!(%entity, "revocation facts disagree with their envelope; ignoring it");
+            return Ok(None);
+        }
+        if let Err(error) = revocation
+            .verify_signature(&dialog_credentials::DidKeyResolver)
+            .await
+        {
+            tracing::warn!(
+                %entity,
+                issuer = %revoker,
+                %error,
+                "revocation envelope signature does not verify; ignoring it"
+            );
+            return Ok(None);
+        }
+        Ok(Some(RevocationMatch {
+            revocation: revocation.to_cid(),
+            principal: revoker.clone(),
+        }))
+    }
+}
+
+impl<Env> RevocationChecker for RetainedRevocations<Env>
+where
+    Env: Provider<Get>
+        + Provider<Put>
+        + Provider<Resolve>
+        + Provider<BlobRead>
+        + Provider<BlobImport>
+        + Provider<Fork<RemoteSite, Get>>
+        + Provider<Fork<RemoteSite, Resolve>>
+        + Provider<Fork<RemoteSite, BlobRead>>
+        + ConditionalSync
+        + 'static,
+{
+    type Error = AuthorizeError;
+
+    async fn query(
+        &self,
+        selector: RevocationSelector<'_>,
+    ) -> Result<Option<RevocationMatch>, Self::Error> {
+        let store = index_store(&self.branch, &self.env).await;
+        let candidates = Select::new(
+            &self.branch,
+            ArtifactSelector::new()
+                .the(REVOCATION_REVOKES.parse().expect("valid attribute"))
+                .is(Value::String(selector.delegation.to_string())),
+        )
+        .execute(store.clone())
+        .await
+        .map_err(|error| unavailable("revocation scan failed", error))?;
+        futures_util::pin_mut!(candidates);
+
+        while let Some(item) = candidates.next().await {
+            let fact = item
+                .and_then(|view| view.to_owned())
+                .map_err(|error| unavailable("revocation fact undecodable", error))?;
+            // Only a revoker the verifier named can count; anyone else's
+            // revocation is skipped without touching its envelope.
+            let Some(revoker) = self.revoker(&store, &fact.of).await? else {
+                continue;
+            };
+            if !selector.by.contains(&revoker) {
+                continue;
+            }
+            if let Some(found) = self.admit(&fact.of, &selector, &revoker).await? {
+                return Ok(Some(found));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::repository::branch::delegation::tests::open_branch;
+    use anyhow::Result;
+    use dialog_credentials::{Ed25519Signer, Signer};
+    use dialog_remote_s3::{Address, S3Credential, S3Error};
+    use dialog_remote_ucan_s3::UcanAuthorizer;
+    use dialog_ucan::UcanDelegation;
+    use dialog_ucan_core::promise::Promised;
+    use dialog_ucan_core::subject::Subject as UcanSubject;
+    use dialog_ucan_core::{
+        Delegation, DelegationBuilder, DelegationChain, InvocationBuilder, InvocationChain,
+    };
+    use dialog_varsig::Principal as _;
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::Arc;
+
+    async fn delegate(
+        issuer: &Ed25519Signer,
+        audience: &Ed25519Signer,
+        subject: &Ed25519Signer,
+    ) -> Delegation<AnySignature> {
+        DelegationBuilder::new()
+            .issuer(Signer::from(issuer.clone()))
+            .audience(audience)
+            .subject(UcanSubject::Specific(subject.did()))
+            .command(vec!["archive".to_string()])
+            .try_build()
+            .await
+            .unwrap()
+    }
+
+    async fn revoke(
+        issuer: &Ed25519Signer,
+        delegation: &Delegation<AnySignature>,
+    ) -> Revocation<AnySignature> {
+        Revocation::issue(Signer::from(issuer.clone()), delegation.to_cid())
+            .await
+            .unwrap()
+    }
+
+    /// `invoker` reads from `space`'s archive on the strength of `proofs`,
+    /// root first.
+    async fn invoke(
+        invoker: &Ed25519Signer,
+        space: &Ed25519Signer,
+        proofs: &[Delegation<AnySignature>],
+    ) -> Vec<u8> {
+        let mut args = BTreeMap::new();
+        args.insert("catalog".to_string(), Promised::String("blobs".to_string()));
+        args.insert("digest".to_string(), Promised::Bytes([0u8; 32].to_vec()));
+        let invocation = InvocationBuilder::new()
+            .issuer(Signer::from(invoker.clone()))
+            .audience(space)
+            .subject(space)
+            .command(vec!["archive".to_string(), "get".to_string()])
+            .arguments(args)
+            .proofs(proofs.iter().map(Delegation::to_cid).collect())
+            .try_build()
+            .await
+            .unwrap();
+        let delegations: HashMap<_, _> = proofs
+            .iter()
+            .map(|delegation| (delegation.to_cid(), Arc::new(delegation.clone())))
+            .collect();
+        InvocationChain::new(invocation, delegations)
+            .to_bytes()
+            .unwrap()
+    }
+
+    fn address() -> Address {
+        Address::builder("https://s3.us-east-1.amazonaws.com")
+            .region("us-east-1")
+            .bucket("test-bucket")
+            .build()
+            .unwrap()
+    }
+
+    #[dialog_common::test]
+    async fn it_records_a_revocation_beside_the_delegations() -> Result<()> {
+        let (branch, operator) = open_branch("revocation-record").await?;
+        let space = Ed25519Signer::generate().await?;
+        let holder = Ed25519Signer::generate().await?;
+        let delegation = delegate(&space, &holder, &space).await;
+        branch
+            .delegations()
+            .retain(UcanDelegation::new(DelegationChain::new(
+                delegation.clone(),
+            )))
+            .perform(&operator)
+            .await?;
+
+        let revocation = revoke(&space, &delegation).await;
+        let entity = branch
+            .delegations()
+            .revoke(revocation.clone())
+            .perform(&operator)
+            .await?
+            .expect("a new revocation is recorded");
+
+        let facts: Vec<_> = branch
+            .claims()
+            .select(ArtifactSelector::new().of(entity))
+            .perform(&operator)
+            .await?
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .map(|item| item.and_then(|view| view.to_owned()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let get = |attribute: &str| {
+            facts
+                .iter()
+                .find(|fact| fact.the.as_str() == attribute)
+                .map(|fact| fact.is.clone())
+        };
+        assert_eq!(
+            get(REVOCATION_REVOKES),
+            Some(Value::String(delegation.to_cid().to_string()))
+        );
+        assert_eq!(
+            get(REVOCATION_ISSUER),
+            Some(Value::String(space.did().to_string()))
+        );
+
+        // Recording it again is a no-op that mints no revision.
+        let head = branch.revision().map(|revision| revision.version());
+        let again = branch
+            .delegations()
+            .revoke(revocation)
+            .perform(&operator)
+            .await?;
+        assert!(again.is_none());
+        assert_eq!(branch.revision().map(|revision| revision.version()), head);
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_denies_invocations_once_any_link_is_revoked() -> Result<()> {
+        let (branch, operator) = open_branch("revocation-authorize").await?;
+        let space = Ed25519Signer::generate().await?;
+        let alice = Ed25519Signer::generate().await?;
+        let bob = Ed25519Signer::generate().await?;
+        let mallory = Ed25519Signer::generate().await?;
+
+        let root = delegate(&space, &alice, &space).await;
+        let leaf = delegate(&alice, &bob, &space).await;
+        let container = invoke(&bob, &space, &[root.clone(), leaf.clone()]).await;
+
+        let authorizer = UcanAuthorizer::new(
+            address(),
+            Some(S3Credential::new("access-key-id", "secret-access-key")),
+        )
+        .with_revocations(branch.delegations().revocations(operator.clone()));
+        authorizer.authorize(&container).await?;
+
+        // Someone outside the chain cannot withdraw it.
+        branch
+            .delegations()
+            .revoke(revoke(&mallory, &leaf).await)
+            .perform(&operator)
+            .await?;
+        authorizer.authorize(&container).await?;
+
+        // Alice withdrawing what she granted Bob refuses Bob's invocation,
+        // through the checker the authorizer was built with.
+        branch
+            .delegations()
+            .revoke(revoke(&alice, &leaf).await)
+            .perform(&operator)
+            .await?;
+        let result = authorizer.authorize(&container).await;
+        assert!(
+            matches!(
+                result,
+                Err(S3Error::Authorization(AuthorizeError::Revoked { .. }))
+            ),
+            "expected a revocation refusal, got {result:?}"
+        );
+
+        // The root's issuer may revoke a link below it, too.
+        let (branch, operator) = open_branch("revocation-authorize-root").await?;
+        branch
+            .delegations()
+            .revoke(revoke(&space, &leaf).await)
+            .perform(&operator)
+            .await?;
+        let authorizer = UcanAuthorizer::new(address(), None)
+            .with_revocations(branch.delegations().revocations(operator));
+        let result = authorizer.authorize(&container).await;
+        assert!(
+            matches!(
+                result,
+                Err(S3Error::Authorization(AuthorizeError::Revoked { .. }))
+            ),
+            "expected a revocation refusal, got {result:?}"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,1137 @@
+//! Key successions as data beside the retained delegations.
+//!
+//! A succession is stored the way a revocation is: its signed envelope (a
+//! `/ucan/rotate` [`Succession`]) as a blob, plus slim facts on the entity
+//! `blob:<hash>` naming the predecessor and the successor, all in one
+//! commit. The delegations it carries — the handover and, for a recovery,
+//! the backup's registration — are retained in that same commit, so proof
+//! search can walk from the predecessor to the successor. Like a
+//! revocation it has no retract: a key that was given up stays given up.
+//!
+//! [`RetainedSuccessions`] answers the verifier's question over those facts.
+//! The facts only route the lookup; the envelope is the authority. A record
+//! counts only once its envelope decodes, names the predecessor its facts
+//! name, and carries valid signatures, so a peer with push access cannot
+//! rotate anyone's key. When several records disagree,
+//! [`SuccessionMatch::settle`] decides: a recovery outranks the rotations
+//! its registration predates and it answers — a compromised key can
+//! rotate, but only the registered backup can recover — and if they still
+//! disagree the succession is contested: nothing the predecessor issued
+//! stands until a recovery settles it.
+//!
+//! [`Delegations::migrate`] then re-issues what the predecessor delegated
+//! under the successor's key, so holders keep working without going back to
+//! each grantor.
+
+use dialog_artifacts::history::{RevisionRecord, Version, descends};
+use dialog_artifacts::{Artifact, ArtifactSelector, Entity, Instruction, Value};
+use dialog_artifacts::{BlobIndexExt as _, BlobRecord, DialogArtifactsError};
+use dialog_capability::access::AuthorizeError;
+use dialog_capability::access::Certificate as _;
+use dialog_capability::{Did, Fork, Provider};
+use dialog_common::Blake3Hash as NodeHash;
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::{Get, Import, Put};
+use dialog_effects::authority::{Attest, Identify};
+use dialog_effects::blob::prelude::{ArchiveBlobExt as _, BlobExt as _};
+use dialog_effects::blob::{Import as BlobImport, Read as BlobRead, Write as BlobWrite};
+use dialog_effects::memory::{Publish, Resolve};
+use dialog_ucan::{UcanCertificate, UcanDelegation};
+use dialog_ucan_core::SuccessionMatch;
+use dialog_ucan_core::issuer::Issuer;
+use dialog_ucan_core::{Delegation, DelegationBuilder, Succession, SuccessionChecker};
+use dialog_varsig::AnySignature;
+use futures_util::{StreamExt as _, stream};
+use std::collections::HashSet;
+use std::fmt::Display;
+
+use super::{DELEGATION_ISSUER, Delegations, encode, field, field_artifacts};
+use crate::repository::branch::blob::index_store;
+use crate::{Blob, Branch, CommitError, Index, RemoteSite, Revision, Select};
+
+/// Attribute naming the principal a succession supersedes.
+pub const SUCCESSION_PREDECESSOR: &str = "dialog.ucan/predecessor";
+/// Attribute naming the principal a succession hands authority to.
+pub const SUCCESSION_SUCCESSOR: &str = "dialog.ucan/successor";
+
+impl<'a> Delegations<'a> {
+    /// Record a signed succession beside the retained delegations: its
+    /// `dialog.ucan/*` facts, its envelope blob, and the delegations it
+    /// carries, in one commit.
+    ///
+    /// Issue one with [`Succession::rotate`], signed by the key being
+    /// retired, or [`Succession::recover`], signed by a backup the key
+    /// registered in advance. A succession whose signatures do not hold is
+    /// stored like any other but never counts.
+    pub fn succeed(self, succession: Succession<AnySignature>) -> SucceedDelegation<'a> {
+        SucceedDelegation {
+            branch: self.branch,
+            succession,
+        }
+    }
+
+    /// A [`SuccessionChecker`] over the successions recorded on this branch,
+    /// reading through `env`.
+    ///
+    /// The checker holds its own handle on the branch, so it sees
+    /// successions committed after it was created.
+    pub fn successions<Env>(self, env: Env) -> RetainedSuccessions<Env> {
+        RetainedSuccessions {
+            branch: self.branch.clone(),
+            env,
+        }
+    }
+
+    /// Re-issue every retained delegation the succession's predecessor
+    /// issued, signed by `successor` — the successor's key — and replace
+    /// the originals with them in one commit.
+    ///
+    /// The succession should already be recorded ([`succeed`](Self::succeed)):
+    /// the re-issued delegations descend from its handover, and verifiers
+    /// only honour that once they find the succession.
+    pub fn migrate<I>(
+        self,
+        succession: Succession<AnySignature>,
+        successor: I,
+    ) -> MigrateDelegations<'a, I> {
+        MigrateDelegations {
+            branch: self.branch,
+            succession,
+            successor,
+        }
+    }
+}
+
+/// The delegations a succession carries: the registration, for a recovery,
+/// then the handover.
+fn carried(succession: &Succession<AnySignature>) -> Vec<UcanCertificate> {
+    succession
+        .registration()
+        .into_iter()
+        .chain([succession.handover()])
+        .cloned()
+        .map(UcanCertificate)
+        .collect()
+}
+
+/// Record a succession as one commit. Created by [`Delegations::succeed`].
+pub struct SucceedDelegation<'a> {
+    branch: &'a Branch,
+    succession: Succession<AnySignature>,
+}
+
+impl SucceedDelegation<'_> {
+    /// Execute the succeed, returning the succession's entity, or `None`
+    /// when this succession was already recorded (a no-op that mints no
+    /// revision).
+    pub async fn perform<Env>(self, env: &Env) -> Result<Option<Entity>, CommitError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Import>
+            + Provider<Resolve>
+            + Provider<Publish>
+            + Provider<Identify>
+            + Provider<Attest>
+            + Provider<BlobWrite>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let branch = self.branch;
+        let bytes = self.succession.to_bytes().map_err(|error| {
+            CommitError::Artifact(DialogArtifactsError::InvalidValue(error.to_string()))
+        })?;
+
+        let mut sink = branch.archive().blob().write().perform(env).await?;
+        sink.write_all(&bytes).await?;
+        let hash = sink.finish().await?;
+        let index_hash: dialog_storage::Blake3Hash = *hash.as_bytes();
+
+        let store = index_store(branch, env).await;
+        let tree = branch
+            .revision()
+            .map(|revision| Index::from_hash(NodeHash::from(*revision.tree.hash())));
+        if let Some(tree) = &tree
+            && tree.get_blob(&store, &index_hash).await?.is_some()
+        {
+            return Ok(None);
+        }
+
+        let entity = Entity::from_blob(&index_hash)?;
+        let mut instructions = vec![
+            Instruction::Assert(field(
+                &entity,
+                SUCCESSION_PREDECESSOR,
+                Value::String(self.succession.predecessor().to_string()),
+            )?),
+            Instruction::Assert(field(
+                &entity,
+                SUCCESSION_SUCCESSOR,
+                Value::String(self.succession.successor().to_string()),
+            )?),
+        ];
+        let mut entries = vec![BlobRecord::new(bytes.len() as u64).entry(&index_hash)];
+
+        // The carried delegations are retained exactly as
+        // `Delegations::retain` would, skipping any the tree already holds.
+        for certificate in carried(&self.succession) {
+            let bytes = encode(&certificate)?;
+            let mut sink = branch.archive().blob().write().perform(env).await?;
+            sink.write_all(&bytes).await?;
+            let hash = sink.finish().await?;
+            let index_hash: dialog_storage::Blake3Hash = *hash.as_bytes();
+            if let Some(tree) = &tree
+                && tree.get_blob(&store, &index_hash).await?.is_some()
+            {
+                continue;
+            }
+            let carried = Entity::from_blob(&index_hash)?;
+            for artifact in field_artifacts(&carried, &certificate)? {
+                instructions.push(Instruction::Assert(artifact));
+            }
+            entries.push(BlobRecord::new(bytes.len() as u64).entry(&index_hash));
+        }
+
+        Box::pin(
+            branch
+                .commit(stream::iter(instructions))
+                .machinery()
+                .with_entries(entries)
+                .perform(env),
+        )
+        .await?;
+
+        Ok(Some(entity))
+    }
+}
+
+/// Read a blob entity's bytes in full.
+async fn read_envelope<Env>(
+    branch: &Branch,
+    env: &Env,
+    entity: &Entity,
+) -> Result<Vec<u8>, CommitError>
+where
+    Env: Provider<Get>
+        + Provider<Put>
+        + Provider<Resolve>
+        + Provider<BlobRead>
+        + Provider<BlobImport>
+        + Provider<Fork<RemoteSite, Get>>
+        + Provider<Fork<RemoteSite, Resolve>>
+        + Provider<Fork<RemoteSite, BlobRead>>
+        + ConditionalSync
+        + 'static,
+{
+    let mut reader = Blob::from(entity.clone())
+        .read(branch.into())
+        .perform(env)
+        .await?;
+    let mut bytes = Vec::new();
+    while let Some(chunk) = reader.next().await? {
+        bytes.extend(chunk);
+    }
+    Ok(bytes)
+}
+
+/// The successions recorded on a branch, as a [`SuccessionChecker`].
+/// Created by [`Delegations::successions`].
+#[derive(Clone)]
+pub struct RetainedSuccessions<Env> {
+    branch: Branch,
+    env: Env,
+}
+
+fn unavailable(context: &str, error: impl Display) -> AuthorizeError {
+    AuthorizeError::Unavailable {
+        detail: format!("{context}: {error}"),
+    }
+}
+
+impl<Env> RetainedSuccessions<Env>
+where
+    Env: Provider<Get>
+        + Provider<Put>
+        + Provider<Resolve>
+        + Provider<BlobRead>
+        + Provider<BlobImport>
+        + Provider<Fork<RemoteSite, Get>>
+        + Provider<Fork<RemoteSite, Resolve>>
+        + Provider<Fork<RemoteSite, BlobRead>>
+        + ConditionalSync
+        + 'static,
+{
+    fn view(&self) -> Successions<'_, Env> {
+        Successions::new(&self.branch, &self.env)
+    }
+
+    /// The keys `principal` has passed through: the principal itself, then
+    /// each successor in turn, for as long as the succession is settled.
+    ///
+    /// History readers use it to attribute revisions signed under earlier
+    /// keys to the principal that holds the latest.
+    pub async fn lineage(&self, principal: &Did) -> Result<Vec<Did>, AuthorizeError> {
+        self.view().lineage(principal).await
+    }
+
+    /// Verify a revision record found at `version` as committed on behalf
+    /// of `principal`: its signature and slot as
+    /// [`RevisionRecord::verify`] checks them, and its authority either
+    /// `principal` or a key `principal` succeeded.
+    ///
+    /// A revision committed under a key that has since been rotated keeps
+    /// verifying, and is attributed to the key that holds the authority
+    /// now.
+    pub async fn verify_record(
+        &self,
+        record: &RevisionRecord,
+        version: &Version,
+        principal: &Did,
+    ) -> Result<(), DialogArtifactsError> {
+        self.view().verify_record(record, version, principal).await
+    }
+
+    /// Verify a head minted elsewhere before adopting it: the record its
+    /// tree holds for it must verify as [`verify_record`](Self::verify_record)
+    /// checks it, on behalf of its own authority, and name the issuer that
+    /// signed the head. Then neither key may have signed after being
+    /// superseded.
+    ///
+    /// Whether a head came before a succession is decided by causality
+    /// alone: a superseded key's head is accepted only if it is the
+    /// revision this branch recorded the succession in, or one of that
+    /// revision's ancestors. A head that has seen the succession, or runs
+    /// concurrently with it, is refused whatever its record's timestamp
+    /// claims, since the superseded key could have signed it at any time.
+    pub async fn verify_head(
+        &self,
+        head: &Revision,
+        record: &RevisionRecord,
+    ) -> Result<(), DialogArtifactsError> {
+        self.view().verify_head(head, record).await
+    }
+}
+
+impl<Env> SuccessionChecker for RetainedSuccessions<Env>
+where
+    Env: Provider<Get>
+        + Provider<Put>
+        + Provider<Resolve>
+        + Provider<BlobRead>
+        + Provider<BlobImport>
+        + Provider<Fork<RemoteSite, Get>>
+        + Provider<Fork<RemoteSite, Resolve>>
+        + Provider<Fork<RemoteSite, BlobRead>>
+        + ConditionalSync
+        + 'static,
+{
+    type Error = AuthorizeError;
+
+    async fn query(&self, principal: &Did) -> Result<Option<SuccessionMatch>, Self::Error> {
+        self.view().query(principal).await
+    }
+}
+
+/// A verified succession and the revision that recorded it.
+struct Recorded {
+    succession: Succession<AnySignature>,
+    at: Option<Version>,
+}
+
+/// The successions recorded on a branch, read through a borrowed
+/// environment: what [`RetainedSuccessions`] answers with, and what pull
+/// checks an adopted head against.
+pub(crate) struct Successions<'a, Env> {
+    branch: &'a Branch,
+    env: &'a Env,
+}
+
+impl<'a, Env> Successions<'a, Env>
+where
+    Env: Provider<Get>
+        + Provider<Put>
+        + Provider<Resolve>
+        + Provider<BlobRead>
+        + Provider<BlobImport>
+        + Provider<Fork<RemoteSite, Get>>
+        + Provider<Fork<RemoteSite, Resolve>>
+        + Provider<Fork<RemoteSite, BlobRead>>
+        + ConditionalSync
+        + 'static,
+{
+    pub(crate) fn new(branch: &'a Branch, env: &'a Env) -> Self {
+        Self { branch, env }
+    }
+
+    /// Whether the branch records no succession at all, in which case no
+    /// head can be signed by a superseded key.
+    pub(crate) async fn is_empty(&self) -> Result<bool, DialogArtifactsError> {
+        let store = index_store(self.branch, self.env).await;
+        let candidates = Select::new(
+            self.branch,
+            ArtifactSelector::new().the(SUCCESSION_PREDECESSOR.parse().expect("valid attribute")),
+        )
+        .execute(store)
+        .await
+        .map_err(|error| unavailable("succession scan failed", error))?;
+        futures_util::pin_mut!(candidates);
+        Ok(candidates.next().await.is_none())
+    }
+
+    /// Every recorded succession of `predecessor` whose envelope agrees
+    /// with its facts and whose signatures hold.
+    async fn recorded(&self, predecessor: &Did) -> Result<Vec<Recorded>, AuthorizeError> {
+        let store = index_store(self.branch, self.env).await;
+        let candidates = Select::new(
+            self.branch,
+            ArtifactSelector::new()
+                .the(SUCCESSION_PREDECESSOR.parse().expect("valid attribute"))
+                .is(Value::String(predecessor.to_string())),
+        )
+        .execute(store)
+        .await
+        .map_err(|error| unavailable("succession scan failed", error))?;
+        futures_util::pin_mut!(candidates);
+
+        let mut recorded = Vec::new();
+        while let Some(item) = candidates.next().await {
+            let view = item.map_err(|error| unavailable("succession fact undecodable", error))?;
+            let at = view.datum().and_then(|datum| datum.version);
+            let fact: Artifact = view
+                .to_owned()
+                .map_err(|error| unavailable("succession fact undecodable", error))?;
+            let entity = fact.of;
+
+            // Failing to read an envelope leaves the question unanswered,
+            // so it is an error rather than "not superseded".
+            let bytes = read_envelope(self.branch, self.env, &entity)
+                .await
+                .map_err(|error| unavailable("succession envelope unavailable", error))?;
+            let succession = match Succession::<AnySignature>::try_from(bytes.as_slice()) {
+                Ok(succession) => succession,
+                Err(error) => {
+                    tracing::warn!(%entity, %error, "succession envelope undecodable; ignoring it");
+                    continue;
+                }
+            };
+            if succession.predecessor() != predecessor {
+                tracing::warn!(%entity, "succession facts disagree with their envelope; ignoring it");
+                continue;
+            }
+            if let Err(error) = succession
+                .verify_signature(&dialog_credentials::DidKeyResolver)
+                .await
+            {
+                tracing::warn!(
+                    %entity,
+                    %error,
+                    "succession envelope signature does not verify; ignoring it"
+                );
+                continue;
+            }
+            recorded.push(Recorded { succession, at });
+        }
+        Ok(recorded)
+    }
+
+    async fn query(&self, principal: &Did) -> Result<Option<SuccessionMatch>, AuthorizeError> {
+        let recorded: Vec<_> = self
+            .recorded(principal)
+            .await?
+            .into_iter()
+            .map(|recorded| recorded.succession)
+            .collect();
+        Ok(SuccessionMatch::settle(principal, &recorded))
+    }
+
+    async fn lineage(&self, principal: &Did) -> Result<Vec<Did>, AuthorizeError> {
+        let mut lineage = vec![principal.clone()];
+        while let Some(found) = self.query(lineage.last().expect("never empty")).await? {
+            match found.successor {
+                Some(successor) if !lineage.contains(&successor) => lineage.push(successor),
+                _ => break,
+            }
+        }
+        Ok(lineage)
+    }
+
+    async fn verify_record(
+        &self,
+        record: &RevisionRecord,
+        version: &Version,
+        principal: &Did,
+    ) -> Result<(), DialogArtifactsError> {
+        let authority = parse_did("authority", &record.authority)?;
+        let lineage = self.lineage(&authority).await?;
+        record.verify_for(version, principal, &lineage)
+    }
+
+    /// See [`RetainedSuccessions::verify_head`].
+    pub(crate) async fn verify_head(
+        &self,
+        head: &Revision,
+        record: &RevisionRecord,
+    ) -> Result<(), DialogArtifactsError> {
+        let version = head.version();
+        if record.issuer != head.issuer.to_string() {
+            return Err(DialogArtifactsError::InvalidSignature(format!(
+                "Revision record at {version} was issued by {}, its head by {}",
+                record.issuer, head.issuer
+            )));
+        }
+        let authority = parse_did("authority", &record.authority)?;
+        self.verify_record(record, &version, &authority).await?;
+
+        let mut keys = vec![authority];
+        if !keys.contains(&head.issuer) {
+            keys.push(head.issuer.clone());
+        }
+        let history = self.branch.history(self.env);
+        for key in keys {
+            for recorded in self.recorded(&key).await? {
+                // What the key signed before its succession is in the
+                // history of the revision recording it, that revision
+                // included: the old key's last word may be pulled like any
+                // other. The head's own clock has no say.
+                let preceded = match &recorded.at {
+                    Some(at) => descends(at, &version, &history).await?,
+                    None => false,
+                };
+                if !preceded {
+                    return Err(DialogArtifactsError::InvalidSignature(format!(
+                        "Revision {version} was signed by {key} after it was superseded"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_did(field: &str, did: &str) -> Result<Did, DialogArtifactsError> {
+    did.parse().map_err(|_| {
+        DialogArtifactsError::InvalidSignature(format!(
+            "Revision record {field} {did} is not a DID"
+        ))
+    })
+}
+
+/// Re-issue a predecessor's delegations under its successor's key, as one
+/// commit. Created by [`Delegations::migrate`].
+pub struct MigrateDelegations<'a, I> {
+    branch: &'a Branch,
+    succession: Succession<AnySignature>,
+    successor: I,
+}
+
+impl<I> MigrateDelegations<'_, I>
+where
+    I: Issuer<AnySignature> + Clone,
+{
+    /// Execute the migration, returning each re-issued delegation as a chain
+    /// rooted in the predecessor: the succession's handover chain extended
+    /// by it. Empty when there was nothing left to migrate (a no-op that
+    /// mints no revision).
+    ///
+    /// A retained delegation whose envelope is missing, undecodable or not
+    /// signed by the predecessor is left alone: re-signing it would lend the
+    /// successor's signature to a forgery.
+    pub async fn perform<Env>(self, env: &Env) -> Result<Vec<UcanDelegation>, CommitError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Import>
+            + Provider<Resolve>
+            + Provider<Publish>
+            + Provider<Identify>
+            + Provider<Attest>
+            + Provider<BlobRead>
+            + Provider<BlobWrite>
+            + Provider<BlobImport>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + Provider<Fork<RemoteSite, BlobRead>>
+            + ConditionalSync
+            + 'static,
+    {
+        let invalid =
+            |detail: String| CommitError::Artifact(DialogArtifactsError::InvalidValue(detail));
+        let branch = self.branch;
+        let succession = &self.succession;
+        let predecessor = succession.predecessor();
+        if &self.successor.did() != succession.successor() {
+            return Err(invalid(format!(
+                "'{}' is not the successor of '{predecessor}'",
+                self.successor.did()
+            )));
+        }
+        succession
+            .verify_signature(&dialog_credentials::DidKeyResolver)
+            .await
+            .map_err(|error| invalid(error.to_string()))?;
+        let handover = succession
+            .handover_chain()
+            .map_err(|error| invalid(error.to_string()))?;
+
+        let Some(revision) = branch.revision() else {
+            return Ok(Vec::new());
+        };
+        let store = index_store(branch, env).await;
+        let tree = Index::from_hash(NodeHash::from(*revision.tree.hash()));
+
+        let issued = Select::new(
+            branch,
+            ArtifactSelector::new()
+                .the(DELEGATION_ISSUER.parse().expect("valid attribute"))
+                .is(Value::String(predecessor.to_string())),
+        )
+        .execute(store.clone())
+        .await
+        .map_err(|error| invalid(format!("delegation scan failed: {error}")))?;
+        futures_util::pin_mut!(issued);
+        let mut entities = Vec::new();
+        while let Some(item) = issued.next().await {
+            entities.push(item.and_then(|view| view.to_owned())?.of);
+        }
+
+        let mut instructions = Vec::new();
+        let mut entries = Vec::new();
+        let mut migrated = Vec::new();
+        for entity in entities {
+            let Some(index_hash) = entity.blob_hash() else {
+                continue;
+            };
+            let original = match read_envelope(branch, env, &entity).await {
+                Ok(bytes) => match UcanCertificate::decode(&bytes) {
+                    Ok(certificate) => certificate.0,
+                    Err(error) => {
+                        tracing::warn!(%entity, %error, "delegation envelope undecodable; not migrating it");
+                        continue;
+                    }
+                },
+                Err(error) => {
+                    tracing::warn!(%entity, %error, "delegation envelope unavailable; not migrating it");
+                    continue;
+                }
+            };
+            if original.issuer() != predecessor
+                || original
+                    .verify_signature(&dialog_credentials::DidKeyResolver)
+                    .await
+                    .is_err()
+            {
+                tracing::warn!(%entity, "delegation envelope is not the predecessor's; not migrating it");
+                continue;
+            }
+            // The links the succession itself relies on stay as they are.
+            if original.audience() == succession.successor()
+                || (succession.is_recovery() && original.audience() == succession.issuer())
+            {
+                continue;
+            }
+
+            let reissued = reissue(&original, self.successor.clone())
+                .await
+                .map_err(invalid)?;
+            let certificate = UcanCertificate(reissued.clone());
+            let bytes = encode(&certificate)?;
+            let mut sink = branch.archive().blob().write().perform(env).await?;
+            sink.write_all(&bytes).await?;
+            let hash = sink.finish().await?;
+            let reissued_hash: dialog_storage::Blake3Hash = *hash.as_bytes();
+            let reissued_entity = Entity::from_blob(&reissued_hash)?;
+            for artifact in field_artifacts(&reissued_entity, &certificate)? {
+                instructions.push(Instruction::Assert(artifact));
+            }
+            entries.push(BlobRecord::new(bytes.len() as u64).entry(&reissued_hash));
+
+            for artifact in field_artifacts(&entity, &UcanCertificate(original))? {
+                instructions.push(Instruction::Retract(artifact));
+            }
+            entries.push(BlobRecord::retract_entry(&index_hash));
+
+            migrated.push(UcanDelegation::new(
+                handover
+                    .push(reissued)
+                    .map_err(|error| invalid(error.to_string()))?,
+            ));
+        }
+
+        if migrated.is_empty() {
+            return Ok(migrated);
+        }
+
+        // The re-issued delegations descend from the handover, so it rides
+        // along in case the succession was recorded on another branch.
+        let mut batched = HashSet::new();
+        for certificate in carried(succession) {
+            let bytes = encode(&certificate)?;
+            let mut sink = branch.archive().blob().write().perform(env).await?;
+            sink.write_all(&bytes).await?;
+            let hash = sink.finish().await?;
+            let index_hash: dialog_storage::Blake3Hash = *hash.as_bytes();
+            if !batched.insert(index_hash) || tree.get_blob(&store, &index_hash).await?.is_some() {
+                continue;
+            }
+            let entity = Entity::from_blob(&index_hash)?;
+            for artifact in field_artifacts(&entity, &certificate)? {
+                instructions.push(Instruction::Assert(artifact));
+            }
+            entries.push(BlobRecord::new(bytes.len() as u64).entry(&index_hash));
+        }
+
+        Box::pin(
+            branch
+                .commit(stream::iter(instructions))
+                .machinery()
+                .with_entries(entries)
+                .perform(env),
+        )
+        .await?;
+
+        Ok(migrated)
+    }
+}
+
+/// `original` with `issuer` in place of its issuer: same audience, subject,
+/// command, policy, validity window and meta, under a fresh nonce.
+async fn reissue<I: Issuer<AnySignature>>(
+    original: &Delegation<AnySignature>,
+    issuer: I,
+) -> Result<Delegation<AnySignature>, String> {
+    let mut builder = DelegationBuilder::new()
+        .issuer(issuer)
+        .audience(original.audience())
+        .subject(original.subject().clone())
+        .command(original.command().segments().clone())
+        .policy(original.policy().clone());
+    if let Some(expiration) = original.expiration() {
+        builder = builder.expiration(expiration);
+    }
+    if let Some(not_before) = original.not_before() {
+        builder = builder.not_before(not_before);
+    }
+    if !original.meta().is_empty() {
+        builder = builder.meta(original.meta().clone());
+    }
+    builder
+        .try_build()
+        .await
+        .map_err(|error| format!("failed to re-issue '{}': {error}", original.to_cid()))
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::repository::branch::delegation::tests::{open_branch, open_branch_with_profile};
+    use crate::{PullError, RepositoryMemoryExt as _};
+    use anyhow::Result;
+    use dialog_artifacts::history::History as _;
+    use dialog_credentials::{Ed25519Signer, Signer};
+    use dialog_remote_s3::{Address, S3Error};
+    use dialog_remote_ucan_s3::UcanAuthorizer;
+    use dialog_ucan_core::promise::Promised;
+    use dialog_ucan_core::subject::Subject as UcanSubject;
+    use dialog_ucan_core::{DelegationChain, InvocationBuilder, InvocationChain};
+    use dialog_varsig::Principal as _;
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::Arc;
+
+    async fn delegate(
+        issuer: &Ed25519Signer,
+        audience: &Ed25519Signer,
+        subject: &Ed25519Signer,
+    ) -> Delegation<AnySignature> {
+        DelegationBuilder::new()
+            .issuer(Signer::from(issuer.clone()))
+            .audience(audience)
+            .subject(UcanSubject::Specific(subject.did()))
+            .command(vec!["archive".to_string()])
+            .try_build()
+            .await
+            .unwrap()
+    }
+
+    async fn rotate(
+        predecessor: &Ed25519Signer,
+        successor: &Ed25519Signer,
+    ) -> Succession<AnySignature> {
+        Succession::rotate(Signer::from(predecessor.clone()), successor)
+            .await
+            .unwrap()
+    }
+
+    /// `invoker` reads from `space`'s archive on the strength of `proofs`,
+    /// root first.
+    async fn invoke(
+        invoker: &Ed25519Signer,
+        space: &Ed25519Signer,
+        proofs: &[Delegation<AnySignature>],
+    ) -> Vec<u8> {
+        let mut args = BTreeMap::new();
+        args.insert("catalog".to_string(), Promised::String("blobs".to_string()));
+        args.insert("digest".to_string(), Promised::Bytes([0u8; 32].to_vec()));
+        let invocation = InvocationBuilder::new()
+            .issuer(Signer::from(invoker.clone()))
+            .audience(space)
+            .subject(space)
+            .command(vec!["archive".to_string(), "get".to_string()])
+            .arguments(args)
+            .proofs(proofs.iter().map(Delegation::to_cid).collect())
+            .try_build()
+            .await
+            .unwrap();
+        let delegations: HashMap<_, _> = proofs
+            .iter()
+            .map(|delegation| (delegation.to_cid(), Arc::new(delegation.clone())))
+            .collect();
+        InvocationChain::new(invocation, delegations)
+            .to_bytes()
+            .unwrap()
+    }
+
+    fn address() -> Address {
+        Address::builder("https://s3.us-east-1.amazonaws.com")
+            .region("us-east-1")
+            .bucket("test-bucket")
+            .build()
+            .unwrap()
+    }
+
+    #[dialog_common::test]
+    async fn it_records_a_succession_with_its_handover() -> Result<()> {
+        let (branch, operator) = open_branch("succession-record").await?;
+        let old = Ed25519Signer::generate().await?;
+        let new = Ed25519Signer::generate().await?;
+        let succession = rotate(&old, &new).await;
+
+        let entity = branch
+            .delegations()
+            .succeed(succession.clone())
+            .perform(&operator)
+            .await?
+            .expect("a new succession is recorded");
+
+        let facts: Vec<_> = branch
+            .claims()
+            .select(ArtifactSelector::new().of(entity))
+            .perform(&operator)
+            .await?
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .map(|item| item.and_then(|view| view.to_owned()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let get = |attribute: &str| {
+            facts
+                .iter()
+                .find(|fact| fact.the.as_str() == attribute)
+                .map(|fact| fact.is.clone())
+        };
+        assert_eq!(
+            get(SUCCESSION_PREDECESSOR),
+            Some(Value::String(old.did().to_string()))
+        );
+        assert_eq!(
+            get(SUCCESSION_SUCCESSOR),
+            Some(Value::String(new.did().to_string()))
+        );
+
+        // The handover is retained in the same commit, like any delegation.
+        let handover: Vec<_> = branch
+            .claims()
+            .select(
+                ArtifactSelector::new()
+                    .the(DELEGATION_ISSUER.parse()?)
+                    .is(Value::String(old.did().to_string())),
+            )
+            .perform(&operator)
+            .await?
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(handover.len(), 1);
+
+        // Recording it again is a no-op that mints no revision.
+        let head = branch.revision().map(|revision| revision.version());
+        let again = branch
+            .delegations()
+            .succeed(succession)
+            .perform(&operator)
+            .await?;
+        assert!(again.is_none());
+        assert_eq!(branch.revision().map(|revision| revision.version()), head);
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_denies_a_superseded_key_until_its_delegations_migrate() -> Result<()> {
+        let (branch, operator) = open_branch("succession-authorize").await?;
+        let space = Ed25519Signer::generate().await?;
+        let alice = Ed25519Signer::generate().await?;
+        let rotated = Ed25519Signer::generate().await?;
+        let bob = Ed25519Signer::generate().await?;
+
+        let root = delegate(&space, &alice, &space).await;
+        let leaf = delegate(&alice, &bob, &space).await;
+        branch
+            .delegations()
+            .retain(UcanDelegation::new(DelegationChain::new(leaf.clone())))
+            .perform(&operator)
+            .await?;
+        let container = invoke(&bob, &space, &[root.clone(), leaf.clone()]).await;
+
+        let authorizer = UcanAuthorizer::new(address(), None)
+            .with_successions(branch.delegations().successions(operator.clone()));
+        authorizer.authorize(&container).await?;
+
+        // Once Alice rotates, what her old key granted Bob no longer counts.
+        let succession = rotate(&alice, &rotated).await;
+        branch
+            .delegations()
+            .succeed(succession.clone())
+            .perform(&operator)
+            .await?;
+        let result = authorizer.authorize(&container).await;
+        assert!(
+            matches!(
+                result,
+                Err(S3Error::Authorization(AuthorizeError::Revoked { .. }))
+            ),
+            "expected a succession refusal, got {result:?}"
+        );
+
+        // Only the successor's key can migrate the grants.
+        let result = branch
+            .delegations()
+            .migrate(succession.clone(), Signer::from(bob.clone()))
+            .perform(&operator)
+            .await;
+        assert!(result.is_err());
+
+        // Migrated, Bob's grant runs through the handover to Alice's new
+        // key, and the old grant is retracted.
+        let migrated = branch
+            .delegations()
+            .migrate(succession.clone(), Signer::from(rotated.clone()))
+            .perform(&operator)
+            .await?;
+        assert_eq!(migrated.len(), 1);
+        let chain = migrated[0].chain();
+        assert_eq!(chain.issuer(), &alice.did());
+        assert_eq!(chain.audience(), &bob.did());
+
+        let mut proofs = vec![root];
+        proofs.extend(chain.proofs().cloned());
+        let container = invoke(&bob, &space, &proofs).await;
+        authorizer.authorize(&container).await?;
+
+        // Nothing is left to migrate.
+        let head = branch.revision().map(|revision| revision.version());
+        let again = branch
+            .delegations()
+            .migrate(succession, Signer::from(rotated))
+            .perform(&operator)
+            .await?;
+        assert!(again.is_empty());
+        assert_eq!(branch.revision().map(|revision| revision.version()), head);
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_lets_a_recovery_settle_conflicting_rotations() -> Result<()> {
+        let (branch, operator) = open_branch("succession-recovery").await?;
+        let alice = Ed25519Signer::generate().await?;
+        let backup = Ed25519Signer::generate().await?;
+        let thief = Ed25519Signer::generate().await?;
+        let recovered = Ed25519Signer::generate().await?;
+        let registration =
+            Succession::<AnySignature>::register_recovery(Signer::from(alice.clone()), &backup)
+                .await?;
+        let successions = branch.delegations().successions(operator.clone());
+
+        branch
+            .delegations()
+            .succeed(rotate(&alice, &recovered).await)
+            .perform(&operator)
+            .await?;
+        let found = successions.query(&alice.did()).await?.expect("superseded");
+        assert_eq!(found.successor, Some(recovered.did()));
+
+        // A stolen key rotating elsewhere leaves the succession contested.
+        branch
+            .delegations()
+            .succeed(rotate(&alice, &thief).await)
+            .perform(&operator)
+            .await?;
+        let found = successions.query(&alice.did()).await?.expect("superseded");
+        assert_eq!(found.successor, None);
+        assert!(!found.admits(&thief.did()));
+
+        // The registered backup settles it, and is admitted alongside the
+        // successor.
+        let recovery =
+            Succession::recover(Signer::from(backup.clone()), registration, &recovered).await?;
+        branch
+            .delegations()
+            .succeed(recovery)
+            .perform(&operator)
+            .await?;
+        let found = successions.query(&alice.did()).await?.expect("superseded");
+        assert_eq!(found.successor, Some(recovered.did()));
+        assert_eq!(found.issuer, backup.did());
+        assert!(found.admits(&backup.did()));
+        assert!(!found.admits(&thief.did()));
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_follows_a_lineage_of_rotations() -> Result<()> {
+        let (branch, operator) = open_branch("succession-lineage").await?;
+        let first = Ed25519Signer::generate().await?;
+        let second = Ed25519Signer::generate().await?;
+        let third = Ed25519Signer::generate().await?;
+        for succession in [rotate(&first, &second).await, rotate(&second, &third).await] {
+            branch
+                .delegations()
+                .succeed(succession)
+                .perform(&operator)
+                .await?;
+        }
+
+        // Revisions signed before the rotations still verify: attribution
+        // moves, signatures do not.
+        branch.revision().expect("committed").verify()?;
+
+        let lineage = branch
+            .delegations()
+            .successions(operator.clone())
+            .lineage(&first.did())
+            .await?;
+        assert_eq!(lineage, vec![first.did(), second.did(), third.did()]);
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_attributes_history_across_a_rotation() -> Result<()> {
+        let (branch, operator, profile) = open_branch_with_profile("succession-history").await?;
+        let revision = branch
+            .commit(stream::iter(vec![Instruction::Assert(Artifact {
+                the: "post/title".parse()?,
+                of: "post:1".parse()?,
+                is: Value::String("Hej".to_string()),
+                cause: None,
+            })]))
+            .perform(&operator)
+            .await?;
+
+        // The profile's key signed for that revision; now it rotates.
+        let successor = Ed25519Signer::generate().await?;
+        branch
+            .delegations()
+            .succeed(profile.access().rotate(&successor).await?)
+            .perform(&operator)
+            .await?;
+
+        let record = branch
+            .history(&operator)
+            .revision_record(&revision.version())
+            .await?
+            .expect("the revision record is retrievable");
+        let successions = branch.delegations().successions(operator.clone());
+        successions
+            .verify_record(&record, &revision.version(), &successor.did())
+            .await?;
+        successions
+            .verify_record(&record, &revision.version(), &profile.did())
+            .await?;
+
+        let stranger = Ed25519Signer::generate().await?;
+        assert!(
+            successions
+                .verify_record(&record, &revision.version(), &stranger.did())
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_refuses_a_pulled_head_signed_after_its_key_was_superseded() -> Result<()> {
+        let (main, operator, profile) = open_branch_with_profile("succession-pull").await?;
+        let post = |title: &str| {
+            Ok::<_, anyhow::Error>(Instruction::Assert(Artifact {
+                the: "post/title".parse()?,
+                of: "post:1".parse()?,
+                is: Value::String(title.to_string()),
+                cause: None,
+            }))
+        };
+        main.commit(stream::iter(vec![post("Hej")?]))
+            .perform(&operator)
+            .await?;
+        let feature = main
+            .subject()
+            .branch("feature")
+            .open()
+            .perform(&operator)
+            .await?;
+        feature.pull().from(&main).perform(&operator).await?;
+        let stale = main
+            .subject()
+            .branch("stale")
+            .open()
+            .perform(&operator)
+            .await?;
+        stale.pull().from(&main).perform(&operator).await?;
+
+        // The revision recording the rotation is the old key's last word,
+        // so pulling it is fine.
+        let successor = Ed25519Signer::generate().await?;
+        main.delegations()
+            .succeed(profile.access().rotate(&successor).await?)
+            .perform(&operator)
+            .await?;
+        feature.pull().from(&main).perform(&operator).await?;
+        let adopted = feature.revision();
+
+        // Anything the old key signs once it has seen the rotation is not.
+        main.commit(stream::iter(vec![post("Hallo")?]))
+            .perform(&operator)
+            .await?;
+        let refused = feature.pull().from(&main).perform(&operator).await;
+        assert!(
+            matches!(
+                refused,
+                Err(PullError::Artifact(DialogArtifactsError::InvalidSignature(
+                    _
+                )))
+            ),
+            "expected the pull to be refused, got {refused:?}"
+        );
+        assert_eq!(feature.revision(), adopted);
+
+        // Nor is what it signs without having seen the rotation, on a
+        // branch the rotation never reached.
+        stale
+            .commit(stream::iter(vec![post("Hallå")?]))
+            .perform(&operator)
+            .await?;
+        let concurrent = feature.pull().from(&stale).perform(&operator).await;
+        assert!(
+            matches!(
+                concurrent,
+                Err(PullError::Artifact(DialogArtifactsError::InvalidSignature(
+                    _
+                )))
+            ),
+            "expected the pull to be refused, got {concurrent:?}"
+        );
+        assert_eq!(feature.revision(), adopted);
+        Ok(())
+    }
+}
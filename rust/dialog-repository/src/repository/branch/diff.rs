@@ -0,0 +1,231 @@
+use dialog_artifacts::selector::Constrained;
+use dialog_artifacts::{ArtifactChange, ArtifactSelector, DialogArtifactsError, artifact_changes};
+use dialog_capability::{Fork, Provider};
+use dialog_common::Blake3Hash as NodeHash;
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::prelude::ArchiveSubjectExt as _;
+use dialog_effects::archive::{Get, Put};
+use dialog_effects::memory::Resolve;
+use dialog_varsig::Principal;
+use futures_util::Stream;
+
+use crate::{
+    Branch, Index, NetworkedIndex, RemoteFallback, RemoteSite, RepositoryArchiveExt as _,
+    RepositoryMemoryExt, Revision, Snapshot,
+};
+
+/// Command struct for listing the facts that changed between two
+/// revisions.
+///
+/// Created by [`Branch::diff`] or [`Snapshot::diff`]. Reads only the parts
+/// of the two trees that differ (see [`artifact_changes`] for how entries
+/// become [`ArtifactChange`]s), so the cost of "what changed in this
+/// commit" follows the size of the commit, not of the index. Facts under
+/// reserved attributes (see [`Attribute::is_reserved`]) are written by
+/// the machinery on every commit and are left out.
+///
+/// [`Attribute::is_reserved`]: dialog_artifacts::Attribute::is_reserved
+pub struct Diff<'a> {
+    branch: &'a Branch,
+    from: Revision,
+    to: Revision,
+    selector: Option<ArtifactSelector<Constrained>>,
+}
+
+impl Branch {
+    /// Create a command listing the facts that changed from revision `from`
+    /// to revision `to`.
+    ///
+    /// Neither revision has to be this branch's head, or on its lineage:
+    /// the branch supplies the store both trees are read from, and the
+    /// upstream a read that misses locally falls back to.
+    pub fn diff(&self, from: &Revision, to: &Revision) -> Diff<'_> {
+        Diff {
+            branch: self,
+            from: from.clone(),
+            to: to.clone(),
+            selector: None,
+        }
+    }
+}
+
+impl<C: Principal> Snapshot<'_, C> {
+    /// Create a command listing the facts that changed from this snapshot
+    /// to revision `to`, read through the same branch view queries against
+    /// this snapshot use.
+    pub fn diff(&self, to: &Revision) -> Diff<'_> {
+        self.view().diff(self.revision(), to)
+    }
+}
+
+impl<'a> Diff<'a> {
+    /// Only yield changes to facts `selector` matches.
+    pub fn matching(mut self, selector: ArtifactSelector<Constrained>) -> Self {
+        self.selector = Some(selector);
+        self
+    }
+
+    /// Stream the changes, in entity then attribute order.
+    pub fn perform<Env>(
+        self,
+        env: &'a Env,
+    ) -> impl Stream<Item = Result<ArtifactChange, DialogArtifactsError>> + 'a
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let Diff {
+            branch,
+            from,
+            to,
+            selector,
+        } = self;
+        async_stream::try_stream! {
+            let upstreams = branch.upstreams();
+            let remote = match upstreams.remote_name() {
+                Some(name) => {
+                    let loaded = branch
+                        .subject()
+                        .remote(name.to_string())
+                        .load()
+                        .perform(env)
+                        .await;
+                    RemoteFallback::from_load(name, loaded)
+                }
+                None => RemoteFallback::None,
+            };
+            let store = NetworkedIndex::new(env, branch.subject().archive().index(), remote);
+
+            let source =
+                Index::from_hash_with_cache(NodeHash::from(*from.tree.hash()), branch.node_cache());
+            let target =
+                Index::from_hash_with_cache(NodeHash::from(*to.tree.hash()), branch.node_cache());
+
+            let changes = artifact_changes(source, target, store, selector);
+            futures_util::pin_mut!(changes);
+            for await change in changes {
+                let change = change?;
+                // Every commit also rewrites machinery facts (its revision
+                // record among them); those are bookkeeping, not changes
+                // anyone committed.
+                if change.the().is_reserved() {
+                    continue;
+                }
+                yield change;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::helpers::test_repo;
+    use anyhow::Result;
+    use dialog_artifacts::{Artifact, Instruction, Value};
+    use dialog_operator::helpers::test_operator_with_profile;
+    use futures_util::{TryStreamExt, stream};
+
+    fn fact(of: &str, the: &str, is: &str) -> Artifact {
+        Artifact {
+            the: the.parse().unwrap(),
+            of: of.parse().unwrap(),
+            is: Value::String(is.to_string()),
+            cause: None,
+        }
+    }
+
+    fn bare(changes: Vec<ArtifactChange>) -> Vec<ArtifactChange> {
+        let bare = |artifact: Artifact| Artifact {
+            cause: None,
+            ..artifact
+        };
+        changes
+            .into_iter()
+            .map(|change| match change {
+                ArtifactChange::Asserted(artifact) => ArtifactChange::Asserted(bare(artifact)),
+                ArtifactChange::Retracted(artifact) => ArtifactChange::Retracted(bare(artifact)),
+                ArtifactChange::Replaced { from, to } => ArtifactChange::Replaced {
+                    from: bare(from),
+                    to: bare(to),
+                },
+            })
+            .collect()
+    }
+
+    #[dialog_common::test]
+    async fn it_diffs_two_revisions_at_the_fact_level() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let alice = fact("id:alice", "user/name", "Alice");
+        let bob = fact("id:bob", "user/name", "Bob");
+        let first = branch
+            .commit(stream::iter(vec![
+                Instruction::Assert(alice.clone()),
+                Instruction::Assert(bob.clone()),
+            ]))
+            .perform(&operator)
+            .await?;
+
+        let renamed = fact("id:alice", "user/name", "Alicia");
+        let second = branch
+            .commit(stream::iter(vec![
+                Instruction::Replace(renamed.clone()),
+                Instruction::Retract(bob.clone()),
+            ]))
+            .perform(&operator)
+            .await?;
+
+        let changes: Vec<_> = branch
+            .diff(&first, &second)
+            .perform(&operator)
+            .try_collect()
+            .await?;
+        assert_eq!(
+            bare(changes),
+            vec![
+                ArtifactChange::Replaced {
+                    from: alice.clone(),
+                    to: renamed.clone(),
+                },
+                ArtifactChange::Retracted(bob.clone()),
+            ]
+        );
+
+        let changes: Vec<_> = branch
+            .diff(&first, &second)
+            .matching(ArtifactSelector::new().of(bob.of.clone()))
+            .perform(&operator)
+            .try_collect()
+            .await?;
+        assert_eq!(bare(changes), vec![ArtifactChange::Retracted(bob.clone())]);
+
+        let snapshot = branch.snapshot(second.clone());
+        let changes: Vec<_> = snapshot
+            .diff(&first)
+            .perform(&operator)
+            .try_collect()
+            .await?;
+        assert_eq!(
+            bare(changes),
+            vec![
+                ArtifactChange::Replaced {
+                    from: renamed,
+                    to: alice,
+                },
+                ArtifactChange::Asserted(bob),
+            ]
+        );
+        Ok(())
+    }
+}
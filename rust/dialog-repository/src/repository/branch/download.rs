@@ -22,7 +22,6 @@ use dialog_effects::blob::{Import as BlobImport, Read as BlobRead};
 use dialog_effects::memory::{Publish, Resolve};
 use futures_util::StreamExt as _;
 
-use crate::repository::snapshot::Snapshot;
 use crate::{
     Branch, DownloadError, Pull, PullError, RemoteSite, RepositoryMemoryExt as _, Revision,
     Upstream,
@@ -96,7 +95,8 @@ impl Download<'_> {
         // The items themselves carry nothing the local store does not
         // already hold by the time they are yielded, so draining the
         // stream is the whole job.
-        let items = Snapshot::of(branch.subject(), revision)
+        let items = branch
+            .snapshot(revision)
             .export()
             .download(remote)
             .perform(env);
@@ -1347,7 +1347,7 @@ async fn it_keeps_a_retraction_through_a_concurrent_pull(s3: S3Address) -> Resul
 async fn it_pushes_novelty_after_adopting_the_upstream_head_by_reference(
     s3: S3Address,
 ) -> Result<()> {
-    use crate::helpers::Counting;
+    use crate::Counting;
 
     let (operator, profile) = test_operator_with_profile().await;
 
@@ -3632,3 +3632,90 @@ async fn it_downloads_spilled_values_a_pull_never_shipped(s3: S3Address) -> Resu
     }
     Ok(())
 }
+
+/// A tag pushed to a remote is adopted by a replica pulling it, signature
+/// and all; a tag whose tree the remote lacks is refused on push.
+#[dialog_common::test]
+async fn it_pushes_and_pulls_tags_through_s3_remote(s3: S3Address) -> Result<()> {
+    let (operator, profile) = test_operator_with_profile().await;
+    let (repo_a, branch) = setup_repo_with_s3_remote(&operator, &profile, &s3, "tag-a").await?;
+    let origin_a = repo_a.remote("origin").load().perform(&operator).await?;
+
+    let artifact = Artifact {
+        the: "user/name".parse()?,
+        of: "user:1".parse()?,
+        is: Value::String("Alice".into()),
+        cause: None,
+    };
+    let revision = branch
+        .commit(stream::iter(vec![Instruction::Assert(artifact.clone())]))
+        .perform(&operator)
+        .await?;
+    repo_a
+        .tag("release")
+        .create(revision.clone())
+        .annotate("first release")
+        .perform(&operator)
+        .await?;
+
+    let unpushed = repo_a
+        .tag("release")
+        .push(&origin_a)
+        .perform(&operator)
+        .await;
+    assert!(
+        matches!(unpushed, Err(crate::PushTagError::Unreplicated { .. })),
+        "the tagged tree is not on the remote yet: {unpushed:?}"
+    );
+
+    branch.push().perform(&operator).await?;
+    let pushed = repo_a
+        .tag("release")
+        .push(&origin_a)
+        .perform(&operator)
+        .await?;
+    // Pushing again is a no-op: the remote already holds this tag.
+    repo_a
+        .tag("release")
+        .push(&origin_a)
+        .perform(&operator)
+        .await?;
+
+    let repo_b = profile
+        .repository(unique_name("tag-b"))
+        .create()
+        .perform(&operator)
+        .await?;
+    let origin_b = repo_b
+        .remote("origin")
+        .create(s3_site_address(&s3))
+        .subject(repo_a.did())
+        .perform(&operator)
+        .await?;
+    let main_b = repo_b.branch("main").open().perform(&operator).await?;
+    let pulled = repo_b
+        .tag("release")
+        .pull(&origin_b, &main_b)
+        .perform(&operator)
+        .await?;
+    assert_eq!(pulled, pushed);
+    assert_eq!(
+        repo_b
+            .tag("release")
+            .load()
+            .perform(&operator)
+            .await?
+            .revision,
+        revision
+    );
+    assert_eq!(repo_b.tags().perform(&operator).await?, vec!["release"]);
+
+    let missing = repo_b
+        .tag("missing")
+        .pull(&origin_b, &main_b)
+        .perform(&operator)
+        .await;
+    assert!(matches!(missing, Err(crate::PullTagError::NotFound { .. })));
+
+    Ok(())
+}
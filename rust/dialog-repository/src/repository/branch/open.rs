@@ -1,11 +1,12 @@
 use std::sync::{Arc, Mutex};
 
+use super::RegistryCache;
 use crate::rules::RuleCache;
-use crate::{Branch, BranchReference, Overlay, ResolveError};
-use dialog_artifacts::history::{CausalityCache, ContextCache};
+use crate::{Branch, BranchReference, Cell, Overlay, ResolveError, Revision, Upstreams};
+use dialog_artifacts::history::{CausalityCache, ContextCache, Retention};
 use dialog_artifacts::tree::spill_cache;
 use dialog_capability::Provider;
-use dialog_effects::memory::Resolve;
+use dialog_effects::memory::{Edition, Resolve, Version};
 use dialog_query::concept::query::PlanCache;
 
 /// Command to open a branch. Resolves the branch's revision and upstream
@@ -36,11 +37,34 @@ impl OpenBranch {
         let induction = self.branch.induction();
         induction.resolve().perform(env).await?;
 
-        Ok(Branch {
-            reference: self.branch,
+        let retention = self.branch.retention();
+        retention.resolve().perform(env).await?;
+
+        Ok(Branch::assemble(
+            self.branch,
+            revision,
+            upstream,
+            induction,
+            retention,
+        ))
+    }
+}
+
+impl Branch {
+    /// A branch over already-resolved cells, with fresh caches.
+    fn assemble(
+        reference: BranchReference,
+        revision: Cell<Revision>,
+        upstream: Cell<Upstreams>,
+        induction: Cell<Revision>,
+        retention: Cell<Retention>,
+    ) -> Self {
+        Branch {
+            reference,
             revision,
             upstream,
             induction,
+            retention,
             node_cache: dialog_search_tree::Cache::new(),
             spill_cache: spill_cache(),
             rule_cache: Arc::new(RuleCache::new()),
@@ -48,11 +72,58 @@ impl OpenBranch {
             causality_cache: CausalityCache::new(),
             context_cache: ContextCache::new(),
             record_cache: dialog_search_tree::Cache::new(),
+            registry_cache: RegistryCache::default(),
             spine: dialog_artifacts::SpineSlot::new(),
             identity_cache: Arc::new(Mutex::new(None)),
             overlay: Overlay::default(),
-        })
+        }
     }
+
+    /// A read-only view of `reference` pinned at `revision`, with no
+    /// upstream and fresh caches. Nothing is resolved: the view's cells
+    /// are seeded in memory and never published, so the branch the
+    /// reference names is neither read nor moved.
+    pub(crate) fn detached(reference: BranchReference, revision: Revision) -> Self {
+        Branch::assemble(
+            reference.clone(),
+            pinned(reference.revision(), revision),
+            reference.upstream(),
+            reference.induction(),
+            reference.retention(),
+        )
+    }
+
+    /// A read-only view of this branch pinned at `revision`.
+    ///
+    /// The view keeps this branch's upstreams, so its reads fall back to
+    /// the same remote, and shares its content-addressed caches, which
+    /// are safe across revisions. It starts with an empty session
+    /// overlay: transient facts belong to the live branch, not to the
+    /// state it held at some other revision.
+    pub(crate) fn pinned(&self, revision: Revision) -> Self {
+        let reference = self.reference.clone();
+        Branch {
+            revision: pinned(reference.revision(), revision),
+            upstream: pinned(reference.upstream(), self.upstreams()),
+            induction: reference.induction(),
+            retention: reference.retention(),
+            spine: dialog_artifacts::SpineSlot::new(),
+            overlay: Overlay::default(),
+            reference,
+            ..self.clone()
+        }
+    }
+}
+
+/// `cell` seeded with `content` in memory only. The edition's version is
+/// empty: a pinned cell is never published, so it never CAS'es against
+/// it.
+fn pinned<T: Clone>(cell: Cell<T>, content: T) -> Cell<T> {
+    cell.reset(Edition {
+        content,
+        version: Version::from(Vec::new()),
+    });
+    cell
 }
 
 #[cfg(test)]
@@ -24,8 +24,8 @@ use futures_util::stream;
 use dialog_artifacts::history::{Context, context_of};
 use dialog_artifacts::{Artifact, Instruction, Value};
 
+use crate::Counting;
 use crate::RepositoryExt as _;
-use crate::helpers::Counting;
 use dialog_artifacts::tree::TreeStorageBridge;
 use dialog_operator::helpers::{test_operator_with_profile, unique_name};
 
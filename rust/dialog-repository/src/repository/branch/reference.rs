@@ -1,3 +1,4 @@
+use dialog_artifacts::history::Retention;
 use dialog_capability::{Capability, Did, Policy, Subject};
 use dialog_effects::memory::Space;
 use dialog_effects::memory::prelude::SpaceExt;
@@ -65,6 +66,13 @@ impl BranchReference {
         self.cell("induction")
     }
 
+    /// The cell holding this branch's history [`Retention`] policy, if
+    /// one is set. Replica-local like the induction watermark: how much
+    /// history a replica keeps is its own affair.
+    pub fn retention(&self) -> Cell<Retention> {
+        self.cell("retention")
+    }
+
     /// Create a typed cell within this branch's space.
     pub fn cell<T>(&self, cell_name: impl Into<String>) -> Cell<T> {
         self.0.clone().cell(cell_name).into()
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use dialog_artifacts::{Artifact, ArtifactSelector, Attribute, EMPTY_TREE_HASH, TreeReference};
+use dialog_capability::{Fork, Provider};
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::{Get, Put};
+use dialog_effects::memory::Resolve;
+use dialog_query::AttributeDescriptor;
+use dialog_query::attribute::descriptor_attr;
+use futures_util::TryStreamExt as _;
+
+use super::validate::registered;
+use crate::{Branch, CommitError, RemoteSite};
+
+/// The attribute descriptors registered at one revision of a branch, by
+/// attribute.
+#[derive(Debug, Default)]
+pub(crate) struct Registry(HashMap<Attribute, AttributeDescriptor>);
+
+impl Registry {
+    /// The descriptor registered for `attribute`, if any.
+    pub(super) fn get(&self, attribute: &Attribute) -> Option<&AttributeDescriptor> {
+        self.0.get(attribute)
+    }
+
+    /// The descriptors of the attributes held to unique values.
+    pub(super) fn unique(&self) -> impl Iterator<Item = &AttributeDescriptor> {
+        self.0.values().filter(|descriptor| descriptor.is_unique())
+    }
+
+    /// The attributes whose values are indexed for text search.
+    pub(super) fn searchable(&self) -> impl Iterator<Item = &Attribute> {
+        self.0
+            .iter()
+            .filter(|(_, descriptor)| descriptor.is_searchable())
+            .map(|(attribute, _)| attribute)
+    }
+}
+
+/// Shared memo of a branch's [`Registry`], keyed by the tree root it was
+/// read at.
+///
+/// Commit validation and the text index both consult the registry on
+/// every commit; the memo spares them a registry scan each. A commit that
+/// leaves the registry alone carries the entry over to the tree it
+/// publishes, so the scan is paid again only after a commit that touches
+/// the registry, or a head the branch moved to some other way (a pull, a
+/// reset).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RegistryCache(Arc<Mutex<Option<Memo>>>);
+
+/// The registry, and the tree root it was read at.
+type Memo = (TreeReference, Arc<Registry>);
+
+impl RegistryCache {
+    fn get(&self, tree: &TreeReference) -> Option<Arc<Registry>> {
+        match &*self.0.lock().expect("registry cache lock poisoned") {
+            Some((at, registry)) if at == tree => Some(registry.clone()),
+            _ => None,
+        }
+    }
+
+    fn insert(&self, tree: TreeReference, registry: Arc<Registry>) {
+        *self.0.lock().expect("registry cache lock poisoned") = Some((tree, registry));
+    }
+
+    /// Carry the registry read at `base` over to `tree`, the tree a commit
+    /// built on it published, unless the commit `touched` the registry.
+    pub(super) fn advance(&self, base: &TreeReference, tree: TreeReference, touched: bool) {
+        let mut entry = self.0.lock().expect("registry cache lock poisoned");
+        *entry = match entry.take() {
+            Some((at, registry)) if !touched && at == *base => Some((tree, registry)),
+            _ => None,
+        };
+    }
+}
+
+impl Branch {
+    /// The attribute descriptors registered at this branch's current
+    /// revision, scanned once per revision (see [`RegistryCache`]).
+    pub(super) async fn registry<Env>(&self, env: &Env) -> Result<Arc<Registry>, CommitError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let tree = self
+            .revision()
+            .map(|revision| revision.tree)
+            .unwrap_or_else(|| TreeReference::from(EMPTY_TREE_HASH));
+        if let Some(registry) = self.registry_cache.get(&tree) {
+            return Ok(registry);
+        }
+        let rows: Vec<Artifact> = self
+            .claims()
+            .select(ArtifactSelector::new().the(descriptor_attr()))
+            .to_owned()
+            .perform(env)
+            .await?
+            .try_collect()
+            .await?;
+        let mut descriptors = HashMap::new();
+        for descriptor in rows.iter().filter_map(registered) {
+            descriptors
+                .entry(Attribute::from(descriptor.the()))
+                .or_insert(descriptor);
+        }
+        let registry = Arc::new(Registry(descriptors));
+        // Only what was read at the head still current is kept.
+        if self.revision().map(|revision| revision.tree) == Some(tree.clone()) {
+            self.registry_cache.insert(tree, registry.clone());
+        }
+        Ok(registry)
+    }
+
+    /// A shared handle to this branch's registry memo.
+    pub(super) fn registries(&self) -> RegistryCache {
+        self.registry_cache.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use crate::helpers::test_repo;
+    use anyhow::Result;
+    use dialog_artifacts::{Attribute, Entity, ValueDataType};
+    use dialog_operator::helpers::test_operator_with_profile;
+    use dialog_query::{AttributeDescriptor, Cardinality, the};
+
+    /// A commit that leaves the registry alone carries the memo over to
+    /// the head it publishes; one that registers a descriptor drops it,
+    /// and the next read sees the registration.
+    #[dialog_common::test]
+    async fn it_reads_the_registry_once_per_registration() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        let alice: Entity = "user:alice".parse()?;
+        let email: Attribute = "user/email".parse()?;
+
+        branch
+            .transaction()
+            .assert(
+                AttributeDescriptor::new(
+                    the!("user/email"),
+                    "A user's email address",
+                    Cardinality::One,
+                    Some(ValueDataType::String),
+                )
+                .unique(),
+            )
+            .commit()
+            .perform(&operator)
+            .await?;
+        let head = branch.revision().expect("committed").tree;
+        assert!(branch.registries().get(&head).is_none());
+        let registry = branch.registry(&operator).await?;
+        assert_eq!(registry.unique().count(), 1);
+
+        branch
+            .transaction()
+            .assert(the!("user/email").of(alice).is("a@example.com".to_string()))
+            .commit()
+            .perform(&operator)
+            .await?;
+        let head = branch.revision().expect("committed").tree;
+        let carried = branch.registries().get(&head).expect("carried over");
+        assert!(
+            carried
+                .get(&email)
+                .is_some_and(AttributeDescriptor::is_unique)
+        );
+
+        branch
+            .transaction()
+            .assert(AttributeDescriptor::new(
+                the!("user/name"),
+                "A user's name",
+                Cardinality::One,
+                Some(ValueDataType::String),
+            ))
+            .commit()
+            .perform(&operator)
+            .await?;
+        let head = branch.revision().expect("committed").tree;
+        assert!(branch.registries().get(&head).is_none());
+        assert!(
+            branch
+                .registry(&operator)
+                .await?
+                .get(&"user/name".parse()?)
+                .is_some()
+        );
+        Ok(())
+    }
+}
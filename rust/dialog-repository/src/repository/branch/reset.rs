@@ -1,7 +1,8 @@
 use dialog_capability::Provider;
-use dialog_effects::memory::Publish;
+use dialog_common::ConditionalSync;
+use dialog_effects::memory::{Publish, Resolve};
 
-use crate::{Branch, PublishError, Revision};
+use crate::{Branch, ResetError, Revision, WriteLease};
 
 /// Command that resets a branch to a given revision.
 ///
@@ -38,15 +39,24 @@ impl Branch {
 
 impl Reset<'_> {
     /// Execute the reset operation.
-    pub async fn perform<Env>(self, env: &Env) -> Result<(), PublishError>
+    ///
+    /// The head may name a tree nothing else reaches yet, so the publish
+    /// holds the archive against a garbage collection.
+    pub async fn perform<Env>(self, env: &Env) -> Result<(), ResetError>
     where
-        Env: Provider<Publish>,
+        Env: Provider<Publish> + Provider<Resolve> + ConditionalSync,
     {
-        self.branch
+        let lease = WriteLease::acquire(self.branch.subject(), env).await?;
+        let published = self
+            .branch
             .revision
             .publish(self.revision)
             .perform(env)
-            .await
+            .await;
+        let released = lease.release(env).await;
+        published?;
+        released?;
+        Ok(())
     }
 }
 
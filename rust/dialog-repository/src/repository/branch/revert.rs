@@ -0,0 +1,436 @@
+use std::collections::HashSet;
+
+use dialog_artifacts::history::{Claim, History as _, Record, Version};
+use dialog_artifacts::{Artifact, ArtifactSelector, DialogArtifactsError, Instruction};
+use dialog_capability::{Fork, Provider};
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::{Get, Import, Put};
+use dialog_effects::authority::{Attest, Identify};
+use dialog_effects::memory::{Publish, Resolve};
+use futures_util::{StreamExt as _, stream};
+
+use crate::{Branch, RemoteSite, RevertError, Revision};
+
+/// Command that undoes one revision from a branch's history.
+///
+/// Created by [`Branch::revert`]. Execute with `.perform(&env)`.
+///
+/// The revision's own changes are the records it wrote to the history
+/// region, and the revert commits their inverse on top of the head —
+/// nothing after the revision is reset away. Each inverse only undoes
+/// what the revision's claim still decides at the head, by the same
+/// observed-remove rules a merge applies (see [`dialog_artifacts::merge`]):
+///
+/// - an assertion is retracted while the revision's claim is the only
+///   one standing behind the fact. A fact another revision also claims
+///   stood without this one, and one a later revision superseded or
+///   withdrew no longer rests on it; both are left alone.
+/// - a replacement is rolled back to the values it superseded, on the
+///   same condition.
+/// - a retraction is undone by asserting the value again, unless a
+///   later revision has claimed the value since — asserting it anew
+///   (whether or not that assertion still stands) or retracting it too.
+///
+/// The inverse is an ordinary commit: restored values are fresh claims,
+/// and every retraction and replacement it makes records as its cause
+/// the claims standing at the head, exactly as if written by hand. A
+/// revision whose changes have all been overtaken reverts to nothing, and
+/// the branch keeps its head.
+pub struct Revert<'a> {
+    branch: &'a Branch,
+    revision: Revision,
+}
+
+impl Branch {
+    /// Create a command to undo what `revision` changed, keeping every
+    /// revision after it. `revision` must be in this branch's history.
+    pub fn revert(&self, revision: &Revision) -> Revert<'_> {
+        Revert {
+            branch: self,
+            revision: revision.clone(),
+        }
+    }
+}
+
+impl Revert<'_> {
+    /// Execute the revert, returning the branch's new head.
+    pub async fn perform<Env>(self, env: &Env) -> Result<Revision, RevertError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Import>
+            + Provider<Resolve>
+            + Provider<Publish>
+            + Provider<Identify>
+            + Provider<Attest>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let branch = self.branch;
+        let version = self.revision.version();
+        let history = branch.history(env);
+
+        if history.revision_record(&version).await?.is_none() {
+            if history.compacted().await?.observes(&version) {
+                return Err(DialogArtifactsError::IncompleteHistory(format!(
+                    "{version}: compacted into a checkpoint"
+                ))
+                .into());
+            }
+            return Err(RevertError::NotInHistory {
+                version: version.to_string(),
+                branch: branch.name().to_string(),
+            });
+        }
+
+        let records = history.records_at(&version).await?;
+        if let Some(record) = records
+            .iter()
+            .find(|record| record.claim().the.is_reserved())
+        {
+            return Err(RevertError::Reserved {
+                version: version.to_string(),
+                attribute: record.claim().the.to_string(),
+            });
+        }
+
+        // Retractions go first: a rollback that retracts a replacement
+        // and asserts what it superseded must not have the retraction
+        // withdraw a restored value.
+        let mut retractions = Vec::new();
+        let mut assertions = Vec::new();
+        for record in records {
+            let is_assertion = record.is_assertion();
+            let claim = match record {
+                Record::Assert(claim) | Record::Retract(claim) => claim,
+            };
+            let support = branch.support(env, &claim).await?;
+            if !is_assertion {
+                if support.is_empty() && !branch.reclaimed(env, &version, &claim).await? {
+                    assertions.push(Instruction::Assert(artifact(claim)));
+                }
+                continue;
+            }
+            if support != [version] {
+                continue;
+            }
+            if claim.cause.is_genesis() {
+                retractions.push(Instruction::Retract(artifact(claim)));
+                continue;
+            }
+
+            let mut superseded: Vec<Artifact> = Vec::new();
+            for cause in claim.cause.versions() {
+                for prior in history.records_at(cause).await? {
+                    let Record::Assert(prior) = prior else {
+                        continue;
+                    };
+                    if prior.of == claim.of
+                        && prior.the == claim.the
+                        && prior.is != claim.is
+                        && !superseded.iter().any(|value| value.is == prior.is)
+                    {
+                        superseded.push(artifact(prior));
+                    }
+                }
+            }
+            match superseded.as_slice() {
+                [] => {
+                    return Err(DialogArtifactsError::IncompleteHistory(format!(
+                        "{version}: the values it replaced are no longer recorded"
+                    ))
+                    .into());
+                }
+                [prior] => assertions.push(Instruction::Replace(prior.clone())),
+                _ => {
+                    retractions.push(Instruction::Retract(artifact(claim)));
+                    assertions.extend(superseded.into_iter().map(Instruction::Assert));
+                }
+            }
+        }
+
+        Ok(Box::pin(
+            branch
+                .commit(stream::iter(retractions.into_iter().chain(assertions)))
+                .perform(env),
+        )
+        .await?)
+    }
+}
+
+impl Branch {
+    /// The versions whose claims keep `claim`'s fact standing at the head,
+    /// in ascending order; empty when the fact is not standing.
+    async fn support<Env>(&self, env: &Env, claim: &Claim) -> Result<Vec<Version>, RevertError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let selector = ArtifactSelector::new()
+            .of(claim.of.clone())
+            .the(claim.the.clone())
+            .is(claim.is.clone());
+        let rows = self.claims().select(selector).perform(env).await?;
+        futures_util::pin_mut!(rows);
+        let Some(row) = rows.next().await.transpose()? else {
+            return Ok(Vec::new());
+        };
+        let mut versions: Vec<Version> = row
+            .datum()
+            .map(|datum| datum.versions().copied().collect())
+            .unwrap_or_default();
+        versions.sort();
+        Ok(versions)
+    }
+}
+
+impl Branch {
+    /// Whether a revision after `version` in this branch's history claims
+    /// `claim`'s value again. Support only reflects the head, so a value
+    /// re-asserted and then retracted since looks exactly like one nobody
+    /// touched; the history between `version` and the head tells them
+    /// apart.
+    ///
+    /// Walks back from the head through revision parents, pruning at
+    /// `version`'s edition: nothing at or below it can come after it.
+    async fn reclaimed<Env>(
+        &self,
+        env: &Env,
+        version: &Version,
+        claim: &Claim,
+    ) -> Result<bool, RevertError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let Some(head) = self.revision() else {
+            return Ok(false);
+        };
+        let history = self.history(env);
+        let mut frontier = vec![head.version()];
+        let mut seen = HashSet::from([head.version()]);
+        while let Some(later) = frontier.pop() {
+            if later.edition <= version.edition {
+                continue;
+            }
+            let claims = history.claims_at(&later, &claim.of, &claim.the).await?;
+            if claims.iter().any(|later| later.is == claim.is) {
+                return Ok(true);
+            }
+            let Some(record) = history.revision_record(&later).await? else {
+                continue;
+            };
+            for parent in record.parents {
+                if seen.insert(parent) {
+                    frontier.push(parent);
+                }
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// The fact a claim is about.
+pub(super) fn artifact(claim: Claim) -> Artifact {
+    Artifact {
+        the: claim.the,
+        of: claim.of,
+        is: claim.is,
+        cause: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::helpers::test_repo;
+    use anyhow::Result;
+    use dialog_artifacts::Value;
+    use dialog_operator::helpers::test_operator_with_profile;
+    use futures_util::TryStreamExt as _;
+
+    fn name(of: &str, is: &str) -> Artifact {
+        Artifact {
+            the: "user/name".parse().unwrap(),
+            of: of.parse().unwrap(),
+            is: Value::String(is.to_string()),
+            cause: None,
+        }
+    }
+
+    /// Every `user/name` standing at the head, as `(entity, name)`.
+    async fn names<Env>(branch: &Branch, env: &Env) -> Result<Vec<(String, Value)>>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let rows: Vec<Artifact> = branch
+            .claims()
+            .select(ArtifactSelector::new().the("user/name".parse()?))
+            .to_owned()
+            .perform(env)
+            .await?
+            .try_collect()
+            .await?;
+        let mut names: Vec<_> = rows
+            .into_iter()
+            .map(|row| (row.of.to_string(), row.is))
+            .collect();
+        names.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(names)
+    }
+
+    #[dialog_common::test]
+    async fn it_reverts_a_revision_from_the_middle_of_history() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        branch
+            .commit(stream::iter(vec![
+                Instruction::Assert(name("id:alice", "Alice")),
+                Instruction::Assert(name("id:bob", "Bob")),
+            ]))
+            .perform(&operator)
+            .await?;
+        let bad = branch
+            .commit(stream::iter(vec![
+                Instruction::Replace(name("id:alice", "Alicia")),
+                Instruction::Retract(name("id:bob", "Bob")),
+                Instruction::Assert(name("id:carol", "Carol")),
+            ]))
+            .perform(&operator)
+            .await?;
+        branch
+            .commit(stream::iter(vec![Instruction::Assert(name(
+                "id:dave", "Dave",
+            ))]))
+            .perform(&operator)
+            .await?;
+
+        let head = branch.revert(&bad).perform(&operator).await?;
+        assert_eq!(head.edition, bad.edition.successor().successor());
+        assert_eq!(
+            names(&branch, &operator).await?,
+            vec![
+                ("id:alice".to_string(), Value::String("Alice".into())),
+                ("id:bob".to_string(), Value::String("Bob".into())),
+                ("id:dave".to_string(), Value::String("Dave".into())),
+            ]
+        );
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_leaves_changes_a_later_revision_overtook() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        branch
+            .commit(stream::iter(vec![Instruction::Assert(name(
+                "id:alice", "Alice",
+            ))]))
+            .perform(&operator)
+            .await?;
+        let renamed = branch
+            .commit(stream::iter(vec![Instruction::Replace(name(
+                "id:alice", "Alicia",
+            ))]))
+            .perform(&operator)
+            .await?;
+        let head = branch
+            .commit(stream::iter(vec![Instruction::Replace(name(
+                "id:alice", "Ali",
+            ))]))
+            .perform(&operator)
+            .await?;
+
+        let reverted = branch.revert(&renamed).perform(&operator).await?;
+        assert_eq!(reverted, head, "nothing the rename decided still stands");
+        assert_eq!(
+            names(&branch, &operator).await?,
+            vec![("id:alice".to_string(), Value::String("Ali".into()))]
+        );
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_leaves_a_retraction_a_later_revision_repeated() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        branch
+            .commit(stream::iter(vec![Instruction::Assert(name(
+                "id:alice", "Alice",
+            ))]))
+            .perform(&operator)
+            .await?;
+        let retracted = branch
+            .commit(stream::iter(vec![Instruction::Retract(name(
+                "id:alice", "Alice",
+            ))]))
+            .perform(&operator)
+            .await?;
+        branch
+            .commit(stream::iter(vec![Instruction::Assert(name(
+                "id:alice", "Alice",
+            ))]))
+            .perform(&operator)
+            .await?;
+        let head = branch
+            .commit(stream::iter(vec![Instruction::Retract(name(
+                "id:alice", "Alice",
+            ))]))
+            .perform(&operator)
+            .await?;
+
+        let reverted = branch.revert(&retracted).perform(&operator).await?;
+        assert_eq!(reverted, head, "a later retraction decided the value");
+        assert!(names(&branch, &operator).await?.is_empty());
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_refuses_a_revision_outside_the_branch() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let main = repo.branch("main").open().perform(&operator).await?;
+        let feature = repo.branch("feature").open().perform(&operator).await?;
+
+        let elsewhere = feature
+            .commit(stream::iter(vec![Instruction::Assert(name(
+                "id:alice", "Alice",
+            ))]))
+            .perform(&operator)
+            .await?;
+
+        let result = main.revert(&elsewhere).perform(&operator).await;
+        assert!(
+            matches!(result, Err(RevertError::NotInHistory { .. })),
+            "got {result:?}"
+        );
+        Ok(())
+    }
+}
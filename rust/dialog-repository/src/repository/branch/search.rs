@@ -0,0 +1,525 @@
+//! Full-text search over a branch's searchable attributes.
+//!
+//! An attribute is searchable once its registered descriptor says so
+//! (see [`AttributeDescriptor::searchable`]). From then on every commit
+//! keeps the branch's text index in step with the attribute's string
+//! values: an assertion adds a posting per distinct token, a retraction
+//! or a replacement erases the postings of the value it removes, and the
+//! commit that first registers the attribute as searchable backfills
+//! the values already standing. The postings ride the commit's batch,
+//! so they seal, publish and replicate with the data.
+//!
+//! [`Search`] reads the index back. The index only narrows candidates:
+//! each one is checked against the attribute's current facts and scored
+//! from the value itself, so a posting that outlived its value (a merge
+//! dropped the value but not the posting) never surfaces.
+//!
+//! [`AttributeDescriptor::searchable`]: dialog_query::AttributeDescriptor::searchable
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::mem::take;
+use std::sync::{Arc, Mutex};
+
+use dialog_artifacts::{
+    Artifact, ArtifactSelector, Attribute, Datum, DialogArtifactsError, Entity, Instruction, Key,
+    State, TextIndexExt as _, TextMatch, TextSearch, Value, posting_entry, postings,
+};
+use dialog_capability::{Fork, Provider};
+use dialog_common::Blake3Hash as NodeHash;
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::{Get, Put};
+use dialog_effects::memory::Resolve;
+use dialog_query::attribute::descriptor_attr;
+use futures_util::TryStreamExt as _;
+
+use super::blob::index_store;
+use super::validate::registered;
+use crate::{Branch, CommitError, Index, RemoteSite};
+
+/// Command that runs a full-text search against a branch.
+///
+/// Created by [`BranchClaims::search`](crate::BranchClaims::search).
+/// Execute with `.perform(&env)`.
+pub struct Search<'a> {
+    branch: &'a Branch,
+    search: TextSearch,
+}
+
+impl<'a> Search<'a> {
+    pub(super) fn new(branch: &'a Branch, search: TextSearch) -> Self {
+        Self { branch, search }
+    }
+
+    /// The entities the branch's text index names for the search: a
+    /// bound entity alone, otherwise every entity holding a posting for
+    /// each of the query's terms. Candidates still need verifying.
+    pub(crate) async fn candidates<Env>(
+        &self,
+        env: &Env,
+    ) -> Result<BTreeSet<Entity>, DialogArtifactsError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        if let Some(of) = &self.search.of {
+            return Ok(BTreeSet::from([of.clone()]));
+        }
+        let Some(revision) = self.branch.revision() else {
+            return Ok(BTreeSet::new());
+        };
+        let store = index_store(self.branch, env).await;
+        let tree = Index::from_hash_with_cache(
+            NodeHash::from(*revision.tree.hash()),
+            self.branch.node_cache(),
+        );
+        tree.text_candidates(&store, &self.search.the, &self.search.query)
+            .await
+    }
+
+    /// Execute the search, returning every matching value in entity
+    /// order.
+    pub async fn perform<Env>(self, env: &Env) -> Result<Vec<TextMatch>, DialogArtifactsError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let mut found = Vec::new();
+        for of in self.candidates(env).await? {
+            let facts: Vec<Artifact> = self
+                .branch
+                .claims()
+                .select(ArtifactSelector::new().of(of).the(self.search.the.clone()))
+                .to_owned()
+                .perform(env)
+                .await?
+                .try_collect()
+                .await?;
+            found.extend(scored(&self.search, facts));
+        }
+        Ok(found)
+    }
+}
+
+/// The facts among `facts` whose string value matches the search, scored.
+pub(crate) fn scored(
+    search: &TextSearch,
+    facts: impl IntoIterator<Item = Artifact>,
+) -> impl Iterator<Item = TextMatch> {
+    let query = search.query.clone();
+    let the = search.the.clone();
+    facts.into_iter().filter_map(move |fact| {
+        let Value::String(is) = fact.is else {
+            return None;
+        };
+        if fact.the != the {
+            return None;
+        }
+        let score = query.score(&is)?;
+        Some(TextMatch {
+            of: fact.of,
+            is,
+            score,
+        })
+    })
+}
+
+/// The instructions of a commit's change stream the text index may need
+/// to reflect, collected as the batch drains the stream.
+///
+/// Only string values, replacements (which remove whatever value stood)
+/// and descriptor registrations can move the index; everything else is
+/// passed over without a copy.
+#[derive(Clone)]
+pub(super) struct TextTap {
+    registry: Attribute,
+    observed: Arc<Mutex<Vec<Instruction>>>,
+}
+
+impl Default for TextTap {
+    fn default() -> Self {
+        Self {
+            registry: descriptor_attr(),
+            observed: Arc::default(),
+        }
+    }
+}
+
+impl TextTap {
+    /// Keep `instruction` if it can move the index.
+    pub(super) fn observe(&self, instruction: &Instruction) {
+        let kept = match instruction {
+            Instruction::Replace(artifact) => Instruction::Replace(artifact.clone()),
+            Instruction::Assert(artifact) if self.moves(artifact) => {
+                Instruction::Assert(artifact.clone())
+            }
+            Instruction::Retract(artifact) if self.moves(artifact) => {
+                Instruction::Retract(artifact.clone())
+            }
+            _ => return,
+        };
+        self.observed
+            .lock()
+            .expect("text tap lock poisoned")
+            .push(kept);
+    }
+
+    /// Whether asserting or retracting `artifact` can move the index.
+    fn moves(&self, artifact: &Artifact) -> bool {
+        matches!(artifact.is, Value::String(_)) || artifact.the == self.registry
+    }
+
+    /// The instructions kept so far.
+    pub(super) fn take(&self) -> Vec<Instruction> {
+        take(&mut *self.observed.lock().expect("text tap lock poisoned"))
+    }
+}
+
+/// Posting edits, netted in instruction order: `true` writes the
+/// posting, `false` erases it.
+#[derive(Default)]
+struct PostingEdits(BTreeMap<Key, bool>);
+
+impl PostingEdits {
+    fn add(&mut self, the: &Attribute, of: &Entity, value: &str) {
+        for key in postings(the, of, value) {
+            self.0.insert(key.into_key(), true);
+        }
+    }
+
+    fn erase(&mut self, the: &Attribute, of: &Entity, value: &str) {
+        for key in postings(the, of, value) {
+            self.0.insert(key.into_key(), false);
+        }
+    }
+}
+
+impl Branch {
+    /// The text-index edits a commit's observed `instructions` call
+    /// for, against this branch's current revision: the postings to
+    /// write and the keys to erase.
+    pub(super) async fn index_text<Env>(
+        &self,
+        env: &Env,
+        instructions: Vec<Instruction>,
+    ) -> Result<(Vec<(Key, State<Datum>)>, Vec<Key>), CommitError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        if instructions.is_empty() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        // Which attributes are searchable before and after the commit:
+        // registrations staged in it take precedence over the branch's.
+        let registry = descriptor_attr();
+        let standing: HashSet<Attribute> =
+            self.registry(env).await?.searchable().cloned().collect();
+        let mut searchable = standing.clone();
+        for instruction in &instructions {
+            let (Instruction::Assert(artifact)
+            | Instruction::Replace(artifact)
+            | Instruction::Retract(artifact)) = instruction;
+            if artifact.the != registry {
+                continue;
+            }
+            let Some(descriptor) = registered(artifact) else {
+                continue;
+            };
+            let attribute = Attribute::from(descriptor.the());
+            match instruction {
+                Instruction::Retract(_) => searchable.remove(&attribute),
+                _ if descriptor.is_searchable() => searchable.insert(attribute),
+                _ => searchable.remove(&attribute),
+            };
+        }
+
+        let mut edits = PostingEdits::default();
+
+        // An attribute that becomes searchable indexes the values it
+        // already holds; one that stops being searchable drops them.
+        for attribute in searchable.symmetric_difference(&standing) {
+            let facts: Vec<Artifact> = self
+                .claims()
+                .select(ArtifactSelector::new().the(attribute.clone()))
+                .to_owned()
+                .perform(env)
+                .await?
+                .try_collect()
+                .await?;
+            for fact in facts {
+                if let Value::String(value) = &fact.is {
+                    if searchable.contains(attribute) {
+                        edits.add(&fact.the, &fact.of, value);
+                    } else {
+                        edits.erase(&fact.the, &fact.of, value);
+                    }
+                }
+            }
+        }
+
+        // String values each (entity, attribute) slot was asserted to
+        // earlier in this commit, which a later replacement removes.
+        let mut asserted: HashMap<(Entity, Attribute), Vec<String>> = HashMap::new();
+        for instruction in instructions {
+            let (Instruction::Assert(artifact)
+            | Instruction::Replace(artifact)
+            | Instruction::Retract(artifact)) = &instruction;
+            if !searchable.contains(&artifact.the) {
+                continue;
+            }
+            let slot = (artifact.of.clone(), artifact.the.clone());
+            if let Instruction::Replace(_) = instruction {
+                let standing: Vec<Artifact> = self
+                    .claims()
+                    .select(
+                        ArtifactSelector::new()
+                            .of(artifact.of.clone())
+                            .the(artifact.the.clone()),
+                    )
+                    .to_owned()
+                    .perform(env)
+                    .await?
+                    .try_collect()
+                    .await?;
+                for fact in standing {
+                    if let Value::String(value) = &fact.is {
+                        edits.erase(&fact.the, &fact.of, value);
+                    }
+                }
+                for value in asserted.remove(&slot).unwrap_or_default() {
+                    edits.erase(&artifact.the, &artifact.of, &value);
+                }
+            }
+            let Value::String(value) = &artifact.is else {
+                continue;
+            };
+            match instruction {
+                Instruction::Retract(_) => {
+                    edits.erase(&artifact.the, &artifact.of, value);
+                    if let Some(values) = asserted.get_mut(&slot) {
+                        values.retain(|other| other != value);
+                    }
+                }
+                _ => {
+                    edits.add(&artifact.the, &artifact.of, value);
+                    asserted.entry(slot).or_default().push(value.clone());
+                }
+            }
+        }
+
+        let mut entries = Vec::new();
+        let mut erasures = Vec::new();
+        for (key, add) in edits.0 {
+            if add {
+                entries.push(posting_entry(dialog_artifacts::TextKey(key)));
+            } else {
+                erasures.push(key);
+            }
+        }
+        Ok((entries, erasures))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::helpers::test_repo;
+    use anyhow::Result;
+    use dialog_artifacts::{TextQuery, ValueDataType};
+    use dialog_operator::helpers::test_operator_with_profile;
+    use dialog_query::query::Output as _;
+    use dialog_query::{
+        AttributeDescriptor, Cardinality, SearchConclusion, SearchQuery, Term, TextMatchesQuery,
+        the,
+    };
+    use futures_util::stream;
+
+    fn body() -> AttributeDescriptor {
+        AttributeDescriptor::new(
+            the!("note/body"),
+            "The text of a note",
+            Cardinality::One,
+            Some(ValueDataType::String),
+        )
+        .searchable()
+    }
+
+    fn search(query: &str) -> TextSearch {
+        TextSearch {
+            the: "note/body".parse().unwrap(),
+            query: TextQuery::parse(query),
+            of: None,
+        }
+    }
+
+    fn found(matches: &[TextMatch]) -> Vec<(&str, f64)> {
+        matches
+            .iter()
+            .map(|found| (found.is.as_str(), found.score))
+            .collect()
+    }
+
+    #[dialog_common::test]
+    async fn it_indexes_searchable_values_as_they_change() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        let first = Entity::new()?;
+        let second = Entity::new()?;
+
+        // Values written before the attribute is searchable are
+        // backfilled when it becomes so.
+        branch
+            .transaction()
+            .assert(
+                the!("note/body")
+                    .of(first.clone())
+                    .is("Prolly trees".to_string()),
+            )
+            .commit()
+            .perform(&operator)
+            .await?;
+        branch
+            .transaction()
+            .assert(body())
+            .assert(
+                the!("note/body")
+                    .of(second.clone())
+                    .is("Binary trees, mostly".to_string()),
+            )
+            .commit()
+            .perform(&operator)
+            .await?;
+
+        let mut matches = branch
+            .claims()
+            .search(search("TREES"))
+            .perform(&operator)
+            .await?;
+        matches.sort_by(|left, right| left.is.cmp(&right.is));
+        assert_eq!(
+            found(&matches),
+            vec![("Binary trees, mostly", 1.0 / 3.0), ("Prolly trees", 0.5)]
+        );
+
+        let matches = branch
+            .claims()
+            .search(search("prol*"))
+            .perform(&operator)
+            .await?;
+        assert_eq!(found(&matches), vec![("Prolly trees", 0.5)]);
+
+        // A replacement drops the old value's postings.
+        let replacement = Artifact {
+            the: "note/body".parse()?,
+            of: first.clone(),
+            is: Value::String("Merkle search".into()),
+            cause: None,
+        };
+        branch
+            .commit(stream::iter([Instruction::Replace(replacement)]))
+            .perform(&operator)
+            .await?;
+        assert!(
+            branch
+                .claims()
+                .search(search("prolly"))
+                .perform(&operator)
+                .await?
+                .is_empty()
+        );
+        let Some(revision) = branch.revision() else {
+            panic!("the branch has a revision");
+        };
+        let store = index_store(&branch, &operator).await;
+        let tree = Index::from_hash(NodeHash::from(*revision.tree.hash()));
+        assert!(
+            tree.text_candidates(&store, &"note/body".parse()?, &TextQuery::parse("prolly"))
+                .await?
+                .is_empty(),
+            "the replaced value's postings are erased"
+        );
+
+        // A retraction drops them too.
+        branch
+            .transaction()
+            .retract(
+                the!("note/body")
+                    .of(second.clone())
+                    .is("Binary trees, mostly".to_string()),
+            )
+            .commit()
+            .perform(&operator)
+            .await?;
+        assert!(
+            branch
+                .claims()
+                .search(search("trees"))
+                .perform(&operator)
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_answers_text_matches_premises() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        let note = Entity::new()?;
+        branch
+            .transaction()
+            .assert(body())
+            .assert(
+                the!("note/body")
+                    .of(note.clone())
+                    .is("Search the prolly tree".to_string()),
+            )
+            .assert(
+                the!("note/body")
+                    .of(Entity::new()?)
+                    .is("Nothing to see".to_string()),
+            )
+            .commit()
+            .perform(&operator)
+            .await?;
+
+        let premise = SearchQuery::TextMatches(TextMatchesQuery {
+            the: Term::from(Value::String("note/body".into())).into(),
+            query: Term::from(Value::String("tree* search".into())).into(),
+            of: Term::var("note"),
+            is: Term::var("body"),
+            score: Term::var("score"),
+        });
+        let rows: Vec<SearchConclusion> = branch
+            .query()
+            .select(premise)
+            .perform(&operator)
+            .try_vec()
+            .await?;
+        assert_eq!(rows.len(), 1, "{rows:?}");
+        assert_eq!(rows[0].get("of"), Some(&Value::Entity(note)));
+        assert_eq!(rows[0].get("score"), Some(&Value::Float(0.5)));
+        Ok(())
+    }
+}
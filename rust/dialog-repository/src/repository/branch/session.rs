@@ -1,10 +1,10 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 
 use dialog_artifacts::inspect::Load;
 use dialog_artifacts::selector::Constrained;
 use dialog_artifacts::{
     Artifact, ArtifactSelector, ArtifactStream, ArtifactViewStream as _, Changes,
-    DialogArtifactsError, Entity, Select, SortKey, Statement,
+    DialogArtifactsError, Entity, Search, Select, SortKey, Statement, TextMatch, TextSearch,
 };
 use dialog_capability::{Capability, Fork, Provider};
 use dialog_common::Blake3Hash as NodeHash;
@@ -19,12 +19,17 @@ use dialog_query::error::EvaluationError;
 use dialog_query::query::{Application, Output};
 use dialog_query::session::ProgramAnalysis;
 use dialog_query::source::SelectRules;
-use dialog_query::{DeductiveRule, Negation, Premise, Proposition};
+use dialog_query::types::Any;
+use dialog_query::{
+    Conjunction, DeductiveRule, Direction, Environment, Explanation, Match, Negation, Order,
+    Ordered, Planner, Premise, Proposition, Term,
+};
 use dialog_search_tree::Buffer;
 use dialog_storage::{Blake3Hash, StorageBackend};
 use futures_util::{StreamExt as _, TryStreamExt as _, stream};
 use std::sync::Arc;
 
+use super::search::scored;
 use crate::layer::{filter_tombstones, merge_grouped, tombstones_from};
 use crate::rules::{
     assemble, builtin, conclusion_selector, hydrate, overlay_rules, rule_entities, source_bytes,
@@ -32,7 +37,7 @@ use crate::rules::{
 };
 use crate::schema::{DidExt as _, Session, SessionBranch, session};
 use crate::{
-    Branch, NetworkedIndex, RemoteFallback, RemoteSite, RepositoryArchiveExt as _,
+    Branch, Counting, NetworkedIndex, RemoteFallback, RemoteSite, RepositoryArchiveExt as _,
     RepositoryMemoryExt, Upstream,
 };
 
@@ -168,6 +173,7 @@ impl<'a> QueryLayer<'a> {
         SelectQuery {
             layer: self.clone(),
             query,
+            order: Order::new(),
         }
     }
 }
@@ -177,6 +183,28 @@ impl<'a> QueryLayer<'a> {
 // `branch.query`, transaction queries, subscription evaluations —
 // constructs through here, so session facts participate in all of
 // them with no per-path wiring.
+impl QueryLayer<'_> {
+    /// The runtime environment a query over this layer reads through:
+    /// resolves the operator's identity via [`Identify`], builds the
+    /// overlay via [`overlay`](Self::overlay) and lifts any retracts in
+    /// it into tombstones.
+    async fn env<'e, Env>(&self, env: &'e Env) -> Result<QueryEnv<'e, Env>, DialogArtifactsError>
+    where
+        Env: Provider<Identify> + ConditionalSync,
+    {
+        let operator = Identify
+            .perform(env)
+            .await
+            .map_err(|e| DialogArtifactsError::Storage(format!("identify: {e}")))?;
+
+        let overlay = self.overlay(&operator);
+        let tombstones = Arc::new(tombstones_from(&overlay));
+
+        let branches = self.branches.iter().map(|&branch| branch.clone()).collect();
+        Ok(QueryEnv::new(branches, overlay, tombstones, env))
+    }
+}
+
 impl<'a> From<&'a Branch> for QueryLayer<'a> {
     fn from(branch: &'a Branch) -> Self {
         Self {
@@ -196,9 +224,14 @@ impl From<Changes> for QueryLayer<'_> {
 }
 
 /// A query command ready to be performed against an environment.
+///
+/// [`order_by`](Self::order_by), [`limit`](Self::limit) and
+/// [`offset`](Self::offset) page through the results; see
+/// [`Order`] for how rows compare.
 pub struct SelectQuery<'a, Q> {
     layer: QueryLayer<'a>,
     query: Q,
+    order: Order,
 }
 
 impl<'a, Q> SelectQuery<'a, Q> {
@@ -206,8 +239,28 @@ impl<'a, Q> SelectQuery<'a, Q> {
         Self {
             layer: QueryLayer::from(branch),
             query,
+            order: Order::new(),
         }
     }
+
+    /// Sort the results on `by` in `direction`, after any keys already
+    /// given.
+    pub fn order_by(mut self, by: impl Into<Term<Any>>, direction: Direction) -> Self {
+        self.order = self.order.by(by, direction);
+        self
+    }
+
+    /// Return at most `limit` results.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.order = self.order.limit(limit);
+        self
+    }
+
+    /// Skip the first `offset` results.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.order = self.order.offset(offset);
+        self
+    }
 }
 
 impl<'a, Q: Application> SelectQuery<'a, Q> {
@@ -229,26 +282,93 @@ impl<'a, Q: Application> SelectQuery<'a, Q> {
             + ConditionalSync
             + 'static,
     {
-        let SelectQuery { layer, query } = self;
+        let SelectQuery {
+            layer,
+            query,
+            order,
+        } = self;
         async_stream::try_stream! {
-            let operator = Identify
-                .perform(env)
-                .await
-                .map_err(|e| DialogArtifactsError::Storage(format!("identify: {e}")))?;
+            let query_env = layer.env(env).await?;
+            if order.is_identity() {
+                let results = Box::pin(query.perform(&query_env));
+                for await result in results {
+                    yield result?;
+                }
+            } else {
+                let results = Box::pin(Ordered::new(query, order).perform(&query_env));
+                for await result in results {
+                    yield result?;
+                }
+            }
+        }
+    }
+}
 
-            let overlay = layer.overlay(&operator);
-            let tombstones = Arc::new(tombstones_from(&overlay));
+impl<'a, Q: Into<Premise>> SelectQuery<'a, Q> {
+    /// Explain how the query would evaluate, without running it: the
+    /// ordered plan with each step's cost estimate, the index each fact
+    /// scan reads and the variable its output is sorted on. A concept
+    /// query expands into the plans of the rules that derive it.
+    pub async fn explain<Env>(self, env: &'a Env) -> Result<Explanation, EvaluationError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Identify>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let SelectQuery { layer, query, .. } = self;
+        let query_env = layer.env(env).await?;
+        match query.into() {
+            Premise::Assert(Proposition::Concept(concept)) => concept.explain(&query_env).await,
+            premise => plan(premise)?.explain(&query_env).await,
+        }
+    }
 
-            let branches = layer.branches.iter().map(|&branch| branch.clone()).collect();
-            let query_env = QueryEnv::new(branches, overlay, tombstones, env);
-            let results = Box::pin(query.perform(&query_env));
-            for await result in results {
-                yield result?;
+    /// Run the query and explain it with what each step did: the rows
+    /// it produced, the blocks it read and the time it took. Reads are
+    /// counted by performing the query over a copy of `env` that tallies
+    /// its effects; the results themselves are discarded.
+    pub async fn analyze<Env>(self, env: &'a Env) -> Result<Explanation, EvaluationError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Identify>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + Clone
+            + 'static,
+    {
+        let SelectQuery { layer, query, .. } = self;
+        let counting = Counting::new(env.clone());
+        let query_env = layer.env(&counting).await?;
+        match query.into() {
+            Premise::Assert(Proposition::Concept(concept)) => {
+                concept.analyze(&query_env, &counting).await
+            }
+            premise => {
+                plan(premise)?
+                    .analyze(Match::new().seed(), &query_env, &counting)
+                    .await
             }
         }
     }
 }
 
+/// A single premise as a one-step plan, the shape evaluation gives it.
+fn plan(premise: Premise) -> Result<Conjunction, EvaluationError> {
+    Planner::from(vec![premise])
+        .plan(&Environment::new())
+        .map_err(|error| EvaluationError::Planning {
+            message: error.to_string(),
+        })
+}
+
 /// The runtime environment that bridges the layer's branches and
 /// per-query overlay changes into the query engine's Provider bounds.
 ///
@@ -509,6 +629,61 @@ where
     }
 }
 
+// The full-text search behind `text/matches` premises. Each branch's
+// text index names candidate entities (the overlay's facts for the
+// attribute add theirs), and every candidate is then verified through
+// the same fact scan a premise over the attribute would run — overlay,
+// tombstones and all — so a search never reports a value the scan
+// would not. The verifying scans record demand as usual, and the
+// attribute-wide selector is recorded up front so a subscription
+// re-evaluates the search when any value of the attribute changes.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl<'a, Env> Provider<Search> for QueryEnv<'a, Env>
+where
+    Env: Provider<Get>
+        + Provider<Put>
+        + Provider<Resolve>
+        + Provider<Fork<RemoteSite, Get>>
+        + Provider<Fork<RemoteSite, Resolve>>
+        + ConditionalSync
+        + 'static,
+{
+    async fn execute(&self, input: TextSearch) -> Result<Vec<TextMatch>, DialogArtifactsError> {
+        let selector = ArtifactSelector::new().the(input.the.clone());
+        self.record_demand(&selector);
+        let candidates = match &input.of {
+            Some(of) => BTreeSet::from([of.clone()]),
+            None => {
+                let mut candidates = BTreeSet::new();
+                for branch in &self.branches {
+                    let search = branch.claims().search(input.clone());
+                    candidates.extend(search.candidates(self.env).await?);
+                }
+                let overlay: Vec<Artifact> =
+                    Provider::<Select<'a>>::execute(&self.changes, selector)
+                        .await?
+                        .owned()
+                        .try_collect()
+                        .await?;
+                candidates.extend(overlay.into_iter().map(|fact| fact.of));
+                candidates
+            }
+        };
+        let mut found = Vec::new();
+        for of in candidates {
+            let selector = ArtifactSelector::new().of(of).the(input.the.clone());
+            let facts: Vec<Artifact> = Provider::<Select<'a>>::execute(self, selector)
+                .await?
+                .owned()
+                .try_collect()
+                .await?;
+            found.extend(scored(&input, facts));
+        }
+        Ok(found)
+    }
+}
+
 impl<'a, Env> QueryEnv<'a, Env>
 where
     Env: Provider<Get>
@@ -772,7 +947,7 @@ mod rule_tests {
     use dialog_query::concept::descriptor::{ConceptConclusion, ConceptDescriptor};
     use dialog_query::concept::query::ConceptQuery;
     use dialog_query::rule::DeductiveRuleDescriptor;
-    use dialog_query::{DeductiveRule, Parameters, Term, the};
+    use dialog_query::{DeductiveRule, Derivation, IndexOrder, Parameters, Term, the};
 
     /// Conclusion concept `employee` (one `name` field). Derived — no
     /// `employee` fact is ever written; rows come only from rules.
@@ -862,6 +1037,116 @@ mod rule_tests {
         Ok(())
     }
 
+    fn employee_query() -> ConceptQuery {
+        let mut terms = Parameters::new();
+        terms.insert("this".into(), Term::var("this"));
+        terms.insert("name".into(), Term::var("name"));
+        ConceptQuery {
+            predicate: employee_descriptor(),
+            terms,
+        }
+    }
+
+    /// `select` sorts and pages the results of a derived concept.
+    #[dialog_common::test]
+    async fn it_orders_and_pages_a_selected_concept() -> anyhow::Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let mut transaction = branch.transaction().assert(employee_from_person());
+        for name in ["bob", "dan", "ann", "cat"] {
+            let person: Entity = format!("id:{name}").parse()?;
+            transaction =
+                transaction.assert(the!("org/person-name").of(person).is(name.to_string()));
+        }
+        transaction.commit().perform(&operator).await?;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let page: Vec<ConceptConclusion> = branch
+            .select(employee_query())
+            .order_by(Term::<String>::var("name"), Direction::Descending)
+            .offset(1)
+            .limit(2)
+            .perform(&operator)
+            .try_vec()
+            .await?;
+        let entities: Vec<Entity> = page.iter().map(|c| c.entity().clone()).collect();
+        let expected: Vec<Entity> = vec!["id:cat".parse()?, "id:bob".parse()?];
+        assert_eq!(entities, expected);
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_explains_a_concept_query_through_its_rules() -> anyhow::Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        branch
+            .transaction()
+            .assert(employee_from_person())
+            .commit()
+            .perform(&operator)
+            .await?;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let explanation = branch
+            .query()
+            .select(employee_query())
+            .explain(&operator)
+            .await?;
+
+        assert_eq!(explanation.steps.len(), 1);
+        let Some(Derivation::TopDown { rules, .. }) = &explanation.steps[0].derivation else {
+            panic!("expected the concept to be derived top-down");
+        };
+        assert_eq!(rules.len(), 2, "the implicit rule and the installed one");
+        assert_eq!(rules[0].steps[0].index, Some(IndexOrder::Aev));
+        let text = explanation.to_string();
+        assert!(
+            text.contains("org/employee-name of ?this is ?name"),
+            "{text}"
+        );
+        assert!(text.contains("AEV index, sorted on ?this"), "{text}");
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_analyzes_rows_and_reads_per_step() -> anyhow::Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        let mut transaction = branch.transaction().assert(employee_from_person());
+        for name in ["Alice", "Bob", "Carol"] {
+            transaction = transaction.assert(
+                the!("org/person-name")
+                    .of(Entity::new()?)
+                    .is(name.to_string()),
+            );
+        }
+        transaction.commit().perform(&operator).await?;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let explanation = branch
+            .query()
+            .select(employee_query())
+            .analyze(&operator)
+            .await?;
+
+        let actual = explanation.steps[0].actual.expect("analyzed");
+        assert_eq!(actual.rows, 3);
+        let Some(Derivation::TopDown { rules, .. }) = &explanation.steps[0].derivation else {
+            panic!("expected the concept to be derived top-down");
+        };
+        let rows: Vec<_> = rules
+            .iter()
+            .map(|rule| rule.steps[0].actual.map(|actual| actual.rows))
+            .collect();
+        assert_eq!(rows, vec![Some(0), Some(3)]);
+        assert!(explanation.to_string().contains("actual 3 rows"));
+        Ok(())
+    }
+
     /// A *reducing* rule stores, discovers, and hydrates through the
     /// same `db.rule/*` rail: the committed rule's reduce block
     /// survives the durable layer round trip, and queries evaluate
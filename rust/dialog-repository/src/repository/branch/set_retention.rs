@@ -0,0 +1,72 @@
+use dialog_artifacts::history::Retention;
+use dialog_capability::Provider;
+use dialog_effects::memory::Publish;
+
+use crate::{Branch, PublishError};
+
+/// Command struct for setting a branch's history retention policy.
+pub struct SetRetention<'a> {
+    branch: &'a Branch,
+    retention: Retention,
+}
+
+impl Branch {
+    /// Create a command to set the [`Retention`] policy this branch's
+    /// [`compact`](Branch::compact) applies.
+    ///
+    /// Setting a policy compacts nothing by itself; it only records how
+    /// much history the next compaction keeps.
+    pub fn set_retention(&self, retention: Retention) -> SetRetention<'_> {
+        SetRetention {
+            branch: self,
+            retention,
+        }
+    }
+}
+
+impl SetRetention<'_> {
+    /// Execute the set_retention operation.
+    pub async fn perform<Env>(self, env: &Env) -> Result<(), PublishError>
+    where
+        Env: Provider<Publish>,
+    {
+        self.branch
+            .retention
+            .publish(self.retention)
+            .perform(env)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use dialog_artifacts::history::Retention;
+
+    use crate::helpers::test_repo;
+    use dialog_operator::helpers::test_operator_with_profile;
+
+    #[dialog_common::test]
+    async fn it_persists_retention_across_reload() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        assert_eq!(branch.retention(), None);
+
+        let policy = Retention::Age(Duration::from_secs(30 * 24 * 60 * 60));
+        branch.set_retention(policy).perform(&operator).await?;
+        assert_eq!(branch.retention(), Some(policy));
+
+        let reopened = repo.branch("main").open().perform(&operator).await?;
+        assert_eq!(reopened.retention(), Some(policy));
+
+        Ok(())
+    }
+}
@@ -2062,7 +2062,7 @@ mod tests {
             clause.insert(
                 (*field).to_string(),
                 dialog_query::ReduceSpec {
-                    apply: *apply,
+                    apply: apply.clone(),
                     of: Term::<Any>::var(*input),
                 },
             );
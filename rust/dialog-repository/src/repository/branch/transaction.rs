@@ -1,10 +1,13 @@
+mod check;
 mod induce;
 mod query;
+mod upsert;
 pub use query::{TransactionQuery, TransactionSelectQuery};
+use upsert::Identity;
 
-use crate::rules::{TriggerFootprint, on_attr, reads_attr};
+use crate::rules::{TriggerFootprint, checks_attr, on_attr, reads_attr};
 use crate::{Branch, CommitError, RemoteSite, Revision};
-use dialog_artifacts::{Changes, Instruction, Statement, Update};
+use dialog_artifacts::{Attribute, Changes, Entity, Instruction, Statement, Update, Value};
 use dialog_capability::{Fork, Provider};
 use dialog_common::ConditionalSync;
 use dialog_effects::archive::{Get, Import, Put};
@@ -25,6 +28,8 @@ pub struct Transaction<'a> {
     branch: &'a Branch,
     changes: Changes,
     transients: Changes,
+    identities: Vec<Identity>,
+    strict: bool,
 }
 
 impl<'a> Transaction<'a> {
@@ -51,6 +56,27 @@ impl<'a> Transaction<'a> {
         self
     }
 
+    /// Upsert by natural key: `of` is a provisional entity standing for
+    /// the entity whose `the` is `is`.
+    ///
+    /// At commit the key is resolved through the VAE index. If an entity
+    /// already holds it, every occurrence of `of` in this transaction —
+    /// as a subject or as an entity value — is rewritten to that entity;
+    /// otherwise `of` is kept and the key is asserted of it. Upserts
+    /// sharing a key resolve to the same entity, and a key held by
+    /// several entities fails the commit with
+    /// [`CommitError::AmbiguousIdentity`]. Pair it with a
+    /// [unique](dialog_query::AttributeDescriptor::unique) attribute to
+    /// keep keys from being shared at all.
+    pub fn upsert(mut self, the: impl Into<Attribute>, is: impl Into<Value>, of: Entity) -> Self {
+        self.identities.push(Identity {
+            the: the.into(),
+            is: is.into(),
+            of,
+        });
+        self
+    }
+
     /// Integrate an external [`Changes`] batch into this transaction.
     ///
     /// Each instruction is replayed as if it had been asserted or
@@ -76,6 +102,15 @@ impl<'a> Transaction<'a> {
         self
     }
 
+    /// Validate the commit against the attribute descriptors registered
+    /// on the branch (see [`Commit::strict`](crate::Commit::strict)). The
+    /// check runs on the settled batch, after induction, so facts rules
+    /// derive are held to the same descriptors.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
     /// Run queries against this transaction's "as-if committed" view of
     /// the branch.
     ///
@@ -105,8 +140,10 @@ impl<'a> Transaction<'a> {
             branch: self.branch,
             changes: self.changes,
             transients: self.transients,
+            identities: self.identities,
             allow_empty: false,
             canonicalize: false,
+            strict: self.strict,
         }
     }
 }
@@ -121,13 +158,16 @@ impl Branch {
             branch: self,
             changes: Changes::new(),
             transients: Changes::new(),
+            identities: Vec::new(),
+            strict: false,
         }
     }
 }
 
-/// Command committing a [`Transaction`]: runs commit-time induction
-/// over the transaction's delta, then delegates the settled durable
-/// batch to [`Branch::commit`].
+/// Command committing a [`Transaction`]: resolves its upserts, runs
+/// commit-time induction over the transaction's delta, checks the
+/// settled durable batch against the constraint rules it could violate,
+/// then delegates it to [`Branch::commit`].
 ///
 /// Mirrors [`Commit`](crate::Commit)'s builder surface
 /// ([`allow_empty`](Self::allow_empty) /
@@ -138,8 +178,10 @@ pub struct TransactionCommit<'a> {
     branch: &'a Branch,
     changes: Changes,
     transients: Changes,
+    identities: Vec<Identity>,
     allow_empty: bool,
     canonicalize: bool,
+    strict: bool,
 }
 
 impl<'a> TransactionCommit<'a> {
@@ -157,9 +199,11 @@ impl<'a> TransactionCommit<'a> {
         self
     }
 
-    /// Run induction, then execute the commit, returning the
-    /// newly-published [`Revision`] (or the unchanged head when the
-    /// settled batch is a no-op).
+    /// Resolve upserts, run induction and the constraint check, then
+    /// execute the commit, returning the newly-published [`Revision`] (or
+    /// the unchanged head when the settled batch is a no-op). A batch
+    /// that violates a constraint rule fails with
+    /// [`CommitError::ConstraintViolation`] and writes nothing.
     pub async fn perform<Env>(self, env: &Env) -> Result<Revision, CommitError>
     where
         Env: Provider<Get>
@@ -175,10 +219,21 @@ impl<'a> TransactionCommit<'a> {
             + 'static,
     {
         let mut changes = self.changes;
-        induce::induce(self.branch, &mut changes, self.transients, env).await?;
+        let mut transients = self.transients;
+        upsert::resolve(
+            self.branch,
+            self.identities,
+            &mut changes,
+            &mut transients,
+            env,
+        )
+        .await?;
+        induce::induce(self.branch, &mut changes, transients, env).await?;
+        check::check(self.branch, &changes, env).await?;
 
         // The trigger footprint is a pure function of the committed
-        // `dialog.rule/on` and `dialog.rule/reads` facts, so a commit
+        // `dialog.rule/on`, `dialog.rule/checks` and `dialog.rule/reads`
+        // facts, so a commit
         // touching neither (checked after induction, which may fold
         // rule installs into the batch) carries the cached footprint
         // forward to the head it publishes. Without this every commit
@@ -187,10 +242,11 @@ impl<'a> TransactionCommit<'a> {
         let previous = self.branch.revision();
         let touches_rules = {
             let on = on_attr();
+            let checks = checks_attr();
             let reads = reads_attr();
-            changes
-                .iter()
-                .any(|(_, attribute, _)| *attribute == on || *attribute == reads)
+            changes.iter().any(|(_, attribute, _)| {
+                *attribute == on || *attribute == checks || *attribute == reads
+            })
         };
 
         let mut commit = self.branch.commit(changes.into_stream());
@@ -200,6 +256,9 @@ impl<'a> TransactionCommit<'a> {
         if self.canonicalize {
             commit = commit.canonicalize();
         }
+        if self.strict {
+            commit = commit.strict();
+        }
         let revision = Box::pin(commit.perform(env)).await?;
 
         // Advance the induction watermark: rules have now evaluated
@@ -0,0 +1,349 @@
+//! Commit-time integrity checking: trigger-indexed dispatch of
+//! constraint rules.
+//!
+//! Runs after induction
+//! ([`TransactionCommit::perform`](super::TransactionCommit::perform)),
+//! over the settled durable batch, and before anything is written. It
+//! shares induction's dispatch: the batch's touched attributes (closed
+//! over deduction) probe the `dialog.rule/checks` trigger index, and
+//! only the constraints watching one are loaded and evaluated — seeded
+//! by the batch's asserted rows where that is complete, over the full
+//! body where a removal or a derived change may have produced the
+//! violation. Each body reads the state the commit would publish
+//! (branch ⊕ batch).
+//!
+//! Checking is incremental: a violation the branch already held is only
+//! reported by a commit that touches what it reads. The exception is a
+//! constraint installed by the commit itself, which is checked in full
+//! — installing an invariant the branch already violates is rejected.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::iter;
+use std::sync::Arc;
+
+use dialog_artifacts::{Attribute, Changes, Entity, Instruction, Value};
+use dialog_capability::{Fork, Provider};
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::{Get, Put};
+use dialog_effects::authority::Identify;
+use dialog_effects::memory::Resolve;
+use dialog_query::{Any, Binding, Environment, Term};
+
+use super::induce::{Delta, Dispatch, OverlayTriggers, full_matches, seeded_matches};
+use crate::layer::tombstones_from;
+use crate::repository::branch::QueryLayer;
+use crate::repository::branch::session::QueryEnv;
+use crate::rules::{checks_attr, on_entity, reads_attr};
+use crate::{Branch, CommitError, RemoteSite};
+
+/// Check the constraint rules `changes` could violate against the state
+/// they would commit, failing with the first violated rule's bindings.
+pub(crate) async fn check<Env>(
+    branch: &Branch,
+    changes: &Changes,
+    env: &Env,
+) -> Result<(), CommitError>
+where
+    Env: Provider<Get>
+        + Provider<Put>
+        + Provider<Resolve>
+        + Provider<Identify>
+        + Provider<Fork<RemoteSite, Get>>
+        + Provider<Fork<RemoteSite, Resolve>>
+        + ConditionalSync
+        + 'static,
+{
+    let stimulus = changes.clone().into_instructions();
+    if stimulus.is_empty() {
+        return Ok(());
+    }
+    let delta = Delta::of(&stimulus);
+    let dispatch = Dispatch::resolve(branch, env).await?;
+    let overlay = OverlayTriggers::scan(changes);
+
+    // Constraints installed by this commit are checked in full, and a
+    // deductive rule installed by it makes its conclusions
+    // derived-touched, exactly as during induction.
+    let checks = checks_attr();
+    let reads = reads_attr();
+    let mut installed: BTreeSet<Entity> = BTreeSet::new();
+    let mut touched = delta.touched.clone();
+    for instruction in &stimulus {
+        let (Instruction::Assert(a) | Instruction::Replace(a)) = instruction else {
+            continue;
+        };
+        if a.the == checks {
+            installed.insert(a.of.clone());
+        } else if a.the == reads
+            && let Some(body) = dispatch.deductive(&a.of, &overlay, env).await?
+        {
+            for (_, field) in body.conclusion().with().iter() {
+                touched.insert(field.descriptor().the().clone().into());
+            }
+        }
+    }
+    dispatch
+        .expand_through_deduction(&mut touched, &overlay, env)
+        .await?;
+    let expanded: BTreeSet<Attribute> = touched.difference(&delta.touched).cloned().collect();
+
+    let mut candidates: BTreeSet<Entity> = installed.clone();
+    for attribute in &touched {
+        let Some(on) = on_entity(attribute) else {
+            continue;
+        };
+        candidates.extend(dispatch.checks(&on, &overlay, env).await?);
+    }
+    if candidates.is_empty() {
+        return Ok(());
+    }
+
+    let operator = Identify.perform(env).await?;
+    let layered = QueryLayer::from(branch)
+        .with(changes.clone())
+        .overlay(&operator);
+    let tombstones = Arc::new(tombstones_from(&layered));
+    let view = QueryEnv::new(vec![branch.clone()], layered, tombstones, env);
+
+    for entity in candidates {
+        let Some(rule) = dispatch.constraint(&entity, &overlay, env).await? else {
+            continue;
+        };
+        let full =
+            installed.contains(&entity) || delta.requires_full_body(rule.analysis(), &expanded);
+        let matches = if full {
+            full_matches(rule.plan(&Environment::new()), &view).await?
+        } else {
+            seeded_matches(
+                rule.analysis(),
+                |scope| rule.plan(scope),
+                &delta.assert_rows,
+                &view,
+            )
+            .await?
+        };
+
+        // A violation is reported by its head: the subject and every
+        // head field the match binds. Seeded evaluation may reach the
+        // same violation through several rows.
+        let mut bindings: Vec<BTreeMap<String, Value>> = Vec::new();
+        for matched in matches {
+            let reported: BTreeMap<String, Value> = iter::once("this")
+                .chain(rule.conclusion().with().iter().map(|(name, _)| name))
+                .filter_map(|name| match matched.lookup(&Term::<Any>::var(name)) {
+                    Ok(Binding::Present(value)) => Some((name.to_string(), value)),
+                    _ => None,
+                })
+                .collect();
+            if !bindings.contains(&reported) {
+                bindings.push(reported);
+            }
+        }
+        if !bindings.is_empty() {
+            return Err(CommitError::ConstraintViolation {
+                rule: entity,
+                bindings,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::helpers::test_repo;
+    use anyhow::Result;
+    use dialog_operator::helpers::test_operator_with_profile;
+    use dialog_query::{ConstraintRule, the};
+    use serde_json::json;
+
+    /// No two users share an email.
+    fn unique_email() -> ConstraintRule {
+        serde_json::from_value(json!({
+            "forbid": {
+                "with": { "email": { "the": "user/email", "as": "Text" } }
+            },
+            "when": [
+                {
+                    "assert": { "with": { "email": { "the": "user/email", "as": "Text" } } },
+                    "where": {
+                        "this": { "?": { "name": "this" } },
+                        "email": { "?": { "name": "email" } }
+                    }
+                },
+                {
+                    "assert": { "with": { "email": { "the": "user/email", "as": "Text" } } },
+                    "where": {
+                        "this": { "?": { "name": "other" } },
+                        "email": { "?": { "name": "email" } }
+                    }
+                }
+            ],
+            "unless": [
+                {
+                    "assert": "==",
+                    "where": {
+                        "this": { "?": { "name": "this" } },
+                        "is": { "?": { "name": "other" } }
+                    }
+                }
+            ]
+        }))
+        .expect("constraint compiles")
+    }
+
+    /// Every task has an owner.
+    fn owned_task() -> ConstraintRule {
+        serde_json::from_value(json!({
+            "forbid": {
+                "with": { "title": { "the": "task/title", "as": "Text" } }
+            },
+            "when": [
+                {
+                    "assert": { "with": { "title": { "the": "task/title", "as": "Text" } } },
+                    "where": {
+                        "this": { "?": { "name": "this" } },
+                        "title": { "?": { "name": "title" } }
+                    }
+                }
+            ],
+            "unless": [
+                {
+                    "assert": { "with": { "owner": { "the": "task/owner", "as": "Entity" } } },
+                    "where": {
+                        "this": { "?": { "name": "this" } },
+                        "owner": { "?": {} }
+                    }
+                }
+            ]
+        }))
+        .expect("constraint compiles")
+    }
+
+    #[dialog_common::test]
+    async fn it_rejects_a_commit_that_violates_a_constraint() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let alice: Entity = "user:alice".parse()?;
+        let bob: Entity = "user:bob".parse()?;
+        branch
+            .transaction()
+            .assert(unique_email())
+            .assert(
+                the!("user/email")
+                    .of(alice.clone())
+                    .is("a@example.com".to_string()),
+            )
+            .commit()
+            .perform(&operator)
+            .await?;
+        let head = branch.revision();
+
+        let rejected = branch
+            .transaction()
+            .assert(
+                the!("user/email")
+                    .of(bob.clone())
+                    .is("a@example.com".to_string()),
+            )
+            .commit()
+            .perform(&operator)
+            .await;
+        let Err(CommitError::ConstraintViolation { rule, bindings }) = rejected else {
+            panic!("expected a constraint violation, got {rejected:?}");
+        };
+        assert_eq!(rule, unique_email().this());
+        let email = Value::String("a@example.com".into());
+        for user in [&alice, &bob] {
+            assert!(
+                bindings.contains(&BTreeMap::from([
+                    ("this".to_string(), Value::Entity(user.clone())),
+                    ("email".to_string(), email.clone()),
+                ])),
+                "{bindings:?}"
+            );
+        }
+        assert_eq!(branch.revision(), head, "nothing was written");
+
+        branch
+            .transaction()
+            .assert(the!("user/email").of(bob).is("b@example.com".to_string()))
+            .commit()
+            .perform(&operator)
+            .await?;
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_checks_removals_and_newly_installed_constraints() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let chores: Entity = "task:chores".parse()?;
+        let taxes: Entity = "task:taxes".parse()?;
+        let owner: Entity = "user:alice".parse()?;
+        branch
+            .transaction()
+            .assert(
+                the!("task/title")
+                    .of(chores.clone())
+                    .is("Chores".to_string()),
+            )
+            .assert(the!("task/owner").of(chores.clone()).is(owner.clone()))
+            .assert(the!("task/title").of(taxes.clone()).is("Taxes".to_string()))
+            .commit()
+            .perform(&operator)
+            .await?;
+
+        // The branch already holds an unowned task, so the constraint
+        // can't be installed until it is fixed.
+        let rejected = branch
+            .transaction()
+            .assert(owned_task())
+            .commit()
+            .perform(&operator)
+            .await;
+        assert!(
+            matches!(&rejected, Err(CommitError::ConstraintViolation { bindings, .. })
+                if bindings.len() == 1 && bindings[0]["this"] == Value::Entity(taxes.clone())),
+            "{rejected:?}"
+        );
+        branch
+            .transaction()
+            .assert(owned_task())
+            .assert(the!("task/owner").of(taxes).is(owner.clone()))
+            .commit()
+            .perform(&operator)
+            .await?;
+
+        // Retracting an owner violates the constraint through `unless`.
+        let rejected = branch
+            .transaction()
+            .retract(the!("task/owner").of(chores.clone()).is(owner.clone()))
+            .commit()
+            .perform(&operator)
+            .await;
+        assert!(
+            matches!(&rejected, Err(CommitError::ConstraintViolation { bindings, .. })
+                if bindings[0]["title"] == Value::String("Chores".into())),
+            "{rejected:?}"
+        );
+
+        // Uninstalling the constraint lifts it.
+        branch
+            .transaction()
+            .retract(owned_task())
+            .retract(the!("task/owner").of(chores).is(owner))
+            .commit()
+            .perform(&operator)
+            .await?;
+        Ok(())
+    }
+}
@@ -42,8 +42,11 @@ use dialog_common::ConditionalSync;
 use dialog_effects::archive::{Get, Put};
 use dialog_effects::authority::Identify;
 use dialog_effects::memory::Resolve;
+use dialog_query::rule::AnalyzedRule;
 use dialog_query::rule::inductive::Polarity;
-use dialog_query::{Any, Binding, Cardinality, Environment, InductiveRule, Match, Term};
+use dialog_query::{
+    Any, Binding, Cardinality, Conjunction, ConstraintRule, Environment, InductiveRule, Match, Term,
+};
 use futures_util::{StreamExt as _, TryStreamExt};
 use std::sync::Arc;
 
@@ -52,8 +55,8 @@ use crate::layer::tombstones_from;
 use crate::repository::branch::QueryLayer;
 use crate::repository::branch::session::QueryEnv;
 use crate::rules::{
-    TriggerFootprint, hydrate, hydrate_inductive, on_attr, on_entity, reads_attr, source_attr,
-    transient_attr,
+    TriggerFootprint, checks_attr, hydrate, hydrate_constraint, hydrate_inductive, on_attr,
+    on_entity, reads_attr, source_attr, transient_attr,
 };
 use crate::{Branch, CommitError, RemoteSite, Revision};
 
@@ -119,35 +122,8 @@ where
         }
 
         // Probe keys straight off the instructions — no schema lookup.
-        // The rows are kept too: assert/replace rows seed
-        // delta-restricted evaluation; retract and replace *attributes*
-        // decide when a candidate needs the full-body fallback (a
-        // removal can newly enable a rule only through `unless`, which
-        // a seed cannot express).
-        let mut assert_rows: Vec<Artifact> = Vec::new();
-        let mut retract_attrs: BTreeSet<Attribute> = BTreeSet::new();
-        let mut replace_attrs: BTreeSet<Attribute> = BTreeSet::new();
-        for instruction in &stimulus {
-            match instruction {
-                Instruction::Assert(a) => assert_rows.push(a.clone()),
-                Instruction::Replace(a) => {
-                    assert_rows.push(a.clone());
-                    replace_attrs.insert(a.the.clone());
-                }
-                Instruction::Retract(a) => {
-                    retract_attrs.insert(a.the.clone());
-                }
-            }
-        }
-        let mut touched: BTreeSet<Attribute> = stimulus
-            .iter()
-            .map(|instruction| match instruction {
-                Instruction::Assert(a) | Instruction::Replace(a) | Instruction::Retract(a) => {
-                    a.the.clone()
-                }
-            })
-            .collect();
-        let direct = touched.clone();
+        let delta = Delta::of(&stimulus);
+        let mut touched = delta.touched.clone();
 
         // The overlay's trigger slice: rules, markers, and support
         // edges staged in this transaction (including novelty from
@@ -223,7 +199,7 @@ where
         // Attributes only reachable through the deductive closure: a
         // candidate premised on one changed *derivedly*, which a base
         // row cannot seed.
-        let expanded: BTreeSet<Attribute> = touched.difference(&direct).cloned().collect();
+        let expanded: BTreeSet<Attribute> = touched.difference(&delta.touched).cloned().collect();
 
         let mut novelty = Changes::new();
         let mut emitted_transients = Changes::new();
@@ -239,30 +215,25 @@ where
             // they match and evaluate with those bindings fixed, so
             // cost follows the delta's join fan-out rather than
             // relation size. The full-body fallback covers what a
-            // seed cannot express: enabling by removal (`unless` over
-            // a retracted or superseded fact) and premises that
-            // changed derivedly through the deductive closure.
-            let (positive_attrs, unless_attrs) = premise_attrs(&rule);
-            let full = installed.contains(&entity)
-                || expanded
-                    .iter()
-                    .any(|a| positive_attrs.contains(a) || unless_attrs.contains(a))
-                || retract_attrs.iter().any(|a| unless_attrs.contains(a))
-                || replace_attrs.iter().any(|a| unless_attrs.contains(a));
-            if full {
-                fire(
-                    &rule,
-                    transient_head,
+            // seed cannot express (see `Delta::requires_full_body`).
+            let full =
+                installed.contains(&entity) || delta.requires_full_body(rule.analysis(), &expanded);
+            let matches = if full {
+                full_matches(rule.plan(&Environment::new()), &view).await?
+            } else {
+                seeded_matches(
+                    rule.analysis(),
+                    |scope| rule.plan(scope),
+                    &delta.assert_rows,
                     &view,
-                    &mut novelty,
-                    &mut emitted_transients,
                 )
-                .await?;
-            } else {
-                fire_seeded(
+                .await?
+            };
+            if !matches.is_empty() {
+                emit_matches(
                     &rule,
                     transient_head,
-                    &assert_rows,
+                    matches,
                     &view,
                     &mut novelty,
                     &mut emitted_transients,
@@ -284,13 +255,73 @@ where
     Ok(())
 }
 
+/// A round's stimulus, classified for dispatch. The touched attributes
+/// are the probe keys; assert/replace rows seed delta-restricted
+/// evaluation; retract and replace *attributes* decide when a candidate
+/// needs the full-body fallback (a removal can newly enable a rule only
+/// through `unless`, which a seed cannot express).
+pub(super) struct Delta {
+    /// Every attribute the stimulus asserts, replaces, or retracts.
+    pub(super) touched: BTreeSet<Attribute>,
+    /// The asserted and replacing rows.
+    pub(super) assert_rows: Vec<Artifact>,
+    /// Attributes the stimulus retracts from.
+    retract_attrs: BTreeSet<Attribute>,
+    /// Attributes the stimulus replaces on.
+    replace_attrs: BTreeSet<Attribute>,
+}
+
+impl Delta {
+    pub(super) fn of(stimulus: &[Instruction]) -> Self {
+        let mut delta = Delta {
+            touched: BTreeSet::new(),
+            assert_rows: Vec::new(),
+            retract_attrs: BTreeSet::new(),
+            replace_attrs: BTreeSet::new(),
+        };
+        for instruction in stimulus {
+            match instruction {
+                Instruction::Assert(a) => delta.assert_rows.push(a.clone()),
+                Instruction::Replace(a) => {
+                    delta.assert_rows.push(a.clone());
+                    delta.replace_attrs.insert(a.the.clone());
+                }
+                Instruction::Retract(a) => {
+                    delta.retract_attrs.insert(a.the.clone());
+                }
+            }
+            let (Instruction::Assert(a) | Instruction::Replace(a) | Instruction::Retract(a)) =
+                instruction;
+            delta.touched.insert(a.the.clone());
+        }
+        delta
+    }
+
+    /// Whether a rule's body must be evaluated in full rather than
+    /// seeded by the asserted rows: when a removal may enable it
+    /// (`unless` over a retracted or superseded fact), or a premise
+    /// changed derivedly — through the deductive closure, `expanded`.
+    pub(super) fn requires_full_body(
+        &self,
+        analysis: &AnalyzedRule,
+        expanded: &BTreeSet<Attribute>,
+    ) -> bool {
+        let (positive_attrs, unless_attrs) = premise_attrs(analysis);
+        expanded
+            .iter()
+            .any(|a| positive_attrs.contains(a) || unless_attrs.contains(a))
+            || self.retract_attrs.iter().any(|a| unless_attrs.contains(a))
+            || self.replace_attrs.iter().any(|a| unless_attrs.contains(a))
+    }
+}
+
 /// The committed side of trigger dispatch for one induction run: the
 /// branch, the head every cache entry is keyed by, and the trigger
 /// footprint (the O(1) gate). All committed lookups flow through the
 /// branch's shared [`RuleCache`](crate::RuleCache) under the
 /// established disciplines — discovery head-keyed, hydrated bodies
 /// content-addressed, the overlay never head-cached.
-struct Dispatch<'a> {
+pub(super) struct Dispatch<'a> {
     branch: &'a Branch,
     head: Option<Revision>,
     footprint: TriggerFootprint,
@@ -300,9 +331,11 @@ struct Dispatch<'a> {
 /// rules, support edges, transience markers, and their retractions
 /// staged (or derived) in this very commit.
 #[derive(Default)]
-struct OverlayTriggers {
+pub(super) struct OverlayTriggers {
     /// `on:` entity → inductive-rule entities asserted in the overlay.
     on: HashMap<Entity, Vec<Entity>>,
+    /// `on:` entity → constraint-rule entities asserted in the overlay.
+    checks: HashMap<Entity, Vec<Entity>>,
     /// `on:` entity → deductive-rule entities asserted in the overlay.
     reads: HashMap<Entity, Vec<Entity>>,
     /// Rule entity → staged `dialog.rule/source` bytes.
@@ -318,8 +351,9 @@ struct OverlayTriggers {
 }
 
 impl OverlayTriggers {
-    fn scan(changes: &Changes) -> Self {
+    pub(super) fn scan(changes: &Changes) -> Self {
         let on = on_attr();
+        let checks = checks_attr();
         let reads = reads_attr();
         let source = source_attr();
         let transient = transient_attr();
@@ -336,6 +370,16 @@ impl OverlayTriggers {
                         .or_default()
                         .push(entity.clone());
                 }
+            } else if *attribute == checks {
+                if let Change::Assert(Value::Entity(key)) | Change::Replace(Value::Entity(key)) =
+                    change
+                {
+                    slice
+                        .checks
+                        .entry(key.clone())
+                        .or_default()
+                        .push(entity.clone());
+                }
             } else if *attribute == reads {
                 if let Change::Assert(Value::Entity(key)) | Change::Replace(Value::Entity(key)) =
                     change
@@ -374,8 +418,12 @@ impl OverlayTriggers {
 impl<'a> Dispatch<'a> {
     /// Resolve the committed dispatch state: the branch head and the
     /// trigger footprint at it (cached per head; one range scan over
-    /// each of `dialog.rule/on` and `dialog.rule/reads` on a miss).
-    async fn resolve<Env>(branch: &'a Branch, env: &Env) -> Result<Dispatch<'a>, CommitError>
+    /// each of `dialog.rule/on`, `dialog.rule/checks` and
+    /// `dialog.rule/reads` on a miss).
+    pub(super) async fn resolve<Env>(
+        branch: &'a Branch,
+        env: &Env,
+    ) -> Result<Dispatch<'a>, CommitError>
     where
         Env: Provider<Get>
             + Provider<Put>
@@ -405,6 +453,13 @@ impl<'a> Dispatch<'a> {
                         footprint.on.insert(key);
                     }
                 }
+                for claim in
+                    committed(branch, ArtifactSelector::new().the(checks_attr()), env).await?
+                {
+                    if let Value::Entity(key) = claim.is {
+                        footprint.checks.insert(key);
+                    }
+                }
                 for claim in
                     committed(branch, ArtifactSelector::new().the(reads_attr()), env).await?
                 {
@@ -470,6 +525,54 @@ impl<'a> Dispatch<'a> {
         Ok(entities)
     }
 
+    /// The constraint-rule entities watching `on`: the committed slice
+    /// (footprint-gated, head-cached) unioned with the overlay's, minus
+    /// rules the overlay retracts. The `dialog.rule/checks` sibling of
+    /// [`triggers`](Self::triggers).
+    pub(super) async fn checks<Env>(
+        &self,
+        on: &Entity,
+        overlay: &OverlayTriggers,
+        env: &Env,
+    ) -> Result<Vec<Entity>, CommitError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let mut entities: Vec<Entity> = Vec::new();
+        if let Some(head) = &self.head
+            && self.footprint.checks.contains(on)
+        {
+            let cache = self.branch.rule_cache();
+            let committed_entities = match cache.checks(on, head) {
+                Some(entities) => entities,
+                None => {
+                    let selector = ArtifactSelector::new()
+                        .the(checks_attr())
+                        .is(Value::Entity(on.clone()));
+                    let entities: Vec<Entity> = committed(self.branch, selector, env)
+                        .await?
+                        .into_iter()
+                        .map(|claim| claim.of)
+                        .collect();
+                    cache.record_checks(on.clone(), head.clone(), entities.clone());
+                    entities
+                }
+            };
+            entities.extend(committed_entities);
+        }
+        if let Some(staged) = overlay.checks.get(on) {
+            entities.extend(staged.iter().cloned());
+        }
+        entities.retain(|entity| !overlay.removed.contains(entity));
+        Ok(entities)
+    }
+
     /// Close `touched` over the deductive support graph: for each
     /// touched attribute, `dialog.rule/reads` names the deductive rules
     /// whose bodies read it; their conclusions' attributes are
@@ -478,7 +581,7 @@ impl<'a> Dispatch<'a> {
     /// late-installed deductive rules are picked up automatically.
     /// Polarity is deliberately ignored across derived edges: through
     /// negation, an assertion of a base fact can retract a derived one.
-    async fn expand_through_deduction<Env>(
+    pub(super) async fn expand_through_deduction<Env>(
         &self,
         touched: &mut BTreeSet<Attribute>,
         overlay: &OverlayTriggers,
@@ -545,7 +648,7 @@ impl<'a> Dispatch<'a> {
     /// bytes, then the committed source claim. A dangling or
     /// undecodable entry yields `None`, skipped like any dangling
     /// index entry.
-    async fn deductive<Env>(
+    pub(super) async fn deductive<Env>(
         &self,
         entity: &Entity,
         overlay: &OverlayTriggers,
@@ -623,6 +726,41 @@ impl<'a> Dispatch<'a> {
         Ok(Some(rule))
     }
 
+    /// Hydrate the constraint rule stored at `entity`, under the same
+    /// discipline as [`load`](Self::load): content-addressed cache, then
+    /// overlay bytes, then the committed source claim; dangling, forged,
+    /// or undecodable entries are inert.
+    pub(super) async fn constraint<Env>(
+        &self,
+        entity: &Entity,
+        overlay: &OverlayTriggers,
+        env: &Env,
+    ) -> Result<Option<ConstraintRule>, CommitError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let cache = self.branch.rule_cache();
+        if let Some(rule) = cache.constraint(entity) {
+            return Ok(Some(rule));
+        }
+        let bytes = match overlay.sources.get(entity) {
+            Some(bytes) => Some(bytes.clone()),
+            None => self.source_bytes(entity, env).await?,
+        };
+        Ok(bytes
+            .and_then(|bytes| hydrate_constraint(&bytes).ok())
+            .filter(|rule| rule.try_this() == Some(entity.clone()))
+            .inspect(|rule| {
+                cache.record_constraint(entity.clone(), rule.clone());
+            }))
+    }
+
     /// Whether the concept at `entity` carries the
     /// `dialog.concept/transient` marker: the overlay's verdict wins
     /// (marked or unmarked in this very commit), else the committed
@@ -871,16 +1009,11 @@ where
         .map_err(|error| CommitError::Induction(format!("dispatch probe: {error}")))
 }
 
-/// Evaluate one rule's body against the frozen round view and emit its
-/// head for every binding: transient heads into `transients`, durable
-/// heads (novelty-checked against the view) into `novelty`.
-async fn fire<'a, Env>(
-    rule: &InductiveRule,
-    transient_head: bool,
+/// Evaluate a rule body's full plan against the frozen round view.
+pub(super) async fn full_matches<'a, Env>(
+    plan: Conjunction,
     view: &QueryEnv<'a, Env>,
-    novelty: &mut Changes,
-    transients: &mut Changes,
-) -> Result<(), CommitError>
+) -> Result<Vec<Match>, CommitError>
 where
     Env: Provider<Get>
         + Provider<Put>
@@ -890,27 +1023,21 @@ where
         + ConditionalSync
         + 'static,
 {
-    let plan = rule.plan(&Environment::new());
-    let matches: Vec<Match> = plan
-        .evaluate(Match::new().seed(), view)
+    plan.evaluate(Match::new().seed(), view)
         .try_collect()
         .await
-        .map_err(|error| CommitError::Induction(format!("rule body: {error}")))?;
-    if matches.is_empty() {
-        return Ok(());
-    }
-    emit_matches(rule, transient_head, matches, view, novelty, transients).await
+        .map_err(|error| CommitError::Induction(format!("rule body: {error}")))
 }
 
 /// The attributes a rule's concept premises name, split by polarity:
 /// positive premise attributes (seedable by an assert/replace row) and
 /// `unless` attributes (only enabled by removal — never seedable).
-fn premise_attrs(rule: &InductiveRule) -> (BTreeSet<Attribute>, BTreeSet<Attribute>) {
+fn premise_attrs(analysis: &AnalyzedRule) -> (BTreeSet<Attribute>, BTreeSet<Attribute>) {
     use dialog_query::{Negation, Premise, Proposition};
 
     let mut positive = BTreeSet::new();
     let mut unless = BTreeSet::new();
-    for premise in &rule.analysis().premises {
+    for premise in &analysis.premises {
         let (target, query) = match premise {
             Premise::Assert(Proposition::Concept(query)) => (&mut positive, query),
             Premise::Unless(Negation(Proposition::Concept(query))) => (&mut unless, query),
@@ -923,22 +1050,21 @@ fn premise_attrs(rule: &InductiveRule) -> (BTreeSet<Attribute>, BTreeSet<Attribu
     (positive, unless)
 }
 
-/// Delta-restricted firing: bind each stimulus row into every positive
-/// concept premise that names its attribute, then evaluate the body
-/// with those bindings fixed — the remaining premises join against the
-/// frozen view through the planner as usual. Every new match this
-/// round must bind at least one new row into at least one positive
-/// premise (removal-enabled and derived-premise firings take the
-/// full-body path instead), so seeding is complete for this candidate
-/// class while costing the delta's join fan-out, not relation size.
-async fn fire_seeded<'a, Env>(
-    rule: &InductiveRule,
-    transient_head: bool,
+/// Delta-restricted evaluation: bind each stimulus row into every
+/// positive concept premise that names its attribute, then evaluate the
+/// body (planned by `plan` for the seeded scope) with those bindings
+/// fixed — the remaining premises join against the frozen view through
+/// the planner as usual. Every new match this round must bind at least
+/// one new row into at least one positive premise (removal-enabled and
+/// derived-premise matches take the full-body path instead), so seeding
+/// is complete for this candidate class while costing the delta's join
+/// fan-out, not relation size.
+pub(super) async fn seeded_matches<'a, Env>(
+    analysis: &AnalyzedRule,
+    plan: impl Fn(&Environment) -> Conjunction,
     rows: &[Artifact],
     view: &QueryEnv<'a, Env>,
-    novelty: &mut Changes,
-    transients: &mut Changes,
-) -> Result<(), CommitError>
+) -> Result<Vec<Match>, CommitError>
 where
     Env: Provider<Get>
         + Provider<Put>
@@ -951,7 +1077,7 @@ where
     use dialog_query::{Premise, Proposition};
 
     let mut matches: Vec<Match> = Vec::new();
-    for premise in &rule.analysis().premises {
+    for premise in &analysis.premises {
         let Premise::Assert(Proposition::Concept(query)) = premise else {
             continue;
         };
@@ -990,8 +1116,7 @@ where
                 continue;
             }
 
-            let plan = rule.plan(&scope);
-            let seeded_matches: Vec<Match> = plan
+            let seeded_matches: Vec<Match> = plan(&scope)
                 .evaluate(matched.seed(), view)
                 .try_collect()
                 .await
@@ -999,10 +1124,7 @@ where
             matches.extend(seeded_matches);
         }
     }
-    if matches.is_empty() {
-        return Ok(());
-    }
-    emit_matches(rule, transient_head, matches, view, novelty, transients).await
+    Ok(matches)
 }
 
 /// Bind a seed value into a premise term: a named variable binds (and
@@ -0,0 +1,312 @@
+//! Commit-time identity resolution: upsert by natural key.
+//!
+//! A [`Transaction::upsert`](super::Transaction::upsert) names a
+//! *provisional* entity by a key — "the entity whose `user/email` is
+//! `alice@example.com`". Before induction runs, each key is looked up
+//! through the VAE index: if one entity already holds it, the
+//! provisional entity is rewritten to it everywhere in the transaction
+//! (as a subject and as an entity-typed value), so the transaction's
+//! facts land on the existing entity; if none does, the provisional
+//! entity is kept and the key fact is asserted of it. Upserts in the
+//! same transaction that share a key resolve to the same entity.
+
+use std::collections::HashMap;
+
+use dialog_artifacts::{
+    Artifact, ArtifactSelector, Attribute, Changes, Entity, Instruction, Update, Value,
+};
+use dialog_capability::{Fork, Provider};
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::{Get, Put};
+use dialog_effects::memory::Resolve;
+use futures_util::TryStreamExt as _;
+
+use crate::{Branch, CommitError, RemoteSite};
+
+/// An upsert staged on a transaction: `of` stands for the entity whose
+/// `the` is `is`.
+#[derive(Debug, Clone)]
+pub(super) struct Identity {
+    pub(super) the: Attribute,
+    pub(super) is: Value,
+    pub(super) of: Entity,
+}
+
+/// Resolve `identities` against the branch, rewriting the provisional
+/// entities in `changes` and `transients` to the entities their keys
+/// identify.
+pub(super) async fn resolve<Env>(
+    branch: &Branch,
+    identities: Vec<Identity>,
+    changes: &mut Changes,
+    transients: &mut Changes,
+    env: &Env,
+) -> Result<(), CommitError>
+where
+    Env: Provider<Get>
+        + Provider<Put>
+        + Provider<Resolve>
+        + Provider<Fork<RemoteSite, Get>>
+        + Provider<Fork<RemoteSite, Resolve>>
+        + ConditionalSync
+        + 'static,
+{
+    if identities.is_empty() {
+        return Ok(());
+    }
+
+    let mut keys: HashMap<(Attribute, Value), Entity> = HashMap::new();
+    let mut resolved: HashMap<Entity, Entity> = HashMap::new();
+    let mut created: Vec<Identity> = Vec::new();
+    for identity in identities {
+        let key = (identity.the.clone(), identity.is.clone());
+        let target = match keys.get(&key) {
+            Some(target) => target.clone(),
+            None => {
+                let selector = ArtifactSelector::new()
+                    .the(identity.the.clone())
+                    .is(identity.is.clone());
+                let standing: Vec<Artifact> = branch
+                    .claims()
+                    .select(selector)
+                    .to_owned()
+                    .perform(env)
+                    .await?
+                    .try_collect()
+                    .await?;
+                let mut holders: Vec<Entity> = Vec::new();
+                for artifact in standing {
+                    if !holders.contains(&artifact.of) {
+                        holders.push(artifact.of);
+                    }
+                }
+                let target = match holders.as_slice() {
+                    [] => {
+                        created.push(identity.clone());
+                        identity.of.clone()
+                    }
+                    [holder] => holder.clone(),
+                    _ => {
+                        return Err(CommitError::AmbiguousIdentity {
+                            the: Box::new(identity.the),
+                            is: Box::new(identity.is),
+                            holders,
+                        });
+                    }
+                };
+                keys.insert(key, target.clone());
+                target
+            }
+        };
+
+        // Two keys of the same provisional entity must agree on what
+        // it is.
+        match resolved.get(&identity.of) {
+            Some(previous) if *previous != target => {
+                return Err(CommitError::AmbiguousIdentity {
+                    the: Box::new(identity.the),
+                    is: Box::new(identity.is),
+                    holders: vec![previous.clone(), target],
+                });
+            }
+            _ => {
+                resolved.insert(identity.of, target);
+            }
+        }
+    }
+
+    resolved.retain(|provisional, target| provisional != target);
+    if !resolved.is_empty() {
+        *changes = substitute(changes, &resolved);
+        *transients = substitute(transients, &resolved);
+    }
+    for Identity { the, is, of } in created {
+        let of = resolved.get(&of).cloned().unwrap_or(of);
+        Update::associate(changes, the, of, is);
+    }
+    Ok(())
+}
+
+/// Replay `changes` with every entity in `resolved` replaced by what it
+/// resolves to.
+fn substitute(changes: &Changes, resolved: &HashMap<Entity, Entity>) -> Changes {
+    let entity = |entity: Entity| resolved.get(&entity).cloned().unwrap_or(entity);
+    let value = |value: Value| match value {
+        Value::Entity(of) => Value::Entity(entity(of)),
+        value => value,
+    };
+    let mut rewritten = Changes::new();
+    for instruction in changes.clone().into_instructions() {
+        match instruction {
+            Instruction::Assert(a) => {
+                Update::associate(&mut rewritten, a.the, entity(a.of), value(a.is));
+            }
+            Instruction::Replace(a) => {
+                Update::associate_unique(&mut rewritten, a.the, entity(a.of), value(a.is));
+            }
+            Instruction::Retract(a) => {
+                Update::dissociate(&mut rewritten, a.the, entity(a.of), value(a.is));
+            }
+        }
+    }
+    rewritten
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::helpers::test_repo;
+    use anyhow::Result;
+    use dialog_operator::helpers::test_operator_with_profile;
+    use dialog_query::the;
+
+    async fn holders<Env>(branch: &Branch, the: &str, is: Value, env: &Env) -> Result<Vec<Entity>>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        let selector = ArtifactSelector::new().the(the.parse()?).is(is);
+        let standing: Vec<Artifact> = branch
+            .claims()
+            .select(selector)
+            .to_owned()
+            .perform(env)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(standing.into_iter().map(|artifact| artifact.of).collect())
+    }
+
+    #[dialog_common::test]
+    async fn it_reuses_the_entity_a_key_identifies() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let email = "alice@example.com".to_string();
+        let first = Entity::new()?;
+        branch
+            .transaction()
+            .upsert(
+                "user/email".parse::<Attribute>()?,
+                email.clone(),
+                first.clone(),
+            )
+            .assert(the!("user/name").of(first.clone()).is("Alice".to_string()))
+            .commit()
+            .perform(&operator)
+            .await?;
+        assert_eq!(
+            holders(
+                &branch,
+                "user/email",
+                Value::String(email.clone()),
+                &operator
+            )
+            .await?,
+            vec![first.clone()]
+        );
+
+        // A second provisional entity with the same key lands on the
+        // first, including where it is referenced as a value.
+        let second = Entity::new()?;
+        let team: Entity = "team:core".parse()?;
+        branch
+            .transaction()
+            .upsert(
+                "user/email".parse::<Attribute>()?,
+                email.clone(),
+                second.clone(),
+            )
+            .assert(
+                the!("user/name")
+                    .of(second.clone())
+                    .is("Alice B.".to_string()),
+            )
+            .assert(the!("team/member").of(team).is(second.clone()))
+            .commit()
+            .perform(&operator)
+            .await?;
+        assert_eq!(
+            holders(&branch, "user/email", Value::String(email), &operator).await?,
+            vec![first.clone()]
+        );
+        let mut names = holders(
+            &branch,
+            "user/name",
+            Value::String("Alice B.".into()),
+            &operator,
+        )
+        .await?;
+        names.extend(
+            holders(
+                &branch,
+                "team/member",
+                Value::Entity(first.clone()),
+                &operator,
+            )
+            .await?,
+        );
+        assert_eq!(names, vec![first.clone(), "team:core".parse()?]);
+        assert!(
+            holders(&branch, "team/member", Value::Entity(second), &operator)
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_unifies_upserts_sharing_a_key_and_rejects_ambiguous_ones() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let key: Attribute = "user/email".parse()?;
+        let email = "bob@example.com".to_string();
+        let (one, two) = (Entity::new()?, Entity::new()?);
+        branch
+            .transaction()
+            .upsert(key.clone(), email.clone(), one.clone())
+            .upsert(key.clone(), email.clone(), two.clone())
+            .assert(the!("user/name").of(two).is("Bob".to_string()))
+            .commit()
+            .perform(&operator)
+            .await?;
+        assert_eq!(
+            holders(&branch, "user/name", Value::String("Bob".into()), &operator).await?,
+            vec![one.clone()]
+        );
+
+        // Without strict mode nothing stops two entities from sharing a
+        // key; an upsert by it then can't pick one.
+        let other = Entity::new()?;
+        branch
+            .transaction()
+            .assert(the!("user/email").of(other).is(email.clone()))
+            .commit()
+            .perform(&operator)
+            .await?;
+        let head = branch.revision();
+        let rejected = branch
+            .transaction()
+            .upsert(key, email, Entity::new()?)
+            .commit()
+            .perform(&operator)
+            .await;
+        assert!(
+            matches!(&rejected, Err(CommitError::AmbiguousIdentity { holders, .. }) if holders.len() == 2),
+            "{rejected:?}"
+        );
+        assert_eq!(branch.revision(), head);
+        Ok(())
+    }
+}
@@ -0,0 +1,593 @@
+use std::collections::HashMap;
+
+use dialog_artifacts::{Artifact, ArtifactSelector, Attribute, Entity, Instruction, Value};
+use dialog_capability::{Fork, Provider};
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::{Get, Put};
+use dialog_effects::memory::Resolve;
+use dialog_query::attribute::{descriptor_attr, descriptor_entity};
+use dialog_query::{AttributeDescriptor, Cardinality};
+use futures_util::TryStreamExt as _;
+
+use crate::{Branch, CommitError, RemoteSite, SchemaViolation};
+
+/// Which descriptor checks [`Branch::validate`] runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Checks {
+    /// Every check a descriptor implies: content type, cardinality and
+    /// uniqueness (a [strict](crate::Commit::strict) commit).
+    All,
+    /// Only uniqueness, which every commit is held to.
+    Unique,
+}
+
+impl Branch {
+    /// Check a commit's instructions against the attribute descriptors
+    /// registered on this branch and those the instructions register
+    /// themselves (see [`Commit::strict`](crate::Commit::strict)).
+    ///
+    /// With [`Checks::Unique`] only unique attributes are checked: a
+    /// commit that touches no application attribute reads nothing, and
+    /// one on a branch with no unique descriptor reads only the registry,
+    /// which the branch scans once per revision (see [`RegistryCache`]).
+    ///
+    /// [`RegistryCache`]: super::RegistryCache
+    pub(super) async fn validate<Env>(
+        &self,
+        env: &Env,
+        instructions: &[Instruction],
+        checks: Checks,
+    ) -> Result<(), CommitError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + ConditionalSync
+            + 'static,
+    {
+        // Registrations staged in this commit take precedence over the
+        // branch's: `None` records an unregistration.
+        let registry = descriptor_attr();
+        let mut descriptors: HashMap<Attribute, Option<AttributeDescriptor>> = HashMap::new();
+        for instruction in instructions {
+            let (Instruction::Assert(artifact)
+            | Instruction::Replace(artifact)
+            | Instruction::Retract(artifact)) = instruction;
+            if artifact.the != registry {
+                continue;
+            }
+            let Some(descriptor) = registered(artifact) else {
+                continue;
+            };
+            let attribute = Attribute::from(descriptor.the());
+            match instruction {
+                Instruction::Retract(_) => descriptors.insert(attribute, None),
+                _ => descriptors.insert(attribute, Some(descriptor)),
+            };
+        }
+
+        if checks == Checks::Unique {
+            let touched = instructions.iter().any(|instruction| {
+                let (Instruction::Assert(artifact) | Instruction::Replace(artifact)) = instruction
+                else {
+                    return false;
+                };
+                !artifact.the.as_str().starts_with("dialog.")
+            });
+            if !touched {
+                return Ok(());
+            }
+        }
+        let registry = self.registry(env).await?;
+        if checks == Checks::Unique
+            && registry.unique().next().is_none()
+            && !descriptors
+                .values()
+                .flatten()
+                .any(AttributeDescriptor::is_unique)
+        {
+            return Ok(());
+        }
+
+        // What each (entity, attribute) is asserted and retracted to in
+        // this commit, for the cardinality-one checks, and which entities
+        // each (attribute, value) is asserted of, for the unique ones.
+        let mut asserted: HashMap<(&Entity, &Attribute), Vec<&Value>> = HashMap::new();
+        let mut retracted: HashMap<(&Entity, &Attribute), Vec<&Value>> = HashMap::new();
+        let mut holders: HashMap<(&Attribute, &Value), Vec<&Entity>> = HashMap::new();
+        for instruction in instructions {
+            match instruction {
+                Instruction::Assert(artifact) | Instruction::Replace(artifact) => {
+                    let values = asserted.entry((&artifact.of, &artifact.the)).or_default();
+                    if !values.contains(&&artifact.is) {
+                        values.push(&artifact.is);
+                    }
+                    let entities = holders.entry((&artifact.the, &artifact.is)).or_default();
+                    if !entities.contains(&&artifact.of) {
+                        entities.push(&artifact.of);
+                    }
+                }
+                Instruction::Retract(artifact) => {
+                    retracted
+                        .entry((&artifact.of, &artifact.the))
+                        .or_default()
+                        .push(&artifact.is);
+                }
+            }
+        }
+
+        for instruction in instructions {
+            let (Instruction::Assert(artifact) | Instruction::Replace(artifact)) = instruction
+            else {
+                continue;
+            };
+            // Rule, concept and descriptor storage is validated by the
+            // machinery that reads it, not by descriptors.
+            if artifact.the.as_str().starts_with("dialog.") {
+                continue;
+            }
+            let descriptor = match descriptors.get(&artifact.the) {
+                Some(staged) => staged.as_ref(),
+                None => registry.get(&artifact.the),
+            };
+            let Some(descriptor) = descriptor else {
+                continue;
+            };
+            if checks == Checks::Unique && !descriptor.is_unique() {
+                continue;
+            }
+
+            if checks == Checks::All
+                && let Some(expected) = descriptor.content_type()
+                && !descriptor.admits(&artifact.is)
+            {
+                return Err(violation(
+                    artifact,
+                    SchemaViolation::TypeMismatch {
+                        expected,
+                        actual: artifact.is.data_type(),
+                    },
+                ));
+            }
+
+            if descriptor.is_unique() {
+                if let Some(holder) = holders
+                    .get(&(&artifact.the, &artifact.is))
+                    .and_then(|entities| entities.iter().find(|entity| ***entity != artifact.of))
+                {
+                    return Err(violation(
+                        artifact,
+                        SchemaViolation::Unique {
+                            holder: (*holder).clone(),
+                        },
+                    ));
+                }
+                let selector = ArtifactSelector::new()
+                    .the(artifact.the.clone())
+                    .is(artifact.is.clone());
+                let standing: Vec<Artifact> = self
+                    .claims()
+                    .select(selector)
+                    .to_owned()
+                    .perform(env)
+                    .await?
+                    .try_collect()
+                    .await?;
+                if let Some(holder) = standing.into_iter().find(|other| {
+                    other.of != artifact.of
+                        && !retracted
+                            .get(&(&other.of, &other.the))
+                            .is_some_and(|values| values.contains(&&other.is))
+                }) {
+                    return Err(violation(
+                        artifact,
+                        SchemaViolation::Unique { holder: holder.of },
+                    ));
+                }
+            }
+
+            if checks == Checks::Unique || descriptor.cardinality() != Cardinality::One {
+                continue;
+            }
+            let slot = (&artifact.of, &artifact.the);
+            if let Some(other) = asserted
+                .get(&slot)
+                .and_then(|values| values.iter().find(|value| ***value != artifact.is))
+            {
+                return Err(violation(
+                    artifact,
+                    SchemaViolation::Cardinality {
+                        other: (*other).clone(),
+                    },
+                ));
+            }
+            // A replacement supersedes whatever stands; a plain assertion
+            // joins it.
+            if let Instruction::Assert(_) = instruction {
+                let selector = ArtifactSelector::new()
+                    .of(artifact.of.clone())
+                    .the(artifact.the.clone());
+                let standing: Vec<Artifact> = self
+                    .claims()
+                    .select(selector)
+                    .to_owned()
+                    .perform(env)
+                    .await?
+                    .try_collect()
+                    .await?;
+                let withdrawn = retracted.get(&slot);
+                if let Some(other) = standing.into_iter().find(|other| {
+                    other.is != artifact.is
+                        && !withdrawn.is_some_and(|values| values.contains(&&other.is))
+                }) {
+                    return Err(violation(
+                        artifact,
+                        SchemaViolation::Cardinality { other: other.is },
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The descriptor a `dialog.attribute/descriptor` fact registers, when it
+/// decodes and names the attribute it is registered under. Anything else
+/// is inert.
+pub(super) fn registered(artifact: &Artifact) -> Option<AttributeDescriptor> {
+    let Value::Bytes(bytes) = &artifact.is else {
+        return None;
+    };
+    let descriptor = AttributeDescriptor::decode(bytes).ok()?;
+    let entity = descriptor_entity(&Attribute::from(descriptor.the()))?;
+    (entity == artifact.of).then_some(descriptor)
+}
+
+fn violation(artifact: &Artifact, violation: SchemaViolation) -> CommitError {
+    CommitError::SchemaViolation {
+        claim: Box::new(artifact.clone()),
+        violation,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use super::*;
+    use crate::helpers::test_repo;
+    use anyhow::Result;
+    use dialog_artifacts::{Changes, Update as _, ValueDataType};
+    use dialog_operator::helpers::test_operator_with_profile;
+    use dialog_query::the;
+    use futures_util::stream;
+
+    fn views() -> AttributeDescriptor {
+        AttributeDescriptor::new(
+            the!("post/views"),
+            "How often a post was read",
+            Cardinality::One,
+            Some(ValueDataType::UnsignedInt),
+        )
+    }
+
+    fn fact(is: Value) -> Artifact {
+        Artifact {
+            the: "post/views".parse().unwrap(),
+            of: "post:1".parse().unwrap(),
+            is,
+            cause: None,
+        }
+    }
+
+    fn assertion(artifact: Artifact) -> Changes {
+        let mut changes = Changes::new();
+        changes.associate(artifact.the, artifact.of, artifact.is);
+        changes
+    }
+
+    #[dialog_common::test]
+    async fn it_rejects_values_of_the_wrong_type_in_strict_mode() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        // A descriptor registered in the same transaction already applies.
+        let rejected = branch
+            .transaction()
+            .assert(views())
+            .integrate(assertion(fact(Value::String("many".into()))))
+            .strict()
+            .commit()
+            .perform(&operator)
+            .await;
+        let Err(CommitError::SchemaViolation { claim, violation }) = rejected else {
+            panic!("expected a schema violation, got {rejected:?}");
+        };
+        assert_eq!(*claim, fact(Value::String("many".into())));
+        assert_eq!(
+            violation,
+            SchemaViolation::TypeMismatch {
+                expected: ValueDataType::UnsignedInt,
+                actual: ValueDataType::String,
+            }
+        );
+        assert!(branch.revision().is_none(), "nothing was written");
+
+        branch
+            .transaction()
+            .assert(views())
+            .commit()
+            .perform(&operator)
+            .await?;
+        let rejected = branch
+            .commit(stream::iter(vec![Instruction::Assert(fact(
+                Value::String("many".into()),
+            ))]))
+            .strict()
+            .perform(&operator)
+            .await;
+        assert!(matches!(rejected, Err(CommitError::SchemaViolation { .. })));
+        branch
+            .commit(stream::iter(vec![Instruction::Assert(fact(
+                Value::UnsignedInt(3),
+            ))]))
+            .strict()
+            .perform(&operator)
+            .await?;
+
+        // Outside strict mode, nothing is checked.
+        branch
+            .commit(stream::iter(vec![Instruction::Replace(fact(
+                Value::String("many".into()),
+            ))]))
+            .perform(&operator)
+            .await?;
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_rejects_a_second_value_for_a_cardinality_one_attribute() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        branch
+            .transaction()
+            .assert(views())
+            .commit()
+            .perform(&operator)
+            .await?;
+
+        let rejected = branch
+            .commit(stream::iter(vec![
+                Instruction::Assert(fact(Value::UnsignedInt(1))),
+                Instruction::Assert(fact(Value::UnsignedInt(2))),
+            ]))
+            .strict()
+            .perform(&operator)
+            .await;
+        assert!(
+            matches!(
+                &rejected,
+                Err(CommitError::SchemaViolation {
+                    violation: SchemaViolation::Cardinality { .. },
+                    ..
+                })
+            ),
+            "{rejected:?}"
+        );
+
+        branch
+            .commit(stream::iter(vec![Instruction::Assert(fact(
+                Value::UnsignedInt(1),
+            ))]))
+            .strict()
+            .perform(&operator)
+            .await?;
+        let rejected = branch
+            .commit(stream::iter(vec![Instruction::Assert(fact(
+                Value::UnsignedInt(2),
+            ))]))
+            .strict()
+            .perform(&operator)
+            .await;
+        let Err(CommitError::SchemaViolation { violation, .. }) = rejected else {
+            panic!("expected a schema violation, got {rejected:?}");
+        };
+        assert_eq!(
+            violation,
+            SchemaViolation::Cardinality {
+                other: Value::UnsignedInt(1)
+            }
+        );
+
+        // Replacing, or retracting the standing value alongside, is fine.
+        branch
+            .commit(stream::iter(vec![Instruction::Replace(fact(
+                Value::UnsignedInt(2),
+            ))]))
+            .strict()
+            .perform(&operator)
+            .await?;
+        branch
+            .commit(stream::iter(vec![
+                Instruction::Retract(fact(Value::UnsignedInt(2))),
+                Instruction::Assert(fact(Value::UnsignedInt(3))),
+            ]))
+            .strict()
+            .perform(&operator)
+            .await?;
+
+        // Unregistering the descriptor lifts the checks.
+        branch
+            .transaction()
+            .retract(views())
+            .commit()
+            .perform(&operator)
+            .await?;
+        branch
+            .commit(stream::iter(vec![Instruction::Assert(fact(
+                Value::UnsignedInt(4),
+            ))]))
+            .strict()
+            .perform(&operator)
+            .await?;
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_rejects_a_unique_value_held_by_another_entity() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let email = AttributeDescriptor::new(
+            the!("user/email"),
+            "A user's email address",
+            Cardinality::One,
+            Some(ValueDataType::String),
+        )
+        .unique();
+        let alice: Entity = "user:alice".parse()?;
+        let bob: Entity = "user:bob".parse()?;
+        let address = || "a@example.com".to_string();
+        branch
+            .transaction()
+            .assert(email)
+            .assert(the!("user/email").of(alice.clone()).is(address()))
+            .strict()
+            .commit()
+            .perform(&operator)
+            .await?;
+
+        let rejected = branch
+            .transaction()
+            .assert(the!("user/email").of(bob.clone()).is(address()))
+            .strict()
+            .commit()
+            .perform(&operator)
+            .await;
+        let Err(CommitError::SchemaViolation { violation, .. }) = rejected else {
+            panic!("expected a schema violation, got {rejected:?}");
+        };
+        assert_eq!(
+            violation,
+            SchemaViolation::Unique {
+                holder: alice.clone()
+            }
+        );
+
+        // Re-asserting it of its holder is fine, and so is handing it
+        // over in one commit.
+        branch
+            .transaction()
+            .assert(the!("user/email").of(alice.clone()).is(address()))
+            .strict()
+            .commit()
+            .perform(&operator)
+            .await?;
+        branch
+            .transaction()
+            .retract(the!("user/email").of(alice).is(address()))
+            .assert(the!("user/email").of(bob).is(address()))
+            .strict()
+            .commit()
+            .perform(&operator)
+            .await?;
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_enforces_unique_values_without_strict_mode() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let email = AttributeDescriptor::new(
+            the!("user/email"),
+            "A user's email address",
+            Cardinality::One,
+            Some(ValueDataType::String),
+        )
+        .unique();
+        let alice: Entity = "user:alice".parse()?;
+        let bob: Entity = "user:bob".parse()?;
+        let carol: Entity = "user:carol".parse()?;
+        let address = || "a@example.com".to_string();
+
+        // A descriptor registered in the same commit already applies.
+        let rejected = branch
+            .transaction()
+            .assert(email)
+            .assert(the!("user/email").of(alice.clone()).is(address()))
+            .assert(the!("user/email").of(bob.clone()).is(address()))
+            .commit()
+            .perform(&operator)
+            .await;
+        assert!(
+            matches!(
+                rejected,
+                Err(CommitError::SchemaViolation {
+                    violation: SchemaViolation::Unique { .. },
+                    ..
+                })
+            ),
+            "expected a uniqueness violation, got {rejected:?}"
+        );
+
+        branch
+            .transaction()
+            .assert(
+                AttributeDescriptor::new(
+                    the!("user/email"),
+                    "A user's email address",
+                    Cardinality::One,
+                    Some(ValueDataType::String),
+                )
+                .unique(),
+            )
+            .assert(the!("user/email").of(alice.clone()).is(address()))
+            .commit()
+            .perform(&operator)
+            .await?;
+
+        // Plain commits and transactions alike are held to it, while
+        // other descriptor checks stay opt-in.
+        let rejected = branch
+            .commit(stream::iter(vec![Instruction::Assert(Artifact {
+                the: "user/email".parse()?,
+                of: bob,
+                is: Value::String(address()),
+                cause: None,
+            })]))
+            .perform(&operator)
+            .await;
+        let Err(CommitError::SchemaViolation { violation, .. }) = rejected else {
+            panic!("expected a schema violation, got {rejected:?}");
+        };
+        assert_eq!(
+            violation,
+            SchemaViolation::Unique {
+                holder: alice.clone()
+            }
+        );
+        let rejected = branch
+            .transaction()
+            .assert(the!("user/email").of(carol.clone()).is(address()))
+            .commit()
+            .perform(&operator)
+            .await;
+        assert!(matches!(rejected, Err(CommitError::SchemaViolation { .. })));
+
+        branch
+            .transaction()
+            .assert(the!("user/email").of(carol).is(7u64))
+            .commit()
+            .perform(&operator)
+            .await?;
+        Ok(())
+    }
+}
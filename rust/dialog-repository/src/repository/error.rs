@@ -1,5 +1,5 @@
 use crate::TreeReference;
-use dialog_artifacts::DialogArtifactsError;
+use dialog_artifacts::{Artifact, Attribute, DialogArtifactsError, Entity, Value, ValueDataType};
 use dialog_capability::access::AuthorizeError;
 use dialog_common::Blake3Hash;
 use dialog_credentials::Ed25519SignerError;
@@ -11,6 +11,8 @@ use dialog_effects::memory::{MemoryError, Version};
 use dialog_effects::storage::StorageError;
 use dialog_search_tree::DialogSearchTreeError;
 use dialog_storage::DialogStorageError;
+use dialog_ucan_core::promise::Unresolved;
+use std::collections::BTreeMap;
 use std::io;
 use thiserror::Error;
 
@@ -151,6 +153,159 @@ pub enum LoadBranchError {
     Resolve(#[from] ResolveError),
 }
 
+/// Errors returned by the create tag command.
+#[derive(Error, Debug)]
+pub enum CreateTagError {
+    /// The tag name is empty.
+    #[error("Invalid tag name {name:?}")]
+    InvalidName {
+        /// The rejected name.
+        name: String,
+    },
+
+    /// A tag by this name already exists. Tags do not move; delete it
+    /// first to repoint it.
+    #[error("Tag {name} already exists")]
+    AlreadyExists {
+        /// The tag name.
+        name: String,
+    },
+
+    /// Identifying or signing as the tagger failed.
+    #[error("Failed to sign tag: {0}")]
+    Authority(#[from] AuthorityError),
+
+    /// Publishing the tag cell failed.
+    #[error("Failed to publish tag: {0}")]
+    Publish(#[from] PublishError),
+}
+
+/// Errors returned by the load tag command.
+#[derive(Error, Debug)]
+pub enum LoadTagError {
+    /// No tag by this name exists.
+    #[error("Tag {name} not found")]
+    NotFound {
+        /// The tag name.
+        name: String,
+    },
+
+    /// Failed to resolve the tag cell.
+    #[error("Failed to resolve tag cell: {0}")]
+    Resolve(#[from] ResolveError),
+}
+
+/// Errors returned by the delete tag command.
+#[derive(Error, Debug)]
+pub enum DeleteTagError {
+    /// No tag by this name exists.
+    #[error("Tag {name} not found")]
+    NotFound {
+        /// The tag name.
+        name: String,
+    },
+
+    /// Failed to resolve the tag cell.
+    #[error("Failed to resolve tag cell: {0}")]
+    Resolve(#[from] ResolveError),
+
+    /// Retracting the tag cell failed, including when the tag changed
+    /// since it was read.
+    #[error("Failed to retract tag cell: {0}")]
+    Memory(#[from] MemoryError),
+}
+
+/// Errors returned by the list tags command.
+#[derive(Error, Debug)]
+pub enum ListTagsError {
+    /// Listing the tag cells failed.
+    #[error("Failed to list tags: {0}")]
+    Memory(#[from] MemoryError),
+}
+
+/// Errors returned by the push tag command.
+#[derive(Error, Debug)]
+pub enum PushTagError {
+    /// Loading the local tag failed.
+    #[error("Failed to load tag: {0}")]
+    LoadTag(#[from] LoadTagError),
+
+    /// The tagged tree is not at the remote; push the branch it was
+    /// committed on first.
+    #[error("Tag {name} names tree {tree}, which the remote does not hold")]
+    Unreplicated {
+        /// The tag name.
+        name: String,
+        /// The tagged tree's root.
+        tree: TreeReference,
+    },
+
+    /// The remote holds a different tag by this name.
+    #[error("Remote already has a different tag {name}")]
+    Conflict {
+        /// The tag name.
+        name: String,
+    },
+
+    /// Checking the remote archive for the tagged tree failed.
+    #[error("Failed to read remote archive: {0}")]
+    Archive(#[from] ArchiveError),
+
+    /// Resolving the remote tag cell failed.
+    #[error("Failed to resolve remote tag: {0}")]
+    Resolve(#[from] ResolveError),
+
+    /// Publishing the remote tag cell failed.
+    #[error("Failed to publish remote tag: {0}")]
+    Publish(#[from] PublishError),
+}
+
+/// Errors returned by the pull tag command.
+#[derive(Error, Debug)]
+pub enum PullTagError {
+    /// The remote has no tag by this name.
+    #[error("Tag {name} not found on remote {remote}")]
+    NotFound {
+        /// The tag name.
+        name: String,
+        /// The remote name.
+        remote: String,
+    },
+
+    /// The remote's tag cell holds a tag carrying another name.
+    #[error("Remote tag {name} carries the name {carried}")]
+    Misplaced {
+        /// The name the tag was found under.
+        name: String,
+        /// The name the tag itself carries.
+        carried: String,
+    },
+
+    /// The local repository holds a different tag by this name.
+    #[error("Tag {name} already exists locally and names another revision")]
+    Conflict {
+        /// The tag name.
+        name: String,
+    },
+
+    /// The tag or its tagged head failed verification.
+    #[error("Remote tag failed verification: {0}")]
+    Artifact(#[from] DialogArtifactsError),
+
+    /// Resolving a tag cell failed.
+    #[error("Failed to resolve tag: {0}")]
+    Resolve(#[from] ResolveError),
+
+    /// Publishing the local tag cell failed.
+    #[error("Failed to publish tag: {0}")]
+    Publish(#[from] PublishError),
+
+    /// Holding the archive for the pull failed, or a garbage collection
+    /// holds it.
+    #[error("Failed to hold the archive for tag pull: {0}")]
+    Lease(#[from] CommitError),
+}
+
 /// Errors specific to setting a branch's upstream.
 #[derive(Error, Debug)]
 pub enum SetUpstreamError {
@@ -167,6 +322,19 @@ pub enum SetUpstreamError {
     Publish(#[from] PublishError),
 }
 
+/// Errors specific to resetting a branch's head.
+#[derive(Error, Debug)]
+pub enum ResetError {
+    /// Holding the archive for the reset failed, or a garbage collection
+    /// holds it.
+    #[error("Failed to hold the archive for reset: {0}")]
+    Lease(#[from] CommitError),
+
+    /// Publishing the new head failed.
+    #[error("Failed to publish reset head: {0}")]
+    Publish(#[from] PublishError),
+}
+
 /// Errors specific to a branch fetch operation.
 #[derive(Error, Debug)]
 pub enum FetchError {
@@ -231,6 +399,166 @@ pub enum CommitError {
     /// Evaluating or loading an inductive rule during commit failed.
     #[error("Commit-time induction failed: {0}")]
     Induction(String),
+
+    /// A strict commit asserted a claim its attribute's registered
+    /// descriptor rejects ([`Commit::strict`](crate::Commit::strict)).
+    #[error("Claim {} of {} violates its attribute descriptor: {violation}", claim.the, claim.of)]
+    SchemaViolation {
+        /// The offending claim.
+        claim: Box<Artifact>,
+        /// What about it the descriptor rejects.
+        violation: SchemaViolation,
+    },
+
+    /// The commit would leave the branch in a state a constraint rule's
+    /// body matches.
+    #[error("Commit violates constraint {rule}: {} violating binding(s)", bindings.len())]
+    ConstraintViolation {
+        /// The violated constraint rule.
+        rule: Entity,
+        /// Each violation's bindings: the subject (`this`) and the
+        /// violation head's fields.
+        bindings: Vec<BTreeMap<String, Value>>,
+    },
+
+    /// An upsert's natural key identifies more than one entity, so it
+    /// can't say which one to reuse.
+    #[error("Upsert key {the} = {is:?} identifies {} entities", holders.len())]
+    AmbiguousIdentity {
+        /// The key attribute.
+        the: Box<Attribute>,
+        /// The key value.
+        is: Box<Value>,
+        /// The entities the key resolved to.
+        holders: Vec<Entity>,
+    },
+
+    /// A garbage collection holds the archive; nothing was written.
+    /// Retry once it finishes.
+    #[error("A garbage collection is running; retry the write once it finishes")]
+    Collecting,
+}
+
+/// How a claim violates its attribute's registered descriptor.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SchemaViolation {
+    /// The value is not of the attribute's content type.
+    #[error("expected a {expected} value, got {actual}")]
+    TypeMismatch {
+        /// The descriptor's content type.
+        expected: ValueDataType,
+        /// The asserted value's type.
+        actual: ValueDataType,
+    },
+
+    /// The attribute is cardinality-one and the entity would hold another
+    /// value too.
+    #[error("cardinality-one attribute would also hold {other:?}")]
+    Cardinality {
+        /// The other value, asserted in the same commit or already standing.
+        other: Value,
+    },
+
+    /// The attribute is a unique identity and another entity holds the
+    /// value too.
+    #[error("unique attribute value is already held by {holder}")]
+    Unique {
+        /// The other entity, asserted in the same commit or already
+        /// standing.
+        holder: Entity,
+    },
+}
+
+/// Errors specific to a history compaction.
+#[derive(Error, Debug)]
+pub enum CompactError {
+    /// Neither the command nor the branch named a retention policy.
+    #[error("Branch {branch} has no retention policy to compact by")]
+    NoRetention {
+        /// The branch with no retention policy.
+        branch: String,
+    },
+
+    /// Reading the history to compact failed.
+    #[error("Failed to read history during compaction: {0}")]
+    Artifact(#[from] DialogArtifactsError),
+
+    /// Identifying or attesting as the current authority failed.
+    #[error("Failed to sign checkpoint: {0}")]
+    Authority(#[from] AuthorityError),
+
+    /// Committing the checkpoint failed.
+    #[error("Failed to commit checkpoint: {0}")]
+    Commit(#[from] CommitError),
+}
+
+/// Errors specific to reverting a revision.
+#[derive(Error, Debug)]
+pub enum RevertError {
+    /// The revision is not in the branch's history: it was never merged
+    /// into the branch, or the branch has no commits at all.
+    #[error("Revision {version} is not in the history of branch {branch}")]
+    NotInHistory {
+        /// The version of the revision asked to be reverted.
+        version: String,
+        /// The branch asked to revert it.
+        branch: String,
+    },
+
+    /// The revision wrote machinery facts, which only the machinery that
+    /// wrote them can withdraw.
+    #[error("Revision {version} wrote reserved attribute {attribute}")]
+    Reserved {
+        /// The version of the revision asked to be reverted.
+        version: String,
+        /// The first reserved attribute it wrote.
+        attribute: String,
+    },
+
+    /// Reading the revision's records, or the facts standing at the head,
+    /// failed. Records compacted into a checkpoint or never replicated
+    /// surface here as `IncompleteHistory`.
+    #[error("Failed to read history during revert: {0}")]
+    Artifact(#[from] DialogArtifactsError),
+
+    /// A search-tree read during revert failed.
+    #[error("Tree operation failed during revert: {0}")]
+    Tree(#[from] DialogSearchTreeError),
+
+    /// Committing the inverse changes failed.
+    #[error("Failed to commit revert: {0}")]
+    Commit(#[from] CommitError),
+}
+
+/// Errors specific to cherry-picking a revision.
+#[derive(Error, Debug)]
+pub enum CherryPickError {
+    /// The revision's records are not in its own tree, locally or at the
+    /// remote the pick reads through: it was compacted, or never
+    /// replicated.
+    #[error("Records of revision {version} are not available")]
+    NotReplicated {
+        /// The version of the revision asked to be picked.
+        version: String,
+    },
+
+    /// The revision wrote machinery facts, which only the machinery that
+    /// wrote them can replay.
+    #[error("Revision {version} wrote reserved attribute {attribute}")]
+    Reserved {
+        /// The version of the revision asked to be picked.
+        version: String,
+        /// The first reserved attribute it wrote.
+        attribute: String,
+    },
+
+    /// Reading the revision's records failed.
+    #[error("Failed to read history during cherry-pick: {0}")]
+    Artifact(#[from] DialogArtifactsError),
+
+    /// Committing the replayed changes failed.
+    #[error("Failed to commit cherry-pick: {0}")]
+    Commit(#[from] CommitError),
 }
 
 /// Errors specific to a pull operation.
@@ -294,6 +622,11 @@ pub enum PullError {
     /// ([`Pull::download`](crate::Pull::download)).
     #[error("Download after pull failed: {0}")]
     Download(#[from] DownloadError),
+
+    /// Committing the resolution of concurrent values failed
+    /// ([`Pull::resolve`](crate::Pull::resolve)).
+    #[error("Failed to commit conflict resolution: {0}")]
+    Commit(#[from] CommitError),
 }
 
 /// Errors specific to a push operation.
@@ -337,6 +670,10 @@ pub enum PushError {
     #[error("Failed to resolve during push: {0}")]
     Resolve(#[from] ResolveError),
 
+    /// Advancing a local upstream's head failed.
+    #[error("Failed to advance local upstream during push: {0}")]
+    Reset(#[from] ResetError),
+
     /// Loading the configured remote failed.
     #[error("Failed to load remote during push: {0}")]
     LoadRemote(#[from] LoadRemoteError),
@@ -674,3 +1011,74 @@ pub enum SnapshotError {
     #[error(transparent)]
     Storage(#[from] DialogStorageError),
 }
+
+/// Errors from collecting unreachable archive content
+/// ([`Repository::collect_garbage`](crate::Repository::collect_garbage)).
+#[derive(Error, Debug)]
+pub enum CollectGarbageError {
+    /// A root cell held something that is not what its name says.
+    ///
+    /// Fatal rather than skipped: a root that cannot be read cannot be
+    /// marked, and sweeping without it would remove content it names.
+    #[error("Root cell {address} could not be decoded: {reason}")]
+    Decode {
+        /// The `{space}/{cell}` address of the cell.
+        address: String,
+        /// Why decoding failed.
+        reason: String,
+    },
+
+    /// Listing or resolving a root cell failed.
+    #[error(transparent)]
+    Memory(#[from] MemoryError),
+
+    /// Listing or removing an archive block failed.
+    #[error(transparent)]
+    Archive(#[from] ArchiveError),
+
+    /// Listing or removing a blob failed.
+    #[error(transparent)]
+    Blob(#[from] BlobError),
+
+    /// Walking a reachable tree failed.
+    #[error(transparent)]
+    Tree(#[from] DialogSearchTreeError),
+
+    /// Classifying a tree entry's references failed.
+    #[error(transparent)]
+    Artifact(#[from] DialogArtifactsError),
+
+    /// Reading a block from the archive failed.
+    #[error(transparent)]
+    Storage(#[from] DialogStorageError),
+
+    /// Writes held the archive, either when the collection tried to start
+    /// or after its claim lapsed mid-sweep. Retry once they finish.
+    #[error("{writers} write(s) in flight; retry the collection once they finish")]
+    Writing {
+        /// How many writes held the archive.
+        writers: usize,
+    },
+
+    /// Reading the archive lease failed.
+    #[error(transparent)]
+    Resolve(#[from] ResolveError),
+
+    /// Recording the archive lease failed.
+    #[error(transparent)]
+    Publish(#[from] PublishError),
+}
+
+/// Errors resolving promised invocation arguments against recorded receipts
+/// ([`Delegations::resolve`](crate::Delegations::resolve)).
+#[derive(Error, Debug)]
+pub enum ResolvePromiseError {
+    /// A promised invocation has no admissible receipt yet, or its receipt
+    /// settled the other way than the promise awaits.
+    #[error(transparent)]
+    Unresolved(#[from] Unresolved),
+
+    /// The recorded receipts could not be read.
+    #[error("Receipt lookup failed: {0}")]
+    Lookup(String),
+}
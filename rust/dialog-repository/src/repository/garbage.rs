@@ -0,0 +1,827 @@
+//! Reclaiming archive content no revision can reach.
+//!
+//! Content-addressed storage only ever grows: a commit writes new nodes
+//! and leaves the ones it replaced behind, a [`reset`](crate::Branch::reset)
+//! orphans a whole tree, and a dropped blob keeps its bytes. Nothing reads
+//! that content again, but nothing removes it either. [`CollectGarbage`]
+//! does, by mark and sweep:
+//!
+//! 1. **Inventory.** List every block in the index catalog and every
+//!    committed blob. This happens *first*, so anything written after it --
+//!    by a pull running concurrently, say -- is never a candidate.
+//! 2. **Mark.** Walk every tree a root names: each branch's revision,
+//!    upstream sync bases and induction watermark, every cached remote
+//!    revision, and whatever revisions the caller asked to retain. Spilled
+//!    values and blobs referenced by an entry are marked with the node that
+//!    holds it.
+//! 3. **Sweep.** Remove what the inventory holds and the mark did not
+//!    reach, unless this is a dry run.
+//!
+//! # Racing a write
+//!
+//! A commit writes its nodes, then publishes the revision that names them;
+//! a blob write stores its bytes, then commits the index entry; a pull
+//! imports the merged tree, then advances the head; a peer's push puts
+//! blocks, then publishes the head (see [`ArchiveLeases`]). Any of them may
+//! reuse a block the mark found unreachable -- writes are idempotent, so
+//! it is not written again -- or land content before the inventory that
+//! nothing names until after the sweep. So the two never overlap: a
+//! collection claims the archive's lease before its inventory and holds it
+//! through the sweep, every write registers in the same lease before it
+//! writes anything and withdraws after it publishes, and each side refuses
+//! to start while the other holds it ([`CollectGarbageError::Writing`],
+//! [`CommitError::Collecting`](crate::CommitError::Collecting)). A claim
+//! lapses if its holder dies; the collection renews its own before every
+//! sweep batch and stops if a write got in meanwhile. A dry run removes
+//! nothing and takes no claim.
+
+use std::collections::{HashMap, HashSet};
+
+use dialog_artifacts::tree::TreeStorageBridge;
+use dialog_artifacts::{Key, ShipmentRef, shipment_ref};
+use dialog_capability::{Provider, Subject};
+use dialog_common::{Blake3Hash as NodeHash, Buffer, ConditionalSync};
+use dialog_effects::archive::prelude::{ArchiveSubjectExt as _, CatalogExt as _};
+use dialog_effects::archive::{self, Catalog};
+use dialog_effects::blob;
+use dialog_effects::blob::prelude::{ArchiveBlobExt as _, BlobExt as _};
+use dialog_effects::memory::prelude::{
+    CellExt as _, MemoryExt as _, MemorySubjectExt as _, SpaceExt as _,
+};
+use dialog_effects::memory::{self as memory_fx, Version};
+use dialog_search_tree::{
+    ArchivedNodeBody, ContentAddressedStorage as TreeStorage, PersistentNode,
+};
+use dialog_storage::{CborEncoder, DialogStorageError, Encoder as _};
+use dialog_varsig::Principal;
+use serde::de::DeserializeOwned;
+
+use super::snapshot::node_entries;
+use crate::{
+    CollectGarbageError, EMPTY_TREE_HASH, LocalIndex, RemoteEdition, Repository,
+    RepositoryArchiveExt as _, Revision, Tag, TreeReference, Upstreams,
+};
+
+mod lease;
+use lease::CollectLease;
+pub use lease::{ArchiveLeases, WriteLease};
+
+/// How many removals a sweep issues between re-reads of the roots.
+///
+/// Each batch is preceded by a renewal of the collection's claim and a
+/// check that no root moved, so this bounds how much is removed on the
+/// strength of one check.
+const SWEEP_BATCH: usize = 64;
+
+/// Collects unreachable blocks and blobs from a repository's archive.
+///
+/// Created by [`Repository::collect_garbage`].
+pub struct CollectGarbage {
+    subject: Subject,
+    retain: Vec<Revision>,
+    dry_run: bool,
+}
+
+impl<C: Principal> Repository<C> {
+    /// Prepare to collect archive content no root reaches.
+    ///
+    /// Roots are discovered from memory: every branch, every tag and every
+    /// cached remote revision. A revision held only by the caller -- a
+    /// [`Snapshot`](crate::Snapshot) about to be exported, say -- is not a
+    /// root unless passed to [`CollectGarbage::retain`].
+    pub fn collect_garbage(&self) -> CollectGarbage {
+        CollectGarbage {
+            subject: self.subject(),
+            retain: Vec::new(),
+            dry_run: false,
+        }
+    }
+}
+
+impl CollectGarbage {
+    /// Keep everything `revision` reaches, even if no branch names it.
+    pub fn retain(mut self, revision: Revision) -> Self {
+        self.retain.push(revision);
+        self
+    }
+
+    /// Report what would be collected without removing anything.
+    pub fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Claim the archive, mark, then sweep.
+    ///
+    /// Fails with [`CollectGarbageError::Writing`] rather than wait if a
+    /// write holds the archive.
+    pub async fn perform<Env>(self, env: &Env) -> Result<Collected, CollectGarbageError>
+    where
+        Env: Provider<archive::Get>
+            + Provider<archive::Put>
+            + Provider<archive::List>
+            + Provider<archive::Remove>
+            + Provider<blob::List>
+            + Provider<blob::Remove>
+            + Provider<memory_fx::Resolve>
+            + Provider<memory_fx::Publish>
+            + Provider<memory_fx::List>
+            + ConditionalSync
+            + 'static,
+    {
+        if self.dry_run {
+            return self.collect(env, None).await;
+        }
+        let lease = CollectLease::acquire(self.subject.clone(), env).await?;
+        let collected = self.collect(env, Some(&lease)).await;
+        // Give the archive back to writers whether or not the sweep
+        // finished; a failed release leaves the claim to lapse.
+        let released = lease.release(env).await;
+        let collected = collected?;
+        released?;
+        Ok(collected)
+    }
+
+    async fn collect<Env>(
+        self,
+        env: &Env,
+        lease: Option<&CollectLease>,
+    ) -> Result<Collected, CollectGarbageError>
+    where
+        Env: Provider<archive::Get>
+            + Provider<archive::Put>
+            + Provider<archive::List>
+            + Provider<archive::Remove>
+            + Provider<blob::List>
+            + Provider<blob::Remove>
+            + Provider<memory_fx::Resolve>
+            + Provider<memory_fx::Publish>
+            + Provider<memory_fx::List>
+            + ConditionalSync
+            + 'static,
+    {
+        let catalog = self.subject.clone().archive().index();
+
+        // Inventory before anything else: content written from here on is
+        // not in it, so it cannot be swept however the mark turns out.
+        let blocks = catalog.clone().list().perform(env).await?;
+        let blobs = self
+            .subject
+            .clone()
+            .archive()
+            .blob()
+            .list()
+            .perform(env)
+            .await?;
+
+        let mut mark = Mark::new(env, catalog.clone());
+        for revision in &self.retain {
+            mark.tree(&revision.tree).await?;
+        }
+
+        let mut roots = Roots::new(self.subject.clone());
+        roots.refresh(env, &mut mark).await?;
+        // Once more, so a commit that published while the walk ran is
+        // accounted for before anything is chosen.
+        roots.refresh(env, &mut mark).await?;
+
+        let blocks: Vec<NodeHash> = blocks
+            .into_iter()
+            .filter(|hash| !mark.blocks.contains(hash))
+            .collect();
+        let blobs: Vec<NodeHash> = blobs
+            .into_iter()
+            .filter(|hash| !mark.blobs.contains(hash))
+            .collect();
+
+        if self.dry_run {
+            return Ok(Collected {
+                roots: roots.count() + self.retain.len(),
+                reachable_blocks: mark.blocks.len(),
+                reachable_blobs: mark.blobs.len(),
+                blocks,
+                blobs,
+                dry_run: true,
+            });
+        }
+
+        let mut swept_blocks = Vec::new();
+        for batch in blocks.chunks(SWEEP_BATCH) {
+            renew(lease, env).await?;
+            roots.refresh(env, &mut mark).await?;
+            for hash in batch.iter().filter(|hash| !mark.blocks.contains(hash)) {
+                catalog.clone().remove(hash.clone()).perform(env).await?;
+                swept_blocks.push(hash.clone());
+            }
+        }
+
+        let mut swept_blobs = Vec::new();
+        for batch in blobs.chunks(SWEEP_BATCH) {
+            renew(lease, env).await?;
+            roots.refresh(env, &mut mark).await?;
+            for hash in batch.iter().filter(|hash| !mark.blobs.contains(hash)) {
+                self.subject
+                    .clone()
+                    .archive()
+                    .blob()
+                    .remove(hash.clone())
+                    .perform(env)
+                    .await?;
+                swept_blobs.push(hash.clone());
+            }
+        }
+
+        Ok(Collected {
+            roots: roots.count() + self.retain.len(),
+            reachable_blocks: mark.blocks.len(),
+            reachable_blobs: mark.blobs.len(),
+            blocks: swept_blocks,
+            blobs: swept_blobs,
+            dry_run: false,
+        })
+    }
+}
+
+/// Renew the collection's claim, if it holds one.
+async fn renew<Env>(lease: Option<&CollectLease>, env: &Env) -> Result<(), CollectGarbageError>
+where
+    Env: Provider<memory_fx::Resolve> + Provider<memory_fx::Publish> + ConditionalSync,
+{
+    match lease {
+        Some(lease) => lease.renew(env).await,
+        None => Ok(()),
+    }
+}
+
+/// What a collection found, and what it removed.
+///
+/// For a dry run, [`blocks`](Self::blocks) and [`blobs`](Self::blobs) are
+/// what *would* have been removed; nothing was.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Collected {
+    /// Memory cells and retained revisions the mark started from.
+    pub roots: usize,
+    /// Distinct blocks the mark reached, including ones not held locally.
+    pub reachable_blocks: usize,
+    /// Distinct blobs the mark reached, including ones not held locally.
+    pub reachable_blobs: usize,
+    /// Unreachable blocks.
+    pub blocks: Vec<NodeHash>,
+    /// Unreachable blobs.
+    pub blobs: Vec<NodeHash>,
+    /// Whether this was a dry run.
+    pub dry_run: bool,
+}
+
+/// Everything the mark has reached so far.
+///
+/// Kept across walks so a second root sharing structure with the first
+/// stops where they meet instead of re-reading the common subtree.
+struct Mark<'a, Env>
+where
+    Env: Provider<archive::Get> + Provider<archive::Put> + ConditionalSync + 'static,
+{
+    storage: TreeStorage<TreeStorageBridge<LocalIndex<'a, Env>>>,
+    blocks: HashSet<NodeHash>,
+    blobs: HashSet<NodeHash>,
+}
+
+impl<'a, Env> Mark<'a, Env>
+where
+    Env: Provider<archive::Get> + Provider<archive::Put> + ConditionalSync + 'static,
+{
+    fn new(env: &'a Env, catalog: dialog_capability::Capability<Catalog>) -> Self {
+        Self {
+            storage: TreeStorage::new(TreeStorageBridge(LocalIndex::new(env, catalog))),
+            blocks: HashSet::new(),
+            blobs: HashSet::new(),
+        }
+    }
+
+    /// Mark everything the tree at `root` reaches.
+    ///
+    /// A node this store does not hold is marked and not descended: what
+    /// hangs beneath it cannot be local either, except by sharing with a
+    /// tree that reaches it some other way, and that tree's walk marks it.
+    async fn tree(&mut self, root: &TreeReference) -> Result<(), CollectGarbageError> {
+        if *root.hash() == EMPTY_TREE_HASH {
+            return Ok(());
+        }
+        let mut frontier = vec![NodeHash::from(*root.hash())];
+        while let Some(hash) = frontier.pop() {
+            if !self.blocks.insert(hash.clone()) {
+                continue;
+            }
+            let Some(bytes) = self.storage.retrieve(&hash).await? else {
+                continue;
+            };
+            let node: PersistentNode<Key, dialog_artifacts::State<dialog_artifacts::Datum>> =
+                PersistentNode::try_from(Buffer::from(bytes))?;
+
+            if let ArchivedNodeBody::Index(index) = node.body() {
+                frontier.extend(index.links()?.into_iter().map(|link| link.node));
+            }
+            for (key, value) in node_entries(&node)? {
+                match shipment_ref(&key, &value, false)? {
+                    Some(ShipmentRef::SpilledValue(reference)) => {
+                        self.blocks.insert(NodeHash::from(reference));
+                    }
+                    Some(ShipmentRef::BlobAdded { hash, .. }) => {
+                        self.blobs.insert(NodeHash::from(hash));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The memory cells that name trees, and the version each was marked at.
+struct Roots {
+    subject: Subject,
+    seen: HashMap<String, Version>,
+}
+
+impl Roots {
+    fn new(subject: Subject) -> Self {
+        Self {
+            subject,
+            seen: HashMap::new(),
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// Re-read every root, marking the trees of any that are new or moved.
+    ///
+    /// A root that disappeared is forgotten but its marks are kept: unmarking
+    /// would need a fresh walk, and sparing its content until the next
+    /// collection costs nothing but space.
+    async fn refresh<Env>(
+        &mut self,
+        env: &Env,
+        mark: &mut Mark<'_, Env>,
+    ) -> Result<(), CollectGarbageError>
+    where
+        Env: Provider<archive::Get>
+            + Provider<archive::Put>
+            + Provider<memory_fx::Resolve>
+            + Provider<memory_fx::List>
+            + ConditionalSync
+            + 'static,
+    {
+        let mut current = HashMap::new();
+
+        for (space, cell, kind) in self.discover(env).await? {
+            let address = format!("{space}/{cell}");
+            let Some(edition) = self
+                .subject
+                .clone()
+                .memory()
+                .space(space)
+                .cell(cell)
+                .resolve()
+                .perform(env)
+                .await?
+            else {
+                continue;
+            };
+
+            if self.seen.get(&address) != Some(&edition.version) {
+                for tree in kind.trees(&address, &edition.content).await? {
+                    mark.tree(&tree).await?;
+                }
+            }
+            current.insert(address, edition.version);
+        }
+
+        self.seen = current;
+        Ok(())
+    }
+
+    /// List the cells that name trees.
+    ///
+    /// Branch spaces are `branch/{name}`, and a name may itself contain
+    /// `/`, so the cell is whatever follows the last separator. A remote's
+    /// cached revisions live at `remote/{remote}/branch/{name}/revision`;
+    /// other cells under a remote (its address, say) name no tree. A tag's
+    /// one cell is `tag/{name}/tag`.
+    async fn discover<Env>(
+        &self,
+        env: &Env,
+    ) -> Result<Vec<(String, String, RootKind)>, CollectGarbageError>
+    where
+        Env: Provider<memory_fx::List> + ConditionalSync,
+    {
+        let mut found = Vec::new();
+
+        let branches = self
+            .subject
+            .clone()
+            .memory()
+            .list("branch/")
+            .perform(env)
+            .await?;
+        for address in branches {
+            let Some((space, cell)) = address.rsplit_once('/') else {
+                continue;
+            };
+            let kind = match cell {
+                "revision" | "induction" => RootKind::Revision,
+                "upstream" => RootKind::Upstreams,
+                _ => continue,
+            };
+            found.push((space.to_string(), cell.to_string(), kind));
+        }
+
+        let remotes = self
+            .subject
+            .clone()
+            .memory()
+            .list("remote/")
+            .perform(env)
+            .await?;
+        for address in remotes {
+            if !address.ends_with("/revision") {
+                continue;
+            }
+            let Some((space, branch)) = address.split_once("/branch/") else {
+                continue;
+            };
+            found.push((
+                space.to_string(),
+                format!("branch/{branch}"),
+                RootKind::RemoteEdition,
+            ));
+        }
+
+        let tags = self
+            .subject
+            .clone()
+            .memory()
+            .list("tag/")
+            .perform(env)
+            .await?;
+        for address in tags {
+            let Some(space) = address.strip_suffix("/tag") else {
+                continue;
+            };
+            found.push((space.to_string(), "tag".to_string(), RootKind::Tag));
+        }
+
+        Ok(found)
+    }
+}
+
+/// How a root cell's content names its trees.
+#[derive(Debug, Clone, Copy)]
+enum RootKind {
+    /// A [`Revision`]: a branch head or induction watermark.
+    Revision,
+    /// A branch's [`Upstreams`], each carrying its last sync tree.
+    Upstreams,
+    /// A cached remote revision.
+    RemoteEdition,
+    /// A [`Tag`]: the revision it names.
+    Tag,
+}
+
+impl RootKind {
+    async fn trees(
+        self,
+        address: &str,
+        content: &[u8],
+    ) -> Result<Vec<TreeReference>, CollectGarbageError> {
+        Ok(match self {
+            RootKind::Revision => vec![decode::<Revision>(address, content).await?.tree],
+            RootKind::Upstreams => decode::<Upstreams>(address, content)
+                .await?
+                .iter()
+                .map(|upstream| upstream.tree().clone())
+                .collect(),
+            RootKind::RemoteEdition => {
+                vec![
+                    decode::<RemoteEdition>(address, content)
+                        .await?
+                        .content
+                        .tree,
+                ]
+            }
+            RootKind::Tag => vec![decode::<Tag>(address, content).await?.revision.tree],
+        })
+    }
+}
+
+/// Decode a root cell. A cell that does not decode fails the collection
+/// rather than being skipped: skipping would leave its tree unmarked.
+async fn decode<T>(address: &str, content: &[u8]) -> Result<T, CollectGarbageError>
+where
+    T: DeserializeOwned + ConditionalSync,
+{
+    CborEncoder
+        .decode(content)
+        .await
+        .map_err(|error: DialogStorageError| CollectGarbageError::Decode {
+            address: address.to_string(),
+            reason: error.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use anyhow::Result;
+    use dialog_artifacts::{Artifact, Instruction, Value};
+    use dialog_storage::provider::storage::VolatileSpace;
+    use futures_util::{StreamExt as _, stream};
+
+    use super::*;
+    use crate::helpers::test_repo;
+    use crate::{Blob, Branch, CommitError, PullError, ResetError, SnapshotError};
+    use dialog_operator::Operator;
+    use dialog_operator::helpers::{generate_data, test_operator_with_profile};
+    use dialog_remote_peer::ArchiveLease as _;
+
+    struct Stage {
+        env: Operator<VolatileSpace>,
+        repository: crate::Repository,
+        branch: Branch,
+        /// A block nothing references.
+        stray_block: NodeHash,
+        /// A blob nothing references.
+        stray_blob: NodeHash,
+        /// The blob the branch references.
+        blob: NodeHash,
+    }
+
+    /// A branch with ordinary facts, a spilled value and a blob, beside a
+    /// block and a blob written straight to the archive that no tree
+    /// names.
+    async fn stage() -> Result<Stage> {
+        let (env, profile) = test_operator_with_profile().await;
+        let repository = test_repo(&env, &profile).await;
+        let branch = repository.branch("main").open().perform(&env).await?;
+
+        let mut facts: Vec<Instruction> = generate_data(20)?
+            .into_iter()
+            .map(Instruction::Assert)
+            .collect();
+        facts.push(Instruction::Assert(Artifact {
+            the: "document/body".parse()?,
+            of: "document:large".parse()?,
+            is: Value::String(
+                "spilled".repeat(dialog_search_tree::Manifest::default().inline_n as usize + 1),
+            ),
+            cause: None,
+        }));
+        branch.commit(stream::iter(facts)).perform(&env).await?;
+
+        let blob_bytes = b"referenced blob".repeat(256);
+        Blob::import(stream::iter(vec![Ok(blob_bytes.clone())]))
+            .write(branch.blobs())
+            .perform(&env)
+            .await?;
+
+        let stray = Buffer::from(b"a block no tree names".to_vec());
+        let stray_block = stray.blake3_hash().clone();
+        repository
+            .subject()
+            .archive()
+            .index()
+            .put(stray)
+            .perform(&env)
+            .await?;
+
+        let stray_bytes = b"a blob no tree names".to_vec();
+        let stray_blob = Buffer::from(stray_bytes.clone()).blake3_hash().clone();
+        let mut writer = repository
+            .subject()
+            .archive()
+            .blob()
+            .import(stray_blob.clone(), stray_bytes.len() as u64)
+            .perform(&env)
+            .await?;
+        writer.write_all(&stray_bytes).await?;
+        writer.finish().await?;
+
+        Ok(Stage {
+            env,
+            repository,
+            branch,
+            stray_block,
+            stray_blob,
+            blob: Buffer::from(blob_bytes).blake3_hash().clone(),
+        })
+    }
+
+    /// Whether every block and blob `revision` reaches is still held.
+    async fn is_complete(stage: &Stage, revision: Revision) -> Result<bool> {
+        let items = stage
+            .repository
+            .snapshot(revision)
+            .export()
+            .perform(&stage.env);
+        futures_util::pin_mut!(items);
+        while let Some(item) = items.next().await {
+            match item {
+                Ok(_) => {}
+                Err(SnapshotError::MissingBlock { .. } | SnapshotError::MissingBlob { .. }) => {
+                    return Ok(false);
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+        Ok(true)
+    }
+
+    #[dialog_common::test]
+    async fn it_reports_without_removing_on_a_dry_run() -> Result<()> {
+        let stage = stage().await?;
+
+        let report = stage
+            .repository
+            .collect_garbage()
+            .dry_run()
+            .perform(&stage.env)
+            .await?;
+
+        assert!(report.dry_run);
+        assert!(report.blocks.contains(&stage.stray_block));
+        assert_eq!(report.blobs, vec![stage.stray_blob.clone()]);
+
+        let held = stage
+            .repository
+            .subject()
+            .archive()
+            .index()
+            .list()
+            .perform(&stage.env)
+            .await?;
+        assert!(
+            held.contains(&stage.stray_block),
+            "a dry run removes nothing"
+        );
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_sweeps_what_no_root_reaches() -> Result<()> {
+        let stage = stage().await?;
+        let head = stage
+            .branch
+            .revision()
+            .expect("staged branch has a revision");
+
+        let report = stage
+            .repository
+            .collect_garbage()
+            .perform(&stage.env)
+            .await?;
+        assert!(!report.dry_run);
+        assert!(report.blocks.contains(&stage.stray_block));
+        assert_eq!(report.blobs, vec![stage.stray_blob.clone()]);
+
+        let blobs = stage
+            .repository
+            .subject()
+            .archive()
+            .blob()
+            .list()
+            .perform(&stage.env)
+            .await?;
+        assert_eq!(blobs, vec![stage.blob.clone()], "the referenced blob stays");
+        assert!(
+            is_complete(&stage, head.clone()).await?,
+            "everything the head reaches, spill and blob included, survives"
+        );
+
+        let again = stage
+            .repository
+            .collect_garbage()
+            .perform(&stage.env)
+            .await?;
+        assert!(again.blocks.is_empty() && again.blobs.is_empty());
+        Ok(())
+    }
+
+    // A revision the branch has moved past is unreachable, so its
+    // replaced nodes go -- unless the caller asks to keep it.
+    #[dialog_common::test]
+    async fn it_keeps_retained_revisions() -> Result<()> {
+        let stage = stage().await?;
+        let earlier = stage
+            .branch
+            .revision()
+            .expect("staged branch has a revision");
+
+        let replacements: Vec<Instruction> = generate_data(20)?
+            .into_iter()
+            .map(Instruction::Assert)
+            .collect();
+        stage
+            .branch
+            .commit(stream::iter(replacements))
+            .perform(&stage.env)
+            .await?;
+
+        stage
+            .repository
+            .collect_garbage()
+            .retain(earlier.clone())
+            .perform(&stage.env)
+            .await?;
+        assert!(is_complete(&stage, earlier.clone()).await?);
+
+        let report = stage
+            .repository
+            .collect_garbage()
+            .perform(&stage.env)
+            .await?;
+        assert!(!report.blocks.is_empty(), "the replaced nodes are swept");
+        assert!(!is_complete(&stage, earlier).await?);
+        assert!(is_complete(&stage, stage.branch.revision().expect("head")).await?);
+        Ok(())
+    }
+
+    // The stray blob stands in for bytes an import has stored but not yet
+    // committed: while that write holds the lease, nothing is swept, and
+    // while a collection holds it, no write starts -- a commit, an import,
+    // a pull, a reset, or a peer's push.
+    #[dialog_common::test]
+    async fn it_keeps_collections_and_writes_apart() -> Result<()> {
+        let stage = stage().await?;
+
+        let write = WriteLease::acquire(stage.repository.subject(), &stage.env).await?;
+        let refused = stage.repository.collect_garbage().perform(&stage.env).await;
+        assert!(matches!(
+            refused,
+            Err(CollectGarbageError::Writing { writers: 1 })
+        ));
+        let blobs = stage
+            .repository
+            .subject()
+            .archive()
+            .blob()
+            .list()
+            .perform(&stage.env)
+            .await?;
+        assert!(blobs.contains(&stage.stray_blob), "nothing was swept");
+        write.release(&stage.env).await?;
+
+        let collection = CollectLease::acquire(stage.repository.subject(), &stage.env).await?;
+        let commit = stage
+            .branch
+            .commit(stream::iter(vec![Instruction::Assert(Artifact {
+                the: "document/title".parse()?,
+                of: "document:late".parse()?,
+                is: Value::String("late".into()),
+                cause: None,
+            })]))
+            .perform(&stage.env)
+            .await;
+        assert!(matches!(commit, Err(CommitError::Collecting)));
+        let import = Blob::import(stream::iter(vec![Ok(b"late blob".to_vec())]))
+            .write(stage.branch.blobs())
+            .perform(&stage.env)
+            .await;
+        assert!(matches!(import, Err(CommitError::Collecting)));
+        let feature = stage
+            .repository
+            .branch("feature")
+            .open()
+            .perform(&stage.env)
+            .await?;
+        let pull = feature.pull().from(&stage.branch).perform(&stage.env).await;
+        assert!(matches!(
+            pull,
+            Err(PullError::Commit(CommitError::Collecting))
+        ));
+        let reset = feature
+            .reset(stage.branch.revision().expect("head"))
+            .perform(&stage.env)
+            .await;
+        assert!(matches!(
+            reset,
+            Err(ResetError::Lease(CommitError::Collecting))
+        ));
+        let peer = ArchiveLeases::new(stage.env.clone());
+        assert!(matches!(
+            peer.acquire(&stage.repository.did()).await,
+            Err(CommitError::Collecting)
+        ));
+        collection.release(&stage.env).await?;
+
+        let report = stage
+            .repository
+            .collect_garbage()
+            .perform(&stage.env)
+            .await?;
+        assert_eq!(report.blobs, vec![stage.stray_blob.clone()]);
+        Ok(())
+    }
+}
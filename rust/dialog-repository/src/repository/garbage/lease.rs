@@ -0,0 +1,215 @@
+//! The archive lease that keeps a collection and a write apart.
+//!
+//! One memory cell, `archive/lease`, records every write in flight and
+//! whether a collection is running. Both sides change it only by CAS, so
+//! a write either registers before the collector claims the archive --
+//! and the collector refuses to start -- or finds the claim and refuses
+//! to start itself. Claims lapse, so a process that dies holding one
+//! blocks the other side for at most the claim's term.
+//!
+//! [`ArchiveLeases`] takes the same claim for a peer
+//! [`Responder`](dialog_remote_peer::Responder), so a push arriving over
+//! the wire holds the archive the way a local commit does.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dialog_capability::{Did, Provider, Subject};
+use dialog_common::ConditionalSync;
+use dialog_common::time::{Duration, UNIX_EPOCH, now};
+use dialog_effects::memory::prelude::{MemoryExt as _, MemorySubjectExt as _, SpaceExt as _};
+use dialog_effects::memory::{Publish, Resolve};
+use dialog_remote_peer::ArchiveLease;
+use serde::{Deserialize, Serialize};
+
+use crate::{Cell, CollectGarbageError, CommitError, PublishError, ResolveError};
+
+/// How long a write's claim holds before a collection may ignore it.
+const WRITE_TERM: Duration = Duration::from_secs(15 * 60);
+
+/// How long a collection's claim holds without being renewed.
+const COLLECT_TERM: Duration = Duration::from_secs(5 * 60);
+
+/// Distinguishes writes started by this process within one millisecond.
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// The content of the lease cell.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Lease {
+    /// Writes in flight: a token per write, and when its claim lapses, in
+    /// milliseconds since the epoch.
+    writers: BTreeMap<String, u64>,
+    /// When the running collection's claim lapses, if one is running.
+    collector: Option<u64>,
+}
+
+impl Lease {
+    /// Drop every claim that has lapsed by `at`.
+    fn prune(&mut self, at: u64) {
+        self.writers.retain(|_, expires| *expires > at);
+        self.collector = self.collector.filter(|expires| *expires > at);
+    }
+}
+
+/// A write's claim on the archive, held from before it writes its first
+/// block or blob until after it publishes the revision that names them.
+pub struct WriteLease {
+    cell: Cell<Lease>,
+    token: String,
+}
+
+impl WriteLease {
+    /// Register a write, unless a collection holds the archive.
+    pub(crate) async fn acquire<Env>(subject: Subject, env: &Env) -> Result<Self, CommitError>
+    where
+        Env: Provider<Resolve> + Provider<Publish> + ConditionalSync,
+    {
+        let cell = cell(subject);
+        let at = millis();
+        let token = format!("{at:x}-{:x}", SEQUENCE.fetch_add(1, Ordering::Relaxed));
+        update(&cell, env, |lease| {
+            if lease.collector.is_some() {
+                return Err(CommitError::Collecting);
+            }
+            lease
+                .writers
+                .insert(token.clone(), at + WRITE_TERM.as_millis() as u64);
+            Ok(())
+        })
+        .await?;
+        Ok(Self { cell, token })
+    }
+
+    /// Withdraw the write's claim.
+    pub(crate) async fn release<Env>(self, env: &Env) -> Result<(), CommitError>
+    where
+        Env: Provider<Resolve> + Provider<Publish> + ConditionalSync,
+    {
+        update(&self.cell, env, |lease| {
+            lease.writers.remove(&self.token);
+            Ok(())
+        })
+        .await
+    }
+}
+
+/// The archive lease of every repository kept in `env`, as a peer
+/// responder serving them claims it.
+#[derive(Debug, Clone)]
+pub struct ArchiveLeases<Env> {
+    env: Env,
+}
+
+impl<Env> ArchiveLeases<Env> {
+    /// Claim the archives of the repositories `env` keeps.
+    pub fn new(env: Env) -> Self {
+        Self { env }
+    }
+}
+
+impl<Env> ArchiveLease for ArchiveLeases<Env>
+where
+    Env: Provider<Resolve> + Provider<Publish> + ConditionalSync,
+{
+    type Claim = WriteLease;
+    type Error = CommitError;
+
+    async fn acquire(&self, subject: &Did) -> Result<WriteLease, CommitError> {
+        WriteLease::acquire(Subject::from(subject.clone()), &self.env).await
+    }
+
+    async fn release(&self, claim: WriteLease) -> Result<(), CommitError> {
+        claim.release(&self.env).await
+    }
+}
+
+/// A collection's claim on the archive.
+pub(crate) struct CollectLease {
+    cell: Cell<Lease>,
+}
+
+impl CollectLease {
+    /// Claim the archive, unless a write holds it.
+    pub(crate) async fn acquire<Env>(
+        subject: Subject,
+        env: &Env,
+    ) -> Result<Self, CollectGarbageError>
+    where
+        Env: Provider<Resolve> + Provider<Publish> + ConditionalSync,
+    {
+        let lease = Self {
+            cell: cell(subject),
+        };
+        lease.renew(env).await?;
+        Ok(lease)
+    }
+
+    /// Extend the claim's term. Fails if it lapsed and a write got in
+    /// meanwhile: whatever that write reused may already be marked for
+    /// removal, so the collection must stop.
+    pub(crate) async fn renew<Env>(&self, env: &Env) -> Result<(), CollectGarbageError>
+    where
+        Env: Provider<Resolve> + Provider<Publish> + ConditionalSync,
+    {
+        let at = millis();
+        update(&self.cell, env, |lease| {
+            if !lease.writers.is_empty() {
+                return Err(CollectGarbageError::Writing {
+                    writers: lease.writers.len(),
+                });
+            }
+            lease.collector = Some(at + COLLECT_TERM.as_millis() as u64);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Give up the claim.
+    pub(crate) async fn release<Env>(self, env: &Env) -> Result<(), CollectGarbageError>
+    where
+        Env: Provider<Resolve> + Provider<Publish> + ConditionalSync,
+    {
+        update(&self.cell, env, |lease| {
+            lease.collector = None;
+            Ok(())
+        })
+        .await
+    }
+}
+
+fn cell(subject: Subject) -> Cell<Lease> {
+    subject.memory().space("archive").cell("lease").into()
+}
+
+fn millis() -> u64 {
+    now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Read the lease, apply `change` to it with lapsed claims pruned, and
+/// publish the result against the edition read, starting over if another
+/// claim landed in between.
+async fn update<Env, E>(
+    cell: &Cell<Lease>,
+    env: &Env,
+    mut change: impl FnMut(&mut Lease) -> Result<(), E>,
+) -> Result<(), E>
+where
+    Env: Provider<Resolve> + Provider<Publish> + ConditionalSync,
+    E: From<ResolveError> + From<PublishError>,
+{
+    loop {
+        cell.resolve().perform(env).await?;
+        let checkpoint = cell.checkpoint();
+        let mut lease = cell.content().unwrap_or_default();
+        lease.prune(millis());
+        change(&mut lease)?;
+        match checkpoint.publish(lease, env).await {
+            Ok(()) => return Ok(()),
+            Err(PublishError::VersionMismatch { .. }) => continue,
+            Err(error) => return Err(error.into()),
+        }
+    }
+}
@@ -1,5 +1,5 @@
 //! Memory capabilities: cells, publish/resolve commands, and caching.
-use crate::{BranchReference, RemoteReference};
+use crate::{BranchReference, RemoteReference, TagReference};
 use dialog_capability::Subject;
 use dialog_effects::memory::prelude::{MemoryExt, MemorySubjectExt};
 
@@ -22,6 +22,9 @@ pub trait RepositoryMemoryExt: MemorySubjectExt {
 
     /// Access a remote scoped to `remote/{name}`.
     fn remote(&self, name: impl Into<String>) -> RemoteReference;
+
+    /// Access a tag scoped to `tag/{name}`.
+    fn tag(&self, name: impl Into<String>) -> TagReference;
 }
 
 impl RepositoryMemoryExt for Subject {
@@ -34,4 +37,9 @@ impl RepositoryMemoryExt for Subject {
         let name = name.into();
         self.clone().memory().space(format!("remote/{name}")).into()
     }
+
+    fn tag(&self, name: impl Into<String>) -> TagReference {
+        let name = name.into();
+        self.clone().memory().space(format!("tag/{name}")).into()
+    }
 }
@@ -36,6 +36,17 @@
 //!
 //! [`SnapshotExport::download`] resolves both by hydrating read-misses from an
 //! upstream as the walk proceeds, which is what makes the result complete.
+//!
+//! # Querying a snapshot
+//!
+//! [`Snapshot::select`] and [`Snapshot::query`] are the branch query
+//! surface — concepts, rules, overlays, ordering — evaluated against the
+//! snapshot's revision. Underneath, a snapshot holds a read-only branch
+//! view pinned at that revision, so every read goes through the same path
+//! a branch read does. A snapshot minted by [`Branch::snapshot`] (or
+//! [`Branch::as_of`]) keeps that branch's upstream and hydrates misses
+//! from it through [`NetworkedIndex`] just as the branch would; one minted
+//! by [`Repository::snapshot`] has no upstream and reads locally.
 
 use std::collections::HashSet;
 use std::marker::PhantomData;
@@ -49,18 +60,22 @@ use dialog_effects::archive::prelude::{ArchiveSubjectExt as _, CatalogExt as _};
 use dialog_effects::archive::{Catalog, Get, Put};
 use dialog_effects::blob::prelude::{ArchiveBlobExt as _, BlobExt as _};
 use dialog_effects::blob::{BlobError, BlobReader, Import as BlobImport, Read as BlobRead};
+use dialog_effects::memory::Resolve;
 use dialog_search_tree::{
-    ArchivedNodeBody, ContentAddressedStorage as TreeStorage, NoveltyOp, Traversable as _, Visit,
-    into_owned,
+    ArchivedNodeBody, ContentAddressedStorage as TreeStorage, DialogSearchTreeError, NoveltyOp,
+    PersistentNode, Traversable as _, Visit, into_owned,
 };
 use futures_util::{Stream, StreamExt as _, stream};
 
 use dialog_credentials::Credential;
 use dialog_varsig::Principal;
 
+use dialog_query::query::Application;
+
 use crate::{
-    Index, NetworkedIndex, RemoteRepository, RemoteSite, Repository, RepositoryArchiveExt as _,
-    Revision, SnapshotError,
+    Branch, Index, LoadTagError, NetworkedIndex, QueryLayer, RemoteRepository, RemoteSite,
+    Repository, RepositoryArchiveExt as _, RepositoryMemoryExt as _, Revision, SelectQuery,
+    SnapshotError, TagReference,
 };
 
 /// How many spill or blob fetches an export keeps in flight at once.
@@ -103,15 +118,22 @@ impl Block {
 ///
 /// Holds the repository's subject rather than the repository itself:
 /// everything an export touches — the archive catalog, the blob channel —
-/// derives from the subject, which lets a [`Branch`](crate::Branch) mint
-/// a snapshot of its own repository too (see
-/// [`Branch::download`](crate::Branch::download)).
+/// derives from the subject, which lets a [`Branch`] mint a snapshot of
+/// its own repository too (see [`Branch::snapshot`]).
 pub struct Snapshot<'a, C: Principal = Credential> {
     subject: Subject,
     revision: Revision,
+    /// The read-only branch view queries evaluate against, pinned at
+    /// `revision`.
+    view: Branch,
     repository: PhantomData<&'a C>,
 }
 
+/// Name of the branch view behind a snapshot minted from a repository
+/// rather than a branch. The view is never opened or published, so the
+/// name only surfaces in the query's branch metadata.
+const DETACHED: &str = "";
+
 impl<C: Principal> Repository<C> {
     /// An immutable view at `revision`.
     ///
@@ -120,21 +142,62 @@ impl<C: Principal> Repository<C> {
     /// the caller's business -- a snapshot does not require the branch it
     /// was minted on to be present, which is why it also cannot hydrate
     /// from an upstream (see [`SnapshotExport::download`]).
-    pub fn snapshot(&self, revision: Revision) -> Snapshot<'_, C> {
+    ///
+    /// A loaded [`Tag`](crate::Tag) converts into the revision it names;
+    /// to view a repository as tagged by name, see
+    /// [`snapshot_tag`](Self::snapshot_tag).
+    pub fn snapshot(&self, revision: impl Into<Revision>) -> Snapshot<'_, C> {
+        let revision = revision.into();
+        let subject = self.subject();
         Snapshot {
-            subject: self.subject(),
+            view: Branch::detached(subject.branch(DETACHED), revision.clone()),
+            subject,
             revision,
             repository: PhantomData,
         }
     }
+
+    /// An immutable view at the revision the tag `name` names.
+    ///
+    /// Resolves `tag/{name}` when performed, failing with
+    /// [`LoadTagError::NotFound`] if there is no such tag.
+    pub fn snapshot_tag(&self, name: impl Into<String>) -> SnapshotTag<'_, C> {
+        SnapshotTag {
+            repository: self,
+            tag: self.tag(name),
+        }
+    }
+}
+
+/// Command to view a repository at a tagged revision, created by
+/// [`Repository::snapshot_tag`].
+pub struct SnapshotTag<'a, C: Principal> {
+    repository: &'a Repository<C>,
+    tag: TagReference,
+}
+
+impl<'a, C: Principal> SnapshotTag<'a, C> {
+    /// Load the tag and view the repository at the revision it names.
+    pub async fn perform<Env>(self, env: &Env) -> Result<Snapshot<'a, C>, LoadTagError>
+    where
+        Env: Provider<Resolve>,
+    {
+        let tag = self.tag.load().perform(env).await?;
+        Ok(self.repository.snapshot(tag))
+    }
 }
 
-impl Snapshot<'static> {
-    /// An immutable view of `subject`'s repository at `revision`, for
-    /// callers that hold a branch rather than a repository.
-    pub(crate) fn of(subject: Subject, revision: Revision) -> Self {
+impl Branch {
+    /// An immutable view of this branch's repository at `revision`.
+    ///
+    /// Unlike [`Repository::snapshot`], the view keeps this branch's
+    /// upstream: queries against it hydrate what the local store lacks
+    /// from the same remote the branch reads through. The branch itself
+    /// is untouched, whatever its head does afterwards.
+    pub fn snapshot(&self, revision: Revision) -> Snapshot<'static> {
         Snapshot {
-            subject,
+            subject: self.subject(),
+            view: self.pinned(revision.clone()),
             revision,
             repository: PhantomData,
         }
@@ -147,12 +210,59 @@ impl<C: Principal> Snapshot<'_, C> {
         &self.revision
     }
 
+    /// The read-only branch view this snapshot reads through.
+    pub(crate) fn view(&self) -> &Branch {
+        &self.view
+    }
+
+    /// A composable query over this snapshot, as
+    /// [`Branch::query`] is over a branch: [`with`](QueryLayer::with) folds
+    /// facts into an overlay, [`join`](QueryLayer::join) reads other
+    /// branches alongside.
+    pub fn query(&self) -> QueryLayer<'_> {
+        QueryLayer::from(&self.view)
+    }
+
+    /// Query this snapshot with an application. Shortcut for
+    /// `snapshot.query().select(query)`.
+    pub fn select<Q: Application>(&self, query: Q) -> SelectQuery<'_, Q> {
+        SelectQuery::new(&self.view, query)
+    }
+
     /// The archive catalog this snapshot's blocks live in.
     pub(crate) fn index(&self) -> Capability<Catalog> {
         self.subject.clone().archive().index()
     }
 }
 
+/// The entries a node physically holds: a leaf's stored entries, or the
+/// buffered asserts riding an index node's novelty buffers.
+///
+/// This only collects. Classifying an entry (see [`shipment_ref`]) returns
+/// artifact errors, which do not belong in a tree-walk callback, so callers
+/// classify the returned pairs themselves.
+pub(crate) fn node_entries(
+    node: &PersistentNode<Key, State<Datum>>,
+) -> Result<Vec<(Key, State<Datum>)>, DialogSearchTreeError> {
+    let mut entries = Vec::new();
+    match node.body() {
+        ArchivedNodeBody::Segment(segment) => {
+            segment.for_each_entry::<Key, _>(|key, value| {
+                entries.push((Key::from(key.to_vec()), into_owned(value)?));
+                Ok(())
+            })?;
+        }
+        ArchivedNodeBody::Index(index) => {
+            for entry in index.all_novelty::<Key>()? {
+                if let NoveltyOp::Assert(value) = entry.op {
+                    entries.push((Key::from(entry.key), value));
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
+
 /// One piece of a snapshot's content.
 ///
 /// Blocks and blobs are separate variants rather than one uniform item
@@ -335,26 +445,8 @@ impl<C: Principal> SnapshotExport<'_, C> {
                 // node it is the buffered asserts riding its novelty
                 // buffers, whose keys and values reference content
                 // exactly like stored entries (a buffered retract
-                // references nothing of its own). The closure only
-                // collects: classification returns artifact errors,
-                // which do not belong in a tree-walk callback.
-                let mut entries: Vec<(Key, State<Datum>)> = Vec::new();
-                match node.body() {
-                    ArchivedNodeBody::Segment(segment) => {
-                        segment.for_each_entry::<Key, _>(|key, value| {
-                            entries.push((Key::from(key.to_vec()), into_owned(value)?));
-                            Ok(())
-                        })?;
-                    }
-                    ArchivedNodeBody::Index(index) => {
-                        for entry in index.all_novelty::<Key>()? {
-                            if let NoveltyOp::Assert(value) = entry.op {
-                                entries.push((Key::from(entry.key), value));
-                            }
-                        }
-                    }
-                }
-                for (key, value) in entries {
+                // references nothing of its own).
+                for (key, value) in node_entries(&node)? {
                     match shipment_ref(&key, &value, false)? {
                         Some(ShipmentRef::SpilledValue(reference)) => {
                             spills.insert(reference);
@@ -0,0 +1,284 @@
+//! Named, immutable pointers to revisions.
+//!
+//! ```text
+//! repo.tag("release-2026-10")          → TagReference   (memory tag/{name})
+//!   ├── .create(revision)              → CreateTag      (lightweight)
+//!   │     └── .annotate(message)       → CreateTag      (signed, annotated)
+//!   ├── .load()                        → LoadTag        → Tag
+//!   ├── .delete()                      → DeleteTag
+//!   ├── .push(&remote)                 → PushTag
+//!   └── .pull(&remote, &branch)        → PullTag
+//! repo.snapshot_tag("release-2026-10") → SnapshotTag    → Snapshot
+//! repo.tags()                          → ListTags       → Vec<String>
+//! ```
+//!
+//! A tag lives in the `tag` cell of the `tag/{name}` memory space, beside
+//! the `branch/{name}` spaces branches use. Unlike a branch head it never
+//! moves: a tag is created by a publish that expects the cell to be empty,
+//! so naming a second revision under the same tag fails rather than
+//! overwriting the first. Deleting and recreating is the only way to
+//! repoint one, and it is deliberate.
+//!
+//! An annotated tag also carries a message and a signature by the session
+//! key that made it (see [`Annotation`]). Pulling a tag verifies the
+//! signature — and the tagged head's own, and that no key it was signed
+//! with had been superseded, by the successions a local branch records —
+//! before anything lands locally.
+
+use dialog_artifacts::history::verify_issuer_signature;
+use dialog_artifacts::{DialogArtifactsError, Revision};
+use dialog_capability::Did;
+use serde::{Deserialize, Serialize};
+
+mod create;
+pub use create::*;
+
+mod delete;
+pub use delete::*;
+
+mod list;
+pub use list::*;
+
+mod load;
+pub use load::*;
+
+mod pull;
+pub use pull::*;
+
+mod push;
+pub use push::*;
+
+mod reference;
+pub use reference::*;
+
+/// The domain tag opening every annotated tag signing payload. The
+/// session key that signs tags also signs heads and revision records, so
+/// tags get a payload space of their own (see
+/// [`HEAD_SIGNING_DOMAIN`](dialog_artifacts::HEAD_SIGNING_DOMAIN)).
+pub const TAG_SIGNING_DOMAIN: &[u8] = b"dialog/tag@1\n";
+
+/// A named revision, as stored in a tag cell.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tag {
+    /// The tag's name. Carried in the value, not only in the cell it is
+    /// stored at, so an annotation's signature binds it: a signed tag
+    /// copied under another name fails to verify.
+    pub name: String,
+    /// The revision the tag names.
+    pub revision: Revision,
+    /// Message and signature, for an annotated tag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<Annotation>,
+}
+
+/// What an annotated tag says about itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotation {
+    /// Free-form text describing the tag.
+    pub message: String,
+    /// DID of the operator (session key) that signed the tag.
+    pub tagger: Did,
+    /// DID of the profile the tagger acts for.
+    pub authority: Did,
+    /// Milliseconds since the Unix epoch, by the tagger's clock. `None`
+    /// when the clock was unavailable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<u64>,
+    /// The tagger's Ed25519 signature over [`Tag::payload`].
+    #[serde(default, with = "serde_bytes")]
+    pub signature: Vec<u8>,
+}
+
+impl Tag {
+    /// The revision this tag names.
+    pub fn revision(&self) -> &Revision {
+        &self.revision
+    }
+
+    /// The canonical signing payload of an annotated tag: every field
+    /// except the signature, deterministically encoded. `None` for a
+    /// lightweight tag, which has nothing to sign.
+    ///
+    /// ```text
+    /// domain tag ("dialog/tag@1\n")
+    /// (length (8, big-endian) ++ UTF-8) for name, tagger, authority, message
+    /// time, when present: 0x01 ++ millis (8, big-endian); else 0x00
+    /// length (8, big-endian) ++ the tagged head's payload
+    /// ```
+    ///
+    /// Embedding the head's payload binds everything the head names —
+    /// branch, issuer, tree root, edition, context — without restating it.
+    pub fn payload(&self) -> Option<Vec<u8>> {
+        let annotation = self.annotation.as_ref()?;
+        let mut bytes = TAG_SIGNING_DOMAIN.to_vec();
+        for field in [
+            self.name.as_str(),
+            annotation.tagger.as_str(),
+            annotation.authority.as_str(),
+            annotation.message.as_str(),
+        ] {
+            bytes.extend_from_slice(&(field.len() as u64).to_be_bytes());
+            bytes.extend_from_slice(field.as_bytes());
+        }
+        match annotation.time {
+            Some(time) => {
+                bytes.push(0x01);
+                bytes.extend_from_slice(&time.to_be_bytes());
+            }
+            None => bytes.push(0x00),
+        }
+        let head = self.revision.payload();
+        bytes.extend_from_slice(&(head.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(&head);
+        Some(bytes)
+    }
+
+    /// Verify an annotated tag's signature under the key its tagger DID
+    /// names. A lightweight tag carries no claim to check and always
+    /// passes; the tagged head is checked separately, by
+    /// [`Revision::verify`].
+    pub fn verify(&self) -> Result<(), DialogArtifactsError> {
+        let (Some(annotation), Some(payload)) = (&self.annotation, self.payload()) else {
+            return Ok(());
+        };
+        verify_issuer_signature(annotation.tagger.as_str(), &payload, &annotation.signature)?;
+        Ok(())
+    }
+}
+
+impl From<Tag> for Revision {
+    fn from(tag: Tag) -> Self {
+        tag.revision
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    use crate::helpers::test_repo;
+    use crate::{CreateTagError, DeleteTagError, LoadTagError};
+    use anyhow::Result;
+    use dialog_artifacts::{Artifact, Instruction, Value};
+    use dialog_operator::helpers::test_operator_with_profile;
+    use futures_util::stream;
+
+    fn name(of: &str, is: &str) -> Artifact {
+        Artifact {
+            the: "user/name".parse().unwrap(),
+            of: of.parse().unwrap(),
+            is: Value::String(is.to_string()),
+            cause: None,
+        }
+    }
+
+    #[dialog_common::test]
+    async fn it_creates_lists_and_deletes_tags() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+
+        let first = branch
+            .commit(stream::iter(vec![Instruction::Assert(name(
+                "id:alice", "Alice",
+            ))]))
+            .perform(&operator)
+            .await?;
+        let second = branch
+            .commit(stream::iter(vec![Instruction::Assert(name(
+                "id:bob", "Bob",
+            ))]))
+            .perform(&operator)
+            .await?;
+
+        repo.tag("pre-migration")
+            .create(first.clone())
+            .perform(&operator)
+            .await?;
+        repo.tag("release/2026-10")
+            .create(second.clone())
+            .perform(&operator)
+            .await?;
+        assert_eq!(
+            repo.tags().perform(&operator).await?,
+            vec!["pre-migration".to_string(), "release/2026-10".to_string()]
+        );
+
+        let again = repo
+            .tag("pre-migration")
+            .create(second.clone())
+            .perform(&operator)
+            .await;
+        assert!(
+            matches!(again, Err(CreateTagError::AlreadyExists { .. })),
+            "tags do not move: {again:?}"
+        );
+
+        let tag = repo.tag("pre-migration").load().perform(&operator).await?;
+        assert_eq!(tag.revision, first);
+        assert!(tag.annotation.is_none());
+
+        assert_eq!(repo.snapshot(tag).revision(), &first);
+        let snapshot = repo
+            .snapshot_tag("release/2026-10")
+            .perform(&operator)
+            .await?;
+        assert_eq!(snapshot.revision(), &second);
+        let missing = repo.snapshot_tag("no-such-tag").perform(&operator).await;
+        assert!(matches!(missing, Err(LoadTagError::NotFound { .. })));
+
+        repo.tag("pre-migration")
+            .delete()
+            .perform(&operator)
+            .await?;
+        assert_eq!(
+            repo.tags().perform(&operator).await?,
+            vec!["release/2026-10".to_string()]
+        );
+        let gone = repo.tag("pre-migration").load().perform(&operator).await;
+        assert!(matches!(gone, Err(LoadTagError::NotFound { .. })));
+        let gone = repo.tag("pre-migration").delete().perform(&operator).await;
+        assert!(matches!(gone, Err(DeleteTagError::NotFound { .. })));
+        Ok(())
+    }
+
+    #[dialog_common::test]
+    async fn it_signs_annotated_tags() -> Result<()> {
+        let (operator, profile) = test_operator_with_profile().await;
+        let repo = test_repo(&operator, &profile).await;
+        let branch = repo.branch("main").open().perform(&operator).await?;
+        let revision = branch
+            .commit(stream::iter(vec![Instruction::Assert(name(
+                "id:alice", "Alice",
+            ))]))
+            .perform(&operator)
+            .await?;
+
+        let tag = repo
+            .tag("release")
+            .create(revision)
+            .annotate("first release")
+            .perform(&operator)
+            .await?;
+        let annotation = tag.annotation.clone().expect("annotated");
+        assert_eq!(annotation.message, "first release");
+        assert_eq!(annotation.authority, profile.did());
+        tag.verify()?;
+        assert_eq!(repo.tag("release").load().perform(&operator).await?, tag);
+
+        let mut renamed = tag.clone();
+        renamed.name = "other".into();
+        assert!(renamed.verify().is_err(), "the signature binds the name");
+
+        let mut reworded = tag;
+        if let Some(annotation) = reworded.annotation.as_mut() {
+            annotation.message = "second release".into();
+        }
+        assert!(
+            reworded.verify().is_err(),
+            "the signature binds the message"
+        );
+        Ok(())
+    }
+}
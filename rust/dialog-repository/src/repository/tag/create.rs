@@ -0,0 +1,79 @@
+//! Command to create a tag.
+
+use dialog_capability::Provider;
+use dialog_common::ConditionalSync;
+use dialog_common::time::{UNIX_EPOCH, now};
+use dialog_effects::authority::{Attest, Identify, OperatorExt as _};
+use dialog_effects::memory::Publish;
+
+use crate::{Annotation, CreateTagError, PublishError, Revision, Tag, TagReference};
+
+/// Command to create a tag naming a revision.
+///
+/// Created by [`TagReference::create`]. The publish expects the tag cell
+/// to be empty, so creating a tag that already exists fails with
+/// [`CreateTagError::AlreadyExists`] rather than repointing it.
+pub struct CreateTag {
+    reference: TagReference,
+    revision: Revision,
+    message: Option<String>,
+}
+
+impl CreateTag {
+    /// Create from a tag reference and the revision to name.
+    pub fn new(reference: TagReference, revision: Revision) -> Self {
+        Self {
+            reference,
+            revision,
+            message: None,
+        }
+    }
+
+    /// Make this an annotated tag: carry `message`, and sign the tag with
+    /// the session key performing the command.
+    pub fn annotate(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Execute the create, returning the stored tag.
+    pub async fn perform<Env>(self, env: &Env) -> Result<Tag, CreateTagError>
+    where
+        Env: Provider<Publish> + Provider<Identify> + Provider<Attest> + ConditionalSync,
+    {
+        let name = self.reference.name().to_string();
+        if name.is_empty() {
+            return Err(CreateTagError::InvalidName { name });
+        }
+
+        let mut tag = Tag {
+            name: name.clone(),
+            revision: self.revision,
+            annotation: None,
+        };
+        if let Some(message) = self.message {
+            let authority = Identify.perform(env).await?;
+            tag.annotation = Some(Annotation {
+                message,
+                tagger: authority.did(),
+                authority: authority.profile().clone(),
+                time: now()
+                    .duration_since(UNIX_EPOCH)
+                    .ok()
+                    .map(|elapsed| elapsed.as_millis() as u64),
+                signature: Vec::new(),
+            });
+            if let (Some(payload), Some(annotation)) = (tag.payload(), tag.annotation.as_mut()) {
+                annotation.signature = Attest::new(payload).perform(env).await?;
+            }
+        }
+
+        match self.reference.tag().publish(tag.clone()).perform(env).await {
+            Ok(()) => Ok(tag),
+            Err(PublishError::VersionMismatch { .. }) => {
+                Err(CreateTagError::AlreadyExists { name })
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+}
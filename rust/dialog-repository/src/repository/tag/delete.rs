@@ -0,0 +1,47 @@
+//! Command to delete a tag.
+
+use dialog_capability::Provider;
+use dialog_common::ConditionalSync;
+use dialog_effects::memory::prelude::{
+    CellExt as _, MemoryExt as _, MemorySubjectExt as _, SpaceExt as _,
+};
+use dialog_effects::memory::{Resolve, Retract};
+
+use crate::{DeleteTagError, Tag, TagReference};
+
+/// Command to delete a tag.
+///
+/// The retraction is CAS'd against the version the tag held when it was
+/// read, so a tag deleted and recreated concurrently is not removed on
+/// the strength of a stale read.
+pub struct DeleteTag(TagReference);
+
+impl DeleteTag {
+    /// Create from a tag reference.
+    pub fn new(reference: TagReference) -> Self {
+        Self(reference)
+    }
+
+    /// Execute the delete, returning the tag that was removed.
+    pub async fn perform<Env>(self, env: &Env) -> Result<Tag, DeleteTagError>
+    where
+        Env: Provider<Resolve> + Provider<Retract> + ConditionalSync,
+    {
+        let name = self.0.name().to_string();
+        let cell = self.0.tag();
+        cell.resolve().perform(env).await?;
+        let Some(edition) = cell.edition() else {
+            return Err(DeleteTagError::NotFound { name });
+        };
+
+        self.0
+            .subject()
+            .memory()
+            .space(format!("tag/{name}"))
+            .cell(cell.name())
+            .retract(edition.version)
+            .perform(env)
+            .await?;
+        Ok(edition.content)
+    }
+}
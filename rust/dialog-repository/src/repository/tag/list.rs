@@ -0,0 +1,53 @@
+//! Command to list a repository's tags.
+
+use dialog_capability::{Provider, Subject};
+use dialog_common::ConditionalSync;
+use dialog_effects::memory::List;
+use dialog_effects::memory::prelude::{MemoryExt as _, MemorySubjectExt as _};
+use dialog_varsig::Principal;
+
+use crate::{ListTagsError, Repository};
+
+/// Command to list the names of a repository's tags.
+///
+/// Created by [`Repository::tags`]. Names come back sorted.
+pub struct ListTags {
+    subject: Subject,
+}
+
+impl<C: Principal> Repository<C> {
+    /// Create a command listing this repository's tags.
+    pub fn tags(&self) -> ListTags {
+        ListTags {
+            subject: self.subject(),
+        }
+    }
+}
+
+impl ListTags {
+    /// Execute the listing.
+    pub async fn perform<Env>(self, env: &Env) -> Result<Vec<String>, ListTagsError>
+    where
+        Env: Provider<List> + ConditionalSync,
+    {
+        // A tag's name may itself contain `/`, so the name is whatever
+        // sits between the space prefix and the cell.
+        let mut names: Vec<String> = self
+            .subject
+            .memory()
+            .list("tag/")
+            .perform(env)
+            .await?
+            .into_iter()
+            .filter_map(|address| {
+                address
+                    .strip_prefix("tag/")?
+                    .strip_suffix("/tag")
+                    .map(str::to_string)
+            })
+            .collect();
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+}
@@ -0,0 +1,28 @@
+//! Command to load an existing tag.
+
+use dialog_capability::Provider;
+use dialog_effects::memory::Resolve;
+
+use crate::{LoadTagError, Tag, TagReference};
+
+/// Command to load an existing tag.
+pub struct LoadTag(TagReference);
+
+impl LoadTag {
+    /// Create from a tag reference.
+    pub fn new(reference: TagReference) -> Self {
+        Self(reference)
+    }
+
+    /// Execute the load operation.
+    pub async fn perform<Env>(self, env: &Env) -> Result<Tag, LoadTagError>
+    where
+        Env: Provider<Resolve>,
+    {
+        let cell = self.0.tag();
+        cell.resolve().perform(env).await?;
+        cell.content().ok_or_else(|| LoadTagError::NotFound {
+            name: self.0.name().to_string(),
+        })
+    }
+}
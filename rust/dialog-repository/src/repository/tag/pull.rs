@@ -0,0 +1,146 @@
+//! Command to adopt a tag from a remote.
+
+use dialog_artifacts::DialogArtifactsError;
+use dialog_artifacts::history::{History as _, TreeHistory};
+use dialog_capability::{Fork, Provider, Subject};
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::{Get, Put};
+use dialog_effects::blob::{Import as BlobImport, Read as BlobRead};
+use dialog_effects::memory::{Publish, Resolve};
+
+use crate::repository::branch::Successions;
+use crate::{
+    Branch, NetworkedIndex, PullTagError, RemoteRepository, RemoteSite, RepositoryArchiveExt as _,
+    RepositoryMemoryExt as _, Tag, TagReference, WriteLease,
+};
+
+/// Command to adopt the remote's tag of the same name locally.
+///
+/// Created by [`TagReference::pull`]. The remote's tag is verified before
+/// it lands: the tagged head's signature, and an annotated tag's own (see
+/// [`Tag::verify`]). A tag the local repository already holds is a no-op,
+/// and one that names a different revision locally is a
+/// [`PullTagError::Conflict`] — tags do not move. The tagged head is also
+/// checked against the key successions recorded on a local branch, the
+/// way a branch pull checks the head it adopts (see
+/// [`RetainedSuccessions::verify_head`](crate::RetainedSuccessions::verify_head)).
+///
+/// Only the tag is pulled. The tagged tree's blocks hydrate as anything
+/// else does: through a branch tracking the remote, or an export that
+/// downloads (see [`SnapshotExport::download`](crate::SnapshotExport::download)).
+pub struct PullTag {
+    reference: TagReference,
+    remote: RemoteRepository,
+    branch: Branch,
+}
+
+impl PullTag {
+    /// Create from the local tag reference, the remote to pull from, and
+    /// the branch whose recorded successions the tagged head must pass.
+    pub fn new(reference: TagReference, remote: RemoteRepository, branch: &Branch) -> Self {
+        Self {
+            reference,
+            remote,
+            branch: branch.clone(),
+        }
+    }
+
+    /// Execute the pull, returning the tag as the local repository now
+    /// holds it.
+    ///
+    /// Blocks of the tagged tree read on the way in land in the local
+    /// archive, and the tag is what names them, so the pull holds the
+    /// archive against a garbage collection until the tag is published.
+    pub async fn perform<Env>(self, env: &Env) -> Result<Tag, PullTagError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Publish>
+            + Provider<BlobRead>
+            + Provider<BlobImport>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + Provider<Fork<RemoteSite, BlobRead>>
+            + ConditionalSync
+            + 'static,
+    {
+        let lease = WriteLease::acquire(self.branch.subject(), env).await?;
+        let pulled = self.adopt(env).await;
+        let released = lease.release(env).await;
+        let tag = pulled?;
+        released?;
+        Ok(tag)
+    }
+
+    /// The work of [`perform`](Self::perform), under its lease.
+    async fn adopt<Env>(self, env: &Env) -> Result<Tag, PullTagError>
+    where
+        Env: Provider<Get>
+            + Provider<Put>
+            + Provider<Resolve>
+            + Provider<Publish>
+            + Provider<BlobRead>
+            + Provider<BlobImport>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + Provider<Fork<RemoteSite, BlobRead>>
+            + ConditionalSync
+            + 'static,
+    {
+        let name = self.reference.name().to_string();
+
+        let upstream = Subject::from(self.remote.did()).tag(&name).tag();
+        upstream
+            .resolve()
+            .fork(self.remote.address().site())
+            .perform(env)
+            .await?;
+        let Some(tag) = upstream.content() else {
+            return Err(PullTagError::NotFound {
+                name,
+                remote: self.remote.site().name().to_string(),
+            });
+        };
+        // The name is part of what an annotation signs; a tag found under
+        // a name it does not carry was copied there.
+        if tag.name != name {
+            return Err(PullTagError::Misplaced {
+                name,
+                carried: tag.name,
+            });
+        }
+        tag.revision.verify().map_err(DialogArtifactsError::from)?;
+        tag.verify()?;
+
+        // The tagged tree lives on the remote; its record for the head is
+        // read from there, and only the head's path through it. Without a
+        // recorded succession there is nothing to hold it to.
+        let successions = Successions::new(&self.branch, env);
+        if !successions.is_empty().await? {
+            let store =
+                NetworkedIndex::new(env, self.branch.archive().index(), self.remote.clone());
+            let version = tag.revision.version();
+            let record = TreeHistory::from_root(tag.revision.tree.hash(), store)
+                .revision_record(&version)
+                .await?
+                .ok_or_else(|| {
+                    DialogArtifactsError::InvalidSignature(format!(
+                        "Tagged head {version} carries no revision record"
+                    ))
+                })?;
+            successions.verify_head(&tag.revision, &record).await?;
+        }
+
+        let local = self.reference.tag();
+        local.resolve().perform(env).await?;
+        match local.content() {
+            Some(existing) if existing == tag => Ok(tag),
+            Some(_) => Err(PullTagError::Conflict { name }),
+            None => {
+                local.publish(tag.clone()).perform(env).await?;
+                Ok(tag)
+            }
+        }
+    }
+}
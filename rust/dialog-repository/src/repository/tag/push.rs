@@ -0,0 +1,78 @@
+//! Command to publish a tag to a remote.
+
+use dialog_capability::{Fork, Provider, Subject};
+use dialog_common::ConditionalSync;
+use dialog_effects::archive::Get;
+use dialog_effects::memory::{Publish, Resolve};
+
+use crate::{
+    EMPTY_TREE_HASH, PushTagError, RemoteRepository, RemoteSite, RepositoryMemoryExt as _, Tag,
+    TagReference,
+};
+
+/// Command to publish a local tag under the same name at a remote.
+///
+/// Created by [`TagReference::push`]. A tag carries no content of its own,
+/// so pushing one uploads nothing: the tagged revision's tree must
+/// already be at the remote — pushed with the branch it was committed
+/// on — or the push fails with [`PushTagError::Unreplicated`] instead of
+/// publishing a tag the remote cannot read. Tags are immutable on the
+/// remote as they are locally: pushing a tag the remote already holds is
+/// a no-op, and pushing over a different one is a
+/// [`PushTagError::Conflict`].
+pub struct PushTag {
+    reference: TagReference,
+    remote: RemoteRepository,
+}
+
+impl PushTag {
+    /// Create from the local tag reference and the remote to push to.
+    pub fn new(reference: TagReference, remote: RemoteRepository) -> Self {
+        Self { reference, remote }
+    }
+
+    /// Execute the push, returning the tag as the remote now holds it.
+    pub async fn perform<Env>(self, env: &Env) -> Result<Tag, PushTagError>
+    where
+        Env: Provider<Resolve>
+            + Provider<Fork<RemoteSite, Get>>
+            + Provider<Fork<RemoteSite, Resolve>>
+            + Provider<Fork<RemoteSite, Publish>>
+            + ConditionalSync,
+    {
+        let name = self.reference.name().to_string();
+        let tag = self.reference.load().perform(env).await?;
+
+        let tree = *tag.revision.tree.hash();
+        if tree != EMPTY_TREE_HASH
+            && self
+                .remote
+                .archive()
+                .index()
+                .get(tree)
+                .perform(env)
+                .await?
+                .is_none()
+        {
+            return Err(PushTagError::Unreplicated {
+                name,
+                tree: tag.revision.tree.clone(),
+            });
+        }
+
+        let address = self.remote.address();
+        let cell = Subject::from(self.remote.did()).tag(&name).tag();
+        cell.resolve().fork(address.site()).perform(env).await?;
+        match cell.content() {
+            Some(existing) if existing == tag => Ok(tag),
+            Some(_) => Err(PushTagError::Conflict { name }),
+            None => {
+                cell.publish(tag.clone())
+                    .fork(address.site())
+                    .perform(env)
+                    .await?;
+                Ok(tag)
+            }
+        }
+    }
+}
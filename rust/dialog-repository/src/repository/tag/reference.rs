@@ -0,0 +1,68 @@
+use dialog_capability::{Capability, Did, Policy, Subject};
+use dialog_effects::memory::Space;
+use dialog_effects::memory::prelude::SpaceExt;
+
+use crate::{
+    Branch, Cell, CreateTag, DeleteTag, LoadTag, PullTag, PushTag, RemoteRepository, Revision, Tag,
+};
+
+/// A reference to a named tag within a repository's memory.
+///
+/// Wraps `Capability<Space>` scoped to `tag/{name}`. Use `.create()`,
+/// `.load()` or `.delete()` to create a command, then `.perform(&env)`.
+#[derive(Debug, Clone)]
+pub struct TagReference(Capability<Space>);
+
+impl From<Capability<Space>> for TagReference {
+    fn from(space: Capability<Space>) -> Self {
+        Self(space)
+    }
+}
+
+impl TagReference {
+    /// The DID of the repository this tag belongs to.
+    pub fn of(&self) -> &Did {
+        self.0.subject()
+    }
+
+    /// The subject (repository) this tag belongs to.
+    pub fn subject(&self) -> Subject {
+        Subject::from(self.of().clone())
+    }
+
+    /// The tag name, extracted from the space path.
+    pub fn name(&self) -> &str {
+        Space::of(&self.0).space.strip_prefix("tag/").unwrap_or("")
+    }
+
+    /// The cell holding this tag's [`Tag`].
+    pub fn tag(&self) -> Cell<Tag> {
+        self.0.clone().cell("tag").into()
+    }
+
+    /// Create the tag, naming `revision`. Fails if the tag exists.
+    pub fn create(self, revision: Revision) -> CreateTag {
+        CreateTag::new(self, revision)
+    }
+
+    /// Load the tag, returning an error if it doesn't exist.
+    pub fn load(self) -> LoadTag {
+        LoadTag::new(self)
+    }
+
+    /// Delete the tag. The revision it named is untouched.
+    pub fn delete(self) -> DeleteTag {
+        DeleteTag::new(self)
+    }
+
+    /// Publish this tag to the same name at `remote`.
+    pub fn push(self, remote: &RemoteRepository) -> PushTag {
+        PushTag::new(self, remote.clone())
+    }
+
+    /// Adopt the tag of the same name at `remote`, provided its head
+    /// passes the key successions recorded on `branch`.
+    pub fn pull(self, remote: &RemoteRepository, branch: &Branch) -> PullTag {
+        PullTag::new(self, remote.clone(), branch)
+    }
+}
@@ -14,9 +14,9 @@
 //!   concludes — the index a layer looks rules up by.
 //! - `dialog.rule/source` `of` rule-entity `is` the canonical dag-cbor
 //!   `DeductiveRuleDescriptor` (a `Value::Bytes`) — the body, hydrated
-//!   via `DeductiveRule::decode`. (Bytes, not Record: `Value::Record`
-//!   isn't yet supported end-to-end through the index; the bytes are
-//!   opaque to the query layer either way.)
+//!   via `DeductiveRule::decode`. (Bytes, not Record: the body is only
+//!   ever decoded whole, so a record's cached form buys nothing, and the
+//!   bytes are opaque to the query layer either way.)
 //!
 //! These names are a dialog-repository convention (like
 //! `dialog.session/*`).
@@ -48,8 +48,8 @@ use dialog_query::formula::revision::{RevisionParentQuery, RevisionQuery};
 use dialog_query::type_system::Type as Kind;
 use dialog_query::types::Any;
 use dialog_query::{
-    AttributeQuery, Cardinality, ConceptQuery, DeductiveRule, Descriptor, FormulaQuery,
-    InductiveRule, Parameters, Premise, Proposition, Term, the,
+    AttributeQuery, Cardinality, ConceptQuery, ConstraintRule, DeductiveRule, Descriptor,
+    FormulaQuery, InductiveRule, Parameters, Premise, Proposition, Term, the,
 };
 use parking_lot::RwLock;
 
@@ -60,7 +60,7 @@ use crate::{Revision, schema};
 // rule types themselves; this module re-uses them for its selectors,
 // caches, and dispatch probing.
 pub(crate) use dialog_query::rule::statement::{
-    conclusion_attr, on_attr, on_entity, reads_attr, source_attr,
+    checks_attr, conclusion_attr, on_attr, on_entity, reads_attr, source_attr,
 };
 
 /// The `dialog.concept/transient` marker attribute. A concept carrying it
@@ -79,6 +79,14 @@ pub(crate) fn hydrate_inductive(source: &[u8]) -> Result<InductiveRule, Evaluati
         .map_err(|reason| EvaluationError::Store(format!("inductive rule hydrate: {reason}")))
 }
 
+/// Hydrate a compiled [`ConstraintRule`] from a `dialog.rule/source` claim
+/// value (the canonical dag-cbor
+/// [`ConstraintRuleDescriptor`](dialog_query::ConstraintRuleDescriptor)).
+pub(crate) fn hydrate_constraint(source: &[u8]) -> Result<ConstraintRule, EvaluationError> {
+    ConstraintRule::decode(source)
+        .map_err(|reason| EvaluationError::Store(format!("constraint rule hydrate: {reason}")))
+}
+
 /// [`Statement`] wrapper declaring a concept transient: facts of it are
 /// commands, dispatched rather than asserted, living for one induction
 /// round and never committed. The marker is a branch-level fact — it is
@@ -274,13 +282,16 @@ pub struct RuleCache {
 }
 
 /// The committed trigger footprint at a branch head: every `on:`
-/// entity present in `dialog.rule/on` (inductive triggers) and
-/// `dialog.rule/reads` (deductive support edges). The O(1) gate commit-time
+/// entity present in `dialog.rule/on` (inductive triggers),
+/// `dialog.rule/checks` (constraint triggers) and `dialog.rule/reads`
+/// (deductive support edges). The O(1) gate commit-time
 /// dispatch intersects touched attributes against before any probe.
 #[derive(Debug, Default, Clone)]
 pub(crate) struct TriggerFootprint {
     /// `on:` entities some inductive rule watches.
     pub(crate) on: BTreeSet<Entity>,
+    /// `on:` entities some constraint rule watches.
+    pub(crate) checks: BTreeSet<Entity>,
     /// `on:` entities some deductive rule's body reads.
     pub(crate) reads: BTreeSet<Entity>,
 }
@@ -300,11 +311,16 @@ struct RuleCacheInner {
     /// Committed inductive-rule entities watching an `on:` entity, as
     /// of a branch head.
     triggers: HashMap<Entity, (Revision, Vec<Entity>)>,
+    /// Committed constraint-rule entities watching an `on:` entity, as
+    /// of a branch head.
+    checks: HashMap<Entity, (Revision, Vec<Entity>)>,
     /// Committed deductive-rule entities whose bodies read an `on:`
     /// entity, as of a branch head.
     reads: HashMap<Entity, (Revision, Vec<Entity>)>,
     /// Hydrated inductive bodies, content-addressed — never stale.
     inductive: HashMap<Entity, InductiveRule>,
+    /// Hydrated constraint bodies, content-addressed — never stale.
+    constraints: HashMap<Entity, ConstraintRule>,
     /// Whether a concept carries the committed `dialog.concept/transient`
     /// marker, as of a branch head.
     transient: HashMap<Entity, (Revision, bool)>,
@@ -373,6 +389,21 @@ impl RuleCache {
         self.inner.write().triggers.insert(on, (head, entities));
     }
 
+    /// Cached committed constraint-rule entities watching `on` if
+    /// scanned at `head`.
+    pub(crate) fn checks(&self, on: &Entity, head: &Revision) -> Option<Vec<Entity>> {
+        match self.inner.read().checks.get(on) {
+            Some((scanned_at, entities)) if scanned_at == head => Some(entities.clone()),
+            _ => None,
+        }
+    }
+
+    /// Record the committed constraint-rule entities watching `on` at
+    /// `head`.
+    pub(crate) fn record_checks(&self, on: Entity, head: Revision, entities: Vec<Entity>) {
+        self.inner.write().checks.insert(on, (head, entities));
+    }
+
     /// Cached committed deductive-rule entities reading `on` if
     /// scanned at `head`.
     pub(crate) fn reads(&self, on: &Entity, head: &Revision) -> Option<Vec<Entity>> {
@@ -399,6 +430,17 @@ impl RuleCache {
         self.inner.write().inductive.insert(rule, body);
     }
 
+    /// A cached hydrated constraint body by rule entity, if present.
+    pub(crate) fn constraint(&self, rule: &Entity) -> Option<ConstraintRule> {
+        self.inner.read().constraints.get(rule).cloned()
+    }
+
+    /// Cache a hydrated constraint body under its content-addressed
+    /// entity.
+    pub(crate) fn record_constraint(&self, rule: Entity, body: ConstraintRule) {
+        self.inner.write().constraints.insert(rule, body);
+    }
+
     /// The cached committed transience verdict for `concept` if
     /// scanned at `head`.
     pub(crate) fn transient(&self, concept: &Entity, head: &Revision) -> Option<bool> {
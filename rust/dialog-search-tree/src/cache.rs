@@ -145,6 +145,16 @@ where
         cache.insert(key, value)
     }
 
+    /// Evicts `key` from the cache, returning its value if it was cached.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let cache = &self.cache;
+        #[cfg(target_arch = "wasm32")]
+        let mut cache = self.cache.borrow_mut();
+
+        cache.remove(key)
+    }
+
     /// Retrieves a value from the cache, if it is cached.
     fn get(&self, key: &K) -> Option<V> {
         #[cfg(not(target_arch = "wasm32"))]
@@ -4,6 +4,8 @@ use dialog_effects::archive::ArchiveError;
 use dialog_effects::memory::MemoryError;
 use thiserror::Error;
 
+use crate::EncryptionError;
+
 /// The common error type used by this crate
 #[derive(Error, Debug)]
 pub enum DialogStorageError {
@@ -31,6 +33,10 @@ pub enum DialogStorageError {
     /// An error that occurs when byte hash verification fails
     #[error("Byte hash verification failed: {0}")]
     Verification(String),
+
+    /// An error that occurs when sealing or opening encrypted content
+    #[error(transparent)]
+    Encryption(#[from] EncryptionError),
 }
 
 impl From<ArchiveError> for DialogStorageError {
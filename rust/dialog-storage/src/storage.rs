@@ -16,6 +16,9 @@ pub use cache::*;
 mod compress;
 pub use compress::*;
 
+mod encrypt;
+pub use encrypt::*;
+
 mod overlay;
 pub use overlay::*;
 
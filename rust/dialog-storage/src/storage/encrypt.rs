@@ -0,0 +1,836 @@
+//! Convergent encryption for stored content.
+//!
+//! Every space has one [`EncryptionKey`], generated at random when the
+//! space is first unlocked ([`EncryptionKey::generate`]) and kept
+//! [wrapped](EncryptionKey::wrap) for each credential allowed to unlock it,
+//! so rotating a credential never changes the key content is sealed under.
+//! Content is sealed with XChaCha20-Poly1305 under subkeys of it, with the
+//! nonce derived from the plaintext itself: sealing is deterministic, so
+//! equal plaintexts seal to equal ciphertexts within a space and
+//! content-addressed dedup keeps working on the sealed bytes.
+//!
+//! Two envelopes are defined here:
+//!
+//! - A *block* ([`EncryptionKey::seal`]) is `[version][nonce][ciphertext]`,
+//!   used for tree nodes and spilled values.
+//! - A *blob* ([`BlobSealer`], [`BlobOpener`]) is a version byte followed by
+//!   fixed [`BLOB_CHUNK_SIZE`] chunks, each sealed on its own and bound to
+//!   its index and to whether it is the last one, so a blob can be streamed
+//!   and read by range without being truncated or reordered undetected.
+//!
+//! The [`Encrypted`](crate::provider::Encrypted) provider stores a sealed
+//! block under a keyed [`address`](EncryptionKey::address) rather than its
+//! plaintext hash, so whoever holds the store cannot confirm a guess at the
+//! content by hashing it. [`EncryptedStorage`] seals values only, under
+//! whatever keys its caller picks.
+//!
+//! Keys are shared with other principals by sealing them to their `did:key`
+//! with HPKE ([`EncryptionKey::seal_for`]), which is what rides in a UCAN
+//! delegation.
+
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use blake3::Hasher;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce, XChaCha20Poly1305, XNonce};
+use curve25519_dalek::MontgomeryPoint;
+use dialog_common::{Blake3Hash, ConditionalSync};
+use dialog_credentials::key::KeyExport;
+use dialog_credentials::{Ed25519Signer, Ed25519Verifier, Signer};
+use dialog_varsig::{Did, Principal};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::DialogStorageError;
+
+use super::StorageBackend;
+
+/// Envelope version byte leading every sealed block and blob.
+const VERSION: u8 = 1;
+
+const NONCE_LENGTH: usize = 24;
+const TAG_LENGTH: usize = 16;
+
+/// Bytes a sealed chunk carries on top of its plaintext.
+const CHUNK_OVERHEAD: usize = NONCE_LENGTH + TAG_LENGTH;
+
+/// Plaintext bytes per sealed blob chunk.
+pub const BLOB_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Bytes of a full sealed blob chunk.
+const SEALED_CHUNK_SIZE: usize = BLOB_CHUNK_SIZE + CHUNK_OVERHEAD;
+
+/// Bytes at the start of a blob that tell whether it is sealed: the version
+/// byte and the first chunk (see [`EncryptionKey::seals_blob`]).
+pub const SEALED_BLOB_HEAD: u64 = 1 + SEALED_CHUNK_SIZE as u64;
+
+/// The message a credential signs to derive the key its copy of a space
+/// key is wrapped under.
+const DERIVATION_MESSAGE: &[u8] = b"dialog-db/encryption-key@1";
+
+const ROOT_CONTEXT: &str = "dialog-db 2026-10 encryption root key";
+const ADDRESS_CONTEXT: &str = "dialog-db 2026-10 block address key";
+const BLOCK_KEY_CONTEXT: &str = "dialog-db 2026-10 block key";
+const BLOCK_NONCE_CONTEXT: &str = "dialog-db 2026-10 block nonce key";
+const BLOB_KEY_CONTEXT: &str = "dialog-db 2026-10 blob key";
+const BLOB_NONCE_CONTEXT: &str = "dialog-db 2026-10 blob nonce key";
+
+/// The HPKE (RFC 9180) suite keys are shared under: DHKEM(X25519,
+/// HKDF-SHA256), HKDF-SHA256 and ChaCha20-Poly1305, in base mode.
+const HPKE_KEM: u16 = 0x0020;
+const HPKE_KDF: u16 = 0x0001;
+const HPKE_AEAD: u16 = 0x0003;
+const HPKE_MODE_BASE: u8 = 0x00;
+const HPKE_VERSION: &[u8] = b"HPKE-v1";
+
+/// The HPKE `info` a shared key is sealed under.
+const SHARE_INFO: &[u8] = b"dialog-db/encryption-key-share@1";
+
+/// Length of a key sealed with [`EncryptionKey::seal_for`]: the
+/// encapsulated key, then the sealed key and its tag.
+const SEALED_KEY_LENGTH: usize = 32 + 32 + TAG_LENGTH;
+
+/// Errors produced while sealing or opening content.
+#[derive(Error, Debug)]
+pub enum EncryptionError {
+    /// The credential cannot derive, wrap or receive an encryption key.
+    #[error("Unsupported key for encryption: {0}")]
+    UnsupportedKey(String),
+
+    /// Signing the derivation message failed.
+    #[error("Could not derive encryption key: {0}")]
+    Derivation(String),
+
+    /// The cipher refused to seal the content.
+    #[error("Could not seal content")]
+    Seal,
+
+    /// The sealed content is malformed, truncated, or was sealed under a
+    /// different key.
+    #[error("Could not open sealed content: {0}")]
+    Open(String),
+
+    /// No key is unlocked for the space.
+    #[error("No encryption key is unlocked for {0}")]
+    Locked(String),
+
+    /// The space's wrapped keys could not be read or recorded.
+    #[error("Could not keep the wrapped keys: {0}")]
+    Keys(String),
+}
+
+/// The symmetric key content of a space is sealed under.
+///
+/// Deliberately opaque: its [`Debug`] never prints the key material.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionKey([u8; 32]);
+
+impl Debug for EncryptionKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("EncryptionKey(..)")
+    }
+}
+
+impl From<[u8; 32]> for EncryptionKey {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl EncryptionKey {
+    /// Generates a fresh key for a space.
+    pub fn generate() -> Result<Self, EncryptionError> {
+        let mut key = [0; 32];
+        getrandom::getrandom(&mut key)
+            .map_err(|error| EncryptionError::Derivation(error.to_string()))?;
+        Ok(Self(key))
+    }
+
+    /// The key `signer`'s copy of a space key is wrapped under.
+    ///
+    /// A hash of the signer's signature over a fixed message. Ed25519
+    /// signatures are deterministic, so every holder of the credential
+    /// derives the same key, including a non-extractable WebCrypto key that
+    /// never reveals its seed.
+    async fn wrapping(signer: &Signer) -> Result<Self, EncryptionError> {
+        let Some(signer) = signer.as_ed25519() else {
+            return Err(EncryptionError::UnsupportedKey(signer.did().to_string()));
+        };
+        let signature = signer
+            .signing_key()
+            .sign_bytes(DERIVATION_MESSAGE)
+            .await
+            .map_err(|error| EncryptionError::Derivation(error.to_string()))?;
+        let signature: [u8; 64] = signature.into();
+        Ok(Self(blake3::derive_key(ROOT_CONTEXT, &signature)))
+    }
+
+    /// Wraps this key for `signer`, so that only a holder of the credential
+    /// can [`unwrap`](Self::unwrap) it.
+    pub async fn wrap(&self, signer: &Signer) -> Result<Vec<u8>, EncryptionError> {
+        Self::wrapping(signer).await?.seal(&self.0)
+    }
+
+    /// Unwraps a key [`wrap`](Self::wrap)ped for `signer`.
+    pub async fn unwrap(wrapped: &[u8], signer: &Signer) -> Result<Self, EncryptionError> {
+        let key = Self::wrapping(signer).await?.open(wrapped)?;
+        let key: [u8; 32] = key
+            .as_slice()
+            .try_into()
+            .map_err(|_| EncryptionError::Open("malformed wrapped key".into()))?;
+        Ok(Self(key))
+    }
+
+    /// The raw key material.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    fn subkey(&self, context: &str) -> [u8; 32] {
+        blake3::derive_key(context, &self.0)
+    }
+
+    fn cipher(&self, context: &str) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(Key::from_slice(&self.subkey(context)))
+    }
+
+    /// The address a sealed block whose plaintext hashes to `digest` is
+    /// stored under: the digest's keyed BLAKE3 hash, so without the key an
+    /// address says nothing about the plaintext.
+    ///
+    /// It is one-way. The digest behind an address is learned by opening
+    /// the block stored there and hashing what it holds.
+    pub fn address(&self, digest: &Blake3Hash) -> Blake3Hash {
+        let address = blake3::keyed_hash(&self.subkey(ADDRESS_CONTEXT), digest.as_bytes());
+        Blake3Hash::from(*address.as_bytes())
+    }
+
+    /// Seals a block: `[version][nonce][ciphertext]`, with the nonce keyed
+    /// by the plaintext so equal blocks seal identically.
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let nonce = blake3::keyed_hash(&self.subkey(BLOCK_NONCE_CONTEXT), plaintext);
+        let nonce = &nonce.as_bytes()[..NONCE_LENGTH];
+        let ciphertext = self
+            .cipher(BLOCK_KEY_CONTEXT)
+            .encrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: &[VERSION],
+                },
+            )
+            .map_err(|_| EncryptionError::Seal)?;
+
+        let mut sealed = Vec::with_capacity(1 + NONCE_LENGTH + ciphertext.len());
+        sealed.push(VERSION);
+        sealed.extend_from_slice(nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    /// Opens a block sealed by [`seal`](Self::seal).
+    pub fn open(&self, sealed: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let Some((&version, rest)) = sealed.split_first() else {
+            return Err(EncryptionError::Open("empty envelope".into()));
+        };
+        if version != VERSION {
+            return Err(EncryptionError::Open(format!(
+                "unsupported envelope version {version}"
+            )));
+        }
+        if rest.len() < CHUNK_OVERHEAD {
+            return Err(EncryptionError::Open("truncated envelope".into()));
+        }
+        let (nonce, ciphertext) = rest.split_at(NONCE_LENGTH);
+        self.cipher(BLOCK_KEY_CONTEXT)
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &[VERSION],
+                },
+            )
+            .map_err(|_| EncryptionError::Open("authentication failed".into()))
+    }
+
+    /// Seals this key to `audience`, an Ed25519 `did:key`, so that only the
+    /// holder of its private key can [`unseal`](Self::unseal) it.
+    ///
+    /// HPKE (RFC 9180) in base mode, to the audience's key converted to its
+    /// X25519 form, with a fresh ephemeral key for every seal. The result
+    /// is `[encapsulated key][sealed key]`.
+    pub fn seal_for(&self, audience: &Did) -> Result<Vec<u8>, EncryptionError> {
+        let verifier = Ed25519Verifier::from_str(audience.as_str())
+            .map_err(|_| EncryptionError::UnsupportedKey(audience.to_string()))?;
+        let recipient = VerifyingKey::from_bytes(&verifier.0.to_bytes())
+            .map_err(|_| EncryptionError::UnsupportedKey(audience.to_string()))?
+            .to_montgomery();
+
+        let mut ephemeral = [0; 32];
+        getrandom::getrandom(&mut ephemeral)
+            .map_err(|error| EncryptionError::Derivation(error.to_string()))?;
+        let encapsulated = MontgomeryPoint::mul_base_clamped(ephemeral);
+        let shared = recipient.mul_clamped(ephemeral);
+        let (cipher, nonce) = share_cipher(&shared, &encapsulated, &recipient)?;
+        let wrapped = cipher
+            .encrypt(Nonce::from_slice(&nonce), self.0.as_slice())
+            .map_err(|_| EncryptionError::Seal)?;
+
+        let mut sealed = Vec::with_capacity(SEALED_KEY_LENGTH);
+        sealed.extend_from_slice(encapsulated.as_bytes());
+        sealed.extend_from_slice(&wrapped);
+        Ok(sealed)
+    }
+
+    /// Opens a key sealed to `recipient` by [`seal_for`](Self::seal_for).
+    ///
+    /// Needs the recipient's seed, so a non-extractable WebCrypto key cannot
+    /// receive a shared key.
+    pub async fn unseal(sealed: &[u8], recipient: &Ed25519Signer) -> Result<Self, EncryptionError> {
+        if sealed.len() != SEALED_KEY_LENGTH {
+            return Err(EncryptionError::Open("malformed sealed key".into()));
+        }
+        let export = recipient
+            .export()
+            .await
+            .map_err(|error| EncryptionError::UnsupportedKey(error.to_string()))?;
+        let seed: [u8; 32] = match export {
+            KeyExport::Extractable(seed) => seed
+                .as_slice()
+                .try_into()
+                .map_err(|_| EncryptionError::UnsupportedKey(recipient.did().to_string()))?,
+            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+            KeyExport::NonExtractable { .. } => {
+                return Err(EncryptionError::UnsupportedKey(recipient.did().to_string()));
+            }
+        };
+        let recipient = SigningKey::from_bytes(&seed);
+
+        let (encapsulated, wrapped) = sealed.split_at(32);
+        let encapsulated: [u8; 32] = encapsulated
+            .try_into()
+            .map_err(|_| EncryptionError::Open("malformed sealed key".into()))?;
+        let encapsulated = MontgomeryPoint(encapsulated);
+        let shared = encapsulated.mul_clamped(recipient.to_scalar_bytes());
+        let (cipher, nonce) = share_cipher(
+            &shared,
+            &encapsulated,
+            &recipient.verifying_key().to_montgomery(),
+        )?;
+        let key = cipher
+            .decrypt(Nonce::from_slice(&nonce), wrapped)
+            .map_err(|_| EncryptionError::Open("sealed for a different key".into()))?;
+        let key: [u8; 32] = key
+            .as_slice()
+            .try_into()
+            .map_err(|_| EncryptionError::Open("malformed sealed key".into()))?;
+        Ok(Self(key))
+    }
+
+    /// Whether `head`, the first [`SEALED_BLOB_HEAD`] bytes of a blob (all
+    /// of it, if shorter), starts a blob sealed under this key. A blob
+    /// stored before its space was unlocked is plaintext and does not.
+    pub fn seals_blob(&self, head: &[u8]) -> bool {
+        let Some((&VERSION, chunk)) = head.split_first() else {
+            return false;
+        };
+        let chunk = &chunk[..chunk.len().min(SEALED_CHUNK_SIZE)];
+        self.open_chunk(0, true, chunk).is_some() || self.open_chunk(0, false, chunk).is_some()
+    }
+
+    fn seal_chunk(&self, index: u64, last: bool, chunk: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let aad = chunk_aad(index, last);
+        let mut nonce = Hasher::new_keyed(&self.subkey(BLOB_NONCE_CONTEXT));
+        nonce.update(&aad);
+        nonce.update(chunk);
+        let nonce = nonce.finalize();
+        let nonce = &nonce.as_bytes()[..NONCE_LENGTH];
+        let ciphertext = self
+            .cipher(BLOB_KEY_CONTEXT)
+            .encrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: chunk,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| EncryptionError::Seal)?;
+
+        let mut sealed = Vec::with_capacity(NONCE_LENGTH + ciphertext.len());
+        sealed.extend_from_slice(nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    fn open_chunk(&self, index: u64, last: bool, sealed: &[u8]) -> Option<Vec<u8>> {
+        if sealed.len() < CHUNK_OVERHEAD {
+            return None;
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+        self.cipher(BLOB_KEY_CONTEXT)
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &chunk_aad(index, last),
+                },
+            )
+            .ok()
+    }
+}
+
+fn chunk_aad(index: u64, last: bool) -> [u8; 9] {
+    let mut aad = [0; 9];
+    aad[..8].copy_from_slice(&index.to_le_bytes());
+    aad[8] = u8::from(last);
+    aad
+}
+
+/// HPKE's `LabeledExtract`, under `suite`.
+fn labeled_extract(suite: &[u8], salt: &[u8], label: &[u8], ikm: &[u8]) -> [u8; 32] {
+    let (prk, _) = Hkdf::<Sha256>::extract(Some(salt), &[HPKE_VERSION, suite, label, ikm].concat());
+    prk.into()
+}
+
+/// HPKE's `LabeledExpand` to `N` bytes, under `suite`.
+fn labeled_expand<const N: usize>(
+    suite: &[u8],
+    prk: &[u8; 32],
+    label: &[u8],
+    info: &[u8],
+) -> [u8; N] {
+    let length = (N as u16).to_be_bytes();
+    let mut okm = [0; N];
+    Hkdf::<Sha256>::from_prk(prk)
+        .expect("a SHA-256 pseudorandom key")
+        .expand(
+            &[&length, HPKE_VERSION, suite, label, info].concat(),
+            &mut okm,
+        )
+        .expect("an output within HKDF-SHA256's bound");
+    okm
+}
+
+/// The AEAD and the nonce of the one message sealed in an HPKE base-mode
+/// context, from the X25519 exchange of `encapsulated` with `recipient`.
+fn share_cipher(
+    shared: &MontgomeryPoint,
+    encapsulated: &MontgomeryPoint,
+    recipient: &MontgomeryPoint,
+) -> Result<(ChaCha20Poly1305, [u8; 12]), EncryptionError> {
+    // An all-zero secret means a low-order point was presented.
+    if shared.as_bytes().iter().all(|byte| *byte == 0) {
+        return Err(EncryptionError::UnsupportedKey(
+            "low-order public key".into(),
+        ));
+    }
+
+    let kem = [b"KEM".as_slice(), &HPKE_KEM.to_be_bytes()].concat();
+    let kem_context = [encapsulated.as_bytes().as_slice(), recipient.as_bytes()].concat();
+    let prk = labeled_extract(&kem, &[], b"eae_prk", shared.as_bytes());
+    let shared_secret: [u8; 32] = labeled_expand(&kem, &prk, b"shared_secret", &kem_context);
+
+    let suite = [
+        b"HPKE".as_slice(),
+        &HPKE_KEM.to_be_bytes(),
+        &HPKE_KDF.to_be_bytes(),
+        &HPKE_AEAD.to_be_bytes(),
+    ]
+    .concat();
+    let psk_id_hash = labeled_extract(&suite, &[], b"psk_id_hash", &[]);
+    let info_hash = labeled_extract(&suite, &[], b"info_hash", SHARE_INFO);
+    let context = [&[HPKE_MODE_BASE][..], &psk_id_hash, &info_hash].concat();
+    let secret = labeled_extract(&suite, &shared_secret, b"secret", &[]);
+    let key: [u8; 32] = labeled_expand(&suite, &secret, b"key", &context);
+    let nonce: [u8; 12] = labeled_expand(&suite, &secret, b"base_nonce", &context);
+    Ok((ChaCha20Poly1305::new(Key::from_slice(&key)), nonce))
+}
+
+/// The size of a blob of `size` plaintext bytes once sealed.
+pub fn sealed_size(size: u64) -> u64 {
+    let chunk = BLOB_CHUNK_SIZE as u64;
+    let chunks = size.div_ceil(chunk).max(1);
+    1 + chunks * CHUNK_OVERHEAD as u64 + size
+}
+
+/// The sealed byte range holding plaintext bytes `offset..offset + length`,
+/// as `(offset, length, first chunk index, bytes to skip in it)`. Chunk
+/// aligned: the opener drops the leading `skip` bytes and trims the tail.
+pub fn sealed_range(offset: u64, length: Option<u64>) -> (u64, Option<u64>, u64, u64) {
+    let chunk = BLOB_CHUNK_SIZE as u64;
+    let sealed_chunk = SEALED_CHUNK_SIZE as u64;
+    let first = offset / chunk;
+    let skip = offset % chunk;
+    let sealed_length = length.map(|length| {
+        let last = (offset + length.max(1) - 1) / chunk;
+        (last - first + 1) * sealed_chunk
+    });
+    (1 + first * sealed_chunk, sealed_length, first, skip)
+}
+
+/// Incrementally seals a blob. Feed plaintext through
+/// [`update`](Self::update) and write every returned byte; the tail comes
+/// out of [`finish`](Self::finish).
+pub struct BlobSealer {
+    key: EncryptionKey,
+    index: u64,
+    started: bool,
+    pending: Vec<u8>,
+}
+
+impl BlobSealer {
+    /// Starts sealing a blob under `key`.
+    pub fn new(key: EncryptionKey) -> Self {
+        Self {
+            key,
+            index: 0,
+            started: false,
+            pending: Vec::new(),
+        }
+    }
+
+    fn header(&mut self, out: &mut Vec<u8>) {
+        if !self.started {
+            self.started = true;
+            out.push(VERSION);
+        }
+    }
+
+    /// Seals every chunk `bytes` completes. A full chunk is held back until
+    /// more bytes arrive, since only then is it known not to be the last.
+    pub fn update(&mut self, bytes: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let mut out = Vec::new();
+        self.header(&mut out);
+        self.pending.extend_from_slice(bytes);
+        while self.pending.len() > BLOB_CHUNK_SIZE {
+            let chunk: Vec<u8> = self.pending.drain(..BLOB_CHUNK_SIZE).collect();
+            out.extend(self.key.seal_chunk(self.index, false, &chunk)?);
+            self.index += 1;
+        }
+        Ok(out)
+    }
+
+    /// Seals the last chunk, which may be empty.
+    pub fn finish(mut self) -> Result<Vec<u8>, EncryptionError> {
+        let mut out = Vec::new();
+        self.header(&mut out);
+        out.extend(self.key.seal_chunk(self.index, true, &self.pending)?);
+        Ok(out)
+    }
+}
+
+/// Incrementally opens a blob sealed by [`BlobSealer`], whole or from a
+/// [`sealed_range`].
+pub struct BlobOpener {
+    key: EncryptionKey,
+    index: u64,
+    /// Whether the stream starts at the version byte (a whole-blob read).
+    header: bool,
+    /// Whether the stream may stop before the blob's last chunk.
+    bounded: bool,
+    skip: u64,
+    remaining: Option<u64>,
+    pending: Vec<u8>,
+}
+
+impl BlobOpener {
+    /// Opens a whole sealed blob.
+    pub fn new(key: EncryptionKey) -> Self {
+        Self {
+            key,
+            index: 0,
+            header: true,
+            bounded: false,
+            skip: 0,
+            remaining: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Opens plaintext bytes `offset..offset + length` from the stream of
+    /// the sealed range [`sealed_range`] computed for them.
+    pub fn ranged(key: EncryptionKey, offset: u64, length: Option<u64>) -> Self {
+        let (_, _, first, skip) = sealed_range(offset, length);
+        Self {
+            key,
+            index: first,
+            header: false,
+            bounded: length.is_some(),
+            skip,
+            remaining: length,
+            pending: Vec::new(),
+        }
+    }
+
+    fn emit(&mut self, mut plaintext: Vec<u8>, out: &mut Vec<u8>) {
+        let skip = (self.skip as usize).min(plaintext.len());
+        self.skip -= skip as u64;
+        plaintext.drain(..skip);
+        if let Some(remaining) = self.remaining.as_mut() {
+            let take = (*remaining as usize).min(plaintext.len());
+            plaintext.truncate(take);
+            *remaining -= take as u64;
+        }
+        out.extend(plaintext);
+    }
+
+    /// Opens every chunk `bytes` completes, holding back the one that may
+    /// turn out to be the last.
+    pub fn update(&mut self, bytes: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        self.pending.extend_from_slice(bytes);
+        if self.header && !self.pending.is_empty() {
+            let version = self.pending.remove(0);
+            if version != VERSION {
+                return Err(EncryptionError::Open(format!(
+                    "unsupported blob version {version}"
+                )));
+            }
+            self.header = false;
+        }
+
+        let mut out = Vec::new();
+        while self.pending.len() > SEALED_CHUNK_SIZE {
+            let chunk: Vec<u8> = self.pending.drain(..SEALED_CHUNK_SIZE).collect();
+            let plaintext = self
+                .key
+                .open_chunk(self.index, false, &chunk)
+                .ok_or_else(|| self.corrupt())?;
+            self.index += 1;
+            self.emit(plaintext, &mut out);
+        }
+        Ok(out)
+    }
+
+    /// Opens the held-back chunk. A whole or open-ended read must end on the
+    /// blob's last chunk, so a truncated stream fails here.
+    pub fn finish(mut self) -> Result<Vec<u8>, EncryptionError> {
+        if self.header {
+            return Err(EncryptionError::Open("truncated blob".into()));
+        }
+        let chunk = std::mem::take(&mut self.pending);
+        // A range past the end of the blob yields nothing at all.
+        if chunk.is_empty() && (self.bounded || self.index > 0 || self.skip > 0) {
+            return Ok(Vec::new());
+        }
+        let plaintext = match self.key.open_chunk(self.index, true, &chunk) {
+            Some(plaintext) => plaintext,
+            // A bounded range may stop short of the last chunk.
+            None if self.bounded => self
+                .key
+                .open_chunk(self.index, false, &chunk)
+                .ok_or_else(|| self.corrupt())?,
+            None => return Err(self.corrupt()),
+        };
+        let mut out = Vec::new();
+        self.emit(plaintext, &mut out);
+        Ok(out)
+    }
+
+    fn corrupt(&self) -> EncryptionError {
+        EncryptionError::Open(format!("blob chunk {} failed to open", self.index))
+    }
+}
+
+/// A layer over a [`StorageBackend`] that seals incoming writes under an
+/// [`EncryptionKey`], and opens outgoing reads.
+///
+/// Only values are sealed: each is stored under the key its caller gives,
+/// as given. A caller keying by plaintext hash who wants the store not to
+/// learn those hashes keys by [`EncryptionKey::address`] instead, as the
+/// [`Encrypted`](crate::provider::Encrypted) provider does.
+///
+/// Sealing is deterministic, so a content-addressed backend still dedups.
+#[derive(Clone)]
+pub struct EncryptedStorage<Backend> {
+    key: EncryptionKey,
+    backend: Backend,
+}
+
+impl<Backend> EncryptedStorage<Backend> {
+    /// Wrap the provided `backend` in an encryption layer keyed by `key`
+    pub fn new(key: EncryptionKey, backend: Backend) -> Self {
+        Self { key, backend }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<Backend> StorageBackend for EncryptedStorage<Backend>
+where
+    Backend: StorageBackend + ConditionalSync,
+    Backend::Value: From<Vec<u8>> + AsRef<[u8]>,
+{
+    type Key = Backend::Key;
+    type Value = Backend::Value;
+    type Error = DialogStorageError;
+
+    async fn set(&mut self, key: Self::Key, value: Self::Value) -> Result<(), Self::Error> {
+        let sealed = self.key.seal(value.as_ref())?;
+        self.backend
+            .set(key, sealed.into())
+            .await
+            .map_err(|error| error.into())
+    }
+
+    async fn get(&self, key: &Self::Key) -> Result<Option<Self::Value>, Self::Error> {
+        match self.backend.get(key).await.map_err(|error| error.into())? {
+            Some(value) => Ok(Some(self.key.open(value.as_ref())?.into())),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryStorageBackend;
+
+    async fn signer() -> Ed25519Signer {
+        Ed25519Signer::generate().await.unwrap()
+    }
+
+    fn key() -> EncryptionKey {
+        EncryptionKey::generate().unwrap()
+    }
+
+    fn seal_blob(key: &EncryptionKey, content: &[u8], write: usize) -> Vec<u8> {
+        let mut sealer = BlobSealer::new(key.clone());
+        let mut sealed = Vec::new();
+        for part in content.chunks(write.max(1)) {
+            sealed.extend(sealer.update(part).unwrap());
+        }
+        sealed.extend(sealer.finish().unwrap());
+        sealed
+    }
+
+    fn open_blob(mut opener: BlobOpener, sealed: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let mut opened = Vec::new();
+        for part in sealed.chunks(10_000) {
+            opened.extend(opener.update(part)?);
+        }
+        opened.extend(opener.finish()?);
+        Ok(opened)
+    }
+
+    #[dialog_common::test]
+    async fn it_unwraps_a_key_only_for_the_credential_it_was_wrapped_for() {
+        let key = key();
+        let signer = Signer::from(signer().await);
+        let wrapped = key.wrap(&signer).await.unwrap();
+
+        assert_eq!(wrapped, key.wrap(&signer).await.unwrap());
+        assert_eq!(EncryptionKey::unwrap(&wrapped, &signer).await.unwrap(), key);
+        assert!(
+            EncryptionKey::unwrap(&wrapped, &Signer::from(self::signer().await))
+                .await
+                .is_err()
+        );
+    }
+
+    #[dialog_common::test]
+    async fn it_addresses_blocks_by_a_keyed_hash() {
+        let key = key();
+        let digest = Blake3Hash::hash(b"node");
+        let address = key.address(&digest);
+
+        assert_ne!(address, digest);
+        assert_eq!(address, key.address(&digest));
+        assert_ne!(address, self::key().address(&digest));
+    }
+
+    #[dialog_common::test]
+    async fn it_seals_blocks_deterministically() {
+        let key = key();
+        let sealed = key.seal(b"node").unwrap();
+        assert_eq!(sealed, key.seal(b"node").unwrap());
+        assert_ne!(sealed, key.seal(b"other").unwrap());
+        assert_eq!(key.open(&sealed).unwrap(), b"node");
+    }
+
+    #[dialog_common::test]
+    async fn it_refuses_tampered_or_foreign_blocks() {
+        let key = key();
+        let mut sealed = key.seal(b"node").unwrap();
+        let foreign = self::key();
+        assert!(foreign.open(&sealed).is_err());
+
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert!(key.open(&sealed).is_err());
+    }
+
+    #[dialog_common::test]
+    async fn it_shares_a_key_with_an_audience() {
+        let key = key();
+        let audience = signer().await;
+        let sealed = key.seal_for(&audience.did()).unwrap();
+        assert_ne!(
+            sealed,
+            key.seal_for(&audience.did()).unwrap(),
+            "every seal takes a fresh ephemeral key"
+        );
+
+        assert_eq!(
+            EncryptionKey::unseal(&sealed, &audience).await.unwrap(),
+            key
+        );
+        assert!(
+            EncryptionKey::unseal(&sealed, &signer().await)
+                .await
+                .is_err()
+        );
+    }
+
+    #[dialog_common::test]
+    async fn it_round_trips_blobs_across_chunk_boundaries() {
+        let key = key();
+        for size in [
+            0,
+            1,
+            BLOB_CHUNK_SIZE,
+            BLOB_CHUNK_SIZE + 1,
+            3 * BLOB_CHUNK_SIZE - 7,
+        ] {
+            let content: Vec<u8> = (0..size).map(|n| (n % 251) as u8).collect();
+            let sealed = seal_blob(&key, &content, 5_000);
+            assert_eq!(sealed.len() as u64, sealed_size(size as u64), "size {size}");
+            assert_eq!(sealed, seal_blob(&key, &content, 70_000));
+            assert_eq!(
+                open_blob(BlobOpener::new(key.clone()), &sealed).unwrap(),
+                content
+            );
+        }
+    }
+
+    #[dialog_common::test]
+    async fn it_detects_a_truncated_blob() {
+        let key = key();
+        let content = vec![1u8; 2 * BLOB_CHUNK_SIZE + 10];
+        let sealed = seal_blob(&key, &content, 4096);
+        let truncated = &sealed[..1 + 2 * SEALED_CHUNK_SIZE];
+        assert!(open_blob(BlobOpener::new(key), truncated).is_err());
+    }
+
+    #[dialog_common::test]
+    async fn it_encrypts_values_of_a_storage_backend() {
+        let key = key();
+        let backend = MemoryStorageBackend::<Vec<u8>, Vec<u8>>::default();
+        let mut storage = EncryptedStorage::new(key.clone(), backend.clone());
+
+        storage
+            .set(b"key".to_vec(), b"value".to_vec())
+            .await
+            .unwrap();
+
+        let raw = backend.get(&b"key".to_vec()).await.unwrap().unwrap();
+        assert_eq!(key.open(&raw).unwrap(), b"value");
+        assert_eq!(
+            storage.get(&b"key".to_vec()).await.unwrap(),
+            Some(b"value".to_vec())
+        );
+    }
+}
@@ -8,6 +8,8 @@
 //! - [`FileSystem`] - Filesystem-based storage for native environments
 //! - [`IndexedDb`] - IndexedDB-based storage for WASM environments
 //! - [`Volatile`] - In-memory storage for testing
+//! - [`Encrypted`] - Seals archive blocks and blobs before handing them to
+//!   another provider
 //!
 //! # Architecture
 //!
@@ -21,6 +23,7 @@
 //! [`FileSystem`]: fs::FileSystem
 //! [`IndexedDb`]: indexeddb::IndexedDb
 //! [`Volatile`]: volatile::Volatile
+//! [`Encrypted`]: encrypted::Encrypted
 
 // The filesystem provider is isomorphic: native is backed by `tokio::fs`,
 // the browser by the File System Access API. It compiles on every target.
@@ -36,6 +39,9 @@ pub use indexeddb::*;
 pub mod volatile;
 pub use volatile::*;
 
+pub mod encrypted;
+pub use encrypted::*;
+
 pub mod space;
 pub use space::{Space, SpaceProvider};
 
@@ -0,0 +1,1453 @@
+//! Encrypting provider: seals archive blocks and blobs on their way into an
+//! inner provider and opens them on their way out.
+//!
+//! [`Encrypted`] wraps any provider of the archive and blob effects. Spaces
+//! whose [`EncryptionKey`] is in its [`Keyring`] are encrypted; every other
+//! subject passes through untouched, so wrapping a provider changes nothing
+//! until a key is unlocked. Once it is, the space reads sealed content
+//! only: a block not found sealed is missing, and a blob that does not open
+//! as sealed is refused, so a store cannot slip a space plaintext in place
+//! of what it sealed. What the space stored before it was unlocked reads
+//! back only while the space is [migrating](Keyring::migrate): a block not
+//! found sealed is then looked up in the clear, and a blob that does not
+//! open as sealed is read as it is.
+//!
+//! Addressing is what makes this fit under a content-addressed store:
+//!
+//! - A block is stored in the sealed counterpart of its catalog, under the
+//!   keyed [`address`](EncryptionKey::address) of its *plaintext* hash. The
+//!   search tree links nodes by plaintext hashes, so a read translates the
+//!   digest and verifies the opened block against it, while the store never
+//!   sees a hash it could confirm a guessed block with. An address is
+//!   one-way, so listing opens each sealed block to learn its digest, and a
+//!   catalog still lists the digests trees link.
+//! - A blob is addressed by its *sealed* bytes. A [`Write`] returns the hash
+//!   of what it stored, and that is the digest the blob index records, so a
+//!   remote verifying an [`Import`] checks the bytes it actually receives.
+//!
+//! Sealing is deterministic (see [`super::super::EncryptedStorage`]), so the
+//! same content lands on the same address on every replica holding the key.
+//!
+//! A space's key is generated the first time the space's own credential
+//! unlocks it, and kept [wrapped](EncryptionKey::wrap) in the space's memory
+//! for every principal allowed to unlock it (see [`Keyring::unlock`] and
+//! [`Keyring::hand_on`]), so rotating a credential leaves it in place.
+//!
+//! The effect-level transform lives in [`Conceal`], and
+//! [`Keyring::conceal`] applies it around any dispatch, which is how the
+//! operator seals fork effects before they are authorized for a remote
+//! site: the authorization then covers the sealed payload.
+//!
+//! [`Write`]: dialog_effects::blob::Write
+//! [`Import`]: dialog_effects::blob::Import
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use blake3::Hasher;
+use dialog_capability::{Ability, Capability, Constrained, Constraint, Did, Effect, Provider};
+use dialog_common::{Blake3Hash, Buffer, ConditionalSend, ConditionalSync};
+use dialog_credentials::Signer;
+use dialog_effects::archive::prelude::GetExt as _;
+use dialog_effects::archive::{self, ArchiveError};
+use dialog_effects::blob::prelude::BlobReadExt as _;
+use dialog_effects::blob::{
+    self, BlobError, BlobReader, BlobSink, BlobSource, BlobWriter, ByteRange,
+};
+use dialog_effects::memory;
+use dialog_effects::memory::prelude::{
+    CellExt as _, MemoryExt as _, MemorySubjectExt as _, SpaceExt as _,
+};
+use dialog_varsig::Principal;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BlobOpener, BlobSealer, EncryptionError, EncryptionKey, SEALED_BLOB_HEAD, sealed_range,
+    sealed_size,
+};
+
+/// Appended to a catalog's name to name the catalog its sealed blocks are
+/// kept in, apart from what the space stored in the clear.
+const SEALED_CATALOG: &str = ".sealed";
+
+/// The memory space and cell a space's wrapped keys are kept in.
+const KEYS_SPACE: &str = "encryption";
+const KEYS_CELL: &str = "keys";
+
+/// A space key, wrapped for one principal.
+#[derive(Serialize, Deserialize)]
+enum Wrapped {
+    /// Under the key the principal's signature derives (see
+    /// [`EncryptionKey::wrap`]).
+    Signed(#[serde(with = "serde_bytes")] Vec<u8>),
+    /// Sealed to the principal's `did:key` (see [`EncryptionKey::seal_for`])
+    /// until it first unlocks the space and wraps the key for itself.
+    Sealed(#[serde(with = "serde_bytes")] Vec<u8>),
+}
+
+/// A space's wrapped keys by principal, and the version of the cell they
+/// were read from.
+type WrappedKeys = (BTreeMap<String, Wrapped>, Option<memory::Version>);
+
+fn keys_error(error: impl Display) -> EncryptionError {
+    EncryptionError::Keys(error.to_string())
+}
+
+fn keys_cell(subject: &Did) -> Capability<memory::Cell> {
+    subject.clone().memory().space(KEYS_SPACE).cell(KEYS_CELL)
+}
+
+async fn wrapped_keys<Env>(subject: &Did, env: &Env) -> Result<WrappedKeys, EncryptionError>
+where
+    Env: Provider<memory::Resolve> + ConditionalSync,
+{
+    let Some(edition) = keys_cell(subject)
+        .resolve()
+        .perform(env)
+        .await
+        .map_err(keys_error)?
+    else {
+        return Ok((BTreeMap::new(), None));
+    };
+    let keys = serde_ipld_dagcbor::from_slice(&edition.content).map_err(keys_error)?;
+    Ok((keys, Some(edition.version)))
+}
+
+async fn record_keys<Env>(
+    subject: &Did,
+    (keys, version): WrappedKeys,
+    env: &Env,
+) -> Result<(), EncryptionError>
+where
+    Env: Provider<memory::Publish> + ConditionalSync,
+{
+    let content = serde_ipld_dagcbor::to_vec(&keys).map_err(keys_error)?;
+    keys_cell(subject)
+        .publish(content, version)
+        .perform(env)
+        .await
+        .map_err(keys_error)?;
+    Ok(())
+}
+
+/// The encryption keys of the spaces this replica can read, by subject.
+///
+/// Cloning shares the same set, so a key unlocked through one handle seals
+/// and opens for every provider holding the keyring.
+#[derive(Clone, Debug, Default)]
+pub struct Keyring {
+    keys: Arc<RwLock<HashMap<Did, EncryptionKey>>>,
+    /// Spaces whose content stored in the clear still reads back.
+    migrating: Arc<RwLock<HashSet<Did>>>,
+}
+
+impl Keyring {
+    /// Create an empty keyring.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Install the key of `subject`'s space.
+    pub fn insert(&self, subject: Did, key: EncryptionKey) {
+        self.keys.write().insert(subject, key);
+    }
+
+    /// The key of `subject`'s space, if unlocked.
+    pub fn get(&self, subject: &Did) -> Option<EncryptionKey> {
+        self.keys.read().get(subject).cloned()
+    }
+
+    /// Forget the key of `subject`'s space.
+    pub fn remove(&self, subject: &Did) -> Option<EncryptionKey> {
+        self.keys.write().remove(subject)
+    }
+
+    /// Keep reading what `subject`'s space stored in the clear before it
+    /// was unlocked, until it is [`migrated`](Self::migrated). Without this
+    /// an unlocked space reads sealed content only.
+    pub fn migrate(&self, subject: Did) {
+        self.migrating.write().insert(subject);
+    }
+
+    /// Stop reading `subject`'s content stored in the clear: it has all
+    /// been sealed again, or is no longer wanted.
+    pub fn migrated(&self, subject: &Did) {
+        self.migrating.write().remove(subject);
+    }
+
+    /// Whether `subject`'s content stored in the clear still reads back.
+    pub fn is_migrating(&self, subject: &Did) -> bool {
+        self.migrating.read().contains(subject)
+    }
+
+    /// Unlock `subject`'s space with `signer`, one of the principals its key
+    /// is wrapped for, reading and recording the wrapped keys through `env`.
+    ///
+    /// The space's own credential generates the key on first unlock. A key
+    /// [handed on](Self::hand_on) to `signer` is wrapped for it by
+    /// signature from then on, so later unlocks need no extractable seed.
+    pub async fn unlock<Env>(
+        &self,
+        subject: &Did,
+        signer: &Signer,
+        env: &Env,
+    ) -> Result<EncryptionKey, EncryptionError>
+    where
+        Env: Provider<memory::Resolve> + Provider<memory::Publish> + ConditionalSync,
+    {
+        let (mut keys, version) = wrapped_keys(subject, env).await?;
+        let principal = signer.did();
+        let key = match keys.get(principal.as_str()) {
+            Some(Wrapped::Signed(wrapped)) => EncryptionKey::unwrap(wrapped, signer).await?,
+            Some(Wrapped::Sealed(sealed)) => {
+                let recipient = signer
+                    .as_ed25519()
+                    .ok_or_else(|| EncryptionError::UnsupportedKey(principal.to_string()))?;
+                EncryptionKey::unseal(sealed, recipient).await?
+            }
+            None if keys.is_empty() && principal == *subject => EncryptionKey::generate()?,
+            None => return Err(EncryptionError::Locked(subject.to_string())),
+        };
+        if !matches!(keys.get(principal.as_str()), Some(Wrapped::Signed(_))) {
+            keys.insert(
+                principal.to_string(),
+                Wrapped::Signed(key.wrap(signer).await?),
+            );
+            record_keys(subject, (keys, version), env).await?;
+        }
+        self.insert(subject.clone(), key.clone());
+        Ok(key)
+    }
+
+    /// Wrap the unlocked key of `subject`'s space for `successor` as well,
+    /// so the space still unlocks once a rotation hands its authority on.
+    pub async fn hand_on<Env>(
+        &self,
+        subject: &Did,
+        successor: &Did,
+        env: &Env,
+    ) -> Result<(), EncryptionError>
+    where
+        Env: Provider<memory::Resolve> + Provider<memory::Publish> + ConditionalSync,
+    {
+        let key = self
+            .get(subject)
+            .ok_or_else(|| EncryptionError::Locked(subject.to_string()))?;
+        let (mut keys, version) = wrapped_keys(subject, env).await?;
+        if keys.contains_key(successor.as_str()) {
+            return Ok(());
+        }
+        keys.insert(
+            successor.to_string(),
+            Wrapped::Sealed(key.seal_for(successor)?),
+        );
+        record_keys(subject, (keys, version), env).await
+    }
+
+    /// Dispatch `capability` through `perform`, sealed for its subject's key
+    /// and with the output opened again. Without a key for the subject the
+    /// capability passes through as is.
+    ///
+    /// `perform` may be called more than once: a read that finds nothing
+    /// sealed goes on to look for what was stored in the clear, if the
+    /// space is [migrating](Self::migrate).
+    pub async fn conceal<Fx, Perform, Performed>(
+        &self,
+        capability: Capability<Fx>,
+        perform: Perform,
+    ) -> Fx::Output
+    where
+        Fx: Conceal,
+        <Fx as Constraint>::Capability: Ability,
+        Perform: Fn(Capability<Fx>) -> Performed,
+        Performed: Future<Output = Fx::Output>,
+    {
+        let Some(key) = self.get(capability.subject()) else {
+            return perform(capability).await;
+        };
+        let legacy = self.is_migrating(capability.subject());
+        let (mut capability, mut veil) = match Fx::conceal(capability, &key, legacy) {
+            Ok(concealed) => concealed,
+            Err(output) => return output,
+        };
+        loop {
+            match Fx::reveal(perform(capability).await, veil).await {
+                Revealed::Output(output) => return output,
+                Revealed::Dispatch(next, next_veil) => {
+                    capability = next;
+                    veil = next_veil;
+                }
+            }
+        }
+    }
+}
+
+/// What [`Conceal::reveal`] makes of the output of a sealed dispatch.
+pub enum Revealed<Fx: Conceal> {
+    /// The effect's final output.
+    Output(Fx::Output),
+    /// Dispatch this capability too, and reveal its output with this veil.
+    Dispatch(Capability<Fx>, Fx::Veil),
+}
+
+/// An effect [`Encrypted`] knows how to carry: how its payload is sealed on
+/// the way in and how its output is opened on the way out.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait Conceal: Effect + Sized {
+    /// What [`reveal`](Self::reveal) needs to open the output.
+    type Veil: ConditionalSend;
+
+    /// Seal the capability's payload under `key`. An `Err` is the effect's
+    /// final output, returned without dispatching at all. `legacy` tells
+    /// whether content stored in the clear still reads back (see
+    /// [`Keyring::migrate`]).
+    fn conceal(
+        capability: Capability<Self>,
+        key: &EncryptionKey,
+        legacy: bool,
+    ) -> Result<(Capability<Self>, Self::Veil), Self::Output>;
+
+    /// Open the output of the sealed capability, or ask for another
+    /// dispatch before there is one.
+    async fn reveal(output: Self::Output, veil: Self::Veil) -> Revealed<Self>;
+}
+
+/// Effects that carry nothing to seal pass through as is.
+macro_rules! transparent {
+    ($($effect:ty),* $(,)?) => {
+        $(
+            #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+            #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+            impl Conceal for $effect {
+                type Veil = ();
+
+                fn conceal(
+                    capability: Capability<Self>,
+                    _: &EncryptionKey,
+                    _: bool,
+                ) -> Result<(Capability<Self>, ()), Self::Output> {
+                    Ok((capability, ()))
+                }
+
+                async fn reveal(output: Self::Output, _: ()) -> Revealed<Self> {
+                    Revealed::Output(output)
+                }
+            }
+        )*
+    };
+}
+
+transparent!(
+    blob::List,
+    blob::Remove,
+    memory::Resolve,
+    memory::Publish,
+    memory::Retract,
+    memory::List,
+);
+
+fn archive_error(error: EncryptionError) -> ArchiveError {
+    ArchiveError::Storage(error.to_string())
+}
+
+fn blob_error(error: EncryptionError) -> BlobError {
+    BlobError::Storage(error.to_string())
+}
+
+/// `catalog`, redirected to the catalog its sealed blocks are kept in.
+fn sealed<Of: Ability>(
+    catalog: Constrained<archive::Catalog, Of>,
+) -> Constrained<archive::Catalog, Of> {
+    Constrained {
+        constraint: archive::Catalog::new(format!(
+            "{}{SEALED_CATALOG}",
+            catalog.constraint.catalog
+        )),
+        capability: catalog.capability,
+    }
+}
+
+fn seal_block(key: &EncryptionKey, block: &Buffer) -> Result<Buffer, ArchiveError> {
+    let sealed = key.seal(block.as_ref()).map_err(archive_error)?;
+    Ok(Buffer::addressed(&sealed, key.address(block.blake3_hash())))
+}
+
+fn open_block(
+    key: &EncryptionKey,
+    digest: &Blake3Hash,
+    block: Vec<u8>,
+) -> Result<Vec<u8>, ArchiveError> {
+    let opened = key.open(&block).map_err(archive_error)?;
+    if Blake3Hash::hash(&opened) != *digest {
+        return Err(ArchiveError::Storage(format!(
+            "sealed block does not open to its address {digest}"
+        )));
+    }
+    Ok(opened)
+}
+
+/// How an [`archive::Get`] is revealed: the sealed catalog is looked in
+/// first, the clear one after it while the space is migrating.
+pub enum BlockLookup {
+    /// Open the block found sealed, or else look `clear` up, if given.
+    Sealed {
+        /// The key the block is sealed under.
+        key: EncryptionKey,
+        /// The plaintext digest the block is read by.
+        digest: Blake3Hash,
+        /// The same read in the clear catalog, for a migrating space.
+        clear: Option<Capability<archive::Get>>,
+    },
+    /// Check that the block found in the clear hashes to this digest.
+    Clear(Blake3Hash),
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Conceal for archive::Get {
+    type Veil = BlockLookup;
+
+    fn conceal(
+        capability: Capability<Self>,
+        key: &EncryptionKey,
+        legacy: bool,
+    ) -> Result<(Capability<Self>, BlockLookup), Self::Output> {
+        let digest = capability.digest().clone();
+        let clear = legacy.then(|| capability.clone());
+        let Constrained { capability, .. } = capability.into_inner();
+        Ok((
+            Capability::new(Constrained {
+                constraint: archive::Get::new(key.address(&digest)),
+                capability: sealed(capability),
+            }),
+            BlockLookup::Sealed {
+                key: key.clone(),
+                digest,
+                clear,
+            },
+        ))
+    }
+
+    async fn reveal(output: Self::Output, veil: BlockLookup) -> Revealed<Self> {
+        match (output, veil) {
+            (
+                Ok(None),
+                BlockLookup::Sealed {
+                    digest,
+                    clear: Some(clear),
+                    ..
+                },
+            ) => Revealed::Dispatch(clear, BlockLookup::Clear(digest)),
+            (Ok(Some(block)), BlockLookup::Sealed { key, digest, .. }) => {
+                Revealed::Output(open_block(&key, &digest, block).map(Some))
+            }
+            (Ok(Some(block)), BlockLookup::Clear(digest)) if Blake3Hash::hash(&block) != digest => {
+                Revealed::Output(Err(ArchiveError::Storage(format!(
+                    "block does not hash to its address {digest}"
+                ))))
+            }
+            (output, _) => Revealed::Output(output),
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Conceal for archive::Put {
+    type Veil = ();
+
+    fn conceal(
+        capability: Capability<Self>,
+        key: &EncryptionKey,
+        _: bool,
+    ) -> Result<(Capability<Self>, ()), Self::Output> {
+        let Constrained {
+            constraint,
+            capability,
+        } = capability.into_inner();
+        let block = seal_block(key, &constraint.block).map_err(Err)?;
+        Ok((
+            Capability::new(Constrained {
+                constraint: archive::Put::new(block),
+                capability: sealed(capability),
+            }),
+            (),
+        ))
+    }
+
+    async fn reveal(output: Self::Output, _: ()) -> Revealed<Self> {
+        Revealed::Output(output)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Conceal for archive::Import {
+    type Veil = ();
+
+    fn conceal(
+        capability: Capability<Self>,
+        key: &EncryptionKey,
+        _: bool,
+    ) -> Result<(Capability<Self>, ()), Self::Output> {
+        let Constrained {
+            constraint,
+            capability,
+        } = capability.into_inner();
+        let blocks = constraint
+            .blocks
+            .iter()
+            .map(|block| seal_block(key, block))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Err)?;
+        Ok((
+            Capability::new(Constrained {
+                constraint: archive::Import { blocks },
+                capability: sealed(capability),
+            }),
+            (),
+        ))
+    }
+
+    async fn reveal(output: Self::Output, _: ()) -> Revealed<Self> {
+        Revealed::Output(output)
+    }
+}
+
+/// Removing a block removes its sealed copy, then whatever copy the clear
+/// catalog holds (the veil, until it has been dispatched too).
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Conceal for archive::Remove {
+    type Veil = Option<Capability<archive::Remove>>;
+
+    fn conceal(
+        capability: Capability<Self>,
+        key: &EncryptionKey,
+        _: bool,
+    ) -> Result<(Capability<Self>, Self::Veil), Self::Output> {
+        let clear = capability.clone();
+        let Constrained {
+            constraint,
+            capability,
+        } = capability.into_inner();
+        Ok((
+            Capability::new(Constrained {
+                constraint: archive::Remove::new(key.address(&constraint.digest)),
+                capability: sealed(capability),
+            }),
+            Some(clear),
+        ))
+    }
+
+    async fn reveal(output: Self::Output, clear: Self::Veil) -> Revealed<Self> {
+        match (output, clear) {
+            (Ok(()), Some(clear)) => Revealed::Dispatch(clear, None),
+            (output, _) => Revealed::Output(output),
+        }
+    }
+}
+
+/// A [`BlobSource`] yielding nothing: a zero-length range.
+struct EmptySource;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl BlobSource for EmptySource {
+    async fn next(&mut self) -> Result<Option<Vec<u8>>, BlobError> {
+        Ok(None)
+    }
+}
+
+/// How an [`OpenedSource`] treats the bytes of its inner source.
+enum Opening {
+    /// Reading the head of a whole blob to tell whether it is sealed, and
+    /// whether one that is not may be read.
+    Probing {
+        key: EncryptionKey,
+        digest: Blake3Hash,
+        legacy: bool,
+        head: Vec<u8>,
+    },
+    /// Opening sealed chunks.
+    Sealed(BlobOpener),
+    /// Passing a blob stored in the clear through, checked against its
+    /// digest once it has all been read.
+    Clear {
+        digest: Blake3Hash,
+        hasher: Box<Hasher>,
+    },
+    /// Nothing more to yield.
+    Finished,
+}
+
+/// A [`BlobSource`] opening the blob an inner source reads.
+struct OpenedSource {
+    source: BlobReader,
+    /// Bytes read ahead while telling whether the blob is sealed.
+    replay: Option<Vec<u8>>,
+    exhausted: bool,
+    opening: Opening,
+}
+
+impl OpenedSource {
+    fn reader(source: BlobReader, opening: Opening) -> BlobReader {
+        Box::new(Self {
+            source,
+            replay: None,
+            exhausted: false,
+            opening,
+        })
+    }
+
+    async fn pull(&mut self) -> Result<Option<Vec<u8>>, BlobError> {
+        if let Some(bytes) = self.replay.take() {
+            return Ok(Some(bytes));
+        }
+        if self.exhausted {
+            return Ok(None);
+        }
+        let next = self.source.next().await?;
+        self.exhausted = next.is_none();
+        Ok(next)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl BlobSource for OpenedSource {
+    async fn next(&mut self) -> Result<Option<Vec<u8>>, BlobError> {
+        loop {
+            if matches!(self.opening, Opening::Finished) {
+                return Ok(None);
+            }
+            let chunk = self.pull().await?;
+            match std::mem::replace(&mut self.opening, Opening::Finished) {
+                Opening::Probing {
+                    key,
+                    digest,
+                    legacy,
+                    mut head,
+                } => {
+                    if let Some(chunk) = chunk {
+                        head.extend(chunk);
+                        if head.len() as u64 <= SEALED_BLOB_HEAD {
+                            self.opening = Opening::Probing {
+                                key,
+                                digest,
+                                legacy,
+                                head,
+                            };
+                            continue;
+                        }
+                    }
+                    self.opening = if key.seals_blob(&head) {
+                        Opening::Sealed(BlobOpener::new(key))
+                    } else if legacy {
+                        Opening::Clear {
+                            digest,
+                            hasher: Box::new(Hasher::new()),
+                        }
+                    } else {
+                        return Err(unsealed_blob(&digest));
+                    };
+                    self.replay = Some(head);
+                }
+                Opening::Sealed(mut opener) => {
+                    let opened = match chunk {
+                        Some(chunk) => {
+                            let opened = opener.update(&chunk).map_err(blob_error)?;
+                            self.opening = Opening::Sealed(opener);
+                            opened
+                        }
+                        None => opener.finish().map_err(blob_error)?,
+                    };
+                    if !opened.is_empty() {
+                        return Ok(Some(opened));
+                    }
+                }
+                Opening::Clear { digest, mut hasher } => match chunk {
+                    Some(chunk) => {
+                        hasher.update(&chunk);
+                        self.opening = Opening::Clear { digest, hasher };
+                        if !chunk.is_empty() {
+                            return Ok(Some(chunk));
+                        }
+                    }
+                    None => {
+                        let actual = Blake3Hash::from(*hasher.finalize().as_bytes());
+                        if actual != digest {
+                            return Err(BlobError::DigestMismatch {
+                                expected: digest.to_string(),
+                                actual: actual.to_string(),
+                            });
+                        }
+                    }
+                },
+                Opening::Finished => {}
+            }
+        }
+    }
+}
+
+/// A [`BlobSink`] sealing what is written into an inner sink, whose
+/// [`finish`](BlobSink::finish) hash is therefore that of the sealed blob.
+struct SealedSink {
+    sink: BlobWriter,
+    sealer: BlobSealer,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl BlobSink for SealedSink {
+    async fn write_all(&mut self, bytes: &[u8]) -> Result<(), BlobError> {
+        let sealed = self.sealer.update(bytes).map_err(blob_error)?;
+        if sealed.is_empty() {
+            return Ok(());
+        }
+        self.sink.write_all(&sealed).await
+    }
+
+    async fn finish(self: Box<Self>) -> Result<Blake3Hash, BlobError> {
+        let SealedSink { mut sink, sealer } = *self;
+        let tail = sealer.finish().map_err(blob_error)?;
+        sink.write_all(&tail).await?;
+        sink.finish().await
+    }
+}
+
+/// How a [`blob::Read`] is revealed.
+///
+/// Whether a blob is sealed shows only in its bytes. A whole read tells
+/// from the head it streams anyway; a ranged read first reads the head on
+/// its own, then the range as it is stored. A blob that is not sealed is
+/// refused unless the space is migrating.
+pub enum BlobReading {
+    /// Open a whole blob if it turns out sealed, or else check it against
+    /// its digest.
+    Whole {
+        /// The key the blob may be sealed under.
+        key: EncryptionKey,
+        /// The digest the blob is read by.
+        digest: Blake3Hash,
+        /// Whether a blob stored in the clear may be read.
+        legacy: bool,
+    },
+    /// Tell from the blob's head how to read `range`.
+    Probe {
+        /// The key the blob may be sealed under.
+        key: EncryptionKey,
+        /// The ranged read asked for.
+        range: Capability<blob::Read>,
+        /// Whether a blob stored in the clear may be read.
+        legacy: bool,
+    },
+    /// Open a range of a sealed blob.
+    Sealed(BlobOpener),
+    /// Pass a range of a blob stored in the clear through.
+    Clear,
+}
+
+/// The read of `range`'s plaintext bytes from the blob as sealed, and the
+/// opener for what it reads.
+fn sealed_read(
+    range: Capability<blob::Read>,
+    key: &EncryptionKey,
+) -> (Capability<blob::Read>, BlobOpener) {
+    let Constrained {
+        constraint,
+        capability,
+    } = range.into_inner();
+    let ByteRange { offset, length } = constraint.range.unwrap_or(ByteRange {
+        offset: 0,
+        length: None,
+    });
+    let (sealed_offset, sealed_length, ..) = sealed_range(offset, length);
+    (
+        Capability::new(Constrained {
+            constraint: blob::Read::range(constraint.digest, sealed_offset, sealed_length),
+            capability,
+        }),
+        BlobOpener::ranged(key.clone(), offset, length),
+    )
+}
+
+/// The refusal of a blob an unlocked space finds stored in the clear.
+fn unsealed_blob(digest: &Blake3Hash) -> BlobError {
+    BlobError::Storage(format!(
+        "blob {digest} is not sealed and its space is not migrating"
+    ))
+}
+
+async fn read_to_end(mut source: BlobReader) -> Result<Vec<u8>, BlobError> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = source.next().await? {
+        bytes.extend(chunk);
+    }
+    Ok(bytes)
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Conceal for blob::Read {
+    type Veil = BlobReading;
+
+    fn conceal(
+        capability: Capability<Self>,
+        key: &EncryptionKey,
+        legacy: bool,
+    ) -> Result<(Capability<Self>, BlobReading), Self::Output> {
+        let digest = capability.digest().clone();
+        match capability.range() {
+            None => Ok((
+                capability,
+                BlobReading::Whole {
+                    key: key.clone(),
+                    digest,
+                    legacy,
+                },
+            )),
+            Some(ByteRange {
+                length: Some(0), ..
+            }) => Err(Ok(Box::new(EmptySource))),
+            Some(_) => {
+                let range = capability.clone();
+                let Constrained { capability, .. } = capability.into_inner();
+                Ok((
+                    Capability::new(Constrained {
+                        constraint: blob::Read::range(digest, 0, Some(SEALED_BLOB_HEAD)),
+                        capability,
+                    }),
+                    BlobReading::Probe {
+                        key: key.clone(),
+                        range,
+                        legacy,
+                    },
+                ))
+            }
+        }
+    }
+
+    async fn reveal(output: Self::Output, veil: BlobReading) -> Revealed<Self> {
+        match (output, veil) {
+            (
+                output,
+                BlobReading::Whole {
+                    key,
+                    digest,
+                    legacy,
+                },
+            ) => Revealed::Output(output.map(|source| {
+                OpenedSource::reader(
+                    source,
+                    Opening::Probing {
+                        key,
+                        digest,
+                        legacy,
+                        head: Vec::new(),
+                    },
+                )
+            })),
+            (Ok(head), BlobReading::Probe { key, range, legacy }) => {
+                match read_to_end(head).await {
+                    Ok(head) if key.seals_blob(&head) => {
+                        let (read, opener) = sealed_read(range, &key);
+                        Revealed::Dispatch(read, BlobReading::Sealed(opener))
+                    }
+                    Ok(_) if legacy => Revealed::Dispatch(range, BlobReading::Clear),
+                    Ok(_) => Revealed::Output(Err(unsealed_blob(range.digest()))),
+                    Err(error) => Revealed::Output(Err(error)),
+                }
+            }
+            (Ok(source), BlobReading::Sealed(opener)) => {
+                Revealed::Output(Ok(OpenedSource::reader(source, Opening::Sealed(opener))))
+            }
+            (output, _) => Revealed::Output(output),
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Conceal for blob::Write {
+    type Veil = EncryptionKey;
+
+    fn conceal(
+        capability: Capability<Self>,
+        key: &EncryptionKey,
+        _: bool,
+    ) -> Result<(Capability<Self>, EncryptionKey), Self::Output> {
+        Ok((capability, key.clone()))
+    }
+
+    async fn reveal(output: Self::Output, key: EncryptionKey) -> Revealed<Self> {
+        Revealed::Output(output.map(|sink| -> BlobWriter {
+            Box::new(SealedSink {
+                sink,
+                sealer: BlobSealer::new(key),
+            })
+        }))
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Conceal for blob::Import {
+    type Veil = EncryptionKey;
+
+    /// The digest already names the sealed blob; only the size changes.
+    /// Per-part hashes describe plaintext parts, so the sealed import is a
+    /// single part.
+    fn conceal(
+        capability: Capability<Self>,
+        key: &EncryptionKey,
+        _: bool,
+    ) -> Result<(Capability<Self>, EncryptionKey), Self::Output> {
+        let Constrained {
+            constraint,
+            capability,
+        } = capability.into_inner();
+        Ok((
+            Capability::new(Constrained {
+                constraint: blob::Import::new(constraint.digest, sealed_size(constraint.size)),
+                capability,
+            }),
+            key.clone(),
+        ))
+    }
+
+    async fn reveal(output: Self::Output, key: EncryptionKey) -> Revealed<Self> {
+        Revealed::Output(output.map(|sink| -> BlobWriter {
+            Box::new(SealedSink {
+                sink,
+                sealer: BlobSealer::new(key),
+            })
+        }))
+    }
+}
+
+/// A provider that encrypts the archive and blob effects of every space
+/// whose key is in its [`Keyring`] before handing them to `P`.
+#[derive(Clone)]
+pub struct Encrypted<P> {
+    inner: P,
+    keyring: Keyring,
+}
+
+impl<P> Encrypted<P> {
+    /// Wrap `inner`, sealing for the spaces unlocked in `keyring`.
+    pub fn new(inner: P, keyring: Keyring) -> Self {
+        Self { inner, keyring }
+    }
+
+    /// The keys this provider seals with.
+    pub fn keyring(&self) -> &Keyring {
+        &self.keyring
+    }
+
+    /// The wrapped provider, which sees sealed content.
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<P, Fx> Provider<Fx> for Encrypted<P>
+where
+    Fx: Conceal + ConditionalSend + 'static,
+    <Fx as Constraint>::Capability: Ability + ConditionalSend,
+    P: Provider<Fx> + ConditionalSync,
+{
+    async fn execute(&self, input: Capability<Fx>) -> Fx::Output {
+        self.keyring
+            .conceal(input, |capability| capability.perform(&self.inner))
+            .await
+    }
+}
+
+/// Listing a catalog lists its sealed counterpart and the clear catalog.
+/// An address is one-way (see [`EncryptionKey::address`]), so every block
+/// found sealed is opened to learn the digest it is listed by.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<P> Provider<archive::List> for Encrypted<P>
+where
+    P: Provider<archive::List> + Provider<archive::Get> + ConditionalSync,
+{
+    async fn execute(
+        &self,
+        input: Capability<archive::List>,
+    ) -> Result<Vec<Blake3Hash>, ArchiveError> {
+        let Some(key) = self.keyring.get(input.subject()) else {
+            return input.perform(&self.inner).await;
+        };
+        let clear = input.clone();
+        let Constrained {
+            constraint,
+            capability,
+        } = input.into_inner();
+        let catalog = sealed(capability);
+        let addresses = Capability::<archive::List>::new(Constrained {
+            constraint,
+            capability: catalog.clone(),
+        })
+        .perform(&self.inner)
+        .await?;
+
+        let mut listed = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let read = Capability::<archive::Get>::new(Constrained {
+                constraint: archive::Get::new(address.clone()),
+                capability: catalog.clone(),
+            });
+            // Removed since it was listed.
+            let Some(block) = read.perform(&self.inner).await? else {
+                continue;
+            };
+            let digest = Blake3Hash::hash(&key.open(&block).map_err(archive_error)?);
+            if key.address(&digest) != address {
+                return Err(ArchiveError::Storage(format!(
+                    "sealed block at {address} does not open to its address"
+                )));
+            }
+            listed.push(digest);
+        }
+        listed.extend(clear.perform(&self.inner).await?);
+        Ok(listed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Volatile;
+    use dialog_credentials::Ed25519Signer;
+    use dialog_effects::archive::prelude::{
+        ArchiveExt as _, ArchiveSubjectExt as _, CatalogExt as _,
+    };
+    use dialog_effects::blob::prelude::{ArchiveBlobExt as _, BlobExt as _};
+
+    async fn signer() -> Signer {
+        Signer::from(Ed25519Signer::generate().await.unwrap())
+    }
+
+    async fn unlocked() -> (Encrypted<Volatile>, Did) {
+        let signer = signer().await;
+        let store = Encrypted::new(Volatile::new(), Keyring::new());
+        store
+            .keyring()
+            .unlock(&signer.did(), &signer, store.inner())
+            .await
+            .unwrap();
+        (store, signer.did())
+    }
+
+    async fn write_blob(store: &Encrypted<Volatile>, subject: &Did, content: &[u8]) -> Blake3Hash {
+        let mut sink = subject
+            .clone()
+            .archive()
+            .blob()
+            .write()
+            .perform(store)
+            .await
+            .unwrap();
+        sink.write_all(content).await.unwrap();
+        sink.finish().await.unwrap()
+    }
+
+    async fn read_all(mut source: BlobReader) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        while let Some(chunk) = source.next().await.unwrap() {
+            bytes.extend(chunk);
+        }
+        bytes
+    }
+
+    #[dialog_common::test]
+    async fn it_stores_sealed_blocks_under_a_keyed_address() {
+        let (store, subject) = unlocked().await;
+        let key = store.keyring().get(&subject).unwrap();
+        let block = Buffer::from(b"a tree node".to_vec());
+        let digest = block.blake3_hash().clone();
+
+        subject
+            .clone()
+            .archive()
+            .catalog("index")
+            .put(block.clone())
+            .perform(&store)
+            .await
+            .unwrap();
+
+        let clear = subject
+            .clone()
+            .archive()
+            .catalog("index")
+            .get(digest.clone())
+            .perform(store.inner())
+            .await
+            .unwrap();
+        assert_eq!(clear, None);
+
+        let raw = subject
+            .clone()
+            .archive()
+            .catalog("index.sealed")
+            .get(key.address(&digest))
+            .perform(store.inner())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(key.open(&raw).unwrap(), block.as_ref());
+
+        let opened = subject
+            .archive()
+            .catalog("index")
+            .get(digest)
+            .perform(&store)
+            .await
+            .unwrap();
+        assert_eq!(opened, Some(block.as_ref().to_vec()));
+    }
+
+    #[dialog_common::test]
+    async fn it_rejects_a_block_that_opens_to_another_address() {
+        let (store, subject) = unlocked().await;
+        let key = store.keyring().get(&subject).unwrap();
+        let forged = key.seal(b"something else").unwrap();
+        let digest = Blake3Hash::hash(b"the real block");
+
+        subject
+            .clone()
+            .archive()
+            .catalog("index.sealed")
+            .put(Buffer::addressed(&forged, key.address(&digest)))
+            .perform(store.inner())
+            .await
+            .unwrap();
+
+        let result = subject
+            .archive()
+            .catalog("index")
+            .get(digest)
+            .perform(&store)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[dialog_common::test]
+    async fn it_lists_and_removes_blocks_by_their_digest() {
+        let signer = signer().await;
+        let subject = signer.did();
+        let store = Encrypted::new(Volatile::new(), Keyring::new());
+        let early = Buffer::from(b"stored in the clear".to_vec());
+        let late = Buffer::from(b"stored sealed".to_vec());
+        let catalog = || subject.clone().archive().catalog("index");
+
+        catalog().put(early.clone()).perform(&store).await.unwrap();
+        store
+            .keyring()
+            .unlock(&subject, &signer, store.inner())
+            .await
+            .unwrap();
+        catalog().put(late.clone()).perform(&store).await.unwrap();
+
+        let mut listed = catalog().list().perform(&store).await.unwrap();
+        listed.sort();
+        let mut expected = vec![early.blake3_hash().clone(), late.blake3_hash().clone()];
+        expected.sort();
+        assert_eq!(listed, expected);
+
+        for block in [&early, &late] {
+            catalog()
+                .remove(block.blake3_hash().clone())
+                .perform(&store)
+                .await
+                .unwrap();
+        }
+        assert!(catalog().list().perform(&store).await.unwrap().is_empty());
+        assert!(
+            subject
+                .clone()
+                .archive()
+                .catalog("index.sealed")
+                .list()
+                .perform(store.inner())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[dialog_common::test]
+    async fn it_reads_content_stored_before_the_space_was_unlocked() {
+        let signer = signer().await;
+        let subject = signer.did();
+        let store = Encrypted::new(Volatile::new(), Keyring::new());
+        let block = Buffer::from(b"an early node".to_vec());
+        let content: Vec<u8> = (0..100_000u32).map(|n| (n % 251) as u8).collect();
+
+        subject
+            .clone()
+            .archive()
+            .catalog("index")
+            .put(block.clone())
+            .perform(&store)
+            .await
+            .unwrap();
+        let digest = write_blob(&store, &subject, &content).await;
+        assert_eq!(digest, Blake3Hash::hash(&content));
+
+        store
+            .keyring()
+            .unlock(&subject, &signer, store.inner())
+            .await
+            .unwrap();
+
+        // Unlocked, the space reads sealed content only.
+        let refused = subject
+            .clone()
+            .archive()
+            .catalog("index")
+            .get(block.blake3_hash().clone())
+            .perform(&store)
+            .await
+            .unwrap();
+        assert_eq!(refused, None);
+        let mut whole = subject
+            .clone()
+            .archive()
+            .blob()
+            .read(digest.clone())
+            .perform(&store)
+            .await
+            .unwrap();
+        assert!(whole.next().await.is_err());
+        assert!(
+            subject
+                .clone()
+                .archive()
+                .blob()
+                .invoke(blob::Read::range(digest.clone(), 70_000, Some(100)))
+                .perform(&store)
+                .await
+                .is_err()
+        );
+
+        store.keyring().migrate(subject.clone());
+        let read = subject
+            .clone()
+            .archive()
+            .catalog("index")
+            .get(block.blake3_hash().clone())
+            .perform(&store)
+            .await
+            .unwrap();
+        assert_eq!(read, Some(block.as_ref().to_vec()));
+
+        let whole = read_all(
+            subject
+                .clone()
+                .archive()
+                .blob()
+                .read(digest.clone())
+                .perform(&store)
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(whole, content);
+
+        let ranged = read_all(
+            subject
+                .archive()
+                .blob()
+                .invoke(blob::Read::range(digest, 70_000, Some(100)))
+                .perform(&store)
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(ranged, content[70_000..70_100]);
+    }
+
+    #[dialog_common::test]
+    async fn it_keeps_a_space_key_across_a_rotation() {
+        let original = signer().await;
+        let successor = signer().await;
+        let subject = original.did();
+        let env = Volatile::new();
+
+        let keyring = Keyring::new();
+        let key = keyring.unlock(&subject, &original, &env).await.unwrap();
+        keyring
+            .hand_on(&subject, &successor.did(), &env)
+            .await
+            .unwrap();
+
+        for _ in 0..2 {
+            let keyring = Keyring::new();
+            assert_eq!(
+                keyring.unlock(&subject, &successor, &env).await.unwrap(),
+                key
+            );
+        }
+        assert_eq!(
+            Keyring::new()
+                .unlock(&subject, &original, &env)
+                .await
+                .unwrap(),
+            key
+        );
+        assert!(matches!(
+            Keyring::new().unlock(&subject, &signer().await, &env).await,
+            Err(EncryptionError::Locked(_))
+        ));
+    }
+
+    #[dialog_common::test]
+    async fn it_passes_subjects_without_a_key_through() {
+        let store = Encrypted::new(Volatile::new(), Keyring::new());
+        let subject = Ed25519Signer::generate().await.unwrap().did();
+        let block = Buffer::from(b"plain".to_vec());
+
+        subject
+            .clone()
+            .archive()
+            .catalog("index")
+            .put(block.clone())
+            .perform(&store)
+            .await
+            .unwrap();
+
+        let raw = subject
+            .archive()
+            .catalog("index")
+            .get(block.blake3_hash().clone())
+            .perform(store.inner())
+            .await
+            .unwrap();
+        assert_eq!(raw, Some(block.as_ref().to_vec()));
+    }
+
+    #[dialog_common::test]
+    async fn it_round_trips_blobs_and_ranges() {
+        let (store, subject) = unlocked().await;
+        let content: Vec<u8> = (0..200_000u32).map(|n| (n % 251) as u8).collect();
+
+        let mut sink = subject
+            .clone()
+            .archive()
+            .blob()
+            .write()
+            .perform(&store)
+            .await
+            .unwrap();
+        for part in content.chunks(10_000) {
+            sink.write_all(part).await.unwrap();
+        }
+        let digest = sink.finish().await.unwrap();
+        assert_ne!(digest, Blake3Hash::hash(&content));
+
+        let raw = read_all(
+            subject
+                .clone()
+                .archive()
+                .blob()
+                .read(digest.clone())
+                .perform(store.inner())
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(raw.len() as u64, sealed_size(content.len() as u64));
+        assert_eq!(Blake3Hash::hash(&raw), digest);
+
+        let whole = read_all(
+            subject
+                .clone()
+                .archive()
+                .blob()
+                .read(digest.clone())
+                .perform(&store)
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(whole, content);
+
+        for (offset, length) in [
+            (0, Some(10)),
+            (65_530, Some(20)),
+            (65_536, Some(65_536)),
+            (150_000, None),
+            (199_990, Some(100)),
+            (300_000, None),
+        ] {
+            let ranged = read_all(
+                subject
+                    .clone()
+                    .archive()
+                    .blob()
+                    .invoke(blob::Read::range(digest.clone(), offset, length))
+                    .perform(&store)
+                    .await
+                    .unwrap(),
+            )
+            .await;
+            let start = (offset as usize).min(content.len());
+            let end = length.map_or(content.len(), |length| {
+                (start + length as usize).min(content.len())
+            });
+            assert_eq!(ranged, content[start..end], "range {offset}+{length:?}");
+        }
+    }
+
+    #[dialog_common::test]
+    async fn it_imports_a_blob_to_its_sealed_digest() {
+        let (source, subject) = unlocked().await;
+        let target = Encrypted::new(Volatile::new(), source.keyring().clone());
+        let content = vec![7u8; 70_000];
+
+        let mut sink = subject
+            .clone()
+            .archive()
+            .blob()
+            .write()
+            .perform(&source)
+            .await
+            .unwrap();
+        sink.write_all(&content).await.unwrap();
+        let digest = sink.finish().await.unwrap();
+
+        let mut sink = subject
+            .clone()
+            .archive()
+            .blob()
+            .import(digest.clone(), content.len() as u64)
+            .perform(&target)
+            .await
+            .unwrap();
+        sink.write_all(&content).await.unwrap();
+        assert_eq!(sink.finish().await.unwrap(), digest);
+
+        let mut sink = subject
+            .archive()
+            .blob()
+            .import(digest, 3)
+            .perform(&target)
+            .await
+            .unwrap();
+        sink.write_all(b"lie").await.unwrap();
+        assert!(matches!(
+            sink.finish().await,
+            Err(BlobError::DigestMismatch { .. })
+        ));
+    }
+}
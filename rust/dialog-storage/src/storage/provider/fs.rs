@@ -13,6 +13,7 @@
 //! ```text
 //! {space_root}/
 //!   archive/{catalog}/{base58(digest)}
+//!   blob/{base58(digest)}
 //!   memory/{space}/{cell}
 //!   credential/key/{address}
 //!   certificate/{audience}/{subject}/{issuer}.{hash}
@@ -60,6 +61,8 @@ pub use error::FileSystemError;
 
 pub use backend::FileWriter;
 
+use base58::FromBase58;
+use dialog_common::Blake3Hash;
 use url::Url;
 
 /// A streamed byte source over a file, yielding owned chunks. Boxed so both
@@ -314,6 +317,28 @@ impl FileSystemHandle {
     pub async fn exists(&self) -> bool {
         backend::exists(self).await
     }
+
+    /// Check if this location exists and is a directory.
+    pub async fn is_directory(&self) -> bool {
+        backend::is_directory(self).await
+    }
+
+    /// List the digests of the content-addressed files in this directory.
+    ///
+    /// Only names that decode as a base58 blake3 digest are returned, so
+    /// staging files from in-flight writes (and anything else that is not a
+    /// committed entry) are skipped.
+    pub async fn list_digests(&self) -> Result<Vec<Blake3Hash>, FileSystemError> {
+        Ok(self
+            .list()
+            .await?
+            .into_iter()
+            .filter_map(|name| {
+                let bytes = name.from_base58().ok()?;
+                Blake3Hash::try_from(bytes).ok()
+            })
+            .collect())
+    }
 }
 
 #[cfg(all(test, not(target_arch = "wasm32")))]
@@ -5,8 +5,9 @@
 use super::{FileSystem, FileSystemError, FileSystemHandle};
 use base58::ToBase58;
 use dialog_capability::{Capability, Provider};
-use dialog_effects::archive::prelude::{GetExt, ImportExt, PutExt};
-use dialog_effects::archive::{ArchiveError, Get, Import, Put};
+use dialog_common::Blake3Hash;
+use dialog_effects::archive::prelude::{GetExt, ImportExt, ListExt, PutExt, RemoveExt};
+use dialog_effects::archive::{ArchiveError, Get, Import, List, Put, Remove};
 use futures_util::future::try_join_all;
 
 const ARCHIVE: &str = "archive";
@@ -99,12 +100,39 @@ impl Provider<Import> for FileSystem {
     }
 }
 
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Provider<List> for FileSystem {
+    async fn execute(&self, effect: Capability<List>) -> Result<Vec<Blake3Hash>, ArchiveError> {
+        // `write_atomic` stages `{digest}.{suffix}.tmp` beside the final
+        // name; those are not blocks yet and do not decode as digests.
+        Ok(self
+            .archive()?
+            .resolve(effect.catalog())?
+            .list_digests()
+            .await?)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Provider<Remove> for FileSystem {
+    async fn execute(&self, effect: Capability<Remove>) -> Result<(), ArchiveError> {
+        let handle = self
+            .archive()?
+            .resolve(effect.catalog())?
+            .resolve(&effect.digest().as_bytes().to_base58())?;
+
+        Ok(handle.remove().await?)
+    }
+}
+
 #[cfg(all(test, not(target_arch = "wasm32")))]
 mod tests {
     use super::*;
     use crate::helpers::{unique_did, unique_name};
     use crate::resource::Resource;
-    use dialog_common::{Blake3Hash, Buffer};
+    use dialog_common::Buffer;
     use dialog_effects::prelude::*;
     use dialog_effects::storage::{Directory, Location as StorageLocation};
 
@@ -354,4 +382,66 @@ mod tests {
 
         Ok(())
     }
+
+    #[dialog_common::test]
+    async fn it_lists_and_removes_blocks() -> anyhow::Result<()> {
+        let location = StorageLocation::new(
+            Directory::Temp,
+            unique_name("fs-it_lists_and_removes_blocks"),
+        );
+        let provider = FileSystem::open(&location).await?;
+        let subject = unique_did().await;
+        let catalog = subject.clone().archive().catalog("index");
+
+        // Nothing stored yet: the catalog directory does not even exist.
+        assert!(catalog.clone().list().perform(&provider).await?.is_empty());
+
+        let blocks: Vec<Buffer> = (0..3u8).map(|i| Buffer::from(vec![i; 32])).collect();
+        catalog
+            .clone()
+            .import(blocks.clone())
+            .perform(&provider)
+            .await?;
+        // A stray staging file left by an interrupted write is not a block.
+        provider
+            .archive()?
+            .resolve("index")?
+            .resolve("interrupted.1.tmp")?
+            .write(b"partial")
+            .await?;
+
+        let listed = catalog.clone().list().perform(&provider).await?;
+        assert_eq!(listed.len(), 3);
+        assert!(
+            blocks
+                .iter()
+                .all(|block| listed.contains(block.blake3_hash()))
+        );
+
+        let removed = blocks[1].blake3_hash().clone();
+        catalog
+            .clone()
+            .remove(removed.clone())
+            .perform(&provider)
+            .await?;
+        catalog
+            .clone()
+            .remove(removed.clone())
+            .perform(&provider)
+            .await?;
+
+        assert!(
+            catalog
+                .clone()
+                .get(removed.clone())
+                .perform(&provider)
+                .await?
+                .is_none()
+        );
+        let listed = catalog.list().perform(&provider).await?;
+        assert_eq!(listed.len(), 2);
+        assert!(!listed.contains(&removed));
+
+        Ok(())
+    }
 }
@@ -15,9 +15,9 @@ use base58::ToBase58;
 use blake3::Hasher;
 use dialog_capability::{Capability, Provider};
 use dialog_common::Blake3Hash;
-use dialog_effects::blob::prelude::{BlobImportExt as _, BlobReadExt as _};
+use dialog_effects::blob::prelude::{BlobImportExt as _, BlobReadExt as _, BlobRemoveExt as _};
 use dialog_effects::blob::{
-    BlobError, BlobReader, BlobSink, BlobSource, BlobWriter, Import, Read, Write,
+    BlobError, BlobReader, BlobSink, BlobSource, BlobWriter, Import, List, Read, Remove, Write,
 };
 use futures_util::StreamExt;
 
@@ -165,6 +165,28 @@ impl Provider<Import> for FileSystem {
     }
 }
 
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider<List> for FileSystem {
+    async fn execute(&self, _effect: Capability<List>) -> Result<Vec<Blake3Hash>, BlobError> {
+        // In-flight sinks live under the `_staging` base name, which never
+        // decodes as a digest, so only committed blobs are listed.
+        Ok(self.blob()?.list_digests().await?)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider<Remove> for FileSystem {
+    async fn execute(&self, effect: Capability<Remove>) -> Result<(), BlobError> {
+        Ok(self
+            .blob()?
+            .resolve(&blob_key(effect.digest()))?
+            .remove()
+            .await?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // On wasm these run in a worker against OPFS, exercising the true-streaming
@@ -291,4 +313,69 @@ mod tests {
             Err(BlobError::DigestMismatch { .. })
         ));
     }
+
+    #[dialog_common::test]
+    async fn it_lists_and_removes_committed_blobs() {
+        let fs = test_space("blob-list-remove").await;
+
+        let mut sink = subject()
+            .archive()
+            .blob()
+            .write()
+            .perform(&fs)
+            .await
+            .unwrap();
+        sink.write_all(b"kept").await.unwrap();
+        let kept = sink.finish().await.unwrap();
+
+        let mut sink = subject()
+            .archive()
+            .blob()
+            .write()
+            .perform(&fs)
+            .await
+            .unwrap();
+        sink.write_all(b"removed").await.unwrap();
+        let removed = sink.finish().await.unwrap();
+
+        // A sink still being written is not listed.
+        let mut pending = subject()
+            .archive()
+            .blob()
+            .write()
+            .perform(&fs)
+            .await
+            .unwrap();
+        pending.write_all(b"in flight").await.unwrap();
+
+        let listed = subject()
+            .archive()
+            .blob()
+            .list()
+            .perform(&fs)
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 2);
+        assert!(listed.contains(&kept) && listed.contains(&removed));
+
+        subject()
+            .archive()
+            .blob()
+            .remove(removed.clone())
+            .perform(&fs)
+            .await
+            .unwrap();
+        let missing = subject().archive().blob().read(removed).perform(&fs).await;
+        assert!(matches!(missing, Err(BlobError::NotFound(_))));
+
+        let listed = subject()
+            .archive()
+            .blob()
+            .list()
+            .perform(&fs)
+            .await
+            .unwrap();
+        assert_eq!(listed, vec![kept]);
+        pending.finish().await.unwrap();
+    }
 }
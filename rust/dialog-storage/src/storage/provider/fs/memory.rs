@@ -9,8 +9,8 @@
 use super::{FileSystem, FileSystemError, FileSystemHandle};
 use dialog_capability::{Capability, Provider};
 use dialog_common::Blake3Hash;
-use dialog_effects::memory::prelude::{PublishExt, ResolveExt, RetractExt};
-use dialog_effects::memory::{Edition, MemoryError, Publish, Resolve, Retract, Version};
+use dialog_effects::memory::prelude::{MemoryListExt, PublishExt, ResolveExt, RetractExt};
+use dialog_effects::memory::{Edition, List, MemoryError, Publish, Resolve, Retract, Version};
 
 const MEMORY: &str = "memory";
 
@@ -190,6 +190,50 @@ impl Provider<Retract> for FileSystem {
     }
 }
 
+/// Whether a file in the memory tree is CAS bookkeeping rather than a cell:
+/// the native `{cell}.lock` lock file, or a `{cell}.{suffix}.tmp` staged by
+/// an in-flight atomic write.
+fn is_bookkeeping(name: &str) -> bool {
+    name.ends_with(".lock") || name.ends_with(".tmp")
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Provider<List> for FileSystem {
+    async fn execute(&self, effect: Capability<List>) -> Result<Vec<String>, MemoryError> {
+        let prefix = effect.prefix();
+
+        // Spaces and cells are nested directories (`branch/main` is two
+        // levels), so walk the whole tree. Every file is a cell and its
+        // address is its path relative to the memory root.
+        let mut addresses = Vec::new();
+        let mut pending = vec![(self.memory()?, String::new())];
+        while let Some((directory, path)) = pending.pop() {
+            for name in directory.list().await? {
+                if is_bookkeeping(&name) {
+                    continue;
+                }
+                let address = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{path}/{name}")
+                };
+                // Skip subtrees that cannot contain a match.
+                if !address.starts_with(prefix) && !prefix.starts_with(&address) {
+                    continue;
+                }
+                let entry = directory.resolve(&name)?;
+                if entry.is_directory().await {
+                    pending.push((entry, address));
+                } else if address.starts_with(prefix) {
+                    addresses.push(address);
+                }
+            }
+        }
+        Ok(addresses)
+    }
+}
+
 #[cfg(all(test, not(target_arch = "wasm32")))]
 mod tests {
     use super::*;
@@ -766,4 +810,71 @@ mod tests {
         assert!(!v2.is_empty());
         Ok(())
     }
+
+    #[dialog_common::test]
+    async fn it_lists_cells_across_nested_spaces() -> anyhow::Result<()> {
+        let location = StorageLocation::new(
+            Directory::Temp,
+            unique_name("fs-it_lists_cells_across_nested_spaces"),
+        );
+        let provider = FileSystem::open(&location).await?;
+        let did = unique_did().await;
+
+        for (space, cell) in [
+            ("branch/main", "revision"),
+            ("branch/main", "upstream"),
+            ("branch/feature/x", "revision"),
+            ("remote/origin", "branch/main/revision"),
+        ] {
+            did.clone()
+                .memory()
+                .space(space)
+                .cell(cell)
+                .publish(b"content".to_vec(), None)
+                .perform(&provider)
+                .await?;
+        }
+
+        // Lock files left beside a cell are not cells.
+        let cell_path: std::path::PathBuf = provider
+            .memory()?
+            .resolve("branch/main")?
+            .cell("revision")?
+            .try_into()?;
+        std::fs::write(cell_path.with_extension("lock"), b"999999999")?;
+
+        let mut branches = did
+            .clone()
+            .memory()
+            .list("branch/")
+            .perform(&provider)
+            .await?;
+        branches.sort();
+        assert_eq!(
+            branches,
+            vec![
+                "branch/feature/x/revision",
+                "branch/main/revision",
+                "branch/main/upstream",
+            ]
+        );
+
+        let remotes = did
+            .clone()
+            .memory()
+            .list("remote/")
+            .perform(&provider)
+            .await?;
+        assert_eq!(remotes, vec!["remote/origin/branch/main/revision"]);
+
+        // A prefix nothing lives under lists nothing.
+        let empty = did
+            .memory()
+            .list("branch/missing")
+            .perform(&provider)
+            .await?;
+        assert!(empty.is_empty());
+
+        Ok(())
+    }
 }
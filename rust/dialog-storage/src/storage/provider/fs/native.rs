@@ -333,6 +333,16 @@ pub(super) async fn exists(handle: &FileSystemHandle) -> bool {
     path.exists()
 }
 
+pub(super) async fn is_directory(handle: &FileSystemHandle) -> bool {
+    let Ok(path) = PathBuf::try_from(handle) else {
+        return false;
+    };
+    fs::metadata(&path)
+        .await
+        .map(|metadata| metadata.is_dir())
+        .unwrap_or(false)
+}
+
 /// Open a streaming reader over the file, starting at `offset` and yielding at
 /// most `len` bytes (all of it when `len` is `None`). Chunks are owned `Vec`s
 /// so nothing buffers the whole file.
@@ -935,6 +935,18 @@ pub(super) async fn list(handle: &FileSystemHandle) -> Result<Vec<String>, FileS
     Ok(names)
 }
 
+pub(super) async fn is_directory(handle: &FileSystemHandle) -> bool {
+    let Ok(segments) = handle.segments() else {
+        return false;
+    };
+    // Resolving a file as a directory rejects with a TypeMismatchError, which
+    // surfaces here as an error rather than a handle.
+    matches!(
+        navigate_directory(&handle.root().handle, &segments, false).await,
+        Ok(Some(_))
+    )
+}
+
 pub(super) async fn exists(handle: &FileSystemHandle) -> bool {
     let Ok(Some((parent, name))) = handle.navigate_parent(false).await else {
         // Either the parent directory is missing, or this is the root handle.
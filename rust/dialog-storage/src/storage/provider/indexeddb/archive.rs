@@ -2,10 +2,11 @@
 
 use super::{IndexedDb, to_uint8array};
 use async_trait::async_trait;
-use base58::ToBase58;
+use base58::{FromBase58, ToBase58};
 use dialog_capability::{Capability, Provider};
-use dialog_effects::archive::prelude::{GetExt, ImportExt, PutExt};
-use dialog_effects::archive::{ArchiveError, Get, Import, Put};
+use dialog_common::Blake3Hash;
+use dialog_effects::archive::prelude::{GetExt, ImportExt, ListExt, PutExt, RemoveExt};
+use dialog_effects::archive::{ArchiveError, Get, Import, List, Put, Remove};
 use js_sys::Uint8Array;
 use wasm_bindgen::{JsCast, JsValue};
 
@@ -112,13 +113,55 @@ impl Provider<Import> for IndexedDb {
     }
 }
 
+#[async_trait(?Send)]
+impl Provider<List> for IndexedDb {
+    async fn execute(&self, effect: Capability<List>) -> Result<Vec<Blake3Hash>, ArchiveError> {
+        let store_name = format!("{ARCHIVE}/{}", effect.catalog());
+
+        let store = self.store(&store_name).await?;
+        let keys = store
+            .query(|object_store| async move {
+                object_store
+                    .get_all_keys(None, None)
+                    .await
+                    .map_err(storage_error)
+            })
+            .await?;
+
+        keys.into_iter()
+            .map(|key| {
+                key.as_string()
+                    .and_then(|key| key.from_base58().ok())
+                    .and_then(|bytes| Blake3Hash::try_from(bytes).ok())
+                    .ok_or_else(|| ArchiveError::Storage(format!("Invalid block key: {key:?}")))
+            })
+            .collect()
+    }
+}
+
+#[async_trait(?Send)]
+impl Provider<Remove> for IndexedDb {
+    async fn execute(&self, effect: Capability<Remove>) -> Result<(), ArchiveError> {
+        let store_name = format!("{ARCHIVE}/{}", effect.catalog());
+        let key = JsValue::from_str(&effect.digest().as_bytes().to_base58());
+
+        let store = self.store(&store_name).await?;
+        store
+            .transact(|object_store| async move {
+                object_store.delete(key).await.map_err(storage_error)?;
+                Ok(())
+            })
+            .await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
 
     use super::*;
     use crate::helpers::{unique_name, unique_subject};
-    use dialog_common::{Blake3Hash, Buffer};
+    use dialog_common::Buffer;
     use dialog_effects::archive::{Archive, Catalog};
 
     #[dialog_common::test]
@@ -299,4 +342,48 @@ mod tests {
 
         Ok(())
     }
+
+    #[dialog_common::test]
+    async fn it_lists_and_removes_blocks() -> anyhow::Result<()> {
+        let provider = IndexedDb::connect(unique_name("archive-list-remove")).await?;
+        let subject = unique_subject("archive-list-remove");
+        let catalog = subject.attenuate(Archive).attenuate(Catalog::new("index"));
+
+        let blocks: Vec<Buffer> = (0..3u8).map(|i| Buffer::from(vec![i; 16])).collect();
+        catalog
+            .clone()
+            .invoke(Import::new(blocks.clone()))
+            .perform(&provider)
+            .await?;
+
+        let listed = catalog.clone().invoke(List).perform(&provider).await?;
+        assert_eq!(listed.len(), 3);
+        assert!(
+            blocks
+                .iter()
+                .all(|block| listed.contains(block.blake3_hash()))
+        );
+
+        let removed = blocks[0].blake3_hash().clone();
+        for _ in 0..2 {
+            catalog
+                .clone()
+                .invoke(Remove::new(removed.clone()))
+                .perform(&provider)
+                .await?;
+        }
+        assert!(
+            catalog
+                .clone()
+                .invoke(Get::new(removed.clone()))
+                .perform(&provider)
+                .await?
+                .is_none()
+        );
+        let listed = catalog.invoke(List).perform(&provider).await?;
+        assert_eq!(listed.len(), 2);
+        assert!(!listed.contains(&removed));
+
+        Ok(())
+    }
 }
@@ -7,9 +7,10 @@ use super::{IndexedDb, to_uint8array};
 use async_trait::async_trait;
 use dialog_capability::{Capability, Provider};
 use dialog_common::Blake3Hash;
-use dialog_effects::memory::prelude::{PublishExt, ResolveExt, RetractExt};
-use dialog_effects::memory::{Edition, MemoryError, Publish, Resolve, Retract, Version};
+use dialog_effects::memory::prelude::{MemoryListExt, PublishExt, ResolveExt, RetractExt};
+use dialog_effects::memory::{Edition, List, MemoryError, Publish, Resolve, Retract, Version};
 use js_sys::Uint8Array;
+use rexie::KeyRange;
 use wasm_bindgen::{JsCast, JsValue};
 
 /// The single object store used for all memory operations.
@@ -184,6 +185,33 @@ impl Provider<Retract> for IndexedDb {
     }
 }
 
+#[async_trait(?Send)]
+impl Provider<List> for IndexedDb {
+    async fn execute(&self, effect: Capability<List>) -> Result<Vec<String>, MemoryError> {
+        let prefix = effect.prefix();
+        // Keys are `{space}/{cell}` strings, so a prefix is a key range.
+        let range = if prefix.is_empty() {
+            None
+        } else {
+            let lower = JsValue::from_str(prefix);
+            let upper = JsValue::from_str(&format!("{prefix}\u{ffff}"));
+            Some(KeyRange::bound(&lower, &upper, None, None).map_err(storage_error)?)
+        };
+
+        let store = self.store(MEMORY).await?;
+        let keys = store
+            .query(|object_store| async move {
+                object_store
+                    .get_all_keys(range, None)
+                    .await
+                    .map_err(storage_error)
+            })
+            .await?;
+
+        Ok(keys.into_iter().filter_map(|key| key.as_string()).collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
@@ -543,4 +571,46 @@ mod tests {
 
         Ok(())
     }
+
+    #[dialog_common::test]
+    async fn it_lists_cells_by_address_prefix() -> anyhow::Result<()> {
+        let provider = IndexedDb::connect(unique_name("mem")).await?;
+        let subject = unique_subject("memory-list");
+
+        for (space, cell) in [
+            ("branch/main", "revision"),
+            ("branch/feature/x", "revision"),
+            ("remote/origin", "branch/main/revision"),
+        ] {
+            subject
+                .clone()
+                .attenuate(Memory)
+                .attenuate(Space::new(space))
+                .attenuate(Cell::new(cell))
+                .invoke(Publish::new(b"content".to_vec(), None))
+                .perform(&provider)
+                .await?;
+        }
+
+        let mut branches = subject
+            .clone()
+            .attenuate(Memory)
+            .invoke(List::new("branch/"))
+            .perform(&provider)
+            .await?;
+        branches.sort();
+        assert_eq!(
+            branches,
+            vec!["branch/feature/x/revision", "branch/main/revision"]
+        );
+
+        let everything = subject
+            .attenuate(Memory)
+            .invoke(List::new(""))
+            .perform(&provider)
+            .await?;
+        assert_eq!(everything.len(), 3);
+
+        Ok(())
+    }
 }
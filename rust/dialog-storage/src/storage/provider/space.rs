@@ -60,9 +60,12 @@ pub trait SpaceProvider:
     Provider<archive::Get>
     + Provider<archive::Put>
     + Provider<archive::Import>
+    + Provider<archive::List>
+    + Provider<archive::Remove>
     + Provider<memory::Resolve>
     + Provider<memory::Publish>
     + Provider<memory::Retract>
+    + Provider<memory::List>
     + Provider<credential::Load<Credential>>
     + Provider<credential::Save<Credential>>
     + Provider<credential::Load<Secret>>
@@ -79,9 +82,12 @@ impl<T> SpaceProvider for T where
     T: Provider<archive::Get>
         + Provider<archive::Put>
         + Provider<archive::Import>
+        + Provider<archive::List>
+        + Provider<archive::Remove>
         + Provider<memory::Resolve>
         + Provider<memory::Publish>
         + Provider<memory::Retract>
+        + Provider<memory::List>
         + Provider<credential::Load<Credential>>
         + Provider<credential::Save<Credential>>
         + Provider<credential::Load<Secret>>
@@ -98,11 +104,17 @@ impl<T> SpaceProvider for T where
 #[derive(Clone, dialog_capability::Provider)]
 pub struct Space<A, M, C, D, B> {
     /// Archive provider (content-addressed blocks).
-    #[provide(archive::Get, archive::Put, archive::Import)]
+    #[provide(
+        archive::Get,
+        archive::Put,
+        archive::Import,
+        archive::List,
+        archive::Remove
+    )]
     pub archive: A,
 
     /// Memory provider.
-    #[provide(memory::Resolve, memory::Publish, memory::Retract)]
+    #[provide(memory::Resolve, memory::Publish, memory::Retract, memory::List)]
     pub memory: M,
 
     /// Credential provider.
@@ -126,7 +138,7 @@ pub struct Space<A, M, C, D, B> {
     /// an OPFS-backed `FileSystem` for streaming throughput. The generated
     /// impls are bound-conditional, so a provider without blob support still
     /// composes as long as nothing performs a blob effect on it.
-    #[provide(blob::Read, blob::Write, blob::Import)]
+    #[provide(blob::Read, blob::Write, blob::Import, blob::List, blob::Remove)]
     pub blob: B,
 }
 
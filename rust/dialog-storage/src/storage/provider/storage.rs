@@ -38,12 +38,17 @@ pub struct Storage<S: Clone> {
         archive::Get,
         archive::Put,
         archive::Import,
+        archive::List,
+        archive::Remove,
         blob::Read,
         blob::Write,
         blob::Import,
+        blob::List,
+        blob::Remove,
         memory::Resolve,
         memory::Publish,
         memory::Retract,
+        memory::List,
         credential::Load<Credential>,
         credential::Save<Credential>,
         credential::Load<Secret>,
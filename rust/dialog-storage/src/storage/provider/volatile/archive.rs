@@ -2,10 +2,11 @@
 
 use super::{ArchiveKey, Volatile};
 use async_trait::async_trait;
-use base58::ToBase58;
+use base58::{FromBase58, ToBase58};
 use dialog_capability::{Capability, Provider};
-use dialog_effects::archive::prelude::{GetExt, ImportExt, PutExt};
-use dialog_effects::archive::{ArchiveError, Get, Import, Put};
+use dialog_common::Blake3Hash;
+use dialog_effects::archive::prelude::{GetExt, ImportExt, ListExt, PutExt, RemoveExt};
+use dialog_effects::archive::{ArchiveError, Get, Import, List, Put, Remove};
 
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
@@ -91,11 +92,55 @@ impl Provider<Import> for Volatile {
     }
 }
 
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider<List> for Volatile {
+    async fn execute(&self, effect: Capability<List>) -> Result<Vec<Blake3Hash>, ArchiveError> {
+        let subject = effect.subject().into();
+        let catalog = effect.catalog();
+
+        let sessions = self.sessions.read();
+        let Some(session) = sessions.get(&subject) else {
+            return Ok(Vec::new());
+        };
+        session
+            .archive
+            .keys()
+            .filter(|(name, _)| name == catalog)
+            .map(|(_, digest)| {
+                digest
+                    .from_base58()
+                    .ok()
+                    .and_then(|bytes| Blake3Hash::try_from(bytes).ok())
+                    .ok_or_else(|| ArchiveError::Storage(format!("Invalid block key: {digest}")))
+            })
+            .collect()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider<Remove> for Volatile {
+    async fn execute(&self, effect: Capability<Remove>) -> Result<(), ArchiveError> {
+        let subject = effect.subject().into();
+        let key: ArchiveKey = (
+            effect.catalog().to_string(),
+            effect.digest().as_bytes().to_base58(),
+        );
+
+        if let Some(session) = self.sessions.write().get_mut(&subject) {
+            session.archive.remove(&key);
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::helpers::unique_subject;
-    use dialog_common::{Blake3Hash, Buffer};
+    use dialog_common::Buffer;
     use dialog_effects::archive::{Archive, Catalog};
 
     #[dialog_common::test]
@@ -359,4 +404,63 @@ mod tests {
 
         Ok(())
     }
+
+    #[dialog_common::test]
+    async fn it_lists_and_removes_blocks() -> anyhow::Result<()> {
+        let provider = Volatile::new();
+        let subject = unique_subject("archive-list-remove");
+        let catalog = subject
+            .clone()
+            .attenuate(Archive)
+            .attenuate(Catalog::new("index"));
+
+        let blocks: Vec<Buffer> = (0..3u8).map(|i| Buffer::from(vec![i; 16])).collect();
+        catalog
+            .clone()
+            .invoke(Import::new(blocks.clone()))
+            .perform(&provider)
+            .await?;
+        // A block in another catalog is not part of this one's inventory.
+        subject
+            .clone()
+            .attenuate(Archive)
+            .attenuate(Catalog::new("other"))
+            .invoke(Put::new(Buffer::from(vec![9u8; 16])))
+            .perform(&provider)
+            .await?;
+
+        let mut listed = catalog.clone().invoke(List).perform(&provider).await?;
+        listed.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        let mut expected: Vec<Blake3Hash> =
+            blocks.iter().map(|b| b.blake3_hash().clone()).collect();
+        expected.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        assert_eq!(listed, expected);
+
+        let removed = blocks[0].blake3_hash().clone();
+        catalog
+            .clone()
+            .invoke(Remove::new(removed.clone()))
+            .perform(&provider)
+            .await?;
+        // Removing again is not an error.
+        catalog
+            .clone()
+            .invoke(Remove::new(removed.clone()))
+            .perform(&provider)
+            .await?;
+
+        assert!(
+            catalog
+                .clone()
+                .invoke(Get::new(removed.clone()))
+                .perform(&provider)
+                .await?
+                .is_none()
+        );
+        let listed = catalog.invoke(List).perform(&provider).await?;
+        assert_eq!(listed.len(), 2);
+        assert!(!listed.contains(&removed));
+
+        Ok(())
+    }
 }
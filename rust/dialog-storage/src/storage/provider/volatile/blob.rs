@@ -12,13 +12,13 @@
 
 use super::{Session, Volatile};
 use async_trait::async_trait;
-use base58::ToBase58;
+use base58::{FromBase58, ToBase58};
 use blake3::Hasher;
 use dialog_capability::{Capability, Did, Provider};
 use dialog_common::Blake3Hash;
-use dialog_effects::blob::prelude::{BlobImportExt as _, BlobReadExt as _};
+use dialog_effects::blob::prelude::{BlobImportExt as _, BlobReadExt as _, BlobRemoveExt as _};
 use dialog_effects::blob::{
-    BlobError, BlobReader, BlobSink, BlobSource, BlobWriter, Import, Read, Write,
+    BlobError, BlobReader, BlobSink, BlobSource, BlobWriter, Import, List, Read, Remove, Write,
 };
 use parking_lot::RwLock;
 use std::collections::HashMap;
@@ -157,6 +157,41 @@ impl Provider<Import> for Volatile {
     }
 }
 
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider<List> for Volatile {
+    async fn execute(&self, effect: Capability<List>) -> Result<Vec<Blake3Hash>, BlobError> {
+        let subject: Did = effect.subject().into();
+
+        let sessions = self.sessions.read();
+        let Some(session) = sessions.get(&subject) else {
+            return Ok(Vec::new());
+        };
+        session
+            .blobs
+            .keys()
+            .map(|key| {
+                key.from_base58()
+                    .ok()
+                    .and_then(|bytes| Blake3Hash::try_from(bytes).ok())
+                    .ok_or_else(|| BlobError::Storage(format!("Invalid blob key: {key}")))
+            })
+            .collect()
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider<Remove> for Volatile {
+    async fn execute(&self, effect: Capability<Remove>) -> Result<(), BlobError> {
+        let subject: Did = effect.subject().into();
+        if let Some(session) = self.sessions.write().get_mut(&subject) {
+            session.blobs.remove(&blob_key(effect.digest()));
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -294,4 +329,53 @@ mod tests {
         assert!(matches!(missing, Err(BlobError::NotFound(_))));
         Ok(())
     }
+
+    #[dialog_common::test]
+    async fn it_lists_and_removes_blobs() -> anyhow::Result<()> {
+        let provider = Volatile::new();
+        let subject = unique_subject("blob-list-remove");
+
+        let mut hashes = Vec::new();
+        for payload in [b"first".as_slice(), b"second".as_slice()] {
+            let mut sink = subject
+                .clone()
+                .archive()
+                .blob()
+                .write()
+                .perform(&provider)
+                .await?;
+            sink.write_all(payload).await?;
+            hashes.push(sink.finish().await?);
+        }
+
+        let listed = subject
+            .clone()
+            .archive()
+            .blob()
+            .list()
+            .perform(&provider)
+            .await?;
+        assert_eq!(listed.len(), 2);
+        assert!(hashes.iter().all(|hash| listed.contains(hash)));
+
+        subject
+            .clone()
+            .archive()
+            .blob()
+            .remove(hashes[0].clone())
+            .perform(&provider)
+            .await?;
+        let missing = subject
+            .clone()
+            .archive()
+            .blob()
+            .read(hashes[0].clone())
+            .perform(&provider)
+            .await;
+        assert!(matches!(missing, Err(BlobError::NotFound(_))));
+
+        let listed = subject.archive().blob().list().perform(&provider).await?;
+        assert_eq!(listed, vec![hashes[1].clone()]);
+        Ok(())
+    }
 }
@@ -7,8 +7,8 @@ use super::{MemoryKey, Volatile};
 use async_trait::async_trait;
 use dialog_capability::{Capability, Provider};
 use dialog_common::Blake3Hash;
-use dialog_effects::memory::prelude::{PublishExt, ResolveExt, RetractExt};
-use dialog_effects::memory::{Edition, MemoryError, Publish, Resolve, Retract, Version};
+use dialog_effects::memory::prelude::{MemoryListExt, PublishExt, ResolveExt, RetractExt};
+use dialog_effects::memory::{Edition, List, MemoryError, Publish, Resolve, Retract, Version};
 
 /// Format edition bytes for error messages.
 fn format_edition(edition: Option<&[u8]>) -> Option<Version> {
@@ -142,10 +142,33 @@ impl Provider<Retract> for Volatile {
     }
 }
 
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Provider<List> for Volatile {
+    async fn execute(&self, effect: Capability<List>) -> Result<Vec<String>, MemoryError> {
+        let subject = effect.subject().into();
+        let prefix = effect.prefix();
+
+        let sessions = self.sessions.read();
+        Ok(sessions
+            .get(&subject)
+            .map(|session| {
+                session
+                    .memory
+                    .keys()
+                    .map(|(space, cell)| format!("{space}/{cell}"))
+                    .filter(|address| address.starts_with(prefix))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::helpers::unique_subject;
+    use dialog_effects::memory::prelude::{CellExt, MemoryExt, MemorySubjectExt, SpaceExt};
     use dialog_effects::memory::{Cell, Memory, Space, Version};
 
     #[dialog_common::test]
@@ -595,4 +618,42 @@ mod tests {
 
         Ok(())
     }
+
+    #[dialog_common::test]
+    async fn it_lists_cells_by_address_prefix() -> anyhow::Result<()> {
+        let provider = Volatile::new();
+        let subject = unique_subject("memory-list");
+
+        for (space, cell) in [
+            ("branch/main", "revision"),
+            ("branch/feature/x", "revision"),
+            ("remote/origin", "branch/main/revision"),
+        ] {
+            subject
+                .clone()
+                .memory()
+                .space(space)
+                .cell(cell)
+                .publish(b"content".to_vec(), None)
+                .perform(&provider)
+                .await?;
+        }
+
+        let mut branches = subject
+            .clone()
+            .memory()
+            .list("branch/")
+            .perform(&provider)
+            .await?;
+        branches.sort();
+        assert_eq!(
+            branches,
+            vec!["branch/feature/x/revision", "branch/main/revision"]
+        );
+
+        let everything = subject.memory().list("").perform(&provider).await?;
+        assert_eq!(everything.len(), 3);
+
+        Ok(())
+    }
 }
@@ -59,6 +59,21 @@ pub enum ContainerError {
         revoker: Did,
     },
 
+    /// A link in the chain was issued by a key that has since been
+    /// superseded, to someone other than its successor. Like a revocation,
+    /// the authority was withdrawn rather than forged.
+    #[error(
+        "Delegation from '{issuer}' was superseded{}",
+        .successor.as_ref().map(|did| format!(" by '{did}'")).unwrap_or_default()
+    )]
+    Superseded {
+        /// The superseded principal.
+        issuer: Did,
+        /// Who succeeded it; `None` when conflicting successions leave that
+        /// contested.
+        successor: Option<Did>,
+    },
+
     /// Invalid configuration.
     #[error("Configuration error: {0}")]
     Configuration(String),
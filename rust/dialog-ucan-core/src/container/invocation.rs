@@ -23,6 +23,7 @@ use crate::{
     invocation::{Invalid, Unavailable, VerifyError},
     promise::Promised,
     revocation::RevocationChecker,
+    succession::SuccessionChecker,
     time::TimeRange,
     verification::{Verifiable, VerificationContext},
 };
@@ -144,6 +145,63 @@ impl<S: Signature> InvocationChain<S> {
             })
     }
 
+    /// Refuse the chain if any of its issuers has been superseded.
+    ///
+    /// Checked apart from [`verify`](Self::verify), after it: a link issued
+    /// by a superseded key stands only if it carries the key's authority to
+    /// its successor (see
+    /// [`SuccessionMatch::admits`](crate::SuccessionMatch::admits)), and an
+    /// invocation signed by a superseded key never does. Which instant the
+    /// link was signed at does not matter — the key that was retired could
+    /// have signed it at any.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContainerError::Superseded`] naming the first superseded
+    /// issuer, or [`ContainerError::Configuration`] if `successions` cannot
+    /// answer.
+    pub async fn verify_successions<Successions>(
+        &self,
+        successions: &Successions,
+    ) -> Result<(), ContainerError>
+    where
+        Successions: SuccessionChecker,
+    {
+        let query = |principal: &Did| {
+            let principal = principal.clone();
+            async move {
+                successions.query(&principal).await.map_err(|source| {
+                    ContainerError::Configuration(format!(
+                        "could not determine whether '{principal}' was superseded: {source}"
+                    ))
+                })
+            }
+        };
+
+        for cid in self.invocation.proofs() {
+            let Some(delegation) = self.delegations.get(cid) else {
+                return Err(ContainerError::Invocation(format!(
+                    "proof not found: {cid}"
+                )));
+            };
+            if let Some(found) = query(delegation.issuer()).await?
+                && !found.admits(delegation.audience())
+            {
+                return Err(ContainerError::Superseded {
+                    issuer: found.predecessor,
+                    successor: found.successor,
+                });
+            }
+        }
+        if let Some(found) = query(self.invocation.issuer()).await? {
+            return Err(ContainerError::Superseded {
+                issuer: found.predecessor,
+                successor: found.successor,
+            });
+        }
+        Ok(())
+    }
+
     /// The proof store this chain's delegations live in.
     ///
     /// Exposed so an environment implementing [`Verifiable`] can hand the
@@ -1330,4 +1388,147 @@ mod tests {
             "Invocation with issuer != subject and no proofs should fail verification"
         );
     }
+
+    /// Reports one principal as superseded, as decided by `succession`.
+    struct Rotated(crate::Succession<AnySignature>);
+
+    impl SuccessionChecker for Rotated {
+        type Error = Unreachable;
+
+        async fn query(
+            &self,
+            principal: &Did,
+        ) -> Result<Option<crate::SuccessionMatch>, Self::Error> {
+            Ok(
+                (principal == self.0.predecessor()).then(|| crate::SuccessionMatch {
+                    succession: self.0.to_cid(),
+                    predecessor: self.0.predecessor().clone(),
+                    successor: Some(self.0.successor().clone()),
+                    issuer: self.0.issuer().clone(),
+                }),
+            )
+        }
+    }
+
+    struct OfflineSuccessions;
+
+    impl SuccessionChecker for OfflineSuccessions {
+        type Error = Unreachable;
+
+        async fn query(
+            &self,
+            _principal: &Did,
+        ) -> Result<Option<crate::SuccessionMatch>, Self::Error> {
+            Err(Unreachable)
+        }
+    }
+
+    /// `invoker` gets `/storage/get` on `subject` on the strength of
+    /// `proofs`, root first.
+    async fn invoke_with(
+        invoker: &dialog_credentials::Signer,
+        subject: &Did,
+        proofs: Vec<Delegation<AnySignature>>,
+    ) -> InvocationChain<AnySignature> {
+        let invocation = InvocationBuilder::new()
+            .issuer(invoker.clone())
+            .audience(subject)
+            .subject(subject)
+            .command(vec!["storage".to_string(), "get".to_string()])
+            .proofs(proofs.iter().map(Delegation::to_cid).collect())
+            .try_build()
+            .await
+            .expect("Failed to build invocation");
+        let delegations = proofs
+            .into_iter()
+            .map(|delegation| (delegation.to_cid(), Arc::new(delegation)))
+            .collect();
+        InvocationChain::new(invocation, delegations)
+    }
+
+    #[dialog_common::test]
+    async fn it_refuses_a_link_from_a_superseded_key() {
+        let old = generate_signer().await;
+        let new = generate_signer().await;
+        let operator = generate_signer().await;
+        let rotation = crate::Succession::rotate(old.clone(), &new)
+            .await
+            .expect("rotated");
+
+        // Signed by the old key before (or after: it cannot be told) the
+        // rotation, to someone other than the successor.
+        let stale = create_delegation(&old, &operator, &old, &["storage"])
+            .await
+            .expect("delegated");
+        let chain = invoke_with(&operator, &old.did(), vec![stale]).await;
+        assert!(
+            chain
+                .verify(&test_context(&test_environment(&chain)))
+                .await
+                .is_ok()
+        );
+
+        let result = chain.verify_successions(&Rotated(rotation)).await;
+        assert!(
+            matches!(
+                &result,
+                Err(ContainerError::Superseded { issuer, successor: Some(successor) })
+                    if issuer == &old.did() && successor == &new.did()
+            ),
+            "expected a superseded refusal, got {result:?}"
+        );
+    }
+
+    #[dialog_common::test]
+    async fn it_admits_a_chain_through_the_handover() {
+        let old = generate_signer().await;
+        let new = generate_signer().await;
+        let operator = generate_signer().await;
+        let rotation = crate::Succession::rotate(old.clone(), &new)
+            .await
+            .expect("rotated");
+
+        let migrated = create_delegation(&new, &operator, &old, &["storage"])
+            .await
+            .expect("delegated");
+        let chain = invoke_with(
+            &operator,
+            &old.did(),
+            vec![rotation.handover().clone(), migrated],
+        )
+        .await;
+        chain
+            .verify(&test_context(&test_environment(&chain)))
+            .await
+            .expect("the migrated chain verifies");
+        chain
+            .verify_successions(&Rotated(rotation))
+            .await
+            .expect("the handover carries the old key's authority");
+    }
+
+    #[dialog_common::test]
+    async fn it_refuses_an_invocation_signed_by_a_superseded_key() {
+        let old = generate_signer().await;
+        let new = generate_signer().await;
+        let rotation = crate::Succession::rotate(old.clone(), &new)
+            .await
+            .expect("rotated");
+
+        let chain = invoke_with(&old, &old.did(), Vec::new()).await;
+        assert!(matches!(
+            chain.verify_successions(&Rotated(rotation)).await,
+            Err(ContainerError::Superseded { .. })
+        ));
+    }
+
+    #[dialog_common::test]
+    async fn it_reports_an_unanswered_succession_query_as_configuration() {
+        let signer = generate_signer().await;
+        let chain = invoke_with(&signer, &signer.did(), Vec::new()).await;
+        assert!(matches!(
+            chain.verify_successions(&OfflineSuccessions).await,
+            Err(ContainerError::Configuration(_))
+        ));
+    }
 }
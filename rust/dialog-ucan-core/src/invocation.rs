@@ -103,6 +103,12 @@ impl<S: Signature> Invocation<S> {
         self.payload().expiration
     }
 
+    /// Getter for the `issued_at` field.
+    #[must_use]
+    pub const fn issued_at(&self) -> Option<Timestamp> {
+        self.payload().issued_at
+    }
+
     /// Getter for the `meta` field. Returns an empty map when meta is absent.
     #[must_use]
     pub fn meta(&self) -> &BTreeMap<String, Ipld> {
@@ -232,7 +238,10 @@ impl<S: Signature> Invocation<S> {
                 {
                     Ok(None) => Ok(()),
                     Ok(Some(found)) => Err(VerifyError::<K, S, T, St, _, _>::Invalid(
-                        Invalid::Revoked { cid, found },
+                        Invalid::Revoked {
+                            cid,
+                            found: Box::new(found),
+                        },
                     )),
                     // The question went unanswered. A caller willing to
                     // proceed without the answer says so by wrapping the
@@ -484,6 +493,12 @@ impl InvocationPayload {
         self.expiration
     }
 
+    /// Getter for the `issued_at` field.
+    #[must_use]
+    pub const fn issued_at(&self) -> Option<Timestamp> {
+        self.issued_at
+    }
+
     /// Getter for the `meta` field. Returns an empty map when meta is absent.
     #[must_use]
     pub fn meta(&self) -> &BTreeMap<String, Ipld> {
@@ -1043,7 +1058,7 @@ pub enum Invalid<
         /// The revoked delegation.
         cid: Cid,
         /// The revocation that matched: its document and its issuer.
-        found: RevocationMatch,
+        found: Box<RevocationMatch>,
     },
 }
 
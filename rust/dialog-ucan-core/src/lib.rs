@@ -17,9 +17,10 @@ pub mod issuer;
 pub mod number;
 pub mod principal;
 pub mod promise;
+pub mod receipt;
 pub mod revocation;
-// pub mod receipt; TODO Reenable after first release
 pub mod subject;
+pub mod succession;
 pub mod sync;
 pub mod task;
 pub mod time;
@@ -44,8 +45,12 @@ pub use invocation::{
     CheckError, CheckFailed, Invalid, Invocation, InvocationPayload, Unavailable, VerifyError,
     builder::{BuildError as InvocationBuildError, InvocationBuilder},
 };
+pub use receipt::{Outcome, Receipt, ReceiptError, ReceiptStore, Receipts, UnrecordedReceipts};
 pub use revocation::{
-    RevocationChecker, RevocationMatch, RevocationSelector, TolerateUnavailability,
-    UnverifiedRevocations,
+    Revocation, RevocationChecker, RevocationError, RevocationMatch, RevocationSelector,
+    TolerateUnavailability, UnverifiedRevocations,
+};
+pub use succession::{
+    Succession, SuccessionChecker, SuccessionError, SuccessionMatch, UnverifiedSuccessions,
 };
 pub use verification::{Environment, Verifiable, VerificationContext};
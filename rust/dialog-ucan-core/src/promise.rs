@@ -1,5 +1,6 @@
 //! Distributed promises
 
+use crate::receipt::Outcome;
 use ipld_core::{cid::Cid, ipld::Ipld};
 use serde::{Deserialize, Serialize, Serializer, de, ser::SerializeMap};
 use std::collections::BTreeMap;
@@ -183,6 +184,86 @@ impl TryFrom<&Promised> for Ipld {
     }
 }
 
+impl Promised {
+    /// The invocations this value waits on, in order of first appearance.
+    #[must_use]
+    pub fn awaits(&self) -> Vec<Cid> {
+        let mut found = Vec::new();
+        self.collect_awaits(&mut found);
+        found
+    }
+
+    fn collect_awaits(&self, found: &mut Vec<Cid>) {
+        match self {
+            Promised::WaitOk(cid) | Promised::WaitErr(cid) | Promised::WaitAny(cid) => {
+                if !found.contains(cid) {
+                    found.push(*cid);
+                }
+            }
+            Promised::List(items) => items.iter().for_each(|item| item.collect_awaits(found)),
+            Promised::Map(entries) => entries
+                .values()
+                .for_each(|value| value.collect_awaits(found)),
+            _ => {}
+        }
+    }
+
+    /// Resolve every promise in this value against receipts' outcomes.
+    ///
+    /// `outcome` looks up the outcome of the receipt for an invocation CID.
+    /// An `ucan/await/ok` promise resolves to the `ok` value, an
+    /// `ucan/await/err` promise to the `err` value, and an `ucan/await/*`
+    /// promise to the whole outcome map (`{"ok": ...}` or `{"err": ...}`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Unresolved::Waiting`] if a promised invocation has no
+    /// receipt yet, and [`Unresolved::Broken`] if its receipt settled the
+    /// other way than the promise awaits: a dependent invocation can never
+    /// run on that result.
+    pub fn resolve<'a, F>(&self, outcome: &F) -> Result<Ipld, Unresolved>
+    where
+        F: Fn(&Cid) -> Option<&'a Outcome>,
+    {
+        let settled =
+            |waiting: WaitingOn, cid: &Cid| outcome(cid).ok_or(Unresolved::Waiting(waiting));
+        match self {
+            Promised::WaitOk(cid) => match settled(WaitingOn::WaitOk(*cid), cid)? {
+                Outcome::Ok(value) => Ok(value.clone()),
+                Outcome::Err(_) => Err(Unresolved::Broken(WaitingOn::WaitOk(*cid))),
+            },
+            Promised::WaitErr(cid) => match settled(WaitingOn::WaitErr(*cid), cid)? {
+                Outcome::Err(value) => Ok(value.clone()),
+                Outcome::Ok(_) => Err(Unresolved::Broken(WaitingOn::WaitErr(*cid))),
+            },
+            Promised::WaitAny(cid) => Ok(settled(WaitingOn::WaitAny(*cid), cid)?.to_ipld()),
+            Promised::List(items) => items
+                .iter()
+                .map(|item| item.resolve(outcome))
+                .collect::<Result<_, _>>()
+                .map(Ipld::List),
+            Promised::Map(entries) => entries
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), value.resolve(outcome)?)))
+                .collect::<Result<_, _>>()
+                .map(Ipld::Map),
+            resolved => Ok(Ipld::try_from(resolved)?),
+        }
+    }
+}
+
+/// Why a [`Promised`] value could not be resolved against receipts.
+#[derive(Debug, Clone, Copy, Error)]
+pub enum Unresolved {
+    /// The promised invocation has no receipt yet.
+    #[error(transparent)]
+    Waiting(#[from] WaitingOn),
+
+    /// The promised invocation's receipt settled the other way.
+    #[error("Promise can never resolve: the receipt settled the other way ({0})")]
+    Broken(WaitingOn),
+}
+
 /// Still waiting to resolve a [`Promised`] value.
 #[derive(Debug, Clone, Copy, Error)]
 pub enum WaitingOn {
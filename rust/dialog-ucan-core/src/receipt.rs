@@ -1 +1,489 @@
 //! Receipts from running an [`Invocation`].
+//!
+//! Per the [UCAN invocation spec](https://github.com/ucan-wg/invocation/#receipt),
+//! whoever performs an invocation signs a receipt naming the invocation it
+//! ran (by CID) and its outcome: `{"ok": ...}` or `{"err": ...}`. Receipts are
+//! what [`Promised`](crate::promise::Promised) arguments wait on — an
+//! argument `{"ucan/await/ok": <cid>}` resolves to the `ok` value of the
+//! receipt for `<cid>` — so a later invocation can depend on an earlier one's
+//! result, and the dependency stays auditable after the fact.
+//!
+//! An executor signs and keeps its receipts through [`Receipts`], which
+//! hands each one to a [`ReceiptStore`] once it is signed.
+//!
+//! [`Invocation`]: crate::Invocation
+
+use crate::{
+    cid::to_dagcbor_cid,
+    envelope::{Envelope, EnvelopePayload, payload_tag::PayloadTag},
+    invocation::{SignatureVerificationError, builder::BuildError},
+    issuer::Issuer,
+    revocation::Never,
+    sync::{ConditionalSend, ConditionalSync},
+    time::Timestamp,
+};
+use dialog_varsig::{Did, Resolver, Signature, Signer, Verifier};
+use ipld_core::{cid::Cid, ipld::Ipld};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::{collections::BTreeMap, fmt::Debug};
+use thiserror::Error;
+
+/// The result of running an invocation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    /// The invocation succeeded with this value.
+    Ok(Ipld),
+
+    /// The invocation failed with this value.
+    Err(Ipld),
+}
+
+impl Outcome {
+    /// Record a result, serializing either branch to IPLD.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReceiptError::Encoding`] if the value cannot be represented
+    /// as IPLD.
+    pub fn from_result<T: Serialize, E: Serialize>(
+        result: &Result<T, E>,
+    ) -> Result<Self, ReceiptError> {
+        let encoding = |e: ipld_core::serde::SerdeError| ReceiptError::Encoding(e.to_string());
+        match result {
+            Ok(value) => Ok(Self::Ok(
+                ipld_core::serde::to_ipld(value).map_err(encoding)?,
+            )),
+            Err(error) => Ok(Self::Err(
+                ipld_core::serde::to_ipld(error).map_err(encoding)?,
+            )),
+        }
+    }
+
+    /// Whether the invocation succeeded.
+    #[must_use]
+    pub const fn is_ok(&self) -> bool {
+        matches!(self, Self::Ok(_))
+    }
+
+    /// The outcome as the map it is encoded as, which is also what an
+    /// `ucan/await/*` promise resolves to.
+    #[must_use]
+    pub fn to_ipld(&self) -> Ipld {
+        let (key, value) = match self {
+            Self::Ok(value) => ("ok", value),
+            Self::Err(value) => ("err", value),
+        };
+        Ipld::Map(BTreeMap::from([(key.to_string(), value.clone())]))
+    }
+}
+
+/// A signed statement of what running an invocation produced.
+#[derive(Clone)]
+pub struct Receipt<S: Signature>(Envelope<S, ReceiptPayload>);
+
+impl<S: Signature> Receipt<S> {
+    /// Issue a receipt for the invocation `ran`, signed by `issuer` — the
+    /// principal that performed it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`BuildError`] if encoding or signing fails.
+    pub async fn issue<I: Issuer<S>>(
+        issuer: I,
+        ran: Cid,
+        out: Outcome,
+    ) -> Result<Self, BuildError> {
+        let payload = ReceiptPayload {
+            issuer: issuer.did(),
+            ran,
+            out,
+            meta: None,
+            issued_at: Some(Timestamp::now()),
+        };
+        let envelope = EnvelopePayload::from(payload);
+        let encoded = envelope
+            .encode()
+            .map_err(|e| BuildError::EncodingError(e.to_string()))?;
+        let signature = Signer::sign(&issuer, &encoded)
+            .await
+            .map_err(BuildError::SigningError)?;
+        Ok(Self(Envelope(signature, envelope)))
+    }
+
+    /// Getter for the `issuer` field: who ran the invocation.
+    #[must_use]
+    pub const fn issuer(&self) -> &Did {
+        &self.payload().issuer
+    }
+
+    /// Getter for the `ran` field: the CID of the invocation.
+    #[must_use]
+    pub const fn ran(&self) -> Cid {
+        self.payload().ran
+    }
+
+    /// Getter for the `out` field.
+    #[must_use]
+    pub const fn out(&self) -> &Outcome {
+        &self.payload().out
+    }
+
+    /// Getter for the `iat` field.
+    #[must_use]
+    pub const fn issued_at(&self) -> Option<Timestamp> {
+        self.payload().issued_at
+    }
+
+    /// Getter for the `meta` field. Returns an empty map when meta is absent.
+    #[must_use]
+    pub fn meta(&self) -> &BTreeMap<String, Ipld> {
+        static EMPTY: BTreeMap<String, Ipld> = BTreeMap::new();
+        self.payload().meta.as_ref().unwrap_or(&EMPTY)
+    }
+
+    /// Compute the CID for this receipt.
+    #[must_use]
+    pub fn to_cid(&self) -> Cid {
+        to_dagcbor_cid(&self)
+    }
+
+    const fn payload(&self) -> &ReceiptPayload {
+        &self.0.1.payload
+    }
+
+    /// Verify that the receipt was signed by its claimed issuer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SignatureVerificationError`] if the issuer cannot be
+    /// resolved or the signature does not verify.
+    pub async fn verify_signature<R>(
+        &self,
+        resolver: &R,
+    ) -> Result<(), SignatureVerificationError<R::Error>>
+    where
+        R: Resolver<S>,
+    {
+        let verifier = resolver
+            .resolve(self.issuer())
+            .await
+            .map_err(SignatureVerificationError::ResolutionError)?;
+        let encoded = self
+            .0
+            .1
+            .encode()
+            .map_err(SignatureVerificationError::EncodingError)?;
+        Verifier::verify(&verifier, &encoded, &self.0.0)
+            .await
+            .map_err(SignatureVerificationError::VerificationError)
+    }
+
+    /// Encode the receipt as DAG-CBOR.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReceiptError::Encoding`] if serialization fails.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ReceiptError> {
+        serde_ipld_dagcbor::to_vec(self).map_err(|e| ReceiptError::Encoding(e.to_string()))
+    }
+}
+
+impl<S: Signature> Debug for Receipt<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Receipt").field(&self.0).finish()
+    }
+}
+
+impl<S: Signature> Serialize for Receipt<S> {
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, S: Signature + for<'ze> Deserialize<'ze>> Deserialize<'de> for Receipt<S> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self(Envelope::<S, ReceiptPayload>::deserialize(
+            deserializer,
+        )?))
+    }
+}
+
+impl<S: Signature + for<'de> Deserialize<'de>> TryFrom<&[u8]> for Receipt<S> {
+    type Error = ReceiptError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        serde_ipld_dagcbor::from_slice(bytes).map_err(|e| ReceiptError::Decoding(e.to_string()))
+    }
+}
+
+/// Keeps the receipts an executor issues where promises awaiting them can
+/// find them.
+pub trait ReceiptStore<S: Signature> {
+    /// Error type for a receipt the store could not keep.
+    type Error: std::error::Error + ConditionalSend + ConditionalSync + 'static;
+
+    /// Keep `receipt`.
+    ///
+    /// Async because keeping it generally means committing it somewhere
+    /// durable.
+    fn record(&self, receipt: Receipt<S>) -> impl Future<Output = Result<(), Self::Error>>;
+}
+
+impl<S: Signature, T: ReceiptStore<S>> ReceiptStore<S> for &T {
+    type Error = T::Error;
+
+    fn record(&self, receipt: Receipt<S>) -> impl Future<Output = Result<(), Self::Error>> {
+        (**self).record(receipt)
+    }
+}
+
+/// A store that keeps nothing: every receipt handed to it is dropped.
+///
+/// The default for an executor that has not been given a store, so that
+/// one that has is a choice made where the executor is built.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnrecordedReceipts;
+
+impl<S: Signature> ReceiptStore<S> for UnrecordedReceipts {
+    type Error = Never;
+
+    async fn record(&self, _receipt: Receipt<S>) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// The key an executor signs its receipts with, and the store they are
+/// kept in.
+#[derive(Clone)]
+pub struct Receipts<I, Store> {
+    executor: I,
+    store: Store,
+}
+
+impl<I, Store> Receipts<I, Store> {
+    /// Sign receipts as `executor` and keep them in `store`.
+    pub const fn new(executor: I, store: Store) -> Self {
+        Self { executor, store }
+    }
+
+    /// Sign a receipt for the invocation `ran` over what running it
+    /// produced, and keep it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReceiptError::Signing`] if the receipt cannot be signed,
+    /// and [`ReceiptError::Recording`] if the store does not keep it. The
+    /// invocation has run either way, and nothing accounts for it, so
+    /// callers pass these on to whoever asked for it rather than drop them.
+    pub async fn issue<S>(&self, ran: Cid, out: Outcome) -> Result<Receipt<S>, ReceiptError>
+    where
+        S: Signature,
+        I: Issuer<S> + Clone,
+        Store: ReceiptStore<S>,
+    {
+        let receipt = Receipt::issue(self.executor.clone(), ran, out)
+            .await
+            .map_err(|e| ReceiptError::Signing(e.to_string()))?;
+        self.store
+            .record(receipt.clone())
+            .await
+            .map_err(|e| ReceiptError::Recording(e.to_string()))?;
+        Ok(receipt)
+    }
+}
+
+/// The unsigned content of a [`Receipt`].
+///
+/// See the [UCAN Receipt payload spec](https://github.com/ucan-wg/invocation/#receipt).
+/// Effects (`fx`) are not produced: nothing here runs follow-up tasks on an
+/// invocation's behalf.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReceiptPayload {
+    #[serde(rename = "iss")]
+    issuer: Did,
+
+    ran: Cid,
+
+    out: Outcome,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    meta: Option<BTreeMap<String, Ipld>>,
+
+    #[serde(rename = "iat", default, skip_serializing_if = "Option::is_none")]
+    issued_at: Option<Timestamp>,
+}
+
+impl PayloadTag for ReceiptPayload {
+    fn spec_id() -> &'static str {
+        "rct"
+    }
+
+    fn version() -> &'static str {
+        "1.0.0-rc.1"
+    }
+}
+
+/// Errors encoding or decoding a [`Receipt`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ReceiptError {
+    /// The receipt or its outcome could not be encoded.
+    #[error("failed to encode receipt: {0}")]
+    Encoding(String),
+
+    /// The bytes are not a receipt.
+    #[error("failed to decode receipt: {0}")]
+    Decoding(String),
+
+    /// The receipt could not be signed.
+    #[error("failed to sign receipt: {0}")]
+    Signing(String),
+
+    /// The receipt was signed, but its store did not keep it.
+    #[error("failed to record receipt: {0}")]
+    Recording(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dialog_credentials::{DidKeyResolver, Ed25519Signer, Signer};
+    use dialog_varsig::{AnySignature, Principal};
+
+    async fn signer() -> Signer {
+        Signer::from(Ed25519Signer::generate().await.expect("signer"))
+    }
+
+    #[dialog_common::test]
+    async fn it_round_trips_a_signed_receipt() {
+        let executor = signer().await;
+        let ran = to_dagcbor_cid(&"invocation");
+        let out = Outcome::from_result::<_, String>(&Ok(42u64)).expect("encodable");
+
+        let receipt = Receipt::<AnySignature>::issue(executor.clone(), ran, out.clone())
+            .await
+            .expect("issued");
+        assert_eq!(receipt.issuer(), &executor.did());
+        assert_eq!(receipt.ran(), ran);
+        assert_eq!(receipt.out(), &out);
+
+        let bytes = receipt.to_bytes().expect("encoded");
+        let decoded = Receipt::<AnySignature>::try_from(bytes.as_slice()).expect("decoded");
+        assert_eq!(decoded.to_cid(), receipt.to_cid());
+        assert_eq!(decoded.out(), &Outcome::Ok(Ipld::Integer(42)));
+        decoded
+            .verify_signature(&DidKeyResolver)
+            .await
+            .expect("signature verifies");
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("store unavailable")]
+    struct Unavailable;
+
+    #[derive(Default)]
+    struct Kept(std::sync::Mutex<Vec<Cid>>);
+
+    impl ReceiptStore<AnySignature> for Kept {
+        type Error = Unavailable;
+
+        async fn record(&self, receipt: Receipt<AnySignature>) -> Result<(), Self::Error> {
+            self.0.lock().expect("unpoisoned").push(receipt.to_cid());
+            Ok(())
+        }
+    }
+
+    struct Refusing;
+
+    impl ReceiptStore<AnySignature> for Refusing {
+        type Error = Unavailable;
+
+        async fn record(&self, _receipt: Receipt<AnySignature>) -> Result<(), Self::Error> {
+            Err(Unavailable)
+        }
+    }
+
+    #[dialog_common::test]
+    async fn it_keeps_each_receipt_it_issues() {
+        let executor = signer().await;
+        let ran = to_dagcbor_cid(&"invocation");
+        let out = Outcome::Ok(Ipld::Null);
+
+        let kept = Kept::default();
+        let receipt: Receipt<AnySignature> = Receipts::new(executor.clone(), &kept)
+            .issue(ran, out.clone())
+            .await
+            .expect("issued");
+        assert_eq!(receipt.ran(), ran);
+        assert_eq!(*kept.0.lock().expect("unpoisoned"), vec![receipt.to_cid()]);
+
+        let refused = Receipts::new(executor, Refusing)
+            .issue::<AnySignature>(ran, out)
+            .await;
+        assert!(matches!(refused, Err(ReceiptError::Recording(_))));
+    }
+
+    #[dialog_common::test]
+    async fn it_encodes_the_outcome_as_a_tagged_map() {
+        let out = Outcome::from_result::<(), _>(&Err("denied")).expect("encodable");
+        assert!(!out.is_ok());
+        assert_eq!(
+            out.to_ipld(),
+            Ipld::Map(BTreeMap::from([(
+                "err".to_string(),
+                Ipld::String("denied".to_string())
+            )]))
+        );
+        let bytes = serde_ipld_dagcbor::to_vec(&out).expect("encoded");
+        let ipld: Ipld = serde_ipld_dagcbor::from_slice(&bytes).expect("decoded");
+        assert_eq!(ipld, out.to_ipld());
+    }
+
+    #[dialog_common::test]
+    async fn it_resolves_promises_against_receipts() {
+        use crate::promise::{Promised, Unresolved};
+
+        let executor = signer().await;
+        let put = to_dagcbor_cid(&"put");
+        let receipt = Receipt::<AnySignature>::issue(
+            executor.clone(),
+            put,
+            Outcome::Ok(Ipld::String("stored".to_string())),
+        )
+        .await
+        .expect("issued");
+        let receipts = BTreeMap::from([(receipt.ran(), receipt.out().clone())]);
+        let lookup = |cid: &Cid| receipts.get(cid);
+
+        // A publish that runs only once the put succeeded.
+        let args = Promised::Map(BTreeMap::from([
+            ("after".to_string(), Promised::WaitOk(put)),
+            ("space".to_string(), Promised::String("main".to_string())),
+        ]));
+        assert_eq!(args.awaits(), vec![put]);
+        assert_eq!(
+            args.resolve(&lookup).expect("resolved"),
+            Ipld::Map(BTreeMap::from([
+                ("after".to_string(), Ipld::String("stored".to_string())),
+                ("space".to_string(), Ipld::String("main".to_string())),
+            ]))
+        );
+
+        assert!(matches!(
+            Promised::WaitErr(put).resolve(&lookup),
+            Err(Unresolved::Broken(_))
+        ));
+        assert!(matches!(
+            Promised::WaitOk(to_dagcbor_cid(&"pending")).resolve(&lookup),
+            Err(Unresolved::Waiting(_))
+        ));
+        assert_eq!(
+            Promised::WaitAny(put).resolve(&lookup).expect("resolved"),
+            receipt.out().to_ipld()
+        );
+    }
+}
@@ -6,6 +6,10 @@
 //! a verifier asks is never "is this CID revoked?" in the abstract — it is
 //! "did any of *these* principals revoke it?" Only the verifier knows the
 //! chain, so it supplies the candidate set.
+//!
+//! The revocation documents themselves are [`Revocation`] records.
+
+pub mod record;
 
 use crate::sync::{ConditionalSend, ConditionalSync};
 use dialog_varsig::Did;
@@ -13,6 +17,8 @@ use ipld_core::cid::Cid;
 use std::error::Error;
 use std::future::Future;
 
+pub use record::{REVOKE_COMMAND, Revocation, RevocationError};
+
 /// Which revocations to match: those of a given delegation, issued by any of
 /// a set of principals.
 #[derive(Debug, Clone, Copy)]
@@ -88,6 +94,17 @@ pub trait RevocationChecker {
     }
 }
 
+impl<T: RevocationChecker> RevocationChecker for &T {
+    type Error = T::Error;
+
+    fn query(
+        &self,
+        selector: RevocationSelector<'_>,
+    ) -> impl Future<Output = Result<Option<RevocationMatch>, Self::Error>> {
+        (**self).query(selector)
+    }
+}
+
 /// A checker that queries nothing: every delegation comes back unrevoked.
 ///
 /// Named for what it does rather than for its effect. It does not establish
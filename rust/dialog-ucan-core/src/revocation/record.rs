@@ -0,0 +1,251 @@
+//! Signed revocation records.
+//!
+//! Per the [UCAN revocation spec](https://github.com/ucan-wg/revocation), a
+//! revocation is itself an invocation: the revoker signs `/ucan/revoke` with
+//! the revoked delegation's CID under the `ucan` argument. That keeps it a
+//! self-certifying document — anyone holding the bytes can check who revoked
+//! what without trusting whoever handed them over — so it can be stored and
+//! replicated as plain data next to the delegations it withdraws.
+//!
+//! Whether a revocation *counts* is not decided here: only a verifier holding
+//! the proof chain knows which principals were entitled to revoke a link (see
+//! [`RevocationSelector`](super::RevocationSelector)). The spec's optional
+//! `path` argument, which lets a revoker prove its place in the chain up
+//! front, is therefore not produced.
+
+use crate::{
+    command::Command,
+    invocation::{Invocation, SignatureVerificationError, builder::BuildError},
+    issuer::Issuer,
+    promise::Promised,
+};
+use dialog_varsig::{Did, Resolver, Signature};
+use ipld_core::cid::Cid;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// The command a revocation is issued under.
+pub const REVOKE_COMMAND: &str = "/ucan/revoke";
+
+/// The argument naming the revoked delegation.
+const REVOKED: &str = "ucan";
+
+fn revoke_command() -> Command {
+    Command::new(vec!["ucan".to_string(), "revoke".to_string()])
+}
+
+/// A signed statement that its issuer withdraws a delegation.
+///
+/// Wraps the `/ucan/revoke` [`Invocation`] it is encoded as, and is only
+/// constructed from one that is well formed: right command, and a `ucan`
+/// argument linking the revoked delegation.
+#[derive(Debug, Clone)]
+pub struct Revocation<S: Signature> {
+    invocation: Invocation<S>,
+    revokes: Cid,
+}
+
+impl<S: Signature> Revocation<S> {
+    /// Issue a revocation of `delegation`, signed by `issuer`.
+    ///
+    /// The revoker is also the subject: a revocation exercises no authority
+    /// over anyone else's resource, so it carries no proofs.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`BuildError`] if encoding or signing fails.
+    pub async fn issue<I: Issuer<S>>(issuer: I, delegation: Cid) -> Result<Self, BuildError> {
+        let revoker = issuer.did();
+        let invocation = Invocation::builder()
+            .issuer(issuer)
+            .audience(&revoker)
+            .subject(&revoker)
+            .command(revoke_command().0)
+            .arguments(BTreeMap::from([(
+                REVOKED.to_string(),
+                Promised::Link(delegation),
+            )]))
+            .proofs(Vec::new())
+            .try_build()
+            .await?;
+        Ok(Self {
+            invocation,
+            revokes: delegation,
+        })
+    }
+
+    /// The revoked delegation.
+    #[must_use]
+    pub const fn revokes(&self) -> Cid {
+        self.revokes
+    }
+
+    /// The principal that signed the revocation.
+    #[must_use]
+    pub const fn issuer(&self) -> &Did {
+        self.invocation.issuer()
+    }
+
+    /// The underlying `/ucan/revoke` invocation.
+    #[must_use]
+    pub const fn invocation(&self) -> &Invocation<S> {
+        &self.invocation
+    }
+
+    /// The CID of the revocation document.
+    #[must_use]
+    pub fn to_cid(&self) -> Cid {
+        self.invocation.to_cid()
+    }
+
+    /// Verify that the revocation was signed by its claimed issuer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SignatureVerificationError`] if the issuer cannot be
+    /// resolved or the signature does not verify.
+    pub async fn verify_signature<R>(
+        &self,
+        resolver: &R,
+    ) -> Result<(), SignatureVerificationError<R::Error>>
+    where
+        R: Resolver<S>,
+    {
+        self.invocation.verify_signature(resolver).await
+    }
+
+    /// Encode the revocation as DAG-CBOR.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RevocationError::Encoding`] if serialization fails.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, RevocationError> {
+        serde_ipld_dagcbor::to_vec(&self.invocation)
+            .map_err(|e| RevocationError::Encoding(e.to_string()))
+    }
+}
+
+impl<S: Signature> TryFrom<Invocation<S>> for Revocation<S> {
+    type Error = RevocationError;
+
+    fn try_from(invocation: Invocation<S>) -> Result<Self, Self::Error> {
+        if invocation.command() != &revoke_command() {
+            return Err(RevocationError::Command(invocation.command().clone()));
+        }
+        let Some(Promised::Link(revokes)) = invocation.arguments().get(REVOKED) else {
+            return Err(RevocationError::MissingDelegation);
+        };
+        let revokes = *revokes;
+        Ok(Self {
+            invocation,
+            revokes,
+        })
+    }
+}
+
+impl<S: Signature + for<'de> Deserialize<'de>> TryFrom<&[u8]> for Revocation<S> {
+    type Error = RevocationError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let invocation: Invocation<S> = serde_ipld_dagcbor::from_slice(bytes)
+            .map_err(|e| RevocationError::Decoding(e.to_string()))?;
+        Self::try_from(invocation)
+    }
+}
+
+impl<S: Signature> Serialize for Revocation<S> {
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        self.invocation.serialize(serializer)
+    }
+}
+
+impl<'de, S: Signature + for<'ze> Deserialize<'ze>> Deserialize<'de> for Revocation<S> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let invocation = Invocation::<S>::deserialize(deserializer)?;
+        Self::try_from(invocation).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Errors producing or reading a [`Revocation`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RevocationError {
+    /// The revocation could not be encoded.
+    #[error("failed to encode revocation: {0}")]
+    Encoding(String),
+
+    /// The bytes are not an invocation.
+    #[error("failed to decode revocation: {0}")]
+    Decoding(String),
+
+    /// The invocation is for some other command.
+    #[error("not a revocation: command is '{0}', expected '{REVOKE_COMMAND}'")]
+    Command(Command),
+
+    /// The invocation does not link the delegation it revokes.
+    #[error("revocation does not link a delegation under its '{REVOKED}' argument")]
+    MissingDelegation,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cid::to_dagcbor_cid;
+    use dialog_credentials::{DidKeyResolver, Ed25519Signer, Signer};
+    use dialog_varsig::{AnySignature, Principal};
+
+    async fn signer() -> Signer {
+        Signer::from(Ed25519Signer::generate().await.expect("signer"))
+    }
+
+    #[dialog_common::test]
+    async fn it_round_trips_a_signed_revocation() {
+        let revoker = signer().await;
+        let delegation = to_dagcbor_cid(&"delegation");
+
+        let revocation = Revocation::<AnySignature>::issue(revoker.clone(), delegation)
+            .await
+            .expect("issued");
+        assert_eq!(revocation.revokes(), delegation);
+        assert_eq!(revocation.issuer(), &revoker.did());
+        assert_eq!(
+            revocation.invocation().command().to_string(),
+            REVOKE_COMMAND
+        );
+
+        let bytes = revocation.to_bytes().expect("encoded");
+        let decoded = Revocation::<AnySignature>::try_from(bytes.as_slice()).expect("decoded");
+        assert_eq!(decoded.revokes(), delegation);
+        assert_eq!(decoded.to_cid(), revocation.to_cid());
+        decoded
+            .verify_signature(&DidKeyResolver)
+            .await
+            .expect("signature verifies");
+    }
+
+    #[dialog_common::test]
+    async fn it_rejects_an_invocation_of_another_command() {
+        let issuer = signer().await;
+        let invocation = Invocation::<AnySignature>::builder()
+            .issuer(issuer.clone())
+            .audience(&issuer)
+            .subject(&issuer)
+            .command(vec!["storage".to_string(), "get".to_string()])
+            .arguments(BTreeMap::from([(
+                REVOKED.to_string(),
+                Promised::Link(to_dagcbor_cid(&"delegation")),
+            )]))
+            .proofs(Vec::new())
+            .try_build()
+            .await
+            .expect("built");
+
+        assert!(matches!(
+            Revocation::try_from(invocation),
+            Err(RevocationError::Command(_))
+        ));
+    }
+}
@@ -0,0 +1,311 @@
+//! Key succession lookup.
+//!
+//! A principal's signing key is not fixed for life: its holder may rotate to
+//! a new key, or — when the old key is lost or compromised — a backup key it
+//! registered in advance may name the successor instead. Either way the
+//! outcome is a signed [`Succession`] record, and from then on the old key's
+//! authority flows only to its successor: a delegation it issued to anyone
+//! else no longer counts, whenever it was signed, because a compromised key
+//! can sign with any timestamp it likes.
+//!
+//! A verifier asks "has this principal been superseded, and by whom?" for
+//! every issuer in a chain; [`SuccessionChecker`] answers it, and
+//! [`SuccessionMatch::settle`] decides between the records it finds.
+
+pub mod record;
+
+use crate::revocation::Never;
+use crate::sync::{ConditionalSend, ConditionalSync};
+use dialog_varsig::{Did, Signature};
+use ipld_core::cid::Cid;
+use std::error::Error;
+use std::future::Future;
+
+pub use record::{ROTATE_COMMAND, Succession, SuccessionError};
+
+/// The succession in force for a principal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuccessionMatch {
+    /// The succession record that decided it. When several conflict, one of
+    /// them.
+    pub succession: Cid,
+
+    /// The superseded principal.
+    pub predecessor: Did,
+
+    /// The principal the predecessor's authority now flows to. `None` when
+    /// conflicting successions leave it contested: then nothing the
+    /// predecessor issued stands until a recovery settles it.
+    pub successor: Option<Did>,
+
+    /// The principal that signed the succession: the predecessor itself for
+    /// a rotation, its registered backup for a recovery.
+    pub issuer: Did,
+}
+
+impl SuccessionMatch {
+    /// Settle which of `recorded` — successions of `principal` whose
+    /// signatures hold — is in force, or `None` when there are none.
+    ///
+    /// A recovery outranks a rotation: a compromised key can rotate, but
+    /// only its registered backup can recover. It does so only for the key
+    /// generation its registration belongs to, though. A recovery counts
+    /// when its registration predates the predecessor's first rotation,
+    /// since a registration issued after the key was handed on is not the
+    /// holder's to give, and when it does not predate the newest rotation
+    /// it overrides. Otherwise the rotations decide. Records that still
+    /// disagree leave the succession contested.
+    #[must_use]
+    pub fn settle<S: Signature>(principal: &Did, recorded: &[Succession<S>]) -> Option<Self> {
+        let (recoveries, rotations): (Vec<_>, Vec<_>) = recorded
+            .iter()
+            .partition(|succession| succession.is_recovery());
+        let first_rotation = rotations.iter().map(|rotation| rotation.issued_at()).min();
+        let newest_rotation = rotations.iter().map(|rotation| rotation.issued_at()).max();
+        let standing: Vec<_> = recoveries
+            .into_iter()
+            .filter(|recovery| {
+                first_rotation.is_none_or(|first| {
+                    recovery
+                        .registered_at()
+                        .is_some_and(|registered| registered <= first)
+                }) && newest_rotation.is_none_or(|newest| recovery.issued_at() >= newest)
+            })
+            .collect();
+        let in_force = if standing.is_empty() {
+            rotations
+        } else {
+            standing
+        };
+        let first = in_force.first()?;
+
+        let agreed = in_force
+            .iter()
+            .all(|succession| succession.successor() == first.successor());
+        Some(if agreed {
+            Self {
+                succession: first.to_cid(),
+                predecessor: principal.clone(),
+                successor: Some(first.successor().clone()),
+                issuer: first.issuer().clone(),
+            }
+        } else {
+            Self {
+                succession: first.to_cid(),
+                predecessor: principal.clone(),
+                successor: None,
+                issuer: principal.clone(),
+            }
+        })
+    }
+
+    /// Whether a delegation from the predecessor to `audience` still stands.
+    ///
+    /// Only the links that carry authority to the successor do: the handover
+    /// itself and, for a recovery, the registration of the backup that
+    /// signed it.
+    #[must_use]
+    pub fn admits(&self, audience: &Did) -> bool {
+        self.successor.as_ref() == Some(audience)
+            || (self.issuer != self.predecessor && &self.issuer == audience)
+    }
+}
+
+/// Queries whether a principal's key has been superseded.
+pub trait SuccessionChecker {
+    /// Error type for query failures.
+    ///
+    /// A failure means the question went unanswered — never that the
+    /// principal was not superseded.
+    type Error: Error + ConditionalSend + ConditionalSync + 'static;
+
+    /// Find the succession in force for `principal`.
+    ///
+    /// `Ok(None)` means the principal has not been superseded. When the
+    /// query cannot be performed, that is `Err`, and verification fails
+    /// rather than proceeding as though the key were current.
+    fn query(
+        &self,
+        principal: &Did,
+    ) -> impl Future<Output = Result<Option<SuccessionMatch>, Self::Error>>;
+}
+
+impl<T: SuccessionChecker> SuccessionChecker for &T {
+    type Error = T::Error;
+
+    fn query(
+        &self,
+        principal: &Did,
+    ) -> impl Future<Output = Result<Option<SuccessionMatch>, Self::Error>> {
+        (**self).query(principal)
+    }
+}
+
+/// A checker that queries nothing: every key comes back current.
+///
+/// Like [`UnverifiedRevocations`](crate::UnverifiedRevocations), it does not
+/// establish that no key was rotated — it never looks.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnverifiedSuccessions;
+
+impl SuccessionChecker for UnverifiedSuccessions {
+    type Error = Never;
+
+    async fn query(&self, _principal: &Did) -> Result<Option<SuccessionMatch>, Self::Error> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cid::to_dagcbor_cid;
+    use crate::time::Timestamp;
+    use dialog_credentials::{Ed25519Signer, Signer};
+    use dialog_varsig::{AnySignature, Principal as _};
+
+    fn did(seed: &str) -> Did {
+        format!("did:web:{seed}.example")
+            .parse()
+            .expect("valid did")
+    }
+
+    #[test]
+    fn a_rotation_admits_only_the_successor() {
+        let rotation = SuccessionMatch {
+            succession: to_dagcbor_cid(&"rotation"),
+            predecessor: did("old"),
+            successor: Some(did("new")),
+            issuer: did("old"),
+        };
+        assert!(rotation.admits(&did("new")));
+        assert!(!rotation.admits(&did("old")));
+        assert!(!rotation.admits(&did("someone")));
+    }
+
+    #[test]
+    fn a_recovery_also_admits_the_backup_that_signed_it() {
+        let recovery = SuccessionMatch {
+            succession: to_dagcbor_cid(&"recovery"),
+            predecessor: did("old"),
+            successor: Some(did("new")),
+            issuer: did("backup"),
+        };
+        assert!(recovery.admits(&did("new")));
+        assert!(recovery.admits(&did("backup")));
+        assert!(!recovery.admits(&did("someone")));
+    }
+
+    #[test]
+    fn a_contested_succession_admits_nobody() {
+        let contested = SuccessionMatch {
+            succession: to_dagcbor_cid(&"rotation"),
+            predecessor: did("old"),
+            successor: None,
+            issuer: did("old"),
+        };
+        assert!(!contested.admits(&did("new")));
+        assert!(!contested.admits(&did("old")));
+    }
+
+    #[dialog_common::test]
+    async fn unverified_matches_nothing() {
+        let found = UnverifiedSuccessions
+            .query(&did("old"))
+            .await
+            .expect("cannot fail");
+        assert!(found.is_none());
+    }
+
+    async fn signer() -> Signer {
+        Signer::from(Ed25519Signer::generate().await.expect("signer"))
+    }
+
+    /// `offset` seconds from a fixed moment.
+    fn at(offset: u64) -> Timestamp {
+        Timestamp::try_from(i128::from(1_700_000_000 + offset)).expect("in range")
+    }
+
+    async fn rotate(old: &Signer, new: &Signer, time: u64) -> Succession<AnySignature> {
+        Succession::issue(old.clone(), old.did(), None, new.did(), at(time))
+            .await
+            .expect("rotated")
+    }
+
+    async fn recover(
+        old: &Signer,
+        backup: &Signer,
+        new: &Signer,
+        registered: u64,
+        time: u64,
+    ) -> Succession<AnySignature> {
+        let registration = Succession::register(old.clone(), backup, at(registered))
+            .await
+            .expect("registered");
+        Succession::issue(
+            backup.clone(),
+            old.did(),
+            Some(registration),
+            new.did(),
+            at(time),
+        )
+        .await
+        .expect("recovered")
+    }
+
+    #[dialog_common::test]
+    async fn a_recovery_outranks_the_rotation_it_answers() {
+        let (old, backup, new, thief) = (
+            signer().await,
+            signer().await,
+            signer().await,
+            signer().await,
+        );
+        let recorded = [
+            rotate(&old, &thief, 10).await,
+            recover(&old, &backup, &new, 0, 20).await,
+        ];
+        let settled = SuccessionMatch::settle(&old.did(), &recorded).expect("superseded");
+        assert_eq!(settled.successor, Some(new.did()));
+        assert_eq!(settled.issuer, backup.did());
+    }
+
+    #[dialog_common::test]
+    async fn a_recovery_that_predates_the_newest_rotation_does_not_count() {
+        let (old, backup, new, next) = (
+            signer().await,
+            signer().await,
+            signer().await,
+            signer().await,
+        );
+        let recorded = [
+            recover(&old, &backup, &new, 0, 10).await,
+            rotate(&old, &next, 20).await,
+        ];
+        let settled = SuccessionMatch::settle(&old.did(), &recorded).expect("superseded");
+        assert_eq!(settled.successor, Some(next.did()));
+        assert_eq!(settled.issuer, old.did());
+    }
+
+    #[dialog_common::test]
+    async fn a_registration_issued_after_the_key_was_handed_on_does_not_count() {
+        // Whoever holds the retired key can still sign with it, but a
+        // backup it registers then belongs to no generation of the key.
+        let (old, new, thief) = (signer().await, signer().await, signer().await);
+        let recorded = [
+            rotate(&old, &new, 10).await,
+            recover(&old, &thief, &thief, 20, 30).await,
+        ];
+        let settled = SuccessionMatch::settle(&old.did(), &recorded).expect("superseded");
+        assert_eq!(settled.successor, Some(new.did()));
+    }
+
+    #[dialog_common::test]
+    async fn disagreeing_rotations_are_contested() {
+        let (old, new, thief) = (signer().await, signer().await, signer().await);
+        let recorded = [rotate(&old, &new, 10).await, rotate(&old, &thief, 20).await];
+        let settled = SuccessionMatch::settle(&old.did(), &recorded).expect("superseded");
+        assert_eq!(settled.successor, None);
+        assert!(SuccessionMatch::settle::<AnySignature>(&old.did(), &[]).is_none());
+    }
+}
@@ -0,0 +1,712 @@
+//! Signed succession records.
+//!
+//! A succession is an invocation of `/ucan/rotate`, modelled on the
+//! [revocation](crate::revocation::record) record: self-certifying data that
+//! can be stored and replicated beside the delegations it affects. It names
+//! the successor under its `successor` argument and links, under `handover`,
+//! the delegation that carries the predecessor's authority to it: a
+//! powerline (`sub: null`, `cmd: "/"`) from the signer to the successor.
+//! Chains rooted in the predecessor keep verifying through that handover.
+//!
+//! There are two ways to sign one:
+//!
+//! - **Rotation** — the predecessor signs it itself. The invocation's issuer
+//!   and subject are both the predecessor, and it carries no proofs.
+//! - **Recovery** — a backup key signs it, proving its standing with a
+//!   registration the predecessor issued in advance
+//!   ([`Succession::register_recovery`]): a powerline from the predecessor
+//!   to the backup, carried as the invocation's only proof.
+//!
+//! Every succession says when it was issued, and a registration says, under
+//! its `registered` meta entry, when the predecessor issued it. That binds
+//! the registration to the key generation that issued it: it stands against
+//! the rotations the predecessor signed after it, not against whatever the
+//! key is made to sign once it has been handed on (see
+//! [`SuccessionMatch::settle`](crate::SuccessionMatch::settle)). A plain
+//! powerline, carrying no such entry, registers nobody.
+//!
+//! Encoded, a succession is a [container](crate::Container) of the
+//! invocation followed by its proof, if any, and the handover.
+
+use crate::{
+    Container, ContainerError, DelegationChain, command::Command, delegation::Delegation,
+    invocation::Invocation, issuer::Issuer, promise::Promised, subject::Subject, time::Timestamp,
+};
+use dialog_varsig::{AnySignature, Did, Principal, Resolver, Signature};
+use ipld_core::cid::Cid;
+use ipld_core::ipld::Ipld;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// The command a succession is issued under.
+pub const ROTATE_COMMAND: &str = "/ucan/rotate";
+
+/// The argument naming the successor.
+const SUCCESSOR: &str = "successor";
+
+/// The argument linking the handover delegation.
+const HANDOVER: &str = "handover";
+
+/// The registration meta entry recording when the predecessor issued it.
+const REGISTERED: &str = "registered";
+
+fn rotate_command() -> Command {
+    Command::new(vec!["ucan".to_string(), "rotate".to_string()])
+}
+
+/// Whether `delegation` is a powerline from `issuer` to `audience`: any
+/// subject, every command.
+fn is_powerline<S: Signature>(delegation: &Delegation<S>, issuer: &Did, audience: &Did) -> bool {
+    delegation.issuer() == issuer
+        && delegation.audience() == audience
+        && delegation.subject() == &Subject::Any
+        && delegation.command() == &Command::new(Vec::new())
+}
+
+/// When `delegation` was issued as a recovery registration, or `None` when
+/// it is not one.
+fn registered_at<S: Signature>(delegation: &Delegation<S>) -> Option<Timestamp> {
+    delegation
+        .meta()
+        .get(REGISTERED)
+        .cloned()
+        .and_then(|time| Timestamp::try_from(time).ok())
+}
+
+/// Whether `delegation` registers `backup` to recover `predecessor`: a
+/// powerline between them that says when it was registered.
+fn is_registration<S: Signature>(
+    delegation: &Delegation<S>,
+    predecessor: &Did,
+    backup: &Did,
+) -> bool {
+    is_powerline(delegation, predecessor, backup) && registered_at(delegation).is_some()
+}
+
+/// A signed statement that a principal's authority has passed to a new key.
+///
+/// Only constructed from a well-formed `/ucan/rotate` invocation whose
+/// registration (for a recovery) and handover have the shapes described in
+/// the [module documentation](self). Whether its signatures hold is checked
+/// separately, by [`verify_signature`](Self::verify_signature).
+#[derive(Debug, Clone)]
+pub struct Succession<S: Signature> {
+    invocation: Invocation<S>,
+    registration: Option<Delegation<S>>,
+    handover: Delegation<S>,
+    successor: Did,
+    issued_at: Timestamp,
+}
+
+impl<S: Signature> Succession<S> {
+    /// Rotate `predecessor`'s key to `successor`, signed by the predecessor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SuccessionError::Signing`] if encoding or signing fails.
+    pub async fn rotate<I: Issuer<S> + Clone>(
+        predecessor: I,
+        successor: &impl Principal,
+    ) -> Result<Self, SuccessionError> {
+        let did = predecessor.did();
+        Self::issue(predecessor, did, None, successor.did(), Timestamp::now()).await
+    }
+
+    /// Register `backup` as able to recover `predecessor`'s authority.
+    ///
+    /// The registration is a powerline from the predecessor to the backup,
+    /// so the backup can act as the predecessor from the moment it is
+    /// issued. Keep it with the backup key; it is the proof
+    /// [`recover`](Self::recover) needs. It is stamped with the time it was
+    /// registered, and only outranks rotations signed after that.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SuccessionError::Signing`] if encoding or signing fails.
+    pub async fn register_recovery<I: Issuer<S>>(
+        predecessor: I,
+        backup: &impl Principal,
+    ) -> Result<Delegation<S>, SuccessionError> {
+        Self::register(predecessor, backup, Timestamp::now()).await
+    }
+
+    pub(crate) async fn register<I: Issuer<S>>(
+        predecessor: I,
+        backup: &impl Principal,
+        registered_at: Timestamp,
+    ) -> Result<Delegation<S>, SuccessionError> {
+        Delegation::builder()
+            .issuer(predecessor)
+            .audience(backup)
+            .subject(Subject::Any)
+            .command(Vec::new())
+            .meta(BTreeMap::from([(
+                REGISTERED.to_string(),
+                Ipld::from(registered_at),
+            )]))
+            .try_build()
+            .await
+            .map_err(|e| SuccessionError::Signing(e.to_string()))
+    }
+
+    /// Recover the authority of `registration`'s issuer to `successor`,
+    /// signed by the backup key the registration names.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SuccessionError::Malformed`] if `registration` is not a
+    /// recovery registration for `backup`, or [`SuccessionError::Signing`]
+    /// if encoding or signing fails.
+    pub async fn recover<I: Issuer<S> + Clone>(
+        backup: I,
+        registration: Delegation<S>,
+        successor: &impl Principal,
+    ) -> Result<Self, SuccessionError> {
+        let predecessor = registration.issuer().clone();
+        if !is_registration(&registration, &predecessor, &backup.did()) {
+            return Err(SuccessionError::Malformed(format!(
+                "'{}' is not a recovery registration for '{}'",
+                registration.to_cid(),
+                backup.did()
+            )));
+        }
+        Self::issue(
+            backup,
+            predecessor,
+            Some(registration),
+            successor.did(),
+            Timestamp::now(),
+        )
+        .await
+    }
+
+    pub(crate) async fn issue<I: Issuer<S> + Clone>(
+        issuer: I,
+        predecessor: Did,
+        registration: Option<Delegation<S>>,
+        successor: Did,
+        issued_at: Timestamp,
+    ) -> Result<Self, SuccessionError> {
+        let handover = Delegation::builder()
+            .issuer(issuer.clone())
+            .audience(&successor)
+            .subject(Subject::Any)
+            .command(Vec::new())
+            .try_build()
+            .await
+            .map_err(|e| SuccessionError::Signing(e.to_string()))?;
+        let invocation = Invocation::builder()
+            .issuer(issuer)
+            .audience(&predecessor)
+            .subject(&predecessor)
+            .command(rotate_command().0)
+            .arguments(BTreeMap::from([
+                (
+                    SUCCESSOR.to_string(),
+                    Promised::String(successor.to_string()),
+                ),
+                (HANDOVER.to_string(), Promised::Link(handover.to_cid())),
+            ]))
+            .proofs(registration.iter().map(Delegation::to_cid).collect())
+            .issued_at(issued_at)
+            .try_build()
+            .await
+            .map_err(|e| SuccessionError::Signing(e.to_string()))?;
+        Ok(Self {
+            invocation,
+            registration,
+            handover,
+            successor,
+            issued_at,
+        })
+    }
+
+    /// Check a decoded invocation and the delegations that came with it,
+    /// picking out the registration and the handover.
+    fn assemble(
+        invocation: Invocation<S>,
+        mut delegations: Vec<Delegation<S>>,
+    ) -> Result<Self, SuccessionError> {
+        if invocation.command() != &rotate_command() {
+            return Err(SuccessionError::Command(invocation.command().clone()));
+        }
+        let Some(Promised::String(successor)) = invocation.arguments().get(SUCCESSOR) else {
+            return Err(SuccessionError::MissingSuccessor);
+        };
+        let successor: Did = successor
+            .parse()
+            .map_err(|_| SuccessionError::MissingSuccessor)?;
+        let Some(Promised::Link(handover)) = invocation.arguments().get(HANDOVER) else {
+            return Err(SuccessionError::MissingHandover);
+        };
+        let issued_at = invocation.issued_at().ok_or_else(|| {
+            SuccessionError::Malformed("a succession must say when it was issued".to_string())
+        })?;
+        let mut take = |cid: &Cid| {
+            delegations
+                .iter()
+                .position(|delegation| &delegation.to_cid() == cid)
+                .map(|at| delegations.swap_remove(at))
+        };
+        let handover = take(handover).ok_or(SuccessionError::MissingHandover)?;
+
+        let predecessor = invocation.subject();
+        let signer = invocation.issuer();
+        let registration = match invocation.proofs().as_slice() {
+            [] if signer == predecessor => None,
+            [] => {
+                return Err(SuccessionError::Malformed(format!(
+                    "'{signer}' cannot rotate the key of '{predecessor}' without a registration"
+                )));
+            }
+            [proof] => {
+                let registration = take(proof).ok_or_else(|| {
+                    SuccessionError::Malformed(format!("registration '{proof}' is missing"))
+                })?;
+                if !is_registration(&registration, predecessor, signer) {
+                    return Err(SuccessionError::Malformed(format!(
+                        "'{proof}' does not register '{signer}' to recover '{predecessor}'"
+                    )));
+                }
+                if registered_at(&registration).is_some_and(|at| at > issued_at) {
+                    return Err(SuccessionError::Malformed(format!(
+                        "'{signer}' recovered '{predecessor}' before it was registered"
+                    )));
+                }
+                Some(registration)
+            }
+            _ => {
+                return Err(SuccessionError::Malformed(
+                    "a succession carries at most one proof".to_string(),
+                ));
+            }
+        };
+        if !is_powerline(&handover, signer, &successor) {
+            return Err(SuccessionError::Malformed(format!(
+                "'{}' does not hand '{predecessor}' over to '{successor}'",
+                handover.to_cid()
+            )));
+        }
+        if &successor == predecessor {
+            return Err(SuccessionError::Malformed(format!(
+                "'{predecessor}' cannot succeed itself"
+            )));
+        }
+
+        Ok(Self {
+            invocation,
+            registration,
+            handover,
+            successor,
+            issued_at,
+        })
+    }
+
+    /// The superseded principal.
+    #[must_use]
+    pub const fn predecessor(&self) -> &Did {
+        self.invocation.subject()
+    }
+
+    /// The principal the predecessor's authority passes to.
+    #[must_use]
+    pub const fn successor(&self) -> &Did {
+        &self.successor
+    }
+
+    /// The principal that signed the succession: the predecessor for a
+    /// rotation, the backup for a recovery.
+    #[must_use]
+    pub const fn issuer(&self) -> &Did {
+        self.invocation.issuer()
+    }
+
+    /// Whether a registered backup, rather than the predecessor, signed it.
+    #[must_use]
+    pub const fn is_recovery(&self) -> bool {
+        self.registration.is_some()
+    }
+
+    /// The registration that entitles the backup to recover, for a
+    /// recovery.
+    #[must_use]
+    pub const fn registration(&self) -> Option<&Delegation<S>> {
+        self.registration.as_ref()
+    }
+
+    /// When the succession was signed, by its signer's clock.
+    #[must_use]
+    pub const fn issued_at(&self) -> Timestamp {
+        self.issued_at
+    }
+
+    /// When the predecessor registered the backup that signed it, for a
+    /// recovery.
+    #[must_use]
+    pub fn registered_at(&self) -> Option<Timestamp> {
+        self.registration.as_ref().and_then(registered_at)
+    }
+
+    /// The powerline that carries the predecessor's authority to the
+    /// successor.
+    #[must_use]
+    pub const fn handover(&self) -> &Delegation<S> {
+        &self.handover
+    }
+
+    /// The underlying `/ucan/rotate` invocation.
+    #[must_use]
+    pub const fn invocation(&self) -> &Invocation<S> {
+        &self.invocation
+    }
+
+    /// The CID of the succession document.
+    #[must_use]
+    pub fn to_cid(&self) -> Cid {
+        self.invocation.to_cid()
+    }
+
+    /// Verify every signature the succession carries: the invocation's, the
+    /// registration's and the handover's, each by its claimed issuer.
+    ///
+    /// Time bounds are not judged, and neither is revocation: a succession
+    /// is a historical fact that must keep holding, and a compromised
+    /// predecessor key must not be able to withdraw the registration that
+    /// lets a backup recover from it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SuccessionError::InvalidSignature`] naming the first issuer
+    /// that cannot be resolved or whose signature does not verify.
+    pub async fn verify_signature<R>(&self, resolver: &R) -> Result<(), SuccessionError>
+    where
+        R: Resolver<S>,
+    {
+        let invalid = |issuer: &Did, detail: String| SuccessionError::InvalidSignature {
+            issuer: issuer.clone(),
+            detail,
+        };
+        self.invocation
+            .verify_signature(resolver)
+            .await
+            .map_err(|e| invalid(self.invocation.issuer(), e.to_string()))?;
+        for delegation in self.registration.iter().chain([&self.handover]) {
+            delegation
+                .verify_signature(resolver)
+                .await
+                .map_err(|e| invalid(delegation.issuer(), e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Encode the succession as a container of its invocation, its
+    /// registration (for a recovery) and its handover.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SuccessionError::Encoding`] if serialization fails.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SuccessionError> {
+        let invocation = serde_ipld_dagcbor::to_vec(&self.invocation)
+            .map_err(|e| SuccessionError::Encoding(e.to_string()))?;
+        let mut tokens = vec![invocation];
+        tokens.extend(
+            self.registration
+                .iter()
+                .map(|registration| registration.encoded().to_vec()),
+        );
+        tokens.push(self.handover.encoded().to_vec());
+        Container::new(tokens)
+            .into_bytes()
+            .map_err(|e| SuccessionError::Encoding(e.to_string()))
+    }
+}
+
+impl Succession<AnySignature> {
+    /// The delegation chain that carries the predecessor's authority to the
+    /// successor: the registration, for a recovery, then the handover.
+    /// Delegations the successor issues extend it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ContainerError`] if the chain's principals do not align,
+    /// which a succession's own construction rules out.
+    pub fn handover_chain(&self) -> Result<DelegationChain, ContainerError> {
+        let mut delegations: Vec<_> = self.registration.iter().cloned().collect();
+        delegations.push(self.handover.clone());
+        DelegationChain::try_from(delegations)
+    }
+}
+
+impl<S: Signature + for<'de> Deserialize<'de>> TryFrom<&[u8]> for Succession<S> {
+    type Error = SuccessionError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let tokens = Container::from_bytes(bytes)
+            .map_err(|e| SuccessionError::Decoding(e.to_string()))?
+            .into_tokens();
+        let (invocation, delegations) = tokens
+            .split_first()
+            .ok_or_else(|| SuccessionError::Decoding("empty container".to_string()))?;
+        let invocation: Invocation<S> = serde_ipld_dagcbor::from_slice(invocation)
+            .map_err(|e| SuccessionError::Decoding(e.to_string()))?;
+        let delegations = delegations
+            .iter()
+            .map(|bytes| serde_ipld_dagcbor::from_slice(bytes))
+            .collect::<Result<Vec<Delegation<S>>, _>>()
+            .map_err(|e| SuccessionError::Decoding(e.to_string()))?;
+        Self::assemble(invocation, delegations)
+    }
+}
+
+/// Errors producing or reading a [`Succession`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SuccessionError {
+    /// A document could not be signed.
+    #[error("failed to sign succession: {0}")]
+    Signing(String),
+
+    /// The succession could not be encoded.
+    #[error("failed to encode succession: {0}")]
+    Encoding(String),
+
+    /// The bytes are not a container of an invocation and its delegations.
+    #[error("failed to decode succession: {0}")]
+    Decoding(String),
+
+    /// The invocation is for some other command.
+    #[error("not a succession: command is '{0}', expected '{ROTATE_COMMAND}'")]
+    Command(Command),
+
+    /// The invocation does not name a successor DID.
+    #[error("succession does not name a successor under its '{SUCCESSOR}' argument")]
+    MissingSuccessor,
+
+    /// The invocation does not link a handover that came with it.
+    #[error("succession does not carry the handover its '{HANDOVER}' argument links")]
+    MissingHandover,
+
+    /// A document does not carry a valid signature from its claimed issuer.
+    #[error("succession signed for '{issuer}' does not verify: {detail}")]
+    InvalidSignature {
+        /// The issuer the signature claims.
+        issuer: Did,
+        /// Human-readable description of the verification failure.
+        detail: String,
+    },
+
+    /// The registration or handover does not have the required shape.
+    #[error("malformed succession: {0}")]
+    Malformed(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dialog_credentials::{DidKeyResolver, Ed25519Signer, Signer};
+
+    async fn signer() -> Signer {
+        Signer::from(Ed25519Signer::generate().await.expect("signer"))
+    }
+
+    fn round_trip(succession: &Succession<AnySignature>) -> Succession<AnySignature> {
+        let bytes = succession.to_bytes().expect("encoded");
+        Succession::try_from(bytes.as_slice()).expect("decoded")
+    }
+
+    #[dialog_common::test]
+    async fn it_round_trips_a_rotation() {
+        let old = signer().await;
+        let new = signer().await;
+
+        let rotation = Succession::<AnySignature>::rotate(old.clone(), &new)
+            .await
+            .expect("rotated");
+        assert_eq!(rotation.predecessor(), &old.did());
+        assert_eq!(rotation.successor(), &new.did());
+        assert_eq!(rotation.issuer(), &old.did());
+        assert!(!rotation.is_recovery());
+
+        let decoded = round_trip(&rotation);
+        assert_eq!(decoded.to_cid(), rotation.to_cid());
+        assert_eq!(decoded.successor(), &new.did());
+        decoded
+            .verify_signature(&DidKeyResolver)
+            .await
+            .expect("signatures verify");
+
+        let chain = decoded.handover_chain().expect("aligned");
+        assert_eq!(chain.issuer(), &old.did());
+        assert_eq!(chain.audience(), &new.did());
+    }
+
+    #[dialog_common::test]
+    async fn it_round_trips_a_recovery() {
+        let old = signer().await;
+        let backup = signer().await;
+        let new = signer().await;
+
+        let registration = Succession::<AnySignature>::register_recovery(old.clone(), &backup)
+            .await
+            .expect("registered");
+        let recovery = Succession::recover(backup.clone(), registration, &new)
+            .await
+            .expect("recovered");
+        assert_eq!(recovery.predecessor(), &old.did());
+        assert_eq!(recovery.issuer(), &backup.did());
+        assert!(recovery.is_recovery());
+
+        let decoded = round_trip(&recovery);
+        assert_eq!(decoded.to_cid(), recovery.to_cid());
+        decoded
+            .verify_signature(&DidKeyResolver)
+            .await
+            .expect("signatures verify");
+
+        let chain = decoded.handover_chain().expect("aligned");
+        assert_eq!(chain.issuer(), &old.did());
+        assert_eq!(chain.audience(), &new.did());
+    }
+
+    #[dialog_common::test]
+    async fn it_refuses_to_recover_without_a_registration_for_the_backup() {
+        let old = signer().await;
+        let backup = signer().await;
+        let stranger = signer().await;
+        let new = signer().await;
+
+        let registration = Succession::<AnySignature>::register_recovery(old, &backup)
+            .await
+            .expect("registered");
+        assert!(matches!(
+            Succession::recover(stranger, registration, &new).await,
+            Err(SuccessionError::Malformed(_))
+        ));
+    }
+
+    #[dialog_common::test]
+    async fn it_rejects_a_rotation_signed_by_someone_else() {
+        // A self-rotation must be signed by the key it retires: anyone else
+        // needs a registration to stand on.
+        let old = signer().await;
+        let mallory = signer().await;
+        let handover = Delegation::<AnySignature>::builder()
+            .issuer(mallory.clone())
+            .audience(&mallory)
+            .subject(Subject::Any)
+            .command(Vec::new())
+            .try_build()
+            .await
+            .expect("built");
+        let invocation = Invocation::<AnySignature>::builder()
+            .issuer(mallory.clone())
+            .audience(&old)
+            .subject(&old)
+            .command(rotate_command().0)
+            .arguments(BTreeMap::from([
+                (
+                    SUCCESSOR.to_string(),
+                    Promised::String(mallory.did().to_string()),
+                ),
+                (HANDOVER.to_string(), Promised::Link(handover.to_cid())),
+            ]))
+            .proofs(Vec::new())
+            .issue_now()
+            .try_build()
+            .await
+            .expect("built");
+
+        assert!(matches!(
+            Succession::assemble(invocation, vec![handover]),
+            Err(SuccessionError::Malformed(_))
+        ));
+    }
+
+    #[dialog_common::test]
+    async fn it_rejects_a_forged_handover_signature() {
+        let old = signer().await;
+        let new = signer().await;
+        let mallory = signer().await;
+        let rotation = Succession::<AnySignature>::rotate(old.clone(), &new)
+            .await
+            .expect("rotated");
+
+        let forged = Delegation::forge(
+            old.did(),
+            new.did(),
+            Subject::Any,
+            Command::new(Vec::new()),
+            &mallory,
+        )
+        .await
+        .expect("forged");
+        let tampered = Succession {
+            handover: forged,
+            ..rotation
+        };
+        assert!(tampered.verify_signature(&DidKeyResolver).await.is_err());
+    }
+
+    #[dialog_common::test]
+    async fn it_does_not_take_a_plain_powerline_for_a_registration() {
+        // Powerlines are handed out for other reasons too; only one issued
+        // as a registration lets its holder recover.
+        let old = signer().await;
+        let device = signer().await;
+        let new = signer().await;
+        let powerline = Delegation::<AnySignature>::builder()
+            .issuer(old.clone())
+            .audience(&device)
+            .subject(Subject::Any)
+            .command(Vec::new())
+            .try_build()
+            .await
+            .expect("built");
+        assert!(matches!(
+            Succession::recover(device.clone(), powerline.clone(), &new).await,
+            Err(SuccessionError::Malformed(_))
+        ));
+
+        let recovery = Succession::issue(
+            device,
+            old.did(),
+            Some(powerline),
+            new.did(),
+            Timestamp::now(),
+        )
+        .await
+        .expect("issued");
+        assert!(matches!(
+            Succession::<AnySignature>::try_from(recovery.to_bytes().expect("encoded").as_slice()),
+            Err(SuccessionError::Malformed(_))
+        ));
+    }
+
+    #[dialog_common::test]
+    async fn it_rejects_a_recovery_signed_before_its_registration() {
+        let old = signer().await;
+        let backup = signer().await;
+        let new = signer().await;
+        let now = Timestamp::now().to_unix();
+        let registration = Succession::<AnySignature>::register(
+            old.clone(),
+            &backup,
+            Timestamp::try_from(i128::from(now)).expect("in range"),
+        )
+        .await
+        .expect("registered");
+        let recovery = Succession::issue(
+            backup,
+            old.did(),
+            Some(registration),
+            new.did(),
+            Timestamp::try_from(i128::from(now - 60)).expect("in range"),
+        )
+        .await
+        .expect("issued");
+        assert!(matches!(
+            Succession::<AnySignature>::try_from(recovery.to_bytes().expect("encoded").as_slice()),
+            Err(SuccessionError::Malformed(_))
+        ));
+    }
+}
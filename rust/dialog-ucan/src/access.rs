@@ -3,6 +3,8 @@
 //! Implements [`Protocol`](dialog_capability::access::Protocol) for [`Ucan`],
 //! defining the UCAN-specific proof, permit, and authorization types.
 
+use std::collections::BTreeMap;
+
 use dialog_capability::Did;
 use dialog_capability::access::{
     Authorization, AuthorizeError, Certificate, Delegation as AccessDelegation, Proof, Protocol,
@@ -15,6 +17,7 @@ use dialog_ucan_core::time::Timestamp;
 use dialog_ucan_core::time::timestamp::{Duration, UNIX_EPOCH};
 use dialog_ucan_core::{Delegation, DelegationChain, InvocationBuilder, InvocationChain};
 use dialog_varsig::AnySignature;
+use ipld_core::ipld::Ipld;
 
 use super::scope::Scope;
 use super::{Ucan, UcanInvocation};
@@ -188,40 +191,14 @@ pub struct UcanAuthorization {
     pub duration: TimeRange,
 }
 
-#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
-#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
-impl Authorization<Ucan> for UcanAuthorization {
-    fn duration(&self) -> &TimeRange {
-        &self.duration
-    }
-
-    fn not_before(mut self, timestamp: u64) -> Result<Self, AuthorizeError> {
-        if let Some(nbf) = self.duration.not_before
-            && timestamp < nbf
-        {
-            return Err(AuthorizeError::NotValidBefore {
-                not_before: nbf,
-                at: timestamp,
-            });
-        }
-        self.duration.not_before = Some(timestamp);
-        Ok(self)
-    }
-
-    fn expires(mut self, timestamp: u64) -> Result<Self, AuthorizeError> {
-        if let Some(exp) = self.duration.expiration
-            && timestamp > exp
-        {
-            return Err(AuthorizeError::Expired {
-                expiration: exp,
-                at: timestamp,
-            });
-        }
-        self.duration.expiration = Some(timestamp);
-        Ok(self)
-    }
-
-    async fn delegate(&self, audience: Did) -> Result<UcanDelegation, AuthorizeError> {
+impl UcanAuthorization {
+    /// Delegate this authorization to `audience`, carrying `meta` on the
+    /// new delegation (an empty map adds none).
+    pub async fn delegate_with(
+        &self,
+        audience: Did,
+        meta: BTreeMap<String, Ipld>,
+    ) -> Result<UcanDelegation, AuthorizeError> {
         let mut builder = DelegationBuilder::new()
             .issuer(self.signer.clone())
             .audience(&audience)
@@ -239,6 +216,9 @@ impl Authorization<Ucan> for UcanAuthorization {
         {
             builder = builder.not_before(ts);
         }
+        if !meta.is_empty() {
+            builder = builder.meta(meta);
+        }
 
         // Building and signing our own delegation: ours to get right, so
         // failing it says nothing about the caller's material.
@@ -260,6 +240,44 @@ impl Authorization<Ucan> for UcanAuthorization {
 
         Ok(UcanDelegation::from(chain))
     }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Authorization<Ucan> for UcanAuthorization {
+    fn duration(&self) -> &TimeRange {
+        &self.duration
+    }
+
+    fn not_before(mut self, timestamp: u64) -> Result<Self, AuthorizeError> {
+        if let Some(nbf) = self.duration.not_before
+            && timestamp < nbf
+        {
+            return Err(AuthorizeError::NotValidBefore {
+                not_before: nbf,
+                at: timestamp,
+            });
+        }
+        self.duration.not_before = Some(timestamp);
+        Ok(self)
+    }
+
+    fn expires(mut self, timestamp: u64) -> Result<Self, AuthorizeError> {
+        if let Some(exp) = self.duration.expiration
+            && timestamp > exp
+        {
+            return Err(AuthorizeError::Expired {
+                expiration: exp,
+                at: timestamp,
+            });
+        }
+        self.duration.expiration = Some(timestamp);
+        Ok(self)
+    }
+
+    async fn delegate(&self, audience: Did) -> Result<UcanDelegation, AuthorizeError> {
+        self.delegate_with(audience, BTreeMap::new()).await
+    }
 
     async fn invoke(&self) -> Result<UcanInvocation, AuthorizeError> {
         let subject_did = match &self.scope.subject {